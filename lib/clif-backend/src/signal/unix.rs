@@ -18,7 +18,10 @@ use nix::sys::signal::{
 use std::cell::{Cell, UnsafeCell};
 use std::ptr;
 use std::sync::Once;
-use wasmer_runtime_core::backend::ExceptionCode;
+use wasmer_runtime_core::{
+    backend::ExceptionCode,
+    fault::{set_last_trap_info, TrapInfo},
+};
 
 extern "C" fn signal_trap_handler(
     signum: ::nix::libc::c_int,
@@ -88,9 +91,9 @@ pub fn call_protected<T>(
                     srcloc: _,
                 }) = handler_data.lookup(inst_ptr)
                 {
-                    Err(CallProtError(Box::new(match Signal::from_c_int(signum) {
+                    let exception_code = match Signal::from_c_int(signum) {
                         Ok(SIGILL) => match trapcode {
-                            TrapCode::StackOverflow => ExceptionCode::MemoryOutOfBounds,
+                            TrapCode::StackOverflow => ExceptionCode::StackOverflow,
                             TrapCode::HeapOutOfBounds => ExceptionCode::MemoryOutOfBounds,
                             TrapCode::TableOutOfBounds => ExceptionCode::CallIndirectOOB,
                             TrapCode::OutOfBounds => ExceptionCode::MemoryOutOfBounds,
@@ -112,7 +115,12 @@ pub fn call_protected<T>(
                             "ExceptionCode::Unknown signal:{:?}",
                             Signal::from_c_int(signum)
                         ),
-                    })))
+                    };
+                    set_last_trap_info(TrapInfo {
+                        exception_code: Some(exception_code),
+                        location: None,
+                    });
+                    Err(CallProtError(Box::new(exception_code)))
                 } else {
                     let signal = match Signal::from_c_int(signum) {
                         Ok(SIGFPE) => "floating-point exception",
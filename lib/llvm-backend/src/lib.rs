@@ -27,6 +27,9 @@ mod trampolines;
 pub use code::LLVMFunctionCodeGenerator as FunctionCodeGenerator;
 pub use code::LLVMModuleCodeGenerator as ModuleCodeGenerator;
 
+/// The current version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 use wasmer_runtime_core::codegen::SimpleStreamingCompilerGen;
 
 pub type LLVMCompiler = SimpleStreamingCompilerGen<
@@ -0,0 +1,136 @@
+#![cfg(any(feature = "singlepass", feature = "llvm"))]
+
+use wabt::wat2wasm;
+
+use wasmer_middleware_common::deterministic_float::*;
+use wasmer_runtime_core::codegen::{MiddlewareChain, StreamingCompiler};
+use wasmer_runtime_core::{backend::Compiler, compile_with, imports, Func, Instance};
+
+#[cfg(feature = "llvm")]
+use wasmer_llvm_backend::ModuleCodeGenerator as MCG;
+
+#[cfg(feature = "singlepass")]
+use wasmer_singlepass_backend::ModuleCodeGenerator as MCG;
+
+fn get_compiler() -> impl Compiler {
+    let c: StreamingCompiler<MCG, _, _, _, _> = StreamingCompiler::new(move || {
+        let mut chain = MiddlewareChain::new();
+        chain.push(DeterministicFloat::new());
+        chain
+    });
+    c
+}
+
+// Each function takes the bits of its operand and returns the bits of its result, so that the
+// NaNs cross the boundary of the instance unchanged.
+static WAT: &'static str = r#"
+    (module
+      (func (export "f32_nearest") (param i32) (result i32)
+        local.get 0 f32.reinterpret_i32 f32.nearest i32.reinterpret_f32)
+      (func (export "f32_trunc") (param i32) (result i32)
+        local.get 0 f32.reinterpret_i32 f32.trunc i32.reinterpret_f32)
+      (func (export "f32_floor") (param i32) (result i32)
+        local.get 0 f32.reinterpret_i32 f32.floor i32.reinterpret_f32)
+      (func (export "f32_ceil") (param i32) (result i32)
+        local.get 0 f32.reinterpret_i32 f32.ceil i32.reinterpret_f32)
+      (func (export "f32_sqrt") (param i32) (result i32)
+        local.get 0 f32.reinterpret_i32 f32.sqrt i32.reinterpret_f32)
+      (func (export "f64_nearest") (param i64) (result i64)
+        local.get 0 f64.reinterpret_i64 f64.nearest i64.reinterpret_f64)
+      (func (export "f64_trunc") (param i64) (result i64)
+        local.get 0 f64.reinterpret_i64 f64.trunc i64.reinterpret_f64)
+      (func (export "f64_floor") (param i64) (result i64)
+        local.get 0 f64.reinterpret_i64 f64.floor i64.reinterpret_f64)
+      (func (export "f64_ceil") (param i64) (result i64)
+        local.get 0 f64.reinterpret_i64 f64.ceil i64.reinterpret_f64)
+      (func (export "f64_sqrt") (param i64) (result i64)
+        local.get 0 f64.reinterpret_i64 f64.sqrt i64.reinterpret_f64))
+    "#;
+
+// (function, operand, result), as bits.
+static F32_VECTOR: &'static [(&'static str, u32, u32)] = &[
+    // Ties round to even.
+    ("f32_nearest", 0x4020_0000, 0x4000_0000), // 2.5 -> 2.0
+    ("f32_nearest", 0x4060_0000, 0x4080_0000), // 3.5 -> 4.0
+    ("f32_nearest", 0xbf00_0000, 0x8000_0000), // -0.5 -> -0.0
+    ("f32_trunc", 0xbfc0_0000, 0xbf80_0000),   // -1.5 -> -1.0
+    ("f32_floor", 0xbfc0_0000, 0xc000_0000),   // -1.5 -> -2.0
+    ("f32_floor", 0x0000_0001, 0x0000_0000),   // smallest subnormal -> 0.0
+    ("f32_ceil", 0xbf00_0000, 0x8000_0000),    // -0.5 -> -0.0
+    ("f32_ceil", 0x0000_0001, 0x3f80_0000),    // smallest subnormal -> 1.0
+    ("f32_sqrt", 0x4080_0000, 0x4000_0000),    // 4.0 -> 2.0
+    ("f32_sqrt", 0x8000_0000, 0x8000_0000),    // -0.0 -> -0.0
+    ("f32_sqrt", 0x7f80_0000, 0x7f80_0000),    // inf -> inf
+    ("f32_sqrt", 0x0000_0001, 0x1a35_04f3),    // smallest subnormal
+    // Invalid operations give the canonical NaN, e.g. x86 gives a negative NaN.
+    ("f32_sqrt", 0xbf80_0000, CANONICAL_NAN_F32), // -1.0
+    ("f32_sqrt", 0xff80_0000, CANONICAL_NAN_F32), // -inf
+    // NaN operands give the canonical NaN, whatever their sign and payload.
+    ("f32_nearest", 0xffc0_0001, CANONICAL_NAN_F32),
+    ("f32_trunc", 0x7f80_0001, CANONICAL_NAN_F32),
+    ("f32_floor", 0xffff_ffff, CANONICAL_NAN_F32),
+    ("f32_ceil", 0x7fc0_1234, CANONICAL_NAN_F32),
+    ("f32_sqrt", 0xffc0_0000, CANONICAL_NAN_F32),
+];
+
+// (function, operand, result), as bits.
+static F64_VECTOR: &'static [(&'static str, u64, u64)] = &[
+    // Ties round to even.
+    ("f64_nearest", 0x4004_0000_0000_0000, 0x4000_0000_0000_0000), // 2.5 -> 2.0
+    ("f64_nearest", 0x400c_0000_0000_0000, 0x4010_0000_0000_0000), // 3.5 -> 4.0
+    ("f64_nearest", 0xbfe0_0000_0000_0000, 0x8000_0000_0000_0000), // -0.5 -> -0.0
+    ("f64_trunc", 0xbff8_0000_0000_0000, 0xbff0_0000_0000_0000),   // -1.5 -> -1.0
+    ("f64_floor", 0xbff8_0000_0000_0000, 0xc000_0000_0000_0000),   // -1.5 -> -2.0
+    ("f64_floor", 0x0000_0000_0000_0001, 0x0000_0000_0000_0000),   // smallest subnormal -> 0.0
+    ("f64_ceil", 0xbfe0_0000_0000_0000, 0x8000_0000_0000_0000),    // -0.5 -> -0.0
+    ("f64_ceil", 0x0000_0000_0000_0001, 0x3ff0_0000_0000_0000),    // smallest subnormal -> 1.0
+    ("f64_sqrt", 0x4010_0000_0000_0000, 0x4000_0000_0000_0000),    // 4.0 -> 2.0
+    ("f64_sqrt", 0x8000_0000_0000_0000, 0x8000_0000_0000_0000),    // -0.0 -> -0.0
+    ("f64_sqrt", 0x7ff0_0000_0000_0000, 0x7ff0_0000_0000_0000),    // inf -> inf
+    ("f64_sqrt", 0x0000_0000_0000_0001, 0x1e60_0000_0000_0000),    // smallest subnormal
+    // Invalid operations give the canonical NaN, e.g. x86 gives a negative NaN.
+    ("f64_sqrt", 0xbff0_0000_0000_0000, CANONICAL_NAN_F64), // -1.0
+    ("f64_sqrt", 0xfff0_0000_0000_0000, CANONICAL_NAN_F64), // -inf
+    // NaN operands give the canonical NaN, whatever their sign and payload.
+    ("f64_nearest", 0xfff8_0000_0000_0001, CANONICAL_NAN_F64),
+    ("f64_trunc", 0x7ff0_0000_0000_0001, CANONICAL_NAN_F64),
+    ("f64_floor", 0xffff_ffff_ffff_ffff, CANONICAL_NAN_F64),
+    ("f64_ceil", 0x7ff8_0000_0000_1234, CANONICAL_NAN_F64),
+    ("f64_sqrt", 0xfff8_0000_0000_0000, CANONICAL_NAN_F64),
+];
+
+fn instantiate() -> Instance {
+    let wasm_binary = wat2wasm(WAT).unwrap();
+    let module = compile_with(&wasm_binary, &get_compiler()).unwrap();
+    module.instantiate(&imports! {}).unwrap()
+}
+
+#[test]
+fn test_f32_vector() {
+    let instance = instantiate();
+
+    for &(name, operand, expected) in F32_VECTOR {
+        let func: Func<u32, u32> = instance.func(name).unwrap();
+        let result = func.call(operand).unwrap();
+        assert_eq!(
+            result, expected,
+            "{}({:#010x}) gave {:#010x} instead of {:#010x}",
+            name, operand, result, expected
+        );
+    }
+}
+
+#[test]
+fn test_f64_vector() {
+    let instance = instantiate();
+
+    for &(name, operand, expected) in F64_VECTOR {
+        let func: Func<u64, u64> = instance.func(name).unwrap();
+        let result = func.call(operand).unwrap();
+        assert_eq!(
+            result, expected,
+            "{}({:#018x}) gave {:#018x} instead of {:#018x}",
+            name, operand, result, expected
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::{cell::Cell, ffi::c_void};
+
+use wasmer_runtime_core::{
+    codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
+    module::ModuleInfo,
+    vm::InternalField,
+    wasmparser::{Operator, Type as WpType, TypeOrFuncType as WpTypeOrFuncType},
+    Instance,
+};
+
+static FIELD_BLOCK_COVERAGE_HOOK: InternalField = InternalField::allocate();
+static FIELD_BLOCK_COVERAGE_HOOK_DATA: InternalField = InternalField::allocate();
+
+/// A function called on the entry of each basic block, with the data given to
+/// `set_block_coverage_hook` and the id of the block.
+pub type BlockCoverageHook = extern "C" fn(data: *mut c_void, block_id: u32);
+
+thread_local! {
+    static CURRENT_BLOCK_COVERAGE_HOOK: Cell<Option<(BlockCoverageHook, *mut c_void)>> =
+        Cell::new(None);
+}
+
+/// Calls the block coverage hook of the running instance, if any, on the entry of every basic
+/// block, e.g. to collect the edge coverage of a fuzzer.
+///
+/// The blocks start where the metering charges its blocks: at the beginning of each function, and
+/// after each control flow operator or call which the execution may continue after. Their ids
+/// are assigned in the order of the module, starting from 0, so they are stable for a given
+/// module, provided that the middleware comes before the middlewares injecting opcodes.
+///
+/// A module compiled with this middleware only reaches the hook when the instance has one, see
+/// `set_block_coverage_hook`, and when it is called through `with_block_coverage_hook`.
+pub struct BlockCoverage {
+    next_block_id: u32,
+    depth: usize,
+}
+
+impl BlockCoverage {
+    pub fn new() -> BlockCoverage {
+        BlockCoverage {
+            next_block_id: 0,
+            depth: 0,
+        }
+    }
+
+    fn push_block_entry(&mut self, sink: &mut EventSink) {
+        let block_id = self.next_block_id;
+        self.next_block_id += 1;
+
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_BLOCK_COVERAGE_HOOK.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64Const { value: 0 }));
+        sink.push(Event::WasmOwned(Operator::I64Ne));
+        sink.push(Event::WasmOwned(Operator::If {
+            ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+        }));
+        sink.push(Event::Internal(InternalEvent::Breakpoint(Box::new(
+            move |_| {
+                CURRENT_BLOCK_COVERAGE_HOOK.with(|current| {
+                    if let Some((hook, data)) = current.get() {
+                        hook(data, block_id);
+                    }
+                });
+                Ok(())
+            },
+        ))));
+        sink.push(Event::WasmOwned(Operator::End));
+    }
+}
+
+impl FunctionMiddleware for BlockCoverage {
+    type Error = String;
+    fn feed_event<'a, 'b: 'a>(
+        &mut self,
+        op: Event<'a, 'b>,
+        _module_info: &ModuleInfo,
+        sink: &mut EventSink<'a, 'b>,
+        _source_loc: u32,
+    ) -> Result<(), Self::Error> {
+        let starts_block = match op {
+            Event::Internal(InternalEvent::FunctionBegin(_)) => {
+                self.depth = 0;
+                true
+            }
+            Event::Wasm(&ref op) | Event::WasmOwned(ref op) => match *op {
+                Operator::Block { .. } | Operator::Loop { .. } | Operator::If { .. } => {
+                    self.depth += 1;
+                    true
+                }
+                // The last `end` closes the function: no block follows it.
+                Operator::End if self.depth == 0 => false,
+                Operator::End => {
+                    self.depth -= 1;
+                    true
+                }
+                Operator::Else
+                | Operator::BrIf { .. }
+                | Operator::Call { .. }
+                | Operator::CallIndirect { .. } => true,
+                _ => false,
+            },
+            _ => false,
+        };
+
+        sink.push(op);
+        if starts_block {
+            self.push_block_entry(sink);
+        }
+
+        Ok(())
+    }
+}
+
+/// Sets the block coverage hook of the instance, or removes it if `hook` is `None`.
+pub fn set_block_coverage_hook(
+    instance: &mut Instance,
+    hook: Option<BlockCoverageHook>,
+    data: *mut c_void,
+) {
+    match hook {
+        Some(hook) => {
+            instance.set_internal(&FIELD_BLOCK_COVERAGE_HOOK, hook as usize as u64);
+            instance.set_internal(&FIELD_BLOCK_COVERAGE_HOOK_DATA, data as usize as u64);
+        }
+        None => {
+            instance.set_internal(&FIELD_BLOCK_COVERAGE_HOOK, 0);
+            instance.set_internal(&FIELD_BLOCK_COVERAGE_HOOK_DATA, 0);
+        }
+    }
+}
+
+/// Returns the block coverage hook of the instance, and its data.
+pub fn get_block_coverage_hook(instance: &Instance) -> Option<(BlockCoverageHook, *mut c_void)> {
+    match instance.get_internal(&FIELD_BLOCK_COVERAGE_HOOK) {
+        0 => None,
+        hook => Some(unsafe {
+            (
+                std::mem::transmute::<usize, BlockCoverageHook>(hook as usize),
+                instance.get_internal(&FIELD_BLOCK_COVERAGE_HOOK_DATA) as usize as *mut c_void,
+            )
+        }),
+    }
+}
+
+/// Runs `f`, which calls into an instance, with `hook` as the block coverage hook of the current
+/// thread. The previous hook is restored afterwards, so that calls can be nested.
+pub fn with_block_coverage_hook<R, F: FnOnce() -> R>(
+    hook: Option<(BlockCoverageHook, *mut c_void)>,
+    f: F,
+) -> R {
+    let previous = CURRENT_BLOCK_COVERAGE_HOOK.with(|current| current.replace(hook));
+    let result = f();
+    CURRENT_BLOCK_COVERAGE_HOOK.with(|current| current.set(previous));
+    result
+}
@@ -0,0 +1,119 @@
+use wasmer_runtime_core::{
+    codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
+    module::ModuleInfo,
+    vm::InternalField,
+    wasmparser::{Operator, Type as WpType, TypeOrFuncType as WpTypeOrFuncType},
+    Instance,
+};
+
+use crate::runtime_breakpoints::{push_runtime_breakpoint, BREAKPOINT_VALUE_CALL_DEPTH_LIMIT};
+
+static FIELD_CALL_DEPTH: InternalField = InternalField::allocate();
+
+/// CallDepth is a compiler middleware that bounds the number of nested calls of wasm functions, so
+/// that a deep recursion traps at the same depth on every machine and backend, instead of whenever
+/// the native stack of the running thread happens to be exhausted.
+///
+/// The depth counts the frames of the wasm functions of the instance: the function called by the
+/// host is at depth 1. Each function checks, before its first operator, that its frame does not
+/// exceed the limit, and traps with `BREAKPOINT_VALUE_CALL_DEPTH_LIMIT` otherwise. The depth of the
+/// callers is kept in an internal field of the instance, raised by one around each `call` and
+/// `call_indirect`.
+///
+/// A limit of 0 means no limit: the code is left as is.
+pub struct CallDepth {
+    max_call_depth: u64,
+    entry_check_pending: bool,
+}
+
+impl CallDepth {
+    pub fn new(max_call_depth: u64) -> CallDepth {
+        CallDepth {
+            max_call_depth,
+            entry_check_pending: false,
+        }
+    }
+
+    /// Traps if the frame of the function exceeds the limit, i.e. if its callers already reached
+    /// it.
+    fn push_entry_check(&self, sink: &mut EventSink) {
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_CALL_DEPTH.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64Const {
+            value: self.max_call_depth as i64,
+        }));
+        sink.push(Event::WasmOwned(Operator::I64GeU));
+        sink.push(Event::WasmOwned(Operator::If {
+            ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+        }));
+        push_runtime_breakpoint(sink, BREAKPOINT_VALUE_CALL_DEPTH_LIMIT);
+        sink.push(Event::WasmOwned(Operator::End));
+    }
+
+    /// Adds the frame of the caller to the call depth, or subtracts it, with `operator`.
+    fn push_depth_update(&self, sink: &mut EventSink, operator: Operator<'static>) {
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_CALL_DEPTH.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64Const { value: 1 }));
+        sink.push(Event::WasmOwned(operator));
+        sink.push(Event::Internal(InternalEvent::SetInternal(
+            FIELD_CALL_DEPTH.index() as _,
+        )));
+    }
+}
+
+impl FunctionMiddleware for CallDepth {
+    type Error = String;
+    fn feed_event<'a, 'b: 'a>(
+        &mut self,
+        op: Event<'a, 'b>,
+        _module_info: &ModuleInfo,
+        sink: &mut EventSink<'a, 'b>,
+        _source_loc: u32,
+    ) -> Result<(), Self::Error> {
+        if self.max_call_depth == 0 {
+            sink.push(op);
+            return Ok(());
+        }
+
+        match op {
+            Event::Internal(InternalEvent::FunctionBegin(_)) => {
+                self.entry_check_pending = true;
+                sink.push(op);
+            }
+            Event::Wasm(&ref wasm_op) | Event::WasmOwned(ref wasm_op) => {
+                if self.entry_check_pending {
+                    self.entry_check_pending = false;
+                    self.push_entry_check(sink);
+                }
+                let is_call = match *wasm_op {
+                    Operator::Call { .. } | Operator::CallIndirect { .. } => true,
+                    _ => false,
+                };
+                if is_call {
+                    self.push_depth_update(sink, Operator::I64Add);
+                    sink.push(op);
+                    self.push_depth_update(sink, Operator::I64Sub);
+                } else {
+                    sink.push(op);
+                }
+            }
+            _ => sink.push(op),
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the number of frames of the callers of the wasm function running in an Instance.
+pub fn get_call_depth(instance: &Instance) -> u64 {
+    instance.get_internal(&FIELD_CALL_DEPTH)
+}
+
+/// Sets the number of frames of the callers of the wasm function running in an Instance, e.g. to
+/// restore it after a call which trapped.
+pub fn set_call_depth(instance: &mut Instance, value: u64) {
+    instance.set_internal(&FIELD_CALL_DEPTH, value);
+}
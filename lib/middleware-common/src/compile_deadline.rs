@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+use wasmer_runtime_core::{
+    codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
+    module::ModuleInfo,
+};
+
+/// Number of events fed between two reads of the clock, so that the deadline check stays cheap
+/// compared to the code generation itself.
+const EVENTS_PER_CHECK: u32 = 4096;
+
+/// CompileDeadline is a compiler middleware that aborts the compilation once a wall-clock timeout
+/// has elapsed. The clock starts when the middleware is created, i.e. when the middleware chain
+/// is generated at the start of the compilation.
+///
+/// The deadline is checked at the beginning of each function, and every `EVENTS_PER_CHECK`
+/// events within a function, so that a single huge function cannot exceed it by much. The work
+/// done by the backend after the code has been streamed, e.g. the LLVM optimizations, cannot be
+/// interrupted: the caller must check the deadline again once the compilation returns, see
+/// `is_expired`.
+pub struct CompileDeadline {
+    timeout: Duration,
+    deadline: Instant,
+    events_until_check: u32,
+}
+
+impl CompileDeadline {
+    pub fn new(timeout: Duration) -> CompileDeadline {
+        CompileDeadline {
+            timeout,
+            deadline: Instant::now() + timeout,
+            events_until_check: EVENTS_PER_CHECK,
+        }
+    }
+
+    fn check(&mut self) -> Result<(), String> {
+        self.events_until_check = EVENTS_PER_CHECK;
+        if is_expired(self.deadline) {
+            return Err(timeout_message(self.timeout));
+        }
+
+        Ok(())
+    }
+}
+
+impl FunctionMiddleware for CompileDeadline {
+    type Error = String;
+    fn feed_event<'a, 'b: 'a>(
+        &mut self,
+        op: Event<'a, 'b>,
+        _module_info: &ModuleInfo,
+        sink: &mut EventSink<'a, 'b>,
+        _source_loc: u32,
+    ) -> Result<(), Self::Error> {
+        match op {
+            Event::Internal(InternalEvent::FunctionBegin(_)) => self.check()?,
+            _ => {
+                self.events_until_check -= 1;
+                if self.events_until_check == 0 {
+                    self.check()?;
+                }
+            }
+        }
+
+        sink.push(op);
+        Ok(())
+    }
+}
+
+/// Returns whether the given compilation deadline has passed.
+pub fn is_expired(deadline: Instant) -> bool {
+    Instant::now() >= deadline
+}
+
+/// Returns the error message of a compilation which exceeded the given timeout.
+pub fn timeout_message(timeout: Duration) -> String {
+    format!("compilation timed out after {} ms", timeout.as_millis())
+}
@@ -0,0 +1,138 @@
+use wasmer_runtime_core::{
+    codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
+    module::ModuleInfo,
+    vm::InternalField,
+    wasmparser::Operator,
+};
+
+/// Holds the bits of the result being canonicalized, since wasm has no operator to duplicate the
+/// top of the stack.
+static FIELD_FLOAT_BITS: InternalField = InternalField::allocate();
+
+/// The bits of the canonical NaN of f32: positive, quiet, with an empty payload.
+pub const CANONICAL_NAN_F32: u32 = 0x7fc0_0000;
+
+/// The bits of the canonical NaN of f64: positive, quiet, with an empty payload.
+pub const CANONICAL_NAN_F64: u64 = 0x7ff8_0000_0000_0000;
+
+/// DeterministicFloat is a compiler middleware that makes the rounding operators, i.e.
+/// `nearest`, `trunc`, `floor`, `ceil` and `sqrt` on f32 and f64, give the same bits on every
+/// backend and CPU.
+///
+/// IEEE 754 defines the result of these operators exactly, and every backend compiles them to
+/// the rounding and square root instructions of the CPU, which follow it: their results only
+/// differ when they are NaNs, whose sign and payload depend on the CPU, e.g. `sqrt(-1)` is
+/// negative on x86 and positive on ARM. The middleware replaces every NaN result with the
+/// canonical NaN, `CANONICAL_NAN_F32` or `CANONICAL_NAN_F64`, right after the operator.
+pub struct DeterministicFloat {}
+
+impl DeterministicFloat {
+    pub fn new() -> DeterministicFloat {
+        DeterministicFloat {}
+    }
+}
+
+impl FunctionMiddleware for DeterministicFloat {
+    type Error = String;
+    fn feed_event<'a, 'b: 'a>(
+        &mut self,
+        op: Event<'a, 'b>,
+        _module_info: &ModuleInfo,
+        sink: &mut EventSink<'a, 'b>,
+        _source_loc: u32,
+    ) -> Result<(), Self::Error> {
+        let result_type = match op {
+            Event::Wasm(&ref wasm_op) | Event::WasmOwned(ref wasm_op) => match *wasm_op {
+                Operator::F32Nearest
+                | Operator::F32Trunc
+                | Operator::F32Floor
+                | Operator::F32Ceil
+                | Operator::F32Sqrt => Some(FloatType::F32),
+                Operator::F64Nearest
+                | Operator::F64Trunc
+                | Operator::F64Floor
+                | Operator::F64Ceil
+                | Operator::F64Sqrt => Some(FloatType::F64),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        sink.push(op);
+
+        match result_type {
+            Some(FloatType::F32) => push_canonicalize_f32(sink),
+            Some(FloatType::F64) => push_canonicalize_f64(sink),
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+enum FloatType {
+    F32,
+    F64,
+}
+
+/// Replaces the f32 on top of the stack with `CANONICAL_NAN_F32` if it is a NaN.
+fn push_canonicalize_f32(sink: &mut EventSink) {
+    sink.push(Event::WasmOwned(Operator::I32ReinterpretF32));
+    sink.push(Event::WasmOwned(Operator::I64ExtendI32U));
+    sink.push(Event::Internal(InternalEvent::SetInternal(
+        FIELD_FLOAT_BITS.index() as _,
+    )));
+
+    sink.push(Event::WasmOwned(Operator::I32Const {
+        value: CANONICAL_NAN_F32 as i32,
+    }));
+    push_float_bits_f32(sink);
+
+    // A NaN is greater than the infinity once its sign is cleared.
+    push_float_bits_f32(sink);
+    sink.push(Event::WasmOwned(Operator::I32Const { value: 0x7fff_ffff }));
+    sink.push(Event::WasmOwned(Operator::I32And));
+    sink.push(Event::WasmOwned(Operator::I32Const { value: 0x7f80_0000 }));
+    sink.push(Event::WasmOwned(Operator::I32GtU));
+
+    sink.push(Event::WasmOwned(Operator::Select));
+    sink.push(Event::WasmOwned(Operator::F32ReinterpretI32));
+}
+
+fn push_float_bits_f32(sink: &mut EventSink) {
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_FLOAT_BITS.index() as _,
+    )));
+    sink.push(Event::WasmOwned(Operator::I32WrapI64));
+}
+
+/// Replaces the f64 on top of the stack with `CANONICAL_NAN_F64` if it is a NaN.
+fn push_canonicalize_f64(sink: &mut EventSink) {
+    sink.push(Event::WasmOwned(Operator::I64ReinterpretF64));
+    sink.push(Event::Internal(InternalEvent::SetInternal(
+        FIELD_FLOAT_BITS.index() as _,
+    )));
+
+    sink.push(Event::WasmOwned(Operator::I64Const {
+        value: CANONICAL_NAN_F64 as i64,
+    }));
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_FLOAT_BITS.index() as _,
+    )));
+
+    // A NaN is greater than the infinity once its sign is cleared.
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_FLOAT_BITS.index() as _,
+    )));
+    sink.push(Event::WasmOwned(Operator::I64Const {
+        value: 0x7fff_ffff_ffff_ffff,
+    }));
+    sink.push(Event::WasmOwned(Operator::I64And));
+    sink.push(Event::WasmOwned(Operator::I64Const {
+        value: 0x7ff0_0000_0000_0000,
+    }));
+    sink.push(Event::WasmOwned(Operator::I64GtU));
+
+    sink.push(Event::WasmOwned(Operator::Select));
+    sink.push(Event::WasmOwned(Operator::F64ReinterpretI64));
+}
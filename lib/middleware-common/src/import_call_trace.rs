@@ -0,0 +1,136 @@
+use std::{
+    cell::Cell,
+    ffi::{c_void, CString},
+    os::raw::c_char,
+};
+
+use wasmer_runtime_core::{
+    codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
+    module::ModuleInfo,
+    structures::TypedIndex,
+    types::ImportedFuncIndex,
+    vm::InternalField,
+    wasmparser::{Operator, Type as WpType, TypeOrFuncType as WpTypeOrFuncType},
+    Instance,
+};
+
+static FIELD_IMPORT_CALL_HOOK: InternalField = InternalField::allocate();
+static FIELD_IMPORT_CALL_HOOK_DATA: InternalField = InternalField::allocate();
+
+/// A function called before each call of an imported function, with the data given to
+/// `set_import_call_hook` and the NUL-terminated name of the import.
+pub type ImportCallHook = extern "C" fn(data: *mut c_void, import_name: *const c_char);
+
+thread_local! {
+    static CURRENT_IMPORT_CALL_HOOK: Cell<Option<(ImportCallHook, *mut c_void)>> = Cell::new(None);
+}
+
+/// Calls the import call hook of the running instance, if any, before each direct `call` of an
+/// imported function. The hook only observes the call: it is given the name of the import, but
+/// neither the arguments nor the instance.
+///
+/// A module compiled with this middleware only reaches the hook when the instance has one, see
+/// `set_import_call_hook`, and when it is called through `with_import_call_hook`.
+pub struct ImportCallTracer {}
+
+impl ImportCallTracer {
+    pub fn new() -> ImportCallTracer {
+        ImportCallTracer {}
+    }
+}
+
+impl FunctionMiddleware for ImportCallTracer {
+    type Error = String;
+    fn feed_event<'a, 'b: 'a>(
+        &mut self,
+        op: Event<'a, 'b>,
+        module_info: &ModuleInfo,
+        sink: &mut EventSink<'a, 'b>,
+        _source_loc: u32,
+    ) -> Result<(), Self::Error> {
+        let imported_function_index = match op {
+            Event::Wasm(&ref op) | Event::WasmOwned(ref op) => match *op {
+                Operator::Call { function_index }
+                    if (function_index as usize) < module_info.imported_functions.len() =>
+                {
+                    Some(ImportedFuncIndex::new(function_index as usize))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(index) = imported_function_index {
+            let import_name = &module_info.imported_functions[index];
+            let name = CString::new(module_info.name_table.get(import_name.name_index))
+                .unwrap_or_default();
+
+            sink.push(Event::Internal(InternalEvent::GetInternal(
+                FIELD_IMPORT_CALL_HOOK.index() as _,
+            )));
+            sink.push(Event::WasmOwned(Operator::I64Const { value: 0 }));
+            sink.push(Event::WasmOwned(Operator::I64Ne));
+            sink.push(Event::WasmOwned(Operator::If {
+                ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+            }));
+            sink.push(Event::Internal(InternalEvent::Breakpoint(Box::new(
+                move |_| {
+                    CURRENT_IMPORT_CALL_HOOK.with(|current| {
+                        if let Some((hook, data)) = current.get() {
+                            hook(data, name.as_ptr());
+                        }
+                    });
+                    Ok(())
+                },
+            ))));
+            sink.push(Event::WasmOwned(Operator::End));
+        }
+
+        sink.push(op);
+
+        Ok(())
+    }
+}
+
+/// Sets the import call hook of the instance, or removes it if `hook` is `None`.
+pub fn set_import_call_hook(
+    instance: &mut Instance,
+    hook: Option<ImportCallHook>,
+    data: *mut c_void,
+) {
+    match hook {
+        Some(hook) => {
+            instance.set_internal(&FIELD_IMPORT_CALL_HOOK, hook as usize as u64);
+            instance.set_internal(&FIELD_IMPORT_CALL_HOOK_DATA, data as usize as u64);
+        }
+        None => {
+            instance.set_internal(&FIELD_IMPORT_CALL_HOOK, 0);
+            instance.set_internal(&FIELD_IMPORT_CALL_HOOK_DATA, 0);
+        }
+    }
+}
+
+/// Returns the import call hook of the instance, and its data.
+pub fn get_import_call_hook(instance: &Instance) -> Option<(ImportCallHook, *mut c_void)> {
+    match instance.get_internal(&FIELD_IMPORT_CALL_HOOK) {
+        0 => None,
+        hook => Some(unsafe {
+            (
+                std::mem::transmute::<usize, ImportCallHook>(hook as usize),
+                instance.get_internal(&FIELD_IMPORT_CALL_HOOK_DATA) as usize as *mut c_void,
+            )
+        }),
+    }
+}
+
+/// Runs `f`, which calls into an instance, with `hook` as the import call hook of the current
+/// thread. The previous hook is restored afterwards, so that calls can be nested.
+pub fn with_import_call_hook<R, F: FnOnce() -> R>(
+    hook: Option<(ImportCallHook, *mut c_void)>,
+    f: F,
+) -> R {
+    let previous = CURRENT_IMPORT_CALL_HOOK.with(|current| current.replace(hook));
+    let result = f();
+    CURRENT_IMPORT_CALL_HOOK.with(|current| current.set(previous));
+    result
+}
@@ -10,13 +10,21 @@
 #![doc(html_favicon_url = "https://wasmer.io/static/icons/favicon.ico")]
 #![doc(html_logo_url = "https://avatars3.githubusercontent.com/u/44205449?s=200&v=4")]
 
+pub mod block_coverage;
 #[cfg(unix)]
 pub mod block_trace;
+pub mod call_depth;
 pub mod call_trace;
+pub mod import_call_trace;
 
 pub mod metering;
 pub mod metering_costs;
+pub mod opcode_codes;
 
-pub mod runtime_breakpoints;
-pub mod opcode_trace;
+pub mod compile_deadline;
+pub mod deterministic_float;
 pub mod opcode_control;
+pub mod opcode_denylist;
+pub mod opcode_trace;
+pub mod runtime_breakpoints;
+pub mod stack_height;
@@ -1,3 +1,5 @@
+use std::{cell::Cell, collections::BTreeMap, ptr};
+
 use wasmer_runtime_core::{
     codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
     module::ModuleInfo,
@@ -6,11 +8,31 @@ use wasmer_runtime_core::{
     Instance,
 };
 
-use crate::metering_costs::{get_opcode_index, get_local_allocate_cost_index};
-use crate::runtime_breakpoints::{push_runtime_breakpoint, BREAKPOINT_VALUE_OUT_OF_GAS};
+use crate::metering_costs::{
+    get_local_allocate_cost_index, get_opcode_category, get_opcode_index, GasCategory,
+    GAS_CATEGORY_COUNT,
+};
+use crate::runtime_breakpoints::{
+    push_runtime_breakpoint, BREAKPOINT_VALUE_INSTRUCTION_LIMIT, BREAKPOINT_VALUE_OUT_OF_GAS,
+};
 
 static FIELD_USED_POINTS: InternalField = InternalField::allocate();
 static FIELD_POINTS_LIMIT: InternalField = InternalField::allocate();
+static FIELD_CALL_START_POINTS_USED: InternalField = InternalField::allocate();
+static FIELD_CHARGE_FULL_ON_OUT_OF_GAS: InternalField = InternalField::allocate();
+static FIELD_EXCLUDE_FAILING_CHARGE_ON_OUT_OF_GAS: InternalField = InternalField::allocate();
+static FIELD_USED_INSTRUCTIONS: InternalField = InternalField::allocate();
+static FIELD_INSTRUCTIONS_LIMIT: InternalField = InternalField::allocate();
+static FIELD_GAS_BREAKDOWN: [InternalField; GAS_CATEGORY_COUNT] = [
+    InternalField::allocate(),
+    InternalField::allocate(),
+    InternalField::allocate(),
+    InternalField::allocate(),
+];
+
+thread_local! {
+    static CURRENT_PER_FUNCTION_GAS: Cell<*mut BTreeMap<u32, u64>> = Cell::new(ptr::null_mut());
+}
 
 /// Metering is a compiler middleware that calculates the cost of WebAssembly instructions at compile
 /// time and will count the cost of executed instructions at runtime. Within the Metering functionality,
@@ -24,12 +46,51 @@ static FIELD_POINTS_LIMIT: InternalField = InternalField::allocate();
 /// Each compiler backend with Metering enabled should produce the same cost used at runtime for
 /// the same function calls so we can say that the metering is deterministic.
 ///
+/// The cost of a block is only known once all its operators went through the middleware, so the
+/// points cannot be charged at the entry of the block. They are charged, and checked against the
+/// limit, right before the first operator of the block which modifies the state of the instance
+/// (a global, a memory or a table), with the cost of the block up to that operator included. A
+/// block which cannot afford to run up to there traps without leaving partial state behind; the
+/// operators before have no effect but on the value stack and the locals, which the trap throws
+/// away. The points are checked as well at each branch and call.
+///
+/// Every check traps as soon as the points used reach the limit.
+///
+/// When a call runs out of gas, the points used include, by default, the cost of the failing
+/// block, i.e. of the operators charged together with the check which failed, so they exceed the
+/// limit. An instance can instead exclude that cost, leaving the points used by the blocks which
+/// ran, see `set_exclude_failing_charge_on_out_of_gas`, or charge exactly its limit, see
+/// `set_charge_full_on_out_of_gas`, which takes precedence.
+///
+/// Created with `new_with_instruction_limit`, the Metering also counts the executed operators,
+/// whatever their cost, in an independent counter checked against a hard instruction limit, see
+/// `set_instructions_limit`. Exceeding it traps with `BREAKPOINT_VALUE_INSTRUCTION_LIMIT`, so that
+/// a safety stop can be told apart from running out of gas. It is checked first, along with the
+/// points, and leaves the points used untouched.
+///
+/// With `with_gas_breakdown`, the Metering also adds the points of each charge to one counter per
+/// `GasCategory` of the charged operators, see `get_gas_breakdown`. The counters are not checked
+/// against any limit, and include the failing charge when running out of gas, whatever the
+/// options of the instance.
+///
+/// With `with_per_function_gas`, the Metering also adds the points of each charge to the function
+/// where they were charged, in the Instance running through `record_per_function_gas`, see
+/// `get_per_function_gas`. Each charge then runs a breakpoint, so this is much slower than the
+/// plain metering, and meant for profiling. Like the breakdown, it includes the failing charge
+/// when running out of gas.
+///
 
 pub struct Metering<'a> {
     unmetered_locals: usize,
     current_block: u64,
     func_locals_costs: u32,
     opcode_costs: &'a [u32],
+    count_instructions: bool,
+    current_block_instructions: u64,
+    gas_breakdown: bool,
+    current_block_categories: [u64; GAS_CATEGORY_COUNT],
+    per_function_gas: bool,
+    current_function: u32,
 }
 
 impl<'a> Metering<'a> {
@@ -39,6 +100,42 @@ impl<'a> Metering<'a> {
             current_block: 0,
             func_locals_costs: 0,
             opcode_costs,
+            count_instructions: false,
+            current_block_instructions: 0,
+            gas_breakdown: false,
+            current_block_categories: [0; GAS_CATEGORY_COUNT],
+            per_function_gas: false,
+            current_function: 0,
+        }
+    }
+
+    /// Creates a Metering which also counts the executed operators against the instruction
+    /// limit of the instance, see `set_instructions_limit`.
+    pub fn new_with_instruction_limit(
+        opcode_costs: &'a [u32],
+        unmetered_locals: usize,
+    ) -> Metering<'a> {
+        Metering {
+            count_instructions: true,
+            ..Metering::new(opcode_costs, unmetered_locals)
+        }
+    }
+
+    /// Makes the Metering break the points used down by `GasCategory`, see
+    /// `get_gas_breakdown`.
+    pub fn with_gas_breakdown(self) -> Metering<'a> {
+        Metering {
+            gas_breakdown: true,
+            ..self
+        }
+    }
+
+    /// Makes the Metering attribute the points used to the function where they were charged, see
+    /// `get_per_function_gas`.
+    pub fn with_per_function_gas(self) -> Metering<'a> {
+        Metering {
+            per_function_gas: true,
+            ..self
         }
     }
 }
@@ -52,17 +149,28 @@ impl<'q> FunctionMiddleware for Metering<'q> {
     fn feed_event<'a, 'b: 'a>(
         &mut self,
         op: Event<'a, 'b>,
-        _module_info: &ModuleInfo,
+        module_info: &ModuleInfo,
         sink: &mut EventSink<'a, 'b>,
         _source_loc: u32,
     ) -> Result<(), Self::Error> {
         match op {
-            Event::Internal(InternalEvent::FunctionBegin(_)) => {
+            Event::Internal(InternalEvent::FunctionBegin(local_function_index)) => {
+                // In the function index space of the module, imported functions first.
+                self.current_function =
+                    module_info.imported_functions.len() as u32 + local_function_index;
                 self.current_block = self.func_locals_costs as u64;
+                self.current_block_instructions = 0;
+                self.current_block_categories = [0; GAS_CATEGORY_COUNT];
+                self.current_block_categories[GasCategory::Memory as usize] =
+                    self.func_locals_costs as u64;
             }
             Event::Wasm(&ref op) | Event::WasmOwned(ref op) => {
                 let opcode_index = get_opcode_index(op);
-                self.current_block += self.opcode_costs[opcode_index] as u64;
+                let cost = self.opcode_costs[opcode_index] as u64;
+                self.current_block += cost;
+                self.current_block_categories[get_opcode_category(op) as usize] += cost;
+                self.current_block_instructions += 1;
+                let mut charged = 0;
                 match *op {
                     Operator::Loop { .. }
                     | Operator::Block { .. }
@@ -76,17 +184,15 @@ impl<'q> FunctionMiddleware for Metering<'q> {
                     | Operator::Call { .. }
                     | Operator::CallIndirect { .. }
                     | Operator::Return => {
-                        sink.push(Event::Internal(InternalEvent::GetInternal(
-                            FIELD_USED_POINTS.index() as _,
-                        )));
-                        sink.push(Event::WasmOwned(Operator::I64Const {
-                            value: self.current_block as i64,
-                        }));
-                        sink.push(Event::WasmOwned(Operator::I64Add));
-                        sink.push(Event::Internal(InternalEvent::SetInternal(
-                            FIELD_USED_POINTS.index() as _,
-                        )));
-                        self.current_block = 0;
+                        charged = self.push_charge(sink);
+                    }
+                    _ if has_side_effect(op) => {
+                        // Charge the block so far, the side-effecting operator included,
+                        // so that a block which cannot afford to run traps before
+                        // modifying the state of the instance.
+                        charged = self.push_charge(sink);
+                        self.push_instruction_limit_check(sink);
+                        push_out_of_gas_check(sink, charged);
                     }
                     _ => {}
                 }
@@ -96,18 +202,8 @@ impl<'q> FunctionMiddleware for Metering<'q> {
                     | Operator::BrIf { .. }
                     | Operator::Call { .. }
                     | Operator::CallIndirect { .. } => {
-                        sink.push(Event::Internal(InternalEvent::GetInternal(
-                            FIELD_USED_POINTS.index() as _,
-                        )));
-                        sink.push(Event::Internal(InternalEvent::GetInternal(
-                            FIELD_POINTS_LIMIT.index() as _,
-                        )));
-                        sink.push(Event::WasmOwned(Operator::I64GeU));
-                        sink.push(Event::WasmOwned(Operator::If {
-                            ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
-                        }));
-                        push_runtime_breakpoint(sink, BREAKPOINT_VALUE_OUT_OF_GAS);
-                        sink.push(Event::WasmOwned(Operator::End));
+                        self.push_instruction_limit_check(sink);
+                        push_out_of_gas_check(sink, charged);
                     }
                     _ => {}
                 }
@@ -120,14 +216,9 @@ impl<'q> FunctionMiddleware for Metering<'q> {
         Ok(())
     }
 
-    fn feed_local(
-        &mut self,
-        _ty: WpType,
-        n: usize,
-        _loc: u32,
-    ) -> Result<(), Self::Error>{
+    fn feed_local(&mut self, _ty: WpType, n: usize, _loc: u32) -> Result<(), Self::Error> {
         if n > self.unmetered_locals {
-            let metered_locals = (n  - self.unmetered_locals) as u32;
+            let metered_locals = (n - self.unmetered_locals) as u32;
             let cost_index = get_local_allocate_cost_index();
             let cost = self.opcode_costs[cost_index];
             // n is already limited by Wasmparser; the following casting and multiplication are
@@ -138,6 +229,189 @@ impl<'q> FunctionMiddleware for Metering<'q> {
     }
 }
 
+impl<'a> Metering<'a> {
+    /// Adds the cost of the operators seen since the last charge to the
+    /// points used, and returns it.
+    fn push_charge<'b, 'c: 'b>(&mut self, sink: &mut EventSink<'b, 'c>) -> u64 {
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_USED_POINTS.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64Const {
+            value: self.current_block as i64,
+        }));
+        sink.push(Event::WasmOwned(Operator::I64Add));
+        sink.push(Event::Internal(InternalEvent::SetInternal(
+            FIELD_USED_POINTS.index() as _,
+        )));
+        let charged = self.current_block;
+        self.current_block = 0;
+
+        if self.count_instructions {
+            sink.push(Event::Internal(InternalEvent::GetInternal(
+                FIELD_USED_INSTRUCTIONS.index() as _,
+            )));
+            sink.push(Event::WasmOwned(Operator::I64Const {
+                value: self.current_block_instructions as i64,
+            }));
+            sink.push(Event::WasmOwned(Operator::I64Add));
+            sink.push(Event::Internal(InternalEvent::SetInternal(
+                FIELD_USED_INSTRUCTIONS.index() as _,
+            )));
+        }
+        self.current_block_instructions = 0;
+
+        if self.gas_breakdown {
+            for (field, &points) in FIELD_GAS_BREAKDOWN
+                .iter()
+                .zip(self.current_block_categories.iter())
+            {
+                if points == 0 {
+                    continue;
+                }
+                sink.push(Event::Internal(InternalEvent::GetInternal(
+                    field.index() as _
+                )));
+                sink.push(Event::WasmOwned(Operator::I64Const {
+                    value: points as i64,
+                }));
+                sink.push(Event::WasmOwned(Operator::I64Add));
+                sink.push(Event::Internal(InternalEvent::SetInternal(
+                    field.index() as _
+                )));
+            }
+        }
+        self.current_block_categories = [0; GAS_CATEGORY_COUNT];
+
+        if self.per_function_gas && charged != 0 {
+            let function = self.current_function;
+            sink.push(Event::Internal(InternalEvent::Breakpoint(Box::new(
+                move |_| {
+                    CURRENT_PER_FUNCTION_GAS.with(|current| {
+                        if let Some(gas) = unsafe { current.get().as_mut() } {
+                            *gas.entry(function).or_insert(0) += charged;
+                        }
+                    });
+                    Ok(())
+                },
+            ))));
+        }
+
+        charged
+    }
+
+    /// Traps with `BREAKPOINT_VALUE_INSTRUCTION_LIMIT` if the instructions used exceed the
+    /// instruction limit, when the instructions are counted.
+    fn push_instruction_limit_check<'b, 'c: 'b>(&self, sink: &mut EventSink<'b, 'c>) {
+        if !self.count_instructions {
+            return;
+        }
+
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_USED_INSTRUCTIONS.index() as _,
+        )));
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_INSTRUCTIONS_LIMIT.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64GtU));
+        sink.push(Event::WasmOwned(Operator::If {
+            ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+        }));
+        push_runtime_breakpoint(sink, BREAKPOINT_VALUE_INSTRUCTION_LIMIT);
+        sink.push(Event::WasmOwned(Operator::End));
+    }
+}
+
+/// Traps with `BREAKPOINT_VALUE_OUT_OF_GAS` if the points used reach the
+/// limit, right after a charge of `charged` points.
+fn push_out_of_gas_check<'a, 'b: 'a>(sink: &mut EventSink<'a, 'b>, charged: u64) {
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_USED_POINTS.index() as _,
+    )));
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_POINTS_LIMIT.index() as _,
+    )));
+    sink.push(Event::WasmOwned(Operator::I64GeU));
+    sink.push(Event::WasmOwned(Operator::If {
+        ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+    }));
+    // Take back the failing charge, if the instance is configured so.
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_EXCLUDE_FAILING_CHARGE_ON_OUT_OF_GAS.index() as _,
+    )));
+    sink.push(Event::WasmOwned(Operator::I64Const { value: 0 }));
+    sink.push(Event::WasmOwned(Operator::I64Ne));
+    sink.push(Event::WasmOwned(Operator::If {
+        ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+    }));
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_USED_POINTS.index() as _,
+    )));
+    sink.push(Event::WasmOwned(Operator::I64Const {
+        value: charged as i64,
+    }));
+    sink.push(Event::WasmOwned(Operator::I64Sub));
+    sink.push(Event::Internal(InternalEvent::SetInternal(
+        FIELD_USED_POINTS.index() as _,
+    )));
+    sink.push(Event::WasmOwned(Operator::End));
+    // Charge the whole limit instead of the points used so far,
+    // if the instance is configured so.
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_CHARGE_FULL_ON_OUT_OF_GAS.index() as _,
+    )));
+    sink.push(Event::WasmOwned(Operator::I64Const { value: 0 }));
+    sink.push(Event::WasmOwned(Operator::I64Ne));
+    sink.push(Event::WasmOwned(Operator::If {
+        ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+    }));
+    sink.push(Event::Internal(InternalEvent::GetInternal(
+        FIELD_POINTS_LIMIT.index() as _,
+    )));
+    sink.push(Event::Internal(InternalEvent::SetInternal(
+        FIELD_USED_POINTS.index() as _,
+    )));
+    sink.push(Event::WasmOwned(Operator::End));
+    push_runtime_breakpoint(sink, BREAKPOINT_VALUE_OUT_OF_GAS);
+    sink.push(Event::WasmOwned(Operator::End));
+}
+
+/// Returns whether an operator modifies the state of the instance, i.e.
+/// its globals, memories, or tables.
+fn has_side_effect(op: &Operator) -> bool {
+    match *op {
+        Operator::GlobalSet { .. }
+        | Operator::I32Store { .. }
+        | Operator::I64Store { .. }
+        | Operator::F32Store { .. }
+        | Operator::F64Store { .. }
+        | Operator::I32Store8 { .. }
+        | Operator::I32Store16 { .. }
+        | Operator::I64Store8 { .. }
+        | Operator::I64Store16 { .. }
+        | Operator::I64Store32 { .. }
+        | Operator::V128Store { .. }
+        | Operator::MemoryGrow { .. }
+        | Operator::MemoryInit { .. }
+        | Operator::DataDrop { .. }
+        | Operator::MemoryCopy { .. }
+        | Operator::MemoryFill { .. }
+        | Operator::TableInit { .. }
+        | Operator::ElemDrop { .. }
+        | Operator::TableCopy { .. }
+        | Operator::TableSet { .. }
+        | Operator::TableGrow { .. }
+        | Operator::TableFill { .. }
+        | Operator::I32AtomicStore { .. }
+        | Operator::I64AtomicStore { .. }
+        | Operator::I32AtomicStore8 { .. }
+        | Operator::I32AtomicStore16 { .. }
+        | Operator::I64AtomicStore8 { .. }
+        | Operator::I64AtomicStore16 { .. }
+        | Operator::I64AtomicStore32 { .. } => true,
+        _ => false,
+    }
+}
+
 /// Returns the number of points used by an Instance.
 pub fn get_points_used(instance: &Instance) -> u64 {
     instance.get_internal(&FIELD_USED_POINTS)
@@ -148,11 +422,126 @@ pub fn set_points_used(instance: &mut Instance, value: u64) {
     instance.set_internal(&FIELD_USED_POINTS, value);
 }
 
+/// Returns the number of points an Instance had used when its current call started.
+pub fn get_call_start_points_used(instance: &Instance) -> u64 {
+    instance.get_internal(&FIELD_CALL_START_POINTS_USED)
+}
+
+/// Sets the number of points an Instance had used when its current call started, which bounds
+/// the refunds of `refund_points_used_ctx`.
+pub fn set_call_start_points_used(instance: &mut Instance, value: u64) {
+    instance.set_internal(&FIELD_CALL_START_POINTS_USED, value);
+}
+
 /// Sets the limit of points to be used by an Instance.
 pub fn set_points_limit(instance: &mut Instance, value: u64) {
     instance.set_internal(&FIELD_POINTS_LIMIT, value);
 }
 
+/// Returns the limit of points to be used by an Instance.
+pub fn get_points_limit(instance: &Instance) -> u64 {
+    instance.get_internal(&FIELD_POINTS_LIMIT)
+}
+
+/// Returns the number of operators executed by an Instance, when compiled with
+/// `Metering::new_with_instruction_limit`.
+pub fn get_instructions_used(instance: &Instance) -> u64 {
+    instance.get_internal(&FIELD_USED_INSTRUCTIONS)
+}
+
+/// Sets the number of operators executed by an Instance.
+pub fn set_instructions_used(instance: &mut Instance, value: u64) {
+    instance.set_internal(&FIELD_USED_INSTRUCTIONS, value);
+}
+
+/// Sets the hard limit of operators to be executed by an Instance, independent of its limit of
+/// points.
+pub fn set_instructions_limit(instance: &mut Instance, value: u64) {
+    instance.set_internal(&FIELD_INSTRUCTIONS_LIMIT, value);
+}
+
+/// Returns the points used by an Instance per `GasCategory`, indexed by category, when compiled
+/// with `Metering::with_gas_breakdown`.
+pub fn get_gas_breakdown(instance: &Instance) -> [u64; GAS_CATEGORY_COUNT] {
+    let mut breakdown = [0; GAS_CATEGORY_COUNT];
+    for (points, field) in breakdown.iter_mut().zip(FIELD_GAS_BREAKDOWN.iter()) {
+        *points = instance.get_internal(field);
+    }
+    breakdown
+}
+
+/// Resets the points used by an Instance per `GasCategory`.
+pub fn reset_gas_breakdown(instance: &mut Instance) {
+    for field in FIELD_GAS_BREAKDOWN.iter() {
+        instance.set_internal(field, 0);
+    }
+}
+
+/// Returns the points used per function by an Instance since the last call to
+/// `reset_per_function_gas`, by the code compiled with `Metering::with_per_function_gas` and
+/// called through `record_per_function_gas`, as pairs of a function index, counting the imported
+/// functions, and its points, sorted by index. The functions which used no points are left out.
+pub fn get_per_function_gas(instance: &Instance) -> Vec<(u32, u64)> {
+    instance
+        .context()
+        .per_function_gas
+        .iter()
+        .map(|(&f, &p)| (f, p))
+        .collect()
+}
+
+/// Clears the points used per function by an Instance.
+pub fn reset_per_function_gas(instance: &mut Instance) {
+    instance.context_mut().per_function_gas.clear();
+}
+
+/// Runs `f`, which calls into the Instance, recording the points used per function into the
+/// Instance. The Instance recording on the current thread before is restored afterwards, so that
+/// calls can be nested.
+pub fn record_per_function_gas<R, F: FnOnce(&mut Instance) -> R>(
+    instance: &mut Instance,
+    f: F,
+) -> R {
+    let gas: *mut BTreeMap<u32, u64> = &mut instance.context_mut().per_function_gas;
+    let previous = CURRENT_PER_FUNCTION_GAS.with(|current| current.replace(gas));
+    let result = f(instance);
+    CURRENT_PER_FUNCTION_GAS.with(|current| current.set(previous));
+    result
+}
+
+/// Sets whether running out of gas charges the whole limit of points to an
+/// Instance, instead of the points used until the exhaustion was detected.
+pub fn set_charge_full_on_out_of_gas(instance: &mut Instance, enabled: bool) {
+    instance.set_internal(&FIELD_CHARGE_FULL_ON_OUT_OF_GAS, enabled as u64);
+}
+
+/// Sets whether running out of gas leaves out of the points used of an Instance the points of
+/// the failing charge, i.e. of the block which could not afford to run.
+pub fn set_exclude_failing_charge_on_out_of_gas(instance: &mut Instance, enabled: bool) {
+    instance.set_internal(&FIELD_EXCLUDE_FAILING_CHARGE_ON_OUT_OF_GAS, enabled as u64);
+}
+
+/// Returns whether running out of gas leaves out the points of the failing charge in a Ctx.
+pub fn is_exclude_failing_charge_on_out_of_gas_ctx(ctx: &Ctx) -> bool {
+    ctx.get_internal(&FIELD_EXCLUDE_FAILING_CHARGE_ON_OUT_OF_GAS) != 0
+}
+
+/// Returns whether running out of gas charges the whole limit of points in a Ctx.
+pub fn is_charge_full_on_out_of_gas_ctx(ctx: &Ctx) -> bool {
+    ctx.get_internal(&FIELD_CHARGE_FULL_ON_OUT_OF_GAS) != 0
+}
+
+/// Returns whether the code running in a Ctx was compiled with the Metering middleware, as
+/// recorded in `ModuleInfo::metered` by the embedder, whatever the way it was instantiated.
+pub fn is_metering_enabled_ctx(ctx: &Ctx) -> bool {
+    unsafe { (*ctx.module).info.metered }
+}
+
+/// Returns the limit of points to be used in a Ctx.
+pub fn get_points_limit_ctx(ctx: &Ctx) -> u64 {
+    ctx.get_internal(&FIELD_POINTS_LIMIT)
+}
+
 /// Returns the number of points used in a Ctx.
 pub fn get_points_used_ctx(ctx: &Ctx) -> u64 {
     ctx.get_internal(&FIELD_USED_POINTS)
@@ -162,3 +551,14 @@ pub fn get_points_used_ctx(ctx: &Ctx) -> u64 {
 pub fn set_points_used_ctx(ctx: &mut Ctx, value: u64) {
     ctx.set_internal(&FIELD_USED_POINTS, value);
 }
+
+/// Refunds up to `amount` points used in a Ctx, without giving back more points than its current
+/// call used, see `set_call_start_points_used`. Returns the number of points refunded.
+pub fn refund_points_used_ctx(ctx: &mut Ctx, amount: u64) -> u64 {
+    let points_used = get_points_used_ctx(ctx);
+    let call_points_used =
+        points_used.saturating_sub(ctx.get_internal(&FIELD_CALL_START_POINTS_USED));
+    let refund = amount.min(call_points_used);
+    set_points_used_ctx(ctx, points_used - refund);
+    refund
+}
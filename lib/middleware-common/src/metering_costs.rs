@@ -1,9 +1,75 @@
+use crate::opcode_codes::Opcode;
 use wasmer_runtime_core::wasmparser::Operator;
 
 pub fn get_local_allocate_cost_index() -> usize {
     447
 }
 
+/// The cost indices, and names, of the opcodes which let the code run again: the metering
+/// only bounds the execution if each of them has a nonzero cost.
+const LOOP_ENABLING_OPCODES: [(usize, &str); 6] = [
+    (3, "loop"),
+    (7, "br"),
+    (8, "br_if"),
+    (9, "br_table"),
+    (11, "call"),
+    (12, "call_indirect"),
+];
+
+/// Checks that the opcode costs charge every opcode which lets the code run again, so that a
+/// metered module cannot loop forever within its gas limit.
+pub fn check_loop_enabling_costs(opcode_costs: &[u32]) -> Result<(), String> {
+    match LOOP_ENABLING_OPCODES
+        .iter()
+        .find(|(index, _)| opcode_costs[*index] == 0)
+    {
+        Some((_, name)) => Err(format!(
+            "the cost of the `{}` opcode must not be zero in metered mode",
+            name
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The categories of opcodes which the gas used can be broken down into, see
+/// `Metering::with_gas_breakdown`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(usize)]
+pub enum GasCategory {
+    /// Constants, locals, parametric, numeric, conversion, reference and SIMD lane operators.
+    Arithmetic = 0,
+    /// Globals, loads, stores, memory and table operators, atomics, and the allocation of
+    /// the locals.
+    Memory = 1,
+    /// Blocks, branches, returns, `nop` and `unreachable`.
+    Control = 2,
+    /// Direct and indirect calls.
+    Call = 3,
+}
+
+pub const GAS_CATEGORY_COUNT: usize = 4;
+
+/// Returns the category of the operator. The opcodes of each category are contiguous in the
+/// order of `Opcode`, which follows the order of the opcodes in the specification.
+pub fn get_opcode_category(op: &Operator) -> GasCategory {
+    let code = Opcode::from_operator(op).code();
+    let within = |first: Opcode, last: Opcode| first.code() <= code && code <= last.code();
+    if within(Opcode::Call, Opcode::CallIndirect) {
+        GasCategory::Call
+    } else if within(Opcode::Unreachable, Opcode::Return) {
+        GasCategory::Control
+    } else if within(Opcode::GlobalGet, Opcode::MemoryGrow)
+        // The bulk memory, table and atomic opcodes, and the SIMD loads and stores.
+        || within(Opcode::MemoryInit, Opcode::V128Store)
+        || within(Opcode::V8x16LoadSplat, Opcode::V64x2LoadSplat)
+        || within(Opcode::I16x8Load8x8S, Opcode::I64x2Load32x2U)
+    {
+        GasCategory::Memory
+    } else {
+        GasCategory::Arithmetic
+    }
+}
+
 pub fn get_opcode_index(op: &Operator) -> usize {
     match *op {
         Operator::Unreachable { .. } => { 0 }
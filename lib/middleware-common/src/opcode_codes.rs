@@ -0,0 +1,1424 @@
+//! A stable numeric encoding of the wasm opcodes, used by the opcode
+//! trace.
+//!
+//! Unlike the indices of `metering_costs::get_opcode_index`, which map
+//! several opcodes to the same cost, each opcode has its own code here.
+//! The codes are part of the trace format consumed by external tools:
+//! existing codes must never change, and new opcodes must be appended.
+
+use wasmer_runtime_core::wasmparser::Operator;
+
+/// The number of opcode codes.
+pub const OPCODE_CODE_COUNT: usize = 447;
+
+/// The stable code of each opcode.
+#[repr(u16)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Opcode {
+    Unreachable = 0,
+    Nop = 1,
+    Block = 2,
+    Loop = 3,
+    If = 4,
+    Else = 5,
+    End = 6,
+    Br = 7,
+    BrIf = 8,
+    BrTable = 9,
+    Return = 10,
+    Call = 11,
+    CallIndirect = 12,
+    Drop = 13,
+    Select = 14,
+    TypedSelect = 15,
+    LocalGet = 16,
+    LocalSet = 17,
+    LocalTee = 18,
+    GlobalGet = 19,
+    GlobalSet = 20,
+    I32Load = 21,
+    I64Load = 22,
+    F32Load = 23,
+    F64Load = 24,
+    I32Load8S = 25,
+    I32Load8U = 26,
+    I32Load16S = 27,
+    I32Load16U = 28,
+    I64Load8S = 29,
+    I64Load8U = 30,
+    I64Load16S = 31,
+    I64Load16U = 32,
+    I64Load32S = 33,
+    I64Load32U = 34,
+    I32Store = 35,
+    I64Store = 36,
+    F32Store = 37,
+    F64Store = 38,
+    I32Store8 = 39,
+    I32Store16 = 40,
+    I64Store8 = 41,
+    I64Store16 = 42,
+    I64Store32 = 43,
+    MemorySize = 44,
+    MemoryGrow = 45,
+    I32Const = 46,
+    I64Const = 47,
+    F32Const = 48,
+    F64Const = 49,
+    RefNull = 50,
+    RefIsNull = 51,
+    RefFunc = 52,
+    I32Eqz = 53,
+    I32Eq = 54,
+    I32Ne = 55,
+    I32LtS = 56,
+    I32LtU = 57,
+    I32GtS = 58,
+    I32GtU = 59,
+    I32LeS = 60,
+    I32LeU = 61,
+    I32GeS = 62,
+    I32GeU = 63,
+    I64Eqz = 64,
+    I64Eq = 65,
+    I64Ne = 66,
+    I64LtS = 67,
+    I64LtU = 68,
+    I64GtS = 69,
+    I64GtU = 70,
+    I64LeS = 71,
+    I64LeU = 72,
+    I64GeS = 73,
+    I64GeU = 74,
+    F32Eq = 75,
+    F32Ne = 76,
+    F32Lt = 77,
+    F32Gt = 78,
+    F32Le = 79,
+    F32Ge = 80,
+    F64Eq = 81,
+    F64Ne = 82,
+    F64Lt = 83,
+    F64Gt = 84,
+    F64Le = 85,
+    F64Ge = 86,
+    I32Clz = 87,
+    I32Ctz = 88,
+    I32Popcnt = 89,
+    I32Add = 90,
+    I32Sub = 91,
+    I32Mul = 92,
+    I32DivS = 93,
+    I32DivU = 94,
+    I32RemS = 95,
+    I32RemU = 96,
+    I32And = 97,
+    I32Or = 98,
+    I32Xor = 99,
+    I32Shl = 100,
+    I32ShrS = 101,
+    I32ShrU = 102,
+    I32Rotl = 103,
+    I32Rotr = 104,
+    I64Clz = 105,
+    I64Ctz = 106,
+    I64Popcnt = 107,
+    I64Add = 108,
+    I64Sub = 109,
+    I64Mul = 110,
+    I64DivS = 111,
+    I64DivU = 112,
+    I64RemS = 113,
+    I64RemU = 114,
+    I64And = 115,
+    I64Or = 116,
+    I64Xor = 117,
+    I64Shl = 118,
+    I64ShrS = 119,
+    I64ShrU = 120,
+    I64Rotl = 121,
+    I64Rotr = 122,
+    F32Abs = 123,
+    F32Neg = 124,
+    F32Ceil = 125,
+    F32Floor = 126,
+    F32Trunc = 127,
+    F32Nearest = 128,
+    F32Sqrt = 129,
+    F32Add = 130,
+    F32Sub = 131,
+    F32Mul = 132,
+    F32Div = 133,
+    F32Min = 134,
+    F32Max = 135,
+    F32Copysign = 136,
+    F64Abs = 137,
+    F64Neg = 138,
+    F64Ceil = 139,
+    F64Floor = 140,
+    F64Trunc = 141,
+    F64Nearest = 142,
+    F64Sqrt = 143,
+    F64Add = 144,
+    F64Sub = 145,
+    F64Mul = 146,
+    F64Div = 147,
+    F64Min = 148,
+    F64Max = 149,
+    F64Copysign = 150,
+    I32WrapI64 = 151,
+    I32TruncF32S = 152,
+    I32TruncF32U = 153,
+    I32TruncF64S = 154,
+    I32TruncF64U = 155,
+    I64ExtendI32S = 156,
+    I64ExtendI32U = 157,
+    I64TruncF32S = 158,
+    I64TruncF32U = 159,
+    I64TruncF64S = 160,
+    I64TruncF64U = 161,
+    F32ConvertI32S = 162,
+    F32ConvertI32U = 163,
+    F32ConvertI64S = 164,
+    F32ConvertI64U = 165,
+    F32DemoteF64 = 166,
+    F64ConvertI32S = 167,
+    F64ConvertI32U = 168,
+    F64ConvertI64S = 169,
+    F64ConvertI64U = 170,
+    F64PromoteF32 = 171,
+    I32ReinterpretF32 = 172,
+    I64ReinterpretF64 = 173,
+    F32ReinterpretI32 = 174,
+    F64ReinterpretI64 = 175,
+    I32Extend8S = 176,
+    I32Extend16S = 177,
+    I64Extend8S = 178,
+    I64Extend16S = 179,
+    I64Extend32S = 180,
+    I32TruncSatF32S = 181,
+    I32TruncSatF32U = 182,
+    I32TruncSatF64S = 183,
+    I32TruncSatF64U = 184,
+    I64TruncSatF32S = 185,
+    I64TruncSatF32U = 186,
+    I64TruncSatF64S = 187,
+    I64TruncSatF64U = 188,
+    MemoryInit = 189,
+    DataDrop = 190,
+    MemoryCopy = 191,
+    MemoryFill = 192,
+    TableInit = 193,
+    ElemDrop = 194,
+    TableCopy = 195,
+    TableFill = 196,
+    TableGet = 197,
+    TableSet = 198,
+    TableGrow = 199,
+    TableSize = 200,
+    AtomicNotify = 201,
+    I32AtomicWait = 202,
+    I64AtomicWait = 203,
+    AtomicFence = 204,
+    I32AtomicLoad = 205,
+    I64AtomicLoad = 206,
+    I32AtomicLoad8U = 207,
+    I32AtomicLoad16U = 208,
+    I64AtomicLoad8U = 209,
+    I64AtomicLoad16U = 210,
+    I64AtomicLoad32U = 211,
+    I32AtomicStore = 212,
+    I64AtomicStore = 213,
+    I32AtomicStore8 = 214,
+    I32AtomicStore16 = 215,
+    I64AtomicStore8 = 216,
+    I64AtomicStore16 = 217,
+    I64AtomicStore32 = 218,
+    I32AtomicRmwAdd = 219,
+    I64AtomicRmwAdd = 220,
+    I32AtomicRmw8AddU = 221,
+    I32AtomicRmw16AddU = 222,
+    I64AtomicRmw8AddU = 223,
+    I64AtomicRmw16AddU = 224,
+    I64AtomicRmw32AddU = 225,
+    I32AtomicRmwSub = 226,
+    I64AtomicRmwSub = 227,
+    I32AtomicRmw8SubU = 228,
+    I32AtomicRmw16SubU = 229,
+    I64AtomicRmw8SubU = 230,
+    I64AtomicRmw16SubU = 231,
+    I64AtomicRmw32SubU = 232,
+    I32AtomicRmwAnd = 233,
+    I64AtomicRmwAnd = 234,
+    I32AtomicRmw8AndU = 235,
+    I32AtomicRmw16AndU = 236,
+    I64AtomicRmw8AndU = 237,
+    I64AtomicRmw16AndU = 238,
+    I64AtomicRmw32AndU = 239,
+    I32AtomicRmwOr = 240,
+    I64AtomicRmwOr = 241,
+    I32AtomicRmw8OrU = 242,
+    I32AtomicRmw16OrU = 243,
+    I64AtomicRmw8OrU = 244,
+    I64AtomicRmw16OrU = 245,
+    I64AtomicRmw32OrU = 246,
+    I32AtomicRmwXor = 247,
+    I64AtomicRmwXor = 248,
+    I32AtomicRmw8XorU = 249,
+    I32AtomicRmw16XorU = 250,
+    I64AtomicRmw8XorU = 251,
+    I64AtomicRmw16XorU = 252,
+    I64AtomicRmw32XorU = 253,
+    I32AtomicRmwXchg = 254,
+    I64AtomicRmwXchg = 255,
+    I32AtomicRmw8XchgU = 256,
+    I32AtomicRmw16XchgU = 257,
+    I64AtomicRmw8XchgU = 258,
+    I64AtomicRmw16XchgU = 259,
+    I64AtomicRmw32XchgU = 260,
+    I32AtomicRmwCmpxchg = 261,
+    I64AtomicRmwCmpxchg = 262,
+    I32AtomicRmw8CmpxchgU = 263,
+    I32AtomicRmw16CmpxchgU = 264,
+    I64AtomicRmw8CmpxchgU = 265,
+    I64AtomicRmw16CmpxchgU = 266,
+    I64AtomicRmw32CmpxchgU = 267,
+    V128Load = 268,
+    V128Store = 269,
+    V128Const = 270,
+    I8x16Splat = 271,
+    I8x16ExtractLaneS = 272,
+    I8x16ExtractLaneU = 273,
+    I8x16ReplaceLane = 274,
+    I16x8Splat = 275,
+    I16x8ExtractLaneS = 276,
+    I16x8ExtractLaneU = 277,
+    I16x8ReplaceLane = 278,
+    I32x4Splat = 279,
+    I32x4ExtractLane = 280,
+    I32x4ReplaceLane = 281,
+    I64x2Splat = 282,
+    I64x2ExtractLane = 283,
+    I64x2ReplaceLane = 284,
+    F32x4Splat = 285,
+    F32x4ExtractLane = 286,
+    F32x4ReplaceLane = 287,
+    F64x2Splat = 288,
+    F64x2ExtractLane = 289,
+    F64x2ReplaceLane = 290,
+    I8x16Eq = 291,
+    I8x16Ne = 292,
+    I8x16LtS = 293,
+    I8x16LtU = 294,
+    I8x16GtS = 295,
+    I8x16GtU = 296,
+    I8x16LeS = 297,
+    I8x16LeU = 298,
+    I8x16GeS = 299,
+    I8x16GeU = 300,
+    I16x8Eq = 301,
+    I16x8Ne = 302,
+    I16x8LtS = 303,
+    I16x8LtU = 304,
+    I16x8GtS = 305,
+    I16x8GtU = 306,
+    I16x8LeS = 307,
+    I16x8LeU = 308,
+    I16x8GeS = 309,
+    I16x8GeU = 310,
+    I32x4Eq = 311,
+    I32x4Ne = 312,
+    I32x4LtS = 313,
+    I32x4LtU = 314,
+    I32x4GtS = 315,
+    I32x4GtU = 316,
+    I32x4LeS = 317,
+    I32x4LeU = 318,
+    I32x4GeS = 319,
+    I32x4GeU = 320,
+    F32x4Eq = 321,
+    F32x4Ne = 322,
+    F32x4Lt = 323,
+    F32x4Gt = 324,
+    F32x4Le = 325,
+    F32x4Ge = 326,
+    F64x2Eq = 327,
+    F64x2Ne = 328,
+    F64x2Lt = 329,
+    F64x2Gt = 330,
+    F64x2Le = 331,
+    F64x2Ge = 332,
+    V128Not = 333,
+    V128And = 334,
+    V128AndNot = 335,
+    V128Or = 336,
+    V128Xor = 337,
+    V128Bitselect = 338,
+    I8x16Neg = 339,
+    I8x16AnyTrue = 340,
+    I8x16AllTrue = 341,
+    I8x16Shl = 342,
+    I8x16ShrS = 343,
+    I8x16ShrU = 344,
+    I8x16Add = 345,
+    I8x16AddSaturateS = 346,
+    I8x16AddSaturateU = 347,
+    I8x16Sub = 348,
+    I8x16SubSaturateS = 349,
+    I8x16SubSaturateU = 350,
+    I8x16MinS = 351,
+    I8x16MinU = 352,
+    I8x16MaxS = 353,
+    I8x16MaxU = 354,
+    I8x16Mul = 355,
+    I16x8Neg = 356,
+    I16x8AnyTrue = 357,
+    I16x8AllTrue = 358,
+    I16x8Shl = 359,
+    I16x8ShrS = 360,
+    I16x8ShrU = 361,
+    I16x8Add = 362,
+    I16x8AddSaturateS = 363,
+    I16x8AddSaturateU = 364,
+    I16x8Sub = 365,
+    I16x8SubSaturateS = 366,
+    I16x8SubSaturateU = 367,
+    I16x8Mul = 368,
+    I16x8MinS = 369,
+    I16x8MinU = 370,
+    I16x8MaxS = 371,
+    I16x8MaxU = 372,
+    I32x4Neg = 373,
+    I32x4AnyTrue = 374,
+    I32x4AllTrue = 375,
+    I32x4Shl = 376,
+    I32x4ShrS = 377,
+    I32x4ShrU = 378,
+    I32x4Add = 379,
+    I32x4Sub = 380,
+    I32x4Mul = 381,
+    I32x4MinS = 382,
+    I32x4MinU = 383,
+    I32x4MaxS = 384,
+    I32x4MaxU = 385,
+    I64x2Neg = 386,
+    I64x2AnyTrue = 387,
+    I64x2AllTrue = 388,
+    I64x2Shl = 389,
+    I64x2ShrS = 390,
+    I64x2ShrU = 391,
+    I64x2Add = 392,
+    I64x2Sub = 393,
+    I64x2Mul = 394,
+    F32x4Abs = 395,
+    F32x4Neg = 396,
+    F32x4Sqrt = 397,
+    F32x4Add = 398,
+    F32x4Sub = 399,
+    F32x4Mul = 400,
+    F32x4Div = 401,
+    F32x4Min = 402,
+    F32x4Max = 403,
+    F64x2Abs = 404,
+    F64x2Neg = 405,
+    F64x2Sqrt = 406,
+    F64x2Add = 407,
+    F64x2Sub = 408,
+    F64x2Mul = 409,
+    F64x2Div = 410,
+    F64x2Min = 411,
+    F64x2Max = 412,
+    I32x4TruncSatF32x4S = 413,
+    I32x4TruncSatF32x4U = 414,
+    I64x2TruncSatF64x2S = 415,
+    I64x2TruncSatF64x2U = 416,
+    F32x4ConvertI32x4S = 417,
+    F32x4ConvertI32x4U = 418,
+    F64x2ConvertI64x2S = 419,
+    F64x2ConvertI64x2U = 420,
+    V8x16Swizzle = 421,
+    V8x16Shuffle = 422,
+    V8x16LoadSplat = 423,
+    V16x8LoadSplat = 424,
+    V32x4LoadSplat = 425,
+    V64x2LoadSplat = 426,
+    I8x16NarrowI16x8S = 427,
+    I8x16NarrowI16x8U = 428,
+    I16x8NarrowI32x4S = 429,
+    I16x8NarrowI32x4U = 430,
+    I16x8WidenLowI8x16S = 431,
+    I16x8WidenHighI8x16S = 432,
+    I16x8WidenLowI8x16U = 433,
+    I16x8WidenHighI8x16U = 434,
+    I32x4WidenLowI16x8S = 435,
+    I32x4WidenHighI16x8S = 436,
+    I32x4WidenLowI16x8U = 437,
+    I32x4WidenHighI16x8U = 438,
+    I16x8Load8x8S = 439,
+    I16x8Load8x8U = 440,
+    I32x4Load16x4S = 441,
+    I32x4Load16x4U = 442,
+    I64x2Load32x2S = 443,
+    I64x2Load32x2U = 444,
+    I8x16RoundingAverageU = 445,
+    I16x8RoundingAverageU = 446,
+}
+
+impl Opcode {
+    /// Returns the opcode of the given operator.
+    pub fn from_operator(op: &Operator) -> Opcode {
+        match *op {
+            Operator::Unreachable { .. } => Opcode::Unreachable,
+            Operator::Nop { .. } => Opcode::Nop,
+            Operator::Block { .. } => Opcode::Block,
+            Operator::Loop { .. } => Opcode::Loop,
+            Operator::If { .. } => Opcode::If,
+            Operator::Else { .. } => Opcode::Else,
+            Operator::End { .. } => Opcode::End,
+            Operator::Br { .. } => Opcode::Br,
+            Operator::BrIf { .. } => Opcode::BrIf,
+            Operator::BrTable { .. } => Opcode::BrTable,
+            Operator::Return { .. } => Opcode::Return,
+            Operator::Call { .. } => Opcode::Call,
+            Operator::CallIndirect { .. } => Opcode::CallIndirect,
+            Operator::Drop { .. } => Opcode::Drop,
+            Operator::Select { .. } => Opcode::Select,
+            Operator::TypedSelect { .. } => Opcode::TypedSelect,
+            Operator::LocalGet { .. } => Opcode::LocalGet,
+            Operator::LocalSet { .. } => Opcode::LocalSet,
+            Operator::LocalTee { .. } => Opcode::LocalTee,
+            Operator::GlobalGet { .. } => Opcode::GlobalGet,
+            Operator::GlobalSet { .. } => Opcode::GlobalSet,
+            Operator::I32Load { .. } => Opcode::I32Load,
+            Operator::I64Load { .. } => Opcode::I64Load,
+            Operator::F32Load { .. } => Opcode::F32Load,
+            Operator::F64Load { .. } => Opcode::F64Load,
+            Operator::I32Load8S { .. } => Opcode::I32Load8S,
+            Operator::I32Load8U { .. } => Opcode::I32Load8U,
+            Operator::I32Load16S { .. } => Opcode::I32Load16S,
+            Operator::I32Load16U { .. } => Opcode::I32Load16U,
+            Operator::I64Load8S { .. } => Opcode::I64Load8S,
+            Operator::I64Load8U { .. } => Opcode::I64Load8U,
+            Operator::I64Load16S { .. } => Opcode::I64Load16S,
+            Operator::I64Load16U { .. } => Opcode::I64Load16U,
+            Operator::I64Load32S { .. } => Opcode::I64Load32S,
+            Operator::I64Load32U { .. } => Opcode::I64Load32U,
+            Operator::I32Store { .. } => Opcode::I32Store,
+            Operator::I64Store { .. } => Opcode::I64Store,
+            Operator::F32Store { .. } => Opcode::F32Store,
+            Operator::F64Store { .. } => Opcode::F64Store,
+            Operator::I32Store8 { .. } => Opcode::I32Store8,
+            Operator::I32Store16 { .. } => Opcode::I32Store16,
+            Operator::I64Store8 { .. } => Opcode::I64Store8,
+            Operator::I64Store16 { .. } => Opcode::I64Store16,
+            Operator::I64Store32 { .. } => Opcode::I64Store32,
+            Operator::MemorySize { .. } => Opcode::MemorySize,
+            Operator::MemoryGrow { .. } => Opcode::MemoryGrow,
+            Operator::I32Const { .. } => Opcode::I32Const,
+            Operator::I64Const { .. } => Opcode::I64Const,
+            Operator::F32Const { .. } => Opcode::F32Const,
+            Operator::F64Const { .. } => Opcode::F64Const,
+            Operator::RefNull { .. } => Opcode::RefNull,
+            Operator::RefIsNull { .. } => Opcode::RefIsNull,
+            Operator::RefFunc { .. } => Opcode::RefFunc,
+            Operator::I32Eqz { .. } => Opcode::I32Eqz,
+            Operator::I32Eq { .. } => Opcode::I32Eq,
+            Operator::I32Ne { .. } => Opcode::I32Ne,
+            Operator::I32LtS { .. } => Opcode::I32LtS,
+            Operator::I32LtU { .. } => Opcode::I32LtU,
+            Operator::I32GtS { .. } => Opcode::I32GtS,
+            Operator::I32GtU { .. } => Opcode::I32GtU,
+            Operator::I32LeS { .. } => Opcode::I32LeS,
+            Operator::I32LeU { .. } => Opcode::I32LeU,
+            Operator::I32GeS { .. } => Opcode::I32GeS,
+            Operator::I32GeU { .. } => Opcode::I32GeU,
+            Operator::I64Eqz { .. } => Opcode::I64Eqz,
+            Operator::I64Eq { .. } => Opcode::I64Eq,
+            Operator::I64Ne { .. } => Opcode::I64Ne,
+            Operator::I64LtS { .. } => Opcode::I64LtS,
+            Operator::I64LtU { .. } => Opcode::I64LtU,
+            Operator::I64GtS { .. } => Opcode::I64GtS,
+            Operator::I64GtU { .. } => Opcode::I64GtU,
+            Operator::I64LeS { .. } => Opcode::I64LeS,
+            Operator::I64LeU { .. } => Opcode::I64LeU,
+            Operator::I64GeS { .. } => Opcode::I64GeS,
+            Operator::I64GeU { .. } => Opcode::I64GeU,
+            Operator::F32Eq { .. } => Opcode::F32Eq,
+            Operator::F32Ne { .. } => Opcode::F32Ne,
+            Operator::F32Lt { .. } => Opcode::F32Lt,
+            Operator::F32Gt { .. } => Opcode::F32Gt,
+            Operator::F32Le { .. } => Opcode::F32Le,
+            Operator::F32Ge { .. } => Opcode::F32Ge,
+            Operator::F64Eq { .. } => Opcode::F64Eq,
+            Operator::F64Ne { .. } => Opcode::F64Ne,
+            Operator::F64Lt { .. } => Opcode::F64Lt,
+            Operator::F64Gt { .. } => Opcode::F64Gt,
+            Operator::F64Le { .. } => Opcode::F64Le,
+            Operator::F64Ge { .. } => Opcode::F64Ge,
+            Operator::I32Clz { .. } => Opcode::I32Clz,
+            Operator::I32Ctz { .. } => Opcode::I32Ctz,
+            Operator::I32Popcnt { .. } => Opcode::I32Popcnt,
+            Operator::I32Add { .. } => Opcode::I32Add,
+            Operator::I32Sub { .. } => Opcode::I32Sub,
+            Operator::I32Mul { .. } => Opcode::I32Mul,
+            Operator::I32DivS { .. } => Opcode::I32DivS,
+            Operator::I32DivU { .. } => Opcode::I32DivU,
+            Operator::I32RemS { .. } => Opcode::I32RemS,
+            Operator::I32RemU { .. } => Opcode::I32RemU,
+            Operator::I32And { .. } => Opcode::I32And,
+            Operator::I32Or { .. } => Opcode::I32Or,
+            Operator::I32Xor { .. } => Opcode::I32Xor,
+            Operator::I32Shl { .. } => Opcode::I32Shl,
+            Operator::I32ShrS { .. } => Opcode::I32ShrS,
+            Operator::I32ShrU { .. } => Opcode::I32ShrU,
+            Operator::I32Rotl { .. } => Opcode::I32Rotl,
+            Operator::I32Rotr { .. } => Opcode::I32Rotr,
+            Operator::I64Clz { .. } => Opcode::I64Clz,
+            Operator::I64Ctz { .. } => Opcode::I64Ctz,
+            Operator::I64Popcnt { .. } => Opcode::I64Popcnt,
+            Operator::I64Add { .. } => Opcode::I64Add,
+            Operator::I64Sub { .. } => Opcode::I64Sub,
+            Operator::I64Mul { .. } => Opcode::I64Mul,
+            Operator::I64DivS { .. } => Opcode::I64DivS,
+            Operator::I64DivU { .. } => Opcode::I64DivU,
+            Operator::I64RemS { .. } => Opcode::I64RemS,
+            Operator::I64RemU { .. } => Opcode::I64RemU,
+            Operator::I64And { .. } => Opcode::I64And,
+            Operator::I64Or { .. } => Opcode::I64Or,
+            Operator::I64Xor { .. } => Opcode::I64Xor,
+            Operator::I64Shl { .. } => Opcode::I64Shl,
+            Operator::I64ShrS { .. } => Opcode::I64ShrS,
+            Operator::I64ShrU { .. } => Opcode::I64ShrU,
+            Operator::I64Rotl { .. } => Opcode::I64Rotl,
+            Operator::I64Rotr { .. } => Opcode::I64Rotr,
+            Operator::F32Abs { .. } => Opcode::F32Abs,
+            Operator::F32Neg { .. } => Opcode::F32Neg,
+            Operator::F32Ceil { .. } => Opcode::F32Ceil,
+            Operator::F32Floor { .. } => Opcode::F32Floor,
+            Operator::F32Trunc { .. } => Opcode::F32Trunc,
+            Operator::F32Nearest { .. } => Opcode::F32Nearest,
+            Operator::F32Sqrt { .. } => Opcode::F32Sqrt,
+            Operator::F32Add { .. } => Opcode::F32Add,
+            Operator::F32Sub { .. } => Opcode::F32Sub,
+            Operator::F32Mul { .. } => Opcode::F32Mul,
+            Operator::F32Div { .. } => Opcode::F32Div,
+            Operator::F32Min { .. } => Opcode::F32Min,
+            Operator::F32Max { .. } => Opcode::F32Max,
+            Operator::F32Copysign { .. } => Opcode::F32Copysign,
+            Operator::F64Abs { .. } => Opcode::F64Abs,
+            Operator::F64Neg { .. } => Opcode::F64Neg,
+            Operator::F64Ceil { .. } => Opcode::F64Ceil,
+            Operator::F64Floor { .. } => Opcode::F64Floor,
+            Operator::F64Trunc { .. } => Opcode::F64Trunc,
+            Operator::F64Nearest { .. } => Opcode::F64Nearest,
+            Operator::F64Sqrt { .. } => Opcode::F64Sqrt,
+            Operator::F64Add { .. } => Opcode::F64Add,
+            Operator::F64Sub { .. } => Opcode::F64Sub,
+            Operator::F64Mul { .. } => Opcode::F64Mul,
+            Operator::F64Div { .. } => Opcode::F64Div,
+            Operator::F64Min { .. } => Opcode::F64Min,
+            Operator::F64Max { .. } => Opcode::F64Max,
+            Operator::F64Copysign { .. } => Opcode::F64Copysign,
+            Operator::I32WrapI64 { .. } => Opcode::I32WrapI64,
+            Operator::I32TruncF32S { .. } => Opcode::I32TruncF32S,
+            Operator::I32TruncF32U { .. } => Opcode::I32TruncF32U,
+            Operator::I32TruncF64S { .. } => Opcode::I32TruncF64S,
+            Operator::I32TruncF64U { .. } => Opcode::I32TruncF64U,
+            Operator::I64ExtendI32S { .. } => Opcode::I64ExtendI32S,
+            Operator::I64ExtendI32U { .. } => Opcode::I64ExtendI32U,
+            Operator::I64TruncF32S { .. } => Opcode::I64TruncF32S,
+            Operator::I64TruncF32U { .. } => Opcode::I64TruncF32U,
+            Operator::I64TruncF64S { .. } => Opcode::I64TruncF64S,
+            Operator::I64TruncF64U { .. } => Opcode::I64TruncF64U,
+            Operator::F32ConvertI32S { .. } => Opcode::F32ConvertI32S,
+            Operator::F32ConvertI32U { .. } => Opcode::F32ConvertI32U,
+            Operator::F32ConvertI64S { .. } => Opcode::F32ConvertI64S,
+            Operator::F32ConvertI64U { .. } => Opcode::F32ConvertI64U,
+            Operator::F32DemoteF64 { .. } => Opcode::F32DemoteF64,
+            Operator::F64ConvertI32S { .. } => Opcode::F64ConvertI32S,
+            Operator::F64ConvertI32U { .. } => Opcode::F64ConvertI32U,
+            Operator::F64ConvertI64S { .. } => Opcode::F64ConvertI64S,
+            Operator::F64ConvertI64U { .. } => Opcode::F64ConvertI64U,
+            Operator::F64PromoteF32 { .. } => Opcode::F64PromoteF32,
+            Operator::I32ReinterpretF32 { .. } => Opcode::I32ReinterpretF32,
+            Operator::I64ReinterpretF64 { .. } => Opcode::I64ReinterpretF64,
+            Operator::F32ReinterpretI32 { .. } => Opcode::F32ReinterpretI32,
+            Operator::F64ReinterpretI64 { .. } => Opcode::F64ReinterpretI64,
+            Operator::I32Extend8S { .. } => Opcode::I32Extend8S,
+            Operator::I32Extend16S { .. } => Opcode::I32Extend16S,
+            Operator::I64Extend8S { .. } => Opcode::I64Extend8S,
+            Operator::I64Extend16S { .. } => Opcode::I64Extend16S,
+            Operator::I64Extend32S { .. } => Opcode::I64Extend32S,
+            Operator::I32TruncSatF32S { .. } => Opcode::I32TruncSatF32S,
+            Operator::I32TruncSatF32U { .. } => Opcode::I32TruncSatF32U,
+            Operator::I32TruncSatF64S { .. } => Opcode::I32TruncSatF64S,
+            Operator::I32TruncSatF64U { .. } => Opcode::I32TruncSatF64U,
+            Operator::I64TruncSatF32S { .. } => Opcode::I64TruncSatF32S,
+            Operator::I64TruncSatF32U { .. } => Opcode::I64TruncSatF32U,
+            Operator::I64TruncSatF64S { .. } => Opcode::I64TruncSatF64S,
+            Operator::I64TruncSatF64U { .. } => Opcode::I64TruncSatF64U,
+            Operator::MemoryInit { .. } => Opcode::MemoryInit,
+            Operator::DataDrop { .. } => Opcode::DataDrop,
+            Operator::MemoryCopy { .. } => Opcode::MemoryCopy,
+            Operator::MemoryFill { .. } => Opcode::MemoryFill,
+            Operator::TableInit { .. } => Opcode::TableInit,
+            Operator::ElemDrop { .. } => Opcode::ElemDrop,
+            Operator::TableCopy { .. } => Opcode::TableCopy,
+            Operator::TableFill { .. } => Opcode::TableFill,
+            Operator::TableGet { .. } => Opcode::TableGet,
+            Operator::TableSet { .. } => Opcode::TableSet,
+            Operator::TableGrow { .. } => Opcode::TableGrow,
+            Operator::TableSize { .. } => Opcode::TableSize,
+            Operator::AtomicNotify { .. } => Opcode::AtomicNotify,
+            Operator::I32AtomicWait { .. } => Opcode::I32AtomicWait,
+            Operator::I64AtomicWait { .. } => Opcode::I64AtomicWait,
+            Operator::AtomicFence { .. } => Opcode::AtomicFence,
+            Operator::I32AtomicLoad { .. } => Opcode::I32AtomicLoad,
+            Operator::I64AtomicLoad { .. } => Opcode::I64AtomicLoad,
+            Operator::I32AtomicLoad8U { .. } => Opcode::I32AtomicLoad8U,
+            Operator::I32AtomicLoad16U { .. } => Opcode::I32AtomicLoad16U,
+            Operator::I64AtomicLoad8U { .. } => Opcode::I64AtomicLoad8U,
+            Operator::I64AtomicLoad16U { .. } => Opcode::I64AtomicLoad16U,
+            Operator::I64AtomicLoad32U { .. } => Opcode::I64AtomicLoad32U,
+            Operator::I32AtomicStore { .. } => Opcode::I32AtomicStore,
+            Operator::I64AtomicStore { .. } => Opcode::I64AtomicStore,
+            Operator::I32AtomicStore8 { .. } => Opcode::I32AtomicStore8,
+            Operator::I32AtomicStore16 { .. } => Opcode::I32AtomicStore16,
+            Operator::I64AtomicStore8 { .. } => Opcode::I64AtomicStore8,
+            Operator::I64AtomicStore16 { .. } => Opcode::I64AtomicStore16,
+            Operator::I64AtomicStore32 { .. } => Opcode::I64AtomicStore32,
+            Operator::I32AtomicRmwAdd { .. } => Opcode::I32AtomicRmwAdd,
+            Operator::I64AtomicRmwAdd { .. } => Opcode::I64AtomicRmwAdd,
+            Operator::I32AtomicRmw8AddU { .. } => Opcode::I32AtomicRmw8AddU,
+            Operator::I32AtomicRmw16AddU { .. } => Opcode::I32AtomicRmw16AddU,
+            Operator::I64AtomicRmw8AddU { .. } => Opcode::I64AtomicRmw8AddU,
+            Operator::I64AtomicRmw16AddU { .. } => Opcode::I64AtomicRmw16AddU,
+            Operator::I64AtomicRmw32AddU { .. } => Opcode::I64AtomicRmw32AddU,
+            Operator::I32AtomicRmwSub { .. } => Opcode::I32AtomicRmwSub,
+            Operator::I64AtomicRmwSub { .. } => Opcode::I64AtomicRmwSub,
+            Operator::I32AtomicRmw8SubU { .. } => Opcode::I32AtomicRmw8SubU,
+            Operator::I32AtomicRmw16SubU { .. } => Opcode::I32AtomicRmw16SubU,
+            Operator::I64AtomicRmw8SubU { .. } => Opcode::I64AtomicRmw8SubU,
+            Operator::I64AtomicRmw16SubU { .. } => Opcode::I64AtomicRmw16SubU,
+            Operator::I64AtomicRmw32SubU { .. } => Opcode::I64AtomicRmw32SubU,
+            Operator::I32AtomicRmwAnd { .. } => Opcode::I32AtomicRmwAnd,
+            Operator::I64AtomicRmwAnd { .. } => Opcode::I64AtomicRmwAnd,
+            Operator::I32AtomicRmw8AndU { .. } => Opcode::I32AtomicRmw8AndU,
+            Operator::I32AtomicRmw16AndU { .. } => Opcode::I32AtomicRmw16AndU,
+            Operator::I64AtomicRmw8AndU { .. } => Opcode::I64AtomicRmw8AndU,
+            Operator::I64AtomicRmw16AndU { .. } => Opcode::I64AtomicRmw16AndU,
+            Operator::I64AtomicRmw32AndU { .. } => Opcode::I64AtomicRmw32AndU,
+            Operator::I32AtomicRmwOr { .. } => Opcode::I32AtomicRmwOr,
+            Operator::I64AtomicRmwOr { .. } => Opcode::I64AtomicRmwOr,
+            Operator::I32AtomicRmw8OrU { .. } => Opcode::I32AtomicRmw8OrU,
+            Operator::I32AtomicRmw16OrU { .. } => Opcode::I32AtomicRmw16OrU,
+            Operator::I64AtomicRmw8OrU { .. } => Opcode::I64AtomicRmw8OrU,
+            Operator::I64AtomicRmw16OrU { .. } => Opcode::I64AtomicRmw16OrU,
+            Operator::I64AtomicRmw32OrU { .. } => Opcode::I64AtomicRmw32OrU,
+            Operator::I32AtomicRmwXor { .. } => Opcode::I32AtomicRmwXor,
+            Operator::I64AtomicRmwXor { .. } => Opcode::I64AtomicRmwXor,
+            Operator::I32AtomicRmw8XorU { .. } => Opcode::I32AtomicRmw8XorU,
+            Operator::I32AtomicRmw16XorU { .. } => Opcode::I32AtomicRmw16XorU,
+            Operator::I64AtomicRmw8XorU { .. } => Opcode::I64AtomicRmw8XorU,
+            Operator::I64AtomicRmw16XorU { .. } => Opcode::I64AtomicRmw16XorU,
+            Operator::I64AtomicRmw32XorU { .. } => Opcode::I64AtomicRmw32XorU,
+            Operator::I32AtomicRmwXchg { .. } => Opcode::I32AtomicRmwXchg,
+            Operator::I64AtomicRmwXchg { .. } => Opcode::I64AtomicRmwXchg,
+            Operator::I32AtomicRmw8XchgU { .. } => Opcode::I32AtomicRmw8XchgU,
+            Operator::I32AtomicRmw16XchgU { .. } => Opcode::I32AtomicRmw16XchgU,
+            Operator::I64AtomicRmw8XchgU { .. } => Opcode::I64AtomicRmw8XchgU,
+            Operator::I64AtomicRmw16XchgU { .. } => Opcode::I64AtomicRmw16XchgU,
+            Operator::I64AtomicRmw32XchgU { .. } => Opcode::I64AtomicRmw32XchgU,
+            Operator::I32AtomicRmwCmpxchg { .. } => Opcode::I32AtomicRmwCmpxchg,
+            Operator::I64AtomicRmwCmpxchg { .. } => Opcode::I64AtomicRmwCmpxchg,
+            Operator::I32AtomicRmw8CmpxchgU { .. } => Opcode::I32AtomicRmw8CmpxchgU,
+            Operator::I32AtomicRmw16CmpxchgU { .. } => Opcode::I32AtomicRmw16CmpxchgU,
+            Operator::I64AtomicRmw8CmpxchgU { .. } => Opcode::I64AtomicRmw8CmpxchgU,
+            Operator::I64AtomicRmw16CmpxchgU { .. } => Opcode::I64AtomicRmw16CmpxchgU,
+            Operator::I64AtomicRmw32CmpxchgU { .. } => Opcode::I64AtomicRmw32CmpxchgU,
+            Operator::V128Load { .. } => Opcode::V128Load,
+            Operator::V128Store { .. } => Opcode::V128Store,
+            Operator::V128Const { .. } => Opcode::V128Const,
+            Operator::I8x16Splat { .. } => Opcode::I8x16Splat,
+            Operator::I8x16ExtractLaneS { .. } => Opcode::I8x16ExtractLaneS,
+            Operator::I8x16ExtractLaneU { .. } => Opcode::I8x16ExtractLaneU,
+            Operator::I8x16ReplaceLane { .. } => Opcode::I8x16ReplaceLane,
+            Operator::I16x8Splat { .. } => Opcode::I16x8Splat,
+            Operator::I16x8ExtractLaneS { .. } => Opcode::I16x8ExtractLaneS,
+            Operator::I16x8ExtractLaneU { .. } => Opcode::I16x8ExtractLaneU,
+            Operator::I16x8ReplaceLane { .. } => Opcode::I16x8ReplaceLane,
+            Operator::I32x4Splat { .. } => Opcode::I32x4Splat,
+            Operator::I32x4ExtractLane { .. } => Opcode::I32x4ExtractLane,
+            Operator::I32x4ReplaceLane { .. } => Opcode::I32x4ReplaceLane,
+            Operator::I64x2Splat { .. } => Opcode::I64x2Splat,
+            Operator::I64x2ExtractLane { .. } => Opcode::I64x2ExtractLane,
+            Operator::I64x2ReplaceLane { .. } => Opcode::I64x2ReplaceLane,
+            Operator::F32x4Splat { .. } => Opcode::F32x4Splat,
+            Operator::F32x4ExtractLane { .. } => Opcode::F32x4ExtractLane,
+            Operator::F32x4ReplaceLane { .. } => Opcode::F32x4ReplaceLane,
+            Operator::F64x2Splat { .. } => Opcode::F64x2Splat,
+            Operator::F64x2ExtractLane { .. } => Opcode::F64x2ExtractLane,
+            Operator::F64x2ReplaceLane { .. } => Opcode::F64x2ReplaceLane,
+            Operator::I8x16Eq { .. } => Opcode::I8x16Eq,
+            Operator::I8x16Ne { .. } => Opcode::I8x16Ne,
+            Operator::I8x16LtS { .. } => Opcode::I8x16LtS,
+            Operator::I8x16LtU { .. } => Opcode::I8x16LtU,
+            Operator::I8x16GtS { .. } => Opcode::I8x16GtS,
+            Operator::I8x16GtU { .. } => Opcode::I8x16GtU,
+            Operator::I8x16LeS { .. } => Opcode::I8x16LeS,
+            Operator::I8x16LeU { .. } => Opcode::I8x16LeU,
+            Operator::I8x16GeS { .. } => Opcode::I8x16GeS,
+            Operator::I8x16GeU { .. } => Opcode::I8x16GeU,
+            Operator::I16x8Eq { .. } => Opcode::I16x8Eq,
+            Operator::I16x8Ne { .. } => Opcode::I16x8Ne,
+            Operator::I16x8LtS { .. } => Opcode::I16x8LtS,
+            Operator::I16x8LtU { .. } => Opcode::I16x8LtU,
+            Operator::I16x8GtS { .. } => Opcode::I16x8GtS,
+            Operator::I16x8GtU { .. } => Opcode::I16x8GtU,
+            Operator::I16x8LeS { .. } => Opcode::I16x8LeS,
+            Operator::I16x8LeU { .. } => Opcode::I16x8LeU,
+            Operator::I16x8GeS { .. } => Opcode::I16x8GeS,
+            Operator::I16x8GeU { .. } => Opcode::I16x8GeU,
+            Operator::I32x4Eq { .. } => Opcode::I32x4Eq,
+            Operator::I32x4Ne { .. } => Opcode::I32x4Ne,
+            Operator::I32x4LtS { .. } => Opcode::I32x4LtS,
+            Operator::I32x4LtU { .. } => Opcode::I32x4LtU,
+            Operator::I32x4GtS { .. } => Opcode::I32x4GtS,
+            Operator::I32x4GtU { .. } => Opcode::I32x4GtU,
+            Operator::I32x4LeS { .. } => Opcode::I32x4LeS,
+            Operator::I32x4LeU { .. } => Opcode::I32x4LeU,
+            Operator::I32x4GeS { .. } => Opcode::I32x4GeS,
+            Operator::I32x4GeU { .. } => Opcode::I32x4GeU,
+            Operator::F32x4Eq { .. } => Opcode::F32x4Eq,
+            Operator::F32x4Ne { .. } => Opcode::F32x4Ne,
+            Operator::F32x4Lt { .. } => Opcode::F32x4Lt,
+            Operator::F32x4Gt { .. } => Opcode::F32x4Gt,
+            Operator::F32x4Le { .. } => Opcode::F32x4Le,
+            Operator::F32x4Ge { .. } => Opcode::F32x4Ge,
+            Operator::F64x2Eq { .. } => Opcode::F64x2Eq,
+            Operator::F64x2Ne { .. } => Opcode::F64x2Ne,
+            Operator::F64x2Lt { .. } => Opcode::F64x2Lt,
+            Operator::F64x2Gt { .. } => Opcode::F64x2Gt,
+            Operator::F64x2Le { .. } => Opcode::F64x2Le,
+            Operator::F64x2Ge { .. } => Opcode::F64x2Ge,
+            Operator::V128Not { .. } => Opcode::V128Not,
+            Operator::V128And { .. } => Opcode::V128And,
+            Operator::V128AndNot { .. } => Opcode::V128AndNot,
+            Operator::V128Or { .. } => Opcode::V128Or,
+            Operator::V128Xor { .. } => Opcode::V128Xor,
+            Operator::V128Bitselect { .. } => Opcode::V128Bitselect,
+            Operator::I8x16Neg { .. } => Opcode::I8x16Neg,
+            Operator::I8x16AnyTrue { .. } => Opcode::I8x16AnyTrue,
+            Operator::I8x16AllTrue { .. } => Opcode::I8x16AllTrue,
+            Operator::I8x16Shl { .. } => Opcode::I8x16Shl,
+            Operator::I8x16ShrS { .. } => Opcode::I8x16ShrS,
+            Operator::I8x16ShrU { .. } => Opcode::I8x16ShrU,
+            Operator::I8x16Add { .. } => Opcode::I8x16Add,
+            Operator::I8x16AddSaturateS { .. } => Opcode::I8x16AddSaturateS,
+            Operator::I8x16AddSaturateU { .. } => Opcode::I8x16AddSaturateU,
+            Operator::I8x16Sub { .. } => Opcode::I8x16Sub,
+            Operator::I8x16SubSaturateS { .. } => Opcode::I8x16SubSaturateS,
+            Operator::I8x16SubSaturateU { .. } => Opcode::I8x16SubSaturateU,
+            Operator::I8x16MinS { .. } => Opcode::I8x16MinS,
+            Operator::I8x16MinU { .. } => Opcode::I8x16MinU,
+            Operator::I8x16MaxS { .. } => Opcode::I8x16MaxS,
+            Operator::I8x16MaxU { .. } => Opcode::I8x16MaxU,
+            Operator::I8x16Mul { .. } => Opcode::I8x16Mul,
+            Operator::I16x8Neg { .. } => Opcode::I16x8Neg,
+            Operator::I16x8AnyTrue { .. } => Opcode::I16x8AnyTrue,
+            Operator::I16x8AllTrue { .. } => Opcode::I16x8AllTrue,
+            Operator::I16x8Shl { .. } => Opcode::I16x8Shl,
+            Operator::I16x8ShrS { .. } => Opcode::I16x8ShrS,
+            Operator::I16x8ShrU { .. } => Opcode::I16x8ShrU,
+            Operator::I16x8Add { .. } => Opcode::I16x8Add,
+            Operator::I16x8AddSaturateS { .. } => Opcode::I16x8AddSaturateS,
+            Operator::I16x8AddSaturateU { .. } => Opcode::I16x8AddSaturateU,
+            Operator::I16x8Sub { .. } => Opcode::I16x8Sub,
+            Operator::I16x8SubSaturateS { .. } => Opcode::I16x8SubSaturateS,
+            Operator::I16x8SubSaturateU { .. } => Opcode::I16x8SubSaturateU,
+            Operator::I16x8Mul { .. } => Opcode::I16x8Mul,
+            Operator::I16x8MinS { .. } => Opcode::I16x8MinS,
+            Operator::I16x8MinU { .. } => Opcode::I16x8MinU,
+            Operator::I16x8MaxS { .. } => Opcode::I16x8MaxS,
+            Operator::I16x8MaxU { .. } => Opcode::I16x8MaxU,
+            Operator::I32x4Neg { .. } => Opcode::I32x4Neg,
+            Operator::I32x4AnyTrue { .. } => Opcode::I32x4AnyTrue,
+            Operator::I32x4AllTrue { .. } => Opcode::I32x4AllTrue,
+            Operator::I32x4Shl { .. } => Opcode::I32x4Shl,
+            Operator::I32x4ShrS { .. } => Opcode::I32x4ShrS,
+            Operator::I32x4ShrU { .. } => Opcode::I32x4ShrU,
+            Operator::I32x4Add { .. } => Opcode::I32x4Add,
+            Operator::I32x4Sub { .. } => Opcode::I32x4Sub,
+            Operator::I32x4Mul { .. } => Opcode::I32x4Mul,
+            Operator::I32x4MinS { .. } => Opcode::I32x4MinS,
+            Operator::I32x4MinU { .. } => Opcode::I32x4MinU,
+            Operator::I32x4MaxS { .. } => Opcode::I32x4MaxS,
+            Operator::I32x4MaxU { .. } => Opcode::I32x4MaxU,
+            Operator::I64x2Neg { .. } => Opcode::I64x2Neg,
+            Operator::I64x2AnyTrue { .. } => Opcode::I64x2AnyTrue,
+            Operator::I64x2AllTrue { .. } => Opcode::I64x2AllTrue,
+            Operator::I64x2Shl { .. } => Opcode::I64x2Shl,
+            Operator::I64x2ShrS { .. } => Opcode::I64x2ShrS,
+            Operator::I64x2ShrU { .. } => Opcode::I64x2ShrU,
+            Operator::I64x2Add { .. } => Opcode::I64x2Add,
+            Operator::I64x2Sub { .. } => Opcode::I64x2Sub,
+            Operator::I64x2Mul { .. } => Opcode::I64x2Mul,
+            Operator::F32x4Abs { .. } => Opcode::F32x4Abs,
+            Operator::F32x4Neg { .. } => Opcode::F32x4Neg,
+            Operator::F32x4Sqrt { .. } => Opcode::F32x4Sqrt,
+            Operator::F32x4Add { .. } => Opcode::F32x4Add,
+            Operator::F32x4Sub { .. } => Opcode::F32x4Sub,
+            Operator::F32x4Mul { .. } => Opcode::F32x4Mul,
+            Operator::F32x4Div { .. } => Opcode::F32x4Div,
+            Operator::F32x4Min { .. } => Opcode::F32x4Min,
+            Operator::F32x4Max { .. } => Opcode::F32x4Max,
+            Operator::F64x2Abs { .. } => Opcode::F64x2Abs,
+            Operator::F64x2Neg { .. } => Opcode::F64x2Neg,
+            Operator::F64x2Sqrt { .. } => Opcode::F64x2Sqrt,
+            Operator::F64x2Add { .. } => Opcode::F64x2Add,
+            Operator::F64x2Sub { .. } => Opcode::F64x2Sub,
+            Operator::F64x2Mul { .. } => Opcode::F64x2Mul,
+            Operator::F64x2Div { .. } => Opcode::F64x2Div,
+            Operator::F64x2Min { .. } => Opcode::F64x2Min,
+            Operator::F64x2Max { .. } => Opcode::F64x2Max,
+            Operator::I32x4TruncSatF32x4S { .. } => Opcode::I32x4TruncSatF32x4S,
+            Operator::I32x4TruncSatF32x4U { .. } => Opcode::I32x4TruncSatF32x4U,
+            Operator::I64x2TruncSatF64x2S { .. } => Opcode::I64x2TruncSatF64x2S,
+            Operator::I64x2TruncSatF64x2U { .. } => Opcode::I64x2TruncSatF64x2U,
+            Operator::F32x4ConvertI32x4S { .. } => Opcode::F32x4ConvertI32x4S,
+            Operator::F32x4ConvertI32x4U { .. } => Opcode::F32x4ConvertI32x4U,
+            Operator::F64x2ConvertI64x2S { .. } => Opcode::F64x2ConvertI64x2S,
+            Operator::F64x2ConvertI64x2U { .. } => Opcode::F64x2ConvertI64x2U,
+            Operator::V8x16Swizzle { .. } => Opcode::V8x16Swizzle,
+            Operator::V8x16Shuffle { .. } => Opcode::V8x16Shuffle,
+            Operator::V8x16LoadSplat { .. } => Opcode::V8x16LoadSplat,
+            Operator::V16x8LoadSplat { .. } => Opcode::V16x8LoadSplat,
+            Operator::V32x4LoadSplat { .. } => Opcode::V32x4LoadSplat,
+            Operator::V64x2LoadSplat { .. } => Opcode::V64x2LoadSplat,
+            Operator::I8x16NarrowI16x8S { .. } => Opcode::I8x16NarrowI16x8S,
+            Operator::I8x16NarrowI16x8U { .. } => Opcode::I8x16NarrowI16x8U,
+            Operator::I16x8NarrowI32x4S { .. } => Opcode::I16x8NarrowI32x4S,
+            Operator::I16x8NarrowI32x4U { .. } => Opcode::I16x8NarrowI32x4U,
+            Operator::I16x8WidenLowI8x16S { .. } => Opcode::I16x8WidenLowI8x16S,
+            Operator::I16x8WidenHighI8x16S { .. } => Opcode::I16x8WidenHighI8x16S,
+            Operator::I16x8WidenLowI8x16U { .. } => Opcode::I16x8WidenLowI8x16U,
+            Operator::I16x8WidenHighI8x16U { .. } => Opcode::I16x8WidenHighI8x16U,
+            Operator::I32x4WidenLowI16x8S { .. } => Opcode::I32x4WidenLowI16x8S,
+            Operator::I32x4WidenHighI16x8S { .. } => Opcode::I32x4WidenHighI16x8S,
+            Operator::I32x4WidenLowI16x8U { .. } => Opcode::I32x4WidenLowI16x8U,
+            Operator::I32x4WidenHighI16x8U { .. } => Opcode::I32x4WidenHighI16x8U,
+            Operator::I16x8Load8x8S { .. } => Opcode::I16x8Load8x8S,
+            Operator::I16x8Load8x8U { .. } => Opcode::I16x8Load8x8U,
+            Operator::I32x4Load16x4S { .. } => Opcode::I32x4Load16x4S,
+            Operator::I32x4Load16x4U { .. } => Opcode::I32x4Load16x4U,
+            Operator::I64x2Load32x2S { .. } => Opcode::I64x2Load32x2S,
+            Operator::I64x2Load32x2U { .. } => Opcode::I64x2Load32x2U,
+            Operator::I8x16RoundingAverageU { .. } => Opcode::I8x16RoundingAverageU,
+            Operator::I16x8RoundingAverageU { .. } => Opcode::I16x8RoundingAverageU,
+        }
+    }
+
+    /// Returns the numeric code of the opcode.
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+
+    /// Returns the mnemonic of the opcode, in the wasm text format.
+    pub fn name(self) -> &'static str {
+        OPCODE_NAMES[self as usize]
+    }
+
+    /// Returns the WebAssembly proposal the opcode belongs to, if it is
+    /// not part of the MVP.
+    pub fn proposal(self) -> Option<Proposal> {
+        match self {
+            Opcode::MemoryInit
+            | Opcode::DataDrop
+            | Opcode::MemoryCopy
+            | Opcode::MemoryFill
+            | Opcode::TableInit
+            | Opcode::ElemDrop
+            | Opcode::TableCopy => Some(Proposal::BulkMemory),
+            Opcode::TypedSelect
+            | Opcode::RefNull
+            | Opcode::RefIsNull
+            | Opcode::RefFunc
+            | Opcode::TableFill
+            | Opcode::TableGet
+            | Opcode::TableSet
+            | Opcode::TableGrow
+            | Opcode::TableSize => Some(Proposal::ReferenceTypes),
+            // The atomic opcodes come right before the SIMD ones, which are
+            // the last opcodes.
+            _ if self.code() >= Opcode::V128Load.code() => Some(Proposal::Simd),
+            _ if self.code() >= Opcode::AtomicNotify.code() => Some(Proposal::Threads),
+            _ => None,
+        }
+    }
+}
+
+/// The WebAssembly proposals which can be enabled when compiling a
+/// module, see `Opcode::proposal`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Proposal {
+    BulkMemory,
+    ReferenceTypes,
+    Simd,
+    Threads,
+}
+
+/// Returns the mnemonic of the opcode with the given code, or `None`
+/// if the code is unknown.
+pub fn get_opcode_name(code: u16) -> Option<&'static str> {
+    OPCODE_NAMES.get(code as usize).copied()
+}
+
+static OPCODE_NAMES: [&str; OPCODE_CODE_COUNT] = [
+    "unreachable",
+    "nop",
+    "block",
+    "loop",
+    "if",
+    "else",
+    "end",
+    "br",
+    "br_if",
+    "br_table",
+    "return",
+    "call",
+    "call_indirect",
+    "drop",
+    "select",
+    "select",
+    "local.get",
+    "local.set",
+    "local.tee",
+    "global.get",
+    "global.set",
+    "i32.load",
+    "i64.load",
+    "f32.load",
+    "f64.load",
+    "i32.load8_s",
+    "i32.load8_u",
+    "i32.load16_s",
+    "i32.load16_u",
+    "i64.load8_s",
+    "i64.load8_u",
+    "i64.load16_s",
+    "i64.load16_u",
+    "i64.load32_s",
+    "i64.load32_u",
+    "i32.store",
+    "i64.store",
+    "f32.store",
+    "f64.store",
+    "i32.store8",
+    "i32.store16",
+    "i64.store8",
+    "i64.store16",
+    "i64.store32",
+    "memory.size",
+    "memory.grow",
+    "i32.const",
+    "i64.const",
+    "f32.const",
+    "f64.const",
+    "ref.null",
+    "ref.is_null",
+    "ref.func",
+    "i32.eqz",
+    "i32.eq",
+    "i32.ne",
+    "i32.lt_s",
+    "i32.lt_u",
+    "i32.gt_s",
+    "i32.gt_u",
+    "i32.le_s",
+    "i32.le_u",
+    "i32.ge_s",
+    "i32.ge_u",
+    "i64.eqz",
+    "i64.eq",
+    "i64.ne",
+    "i64.lt_s",
+    "i64.lt_u",
+    "i64.gt_s",
+    "i64.gt_u",
+    "i64.le_s",
+    "i64.le_u",
+    "i64.ge_s",
+    "i64.ge_u",
+    "f32.eq",
+    "f32.ne",
+    "f32.lt",
+    "f32.gt",
+    "f32.le",
+    "f32.ge",
+    "f64.eq",
+    "f64.ne",
+    "f64.lt",
+    "f64.gt",
+    "f64.le",
+    "f64.ge",
+    "i32.clz",
+    "i32.ctz",
+    "i32.popcnt",
+    "i32.add",
+    "i32.sub",
+    "i32.mul",
+    "i32.div_s",
+    "i32.div_u",
+    "i32.rem_s",
+    "i32.rem_u",
+    "i32.and",
+    "i32.or",
+    "i32.xor",
+    "i32.shl",
+    "i32.shr_s",
+    "i32.shr_u",
+    "i32.rotl",
+    "i32.rotr",
+    "i64.clz",
+    "i64.ctz",
+    "i64.popcnt",
+    "i64.add",
+    "i64.sub",
+    "i64.mul",
+    "i64.div_s",
+    "i64.div_u",
+    "i64.rem_s",
+    "i64.rem_u",
+    "i64.and",
+    "i64.or",
+    "i64.xor",
+    "i64.shl",
+    "i64.shr_s",
+    "i64.shr_u",
+    "i64.rotl",
+    "i64.rotr",
+    "f32.abs",
+    "f32.neg",
+    "f32.ceil",
+    "f32.floor",
+    "f32.trunc",
+    "f32.nearest",
+    "f32.sqrt",
+    "f32.add",
+    "f32.sub",
+    "f32.mul",
+    "f32.div",
+    "f32.min",
+    "f32.max",
+    "f32.copysign",
+    "f64.abs",
+    "f64.neg",
+    "f64.ceil",
+    "f64.floor",
+    "f64.trunc",
+    "f64.nearest",
+    "f64.sqrt",
+    "f64.add",
+    "f64.sub",
+    "f64.mul",
+    "f64.div",
+    "f64.min",
+    "f64.max",
+    "f64.copysign",
+    "i32.wrap_i64",
+    "i32.trunc_f32_s",
+    "i32.trunc_f32_u",
+    "i32.trunc_f64_s",
+    "i32.trunc_f64_u",
+    "i64.extend_i32_s",
+    "i64.extend_i32_u",
+    "i64.trunc_f32_s",
+    "i64.trunc_f32_u",
+    "i64.trunc_f64_s",
+    "i64.trunc_f64_u",
+    "f32.convert_i32_s",
+    "f32.convert_i32_u",
+    "f32.convert_i64_s",
+    "f32.convert_i64_u",
+    "f32.demote_f64",
+    "f64.convert_i32_s",
+    "f64.convert_i32_u",
+    "f64.convert_i64_s",
+    "f64.convert_i64_u",
+    "f64.promote_f32",
+    "i32.reinterpret_f32",
+    "i64.reinterpret_f64",
+    "f32.reinterpret_i32",
+    "f64.reinterpret_i64",
+    "i32.extend8_s",
+    "i32.extend16_s",
+    "i64.extend8_s",
+    "i64.extend16_s",
+    "i64.extend32_s",
+    "i32.trunc_sat_f32_s",
+    "i32.trunc_sat_f32_u",
+    "i32.trunc_sat_f64_s",
+    "i32.trunc_sat_f64_u",
+    "i64.trunc_sat_f32_s",
+    "i64.trunc_sat_f32_u",
+    "i64.trunc_sat_f64_s",
+    "i64.trunc_sat_f64_u",
+    "memory.init",
+    "data.drop",
+    "memory.copy",
+    "memory.fill",
+    "table.init",
+    "elem.drop",
+    "table.copy",
+    "table.fill",
+    "table.get",
+    "table.set",
+    "table.grow",
+    "table.size",
+    "atomic.notify",
+    "i32.atomic.wait",
+    "i64.atomic.wait",
+    "atomic.fence",
+    "i32.atomic.load",
+    "i64.atomic.load",
+    "i32.atomic.load8_u",
+    "i32.atomic.load16_u",
+    "i64.atomic.load8_u",
+    "i64.atomic.load16_u",
+    "i64.atomic.load32_u",
+    "i32.atomic.store",
+    "i64.atomic.store",
+    "i32.atomic.store8",
+    "i32.atomic.store16",
+    "i64.atomic.store8",
+    "i64.atomic.store16",
+    "i64.atomic.store32",
+    "i32.atomic.rmw.add",
+    "i64.atomic.rmw.add",
+    "i32.atomic.rmw8.add_u",
+    "i32.atomic.rmw16.add_u",
+    "i64.atomic.rmw8.add_u",
+    "i64.atomic.rmw16.add_u",
+    "i64.atomic.rmw32.add_u",
+    "i32.atomic.rmw.sub",
+    "i64.atomic.rmw.sub",
+    "i32.atomic.rmw8.sub_u",
+    "i32.atomic.rmw16.sub_u",
+    "i64.atomic.rmw8.sub_u",
+    "i64.atomic.rmw16.sub_u",
+    "i64.atomic.rmw32.sub_u",
+    "i32.atomic.rmw.and",
+    "i64.atomic.rmw.and",
+    "i32.atomic.rmw8.and_u",
+    "i32.atomic.rmw16.and_u",
+    "i64.atomic.rmw8.and_u",
+    "i64.atomic.rmw16.and_u",
+    "i64.atomic.rmw32.and_u",
+    "i32.atomic.rmw.or",
+    "i64.atomic.rmw.or",
+    "i32.atomic.rmw8.or_u",
+    "i32.atomic.rmw16.or_u",
+    "i64.atomic.rmw8.or_u",
+    "i64.atomic.rmw16.or_u",
+    "i64.atomic.rmw32.or_u",
+    "i32.atomic.rmw.xor",
+    "i64.atomic.rmw.xor",
+    "i32.atomic.rmw8.xor_u",
+    "i32.atomic.rmw16.xor_u",
+    "i64.atomic.rmw8.xor_u",
+    "i64.atomic.rmw16.xor_u",
+    "i64.atomic.rmw32.xor_u",
+    "i32.atomic.rmw.xchg",
+    "i64.atomic.rmw.xchg",
+    "i32.atomic.rmw8.xchg_u",
+    "i32.atomic.rmw16.xchg_u",
+    "i64.atomic.rmw8.xchg_u",
+    "i64.atomic.rmw16.xchg_u",
+    "i64.atomic.rmw32.xchg_u",
+    "i32.atomic.rmw.cmpxchg",
+    "i64.atomic.rmw.cmpxchg",
+    "i32.atomic.rmw8.cmpxchg_u",
+    "i32.atomic.rmw16.cmpxchg_u",
+    "i64.atomic.rmw8.cmpxchg_u",
+    "i64.atomic.rmw16.cmpxchg_u",
+    "i64.atomic.rmw32.cmpxchg_u",
+    "v128.load",
+    "v128.store",
+    "v128.const",
+    "i8x16.splat",
+    "i8x16.extract_lane_s",
+    "i8x16.extract_lane_u",
+    "i8x16.replace_lane",
+    "i16x8.splat",
+    "i16x8.extract_lane_s",
+    "i16x8.extract_lane_u",
+    "i16x8.replace_lane",
+    "i32x4.splat",
+    "i32x4.extract_lane",
+    "i32x4.replace_lane",
+    "i64x2.splat",
+    "i64x2.extract_lane",
+    "i64x2.replace_lane",
+    "f32x4.splat",
+    "f32x4.extract_lane",
+    "f32x4.replace_lane",
+    "f64x2.splat",
+    "f64x2.extract_lane",
+    "f64x2.replace_lane",
+    "i8x16.eq",
+    "i8x16.ne",
+    "i8x16.lt_s",
+    "i8x16.lt_u",
+    "i8x16.gt_s",
+    "i8x16.gt_u",
+    "i8x16.le_s",
+    "i8x16.le_u",
+    "i8x16.ge_s",
+    "i8x16.ge_u",
+    "i16x8.eq",
+    "i16x8.ne",
+    "i16x8.lt_s",
+    "i16x8.lt_u",
+    "i16x8.gt_s",
+    "i16x8.gt_u",
+    "i16x8.le_s",
+    "i16x8.le_u",
+    "i16x8.ge_s",
+    "i16x8.ge_u",
+    "i32x4.eq",
+    "i32x4.ne",
+    "i32x4.lt_s",
+    "i32x4.lt_u",
+    "i32x4.gt_s",
+    "i32x4.gt_u",
+    "i32x4.le_s",
+    "i32x4.le_u",
+    "i32x4.ge_s",
+    "i32x4.ge_u",
+    "f32x4.eq",
+    "f32x4.ne",
+    "f32x4.lt",
+    "f32x4.gt",
+    "f32x4.le",
+    "f32x4.ge",
+    "f64x2.eq",
+    "f64x2.ne",
+    "f64x2.lt",
+    "f64x2.gt",
+    "f64x2.le",
+    "f64x2.ge",
+    "v128.not",
+    "v128.and",
+    "v128.andnot",
+    "v128.or",
+    "v128.xor",
+    "v128.bitselect",
+    "i8x16.neg",
+    "i8x16.any_true",
+    "i8x16.all_true",
+    "i8x16.shl",
+    "i8x16.shr_s",
+    "i8x16.shr_u",
+    "i8x16.add",
+    "i8x16.add_saturate_s",
+    "i8x16.add_saturate_u",
+    "i8x16.sub",
+    "i8x16.sub_saturate_s",
+    "i8x16.sub_saturate_u",
+    "i8x16.min_s",
+    "i8x16.min_u",
+    "i8x16.max_s",
+    "i8x16.max_u",
+    "i8x16.mul",
+    "i16x8.neg",
+    "i16x8.any_true",
+    "i16x8.all_true",
+    "i16x8.shl",
+    "i16x8.shr_s",
+    "i16x8.shr_u",
+    "i16x8.add",
+    "i16x8.add_saturate_s",
+    "i16x8.add_saturate_u",
+    "i16x8.sub",
+    "i16x8.sub_saturate_s",
+    "i16x8.sub_saturate_u",
+    "i16x8.mul",
+    "i16x8.min_s",
+    "i16x8.min_u",
+    "i16x8.max_s",
+    "i16x8.max_u",
+    "i32x4.neg",
+    "i32x4.any_true",
+    "i32x4.all_true",
+    "i32x4.shl",
+    "i32x4.shr_s",
+    "i32x4.shr_u",
+    "i32x4.add",
+    "i32x4.sub",
+    "i32x4.mul",
+    "i32x4.min_s",
+    "i32x4.min_u",
+    "i32x4.max_s",
+    "i32x4.max_u",
+    "i64x2.neg",
+    "i64x2.any_true",
+    "i64x2.all_true",
+    "i64x2.shl",
+    "i64x2.shr_s",
+    "i64x2.shr_u",
+    "i64x2.add",
+    "i64x2.sub",
+    "i64x2.mul",
+    "f32x4.abs",
+    "f32x4.neg",
+    "f32x4.sqrt",
+    "f32x4.add",
+    "f32x4.sub",
+    "f32x4.mul",
+    "f32x4.div",
+    "f32x4.min",
+    "f32x4.max",
+    "f64x2.abs",
+    "f64x2.neg",
+    "f64x2.sqrt",
+    "f64x2.add",
+    "f64x2.sub",
+    "f64x2.mul",
+    "f64x2.div",
+    "f64x2.min",
+    "f64x2.max",
+    "i32x4.trunc_sat_f32x4_s",
+    "i32x4.trunc_sat_f32x4_u",
+    "i64x2.trunc_sat_f64x2_s",
+    "i64x2.trunc_sat_f64x2_u",
+    "f32x4.convert_i32x4_s",
+    "f32x4.convert_i32x4_u",
+    "f64x2.convert_i64x2_s",
+    "f64x2.convert_i64x2_u",
+    "v8x16.swizzle",
+    "v8x16.shuffle",
+    "v8x16.load_splat",
+    "v16x8.load_splat",
+    "v32x4.load_splat",
+    "v64x2.load_splat",
+    "i8x16.narrow_i16x8_s",
+    "i8x16.narrow_i16x8_u",
+    "i16x8.narrow_i32x4_s",
+    "i16x8.narrow_i32x4_u",
+    "i16x8.widen_low_i8x16_s",
+    "i16x8.widen_high_i8x16_s",
+    "i16x8.widen_low_i8x16_u",
+    "i16x8.widen_high_i8x16_u",
+    "i32x4.widen_low_i16x8_s",
+    "i32x4.widen_high_i16x8_s",
+    "i32x4.widen_low_i16x8_u",
+    "i32x4.widen_high_i16x8_u",
+    "i16x8.load8x8_s",
+    "i16x8.load8x8_u",
+    "i32x4.load16x4_s",
+    "i32x4.load16x4_u",
+    "i64x2.load32x2_s",
+    "i64x2.load32x2_u",
+    "i8x16.avgr_u",
+    "i16x8.avgr_u",
+];
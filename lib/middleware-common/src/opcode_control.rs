@@ -1,17 +1,30 @@
 use wasmer_runtime_core::{
     codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
     module::ModuleInfo,
+    structures::TypedIndex,
+    types::TableIndex,
+    units::Pages,
     vm::InternalField,
     wasmparser::{Operator, Type as WpType, TypeOrFuncType as WpTypeOrFuncType},
     Instance,
 };
 
-use crate::runtime_breakpoints::{push_runtime_breakpoint, BREAKPOINT_VALUE_MEMORY_LIMIT};
+use crate::runtime_breakpoints::{
+    push_runtime_breakpoint, BREAKPOINT_VALUE_MEMORY_LIMIT, BREAKPOINT_VALUE_TABLE_LIMIT,
+};
 
 static FIELD_MEMORY_GROW_COUNT: InternalField = InternalField::allocate();
 
 static FIELD_OPERAND_BACKUP: InternalField = InternalField::allocate();
 
+static FIELD_MAX_TABLE_ELEMENTS: InternalField = InternalField::allocate();
+
+static FIELD_MEMORY_GROW_LIMITED: InternalField = InternalField::allocate();
+
+static FIELD_MAX_MEMORY_GROW: InternalField = InternalField::allocate();
+
+static FIELD_MAX_MEMORY_GROW_DELTA: InternalField = InternalField::allocate();
+
 pub struct OpcodeControl {
     pub max_memory_grow: usize,
     pub max_memory_grow_delta: usize,
@@ -65,6 +78,34 @@ impl OpcodeControl {
         push_runtime_breakpoint(sink, BREAKPOINT_VALUE_MEMORY_LIMIT);
         sink.push(Event::WasmOwned(Operator::End));
     }
+
+    fn inject_table_elements_limit(&mut self, table: u32, sink: &mut EventSink) {
+        // A limit of 0 means no limit.
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_MAX_TABLE_ELEMENTS.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64Const { value: 0 }));
+        sink.push(Event::WasmOwned(Operator::I64Ne));
+        sink.push(Event::WasmOwned(Operator::If {
+            ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+        }));
+        sink.push(Event::WasmOwned(Operator::TableSize { table }));
+        sink.push(Event::WasmOwned(Operator::I64ExtendI32U));
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_OPERAND_BACKUP.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64Add));
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_MAX_TABLE_ELEMENTS.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64GtU));
+        sink.push(Event::WasmOwned(Operator::If {
+            ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+        }));
+        push_runtime_breakpoint(sink, BREAKPOINT_VALUE_TABLE_LIMIT);
+        sink.push(Event::WasmOwned(Operator::End));
+        sink.push(Event::WasmOwned(Operator::End));
+    }
 }
 
 impl FunctionMiddleware for OpcodeControl {
@@ -104,6 +145,18 @@ impl FunctionMiddleware for OpcodeControl {
                             FIELD_OPERAND_BACKUP.index() as _,
                         )));
                     }
+                    Operator::TableGrow { table } => {
+                        // Like for memory.grow, the delta is backed up to be checked, with the
+                        // current size of the table, against the max_table_elements limit of
+                        // the instance, then brought back for table.grow itself.
+                        sink.push(Event::Internal(InternalEvent::SetInternal(
+                            FIELD_OPERAND_BACKUP.index() as _,
+                        )));
+                        self.inject_table_elements_limit(table, sink);
+                        sink.push(Event::Internal(InternalEvent::GetInternal(
+                            FIELD_OPERAND_BACKUP.index() as _,
+                        )));
+                    }
                     _ => {}
                 }
             }
@@ -119,3 +172,90 @@ impl FunctionMiddleware for OpcodeControl {
 pub fn reset_memory_grow_count(instance: &mut Instance) {
     instance.set_internal(&FIELD_MEMORY_GROW_COUNT, 0);
 }
+
+/// Sets the limits `grow_memory` applies, which are those the `OpcodeControl` the Instance was
+/// compiled with applies to `memory.grow`. Without them, `grow_memory` applies no limit, as
+/// `memory.grow` does without `OpcodeControl`.
+pub fn set_memory_grow_limits(
+    instance: &mut Instance,
+    max_memory_grow: usize,
+    max_memory_grow_delta: usize,
+) {
+    instance.set_internal(&FIELD_MEMORY_GROW_LIMITED, 1);
+    instance.set_internal(&FIELD_MAX_MEMORY_GROW, max_memory_grow as u64);
+    instance.set_internal(&FIELD_MAX_MEMORY_GROW_DELTA, max_memory_grow_delta as u64);
+}
+
+/// Grows the memory at `memory_index` of an Instance by `delta` pages the way `memory.grow` does:
+/// the growth counts towards the `max_memory_grow` limit and may not exceed the
+/// `max_memory_grow_delta` limit, if `set_memory_grow_limits` set them, and the memory grow hook
+/// of the Instance must allow it. Returns the previous size of the memory, in pages.
+pub fn grow_memory(instance: &mut Instance, memory_index: u32, delta: u32) -> Result<u32, String> {
+    if instance.get_internal(&FIELD_MEMORY_GROW_LIMITED) != 0 {
+        check_memory_grow_limits(instance, delta)?;
+    }
+
+    instance
+        .context_mut()
+        .grow_memory(memory_index, Pages(delta))
+        .map(|previous_size| previous_size.0)
+}
+
+fn check_memory_grow_limits(instance: &mut Instance, delta: u32) -> Result<(), String> {
+    let count = instance.get_internal(&FIELD_MEMORY_GROW_COUNT);
+    let max_memory_grow = instance.get_internal(&FIELD_MAX_MEMORY_GROW);
+    if count >= max_memory_grow {
+        return Err(format!(
+            "the memory may not grow more than {} times",
+            max_memory_grow
+        ));
+    }
+    instance.set_internal(&FIELD_MEMORY_GROW_COUNT, count + 1);
+
+    let max_memory_grow_delta = instance.get_internal(&FIELD_MAX_MEMORY_GROW_DELTA);
+    if u64::from(delta) > max_memory_grow_delta {
+        return Err(format!(
+            "growing the memory by {} pages exceeds the limit of {} pages",
+            delta, max_memory_grow_delta
+        ));
+    }
+
+    Ok(())
+}
+
+/// Sets the maximum number of elements the tables of an Instance may grow to, with
+/// `table.grow` or with `grow_table`. A limit of 0 means no limit.
+pub fn set_max_table_elements(instance: &mut Instance, value: u32) {
+    instance.set_internal(&FIELD_MAX_TABLE_ELEMENTS, u64::from(value));
+}
+
+/// Returns the maximum number of elements the tables of an Instance may grow to, 0 meaning no
+/// limit.
+pub fn get_max_table_elements(instance: &Instance) -> u32 {
+    instance.get_internal(&FIELD_MAX_TABLE_ELEMENTS) as u32
+}
+
+/// Grows the table at `table_index` of an Instance by `delta` elements, within the
+/// `max_table_elements` limit of the Instance, and returns its previous size.
+pub fn grow_table(instance: &Instance, table_index: TableIndex, delta: u32) -> Result<u32, String> {
+    let size = instance
+        .table_size(table_index)
+        .ok_or_else(|| format!("table index {} is out of range", table_index.index()))?;
+    let max_table_elements = get_max_table_elements(instance);
+    if max_table_elements != 0 && u64::from(size) + u64::from(delta) > u64::from(max_table_elements)
+    {
+        return Err(format!(
+            "growing the table from {} by {} elements exceeds the limit of {} elements",
+            size, delta, max_table_elements
+        ));
+    }
+
+    match instance.table_grow(table_index, delta) {
+        Some(Ok(previous_size)) => Ok(previous_size),
+        Some(Err(error)) => Err(error.to_string()),
+        None => Err(format!(
+            "table index {} is out of range",
+            table_index.index()
+        )),
+    }
+}
@@ -0,0 +1,78 @@
+use wasmer_runtime_core::{
+    codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
+    module::ModuleInfo,
+};
+
+use crate::opcode_codes::{get_opcode_name, Opcode, OPCODE_CODE_COUNT};
+
+/// OpcodeDenylist is a compiler middleware that rejects the modules using any of a set of opcodes,
+/// identified by their codes, see `opcode_codes::Opcode`. It gives a finer control than the
+/// feature toggles of the compiler, e.g. to forbid the float operators only.
+///
+/// The compilation fails on the first denied opcode, with an error naming the opcode, the index
+/// of the function and the byte offset of the opcode in the wasm binary.
+///
+/// It must come first in the middleware chain, so that it only sees the opcodes of the module,
+/// and not the ones injected by the other middlewares.
+pub struct OpcodeDenylist {
+    denied: Vec<bool>,
+    func_index: usize,
+}
+
+impl OpcodeDenylist {
+    /// Creates an OpcodeDenylist denying the opcodes with the given codes. Unknown codes are
+    /// ignored, see `check_opcode_codes`.
+    pub fn new(denied_opcodes: &[u16]) -> OpcodeDenylist {
+        let mut denied = vec![false; OPCODE_CODE_COUNT];
+        for &code in denied_opcodes {
+            if let Some(entry) = denied.get_mut(code as usize) {
+                *entry = true;
+            }
+        }
+
+        OpcodeDenylist {
+            denied,
+            func_index: 0,
+        }
+    }
+}
+
+impl FunctionMiddleware for OpcodeDenylist {
+    type Error = String;
+    fn feed_event<'a, 'b: 'a>(
+        &mut self,
+        op: Event<'a, 'b>,
+        module_info: &ModuleInfo,
+        sink: &mut EventSink<'a, 'b>,
+        source_loc: u32,
+    ) -> Result<(), Self::Error> {
+        match op {
+            Event::Internal(InternalEvent::FunctionBegin(id)) => {
+                self.func_index = module_info.imported_functions.len() + id as usize;
+            }
+            Event::Wasm(&ref wasm_op) | Event::WasmOwned(ref wasm_op) => {
+                let opcode = Opcode::from_operator(wasm_op);
+                if self.denied[opcode.code() as usize] {
+                    return Err(format!(
+                        "opcode `{}` is not allowed, in function {} at offset {}",
+                        opcode.name(),
+                        self.func_index,
+                        source_loc
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        sink.push(op);
+        Ok(())
+    }
+}
+
+/// Checks that each code of a list of denied opcodes is the code of an opcode.
+pub fn check_opcode_codes(codes: &[u16]) -> Result<(), String> {
+    match codes.iter().find(|&&code| get_opcode_name(code).is_none()) {
+        Some(code) => Err(format!("unknown opcode code {}", code)),
+        None => Ok(()),
+    }
+}
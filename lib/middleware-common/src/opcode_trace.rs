@@ -1,20 +1,44 @@
+use std::cell::Cell;
 use std::fs::File;
 use std::io::Write;
+use std::ptr;
 
 use wasmer_runtime_core::{
     codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
     module::ModuleInfo,
-    vm::InternalField,
+    vm::{Ctx, InternalField},
     wasmparser::Operator,
     Instance,
 };
 
+pub use wasmer_runtime_core::vm::OpcodeTraceEntry;
+
+use crate::opcode_codes::Opcode;
+
 static OPCODE_LAST_LOCATION: InternalField = InternalField::allocate();
 
+thread_local! {
+    /// The opcode trace of the Instance running through `record_opcode_trace` on this thread,
+    /// where the `OpcodeTracer` breakpoints record the executed opcodes.
+    static CURRENT_OPCODE_TRACE: Cell<*mut Vec<OpcodeTraceEntry>> = Cell::new(ptr::null_mut());
+}
+
+/// The `opcode` of the trace entries which are markers rather than opcodes, see
+/// `push_opcode_trace_marker`. No opcode has this code.
+pub const OPCODE_TRACE_MARKER: u16 = u16::MAX;
+
+/// The maximum number of entries of the opcode trace of an Instance. The entries recorded once
+/// the trace is full are dropped, so that a long running call cannot exhaust the memory.
+pub const OPCODE_TRACE_MAX_ENTRIES: usize = 1 << 20;
+
 pub struct OpcodeTracer {
     pub output_file: File,
     local_function_index: u32,
     counter: u32,
+    collect_trace: bool,
+    function_start: u32,
+    function_end: u32,
+    traced_function: bool,
 }
 
 impl OpcodeTracer {
@@ -23,6 +47,30 @@ impl OpcodeTracer {
             output_file: File::create("opcode.trace").unwrap(),
             local_function_index: 0,
             counter: 0,
+            collect_trace: false,
+            function_start: 0,
+            function_end: u32::MAX,
+            traced_function: true,
+        }
+    }
+
+    /// Creates an `OpcodeTracer` which additionally records every executed opcode, together with
+    /// its byte offset in the wasm binary, into a buffer readable with `get_opcode_trace`.
+    pub fn new_with_trace_collection() -> OpcodeTracer {
+        OpcodeTracer {
+            collect_trace: true,
+            ..OpcodeTracer::new()
+        }
+    }
+
+    /// Restricts the tracing to the functions whose index, counting the imported functions, is
+    /// in `start..end`. The other functions are left uninstrumented, so they run without any
+    /// tracing overhead.
+    pub fn with_function_range(self, start: u32, end: u32) -> OpcodeTracer {
+        OpcodeTracer {
+            function_start: start,
+            function_end: end,
+            ..self
         }
     }
 
@@ -60,6 +108,24 @@ impl OpcodeTracer {
             OPCODE_LAST_LOCATION.index() as _,
         )));
     }
+
+    fn push_trace_collector(&self, sink: &mut EventSink, op: &Operator, source_loc: u32) {
+        let entry = OpcodeTraceEntry {
+            opcode: Opcode::from_operator(op).code(),
+            offset: source_loc,
+            marker: 0,
+        };
+        sink.push(Event::Internal(InternalEvent::Breakpoint(Box::new(
+            move |_| {
+                CURRENT_OPCODE_TRACE.with(|current| {
+                    if let Some(trace) = unsafe { current.get().as_mut() } {
+                        push_opcode_trace_entry(trace, entry);
+                    }
+                });
+                Ok(())
+            },
+        ))));
+    }
 }
 
 impl FunctionMiddleware for OpcodeTracer {
@@ -67,13 +133,32 @@ impl FunctionMiddleware for OpcodeTracer {
     fn feed_event<'a, 'b: 'a>(
         &mut self,
         op: Event<'a, 'b>,
-        _module_info: &ModuleInfo,
+        module_info: &ModuleInfo,
         sink: &mut EventSink<'a, 'b>,
         source_loc: u32,
     ) -> Result<(), Self::Error> {
+        if let Event::Internal(InternalEvent::FunctionBegin(local_function_index)) = op {
+            let function_index = module_info.imported_functions.len() as u32 + local_function_index;
+            self.traced_function =
+                function_index >= self.function_start && function_index < self.function_end;
+        }
+        if !self.traced_function {
+            sink.push(op);
+            return Ok(());
+        }
+
         self.trace_operator(&op);
         self.push_last_location_tracer(sink, source_loc);
 
+        if self.collect_trace {
+            match op {
+                Event::Wasm(&ref op) | Event::WasmOwned(ref op) => {
+                    self.push_trace_collector(sink, op, source_loc);
+                }
+                _ => {}
+            }
+        }
+
         sink.push(op);
 
         Ok(())
@@ -87,3 +172,44 @@ pub fn get_opcodetracer_last_location(instance: &mut Instance) -> u64 {
 pub fn reset_opcodetracer_last_location(instance: &mut Instance) {
     instance.set_internal(&OPCODE_LAST_LOCATION, 0);
 }
+
+/// Returns the opcodes recorded for an Instance since the last call to `reset_opcode_trace`, by
+/// the code compiled with `OpcodeTracer::new_with_trace_collection` and called through
+/// `record_opcode_trace`, and the markers pushed by its host functions.
+pub fn get_opcode_trace(instance: &Instance) -> &[OpcodeTraceEntry] {
+    &instance.context().opcode_trace
+}
+
+/// Appends a marker to the opcodes recorded for the Instance of `ctx`, from a host function, to
+/// show where it ran among the opcodes of the trace. Markers are recorded even when no tracer
+/// collects the opcodes.
+pub fn push_opcode_trace_marker(ctx: &mut Ctx, marker: u64) {
+    let entry = OpcodeTraceEntry {
+        opcode: OPCODE_TRACE_MARKER,
+        offset: 0,
+        marker,
+    };
+    push_opcode_trace_entry(&mut ctx.opcode_trace, entry);
+}
+
+/// Clears the opcodes recorded for an Instance.
+pub fn reset_opcode_trace(instance: &mut Instance) {
+    instance.context_mut().opcode_trace.clear();
+}
+
+/// Runs `f`, recording the opcodes executed by the code compiled with
+/// `OpcodeTracer::new_with_trace_collection` into the opcode trace of the Instance, see
+/// `get_opcode_trace`. The calls may nest, e.g. from a host function calling another Instance.
+pub fn record_opcode_trace<R, F: FnOnce(&mut Instance) -> R>(instance: &mut Instance, f: F) -> R {
+    let trace: *mut Vec<OpcodeTraceEntry> = &mut instance.context_mut().opcode_trace;
+    let previous = CURRENT_OPCODE_TRACE.with(|current| current.replace(trace));
+    let result = f(instance);
+    CURRENT_OPCODE_TRACE.with(|current| current.set(previous));
+    result
+}
+
+fn push_opcode_trace_entry(trace: &mut Vec<OpcodeTraceEntry>, entry: OpcodeTraceEntry) {
+    if trace.len() < OPCODE_TRACE_MAX_ENTRIES {
+        trace.push(entry);
+    }
+}
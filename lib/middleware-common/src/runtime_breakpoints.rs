@@ -1,18 +1,29 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use wasmer_runtime_core::{
     codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
+    error::RuntimeError,
     module::ModuleInfo,
-    vm::InternalField,
+    vm::{Ctx, InternalField},
     wasmparser::{Operator, Type as WpType, TypeOrFuncType as WpTypeOrFuncType},
-    error::RuntimeError,
     Instance,
 };
 
 pub static FIELD_RUNTIME_BREAKPOINT_VALUE: InternalField = InternalField::allocate();
+/// Nonzero while an interrupt requested by `request_interrupt` is pending. It is kept apart
+/// from the breakpoint value, which the wasm code writes without synchronization.
+pub static FIELD_INTERRUPT_REQUESTED: InternalField = InternalField::allocate();
 pub const BREAKPOINT_VALUE_NO_BREAKPOINT: u64 = 0;
 pub const BREAKPOINT_VALUE_EXECUTION_FAILED: u64 = 1;
 pub const BREAKPOINT_VALUE_OUT_OF_GAS: u64 = 4;
 pub const BREAKPOINT_VALUE_MEMORY_LIMIT: u64 = 5;
-
+pub const BREAKPOINT_VALUE_INTERRUPTED: u64 = 6;
+pub const BREAKPOINT_VALUE_STACK_LIMIT: u64 = 7;
+pub const BREAKPOINT_VALUE_INSTRUCTION_LIMIT: u64 = 8;
+pub const BREAKPOINT_VALUE_TABLE_LIMIT: u64 = 9;
+pub const BREAKPOINT_VALUE_CALL_DEPTH_LIMIT: u64 = 10;
+/// Breakpoint values up to this one are reserved for the runtime; values
+/// set by host code must be greater.
+pub const BREAKPOINT_VALUE_RESERVED_MAX: u64 = 15;
 
 pub struct RuntimeBreakpointHandler {}
 
@@ -31,18 +42,18 @@ impl FunctionMiddleware for RuntimeBreakpointHandler {
         sink: &mut EventSink<'a, 'b>,
         _source_loc: u32,
     ) -> Result<(), Self::Error> {
-
         let must_add_breakpoint = match op {
             Event::Wasm(&ref op) | Event::WasmOwned(ref op) => {
                 match *op {
+                    // The checks at the start of each loop iteration bound the time
+                    // an interrupt request waits for, see `request_interrupt`.
                     Operator::Call { .. }
-                    | Operator::CallIndirect { .. } => {
-                        true
-                    }
-                    _ => false
+                    | Operator::CallIndirect { .. }
+                    | Operator::Loop { .. } => true,
+                    _ => false,
                 }
             }
-            _ => false
+            _ => false,
         };
 
         sink.push(op);
@@ -51,6 +62,10 @@ impl FunctionMiddleware for RuntimeBreakpointHandler {
             sink.push(Event::Internal(InternalEvent::GetInternal(
                 FIELD_RUNTIME_BREAKPOINT_VALUE.index() as _,
             )));
+            sink.push(Event::Internal(InternalEvent::GetInternal(
+                FIELD_INTERRUPT_REQUESTED.index() as _,
+            )));
+            sink.push(Event::WasmOwned(Operator::I64Or));
             sink.push(Event::WasmOwned(Operator::I64Const {
                 value: BREAKPOINT_VALUE_NO_BREAKPOINT as i64,
             }));
@@ -59,7 +74,9 @@ impl FunctionMiddleware for RuntimeBreakpointHandler {
                 ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
             }));
             sink.push(Event::Internal(InternalEvent::Breakpoint(Box::new(|_| {
-                Err(Box::new(RuntimeError(Box::new("breakpoint reached".to_string()))))
+                Err(Box::new(RuntimeError(Box::new(
+                    "breakpoint reached".to_string(),
+                ))))
             }))));
             sink.push(Event::WasmOwned(Operator::End));
         }
@@ -76,7 +93,9 @@ pub fn push_runtime_breakpoint(sink: &mut EventSink, value: u64) {
         FIELD_RUNTIME_BREAKPOINT_VALUE.index() as _,
     )));
     sink.push(Event::Internal(InternalEvent::Breakpoint(Box::new(|_| {
-        Err(Box::new(RuntimeError(Box::new("breakpoint reached".to_string()))))
+        Err(Box::new(RuntimeError(Box::new(
+            "breakpoint reached".to_string(),
+        ))))
     }))));
 }
 
@@ -87,3 +106,43 @@ pub fn set_runtime_breakpoint_value(instance: &mut Instance, value: u64) {
 pub fn get_runtime_breakpoint_value(instance: &mut Instance) -> u64 {
     instance.get_internal(&FIELD_RUNTIME_BREAKPOINT_VALUE)
 }
+
+/// Requests the running call of the instance, or else the next one, to trap at its next call or
+/// loop iteration. The request stays pending until `take_interrupt_request` consumes it, whatever
+/// the breakpoint value.
+///
+/// The request is stored atomically, in its own field: unlike the other functions of this module,
+/// this one may be called from another thread while the instance is running.
+pub fn request_interrupt(instance: &Instance) {
+    interrupt_requested(instance).store(1, Ordering::SeqCst);
+}
+
+/// Consumes the pending interrupt request of the instance, if any, after a call which trapped:
+/// the breakpoint value becomes `BREAKPOINT_VALUE_INTERRUPTED`, unless the call trapped at
+/// another breakpoint. Returns whether a request was pending.
+pub fn take_interrupt_request(instance: &mut Instance) -> bool {
+    if interrupt_requested(instance).swap(0, Ordering::SeqCst) == 0 {
+        return false;
+    }
+    if get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_NO_BREAKPOINT {
+        set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_INTERRUPTED);
+    }
+    true
+}
+
+fn interrupt_requested(instance: &Instance) -> &AtomicU64 {
+    let internals = instance.context().internal.internals;
+    unsafe {
+        &*((*internals)
+            .as_mut_ptr()
+            .add(FIELD_INTERRUPT_REQUESTED.index()) as *const AtomicU64)
+    }
+}
+
+pub fn set_runtime_breakpoint_value_ctx(ctx: &mut Ctx, value: u64) {
+    ctx.set_internal(&FIELD_RUNTIME_BREAKPOINT_VALUE, value);
+}
+
+pub fn get_runtime_breakpoint_value_ctx(ctx: &Ctx) -> u64 {
+    ctx.get_internal(&FIELD_RUNTIME_BREAKPOINT_VALUE)
+}
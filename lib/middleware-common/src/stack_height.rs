@@ -0,0 +1,145 @@
+use wasmer_runtime_core::{
+    codegen::{Event, EventSink, FunctionMiddleware, InternalEvent},
+    module::ModuleInfo,
+    structures::TypedIndex,
+    types::FuncIndex,
+    vm::InternalField,
+    wasmparser::{Operator, Type as WpType, TypeOrFuncType as WpTypeOrFuncType},
+    Instance,
+};
+
+use crate::runtime_breakpoints::{push_runtime_breakpoint, BREAKPOINT_VALUE_STACK_LIMIT};
+
+static FIELD_STACK_HEIGHT: InternalField = InternalField::allocate();
+
+/// StackHeight is a compiler middleware that bounds the height of the wasm stack, so that deep
+/// recursion or functions with huge frames trap deterministically instead of exhausting the native
+/// stack, whatever the backend.
+///
+/// The height is measured in slots: the frame of a function takes one slot for the call itself,
+/// plus one slot for each of its parameters and locals. Each function checks, before its first
+/// operator, that its frame fits on top of the frames of its callers, and traps with
+/// `BREAKPOINT_VALUE_STACK_LIMIT` otherwise. The height of the callers is kept in an internal field
+/// of the instance, raised by the frame of the caller around each `call` and `call_indirect`.
+///
+/// A limit of 0 means no limit: the code is left as is.
+///
+/// StackHeight charges no gas: the gas side of large frames is covered by the Metering middleware,
+/// which charges the allocation of the locals of each function.
+pub struct StackHeight {
+    max_stack_height: u64,
+    frame_locals: u64,
+    frame_height: u64,
+    entry_check_pending: bool,
+}
+
+impl StackHeight {
+    pub fn new(max_stack_height: u64) -> StackHeight {
+        StackHeight {
+            max_stack_height,
+            frame_locals: 0,
+            frame_height: 0,
+            entry_check_pending: false,
+        }
+    }
+
+    /// Traps if the frame of the function does not fit under the limit.
+    fn push_entry_check(&self, sink: &mut EventSink) {
+        if self.frame_height > self.max_stack_height {
+            push_runtime_breakpoint(sink, BREAKPOINT_VALUE_STACK_LIMIT);
+            return;
+        }
+
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_STACK_HEIGHT.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64Const {
+            value: (self.max_stack_height - self.frame_height) as i64,
+        }));
+        sink.push(Event::WasmOwned(Operator::I64GtU));
+        sink.push(Event::WasmOwned(Operator::If {
+            ty: WpTypeOrFuncType::Type(WpType::EmptyBlockType),
+        }));
+        push_runtime_breakpoint(sink, BREAKPOINT_VALUE_STACK_LIMIT);
+        sink.push(Event::WasmOwned(Operator::End));
+    }
+
+    /// Adds the frame of the function to the stack height, or subtracts it, with `operator`.
+    fn push_height_update(&self, sink: &mut EventSink, operator: Operator<'static>) {
+        sink.push(Event::Internal(InternalEvent::GetInternal(
+            FIELD_STACK_HEIGHT.index() as _,
+        )));
+        sink.push(Event::WasmOwned(Operator::I64Const {
+            value: self.frame_height as i64,
+        }));
+        sink.push(Event::WasmOwned(operator));
+        sink.push(Event::Internal(InternalEvent::SetInternal(
+            FIELD_STACK_HEIGHT.index() as _,
+        )));
+    }
+}
+
+impl FunctionMiddleware for StackHeight {
+    type Error = String;
+    fn feed_event<'a, 'b: 'a>(
+        &mut self,
+        op: Event<'a, 'b>,
+        module_info: &ModuleInfo,
+        sink: &mut EventSink<'a, 'b>,
+        _source_loc: u32,
+    ) -> Result<(), Self::Error> {
+        if self.max_stack_height == 0 {
+            sink.push(op);
+            return Ok(());
+        }
+
+        match op {
+            Event::Internal(InternalEvent::FunctionBegin(id)) => {
+                let func_index = FuncIndex::new(module_info.imported_functions.len() + id as usize);
+                let params = module_info.signatures[module_info.func_assoc[func_index]]
+                    .params()
+                    .len() as u64;
+                self.frame_height = 1 + params + self.frame_locals;
+                self.frame_locals = 0;
+                self.entry_check_pending = true;
+                sink.push(op);
+            }
+            Event::Wasm(&ref wasm_op) | Event::WasmOwned(ref wasm_op) => {
+                if self.entry_check_pending {
+                    self.entry_check_pending = false;
+                    self.push_entry_check(sink);
+                }
+                let is_call = match *wasm_op {
+                    Operator::Call { .. } | Operator::CallIndirect { .. } => true,
+                    _ => false,
+                };
+                if is_call {
+                    self.push_height_update(sink, Operator::I64Add);
+                    sink.push(op);
+                    self.push_height_update(sink, Operator::I64Sub);
+                } else {
+                    sink.push(op);
+                }
+            }
+            _ => sink.push(op),
+        }
+
+        Ok(())
+    }
+
+    fn feed_local(&mut self, _ty: WpType, n: usize, _loc: u32) -> Result<(), Self::Error> {
+        self.frame_locals += n as u64;
+        Ok(())
+    }
+}
+
+/// Returns the height of the frames of the wasm functions running in an Instance, in slots.
+pub fn get_stack_height(instance: &Instance) -> u64 {
+    instance.get_internal(&FIELD_STACK_HEIGHT)
+}
+
+/// Sets the height of the frames of the wasm functions running in an Instance, in slots, e.g. to
+/// restore it after a call which trapped.
+pub fn set_stack_height(instance: &mut Instance, value: u64) {
+    instance.set_internal(&FIELD_STACK_HEIGHT, value);
+}
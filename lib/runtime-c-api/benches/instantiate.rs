@@ -0,0 +1,87 @@
+#[macro_use]
+extern crate criterion;
+use criterion::Criterion;
+use std::ptr;
+use wasmer_runtime_c_api::{
+    export::{wasmer_import_export_kind, wasmer_import_export_value},
+    global::{wasmer_global_destroy, wasmer_global_new},
+    import::wasmer_import_t,
+    instance::{wasmer_instance_destroy, wasmer_instance_t, wasmer_instantiate},
+    value::{wasmer_value, wasmer_value_t, wasmer_value_tag},
+    wasmer_byte_array, wasmer_result_t,
+};
+
+/// A module which imports nothing.
+static SUM_WASM: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/assets/sum.wasm"
+));
+
+fn instantiate(imports: &mut [wasmer_import_t]) {
+    let mut wasm_bytes = SUM_WASM.to_vec();
+    let mut instance: *mut wasmer_instance_t = ptr::null_mut();
+
+    let result = unsafe {
+        wasmer_instantiate(
+            &mut instance,
+            wasm_bytes.as_mut_ptr(),
+            wasm_bytes.len() as u32,
+            imports.as_mut_ptr(),
+            imports.len() as _,
+        )
+    };
+    match result {
+        wasmer_result_t::WASMER_OK => {}
+        _ => panic!("the instantiation failed"),
+    }
+
+    wasmer_instance_destroy(instance);
+}
+
+fn instantiate_without_imports_benchmark(c: &mut Criterion) {
+    c.bench_function("instantiate without imports", |b| {
+        b.iter(|| instantiate(&mut []))
+    });
+}
+
+// The module ignores the import, but it still builds the import object,
+// as `wasmer_instantiate` did for every module.
+fn instantiate_with_unused_import_benchmark(c: &mut Criterion) {
+    let global = unsafe {
+        wasmer_global_new(
+            wasmer_value_t {
+                tag: wasmer_value_tag::WASM_I32,
+                value: wasmer_value { I32: 0 },
+            },
+            false,
+        )
+    };
+    let module_name = "env";
+    let import_name = "unused";
+
+    c.bench_function("instantiate with an unused import", move |b| {
+        b.iter(|| {
+            instantiate(&mut [wasmer_import_t {
+                module_name: wasmer_byte_array {
+                    bytes: module_name.as_ptr(),
+                    bytes_len: module_name.len() as u32,
+                },
+                import_name: wasmer_byte_array {
+                    bytes: import_name.as_ptr(),
+                    bytes_len: import_name.len() as u32,
+                },
+                tag: wasmer_import_export_kind::WASM_GLOBAL,
+                value: wasmer_import_export_value { global },
+            }])
+        })
+    });
+
+    wasmer_global_destroy(global);
+}
+
+criterion_group! {
+    name = instantiate_bench;
+    config = Criterion::default().sample_size(20);
+    targets = instantiate_without_imports_benchmark, instantiate_with_unused_import_benchmark,
+}
+criterion_main!(instantiate_bench);
@@ -1,6 +1,6 @@
 extern crate cbindgen;
 
-use cbindgen::{Builder, Language};
+use cbindgen::{Builder, Config, Language, Style};
 use std::{env, fs, path::PathBuf};
 
 fn main() {
@@ -41,6 +41,14 @@ fn main() {
 #  define DEPRECATED(message) __declspec(deprecated(message))
 #endif
 
+// Define the `WASMER_ALIGNED` macro, used by the aligned types, e.g.
+// `wasmer_value`.
+#if defined(GCC) || defined(__GNUC__) || __has_attribute(aligned)
+#  define WASMER_ALIGNED(n) __attribute__((aligned(n)))
+#elif defined(MSVC) || __has_declspec_attribute(align)
+#  define WASMER_ALIGNED(n) __declspec(align(n))
+#endif
+
 "#
     .to_string();
 
@@ -60,6 +68,7 @@ fn main() {
     // Generate the C bindings in the `OUT_DIR`.
     out_wasmer_header_file.set_extension("h");
     Builder::new()
+        .with_config(config())
         .with_crate(crate_dir.clone())
         .with_language(Language::C)
         .with_include_guard("WASMER_H")
@@ -75,6 +84,7 @@ fn main() {
     // Generate the C++ bindings in the `OUT_DIR`.
     out_wasmer_header_file.set_extension("hh");
     Builder::new()
+        .with_config(config())
         .with_crate(crate_dir)
         .with_language(Language::Cxx)
         .with_include_guard("WASMER_H")
@@ -104,3 +114,14 @@ fn main() {
     fs::copy(out_wasmer_header_file, crate_wasmer_header_file)
         .expect("Unable to copy the generated C++ bindings");
 }
+
+/// The configuration shared by the C and C++ bindings: the types are
+/// declared as `typedef`s, and the `#[repr(align(n))]` types, e.g.
+/// `wasmer_value`, are marked with the `WASMER_ALIGNED(n)` macro of the
+/// pre header.
+fn config() -> Config {
+    let mut config = Config::default();
+    config.style = Style::Type;
+    config.layout.aligned_n = Some("WASMER_ALIGNED".to_string());
+    config
+}
@@ -0,0 +1,267 @@
+//! Let the host allocate the buffers returned to C, and the modules
+//! allocate the buffers returned to wasm.
+
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    instance::{context_memory_range, wasmer_instance_context_t, wasmer_instance_t},
+    wasmer_result_t,
+};
+use lazy_static::lazy_static;
+use libc::{c_char, c_void};
+use std::{ffi::CStr, ptr, slice, sync::RwLock};
+use wasmer_runtime_core::{
+    module::ExportIndex,
+    types::{Type, Value},
+    vm::Ctx,
+    Instance,
+};
+
+/// A `malloc`-like function, used to allocate the buffers returned to
+/// C. See `wasmer_set_allocator()`.
+#[allow(non_camel_case_types)]
+pub type wasmer_malloc_t = extern "C" fn(size: usize) -> *mut c_void;
+
+/// A `free`-like function, used to free the buffers returned to C.
+/// See `wasmer_set_allocator()`.
+#[allow(non_camel_case_types)]
+pub type wasmer_free_t = extern "C" fn(ptr: *mut c_void);
+
+lazy_static! {
+    /// The allocator registered by `wasmer_set_allocator`.
+    static ref ALLOCATOR: RwLock<Option<(wasmer_malloc_t, wasmer_free_t)>> = RwLock::new(None);
+}
+
+/// The export of the modules called by `wasmer_instance_context_return_bytes`, unless
+/// another one is set with `wasmer_instance_set_wasm_allocator_export`.
+const DEFAULT_WASM_ALLOCATOR_EXPORT: &str = "allocate";
+
+/// Registers the `malloc` and `free` functions used to allocate and
+/// free the buffers returned to C, e.g. by `wasmer_instance_cache()`.
+/// Such buffers can then be freed directly by the host with its own
+/// `free` function.
+///
+/// When both functions are null, the default Rust allocator is used
+/// again. Buffers allocated with the Rust allocator must be freed with
+/// `wasmer_buffer_destroy()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` if only one of the functions
+/// is null. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_set_allocator(malloc, free);
+///
+/// const uint8_t *cache_bytes;
+/// uint32_t cache_length;
+/// wasmer_instance_cache(instance, &cache_bytes, &cache_length);
+///
+/// // Do something with the cache bytes.
+///
+/// free((void *) cache_bytes);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_set_allocator(
+    malloc_fn: Option<wasmer_malloc_t>,
+    free_fn: Option<wasmer_free_t>,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let allocator = match (malloc_fn, free_fn) {
+            (Some(malloc_fn), Some(free_fn)) => Some((malloc_fn, free_fn)),
+            (None, None) => None,
+            _ => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: "malloc_fn and free_fn must be both null or both non-null".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        *ALLOCATOR.write().unwrap() = allocator;
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Frees a buffer returned to C, e.g. by `wasmer_instance_cache()`,
+/// with the registered `free` function if any, or with the Rust
+/// allocator otherwise. `length` must be the length of the buffer.
+///
+/// The allocator must not have changed since the buffer was returned.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_buffer_destroy(buffer: *mut u8, length: u32) {
+    if buffer.is_null() {
+        return;
+    }
+
+    match *ALLOCATOR.read().unwrap() {
+        Some((_, free_fn)) => free_fn(buffer as *mut c_void),
+        None => {
+            Box::from_raw(slice::from_raw_parts_mut(buffer, length as usize));
+        }
+    }
+}
+
+/// Hands `bytes` over to C, in a buffer allocated with the registered
+/// `malloc` function if any. Returns a null pointer if the allocation
+/// failed.
+pub(crate) unsafe fn into_c_buffer(bytes: Box<[u8]>) -> *mut u8 {
+    match *ALLOCATOR.read().unwrap() {
+        Some((malloc_fn, _)) => {
+            let buffer = malloc_fn(bytes.len()) as *mut u8;
+
+            if !buffer.is_null() {
+                ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len());
+            }
+
+            buffer
+        }
+        None => Box::into_raw(bytes) as *mut u8,
+    }
+}
+
+/// Sets the name of the function which the module of the instance
+/// exports to allocate the buffers returned to it by the host
+/// functions, see `wasmer_instance_context_return_bytes()`. The name is
+/// `allocate` by default, and only applies to this instance.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_set_wasm_allocator_export(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || name.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and name ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let name = match CStr::from_ptr(name).to_str() {
+            Ok(name) => name,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "name is not a valid UTF-8 string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let instance = &mut *(instance as *mut Instance);
+        instance.context_mut().wasm_allocator_export = Some(name.to_string());
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Returns `len` bytes from `data` to the instance, from within a host
+/// function: the bytes are copied into a buffer allocated by the
+/// instance, whose offset in the memory is returned, so that the host
+/// function can return it to wasm.
+///
+/// The buffer is allocated by calling the allocator export of the
+/// module, see `wasmer_instance_set_wasm_allocator_export()`, which
+/// takes the length of the buffer as an `i32` and returns its offset as
+/// an `i32`.
+/// The call runs like any call of the instance: it is metered, and may
+/// trap. The instance owns the buffer once it is returned.
+///
+/// This function returns the offset of the buffer in the memory of the
+/// instance, or -1 upon failure, including when the module does not
+/// export an allocator with the expected signature, or when the
+/// returned buffer is out of the bounds of the memory. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// int32_t get_name(wasmer_instance_context_t *ctx) {
+///     const char *name = "wasmer";
+///     int64_t offset = wasmer_instance_context_return_bytes(ctx, (const uint8_t *) name, strlen(name));
+///     return offset < 0 ? 0 : (int32_t) offset;
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_return_bytes(
+    ctx: *mut wasmer_instance_context_t,
+    data: *const u8,
+    len: u32,
+) -> i64 {
+    catch_panic(-1, || {
+        if ctx.is_null() || (data.is_null() && len != 0) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "ctx and data ptrs must not be null".to_string(),
+            });
+            return -1;
+        }
+
+        let context = &mut *(ctx as *mut Ctx);
+        let export_name = context
+            .wasm_allocator_export
+            .clone()
+            .unwrap_or_else(|| DEFAULT_WASM_ALLOCATOR_EXPORT.to_string());
+        let info = &(*context.module).info;
+        let func_index = match info.exports.get(&export_name) {
+            Some(ExportIndex::Func(func_index)) => *func_index,
+            _ => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: format!("the module does not export the allocator `{}`", export_name),
+                });
+                return -1;
+            }
+        };
+
+        let signature = &info.signatures[info.func_assoc[func_index]];
+        if signature.params() != [Type::I32] || signature.returns() != [Type::I32] {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "the allocator `{}` has the signature {}, expected [I32] -> [I32]",
+                    export_name, signature
+                ),
+            });
+            return -1;
+        }
+
+        let offset = match context.call_with_index(func_index, &[Value::I32(len as i32)]) {
+            Ok(results) => match results[..] {
+                [Value::I32(offset)] => offset as u32,
+                _ => unreachable!("the signature of the allocator was checked"),
+            },
+            Err(error) => {
+                update_last_error(error);
+                return -1;
+            }
+        };
+
+        match context_memory_range(ctx, offset, len as usize) {
+            Ok(buffer) => {
+                if len != 0 {
+                    buffer.copy_from_slice(slice::from_raw_parts(data, len as usize));
+                }
+                offset as i64
+            }
+            Err(error) => {
+                update_last_error(error);
+                -1
+            }
+        }
+    })
+}
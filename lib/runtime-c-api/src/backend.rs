@@ -0,0 +1,97 @@
+//! List the compiler backends this library was built with.
+
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    wasmer_result_t,
+};
+use std::slice;
+
+/// Represents a compiler backend.
+#[allow(non_camel_case_types)]
+#[repr(u32)]
+#[derive(Clone, Copy)]
+pub enum wasmer_compiler_backend_t {
+    /// The Singlepass backend.
+    WASMER_BACKEND_SINGLEPASS,
+
+    /// The Cranelift backend.
+    WASMER_BACKEND_CRANELIFT,
+
+    /// The LLVM backend.
+    WASMER_BACKEND_LLVM,
+}
+
+/// The backends enabled by the features of this build.
+const AVAILABLE_BACKENDS: &[wasmer_compiler_backend_t] = &[
+    #[cfg(feature = "singlepass-backend")]
+    wasmer_compiler_backend_t::WASMER_BACKEND_SINGLEPASS,
+    #[cfg(feature = "cranelift-backend")]
+    wasmer_compiler_backend_t::WASMER_BACKEND_CRANELIFT,
+    #[cfg(feature = "llvm-backend")]
+    wasmer_compiler_backend_t::WASMER_BACKEND_LLVM,
+];
+
+/// Lists the compiler backends this library was built with, since they
+/// depend on the features enabled at build time.
+///
+/// The backends are written to `out`, which can hold `out_len` of them,
+/// and their number is stored in `written`. At most 3 backends are
+/// available.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// `out` is too small, in which case nothing is written to `out`, and
+/// `written` receives the number of available backends. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_compiler_backend_t backends[3];
+/// uint32_t backends_length = 0;
+/// wasmer_available_backends(backends, 3, &backends_length);
+///
+/// for (uint32_t i = 0; i < backends_length; i++) {
+///     if (backends[i] == WASMER_BACKEND_SINGLEPASS) {
+///         // Singlepass is available.
+///     }
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_available_backends(
+    out: *mut wasmer_compiler_backend_t,
+    out_len: u32,
+    written: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if out.is_null() || written.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "out and written ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        *written = AVAILABLE_BACKENDS.len() as u32;
+
+        if (out_len as usize) < AVAILABLE_BACKENDS.len() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "out is too small: {} backends, while {} backends are available",
+                    out_len,
+                    AVAILABLE_BACKENDS.len()
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        slice::from_raw_parts_mut(out, AVAILABLE_BACKENDS.len())
+            .copy_from_slice(AVAILABLE_BACKENDS);
+
+        wasmer_result_t::WASMER_OK
+    })
+}
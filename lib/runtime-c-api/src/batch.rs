@@ -0,0 +1,268 @@
+//! Run a batch of calls on an instance, and report the outcome of each
+//! call.
+
+use crate::{
+    error::{
+        catch_panic, error_code, take_last_error, update_last_error, wasmer_error_code_t, CApiError,
+    },
+    instance::{wasmer_instance_call, wasmer_instance_t},
+    value::wasmer_value_t,
+    wasmer_result_t,
+};
+use libc::c_char;
+use std::{
+    ffi::{CStr, CString},
+    ptr, slice,
+};
+
+/// Opaque pointer to a batch of calls, see `wasmer_call_batch_new()`.
+#[repr(C)]
+pub struct wasmer_call_batch_t;
+
+/// The `wasmer_call_batch_result_t` struct describes the outcome of a
+/// call of a batch. See `wasmer_call_batch_results()`.
+#[repr(C)]
+pub struct wasmer_call_batch_result_t {
+    /// The result of the call, as returned by `wasmer_instance_call()`.
+    pub result: wasmer_result_t,
+
+    /// The category of the error of the call, or
+    /// `WASMER_ERROR_CODE_NONE` if the call succeeded.
+    pub error_code: wasmer_error_code_t,
+
+    /// The message of the error of the call, or a null pointer if the
+    /// call succeeded.
+    pub error_message: *const c_char,
+}
+
+struct BatchedCall {
+    name: CString,
+    params: Vec<wasmer_value_t>,
+}
+
+struct CallBatch {
+    stop_on_error: bool,
+    calls: Vec<BatchedCall>,
+    results: Vec<wasmer_call_batch_result_t>,
+    // The messages `results` point to.
+    error_messages: Vec<CString>,
+}
+
+/// Creates an empty batch of calls. Add calls with
+/// `wasmer_call_batch_add()`, then run them with
+/// `wasmer_call_batch_run()`.
+///
+/// If `stop_on_error` is true, running the batch stops at the first
+/// call which fails; otherwise every call runs, whatever the outcome of
+/// the previous ones.
+///
+/// The caller owns the object and should call
+/// `wasmer_call_batch_destroy()` to free it.
+#[no_mangle]
+pub extern "C" fn wasmer_call_batch_new(stop_on_error: bool) -> *mut wasmer_call_batch_t {
+    let batch = Box::new(CallBatch {
+        stop_on_error,
+        calls: Vec::new(),
+        results: Vec::new(),
+        error_messages: Vec::new(),
+    });
+
+    Box::into_raw(batch) as *mut wasmer_call_batch_t
+}
+
+/// Adds to a batch a call of the exported function `name` with the
+/// given parameters. The name and the parameters are copied.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_call_batch_add(
+    batch: *mut wasmer_call_batch_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if batch.is_null() || name.is_null() || (params.is_null() && params_len != 0) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "batch, name and params ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let batch = &mut *(batch as *mut CallBatch);
+        let params = if params_len == 0 {
+            Vec::new()
+        } else {
+            slice::from_raw_parts(params, params_len as usize).to_vec()
+        };
+        batch.calls.push(BatchedCall {
+            name: CStr::from_ptr(name).to_owned(),
+            params,
+        });
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Runs the calls of a batch on an instance, in the order they were
+/// added, like with `wasmer_instance_call()`; the results of the
+/// functions are discarded. Use `wasmer_call_batch_results()` to see
+/// the outcome of each call.
+///
+/// The batch can be run again, on the same instance or on another one.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` if every call
+/// succeeded, and `wasmer_result_t::WASMER_ERROR` if a call failed, or
+/// upon failure. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message; its category is
+/// the one of the first failed call.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_call_batch_t *batch = wasmer_call_batch_new(false);
+/// wasmer_call_batch_add(batch, "transfer", transfer_params, 2);
+/// wasmer_call_batch_add(batch, "burn", burn_params, 1);
+///
+/// if (wasmer_call_batch_run(batch, instance) != WASMER_OK) {
+///     uint32_t results_len = 0;
+///     const wasmer_call_batch_result_t *results = wasmer_call_batch_results(batch, &results_len);
+///
+///     for (uint32_t index = 0; index < results_len; index++) {
+///         if (results[index].result != WASMER_OK) {
+///             printf("Call %d failed: %s\n", index, results[index].error_message);
+///         }
+///     }
+/// }
+///
+/// wasmer_call_batch_destroy(batch);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_call_batch_run(
+    batch: *mut wasmer_call_batch_t,
+    instance: *mut wasmer_instance_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if batch.is_null() || instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "batch and instance ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let batch = &mut *(batch as *mut CallBatch);
+        batch.results.clear();
+        batch.error_messages.clear();
+
+        let mut failed_calls = 0;
+        let mut first_error_code = wasmer_error_code_t::WASMER_ERROR_CODE_NONE;
+        for call in &batch.calls {
+            let result = wasmer_instance_call(
+                instance,
+                call.name.as_ptr(),
+                call.params.as_ptr(),
+                call.params.len() as u32,
+                ptr::null_mut(),
+                0,
+            );
+
+            if let wasmer_result_t::WASMER_OK = result {
+                batch.results.push(wasmer_call_batch_result_t {
+                    result,
+                    error_code: wasmer_error_code_t::WASMER_ERROR_CODE_NONE,
+                    error_message: ptr::null(),
+                });
+                continue;
+            }
+
+            let (code, msg) = match take_last_error() {
+                Some(error) => (error_code(&*error), error.to_string()),
+                None => (
+                    wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                    String::new(),
+                ),
+            };
+            let msg = CString::new(msg).unwrap_or_default();
+            batch.results.push(wasmer_call_batch_result_t {
+                result,
+                error_code: code,
+                error_message: msg.as_ptr(),
+            });
+            batch.error_messages.push(msg);
+
+            if failed_calls == 0 {
+                first_error_code = code;
+            }
+            failed_calls += 1;
+
+            if batch.stop_on_error {
+                break;
+            }
+        }
+
+        if failed_calls == 0 {
+            return wasmer_result_t::WASMER_OK;
+        }
+
+        update_last_error(CApiError {
+            code: first_error_code,
+            msg: format!(
+                "{} of the {} calls run by the batch failed",
+                failed_calls,
+                batch.results.len()
+            ),
+        });
+        wasmer_result_t::WASMER_ERROR
+    })
+}
+
+/// Returns the outcomes of the calls of the last run of a batch, in the
+/// order of the calls, and stores their number in `results_len`. With
+/// `stop_on_error`, there are no outcomes for the calls following the
+/// first failed one, which were not run.
+///
+/// The outcomes, and their error messages, are owned by the batch, and
+/// stay valid until it is run again or destroyed.
+///
+/// This function returns a null pointer, and stores 0 in
+/// `results_len`, if the batch has not been run, or if `batch` is a
+/// null pointer.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_call_batch_results(
+    batch: *const wasmer_call_batch_t,
+    results_len: *mut u32,
+) -> *const wasmer_call_batch_result_t {
+    let results: &[wasmer_call_batch_result_t] = if batch.is_null() {
+        &[]
+    } else {
+        &(*(batch as *const CallBatch)).results
+    };
+
+    if !results_len.is_null() {
+        *results_len = results.len() as u32;
+    }
+
+    if results.is_empty() {
+        ptr::null()
+    } else {
+        results.as_ptr()
+    }
+}
+
+/// Frees memory for the given batch of calls.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_call_batch_destroy(batch: *mut wasmer_call_batch_t) {
+    if !batch.is_null() {
+        unsafe { Box::from_raw(batch as *mut CallBatch) };
+    }
+}
@@ -0,0 +1,286 @@
+//! Compile a module from wasm bytes received in chunks, e.g. streamed
+//! from a peer. The chunks are buffered, and the module is compiled
+//! once all its bytes are received.
+
+#[cfg(feature = "metering")]
+use crate::instance::{compile_with_options, read_compilation_options};
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError, CausedError},
+    instance::{compile_failure_result, wasmer_compilation_options_t},
+    module::wasmer_module_t,
+    wasmer_result_t,
+};
+#[cfg(feature = "metering")]
+use std::ptr;
+use std::slice;
+use wasmer_runtime::{compile, Module};
+
+/// Opaque pointer to a streaming compilation, see
+/// `wasmer_compile_streaming_begin()`.
+#[repr(C)]
+pub struct wasmer_compile_streaming_t;
+
+const HEADER: [u8; 8] = *b"\0asm\x01\0\0\0";
+const MAX_SECTION_ID: u8 = 12;
+
+struct StreamingCompilation {
+    bytes: Vec<u8>,
+    // The offset of the header of the next section, which may not have
+    // been received yet.
+    next_section: usize,
+}
+
+impl StreamingCompilation {
+    /// Appends a chunk of the module, and checks the module header and
+    /// the headers of the sections received so far.
+    fn feed(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.bytes.extend_from_slice(chunk);
+
+        let header_len = self.bytes.len().min(HEADER.len());
+        if self.bytes[..header_len] != HEADER[..header_len] {
+            return Err("invalid wasm header".to_string());
+        }
+
+        while let Some((id, payload_start, payload_len)) = self.next_section_header()? {
+            if id > MAX_SECTION_ID {
+                return Err(format!("unknown section id {}", id));
+            }
+            self.next_section = payload_start
+                .checked_add(payload_len)
+                .ok_or_else(|| "section size out of bounds".to_string())?;
+        }
+
+        Ok(())
+    }
+
+    /// Decodes the header of the next section, once it is fully
+    /// received: its id, and the offset and length of its payload.
+    fn next_section_header(&self) -> Result<Option<(u8, usize, usize)>, String> {
+        let position = self.next_section;
+        let id = match self.bytes.get(position) {
+            Some(&id) => id,
+            None => return Ok(None),
+        };
+
+        let mut payload_len: u64 = 0;
+        let mut shift = 0;
+        for (count, &byte) in self.bytes[position + 1..].iter().enumerate() {
+            payload_len |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(Some((id, position + 2 + count, payload_len as usize)));
+            }
+            shift += 7;
+            if shift > 28 {
+                return Err("invalid section size".to_string());
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Whether the bytes received so far end with a complete section.
+    fn is_complete(&self) -> bool {
+        self.bytes.len() >= HEADER.len() && self.next_section == self.bytes.len()
+    }
+}
+
+/// Starts the compilation of a module whose wasm bytes are received in
+/// chunks, e.g. fetched from a peer. Give the chunks, in order, to
+/// `wasmer_compile_streaming_feed()`, then compile the module with
+/// `wasmer_compile_streaming_finish()`.
+///
+/// The headers of the module and of its sections are checked as the
+/// chunks arrive, so that a malformed module is rejected before all its
+/// bytes are received. The module itself is only compiled by
+/// `wasmer_compile_streaming_finish()`: the chunks are buffered until
+/// then, so the compilation does not overlap with the transfer of the
+/// bytes. The caller can release its own copies of the chunks.
+///
+/// The caller owns the object until it is given to
+/// `wasmer_compile_streaming_finish()`, or freed with
+/// `wasmer_compile_streaming_destroy()` to abort the compilation.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_compile_streaming_t *streaming = wasmer_compile_streaming_begin();
+///
+/// while ((chunk_len = receive_chunk(peer, chunk, sizeof(chunk))) > 0) {
+///     if (wasmer_compile_streaming_feed(streaming, chunk, chunk_len) != WASMER_OK) {
+///         // The streaming compilation is freed.
+///         return;
+///     }
+/// }
+///
+/// wasmer_module_t *module = NULL;
+/// if (wasmer_compile_streaming_finish(streaming, &module, options) != WASMER_OK) {
+///     // Get the error message with `wasmer_last_error_message()`.
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn wasmer_compile_streaming_begin() -> *mut wasmer_compile_streaming_t {
+    let streaming = Box::new(StreamingCompilation {
+        bytes: Vec::new(),
+        next_section: HEADER.len(),
+    });
+
+    Box::into_raw(streaming) as *mut wasmer_compile_streaming_t
+}
+
+/// Gives the next chunk of the wasm bytes to a streaming compilation,
+/// see `wasmer_compile_streaming_begin()`.
+///
+/// `chunk` may be a null pointer if `chunk_len` is 0.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, with the
+/// `WASMER_ERROR_CODE_COMPILE` error code if the bytes received so far
+/// are not the beginning of a valid module, e.g. they have an invalid
+/// header or an unknown section. The streaming compilation is freed
+/// upon failure, and must not be used anymore. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_compile_streaming_feed(
+    streaming: *mut wasmer_compile_streaming_t,
+    chunk: *const u8,
+    chunk_len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if streaming.is_null() || (chunk.is_null() && chunk_len != 0) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "streaming and chunk ptrs must not be null".to_string(),
+            });
+            if !streaming.is_null() {
+                wasmer_compile_streaming_destroy(streaming);
+            }
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let chunk: &[u8] = if chunk_len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(chunk, chunk_len as usize)
+        };
+
+        let result = (*(streaming as *mut StreamingCompilation)).feed(chunk);
+        match result {
+            Ok(()) => wasmer_result_t::WASMER_OK,
+            Err(msg) => {
+                wasmer_compile_streaming_destroy(streaming);
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                    msg: format!("compile error: {}", msg),
+                });
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
+/// Compiles the module of a streaming compilation, once all its chunks
+/// have been given to `wasmer_compile_streaming_feed()`, and frees the
+/// streaming compilation, whatever the outcome.
+///
+/// The module is compiled like by `wasmer_compile_file_with_options()`,
+/// with the given options, or like by `wasmer_compile()` if `options` is
+/// a null pointer. The options are only supported with the `metering`
+/// feature.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the bytes end in the middle of a section, and
+/// `wasmer_result_t::WASMER_TIMEOUT` when the compilation exceeds the
+/// `compile_timeout_ms` option; no module is created then. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_compile_streaming_finish(
+    streaming: *mut wasmer_compile_streaming_t,
+    module: *mut *mut wasmer_module_t,
+    options: *const wasmer_compilation_options_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if streaming.is_null() || module.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "streaming and module ptrs must not be null".to_string(),
+            });
+            if !streaming.is_null() {
+                wasmer_compile_streaming_destroy(streaming);
+            }
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let streaming = Box::from_raw(streaming as *mut StreamingCompilation);
+        if !streaming.is_complete() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                msg: "compile error: unexpected end of the wasm bytes".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let result = if options.is_null() {
+            compile(&streaming.bytes).map_err(|error| {
+                CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                    msg: error.to_string(),
+                }
+                .caused_by(error)
+            })
+        } else {
+            compile_with_options_ptr(&streaming.bytes, options)
+        };
+        let new_module = match result {
+            Ok(module) => module,
+            Err(error) => {
+                let result = compile_failure_result(&error);
+                update_last_error(error);
+                return result;
+            }
+        };
+        *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Compiles the bytes with the given options, like
+/// `wasmer_compile_file_with_options()`.
+#[cfg(feature = "metering")]
+unsafe fn compile_with_options_ptr(
+    bytes: &[u8],
+    options: *const wasmer_compilation_options_t,
+) -> Result<Module, CausedError> {
+    read_compilation_options(options)
+        .map_err(Into::into)
+        .and_then(|options| compile_with_options(bytes, &options, ptr::null_mut()))
+}
+
+/// The compilation options need the `metering` feature.
+#[cfg(not(feature = "metering"))]
+unsafe fn compile_with_options_ptr(
+    _bytes: &[u8],
+    _options: *const wasmer_compilation_options_t,
+) -> Result<Module, CausedError> {
+    Err(CApiError {
+        code: wasmer_error_code_t::WASMER_ERROR_CODE_UNSUPPORTED,
+        msg: "the compilation options need the `metering` feature".to_string(),
+    }
+    .into())
+}
+
+/// Frees a streaming compilation without compiling its module, e.g.
+/// when the transfer of the bytes fails.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_compile_streaming_destroy(streaming: *mut wasmer_compile_streaming_t) {
+    if !streaming.is_null() {
+        unsafe { Box::from_raw(streaming as *mut StreamingCompilation) };
+    }
+}
@@ -0,0 +1,211 @@
+//! Read every violation of the compilation options by the last module
+//! which failed to compile.
+//!
+//! The compilation stops on the first opcode of a disabled proposal, or
+//! on the first denied opcode. When it fails, the module is scanned
+//! once more to collect all of them, so that they can be fixed at once.
+
+use crate::{
+    error::{update_last_error, wasmer_error_code_t, CApiError},
+    instance::{denied_opcodes, wasmer_compilation_options_t},
+    wasmer_result_t,
+};
+use std::cell::RefCell;
+use wasmer_middleware_common::opcode_codes::{Opcode, Proposal};
+use wasmer_runtime_core::wasmparser::{ImportSectionEntryType, Parser, ParserState, WasmDecoder};
+
+thread_local! {
+    static LAST_COMPILE_VIOLATIONS: RefCell<Vec<wasmer_compile_violation_t>> =
+        RefCell::new(Vec::new());
+}
+
+/// The reasons why an opcode is not allowed by the compilation options.
+#[allow(non_camel_case_types)]
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum wasmer_compile_violation_kind_t {
+    /// The opcode needs the `enable_bulk_memory` option.
+    WASMER_COMPILE_VIOLATION_BULK_MEMORY = 0,
+
+    /// The opcode needs the `enable_reference_types` option.
+    WASMER_COMPILE_VIOLATION_REFERENCE_TYPES = 1,
+
+    /// The opcode needs the `enable_simd` option.
+    WASMER_COMPILE_VIOLATION_SIMD = 2,
+
+    /// The opcode needs the `enable_threads` option.
+    WASMER_COMPILE_VIOLATION_THREADS = 3,
+
+    /// The opcode is one of the `denied_opcodes` of the options.
+    WASMER_COMPILE_VIOLATION_DENIED_OPCODE = 4,
+}
+
+/// An opcode of a module which is not allowed by the compilation
+/// options, see `wasmer_last_compile_violations_get()`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct wasmer_compile_violation_t {
+    /// Why the opcode is not allowed.
+    pub kind: wasmer_compile_violation_kind_t,
+
+    /// The stable code of the opcode, see `wasmer_opcode_name()`.
+    pub opcode: u16,
+
+    /// The index of the function using the opcode, in the function
+    /// index space of the module, imported functions first.
+    pub func_index: u32,
+
+    /// The byte offset of the opcode in the wasm binary.
+    pub offset: u32,
+}
+
+/// Forgets the violations of the previous compilation.
+pub(crate) fn clear_compile_violations() {
+    LAST_COMPILE_VIOLATIONS.with(|violations| violations.borrow_mut().clear());
+}
+
+/// Collects every opcode of the module which is not allowed by the
+/// options, as the violations of the last compilation. The scan stops
+/// at the first malformed section, if any.
+pub(crate) unsafe fn record_compile_violations(
+    bytes: &[u8],
+    options: &wasmer_compilation_options_t,
+) {
+    let denied_opcodes = denied_opcodes(options);
+    let mut violations = Vec::new();
+    let mut parser = Parser::new(bytes);
+    let mut func_index = 0;
+    let mut offset = parser.current_position();
+
+    loop {
+        match *parser.read() {
+            ParserState::EndWasm | ParserState::Error(_) => break,
+            ParserState::ImportSectionEntry {
+                ty: ImportSectionEntryType::Function(_),
+                ..
+            } => func_index += 1,
+            ParserState::EndFunctionBody => func_index += 1,
+            ParserState::CodeOperator(ref op) => {
+                let opcode = Opcode::from_operator(op);
+                if let Some(kind) = violation_kind(opcode, options, denied_opcodes) {
+                    violations.push(wasmer_compile_violation_t {
+                        kind,
+                        opcode: opcode.code(),
+                        func_index,
+                        offset: offset as u32,
+                    });
+                }
+            }
+            _ => {}
+        }
+        offset = parser.current_position();
+    }
+
+    LAST_COMPILE_VIOLATIONS.with(|last_violations| *last_violations.borrow_mut() = violations);
+}
+
+/// Returns why the opcode is not allowed, if it is not. A disabled
+/// proposal takes precedence over a denied opcode.
+fn violation_kind(
+    opcode: Opcode,
+    options: &wasmer_compilation_options_t,
+    denied_opcodes: &[u16],
+) -> Option<wasmer_compile_violation_kind_t> {
+    use wasmer_compile_violation_kind_t::*;
+
+    let kind = match opcode.proposal() {
+        Some(Proposal::BulkMemory) if !options.enable_bulk_memory => {
+            Some(WASMER_COMPILE_VIOLATION_BULK_MEMORY)
+        }
+        Some(Proposal::ReferenceTypes) if !options.enable_reference_types => {
+            Some(WASMER_COMPILE_VIOLATION_REFERENCE_TYPES)
+        }
+        Some(Proposal::Simd) if !options.enable_simd => Some(WASMER_COMPILE_VIOLATION_SIMD),
+        Some(Proposal::Threads) if !options.enable_threads => {
+            Some(WASMER_COMPILE_VIOLATION_THREADS)
+        }
+        _ => None,
+    };
+
+    kind.or_else(|| {
+        if denied_opcodes.contains(&opcode.code()) {
+            Some(WASMER_COMPILE_VIOLATION_DENIED_OPCODE)
+        } else {
+            None
+        }
+    })
+}
+
+/// Gets the number of opcodes of the last module which failed to
+/// compile with options, e.g. with
+/// `wasmer_instantiate_with_options()`, that are not allowed by these
+/// options.
+///
+/// Only the opcodes of the disabled proposals, such as SIMD, and the
+/// denied opcodes are reported; other errors, such as a shared memory
+/// without the threads proposal, are only reported by the error
+/// message of the compilation. The violations are cleared by each
+/// compilation with options on the current thread, and are only
+/// collected when it fails.
+///
+/// Example:
+///
+/// ```c
+/// if (wasmer_instantiate_with_options(&instance, bytes, bytes_len, options) != WASMER_OK) {
+///     uint32_t violations_len = wasmer_last_compile_violations_len();
+///
+///     for (uint32_t i = 0; i < violations_len; i++) {
+///         wasmer_compile_violation_t violation;
+///         wasmer_last_compile_violations_get(i, &violation);
+///         printf("%s at offset %#x\n", wasmer_opcode_name(violation.opcode), violation.offset);
+///     }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn wasmer_last_compile_violations_len() -> u32 {
+    LAST_COMPILE_VIOLATIONS.with(|violations| violations.borrow().len() as u32)
+}
+
+/// Gets the `index`th violation of the compilation options by the last
+/// module which failed to compile, in the order of the opcodes in the
+/// module, see `wasmer_last_compile_violations_len()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// `index` is out of bounds. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_last_compile_violations_get(
+    index: u32,
+    out: *mut wasmer_compile_violation_t,
+) -> wasmer_result_t {
+    if out.is_null() {
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "out ptr is null".to_string(),
+        });
+        return wasmer_result_t::WASMER_ERROR;
+    }
+
+    let violation =
+        LAST_COMPILE_VIOLATIONS.with(|violations| violations.borrow().get(index as usize).copied());
+
+    match violation {
+        Some(violation) => {
+            *out = violation;
+            wasmer_result_t::WASMER_OK
+        }
+        None => {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "violation index {} is out of bounds, there are {} violations",
+                    index,
+                    wasmer_last_compile_violations_len()
+                ),
+            });
+            wasmer_result_t::WASMER_ERROR
+        }
+    }
+}
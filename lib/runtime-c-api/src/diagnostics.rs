@@ -0,0 +1,157 @@
+//! Dump the state of an instance for post-mortem analysis.
+
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    instance::wasmer_instance_t,
+    trap, wasmer_result_t,
+};
+use std::slice;
+use wasmer_runtime::Instance;
+use wasmer_runtime_core::units::Bytes;
+
+#[cfg(not(feature = "cranelift-backend"))]
+use wasmer_middleware_common::metering;
+use wasmer_middleware_common::{opcode_trace, runtime_breakpoints};
+
+/// Formats the diagnostics of the instance as a compact JSON object,
+/// see `wasmer_instance_dump_diagnostics()`.
+fn diagnostics(instance: &mut Instance) -> String {
+    let mut fields = Vec::new();
+
+    #[cfg(not(feature = "cranelift-backend"))]
+    {
+        fields.push(format!(
+            "\"gas_used\":{}",
+            metering::get_points_used(instance)
+        ));
+        fields.push(format!(
+            "\"gas_limit\":{}",
+            metering::get_points_limit(instance)
+        ));
+    }
+
+    fields.push(format!(
+        "\"last_opcode_location\":{}",
+        opcode_trace::get_opcodetracer_last_location(instance)
+    ));
+    fields.push(format!(
+        "\"runtime_breakpoint\":{}",
+        runtime_breakpoints::get_runtime_breakpoint_value(instance)
+    ));
+
+    let context = instance.context();
+    let memory_size = if context.internal.memory_base.is_null() {
+        0
+    } else {
+        let Bytes(length) = context.memory(0).size().bytes();
+        length
+    };
+    fields.push(format!("\"memory_size\":{}", memory_size));
+
+    let info = &instance.module.info;
+    fields.push(format!("\"exports\":{}", info.exports.len()));
+    fields.push(format!(
+        "\"imports\":{}",
+        info.imported_functions.len()
+            + info.imported_memories.len()
+            + info.imported_tables.len()
+            + info.imported_globals.len()
+    ));
+
+    // The trap messages are fixed descriptions and numbers, which need
+    // no escaping.
+    fields.push(match trap::last_trap_message(instance) {
+        Some(message) => format!("\"last_trap\":\"{}\"", message),
+        None => "\"last_trap\":null".to_string(),
+    });
+
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Writes a snapshot of the state of the instance, e.g. for the crash
+/// report of an unexpected execution failure, as a compact JSON object
+/// into `out`:
+///
+/// * `gas_used` and `gas_limit`: the gas used by the instance, and its
+///   limit, see `wasmer_instance_get_points_used()`,
+/// * `last_opcode_location`: see
+///   `wasmer_instance_get_last_opcode_location()`,
+/// * `runtime_breakpoint`: see
+///   `wasmer_instance_get_runtime_breakpoint_value()`,
+/// * `memory_size`: the size in bytes of the memory, or 0 without a
+///   memory,
+/// * `exports` and `imports`: the number of exports and imports of the
+///   module,
+/// * `last_trap`: the trap raised during the last call on the
+///   instance, see `wasmer_instance_last_trap_message_with_location()`,
+///   or `null`.
+///
+/// The fields may be extended, so the snapshot should be parsed as
+/// JSON rather than matched as text. It is not NUL-terminated.
+///
+/// The snapshot is copied into `out`, and its length is stored in
+/// `written`. If `out` is a null pointer, nothing is copied and the
+/// length of the snapshot is stored in `written`, which can be used to
+/// allocate a buffer of the right size.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including
+/// when `out_len` is smaller than the snapshot: then nothing is copied,
+/// the length of the snapshot is still stored in `written`, and the
+/// error code is `WASMER_ERROR_CODE_INVALID_ARGUMENT`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t diagnostics_length = 0;
+/// wasmer_instance_dump_diagnostics(instance, NULL, 0, &diagnostics_length);
+///
+/// uint8_t *diagnostics = malloc(diagnostics_length);
+/// wasmer_instance_dump_diagnostics(instance, diagnostics, diagnostics_length, &diagnostics_length);
+/// fprintf(stderr, "%.*s\n", diagnostics_length, diagnostics);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_dump_diagnostics(
+    instance: *mut wasmer_instance_t,
+    out: *mut u8,
+    out_len: u32,
+    written: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || written.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and written ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &mut *(instance as *mut Instance);
+        let diagnostics = diagnostics(instance);
+
+        if out.is_null() {
+            *written = diagnostics.len() as u32;
+            return wasmer_result_t::WASMER_OK;
+        }
+
+        *written = diagnostics.len() as u32;
+        if diagnostics.len() > out_len as usize {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "the diagnostics take {} bytes, but out_len is {}",
+                    diagnostics.len(),
+                    out_len
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        slice::from_raw_parts_mut(out, diagnostics.len()).copy_from_slice(diagnostics.as_bytes());
+        wasmer_result_t::WASMER_OK
+    })
+}
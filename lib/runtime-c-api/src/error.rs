@@ -2,11 +2,16 @@
 
 use libc::{c_char, c_int};
 use std::{
+    any::Any,
     cell::RefCell,
     error::Error,
     fmt::{self, Display, Formatter},
+    panic::{self, AssertUnwindSafe},
     ptr, slice,
 };
+use wasmer_runtime_core::error::{
+    CallError, CompileError, CreationError, Error as RuntimeCoreError, GrowError, RuntimeError,
+};
 
 thread_local! {
     static LAST_ERROR: RefCell<Option<Box<dyn Error>>> = RefCell::new(None);
@@ -18,11 +23,100 @@ pub fn update_last_error<E: Error + 'static>(err: E) {
     });
 }
 
+/// Runs `f` and returns its result. A panic must not unwind across
+/// the C ABI, so a panic raised by `f` is caught, stored as the last
+/// error, and `default` is returned instead.
+pub(crate) fn catch_panic<T, F: FnOnce() -> T>(default: T, f: F) -> T {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(payload) => {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                msg: format!("panic: {}", panic_message(&*payload)),
+            });
+            default
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
 /// Retrieve the most recent error, clearing it in the process.
 pub(crate) fn take_last_error() -> Option<Box<dyn Error>> {
     LAST_ERROR.with(|prev| prev.borrow_mut().take())
 }
 
+/// Gets the category of the last error if any, to handle errors
+/// without parsing their message.
+///
+/// The last error is left untouched: call this function before
+/// `wasmer_last_error_message()`, which clears it.
+///
+/// Returns `WASMER_ERROR_CODE_NONE` if there is no last error.
+///
+/// Example:
+///
+/// ```c
+/// if (wasmer_instance_call(instance, "sum", params, 2, results, 1) != WASMER_OK) {
+///     if (wasmer_last_error_code() == WASMER_ERROR_CODE_OUT_OF_GAS) {
+///         // Charge the whole gas limit.
+///     }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn wasmer_last_error_code() -> wasmer_error_code_t {
+    LAST_ERROR.with(|prev| match *prev.borrow() {
+        Some(ref err) => error_code(&**err),
+        None => wasmer_error_code_t::WASMER_ERROR_CODE_NONE,
+    })
+}
+
+/// Returns the category of an error: the one of a `CApiError`, or the
+/// one matching the runtime error type.
+pub(crate) fn error_code(error: &(dyn Error + 'static)) -> wasmer_error_code_t {
+    use wasmer_error_code_t::*;
+
+    fn call_error_code(error: &CallError) -> wasmer_error_code_t {
+        match error {
+            CallError::Resolve(_) => WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            CallError::Runtime(_) => WASMER_ERROR_CODE_TRAP,
+        }
+    }
+
+    if let Some(error) = error.downcast_ref::<CApiError>() {
+        error.code
+    } else if let Some(error) = error.downcast_ref::<CausedError>() {
+        error.error.code
+    } else if let Some(error) = error.downcast_ref::<RuntimeCoreError>() {
+        match error {
+            RuntimeCoreError::CompileError(_) => WASMER_ERROR_CODE_COMPILE,
+            RuntimeCoreError::LinkError(_) => WASMER_ERROR_CODE_LINK,
+            RuntimeCoreError::RuntimeError(_) => WASMER_ERROR_CODE_TRAP,
+            RuntimeCoreError::ResolveError(_) => WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            RuntimeCoreError::CallError(error) => call_error_code(error),
+            RuntimeCoreError::CreationError(_) => WASMER_ERROR_CODE_INVALID_ARGUMENT,
+        }
+    } else if let Some(error) = error.downcast_ref::<CallError>() {
+        call_error_code(error)
+    } else if error.is::<CompileError>() {
+        WASMER_ERROR_CODE_COMPILE
+    } else if error.is::<RuntimeError>() {
+        WASMER_ERROR_CODE_TRAP
+    } else if error.is::<CreationError>() || error.is::<GrowError>() {
+        WASMER_ERROR_CODE_INVALID_ARGUMENT
+    } else {
+        WASMER_ERROR_CODE_UNKNOWN
+    }
+}
+
 /// Gets the length in bytes of the last error if any.
 ///
 /// This can be used to dynamically allocate a buffer with the correct number of
@@ -99,8 +193,119 @@ pub unsafe extern "C" fn wasmer_last_error_message(buffer: *mut c_char, length:
     error_message.len() as c_int + 1
 }
 
+/// Gets the message of a cause of the last error into the provided
+/// buffer `buffer` up to the given `length`, to see why an operation
+/// failed beyond the message of the error itself, e.g. the compile
+/// error behind a failed instantiation.
+///
+/// The causes form a chain: the cause at index 0 is the one of the last
+/// error, the cause at index 1 is the one of the cause at index 0, and
+/// so on. Walk the chain by increasing `index` until this function
+/// returns 0.
+///
+/// The last error is left untouched: call this function before
+/// `wasmer_last_error_message()`, which clears it.
+///
+/// The function returns the length of the message in bytes, trailing
+/// null character included, 0 if there is no cause at `index`, and -1
+/// if `buffer` is a null pointer, or is too small to hold the message.
+///
+/// Example:
+///
+/// ```c
+/// char cause[256];
+///
+/// for (uint32_t index = 0; wasmer_last_error_cause(index, cause, sizeof(cause)) > 0; index++) {
+///     printf("Caused by: `%s`\n", cause);
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_last_error_cause(
+    index: u32,
+    buffer: *mut c_char,
+    length: c_int,
+) -> c_int {
+    if buffer.is_null() {
+        return -1;
+    }
+
+    let cause_message = LAST_ERROR.with(|prev| {
+        let mut cause = prev.borrow().as_ref()?.source();
+        for _ in 0..index {
+            cause = cause?.source();
+        }
+        cause.map(|cause| cause.to_string())
+    });
+    let cause_message = match cause_message {
+        Some(cause_message) => cause_message,
+        None => return 0,
+    };
+
+    let length = length as usize;
+
+    if cause_message.len() >= length {
+        return -1;
+    }
+
+    let buffer = slice::from_raw_parts_mut(buffer as *mut u8, length);
+    buffer[..cause_message.len()].copy_from_slice(cause_message.as_bytes());
+    buffer[cause_message.len()] = 0;
+
+    cause_message.len() as c_int + 1
+}
+
+/// The categories of the errors of the C API, see
+/// `wasmer_last_error_code()`.
+#[allow(non_camel_case_types)]
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum wasmer_error_code_t {
+    /// There is no last error.
+    WASMER_ERROR_CODE_NONE = 0,
+
+    /// A pointer argument is null.
+    WASMER_ERROR_CODE_NULL_POINTER = 1,
+
+    /// A string argument is not valid UTF-8.
+    WASMER_ERROR_CODE_UTF8 = 2,
+
+    /// The wasm bytes cannot be parsed, validated or compiled.
+    WASMER_ERROR_CODE_COMPILE = 3,
+
+    /// The imports do not match the imports of the module.
+    WASMER_ERROR_CODE_LINK = 4,
+
+    /// The wasm code trapped, or hit a runtime breakpoint other than
+    /// the out of gas one.
+    WASMER_ERROR_CODE_TRAP = 5,
+
+    /// The gas limit of the instance is exceeded.
+    WASMER_ERROR_CODE_OUT_OF_GAS = 6,
+
+    /// An argument is invalid, e.g. an index out of bounds, or the
+    /// module does not comply with the compilation options.
+    WASMER_ERROR_CODE_INVALID_ARGUMENT = 7,
+
+    /// The operation is not supported, e.g. by the C API, or without
+    /// the `metering` compilation option.
+    WASMER_ERROR_CODE_UNSUPPORTED = 8,
+
+    /// The compilation exceeded the `compile_timeout_ms` compilation
+    /// option.
+    WASMER_ERROR_CODE_TIMEOUT = 9,
+
+    /// The call executed more instructions than the
+    /// `hard_instruction_limit` compilation option, whatever its gas
+    /// limit.
+    WASMER_ERROR_CODE_INSTRUCTION_LIMIT = 10,
+
+    /// Any other error, e.g. a panic or a failed allocation.
+    WASMER_ERROR_CODE_UNKNOWN = 255,
+}
+
 #[derive(Debug)]
 pub struct CApiError {
+    pub code: wasmer_error_code_t,
     pub msg: String,
 }
 
@@ -111,3 +316,40 @@ impl Display for CApiError {
 }
 
 impl Error for CApiError {}
+
+impl CApiError {
+    /// Attaches the error which caused this one, so that it can be
+    /// read with `wasmer_last_error_cause()`.
+    pub(crate) fn caused_by<E: Error + 'static>(self, cause: E) -> CausedError {
+        CausedError {
+            error: self,
+            cause: Some(Box::new(cause)),
+        }
+    }
+}
+
+/// A `CApiError` along with the error which caused it, if any, as the
+/// source of its chain of causes.
+#[derive(Debug)]
+pub struct CausedError {
+    pub error: CApiError,
+    pub cause: Option<Box<dyn Error>>,
+}
+
+impl From<CApiError> for CausedError {
+    fn from(error: CApiError) -> Self {
+        CausedError { error, cause: None }
+    }
+}
+
+impl Display for CausedError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl Error for CausedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.cause.as_ref().map(|cause| &**cause)
+    }
+}
@@ -2,17 +2,18 @@
 //! and table) on an instance.
 
 use crate::{
-    error::{update_last_error, CApiError},
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
     global::wasmer_global_t,
     import::wasmer_import_func_t,
+    instance::call_instance,
     memory::wasmer_memory_t,
     module::wasmer_module_t,
     table::wasmer_table_t,
     value::{wasmer_value, wasmer_value_t, wasmer_value_tag},
     wasmer_byte_array, wasmer_result_t,
 };
-use libc::{c_int, c_uint};
-use std::{ptr, slice};
+use libc::{c_char, c_int, c_uint};
+use std::{convert::TryFrom, ffi::CStr, ptr, slice};
 use wasmer_runtime::{Instance, Module, Value};
 use wasmer_runtime_core::{export::Export, module::ExportIndex};
 
@@ -261,6 +262,82 @@ pub unsafe extern "C" fn wasmer_exports_get(
     &mut (*named_exports).0[idx as usize] as *mut NamedExport as *mut wasmer_export_t
 }
 
+/// Gets the export of the given name and kind.
+///
+/// Export names are unique within a module, whatever their kind: a
+/// module exporting, say, a function and a global under the same name
+/// is invalid, and fails to compile. Looking an export up by its name
+/// and kind therefore never picks an export of another kind, where a
+/// lookup by name alone would have to check the kind of the export it
+/// found. Should several exports match, the first one in declaration
+/// order is returned.
+///
+/// The returned export is owned by `exports`, and is valid as long as
+/// `exports` is.
+///
+/// This function returns a null pointer if no export matches. It
+/// returns a null pointer and updates the last error if `kind` is not a
+/// `wasmer_import_export_kind`, if `exports` or `name` is a null
+/// pointer, or if `name` is not a valid UTF-8 string.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_export_t *memory_export =
+///     wasmer_exports_get_by_name_and_kind(exports, "memory", WASM_MEMORY);
+///
+/// if (memory_export != NULL) {
+///     wasmer_memory_t *memory = NULL;
+///     wasmer_export_to_memory(memory_export, &memory);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_exports_get_by_name_and_kind(
+    exports: *mut wasmer_exports_t,
+    name: *const c_char,
+    kind: u32,
+) -> *mut wasmer_export_t {
+    let kind = match wasmer_import_export_kind::try_from(kind) {
+        Ok(kind) => kind,
+        Err(_) => {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "kind is not a valid `wasmer_import_export_kind`".to_string(),
+            });
+            return ptr::null_mut();
+        }
+    };
+
+    if exports.is_null() || name.is_null() {
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "exports or name ptr is null".to_string(),
+        });
+        return ptr::null_mut();
+    }
+
+    let name = match CStr::from_ptr(name).to_str() {
+        Ok(name) => name,
+        Err(_) => {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                msg: "name is not a valid UTF-8 string".to_string(),
+            });
+            return ptr::null_mut();
+        }
+    };
+
+    let named_exports = &mut *(exports as *mut NamedExports);
+    named_exports
+        .0
+        .iter_mut()
+        .find(|named_export| named_export.name == name && export_kind(&named_export.export) == kind)
+        .map_or(ptr::null_mut(), |named_export| {
+            named_export as *mut NamedExport as *mut wasmer_export_t
+        })
+}
+
 /// Gets wasmer_export kind
 #[no_mangle]
 #[allow(clippy::cast_ptr_alignment)]
@@ -268,7 +345,11 @@ pub unsafe extern "C" fn wasmer_export_kind(
     export: *mut wasmer_export_t,
 ) -> wasmer_import_export_kind {
     let named_export = &*(export as *mut NamedExport);
-    match named_export.export {
+    export_kind(&named_export.export)
+}
+
+fn export_kind(export: &Export) -> wasmer_import_export_kind {
+    match export {
         Export::Table(_) => wasmer_import_export_kind::WASM_TABLE,
         Export::Function { .. } => wasmer_import_export_kind::WASM_FUNCTION,
         Export::Global(_) => wasmer_import_export_kind::WASM_GLOBAL,
@@ -288,17 +369,20 @@ pub unsafe extern "C" fn wasmer_export_func_params_arity(
     func: *const wasmer_export_func_t,
     result: *mut u32,
 ) -> wasmer_result_t {
-    let named_export = &*(func as *const NamedExport);
-    let export = &named_export.export;
-    if let Export::Function { ref signature, .. } = *export {
-        *result = signature.params().len() as u32;
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "func ptr error in wasmer_export_func_params_arity".to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let named_export = &*(func as *const NamedExport);
+        let export = &named_export.export;
+        if let Export::Function { ref signature, .. } = *export {
+            *result = signature.params().len() as u32;
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "func ptr error in wasmer_export_func_params_arity".to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
+        }
+    })
 }
 
 /// Sets the params buffer to the parameter types of the given wasmer_export_func_t
@@ -314,21 +398,24 @@ pub unsafe extern "C" fn wasmer_export_func_params(
     params: *mut wasmer_value_tag,
     params_len: u32,
 ) -> wasmer_result_t {
-    let named_export = &*(func as *const NamedExport);
-    let export = &named_export.export;
-    if let Export::Function { ref signature, .. } = *export {
-        let params: &mut [wasmer_value_tag] =
-            slice::from_raw_parts_mut(params, params_len as usize);
-        for (i, item) in signature.params().iter().enumerate() {
-            params[i] = item.into();
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let named_export = &*(func as *const NamedExport);
+        let export = &named_export.export;
+        if let Export::Function { ref signature, .. } = *export {
+            let params: &mut [wasmer_value_tag] =
+                slice::from_raw_parts_mut(params, params_len as usize);
+            for (i, item) in signature.params().iter().enumerate() {
+                params[i] = item.into();
+            }
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "func ptr error in wasmer_export_func_params".to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
         }
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "func ptr error in wasmer_export_func_params".to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    })
 }
 
 /// Sets the returns buffer to the parameter types of the given wasmer_export_func_t
@@ -344,21 +431,24 @@ pub unsafe extern "C" fn wasmer_export_func_returns(
     returns: *mut wasmer_value_tag,
     returns_len: u32,
 ) -> wasmer_result_t {
-    let named_export = &*(func as *const NamedExport);
-    let export = &named_export.export;
-    if let Export::Function { ref signature, .. } = *export {
-        let returns: &mut [wasmer_value_tag] =
-            slice::from_raw_parts_mut(returns, returns_len as usize);
-        for (i, item) in signature.returns().iter().enumerate() {
-            returns[i] = item.into();
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let named_export = &*(func as *const NamedExport);
+        let export = &named_export.export;
+        if let Export::Function { ref signature, .. } = *export {
+            let returns: &mut [wasmer_value_tag] =
+                slice::from_raw_parts_mut(returns, returns_len as usize);
+            for (i, item) in signature.returns().iter().enumerate() {
+                returns[i] = item.into();
+            }
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "func ptr error in wasmer_export_func_returns".to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
         }
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "func ptr error in wasmer_export_func_returns".to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    })
 }
 
 /// Sets the result parameter to the arity of the returns of the wasmer_export_func_t
@@ -373,17 +463,20 @@ pub unsafe extern "C" fn wasmer_export_func_returns_arity(
     func: *const wasmer_export_func_t,
     result: *mut u32,
 ) -> wasmer_result_t {
-    let named_export = &*(func as *const NamedExport);
-    let export = &named_export.export;
-    if let Export::Function { ref signature, .. } = *export {
-        *result = signature.returns().len() as u32;
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "func ptr error in wasmer_export_func_results_arity".to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let named_export = &*(func as *const NamedExport);
+        let export = &named_export.export;
+        if let Export::Function { ref signature, .. } = *export {
+            *result = signature.returns().len() as u32;
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "func ptr error in wasmer_export_func_results_arity".to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
+        }
+    })
 }
 
 /// Gets export func from export
@@ -407,21 +500,24 @@ pub unsafe extern "C" fn wasmer_export_to_memory(
     export: *const wasmer_export_t,
     memory: *mut *mut wasmer_memory_t,
 ) -> wasmer_result_t {
-    let named_export = &*(export as *const NamedExport);
-    let export = &named_export.export;
-
-    if let Export::Memory(exported_memory) = export {
-        let mem = Box::new(exported_memory.clone());
-        *memory = Box::into_raw(mem) as *mut wasmer_memory_t;
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "cannot cast the `wasmer_export_t` pointer to a  `wasmer_memory_t` \
-                  pointer because it does not represent a memory export."
-                .to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let named_export = &*(export as *const NamedExport);
+        let export = &named_export.export;
+
+        if let Export::Memory(exported_memory) = export {
+            let mem = Box::new(exported_memory.clone());
+            *memory = Box::into_raw(mem) as *mut wasmer_memory_t;
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "cannot cast the `wasmer_export_t` pointer to a  `wasmer_memory_t` \
+                      pointer because it does not represent a memory export."
+                    .to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
+        }
+    })
 }
 
 /// Gets name from wasmer_export
@@ -438,6 +534,9 @@ pub unsafe extern "C" fn wasmer_export_name(export: *mut wasmer_export_t) -> was
 /// Calls a `func` with the provided parameters.
 /// Results are set using the provided `results` pointer.
 ///
+/// The call is run like by `wasmer_instance_call()`, with the same
+/// per-call bookkeeping and hooks of the instance holding the export.
+///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
 ///
 /// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
@@ -451,70 +550,77 @@ pub unsafe extern "C" fn wasmer_export_func_call(
     results: *mut wasmer_value_t,
     results_len: c_uint,
 ) -> wasmer_result_t {
-    if func.is_null() {
-        update_last_error(CApiError {
-            msg: "func ptr is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-
-    if params_len > 0 && params.is_null() {
-        update_last_error(CApiError {
-            msg: "params ptr is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if func.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "func ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
 
-    let params: Vec<Value> = {
-        if params_len == 0 {
-            vec![]
-        } else {
-            slice::from_raw_parts::<wasmer_value_t>(params, params_len as usize)
-                .iter()
-                .cloned()
-                .map(|x| x.into())
-                .collect()
+        if params_len > 0 && params.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "params ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
         }
-    };
 
-    let named_export = &*(func as *mut NamedExport);
-
-    let results: &mut [wasmer_value_t] = slice::from_raw_parts_mut(results, results_len as usize);
-
-    let instance = &*named_export.instance;
-    let result = instance.call(&named_export.name, &params[..]);
-
-    match result {
-        Ok(results_vec) => {
-            if !results_vec.is_empty() {
-                let ret = match results_vec[0] {
-                    Value::I32(x) => wasmer_value_t {
-                        tag: wasmer_value_tag::WASM_I32,
-                        value: wasmer_value { I32: x },
-                    },
-                    Value::I64(x) => wasmer_value_t {
-                        tag: wasmer_value_tag::WASM_I64,
-                        value: wasmer_value { I64: x },
-                    },
-                    Value::F32(x) => wasmer_value_t {
-                        tag: wasmer_value_tag::WASM_F32,
-                        value: wasmer_value { F32: x },
-                    },
-                    Value::F64(x) => wasmer_value_t {
-                        tag: wasmer_value_tag::WASM_F64,
-                        value: wasmer_value { F64: x },
-                    },
-                    Value::V128(_) => unimplemented!("returning V128 type"),
-                };
-                results[0] = ret;
+        let params: Vec<Value> = {
+            if params_len == 0 {
+                vec![]
+            } else {
+                slice::from_raw_parts::<wasmer_value_t>(params, params_len as usize)
+                    .iter()
+                    .cloned()
+                    .map(|x| x.into())
+                    .collect()
+            }
+        };
+
+        let named_export = &*(func as *mut NamedExport);
+
+        let results: &mut [wasmer_value_t] =
+            slice::from_raw_parts_mut(results, results_len as usize);
+
+        let instance = &mut *named_export.instance;
+        let result = call_instance(instance, |instance| {
+            instance.call(&named_export.name, &params[..])
+        });
+
+        match result {
+            Ok(results_vec) => {
+                if !results_vec.is_empty() {
+                    let ret = match results_vec[0] {
+                        Value::I32(x) => wasmer_value_t {
+                            tag: wasmer_value_tag::WASM_I32,
+                            value: wasmer_value { I32: x },
+                        },
+                        Value::I64(x) => wasmer_value_t {
+                            tag: wasmer_value_tag::WASM_I64,
+                            value: wasmer_value { I64: x },
+                        },
+                        Value::F32(x) => wasmer_value_t {
+                            tag: wasmer_value_tag::WASM_F32,
+                            value: wasmer_value { F32: x },
+                        },
+                        Value::F64(x) => wasmer_value_t {
+                            tag: wasmer_value_tag::WASM_F64,
+                            value: wasmer_value { F64: x },
+                        },
+                        Value::V128(_) => unimplemented!("returning V128 type"),
+                    };
+                    results[0] = ret;
+                }
+                wasmer_result_t::WASMER_OK
+            }
+            Err(err) => {
+                update_last_error(err);
+                wasmer_result_t::WASMER_ERROR
             }
-            wasmer_result_t::WASMER_OK
-        }
-        Err(err) => {
-            update_last_error(err);
-            wasmer_result_t::WASMER_ERROR
         }
-    }
+    })
 }
 
 impl From<(&std::string::String, &ExportIndex)> for NamedExportDescriptor {
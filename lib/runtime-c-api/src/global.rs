@@ -1,7 +1,13 @@
 //! Create, set, get and destroy global variables of an instance.
 
-use crate::value::{wasmer_value_t, wasmer_value_tag};
-use wasmer_runtime::Global;
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    instance::wasmer_instance_t,
+    value::{wasmer_value_t, wasmer_value_tag},
+    wasmer_result_t,
+};
+use std::{convert::TryInto, slice};
+use wasmer_runtime::{Global, Instance, Value};
 
 #[repr(C)]
 #[derive(Clone)]
@@ -68,3 +74,238 @@ pub extern "C" fn wasmer_global_destroy(global: *mut wasmer_global_t) {
         unsafe { Box::from_raw(global as *mut Global) };
     }
 }
+
+/// Length of the header of a globals snapshot: the hash of the module,
+/// followed by the number of mutable globals.
+const GLOBALS_SNAPSHOT_HEADER_LENGTH: usize = 32 + 4;
+
+/// Length of a global in a globals snapshot: its type, followed by the
+/// bits of its value.
+const GLOBALS_SNAPSHOT_ENTRY_LENGTH: usize = 1 + 8;
+
+/// Returns the length in bytes of the snapshot written by
+/// `wasmer_instance_globals_snapshot()` for the given instance.
+///
+/// Returns 0 if `instance` is a null pointer.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_globals_snapshot_length(
+    instance: *const wasmer_instance_t,
+) -> u32 {
+    if instance.is_null() {
+        return 0;
+    }
+
+    let instance = &*(instance as *const Instance);
+
+    snapshot_length(&mutable_globals(instance)) as u32
+}
+
+/// Captures the values of all the mutable globals defined by the
+/// instance into `out`, as an opaque snapshot to be given to
+/// `wasmer_instance_globals_restore()` later. `out_len` must be at
+/// least `wasmer_instance_globals_snapshot_length()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t snapshot_length = wasmer_instance_globals_snapshot_length(instance);
+/// uint8_t *snapshot = malloc(snapshot_length);
+/// wasmer_instance_globals_snapshot(instance, snapshot, snapshot_length);
+///
+/// // Run the instance.
+///
+/// wasmer_instance_globals_restore(instance, snapshot, snapshot_length);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_globals_snapshot(
+    instance: *const wasmer_instance_t,
+    out: *mut u8,
+    out_len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and out ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let globals = mutable_globals(instance);
+        let length = snapshot_length(&globals);
+        if (out_len as usize) < length {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "out is too small: {} bytes, while the snapshot needs {} bytes",
+                    out_len, length
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let mut snapshot = Vec::with_capacity(length);
+        snapshot.extend_from_slice(&instance.module.info.wasm_hash);
+        snapshot.extend_from_slice(&(globals.len() as u32).to_le_bytes());
+        for global in &globals {
+            let (code, bits) = match encode_value(global.get()) {
+                Ok(encoded) => encoded,
+                Err(msg) => {
+                    update_last_error(CApiError {
+                        code: wasmer_error_code_t::WASMER_ERROR_CODE_UNSUPPORTED,
+                        msg,
+                    });
+                    return wasmer_result_t::WASMER_ERROR;
+                }
+            };
+            snapshot.push(code);
+            snapshot.extend_from_slice(&bits.to_le_bytes());
+        }
+
+        slice::from_raw_parts_mut(out, length).copy_from_slice(&snapshot);
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Restores the values of the mutable globals of the instance from a
+/// snapshot written by `wasmer_instance_globals_snapshot()`.
+///
+/// The snapshot must come from an instance of the same module: if its
+/// layout does not match the globals of the instance, no global is
+/// modified and an error is returned.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_globals_restore(
+    instance: *mut wasmer_instance_t,
+    snapshot: *const u8,
+    snapshot_len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || snapshot.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and snapshot ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let snapshot = slice::from_raw_parts(snapshot, snapshot_len as usize);
+        let globals = mutable_globals(instance);
+
+        let values = match decode_snapshot(instance, &globals, snapshot) {
+            Ok(values) => values,
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: format!("snapshot does not match the instance: {}", msg),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        for (global, value) in globals.iter().zip(values) {
+            global.set(value);
+        }
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+fn mutable_globals(instance: &Instance) -> Vec<Global> {
+    instance
+        .local_globals()
+        .into_iter()
+        .filter(|global| global.descriptor().mutable)
+        .collect()
+}
+
+fn snapshot_length(globals: &[Global]) -> usize {
+    GLOBALS_SNAPSHOT_HEADER_LENGTH + globals.len() * GLOBALS_SNAPSHOT_ENTRY_LENGTH
+}
+
+fn encode_value(value: Value) -> Result<(u8, u64), String> {
+    Ok(match value {
+        Value::I32(x) => (0, u64::from(x as u32)),
+        Value::I64(x) => (1, x as u64),
+        Value::F32(x) => (2, u64::from(x.to_bits())),
+        Value::F64(x) => (3, x.to_bits()),
+        Value::V128(_) => return Err("v128 globals are not supported".to_string()),
+    })
+}
+
+fn decode_value(code: u8, bits: u64) -> Result<Value, String> {
+    Ok(match code {
+        0 => Value::I32(bits as u32 as i32),
+        1 => Value::I64(bits as i64),
+        2 => Value::F32(f32::from_bits(bits as u32)),
+        3 => Value::F64(f64::from_bits(bits)),
+        _ => return Err(format!("unknown value type {}", code)),
+    })
+}
+
+/// Decodes all the values of a snapshot, checking them against the
+/// globals of the instance, before any of them is restored.
+fn decode_snapshot(
+    instance: &Instance,
+    globals: &[Global],
+    snapshot: &[u8],
+) -> Result<Vec<Value>, String> {
+    if snapshot.len() != snapshot_length(globals) {
+        return Err(format!(
+            "expected {} bytes, got {}",
+            snapshot_length(globals),
+            snapshot.len()
+        ));
+    }
+
+    let (hash, rest) = snapshot.split_at(32);
+    if hash != instance.module.info.wasm_hash {
+        return Err("it was taken from another module".to_string());
+    }
+
+    let (count, mut entries) = rest.split_at(4);
+    let count = u32::from_le_bytes(count.try_into().unwrap()) as usize;
+    if count != globals.len() {
+        return Err(format!(
+            "expected {} mutable globals, got {}",
+            globals.len(),
+            count
+        ));
+    }
+
+    let mut values = Vec::with_capacity(count);
+    for (index, global) in globals.iter().enumerate() {
+        let (entry, rest) = entries.split_at(GLOBALS_SNAPSHOT_ENTRY_LENGTH);
+        entries = rest;
+
+        let bits = u64::from_le_bytes(entry[1..].try_into().unwrap());
+        let value = decode_value(entry[0], bits)?;
+        let expected_ty = global.descriptor().ty;
+        if value.ty() != expected_ty {
+            return Err(format!(
+                "global {} has type {:?}, got {:?}",
+                index,
+                expected_ty,
+                value.ty()
+            ));
+        }
+        values.push(value);
+    }
+
+    Ok(values)
+}
@@ -23,7 +23,10 @@ pub unsafe extern "C" fn wasmer_emscripten_get_globals(
     match EmscriptenGlobals::new(module) {
         Ok(globals) => Box::into_raw(Box::new(globals)) as *mut wasmer_emscripten_globals_t,
         Err(msg) => {
-            update_last_error(CApiError { msg });
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg,
+            });
             return ptr::null_mut();
         }
     }
@@ -51,25 +54,27 @@ pub unsafe extern "C" fn wasmer_emscripten_set_up(
     instance: *mut wasmer_instance_t,
     globals: *mut wasmer_emscripten_globals_t,
 ) -> wasmer_result_t {
-    if globals.is_null() || instance.is_null() {
-        return wasmer_result_t::WASMER_ERROR;
-    }
-    let instance = &mut *(instance as *mut Instance);
-    let globals = &*(globals as *mut EmscriptenGlobals);
-    let em_data = Box::into_raw(Box::new(EmscriptenData::new(
-        instance,
-        &globals.data,
-        Default::default(),
-    ))) as *mut c_void;
-    instance.context_mut().data = em_data;
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if globals.is_null() || instance.is_null() {
+            return wasmer_result_t::WASMER_ERROR;
+        }
+        let instance = &mut *(instance as *mut Instance);
+        let globals = &*(globals as *mut EmscriptenGlobals);
+        let em_data = Box::into_raw(Box::new(EmscriptenData::new(
+            instance,
+            &globals.data,
+            Default::default(),
+        ))) as *mut c_void;
+        instance.context_mut().data = em_data;
 
-    match wasmer_emscripten::set_up_emscripten(instance) {
-        Ok(_) => wasmer_result_t::WASMER_OK,
-        Err(e) => {
-            update_last_error(e);
-            wasmer_result_t::WASMER_ERROR
+        match wasmer_emscripten::set_up_emscripten(instance) {
+            Ok(_) => wasmer_result_t::WASMER_OK,
+            Err(e) => {
+                update_last_error(e);
+                wasmer_result_t::WASMER_ERROR
+            }
         }
-    }
+    })
 }
 
 /// Convenience function for setting up arguments and calling the Emscripten
@@ -87,39 +92,42 @@ pub unsafe extern "C" fn wasmer_emscripten_call_main(
     args: *const wasmer_byte_array,
     args_len: c_uint,
 ) -> wasmer_result_t {
-    if instance.is_null() || args.is_null() {
-        return wasmer_result_t::WASMER_ERROR;
-    }
-    let instance = &mut *(instance as *mut Instance);
-
-    let arg_list = get_slice_checked(args, args_len as usize);
-    let arg_process_result: Result<Vec<&str>, _> =
-        arg_list.iter().map(|arg| arg.as_str()).collect();
-    let arg_vec = match arg_process_result.as_ref() {
-        Ok(arg_vec) => arg_vec,
-        Err(err) => {
-            update_last_error(*err);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || args.is_null() {
             return wasmer_result_t::WASMER_ERROR;
         }
-    };
+        let instance = &mut *(instance as *mut Instance);
+
+        let arg_list = get_slice_checked(args, args_len as usize);
+        let arg_process_result: Result<Vec<&str>, _> =
+            arg_list.iter().map(|arg| arg.as_str()).collect();
+        let arg_vec = match arg_process_result.as_ref() {
+            Ok(arg_vec) => arg_vec,
+            Err(err) => {
+                update_last_error(*err);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
 
-    let prog_name = if let Some(prog_name) = arg_vec.first() {
-        prog_name
-    } else {
-        update_last_error(CApiError {
-            msg: "First argument (program name) is required to execute Emscripten's main function"
-                .to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    };
+        let prog_name = if let Some(prog_name) = arg_vec.first() {
+            prog_name
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "First argument (program name) is required to execute Emscripten's main function"
+                    .to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        };
 
-    match wasmer_emscripten::emscripten_call_main(instance, prog_name, &arg_vec[1..]) {
-        Ok(_) => wasmer_result_t::WASMER_OK,
-        Err(e) => {
-            update_last_error(e);
-            wasmer_result_t::WASMER_ERROR
+        match wasmer_emscripten::emscripten_call_main(instance, prog_name, &arg_vec[1..]) {
+            Ok(_) => wasmer_result_t::WASMER_OK,
+            Err(e) => {
+                update_last_error(e);
+                wasmer_result_t::WASMER_ERROR
+            }
         }
-    }
+    })
 }
 
 /// Create a `wasmer_import_object_t` with Emscripten imports, use
@@ -2,37 +2,85 @@
 //! and table) on an instance.
 
 use crate::{
-    error::{update_last_error, CApiError},
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
     export::{wasmer_import_export_kind, wasmer_import_export_value},
     instance::wasmer_instance_context_t,
     module::wasmer_module_t,
+    trap::set_host_forced_trap,
     value::wasmer_value_tag,
     wasmer_byte_array, wasmer_result_t,
 };
 use libc::c_uint;
+use std::collections::HashMap;
 use std::{
+    cell::RefCell,
     convert::TryFrom,
-    ffi::{c_void, CStr},
+    ffi::{c_void, CStr, CString},
     os::raw::c_char,
-    ptr, slice,
-    sync::Arc,
+    ptr,
     result::Result,
+    slice,
+    sync::Arc,
 };
 use wasmer_runtime::{Ctx, Global, Memory, Module, Table};
 use wasmer_runtime_core::{
     export::{Context, Export, FuncPointer},
-    import::{ImportObject, Namespace, ImportObjectIterator},
+    import::{ImportObject, ImportObjectIterator, Namespace, UnresolvedFunctionHandler},
     module::ImportName,
     types::{FuncSig, Type},
 };
-use std::{collections::HashMap};
 
 pub enum ImportError {
     ModuleNameError,
     ImportNameError,
 }
 
-pub static mut GLOBAL_IMPORT_OBJECT: *mut ImportObject = 0 as *mut ImportObject;
+/// A function called in place of the function imports which are not
+/// provided, see `wasmer_import_object_set_unresolved_import_handler()`.
+#[allow(non_camel_case_types)]
+pub type wasmer_unresolved_import_handler_t = extern "C" fn(
+    ctx: *mut wasmer_instance_context_t,
+    module_name: *const c_char,
+    import_name: *const c_char,
+);
+
+thread_local! {
+    /// The import object set by `wasmer_import_object_cache_from_imports`
+    /// on this thread.
+    static CACHED_IMPORT_OBJECT: RefCell<Option<ImportObject>> = RefCell::new(None);
+}
+
+/// Wraps a C handler of the unresolved imports into the handler called
+/// by the runtime.
+fn unresolved_function_handler(
+    handler: wasmer_unresolved_import_handler_t,
+) -> UnresolvedFunctionHandler {
+    Arc::new(move |ctx: &mut Ctx, module_name: &str, import_name: &str| {
+        // Names holding a NUL byte are passed as empty strings.
+        let module_name = CString::new(module_name).unwrap_or_default();
+        let import_name = CString::new(import_name).unwrap_or_default();
+
+        handler(
+            ctx as *mut Ctx as *mut wasmer_instance_context_t,
+            module_name.as_ptr(),
+            import_name.as_ptr(),
+        );
+    })
+}
+
+/// Returns a new reference to the import object set by
+/// `wasmer_import_object_cache_from_imports` on the current thread, or
+/// an empty import object if none was set.
+///
+/// Each instantiation owns its reference, and each thread its cached
+/// import object, so caching imports on a thread never affects the
+/// instantiations running on the others.
+pub fn cached_import_object() -> ImportObject {
+    CACHED_IMPORT_OBJECT.with(|cached| match *cached.borrow() {
+        Some(ref import_object) => import_object.clone_ref(),
+        None => ImportObject::new(),
+    })
+}
 
 #[repr(C)]
 pub struct wasmer_import_t {
@@ -71,31 +119,100 @@ pub unsafe extern "C" fn wasmer_import_object_new() -> *mut wasmer_import_object
     Box::into_raw(import_object) as *mut wasmer_import_object_t
 }
 
+/// Sets the handler of the function imports which are not provided by
+/// the import object, instead of failing the instantiations against it.
+///
+/// Each function import which is not provided is resolved to a
+/// placeholder which calls the handler with the names of the import,
+/// then traps with a message such as `called unimplemented host
+/// function env.foo`, so the instantiation only fails when the missing
+/// function is actually called.
+///
+/// The handler only applies to the instantiations against this import
+/// object, e.g. with `wasmer_module_import_instantiate()`. When it is
+/// null, these instantiations fail again on missing imports, which is
+/// the default. The handler is only supported on x86_64 unix platforms,
+/// and ignored elsewhere.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void log_unresolved_import(wasmer_instance_context_t *ctx, const char *module_name, const char *import_name) {
+///     fprintf(stderr, "unresolved import %s.%s\n", module_name, import_name);
+/// }
+///
+/// wasmer_import_object_t *import_object = wasmer_import_object_new();
+/// wasmer_import_object_set_unresolved_import_handler(import_object, log_unresolved_import);
+/// ```
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
-pub unsafe extern "C" fn wasmer_import_object_cache_from_imports(
-    imports: *mut wasmer_import_t,
-    imports_len: c_uint,
+pub unsafe extern "C" fn wasmer_import_object_set_unresolved_import_handler(
+    import_object: *mut wasmer_import_object_t,
+    handler: Option<wasmer_unresolved_import_handler_t>,
 ) -> wasmer_result_t {
-    let imports_result = wasmer_create_import_object_from_imports(imports, imports_len);
-    let import_object = match imports_result {
-        Err(ImportError::ModuleNameError) => {
-            update_last_error(CApiError { msg: "error converting module name to string".to_string() });
-            return wasmer_result_t::WASMER_ERROR;
-        }
-        Err(ImportError::ImportNameError) => {
-            update_last_error(CApiError { msg: "error converting import_name to string".to_string() });
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if import_object.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "import_object ptr is null".to_string(),
+            });
             return wasmer_result_t::WASMER_ERROR;
         }
-        Ok(created_imports_object) => created_imports_object
-    };
 
-    if GLOBAL_IMPORT_OBJECT != (0 as *mut ImportObject) {
-        let _ = Box::from_raw(GLOBAL_IMPORT_OBJECT); // deallocate previous GLOBAL_IMPORT_OBJECT
-    }
+        let import_object = &mut *(import_object as *mut ImportObject);
+        import_object.unresolved_function_handler = handler.map(unresolved_function_handler);
 
-    GLOBAL_IMPORT_OBJECT = Box::into_raw(Box::new(import_object));
-    return wasmer_result_t::WASMER_OK
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Caches the given imports for the later instantiations made on the
+/// current thread which take no imports, e.g.
+/// `wasmer_instantiate_with_options()` or `wasmer_instance_from_cache()`.
+///
+/// The cache is per thread: each thread instantiating modules must
+/// cache its own imports, and replacing them on a thread does not affect
+/// the instantiations made on the others. The instances already created
+/// keep the imports they were given.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_import_object_cache_from_imports(
+    imports: *mut wasmer_import_t,
+    imports_len: c_uint,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let imports_result = wasmer_create_import_object_from_imports(imports, imports_len);
+        let import_object = match imports_result {
+            Err(ImportError::ModuleNameError) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "error converting module name to string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+            Err(ImportError::ImportNameError) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "error converting import_name to string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+            Ok(created_imports_object) => created_imports_object,
+        };
+
+        CACHED_IMPORT_OBJECT.with(|cached| *cached.borrow_mut() = Some(import_object));
+        return wasmer_result_t::WASMER_OK;
+    })
 }
 
 /// Assembles an ImportObject from a list of imports received on the C API
@@ -117,7 +234,7 @@ pub unsafe fn wasmer_create_import_object_from_imports(
         let module_name = if let Ok(s) = std::str::from_utf8(module_name) {
             s
         } else {
-            return Err(ImportError::ModuleNameError)
+            return Err(ImportError::ModuleNameError);
         };
         let import_name = slice::from_raw_parts(
             import.import_name.bytes,
@@ -126,7 +243,7 @@ pub unsafe fn wasmer_create_import_object_from_imports(
         let import_name = if let Ok(s) = std::str::from_utf8(import_name) {
             s
         } else {
-            return Err(ImportError::ImportNameError)
+            return Err(ImportError::ImportNameError);
         };
 
         let namespace = namespaces.entry(module_name).or_insert_with(Namespace::new);
@@ -155,7 +272,7 @@ pub unsafe fn wasmer_create_import_object_from_imports(
     for (module_name, namespace) in namespaces.into_iter() {
         import_object.register(module_name, namespace);
     }
-    
+
     Ok(import_object)
 }
 
@@ -186,100 +303,111 @@ pub unsafe extern "C" fn wasmer_import_object_get_import(
     import_export_value: *mut wasmer_import_export_value,
     tag: u32,
 ) -> wasmer_result_t {
-    let tag: wasmer_import_export_kind = if let Ok(t) = TryFrom::try_from(tag) {
-        t
-    } else {
-        update_last_error(CApiError {
-            msg: "wasmer_import_export_tag out of range".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    };
-    let import_object: &mut ImportObject = &mut *(import_object as *mut ImportObject);
-    let namespace_str = if let Ok(ns) = namespace.as_str() {
-        ns
-    } else {
-        update_last_error(CApiError {
-            msg: "error converting namespace to UTF-8 string".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    };
-    let name_str = if let Ok(name) = name.as_str() {
-        name
-    } else {
-        update_last_error(CApiError {
-            msg: "error converting name to UTF-8 string".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    };
-    if import.is_null() || import_export_value.is_null() {
-        update_last_error(CApiError {
-            msg: "pointers to import and import_export_value must not be null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-    let import_out = &mut *import;
-    let import_export_value_out = &mut *import_export_value;
-    if let Some(export) =
-        import_object.maybe_with_namespace(namespace_str, |ns| ns.get_export(name_str))
-    {
-        match export {
-            Export::Function { .. } => {
-                if tag != wasmer_import_export_kind::WASM_FUNCTION {
-                    update_last_error(CApiError {
-                        msg: format!("Found function, expected {}", tag.to_str()),
-                    });
-                    return wasmer_result_t::WASMER_ERROR;
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let tag: wasmer_import_export_kind = if let Ok(t) = TryFrom::try_from(tag) {
+            t
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "wasmer_import_export_tag out of range".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        };
+        let import_object: &mut ImportObject = &mut *(import_object as *mut ImportObject);
+        let namespace_str = if let Ok(ns) = namespace.as_str() {
+            ns
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                msg: "error converting namespace to UTF-8 string".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        };
+        let name_str = if let Ok(name) = name.as_str() {
+            name
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                msg: "error converting name to UTF-8 string".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        };
+        if import.is_null() || import_export_value.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "pointers to import and import_export_value must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+        let import_out = &mut *import;
+        let import_export_value_out = &mut *import_export_value;
+        if let Some(export) =
+            import_object.maybe_with_namespace(namespace_str, |ns| ns.get_export(name_str))
+        {
+            match export {
+                Export::Function { .. } => {
+                    if tag != wasmer_import_export_kind::WASM_FUNCTION {
+                        update_last_error(CApiError {
+                            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                            msg: format!("Found function, expected {}", tag.to_str()),
+                        });
+                        return wasmer_result_t::WASMER_ERROR;
+                    }
+                    import_out.tag = wasmer_import_export_kind::WASM_FUNCTION;
+                    let writer = import_export_value_out.func as *mut Export;
+                    *writer = export.clone();
                 }
-                import_out.tag = wasmer_import_export_kind::WASM_FUNCTION;
-                let writer = import_export_value_out.func as *mut Export;
-                *writer = export.clone();
-            }
-            Export::Memory(memory) => {
-                if tag != wasmer_import_export_kind::WASM_MEMORY {
-                    update_last_error(CApiError {
-                        msg: format!("Found memory, expected {}", tag.to_str()),
-                    });
-                    return wasmer_result_t::WASMER_ERROR;
+                Export::Memory(memory) => {
+                    if tag != wasmer_import_export_kind::WASM_MEMORY {
+                        update_last_error(CApiError {
+                            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                            msg: format!("Found memory, expected {}", tag.to_str()),
+                        });
+                        return wasmer_result_t::WASMER_ERROR;
+                    }
+                    import_out.tag = wasmer_import_export_kind::WASM_MEMORY;
+                    let writer = import_export_value_out.func as *mut Memory;
+                    *writer = memory.clone();
                 }
-                import_out.tag = wasmer_import_export_kind::WASM_MEMORY;
-                let writer = import_export_value_out.func as *mut Memory;
-                *writer = memory.clone();
-            }
-            Export::Table(table) => {
-                if tag != wasmer_import_export_kind::WASM_TABLE {
-                    update_last_error(CApiError {
-                        msg: format!("Found table, expected {}", tag.to_str()),
-                    });
-                    return wasmer_result_t::WASMER_ERROR;
+                Export::Table(table) => {
+                    if tag != wasmer_import_export_kind::WASM_TABLE {
+                        update_last_error(CApiError {
+                            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                            msg: format!("Found table, expected {}", tag.to_str()),
+                        });
+                        return wasmer_result_t::WASMER_ERROR;
+                    }
+                    import_out.tag = wasmer_import_export_kind::WASM_TABLE;
+                    let writer = import_export_value_out.func as *mut Table;
+                    *writer = table.clone();
                 }
-                import_out.tag = wasmer_import_export_kind::WASM_TABLE;
-                let writer = import_export_value_out.func as *mut Table;
-                *writer = table.clone();
-            }
-            Export::Global(global) => {
-                if tag != wasmer_import_export_kind::WASM_GLOBAL {
-                    update_last_error(CApiError {
-                        msg: format!("Found global, expected {}", tag.to_str()),
-                    });
-                    return wasmer_result_t::WASMER_ERROR;
+                Export::Global(global) => {
+                    if tag != wasmer_import_export_kind::WASM_GLOBAL {
+                        update_last_error(CApiError {
+                            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                            msg: format!("Found global, expected {}", tag.to_str()),
+                        });
+                        return wasmer_result_t::WASMER_ERROR;
+                    }
+                    import_out.tag = wasmer_import_export_kind::WASM_GLOBAL;
+                    let writer = import_export_value_out.func as *mut Global;
+                    *writer = global.clone();
                 }
-                import_out.tag = wasmer_import_export_kind::WASM_GLOBAL;
-                let writer = import_export_value_out.func as *mut Global;
-                *writer = global.clone();
             }
-        }
 
-        import_out.value = *import_export_value;
-        import_out.module_name = namespace;
-        import_out.import_name = name;
+            import_out.value = *import_export_value;
+            import_out.module_name = namespace;
+            import_out.import_name = name;
 
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: format!("Export {} {} not found", namespace_str, name_str),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!("Export {} {} not found", namespace_str, name_str),
+            });
+            wasmer_result_t::WASMER_ERROR
+        }
+    })
 }
 
 /// private wrapper data type used for casting
@@ -297,6 +425,7 @@ pub unsafe extern "C" fn wasmer_import_object_iterate_functions(
 ) -> *mut wasmer_import_object_iter_t {
     if import_object.is_null() {
         update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
             msg: "import_object must not be null".to_owned(),
         });
         return std::ptr::null_mut();
@@ -324,83 +453,86 @@ pub unsafe extern "C" fn wasmer_import_object_iter_next(
     import_object_iter: *mut wasmer_import_object_iter_t,
     import: *mut wasmer_import_t,
 ) -> wasmer_result_t {
-    if import_object_iter.is_null() || import.is_null() {
-        update_last_error(CApiError {
-            msg: "import_object_iter and import must not be null".to_owned(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-
-    let iter = &mut *(import_object_iter as *mut WasmerImportObjectIterator);
-    let out = &mut *import;
-    // TODO: the copying here can be optimized away, we just need to use a different type of
-    // iterator internally
-    if let Some((namespace, name, export)) = iter.0.next() {
-        let ns = {
-            let mut n = namespace.clone();
-            n.shrink_to_fit();
-            n.into_bytes()
-        };
-        let ns_bytes = wasmer_byte_array {
-            bytes: ns.as_ptr(),
-            bytes_len: ns.len() as u32,
-        };
-
-        let name = {
-            let mut n = name.clone();
-            n.shrink_to_fit();
-            n.into_bytes()
-        };
-        let name_bytes = wasmer_byte_array {
-            bytes: name.as_ptr(),
-            bytes_len: name.len() as u32,
-        };
-
-        out.module_name = ns_bytes;
-        out.import_name = name_bytes;
-
-        std::mem::forget(ns);
-        std::mem::forget(name);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if import_object_iter.is_null() || import.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "import_object_iter and import must not be null".to_owned(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
 
-        match export {
-            Export::Function { .. } => {
-                let func = Box::new(export.clone());
+        let iter = &mut *(import_object_iter as *mut WasmerImportObjectIterator);
+        let out = &mut *import;
+        // TODO: the copying here can be optimized away, we just need to use a different type of
+        // iterator internally
+        if let Some((namespace, name, export)) = iter.0.next() {
+            let ns = {
+                let mut n = namespace.clone();
+                n.shrink_to_fit();
+                n.into_bytes()
+            };
+            let ns_bytes = wasmer_byte_array {
+                bytes: ns.as_ptr(),
+                bytes_len: ns.len() as u32,
+            };
+
+            let name = {
+                let mut n = name.clone();
+                n.shrink_to_fit();
+                n.into_bytes()
+            };
+            let name_bytes = wasmer_byte_array {
+                bytes: name.as_ptr(),
+                bytes_len: name.len() as u32,
+            };
+
+            out.module_name = ns_bytes;
+            out.import_name = name_bytes;
+
+            std::mem::forget(ns);
+            std::mem::forget(name);
+
+            match export {
+                Export::Function { .. } => {
+                    let func = Box::new(export.clone());
+
+                    out.tag = wasmer_import_export_kind::WASM_FUNCTION;
+                    out.value = wasmer_import_export_value {
+                        func: Box::into_raw(func) as *mut _ as *const _,
+                    };
+                }
+                Export::Global(global) => {
+                    let glbl = Box::new(global.clone());
 
-                out.tag = wasmer_import_export_kind::WASM_FUNCTION;
-                out.value = wasmer_import_export_value {
-                    func: Box::into_raw(func) as *mut _ as *const _,
-                };
-            }
-            Export::Global(global) => {
-                let glbl = Box::new(global.clone());
+                    out.tag = wasmer_import_export_kind::WASM_GLOBAL;
+                    out.value = wasmer_import_export_value {
+                        global: Box::into_raw(glbl) as *mut _ as *const _,
+                    };
+                }
+                Export::Memory(memory) => {
+                    let mem = Box::new(memory.clone());
 
-                out.tag = wasmer_import_export_kind::WASM_GLOBAL;
-                out.value = wasmer_import_export_value {
-                    global: Box::into_raw(glbl) as *mut _ as *const _,
-                };
-            }
-            Export::Memory(memory) => {
-                let mem = Box::new(memory.clone());
+                    out.tag = wasmer_import_export_kind::WASM_MEMORY;
+                    out.value = wasmer_import_export_value {
+                        memory: Box::into_raw(mem) as *mut _ as *const _,
+                    };
+                }
+                Export::Table(table) => {
+                    let tbl = Box::new(table.clone());
 
-                out.tag = wasmer_import_export_kind::WASM_MEMORY;
-                out.value = wasmer_import_export_value {
-                    memory: Box::into_raw(mem) as *mut _ as *const _,
-                };
+                    out.tag = wasmer_import_export_kind::WASM_TABLE;
+                    out.value = wasmer_import_export_value {
+                        memory: Box::into_raw(tbl) as *mut _ as *const _,
+                    };
+                }
             }
-            Export::Table(table) => {
-                let tbl = Box::new(table.clone());
 
-                out.tag = wasmer_import_export_kind::WASM_TABLE;
-                out.value = wasmer_import_export_value {
-                    memory: Box::into_raw(tbl) as *mut _ as *const _,
-                };
-            }
+            wasmer_result_t::WASMER_OK
+        } else {
+            wasmer_result_t::WASMER_ERROR
         }
-
-        wasmer_result_t::WASMER_OK
-    } else {
-        wasmer_result_t::WASMER_ERROR
-    }
+    })
 }
 
 /// Returns true if further calls to `wasmer_import_object_iter_next` will
@@ -411,6 +543,7 @@ pub unsafe extern "C" fn wasmer_import_object_iter_at_end(
 ) -> bool {
     if import_object_iter.is_null() {
         update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
             msg: "import_object_iter must not be null".to_owned(),
         });
         return true;
@@ -479,63 +612,104 @@ pub unsafe extern "C" fn wasmer_import_object_extend(
     imports: *const wasmer_import_t,
     imports_len: c_uint,
 ) -> wasmer_result_t {
-    let import_object: &mut ImportObject = &mut *(import_object as *mut ImportObject);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let import_object: &mut ImportObject = &mut *(import_object as *mut ImportObject);
+
+        let mut extensions: Vec<(String, String, Export)> = Vec::new();
+
+        let imports: &[wasmer_import_t] = slice::from_raw_parts(imports, imports_len as usize);
+        for import in imports {
+            let module_name = slice::from_raw_parts(
+                import.module_name.bytes,
+                import.module_name.bytes_len as usize,
+            );
+            let module_name = if let Ok(s) = std::str::from_utf8(module_name) {
+                s
+            } else {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "error converting module name to string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            };
+            let import_name = slice::from_raw_parts(
+                import.import_name.bytes,
+                import.import_name.bytes_len as usize,
+            );
+            let import_name = if let Ok(s) = std::str::from_utf8(import_name) {
+                s
+            } else {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "error converting import_name to string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            };
+
+            let export = match import.tag {
+                wasmer_import_export_kind::WASM_MEMORY => {
+                    let mem = import.value.memory as *mut Memory;
+                    Export::Memory((&*mem).clone())
+                }
+                wasmer_import_export_kind::WASM_FUNCTION => {
+                    let func_export = import.value.func as *mut Export;
+                    (&*func_export).clone()
+                }
+                wasmer_import_export_kind::WASM_GLOBAL => {
+                    let global = import.value.global as *mut Global;
+                    Export::Global((&*global).clone())
+                }
+                wasmer_import_export_kind::WASM_TABLE => {
+                    let table = import.value.table as *mut Table;
+                    Export::Table((&*table).clone())
+                }
+            };
 
-    let mut extensions: Vec<(String, String, Export)> = Vec::new();
+            let extension = (module_name.to_string(), import_name.to_string(), export);
+            extensions.push(extension)
+        }
 
-    let imports: &[wasmer_import_t] = slice::from_raw_parts(imports, imports_len as usize);
-    for import in imports {
-        let module_name = slice::from_raw_parts(
-            import.module_name.bytes,
-            import.module_name.bytes_len as usize,
-        );
-        let module_name = if let Ok(s) = std::str::from_utf8(module_name) {
-            s
-        } else {
-            update_last_error(CApiError {
-                msg: "error converting module name to string".to_string(),
-            });
-            return wasmer_result_t::WASMER_ERROR;
-        };
-        let import_name = slice::from_raw_parts(
-            import.import_name.bytes,
-            import.import_name.bytes_len as usize,
-        );
-        let import_name = if let Ok(s) = std::str::from_utf8(import_name) {
-            s
-        } else {
-            update_last_error(CApiError {
-                msg: "error converting import_name to string".to_string(),
-            });
-            return wasmer_result_t::WASMER_ERROR;
-        };
+        import_object.extend(extensions);
 
-        let export = match import.tag {
-            wasmer_import_export_kind::WASM_MEMORY => {
-                let mem = import.value.memory as *mut Memory;
-                Export::Memory((&*mem).clone())
-            }
-            wasmer_import_export_kind::WASM_FUNCTION => {
-                let func_export = import.value.func as *mut Export;
-                (&*func_export).clone()
-            }
-            wasmer_import_export_kind::WASM_GLOBAL => {
-                let global = import.value.global as *mut Global;
-                Export::Global((&*global).clone())
-            }
-            wasmer_import_export_kind::WASM_TABLE => {
-                let table = import.value.table as *mut Table;
-                Export::Table((&*table).clone())
-            }
-        };
+        return wasmer_result_t::WASMER_OK;
+    })
+}
 
-        let extension = (module_name.to_string(), import_name.to_string(), export);
-        extensions.push(extension)
+/// Registers a single host import in an existing import object, under
+/// the given module name and import name. The value is copied, so the
+/// caller keeps ownership of it.
+///
+/// The import object can then be shared by many instantiations, see
+/// `wasmer_instantiate_with_import_object()`.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_import_object_register(
+    import_object: *mut wasmer_import_object_t,
+    module_name: wasmer_byte_array,
+    import_name: wasmer_byte_array,
+    tag: wasmer_import_export_kind,
+    value: wasmer_import_export_value,
+) -> wasmer_result_t {
+    if import_object.is_null() || module_name.bytes.is_null() || import_name.bytes.is_null() {
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "import_object, module_name and import_name ptrs must not be null".to_string(),
+        });
+        return wasmer_result_t::WASMER_ERROR;
     }
+    let import = wasmer_import_t {
+        module_name,
+        import_name,
+        tag,
+        value,
+    };
 
-    import_object.extend(extensions);
-
-    return wasmer_result_t::WASMER_OK;
+    wasmer_import_object_extend(import_object, &import, 1)
 }
 
 /// Gets import descriptors for the given module
@@ -728,16 +902,19 @@ pub unsafe extern "C" fn wasmer_import_func_params_arity(
     func: *const wasmer_import_func_t,
     result: *mut u32,
 ) -> wasmer_result_t {
-    let export = &*(func as *const Export);
-    if let Export::Function { ref signature, .. } = *export {
-        *result = signature.params().len() as u32;
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "func ptr error in wasmer_import_func_params_arity".to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let export = &*(func as *const Export);
+        if let Export::Function { ref signature, .. } = *export {
+            *result = signature.params().len() as u32;
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "func ptr error in wasmer_import_func_params_arity".to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
+        }
+    })
 }
 
 /// Creates new host function, aka imported function. `func` is a
@@ -755,6 +932,12 @@ pub unsafe extern "C" fn wasmer_import_func_params_arity(
 /// The function pointer must have a lifetime greater than the
 /// WebAssembly instance lifetime.
 ///
+/// `params` and `returns` are the signature of the function. It is
+/// checked against the signature the module declares for the import
+/// when instantiating the module, so a mismatch fails the
+/// instantiation with an `Incorrect import signature` error, instead of
+/// trapping at the first call.
+///
 /// The caller owns the object and should call
 /// `wasmer_import_func_destroy` to free it.
 #[no_mangle]
@@ -789,6 +972,10 @@ pub unsafe extern "C" fn wasmer_import_func_new(
 /// function itself since the error is read outside the host function
 /// with `wasmer_last_error_message`.
 ///
+/// The trap is recorded as forced by the host, with the error code 0,
+/// see `wasmer_instance_last_trap_was_host_forced()` and
+/// `wasmer_trap_with_code()`.
+///
 /// This function returns `wasmer_result_t::WASMER_ERROR` if `ctx` or
 /// `error_message` are null.
 ///
@@ -796,12 +983,58 @@ pub unsafe extern "C" fn wasmer_import_func_new(
 #[no_mangle]
 #[allow(clippy::cast_ptr_alignment)]
 pub unsafe extern "C" fn wasmer_trap(
-    ctx: *const wasmer_instance_context_t,
+    ctx: *mut wasmer_instance_context_t,
     error_message: *const c_char,
+) -> wasmer_result_t {
+    force_trap(ctx, 0, error_message, "wasmer_trap")
+}
+
+/// Stops the execution of a host function like `wasmer_trap()`, and
+/// records `code` as the error code of the trap, so that the caller of
+/// the instance can tell why the host aborted the call, e.g. an access
+/// control rejection, from a trap of the WebAssembly code itself. See
+/// `wasmer_instance_last_host_trap_code()`.
+///
+/// The meaning of the codes is up to the embedder.
+///
+/// This function returns `wasmer_result_t::WASMER_ERROR` if `ctx` or
+/// `error_message` are null.
+///
+/// This function never returns otherwise.
+///
+/// Example:
+///
+/// ```c
+/// void storage_store(wasmer_instance_context_t *ctx, int32_t key, int32_t value) {
+///     if (!is_writable(key)) {
+///         wasmer_trap_with_code(ctx, ACCESS_DENIED, "storage key is read-only");
+///     }
+///     // …
+/// }
+/// ```
+#[no_mangle]
+#[allow(clippy::cast_ptr_alignment)]
+pub unsafe extern "C" fn wasmer_trap_with_code(
+    ctx: *mut wasmer_instance_context_t,
+    code: u32,
+    error_message: *const c_char,
+) -> wasmer_result_t {
+    force_trap(ctx, code, error_message, "wasmer_trap_with_code")
+}
+
+/// Records the host forced trap on the instance of `ctx`, and traps
+/// with `error_message`. `function_name` names the caller in the error
+/// messages.
+unsafe fn force_trap(
+    ctx: *mut wasmer_instance_context_t,
+    code: u32,
+    error_message: *const c_char,
+    function_name: &str,
 ) -> wasmer_result_t {
     if ctx.is_null() {
         update_last_error(CApiError {
-            msg: "ctx ptr is null in wasmer_trap".to_string(),
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: format!("ctx ptr is null in {}", function_name),
         });
 
         return wasmer_result_t::WASMER_ERROR;
@@ -809,14 +1042,30 @@ pub unsafe extern "C" fn wasmer_trap(
 
     if error_message.is_null() {
         update_last_error(CApiError {
-            msg: "error_message is null in wasmer_trap".to_string(),
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: format!("error_message is null in {}", function_name),
         });
 
         return wasmer_result_t::WASMER_ERROR;
     }
 
-    let ctx = &*(ctx as *const Ctx);
-    let error_message = CStr::from_ptr(error_message).to_str().unwrap();
+    let ctx = &mut *(ctx as *mut Ctx);
+    let error_message = match CStr::from_ptr(error_message).to_str() {
+        Ok(error_message) => error_message,
+        Err(_) => {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                msg: format!(
+                    "error_message is not a valid UTF-8 string in {}",
+                    function_name
+                ),
+            });
+
+            return wasmer_result_t::WASMER_ERROR;
+        }
+    };
+
+    set_host_forced_trap(ctx, code);
 
     (&*ctx.module)
         .runnable_module
@@ -845,20 +1094,23 @@ pub unsafe extern "C" fn wasmer_import_func_params(
     params: *mut wasmer_value_tag,
     params_len: c_uint,
 ) -> wasmer_result_t {
-    let export = &*(func as *const Export);
-    if let Export::Function { ref signature, .. } = *export {
-        let params: &mut [wasmer_value_tag] =
-            slice::from_raw_parts_mut(params, params_len as usize);
-        for (i, item) in signature.params().iter().enumerate() {
-            params[i] = item.into();
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let export = &*(func as *const Export);
+        if let Export::Function { ref signature, .. } = *export {
+            let params: &mut [wasmer_value_tag] =
+                slice::from_raw_parts_mut(params, params_len as usize);
+            for (i, item) in signature.params().iter().enumerate() {
+                params[i] = item.into();
+            }
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "func ptr error in wasmer_import_func_params".to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
         }
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "func ptr error in wasmer_import_func_params".to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    })
 }
 
 /// Sets the returns buffer to the parameter types of the given wasmer_import_func_t
@@ -874,20 +1126,23 @@ pub unsafe extern "C" fn wasmer_import_func_returns(
     returns: *mut wasmer_value_tag,
     returns_len: c_uint,
 ) -> wasmer_result_t {
-    let export = &*(func as *const Export);
-    if let Export::Function { ref signature, .. } = *export {
-        let returns: &mut [wasmer_value_tag] =
-            slice::from_raw_parts_mut(returns, returns_len as usize);
-        for (i, item) in signature.returns().iter().enumerate() {
-            returns[i] = item.into();
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let export = &*(func as *const Export);
+        if let Export::Function { ref signature, .. } = *export {
+            let returns: &mut [wasmer_value_tag] =
+                slice::from_raw_parts_mut(returns, returns_len as usize);
+            for (i, item) in signature.returns().iter().enumerate() {
+                returns[i] = item.into();
+            }
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "func ptr error in wasmer_import_func_returns".to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
         }
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "func ptr error in wasmer_import_func_returns".to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    })
 }
 
 /// Sets the result parameter to the arity of the returns of the wasmer_import_func_t
@@ -902,16 +1157,19 @@ pub unsafe extern "C" fn wasmer_import_func_returns_arity(
     func: *const wasmer_import_func_t,
     result: *mut u32,
 ) -> wasmer_result_t {
-    let export = &*(func as *const Export);
-    if let Export::Function { ref signature, .. } = *export {
-        *result = signature.returns().len() as u32;
-        wasmer_result_t::WASMER_OK
-    } else {
-        update_last_error(CApiError {
-            msg: "func ptr error in wasmer_import_func_results_arity".to_string(),
-        });
-        wasmer_result_t::WASMER_ERROR
-    }
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let export = &*(func as *const Export);
+        if let Export::Function { ref signature, .. } = *export {
+            *result = signature.returns().len() as u32;
+            wasmer_result_t::WASMER_OK
+        } else {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "func ptr error in wasmer_import_func_results_arity".to_string(),
+            });
+            wasmer_result_t::WASMER_ERROR
+        }
+    })
 }
 
 /// Frees memory for the given Func
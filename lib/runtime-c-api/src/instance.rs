@@ -1,31 +1,62 @@
 //! Instantiate a module, call functions, and read exports.
 
+#[cfg(feature = "metering")]
+use crate::compile_violations;
 use crate::{
-    error::{update_last_error, CApiError},
+    allocator::into_c_buffer,
+    error::{
+        catch_panic, error_code, update_last_error, wasmer_error_code_t, CApiError, CausedError,
+    },
     export::{wasmer_exports_t, wasmer_import_export_kind, NamedExport, NamedExports},
-    import::{wasmer_import_t, GLOBAL_IMPORT_OBJECT},
+    import::{
+        cached_import_object, wasmer_create_import_object_from_imports, wasmer_import_t,
+        ImportError,
+    },
     memory::wasmer_memory_t,
+    module::wasmer_module_t,
+    opcode_trace::reset_opcode_trace_state,
+    trap,
     value::{wasmer_value, wasmer_value_t, wasmer_value_tag},
     wasmer_result_t,
 };
 use libc::{c_char, c_int, c_void};
-use std::{collections::HashMap, ffi::CStr, ptr, slice};
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    ffi::{CStr, CString},
+    fmt, mem, ptr, slice, str,
+    time::{Duration, Instant},
+};
 use wasmer_runtime::{Ctx, Global, Instance, Memory, Table, Value};
 use wasmer_runtime_core::{
+    cache::WasmHash,
+    error::{CallError, CallResult, RuntimeError},
     export::Export,
     import::{ImportObject, Namespace},
+    structures::TypedIndex,
+    types::{FuncIndex, Type},
+    units::{Bytes, Pages, WASM_PAGE_SIZE},
 };
 
 use crate::metering::OPCODE_COSTS;
-use wasmer_runtime_core::backend::Compiler;
+use wasmer_runtime_core::backend::{Compiler, CompilerConfig, ExceptionCode, Features};
 use wasmer_runtime_core::codegen::{MiddlewareChain, StreamingCompiler};
 
 #[cfg(not(feature = "cranelift-backend"))]
 use wasmer_middleware_common::metering;
+#[cfg(feature = "metering")]
+use wasmer_middleware_common::metering_costs;
 
+use wasmer_middleware_common::block_coverage;
+use wasmer_middleware_common::call_depth;
+use wasmer_middleware_common::compile_deadline;
+use wasmer_middleware_common::deterministic_float;
+use wasmer_middleware_common::import_call_trace;
 use wasmer_middleware_common::opcode_control::{self, reset_memory_grow_count};
+use wasmer_middleware_common::opcode_denylist;
 use wasmer_middleware_common::opcode_trace;
 use wasmer_middleware_common::runtime_breakpoints;
+use wasmer_middleware_common::stack_height;
 
 /// Opaque pointer to a `wasmer_runtime::Instance` value in Rust.
 ///
@@ -75,6 +106,8 @@ pub struct wasmer_instance_context_t;
 /// The caller is responsible to free the instance with
 /// `wasmer_instance_destroy()`.
 ///
+/// `imports` may be a null pointer when `imports_len` is 0.
+///
 /// Example:
 ///
 /// ```c
@@ -114,13 +147,47 @@ pub unsafe extern "C" fn wasmer_instantiate(
     imports: *mut wasmer_import_t,
     imports_len: c_int,
 ) -> wasmer_result_t {
-    if wasm_bytes.is_null() {
-        update_last_error(CApiError {
-            msg: "wasm bytes ptr is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-    let imports: &[wasmer_import_t] = slice::from_raw_parts(imports, imports_len as usize);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if wasm_bytes.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "wasm bytes ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+        // Many modules import nothing: spare them the namespaces.
+        let import_object = if imports_len == 0 {
+            ImportObject::new()
+        } else {
+            let imports: &[wasmer_import_t] = slice::from_raw_parts(imports, imports_len as usize);
+            match import_object_from_imports(imports) {
+                Ok(import_object) => import_object,
+                Err(error) => {
+                    update_last_error(error);
+                    return wasmer_result_t::WASMER_ERROR;
+                }
+            }
+        };
+
+        let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
+        let result = wasmer_runtime::instantiate(bytes, &import_object);
+        let new_instance = match result {
+            Ok(instance) => instance,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Builds the import object of the given imports, grouping them by
+/// namespace.
+unsafe fn import_object_from_imports(
+    imports: &[wasmer_import_t],
+) -> Result<ImportObject, CApiError> {
     let mut import_object = ImportObject::new();
     let mut namespaces = HashMap::new();
     for import in imports {
@@ -131,10 +198,10 @@ pub unsafe extern "C" fn wasmer_instantiate(
         let module_name = if let Ok(s) = std::str::from_utf8(module_name) {
             s
         } else {
-            update_last_error(CApiError {
+            return Err(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
                 msg: "error converting module name to string".to_string(),
             });
-            return wasmer_result_t::WASMER_ERROR;
         };
         let import_name = slice::from_raw_parts(
             import.import_name.bytes,
@@ -143,10 +210,10 @@ pub unsafe extern "C" fn wasmer_instantiate(
         let import_name = if let Ok(s) = std::str::from_utf8(import_name) {
             s
         } else {
-            update_last_error(CApiError {
+            return Err(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
                 msg: "error converting import_name to string".to_string(),
             });
-            return wasmer_result_t::WASMER_ERROR;
         };
 
         let namespace = namespaces.entry(module_name).or_insert_with(Namespace::new);
@@ -176,429 +243,3316 @@ pub unsafe extern "C" fn wasmer_instantiate(
         import_object.register(module_name, namespace);
     }
 
-    let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
-    let result = wasmer_runtime::instantiate(bytes, &import_object);
-    let new_instance = match result {
-        Ok(instance) => instance,
-        Err(error) => {
-            update_last_error(error);
-            return wasmer_result_t::WASMER_ERROR;
-        }
-    };
-    *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
-    wasmer_result_t::WASMER_OK
+    Ok(import_object)
 }
 
 #[repr(C)]
 pub struct wasmer_import_object_t;
 
+/// The `wasmer_compilation_options_t` struct holds the options of the
+/// compilation and the instantiation of a module, e.g. for
+/// `wasmer_instantiate_with_options()`.
+///
+/// `struct_size` must be set to `sizeof(wasmer_compilation_options_t)`.
+/// The fields past `struct_size` are read as zeroes, so that a caller
+/// built against an older `wasmer.h` keeps working when fields are
+/// appended; new fields are only ever appended. Every option is
+/// disabled when its field is zero, so a zero-initialized struct with
+/// its `struct_size` set holds the default options, except for the
+/// `memory.grow` limits, which deny any growth when zero:
+///
+/// ```c
+/// wasmer_compilation_options_t options = { .struct_size = sizeof(options) };
+/// options.metering = true;
+/// options.gas_limit = 1000000;
+/// ```
 #[repr(C)]
-pub struct wasmer_compilation_options_t;
+#[derive(Clone, Copy)]
+pub struct wasmer_compilation_options_t {
+    /// The size of the struct as known by the caller.
+    pub struct_size: u32,
 
-pub struct CompilationOptions {
+    /// The gas limit of the instance.
     pub gas_limit: u64,
+
+    /// The number of locals of a function which are not charged.
     pub unmetered_locals: usize,
+
+    /// The maximum number of `memory.grow` executed by the instance.
     pub max_memory_grow: usize,
+
+    /// The maximum number of pages added by a single `memory.grow`.
     pub max_memory_grow_delta: usize,
+
+    /// Records the location of the last executed opcode, see
+    /// `wasmer_instance_get_last_opcode_location()`.
     pub opcode_trace: bool,
+
+    /// Meters the gas used by the instance, with the opcode costs set
+    /// by `wasmer_set_opcode_costs()`.
     pub metering: bool,
+
+    /// Lets the instance stop at runtime breakpoints, e.g. when it runs
+    /// out of gas.
     pub runtime_breakpoints: bool,
-}
 
-#[allow(clippy::cast_ptr_alignment)]
-#[cfg(feature = "metering")]
-#[no_mangle]
-pub unsafe extern "C" fn wasmer_instantiate_with_options(
-    instance: *mut *mut wasmer_instance_t,
-    wasm_bytes: *mut u8,
-    wasm_bytes_len: u32,
-    options: *const wasmer_compilation_options_t,
-) -> wasmer_result_t {
-    if wasm_bytes.is_null() {
-        update_last_error(CApiError {
-            msg: "wasm bytes ptr is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
+    /// Records the executed opcodes, see
+    /// `wasmer_instance_get_opcode_trace()`.
+    pub collect_opcode_trace: bool,
 
-    let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
-    let options: &CompilationOptions = &*(options as *const CompilationOptions);
-    let compiler_chain_generator = prepare_middleware_chain_generator(&options);
-    let compiler = get_compiler(compiler_chain_generator);
-    let result_compilation = wasmer_runtime_core::compile_with(bytes, &compiler);
-    let new_module = match result_compilation {
-        Ok(module) => module,
-        Err(_) => {
-            update_last_error(CApiError {
-                msg: "compile error".to_string(),
-            });
-            return wasmer_result_t::WASMER_ERROR;
-        }
-    };
+    /// Accepts the bulk memory operations.
+    pub enable_bulk_memory: bool,
 
-    let import_object: &mut ImportObject = &mut *(GLOBAL_IMPORT_OBJECT as *mut ImportObject);
-    let result_instantiation = new_module.instantiate(&import_object);
-    let mut new_instance = match result_instantiation {
-        Ok(instance) => instance,
-        Err(error) => {
-            update_last_error(error);
-            return wasmer_result_t::WASMER_ERROR;
-        }
-    };
-    metering::set_points_limit(&mut new_instance, options.gas_limit);
-    *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
-    wasmer_result_t::WASMER_OK
-}
+    /// Accepts the reference types.
+    pub enable_reference_types: bool,
 
-/// Reset an WebAssembly instance, cleaning memories and globals
-#[allow(clippy::cast_ptr_alignment)]
-#[no_mangle]
-pub unsafe extern "C" fn wasmer_instance_reset(
-    instance: *mut wasmer_instance_t,
-) -> wasmer_result_t {
-    if instance.is_null() {
-        update_last_error(CApiError {
-            msg: "null instance".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
+    /// Accepts the SIMD operations.
+    pub enable_simd: bool,
 
-    let instance = &mut *(instance as *mut Instance);
+    /// Accepts the threads operations.
+    pub enable_threads: bool,
 
-    if let Err(error) = instance.reset() {
-        update_last_error(CApiError {
-            msg: error.to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
+    /// The image written at `initial_memory_offset` into the memory of
+    /// the instance once it is created, or a null pointer.
+    pub initial_memory: *const u8,
 
-    reset_memory_grow_count(instance);
+    /// The length of `initial_memory`.
+    pub initial_memory_len: u32,
 
-    wasmer_result_t::WASMER_OK
-}
+    /// The offset of `initial_memory` in the memory.
+    pub initial_memory_offset: u32,
 
-pub unsafe fn prepare_middleware_chain_generator(
-    options: &CompilationOptions,
-) -> impl Fn() -> MiddlewareChain + '_ {
-    let options = options.clone();
+    /// The gas charged for each byte of `initial_memory`.
+    pub initial_memory_gas_per_byte: u64,
 
-    let chain_generator = move || {
-        let mut chain = MiddlewareChain::new();
+    /// Rejects the modules with a start function.
+    pub reject_start_function: bool,
 
-        if options.metering {
-            #[cfg(feature = "metering")]
-            chain.push(metering::Metering::new(
-                &OPCODE_COSTS,
-                options.unmetered_locals,
-            ));
-        }
+    /// Removes the custom sections before the compilation.
+    pub strip_custom_sections: bool,
 
-        chain.push(opcode_control::OpcodeControl::new(
-            options.max_memory_grow,
-            options.max_memory_grow_delta,
-        ));
+    /// The maximum initial size of the memory in pages, 0 meaning no
+    /// maximum.
+    pub max_initial_memory_pages: u32,
 
-        // The RuntimeBreakpointHandler must be the last middleware in the chain (OpcodeTracer is
-        // an exception since it does not alter the opcodes meaningfully.
-        if options.runtime_breakpoints {
-            chain.push(runtime_breakpoints::RuntimeBreakpointHandler::new());
-        }
+    /// Charges the whole gas limit when the instance runs out of gas.
+    pub charge_full_on_oog: bool,
 
-        if options.opcode_trace {
-            chain.push(opcode_trace::OpcodeTracer::new());
-        };
+    /// Traps when the stack height of the instance exceeds
+    /// `max_stack_height`, with the runtime breakpoint value
+    /// `BREAKPOINT_VALUE_STACK_LIMIT`. The frame of a function takes
+    /// one slot, plus one per parameter and local. No gas is charged
+    /// for the frames: the `metering` option charges the locals of each
+    /// function, beyond `unmetered_locals`, as their allocation.
+    pub meter_stack_height: bool,
 
-        chain
-    };
+    /// The maximum stack height, in slots, see `meter_stack_height`.
+    /// 0 means no limit.
+    pub max_stack_height: u64,
 
-    chain_generator
-}
+    /// The codes of the opcodes rejected at compilation, or a null
+    /// pointer, see `wasmer_opcode_name()`.
+    pub denied_opcodes: *const u16,
 
-pub unsafe fn get_compiler(chain_generator: impl Fn() -> MiddlewareChain) -> impl Compiler {
-    #[cfg(feature = "llvm-backend")]
-    use wasmer_llvm_backend::ModuleCodeGenerator as MeteredMCG;
+    /// The length of `denied_opcodes`.
+    pub denied_opcodes_len: u32,
 
-    #[cfg(feature = "singlepass-backend")]
-    use wasmer_singlepass_backend::ModuleCodeGenerator as MeteredMCG;
+    /// Does not run the start function at instantiation, see
+    /// `wasmer_instance_run_start()`.
+    pub skip_start: bool,
 
-    #[cfg(feature = "cranelift-backend")]
-    use wasmer_clif_backend::CraneliftModuleCodeGenerator as MeteredMCG;
+    /// The maximum number of instructions executed by a call, 0 meaning
+    /// no maximum.
+    pub hard_instruction_limit: u64,
 
-    let compiler: StreamingCompiler<MeteredMCG, _, _, _, _> =
-        StreamingCompiler::new(chain_generator);
-    compiler
-}
+    /// The maximum duration of the compilation in milliseconds, 0
+    /// meaning no maximum.
+    pub compile_timeout_ms: u64,
 
-/// Returns the instance context. Learn more by looking at the
-/// `wasmer_instance_context_t` struct.
-///
-/// This function returns `null` if `instance` is a null pointer.
-///
-/// Example:
-///
-/// ```c
-/// const wasmer_instance_context_get *context = wasmer_instance_context_get(instance);
-/// my_data *data = (my_data *) wasmer_instance_context_data_get(context);
-/// // Do something with `my_data`.
-/// ```
-///
-/// It is often useful with `wasmer_instance_context_data_set()`.
-#[allow(clippy::cast_ptr_alignment)]
-#[no_mangle]
-pub extern "C" fn wasmer_instance_context_get(
-    instance: *mut wasmer_instance_t,
-) -> *const wasmer_instance_context_t {
-    if instance.is_null() {
-        return ptr::null() as _;
-    }
+    /// The index of the first function traced by the opcode trace.
+    pub trace_function_start: u32,
 
-    let instance = unsafe { &*(instance as *const Instance) };
-    let context: *const Ctx = instance.context() as *const _;
+    /// The index past the last function traced by the opcode trace, 0
+    /// meaning no bound.
+    pub trace_function_end: u32,
 
-    context as *const wasmer_instance_context_t
+    /// Reports the entries of the basic blocks, see
+    /// `wasmer_instance_set_block_coverage_callback()`.
+    pub block_coverage: bool,
+
+    /// Breaks the gas used down by opcode category, see
+    /// `wasmer_instance_get_gas_breakdown()`.
+    pub gas_breakdown: bool,
+
+    /// The maximum number of elements of the table, 0 meaning no
+    /// maximum, see `wasmer_instance_table_grow()`. The singlepass
+    /// backend does not compile `table.grow`, so the modules growing
+    /// their tables themselves are rejected anyway.
+    pub max_table_elements: u32,
+
+    /// Records the gas used by each function, see
+    /// `wasmer_instance_get_per_function_gas()`.
+    pub per_function_gas: bool,
+
+    /// Skips the validation of bytes already validated, see
+    /// `wasmer_validate()`.
+    pub already_validated: bool,
+
+    /// Records the kind of each instruction which may trap, see
+    /// `wasmer_trap_code_t`.
+    pub deterministic_traps: bool,
+
+    /// Rejects a metered instance when one of the opcodes which let the
+    /// code run again, i.e. `loop`, `br`, `br_if`, `br_table`, `call`
+    /// and `call_indirect`, costs nothing, since the gas limit would not
+    /// bound its execution, see `wasmer_check_opcode_costs()`.
+    pub require_loop_costs: bool,
+
+    /// Reports the calls of imported functions, see
+    /// `wasmer_instance_set_import_call_callback()`.
+    pub trace_import_calls: bool,
+
+    /// The maximum number of nested calls of wasm functions, 0 meaning
+    /// no maximum. A call going deeper traps with the runtime breakpoint
+    /// value `BREAKPOINT_VALUE_CALL_DEPTH_LIMIT` and returns
+    /// `WASMER_STACK_OVERFLOW`. Unlike the size of the native stack, the
+    /// depth does not depend on the machine, so that every machine traps
+    /// at the same call.
+    pub max_call_depth: u32,
+
+    /// Replaces the NaN results of `nearest`, `trunc`, `floor`, `ceil` and
+    /// `sqrt` on f32 and f64 with the canonical NaN, so that these
+    /// operators give the same bits on every backend and CPU: their other
+    /// results are exact. The runtime built with the default
+    /// `deterministic-execution` feature rejects the float operators
+    /// anyway.
+    pub deterministic_float: bool,
 }
 
-/// Verifies whether the specified function name is imported by the given instance.
-#[allow(clippy::cast_ptr_alignment)]
-#[no_mangle]
-pub unsafe extern "C" fn wasmer_instance_is_function_imported(
-    instance: *mut wasmer_instance_t,
-    name: *const c_char,
-) -> bool {
-    if instance.is_null() {
-        return false;
+/// Reads the options given by a caller, which may be built against an
+/// older `wasmer.h`, see `wasmer_compilation_options_t`.
+pub(crate) unsafe fn read_compilation_options(
+    options: *const wasmer_compilation_options_t,
+) -> Result<wasmer_compilation_options_t, CApiError> {
+    if options.is_null() {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "options ptr is null".to_string(),
+        });
     }
 
-    if name.is_null() {
-        return false;
+    let struct_size = (*options).struct_size as usize;
+    if struct_size < mem::size_of::<u32>()
+        || struct_size > mem::size_of::<wasmer_compilation_options_t>()
+    {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: format!(
+                "invalid options struct_size {}, expected sizeof(wasmer_compilation_options_t) = {}",
+                struct_size,
+                mem::size_of::<wasmer_compilation_options_t>()
+            ),
+        });
     }
 
-    let instance = &*(instance as *const Instance);
-
-    let func_name_c = CStr::from_ptr(name);
-    let func_name_r = func_name_c.to_str().unwrap();
-
-    let module = instance.module();
+    let mut read_options: wasmer_compilation_options_t = mem::zeroed();
+    ptr::copy_nonoverlapping(
+        options as *const u8,
+        &mut read_options as *mut wasmer_compilation_options_t as *mut u8,
+        struct_size,
+    );
 
-    let functions = module.info().name_table.to_vec();
+    Ok(read_options)
+}
 
-    functions.contains(&func_name_r)
+/// Compiles and instantiates WebAssembly bytes with the given
+/// compilation options, see `wasmer_instantiate_with_options_and_gas()`.
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instantiate_with_options(
+    instance: *mut *mut wasmer_instance_t,
+    wasm_bytes: *mut u8,
+    wasm_bytes_len: u32,
+    options: *const wasmer_compilation_options_t,
+) -> wasmer_result_t {
+    wasmer_instantiate_with_options_and_gas(
+        instance,
+        wasm_bytes,
+        wasm_bytes_len,
+        options,
+        ptr::null_mut(),
+    )
 }
 
-/// Calls an exported function of a WebAssembly instance by `name`
-/// with the provided parameters. The exported function results are
-/// stored on the provided `results` pointer.
-///
-/// This function returns `wasmer_result_t::WASMER_OK` upon success,
-/// `wasmer_result_t::WASMER_ERROR` otherwise. You can use
-/// `wasmer_last_error_message()` to get the generated error message.
+/// Compiles and instantiates WebAssembly bytes with the given
+/// compilation options, and stores the gas consumed by the
+/// instantiation in `gas_used`, unless it is a null pointer.
 ///
-/// Potential errors are the following:
+/// The imports of the module are resolved against the ones cached on
+/// the current thread by `wasmer_import_object_cache_from_imports()`.
 ///
-///   * `instance` is a null pointer,
-///   * `name` is a null pointer,
-///   * `params` is a null pointer.
+/// The start function of the module, if any, runs with the
+/// `gas_limit` of the options, and its gas is charged like the gas of
+/// any call. The gas charged for the initial memory image of the
+/// options is added to it, and the total is the gas used by the
+/// instance once it is returned.
 ///
-/// Example of calling an exported function that needs two parameters, and returns one value:
+/// The start function is not run if the `skip_start` option is set;
+/// run it later with `wasmer_instance_run_start()`.
 ///
-/// ```c
-/// // First argument.
-/// wasmer_value_t argument_one = {
-///     .tag = WASM_I32,
-///     .value.I32 = 3,
-/// };
+/// If the start function runs out of gas, no instance is created,
+/// `gas_used` still receives the gas consumed, and this function
+/// returns `wasmer_result_t::WASMER_OUT_OF_GAS`, with the error message
+/// `out of gas`.
 ///
-/// // Second argument.
-/// wasmer_value_t argument_two = {
-///     .tag = WASM_I32,
-///     .value.I32 = 4,
-/// };
+/// If the initial memory image does not fit in the memory, or in the
+/// gas left by the start function, no instance is created either, and
+/// this function returns `wasmer_result_t::WASMER_ERROR` or
+/// `wasmer_result_t::WASMER_OUT_OF_GAS` respectively.
 ///
-/// // First result.
-/// wasmer_value_t result_one;
+/// If the compilation takes longer than the `compile_timeout_ms`
+/// option, 0 meaning no timeout, it is aborted and no instance is
+/// created. The partially compiled code is freed, and this function
+/// returns `wasmer_result_t::WASMER_TIMEOUT`.
 ///
-/// // All arguments and results.
-/// wasmer_value_t arguments[] = {argument_one, argument_two};
-/// wasmer_value_t results[]   = {result_one};
+/// The `already_validated` option skips the validation of the bytes
+/// before their compilation, for the bytes already validated, e.g. with
+/// `wasmer_validate()`, and loaded again from a trusted store. It is
+/// unsafe for untrusted bytes, and must never be set for them.
 ///
-/// wasmer_result_t call_result = wasmer_instance_call(
-///     instance,  // instance pointer
-///     "sum",     // the exported function name
-///     arguments, // the arguments
-///     2,         // the number of arguments
-///     results,   // the results
-///     1          // the number of results
-/// );
+/// The `deterministic_traps` option records the kind of each
+/// instruction which may trap, so that the traps of the instance report
+/// the same code on every backend, see `wasmer_trap_code_t`. It makes
+/// the compilation slower and the module bigger.
 ///
-/// if (call_result == WASMER_OK) {
-///     printf("Result is: %d\n", results[0].value.I32);
-/// }
-/// ```
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
 #[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
 #[no_mangle]
-pub unsafe extern "C" fn wasmer_instance_call(
-    instance: *mut wasmer_instance_t,
-    name: *const c_char,
-    params: *const wasmer_value_t,
-    params_len: u32,
-    results: *mut wasmer_value_t,
-    results_len: u32,
+pub unsafe extern "C" fn wasmer_instantiate_with_options_and_gas(
+    instance: *mut *mut wasmer_instance_t,
+    wasm_bytes: *mut u8,
+    wasm_bytes_len: u32,
+    options: *const wasmer_compilation_options_t,
+    gas_used: *mut u64,
 ) -> wasmer_result_t {
-    if instance.is_null() {
-        update_last_error(CApiError {
-            msg: "instance ptr is null".to_string(),
-        });
-
-        return wasmer_result_t::WASMER_ERROR;
-    }
-
-    if name.is_null() {
-        update_last_error(CApiError {
-            msg: "name ptr is null".to_string(),
-        });
-
-        return wasmer_result_t::WASMER_ERROR;
-    }
-
-    if params.is_null() {
-        update_last_error(CApiError {
-            msg: "params ptr is null".to_string(),
-        });
-
-        return wasmer_result_t::WASMER_ERROR;
-    }
+    wasmer_instantiate_with_options_and_stats(
+        instance,
+        wasm_bytes,
+        wasm_bytes_len,
+        options,
+        gas_used,
+        ptr::null_mut(),
+    )
+}
 
-    let params: &[wasmer_value_t] = slice::from_raw_parts(params, params_len as usize);
-    let params: Vec<Value> = params.iter().cloned().map(|x| x.into()).collect();
+/// The `wasmer_compile_stats_t` struct receives statistics about the
+/// compilation and the instantiation of a module, see
+/// `wasmer_instantiate_with_options_and_stats()`.
+#[repr(C)]
+pub struct wasmer_compile_stats_t {
+    /// The time spent compiling the module, in nanoseconds.
+    pub compile_nanos: u64,
 
-    let func_name_c = CStr::from_ptr(name);
-    let func_name_r = func_name_c.to_str().unwrap();
+    /// The time spent instantiating the module, including its start
+    /// function and its initial memory image, in nanoseconds.
+    pub instantiate_nanos: u64,
 
-    let results: &mut [wasmer_value_t] = slice::from_raw_parts_mut(results, results_len as usize);
-    let instance = &mut *(instance as *mut Instance);
+    /// The size in bytes of the machine code generated for the module,
+    /// or 0 if the backend does not expose its code.
+    pub code_size: u64,
 
-    wasmer_middleware_common::opcode_trace::reset_opcodetracer_last_location(instance);
-    let result = instance.call(func_name_r, &params[..]);
+    /// The number of functions compiled, i.e. the functions defined by
+    /// the module, imported functions excluded.
+    pub function_count: u32,
+}
 
-    let result = match result {
-        Ok(results_vec) => {
-            if !results_vec.is_empty() {
-                let ret = match results_vec[0] {
-                    Value::I32(x) => wasmer_value_t {
-                        tag: wasmer_value_tag::WASM_I32,
-                        value: wasmer_value { I32: x },
-                    },
-                    Value::I64(x) => wasmer_value_t {
-                        tag: wasmer_value_tag::WASM_I64,
-                        value: wasmer_value { I64: x },
-                    },
-                    Value::F32(x) => wasmer_value_t {
-                        tag: wasmer_value_tag::WASM_F32,
-                        value: wasmer_value { F32: x },
-                    },
-                    Value::F64(x) => wasmer_value_t {
-                        tag: wasmer_value_tag::WASM_F64,
-                        value: wasmer_value { F64: x },
-                    },
-                    Value::V128(_) => unimplemented!("calling function with V128 parameter"),
-                };
-                results[0] = ret;
+/// Compiles and instantiates WebAssembly bytes like
+/// `wasmer_instantiate_with_options_and_gas()`, and stores statistics
+/// about both phases in `stats`, unless it is a null pointer.
+///
+/// The compilation statistics are stored once the module is compiled,
+/// even if the instantiation fails then; `instantiate_nanos` is only
+/// set upon success. Nothing is measured when `stats` is a null
+/// pointer.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_compile_stats_t stats;
+/// wasmer_result_t result = wasmer_instantiate_with_options_and_stats(&instance, bytes, bytes_len, options, NULL, &stats);
+///
+/// if (result == WASMER_OK) {
+///     printf("Compiled %u functions in %llu ns\n", stats.function_count, stats.compile_nanos);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instantiate_with_options_and_stats(
+    instance: *mut *mut wasmer_instance_t,
+    wasm_bytes: *mut u8,
+    wasm_bytes_len: u32,
+    options: *const wasmer_compilation_options_t,
+    gas_used: *mut u64,
+    stats: *mut wasmer_compile_stats_t,
+) -> wasmer_result_t {
+    instantiate_with_options(
+        instance,
+        wasm_bytes,
+        wasm_bytes_len,
+        &cached_import_object(),
+        options,
+        gas_used,
+        stats,
+    )
+}
+
+/// Compiles and instantiates WebAssembly bytes with the given
+/// compilation options, resolving the imports of the module against
+/// the given import object instead of the cached one.
+///
+/// The import object is only borrowed during the instantiation: it can
+/// be shared by any number of instantiations, and each instance keeps
+/// its own reference to the imports, so the import object can be freed
+/// with `wasmer_import_object_destroy` while the instances created from
+/// it are still in use. It is still owned by the caller. See also
+/// `wasmer_import_object_register()`.
+///
+/// The gas semantics are the ones of
+/// `wasmer_instantiate_with_options_and_gas()`.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instantiate_with_import_object(
+    instance: *mut *mut wasmer_instance_t,
+    wasm_bytes: *mut u8,
+    wasm_bytes_len: u32,
+    import_object: *const crate::import::wasmer_import_object_t,
+    options: *const wasmer_compilation_options_t,
+) -> wasmer_result_t {
+    if import_object.is_null() {
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "import_object ptr is null".to_string(),
+        });
+        return wasmer_result_t::WASMER_ERROR;
+    }
+    let import_object: &ImportObject = &*(import_object as *const ImportObject);
+
+    instantiate_with_options(
+        instance,
+        wasm_bytes,
+        wasm_bytes_len,
+        import_object,
+        options,
+        ptr::null_mut(),
+        ptr::null_mut(),
+    )
+}
+
+#[cfg(feature = "metering")]
+unsafe fn instantiate_with_options(
+    instance: *mut *mut wasmer_instance_t,
+    wasm_bytes: *mut u8,
+    wasm_bytes_len: u32,
+    import_object: &ImportObject,
+    options: *const wasmer_compilation_options_t,
+    gas_used: *mut u64,
+    stats: *mut wasmer_compile_stats_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if wasm_bytes.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "wasm bytes ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
+        let options = match read_compilation_options(options) {
+            Ok(options) => options,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
             }
-            wasmer_result_t::WASMER_OK
+        };
+        let new_module = match compile_with_options(bytes, &options, stats) {
+            Ok(module) => module,
+            Err(error) => {
+                let result = compile_failure_result(&error);
+                update_last_error(error);
+                return result;
+            }
+        };
+
+        let instantiate_start = start_timer(stats);
+        let new_instance = match instantiate_module(&new_module, import_object, &options, gas_used)
+        {
+            Ok(instance) => instance,
+            Err(error) => {
+                let result = start_failure_result(&error);
+                update_last_error(error);
+                return result;
+            }
+        };
+        if let Some(instantiate_start) = instantiate_start {
+            (*stats).instantiate_nanos = elapsed_nanos(instantiate_start);
         }
-        Err(err) => {
-            update_last_error(err);
-            wasmer_result_t::WASMER_ERROR
+        *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Checks the opcode costs set by `wasmer_set_opcode_costs()` when the
+/// `require_loop_costs` option applies to a metered instance.
+#[cfg(feature = "metering")]
+pub(crate) unsafe fn check_required_loop_costs(
+    options: &wasmer_compilation_options_t,
+) -> Result<(), CApiError> {
+    if !options.metering || !options.require_loop_costs {
+        return Ok(());
+    }
+    metering_costs::check_loop_enabling_costs(&OPCODE_COSTS).map_err(|msg| CApiError {
+        code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+        msg,
+    })
+}
+
+/// Compiles WebAssembly bytes with the given compilation options, and
+/// stores the compilation statistics in `stats`, unless it is a null
+/// pointer.
+///
+/// The compilation fails with `WASMER_ERROR_CODE_TIMEOUT` when it
+/// exceeds the `compile_timeout_ms` option. The streaming of the code
+/// is aborted by the `CompileDeadline` middleware, but the work of the
+/// backend afterwards cannot be interrupted: a module completed past
+/// the deadline is dropped rather than returned.
+#[cfg(feature = "metering")]
+pub(crate) unsafe fn compile_with_options(
+    bytes: &[u8],
+    options: &wasmer_compilation_options_t,
+    stats: *mut wasmer_compile_stats_t,
+) -> Result<wasmer_runtime::Module, CausedError> {
+    // An early failure must not leave the violations of a previous compilation behind.
+    compile_violations::clear_compile_violations();
+    let stripped_bytes;
+    let bytes = if options.strip_custom_sections {
+        stripped_bytes = crate::module::strip_custom_sections(bytes).map_err(|msg| CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+            msg,
+        })?;
+        &stripped_bytes[..]
+    } else {
+        bytes
+    };
+    check_required_loop_costs(options)?;
+    opcode_denylist::check_opcode_codes(denied_opcodes(options)).map_err(|msg| CApiError {
+        code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+        msg,
+    })?;
+    let compiler_chain_generator = prepare_middleware_chain_generator(&options);
+    let compiler = get_compiler(compiler_chain_generator);
+    let compiler_config = get_compiler_config(&options);
+    let compile_start = start_timer(stats);
+    let timeout = compile_timeout(options);
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let timed_out = || match (timeout, deadline) {
+        (Some(timeout), Some(deadline)) if compile_deadline::is_expired(deadline) => {
+            Some(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_TIMEOUT,
+                msg: compile_deadline::timeout_message(timeout),
+            })
         }
+        _ => None,
     };
+    let module = wasmer_runtime_core::compile_with_config(bytes, &compiler, compiler_config)
+        .map_err(|e| {
+            if let Some(error) = timed_out() {
+                return error.caused_by(e);
+            }
+            compile_violations::record_compile_violations(bytes, options);
+            CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                msg: format!("compile error: {}", e),
+            }
+            .caused_by(e)
+        })?;
+    if let Some(error) = timed_out() {
+        return Err(error.into());
+    }
+    if let Some(compile_start) = compile_start {
+        write_compile_stats(&mut *stats, &module, compile_start);
+    }
 
-    let last_opcode_location =
-        wasmer_middleware_common::opcode_trace::get_opcodetracer_last_location(instance);
-    if last_opcode_location > 0 {
-        let imported_functions = instance.module.info.name_table.to_vec();
-        for i in 0..imported_functions.len() {
-            println!("Import {}\t{}", i, imported_functions[i]);
-        }
+    Ok(module)
+}
 
-        for (k, v) in instance.module.info.exports.iter() {
-            println!("Export {:?}\t{}", v, k);
+/// Creates a new instance from the given module and imports, like
+/// `wasmer_module_instantiate()`, with the given options.
+///
+/// The options which apply to the compilation, such as `metering`,
+/// are ignored: they are the ones the module was compiled with, e.g.
+/// by `wasmer_compile_with_gas_metering()`. All the others apply as in
+/// `wasmer_instantiate_with_options_and_gas()`, including the gas
+/// limit of the start function, and `gas_used`, unless it is a null
+/// pointer, receives the gas consumed by the instantiation.
+///
+/// A null `options` instantiates the module exactly like
+/// `wasmer_module_instantiate()`, and leaves `gas_used` untouched.
+///
+/// `imports` may be a null pointer if `imports_len` is 0.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_instantiate_with_options(
+    module: *const wasmer_module_t,
+    instance: *mut *mut wasmer_instance_t,
+    imports: *mut wasmer_import_t,
+    imports_len: u32,
+    options: *const wasmer_compilation_options_t,
+    gas_used: *mut u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() || instance.is_null() || (imports.is_null() && imports_len != 0) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module, instance and imports ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
         }
 
-        println!(
-            "wasmer_instance_call OPCODE_LAST_LOCATION = {}",
-            last_opcode_location
-        );
+        let import_object = if imports_len == 0 {
+            ImportObject::new()
+        } else {
+            match wasmer_create_import_object_from_imports(imports, imports_len) {
+                Ok(import_object) => import_object,
+                Err(ImportError::ModuleNameError) => {
+                    update_last_error(CApiError {
+                        code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                        msg: "error converting module name to string".to_string(),
+                    });
+                    return wasmer_result_t::WASMER_ERROR;
+                }
+                Err(ImportError::ImportNameError) => {
+                    update_last_error(CApiError {
+                        code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                        msg: "error converting import_name to string".to_string(),
+                    });
+                    return wasmer_result_t::WASMER_ERROR;
+                }
+            }
+        };
+        let module = &*(module as *const wasmer_runtime_core::Module);
+
+        let result = if options.is_null() {
+            module
+                .instantiate(&import_object)
+                .map_err(|error| CApiError {
+                    code: error_code(&error),
+                    msg: error.to_string(),
+                })
+        } else {
+            read_compilation_options(options)
+                .and_then(|options| instantiate_module(module, &import_object, &options, gas_used))
+        };
+        let new_instance = match result {
+            Ok(instance) => instance,
+            Err(error) => {
+                let result = start_failure_result(&error);
+                update_last_error(error);
+                return result;
+            }
+        };
+        *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Starts measuring a phase reported in `stats`, unless `stats` is a
+/// null pointer.
+#[cfg(feature = "metering")]
+pub(crate) fn start_timer(stats: *const wasmer_compile_stats_t) -> Option<Instant> {
+    if stats.is_null() {
+        None
+    } else {
+        Some(Instant::now())
     }
+}
 
-    result
+#[cfg(feature = "metering")]
+pub(crate) fn elapsed_nanos(start: Instant) -> u64 {
+    let nanos = start.elapsed().as_nanos();
+    if nanos > u128::from(u64::max_value()) {
+        u64::max_value()
+    } else {
+        nanos as u64
+    }
 }
 
-/// Gets all the exports of the given WebAssembly instance.
+/// Fills the compilation statistics of a freshly compiled module.
+/// `instantiate_nanos` is reset, as the module is not instantiated yet.
+#[cfg(feature = "metering")]
+pub(crate) fn write_compile_stats(
+    stats: &mut wasmer_compile_stats_t,
+    module: &wasmer_runtime_core::Module,
+    compile_start: Instant,
+) {
+    let info = module.info();
+
+    *stats = wasmer_compile_stats_t {
+        compile_nanos: elapsed_nanos(compile_start),
+        instantiate_nanos: 0,
+        code_size: module.code_size() as u64,
+        function_count: (info.func_assoc.len() - info.imported_functions.len()) as u32,
+    };
+}
+
+/// Instantiates a module with the given options against the given
+/// import object: checks the module against the options, runs its
+/// start function with `instantiate_metered`, and writes the initial
+/// memory image of the options. The gas consumed by the instantiation
+/// is stored in `gas_used`, unless it is a null pointer, including when
+/// the start function runs out of gas.
+#[cfg(feature = "metering")]
+unsafe fn instantiate_module(
+    module: &wasmer_runtime_core::Module,
+    import_object: &ImportObject,
+    options: &wasmer_compilation_options_t,
+    gas_used: *mut u64,
+) -> Result<Instance, CApiError> {
+    if options.reject_start_function && module.info().start_func.is_some() {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: "module has a start function".to_string(),
+        });
+    }
+    crate::module::check_initial_memory_pages(module.info(), options.max_initial_memory_pages)
+        .map_err(|msg| CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg,
+        })?;
+
+    let mut start_gas_used = 0;
+    let result_instantiation =
+        instantiate_metered(module, import_object, options, &mut start_gas_used);
+    if !gas_used.is_null() {
+        *gas_used = start_gas_used;
+    }
+    let mut new_instance = result_instantiation?;
+    write_initial_memory(&mut new_instance, options)?;
+    if !gas_used.is_null() {
+        *gas_used = metering::get_points_used(&new_instance);
+    }
+    Ok(new_instance)
+}
+
+/// Instantiates a module compiled with the given options against the
+/// given import object, and calls its start function, if any, once the
+/// gas limit of the options is set, so that the start function is
+/// metered like any call. The gas consumed by the start function is
+/// stored in `gas_used`, including when it runs out of gas.
 ///
+/// The start function is not called if the `skip_start` option is
+/// set; it is left to `wasmer_instance_run_start()`.
+#[cfg(not(feature = "cranelift-backend"))]
+pub(crate) fn instantiate_metered(
+    module: &wasmer_runtime_core::Module,
+    import_object: &ImportObject,
+    options: &wasmer_compilation_options_t,
+    gas_used: &mut u64,
+) -> Result<Instance, CApiError> {
+    let mut instance = module
+        .instantiate_without_start(import_object)
+        .map_err(|error| CApiError {
+            code: error_code(&error),
+            msg: error.to_string(),
+        })?;
+    metering::set_points_limit(&mut instance, options.gas_limit);
+    metering::set_charge_full_on_out_of_gas(&mut instance, options.charge_full_on_oog);
+    metering::set_instructions_limit(&mut instance, options.hard_instruction_limit);
+    opcode_control::set_max_table_elements(&mut instance, options.max_table_elements);
+    opcode_control::set_memory_grow_limits(
+        &mut instance,
+        options.max_memory_grow,
+        options.max_memory_grow_delta,
+    );
+    metering::set_points_used(&mut instance, 0);
 
-/// This function stores a Rust vector of exports into `exports` as an
-/// opaque pointer of kind `wasmer_exports_t`.
+    *gas_used = 0;
+    if !options.skip_start {
+        call_start_metered(&mut instance, gas_used)?;
+    }
+
+    Ok(instance)
+}
+
+/// Calls the start function of the instance, if any, with the
+/// bookkeeping of `call_instance`, and stores the gas it consumed in
+/// `gas_used`, including when it runs out of gas.
+#[cfg(not(feature = "cranelift-backend"))]
+fn call_start_metered(instance: &mut Instance, gas_used: &mut u64) -> Result<(), CApiError> {
+    let previous_points_used = metering::get_points_used(instance);
+    let result = unsafe {
+        call_instance(instance, |instance| {
+            instance
+                .call_start_func()
+                .map(|()| Vec::new())
+                .map_err(CallError::Runtime)
+        })
+    };
+    *gas_used = metering::get_points_used(instance).saturating_sub(previous_points_used);
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(_)
+            if runtime_breakpoints::get_runtime_breakpoint_value(instance)
+                == runtime_breakpoints::BREAKPOINT_VALUE_OUT_OF_GAS =>
+        {
+            Err(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_OUT_OF_GAS,
+                msg: "out of gas".to_string(),
+            })
+        }
+        Err(_)
+            if runtime_breakpoints::get_runtime_breakpoint_value(instance)
+                == runtime_breakpoints::BREAKPOINT_VALUE_INSTRUCTION_LIMIT =>
+        {
+            Err(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INSTRUCTION_LIMIT,
+                msg: "hard instruction limit exceeded".to_string(),
+            })
+        }
+        Err(error) => Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_TRAP,
+            msg: error.to_string(),
+        }),
+    }
+}
+
+/// Runs the start function of a WebAssembly instance created with the
+/// `skip_start` compilation option set, e.g. once the module has been
+/// inspected, or once its imports are ready.
 ///
-/// As is, you can do anything with `exports` except using the
-/// companion functions, like `wasmer_exports_len()`,
-/// `wasmer_exports_get()` or `wasmer_export_kind()`. See the example below.
+/// The start function is metered like any call, against the gas limit
+/// and the gas already used by the instance; the gas it consumes is
+/// stored in `gas_used`, unless it is a null pointer, including when it
+/// runs out of gas. Nothing is run if the module has no start function.
 ///
-/// **Warning**: The caller owns the object and should call
-/// `wasmer_exports_destroy()` to free it.
+/// The start function is run again at each call, it is up to the caller
+/// to run it only once.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_OUT_OF_GAS`, with the error message
+/// `out of gas`, if the start function runs out of gas, and
+/// `wasmer_result_t::WASMER_ERROR` upon any other failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
 ///
 /// Example:
 ///
 /// ```c
-/// // Get the exports.
-/// wasmer_exports_t *exports = NULL;
-/// wasmer_instance_exports(instance, &exports);
-///
-/// // Get the number of exports.
-/// int exports_length = wasmer_exports_len(exports);
-/// printf("Number of exports: %d\n", exports_length);
+/// uint64_t start_gas_used = 0;
 ///
-/// // Read the first export.
-/// wasmer_export_t *export = wasmer_exports_get(exports, 0);
+/// if (wasmer_instance_run_start(instance, &start_gas_used) != WASMER_OK) {
+///     // The instance is unusable.
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_run_start(
+    instance: *mut wasmer_instance_t,
+    gas_used: *mut u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &mut *(instance as *mut Instance);
+        let mut start_gas_used = 0;
+        let result = call_start_metered(instance, &mut start_gas_used);
+        if !gas_used.is_null() {
+            *gas_used = start_gas_used;
+        }
+
+        match result {
+            Ok(()) => wasmer_result_t::WASMER_OK,
+            Err(error) => {
+                let result = start_failure_result(&error);
+                update_last_error(error);
+                result
+            }
+        }
+    })
+}
+
+/// Copies the initial memory image of the `wasmer_compilation_options_t`, if
+/// any, into the memory of a freshly created instance, and charges
+/// `initial_memory_gas_per_byte` for each copied byte, on top of the
+/// gas already used by the start function.
+#[cfg(feature = "metering")]
+unsafe fn write_initial_memory(
+    instance: &mut Instance,
+    options: &wasmer_compilation_options_t,
+) -> Result<(), CApiError> {
+    if options.initial_memory.is_null() || options.initial_memory_len == 0 {
+        return Ok(());
+    }
+
+    let module_info = &instance.module.info;
+    if module_info.memories.len() + module_info.imported_memories.len() == 0 {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: "cannot write initial memory: the module has no memory".to_string(),
+        });
+    }
+
+    let gas = u64::from(options.initial_memory_len)
+        .saturating_mul(options.initial_memory_gas_per_byte)
+        .saturating_add(metering::get_points_used(instance));
+    if gas > options.gas_limit {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_OUT_OF_GAS,
+            msg: "not enough gas to write initial memory".to_string(),
+        });
+    }
+
+    let data = slice::from_raw_parts(options.initial_memory, options.initial_memory_len as usize);
+    let start = options.initial_memory_offset as usize;
+    let end = start + data.len();
+
+    let memory = instance.context().memory(0);
+    let view = memory.view::<u8>();
+    if end > view.len() {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: format!(
+                "initial memory does not fit: {} bytes at offset {} exceed the memory size of {} bytes",
+                data.len(),
+                start,
+                view.len()
+            ),
+        });
+    }
+
+    for (cell, &byte) in view[start..end].iter().zip(data.iter()) {
+        cell.set(byte);
+    }
+
+    metering::set_points_used(instance, gas);
+    Ok(())
+}
+
+/// Resets a WebAssembly instance to its state right after
+/// instantiation: the memories are shrunk to their minimum size,
+/// zeroed, and re-initialized with the data segments, the mutable
+/// globals are re-initialized, and the start function, if any, is
+/// called again. The gas used, the runtime breakpoint and the memory
+/// grow count are reset before the start function runs, so that the
+/// gas used afterwards is the gas of the start function, as right
+/// after instantiation.
 ///
-/// // Get the kind of the export.
-/// wasmer_import_export_kind export_kind = wasmer_export_kind(export);
+/// Contents written to the memories after instantiation, e.g. the
+/// initial memory image given to `wasmer_instantiate_with_options()`,
+/// are not restored.
 ///
-/// // Assert it is a function (why not).
-/// assert(export_kind == WASM_FUNCTION);
+/// Returns `wasmer_result_t::WASMER_OK` upon success, and
+/// `wasmer_result_t::WASMER_OUT_OF_GAS` if the start function runs out
+/// of gas.
 ///
-/// // Read the export name.
-/// wasmer_byte_array name_bytes = wasmer_export_name(export);
+/// Returns `wasmer_result_t::WASMER_ERROR` upon any other failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_reset(
+    instance: *mut wasmer_instance_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        reset_instance(instance, true)
+    })
+}
+
+/// Resets a WebAssembly instance to a clean slate: like
+/// `wasmer_instance_reset()`, except that the start function is not
+/// called, so the memories only contain the data segments and zeroes.
 ///
-/// assert(name_bytes.bytes_len == sizeof("sum") - 1);
-/// assert(memcmp(name_bytes.bytes, "sum", sizeof("sum") - 1) == 0);
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
 ///
-/// // Destroy the exports.
-/// wasmer_exports_destroy(exports);
-/// ```
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
-pub unsafe extern "C" fn wasmer_instance_exports(
+pub unsafe extern "C" fn wasmer_instance_reset_zeroed(
     instance: *mut wasmer_instance_t,
-    exports: *mut *mut wasmer_exports_t,
-) {
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        reset_instance(instance, false)
+    })
+}
+
+unsafe fn reset_instance(instance: *mut wasmer_instance_t, call_start: bool) -> wasmer_result_t {
     if instance.is_null() {
-        return;
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "null instance".to_string(),
+        });
+        return wasmer_result_t::WASMER_ERROR;
     }
 
-    let instance_ref = &mut *(instance as *mut Instance);
-    let mut exports_vec: Vec<NamedExport> = Vec::with_capacity(instance_ref.exports().count());
+    let instance = &mut *(instance as *mut Instance);
 
-    for (name, export) in instance_ref.exports() {
-        exports_vec.push(NamedExport {
-            name: name.clone(),
-            export: export.clone(),
-            instance: instance as *mut Instance,
+    if let Err(error) = instance.reset_zeroed() {
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_TRAP,
+            msg: error.to_string(),
         });
+        return wasmer_result_t::WASMER_ERROR;
     }
 
-    let named_exports: Box<NamedExports> = Box::new(NamedExports(exports_vec));
+    // The state of the last call is cleared before the start function
+    // runs again, so that it is metered from zero, as at instantiation.
+    reset_memory_grow_count(instance);
+    metering::set_points_used(instance, 0);
+    runtime_breakpoints::set_runtime_breakpoint_value(
+        instance,
+        runtime_breakpoints::BREAKPOINT_VALUE_NO_BREAKPOINT,
+    );
+
+    if call_start {
+        let mut gas_used = 0;
+        if let Err(error) = call_start_metered(instance, &mut gas_used) {
+            let result = start_failure_result(&error);
+            update_last_error(error);
+            return result;
+        }
+    } else {
+        metering::reset_gas_breakdown(instance);
+        metering::reset_per_function_gas(instance);
+    }
+
+    wasmer_result_t::WASMER_OK
+}
+
+/// Returns the compilation timeout of the options, if any.
+pub(crate) fn compile_timeout(options: &wasmer_compilation_options_t) -> Option<Duration> {
+    if options.compile_timeout_ms == 0 {
+        None
+    } else {
+        Some(Duration::from_millis(options.compile_timeout_ms))
+    }
+}
+
+/// Returns the result of a compilation which failed with the given
+/// error, i.e. `wasmer_result_t::WASMER_TIMEOUT` when it timed out,
+/// `wasmer_result_t::WASMER_ERROR` otherwise.
+pub(crate) fn compile_failure_result(error: &CausedError) -> wasmer_result_t {
+    match error.error.code {
+        wasmer_error_code_t::WASMER_ERROR_CODE_TIMEOUT => wasmer_result_t::WASMER_TIMEOUT,
+        _ => wasmer_result_t::WASMER_ERROR,
+    }
+}
+
+/// Returns the result of an instantiation, or of a run of the start
+/// function, which failed with the given error, i.e.
+/// `wasmer_result_t::WASMER_OUT_OF_GAS` when the start function or the
+/// initial memory image ran out of gas, `wasmer_result_t::WASMER_ERROR`
+/// otherwise.
+pub(crate) fn start_failure_result(error: &CApiError) -> wasmer_result_t {
+    match error.code {
+        wasmer_error_code_t::WASMER_ERROR_CODE_OUT_OF_GAS => wasmer_result_t::WASMER_OUT_OF_GAS,
+        _ => wasmer_result_t::WASMER_ERROR,
+    }
+}
+
+/// Returns the codes of the opcodes denied by the options, see
+/// `opcode_codes::Opcode`.
+pub(crate) unsafe fn denied_opcodes(options: &wasmer_compilation_options_t) -> &[u16] {
+    if options.denied_opcodes.is_null() {
+        return &[];
+    }
+
+    slice::from_raw_parts(options.denied_opcodes, options.denied_opcodes_len as usize)
+}
+
+pub unsafe fn prepare_middleware_chain_generator(
+    options: &wasmer_compilation_options_t,
+) -> impl Fn() -> MiddlewareChain + '_ {
+    let options = *options;
+    let denied_opcodes = denied_opcodes(&options).to_vec();
+
+    let chain_generator = move || {
+        let mut chain = MiddlewareChain::new();
+
+        // The OpcodeDenylist comes first, so that it does not see the opcodes injected by the
+        // other middlewares.
+        if !denied_opcodes.is_empty() {
+            chain.push(opcode_denylist::OpcodeDenylist::new(&denied_opcodes));
+        }
+
+        if let Some(timeout) = compile_timeout(&options) {
+            chain.push(compile_deadline::CompileDeadline::new(timeout));
+        }
+
+        // The BlockCoverage comes before the middlewares injecting opcodes, so that the ids of the
+        // blocks do not depend on the other options.
+        if options.block_coverage {
+            chain.push(block_coverage::BlockCoverage::new());
+        }
+
+        if options.metering {
+            // A hard instruction limit of 0 means no limit, and spares the counting.
+            #[cfg(feature = "metering")]
+            {
+                let metering = if options.hard_instruction_limit != 0 {
+                    metering::Metering::new_with_instruction_limit(
+                        &OPCODE_COSTS,
+                        options.unmetered_locals,
+                    )
+                } else {
+                    metering::Metering::new(&OPCODE_COSTS, options.unmetered_locals)
+                };
+                let metering = if options.gas_breakdown {
+                    metering.with_gas_breakdown()
+                } else {
+                    metering
+                };
+                chain.push(if options.per_function_gas {
+                    metering.with_per_function_gas()
+                } else {
+                    metering
+                });
+            }
+        }
+
+        chain.push(opcode_control::OpcodeControl::new(
+            options.max_memory_grow,
+            options.max_memory_grow_delta,
+        ));
+
+        if options.deterministic_float {
+            chain.push(deterministic_float::DeterministicFloat::new());
+        }
+
+        if options.meter_stack_height {
+            chain.push(stack_height::StackHeight::new(options.max_stack_height));
+        }
+
+        if options.max_call_depth != 0 {
+            chain.push(call_depth::CallDepth::new(u64::from(
+                options.max_call_depth,
+            )));
+        }
+
+        if options.trace_import_calls {
+            chain.push(import_call_trace::ImportCallTracer::new());
+        }
+
+        // The RuntimeBreakpointHandler must be the last middleware in the chain (OpcodeTracer is
+        // an exception since it does not alter the opcodes meaningfully.
+        if options.runtime_breakpoints {
+            chain.push(runtime_breakpoints::RuntimeBreakpointHandler::new());
+        }
+
+        // A `trace_function_end` of 0 means no upper bound, so that zeroed options trace every
+        // function.
+        let trace_function_end = match options.trace_function_end {
+            0 => u32::max_value(),
+            end => end,
+        };
+        if options.collect_opcode_trace {
+            chain.push(
+                opcode_trace::OpcodeTracer::new_with_trace_collection()
+                    .with_function_range(options.trace_function_start, trace_function_end),
+            );
+        } else if options.opcode_trace {
+            chain.push(
+                opcode_trace::OpcodeTracer::new()
+                    .with_function_range(options.trace_function_start, trace_function_end),
+            );
+        };
+
+        chain
+    };
+
+    chain_generator
+}
+
+pub unsafe fn get_compiler(chain_generator: impl Fn() -> MiddlewareChain) -> impl Compiler {
+    #[cfg(feature = "llvm-backend")]
+    use wasmer_llvm_backend::ModuleCodeGenerator as MeteredMCG;
+
+    #[cfg(feature = "singlepass-backend")]
+    use wasmer_singlepass_backend::ModuleCodeGenerator as MeteredMCG;
+
+    #[cfg(feature = "cranelift-backend")]
+    use wasmer_clif_backend::CraneliftModuleCodeGenerator as MeteredMCG;
+
+    let compiler: StreamingCompiler<MeteredMCG, _, _, _, _> =
+        StreamingCompiler::new(chain_generator);
+    compiler
+}
+
+/// Builds the `CompilerConfig` restricting the accepted WebAssembly
+/// features to the ones enabled in the `wasmer_compilation_options_t`, skipping
+/// the validation of the `already_validated` bytes, recording the trap
+/// codes of the `deterministic_traps` modules, and marking the metered
+/// modules as such.
+pub fn get_compiler_config(options: &wasmer_compilation_options_t) -> CompilerConfig {
+    CompilerConfig {
+        compile_options_hash: unsafe { compile_options_hash(options) },
+        metered: cfg!(feature = "metering") && options.metering,
+        features: Features {
+            simd: options.enable_simd,
+            threads: options.enable_threads,
+            bulk_memory: options.enable_bulk_memory,
+            reference_types: options.enable_reference_types,
+        },
+        skip_pre_validation: options.already_validated,
+        precise_trap_codes: options.deterministic_traps,
+        ..Default::default()
+    }
+}
+
+/// Tags the input of `compile_options_hash`.
+const COMPILE_OPTIONS_HASH_TAG: u8 = 1;
+
+/// Tags the input of `gas_metering_options_hash`.
+const GAS_METERING_OPTIONS_HASH_TAG: u8 = 2;
+
+/// Hashes the compilation options which change the compiled code,
+/// along with the opcode costs of the metered modules, see
+/// `wasmer_module_content_hash()`. The options which only bound the
+/// compilation, e.g. `compile_timeout_ms`, or which only apply at
+/// instantiation, e.g. `gas_limit`, are left out.
+pub(crate) unsafe fn compile_options_hash(options: &wasmer_compilation_options_t) -> [u8; 32] {
+    let mut input = vec![COMPILE_OPTIONS_HASH_TAG];
+    for &flag in &[
+        options.metering,
+        options.runtime_breakpoints,
+        options.opcode_trace,
+        options.collect_opcode_trace,
+        options.enable_bulk_memory,
+        options.enable_reference_types,
+        options.enable_simd,
+        options.enable_threads,
+        options.strip_custom_sections,
+        options.meter_stack_height,
+        options.block_coverage,
+        options.gas_breakdown,
+        options.per_function_gas,
+        options.deterministic_traps,
+        options.trace_import_calls,
+        options.deterministic_float,
+    ] {
+        input.push(flag as u8);
+    }
+    for &value in &[
+        options.unmetered_locals as u64,
+        options.max_memory_grow as u64,
+        options.max_memory_grow_delta as u64,
+        options.max_stack_height,
+        options.hard_instruction_limit,
+        u64::from(options.trace_function_start),
+        u64::from(options.trace_function_end),
+        u64::from(options.max_call_depth),
+    ] {
+        input.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let mut denied_opcodes = denied_opcodes(options).to_vec();
+    denied_opcodes.sort();
+    denied_opcodes.dedup();
+    input.extend_from_slice(&(denied_opcodes.len() as u64).to_le_bytes());
+    for opcode in denied_opcodes {
+        input.extend_from_slice(&opcode.to_le_bytes());
+    }
+
+    if options.metering {
+        hash_opcode_costs(&mut input);
+    }
+
+    WasmHash::generate(&input).into_array()
+}
+
+/// Hashes the opcode costs of the modules compiled by
+/// `wasmer_compile_with_gas_metering()`, see `compile_options_hash`.
+pub(crate) unsafe fn gas_metering_options_hash() -> [u8; 32] {
+    let mut input = vec![GAS_METERING_OPTIONS_HASH_TAG];
+    hash_opcode_costs(&mut input);
+
+    WasmHash::generate(&input).into_array()
+}
+
+unsafe fn hash_opcode_costs(input: &mut Vec<u8>) {
+    for cost in OPCODE_COSTS.iter() {
+        input.extend_from_slice(&cost.to_le_bytes());
+    }
+}
+
+/// Returns the instance context. Learn more by looking at the
+/// `wasmer_instance_context_t` struct.
+///
+/// This function returns `null` if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// const wasmer_instance_context_get *context = wasmer_instance_context_get(instance);
+/// my_data *data = (my_data *) wasmer_instance_context_data_get(context);
+/// // Do something with `my_data`.
+/// ```
+///
+/// It is often useful with `wasmer_instance_context_data_set()`.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_instance_context_get(
+    instance: *mut wasmer_instance_t,
+) -> *const wasmer_instance_context_t {
+    if instance.is_null() {
+        return ptr::null() as _;
+    }
+
+    let instance = unsafe { &*(instance as *const Instance) };
+    let context: *const Ctx = instance.context() as *const _;
+
+    context as *const wasmer_instance_context_t
+}
+
+/// Verifies whether the specified function name is imported by the given instance.
+///
+/// This function returns `false` if `instance` or `name` is a null
+/// pointer. It also returns `false`, with a `WASMER_ERROR_CODE_UTF8`
+/// last error, if `name` is not a valid UTF-8 string.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_is_function_imported(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+) -> bool {
+    catch_panic(false, || {
+        if instance.is_null() {
+            return false;
+        }
+
+        if name.is_null() {
+            return false;
+        }
+
+        let instance = &*(instance as *const Instance);
+
+        let func_name_c = CStr::from_ptr(name);
+        let func_name_r = match func_name_c.to_str() {
+            Ok(func_name_r) => func_name_r,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "name is not a valid UTF-8 string".to_string(),
+                });
+
+                return false;
+            }
+        };
+
+        let module = instance.module();
+
+        let functions = module.info().name_table.to_vec();
+
+        functions.contains(&func_name_r)
+    })
+}
+
+/// Calls an exported function of a WebAssembly instance by `name`
+/// with the provided parameters. The exported function results are
+/// stored on the provided `results` pointer, which may be a null
+/// pointer if `results_len` is 0.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. You can use
+/// `wasmer_last_error_message()` to get the generated error message.
+///
+/// Potential errors are the following:
+///
+///   * `instance` is a null pointer,
+///   * `name` is a null pointer,
+///   * `params` is a null pointer,
+///   * `results` is a null pointer while `results_len` is not 0.
+///
+/// It returns `wasmer_result_t::WASMER_INTERRUPTED` if the call was
+/// interrupted by `wasmer_instance_request_interrupt()`, and
+/// `wasmer_result_t::WASMER_STACK_OVERFLOW` if it trapped because its
+/// nested calls went deeper than the `max_call_depth` compilation
+/// option, e.g. with a too deep recursion. The instance can still be
+/// called afterwards.
+///
+/// A panic of an imported function implemented by the runtime, e.g. a
+/// WASI function, does not unwind through the caller: the call traps,
+/// and the error message holds `panic: ` and the panic message.
+///
+/// When the instance is metered, the call resets neither the gas used
+/// nor the gas limit of the instance: the gas it consumes is added to
+/// the gas used by the previous calls, and the limit bounds the total.
+/// See `wasmer_instance_call_shared_gas()` to charge a sequence of calls
+/// on one budget, and `wasmer_instance_call_with_gas()` to give a call
+/// its own budget.
+///
+/// Example of calling an exported function that needs two parameters, and returns one value:
+///
+/// ```c
+/// // First argument.
+/// wasmer_value_t argument_one = {
+///     .tag = WASM_I32,
+///     .value.I32 = 3,
+/// };
+///
+/// // Second argument.
+/// wasmer_value_t argument_two = {
+///     .tag = WASM_I32,
+///     .value.I32 = 4,
+/// };
+///
+/// // First result.
+/// wasmer_value_t result_one;
+///
+/// // All arguments and results.
+/// wasmer_value_t arguments[] = {argument_one, argument_two};
+/// wasmer_value_t results[]   = {result_one};
+///
+/// wasmer_result_t call_result = wasmer_instance_call(
+///     instance,  // instance pointer
+///     "sum",     // the exported function name
+///     arguments, // the arguments
+///     2,         // the number of arguments
+///     results,   // the results
+///     1          // the number of results
+/// );
+///
+/// if (call_result == WASMER_OK) {
+///     printf("Result is: %d\n", results[0].value.I32);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_call(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    results: *mut wasmer_value_t,
+    results_len: u32,
+) -> wasmer_result_t {
+    wasmer_instance_call_with_results_count(
+        instance,
+        name,
+        params,
+        params_len,
+        results,
+        results_len,
+        ptr::null_mut(),
+    )
+}
+
+/// Calls an exported function like `wasmer_instance_call()`, and
+/// stores in `results_written_out`, unless it is a null pointer, the
+/// number of results the function produced, or 0 if the call failed.
+///
+/// Only the first result is stored in `results`, whatever
+/// `results_len`: a count greater than 1, or greater than
+/// `results_len`, tells that results were dropped.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t results_count = 0;
+/// wasmer_result_t call_result = wasmer_instance_call_with_results_count(
+///     instance, "sum", arguments, 2, results, 1, &results_count);
+///
+/// if (call_result == WASMER_OK && results_count > 1) {
+///     // Some results were lost.
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_call_with_results_count(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    results: *mut wasmer_value_t,
+    results_len: u32,
+    results_written_out: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if !results_written_out.is_null() {
+            *results_written_out = 0;
+        }
+
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if name.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "name ptr is null".to_string(),
+            });
+
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if params.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "params ptr is null".to_string(),
+            });
+
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if results.is_null() && results_len != 0 {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "results ptr is null".to_string(),
+            });
+
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let params: &[wasmer_value_t] = slice::from_raw_parts(params, params_len as usize);
+        let params: Vec<Value> = params.iter().cloned().map(|x| x.into()).collect();
+
+        let func_name_c = CStr::from_ptr(name);
+        let func_name_r = match func_name_c.to_str() {
+            Ok(func_name_r) => func_name_r,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "name is not a valid UTF-8 string".to_string(),
+                });
+
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let results: &mut [wasmer_value_t] = if results.is_null() {
+            &mut []
+        } else {
+            slice::from_raw_parts_mut(results, results_len as usize)
+        };
+        let instance = &mut *(instance as *mut Instance);
+        let result = call_instance(instance, |instance| instance.call(func_name_r, &params[..]));
+
+        write_call_outcome(instance, result, results, results_written_out)
+    })
+}
+
+/// Runs a call of the instance, with the bookkeeping of every call:
+/// the opcode trace, the last trap information, the trap forced by the
+/// host, the instructions used, the gas breakdown and the gas per
+/// function are reset, as is the breakpoint of a call which went too
+/// deep, the gas used so far is excluded from the refunds, the import
+/// call and block coverage hooks of the instance are installed, the
+/// opcode trace and the gas per function are recorded into the
+/// instance, and the stack height and the call depth are restored
+/// afterwards.
+pub(crate) unsafe fn call_instance(
+    instance: &mut Instance,
+    call: impl FnOnce(&Instance) -> CallResult<Vec<Value>>,
+) -> CallResult<Vec<Value>> {
+    reset_opcode_trace_state(instance);
+    wasmer_runtime_core::fault::clear_last_trap_info();
+    trap::reset_last_trap_info(instance);
+    trap::reset_host_forced_trap(instance);
+    // Unlike the gas limit, the call depth bounds each call on its own.
+    if runtime_breakpoints::get_runtime_breakpoint_value(instance)
+        == runtime_breakpoints::BREAKPOINT_VALUE_CALL_DEPTH_LIMIT
+    {
+        runtime_breakpoints::set_runtime_breakpoint_value(
+            instance,
+            runtime_breakpoints::BREAKPOINT_VALUE_NO_BREAKPOINT,
+        );
+    }
+    // The hard instruction limit bounds each call on its own, the gas breakdown and the gas
+    // per function describe it, and the refunds may only give back the gas it uses.
+    #[cfg(not(feature = "cranelift-backend"))]
+    {
+        let points_used = metering::get_points_used(instance);
+        metering::set_call_start_points_used(instance, points_used);
+        metering::set_instructions_used(instance, 0);
+        metering::reset_gas_breakdown(instance);
+        metering::reset_per_function_gas(instance);
+    }
+    let import_call_hook = import_call_trace::get_import_call_hook(instance);
+    let stack_height = stack_height::get_stack_height(instance);
+    let call_depth = call_depth::get_call_depth(instance);
+    let block_coverage_hook = block_coverage::get_block_coverage_hook(instance);
+    let result = import_call_trace::with_import_call_hook(import_call_hook, || {
+        block_coverage::with_block_coverage_hook(block_coverage_hook, || {
+            opcode_trace::record_opcode_trace(instance, |instance| {
+                record_per_function_gas(instance, call)
+            })
+        })
+    });
+    // A trap leaves the frames of the call on the stack height and the call depth.
+    stack_height::set_stack_height(instance, stack_height);
+    call_depth::set_call_depth(instance, call_depth);
+    trap::record_last_trap_info(instance, result.is_err());
+    if result.is_err() {
+        runtime_breakpoints::take_interrupt_request(instance);
+    }
+
+    result
+}
+
+/// Runs `call`, recording the gas it uses per function into the instance.
+#[cfg(not(feature = "cranelift-backend"))]
+fn record_per_function_gas<R>(instance: &mut Instance, call: impl FnOnce(&Instance) -> R) -> R {
+    metering::record_per_function_gas(instance, |instance| call(instance))
+}
+
+#[cfg(feature = "cranelift-backend")]
+fn record_per_function_gas<R>(instance: &mut Instance, call: impl FnOnce(&Instance) -> R) -> R {
+    call(instance)
+}
+
+/// Whether the call trapped because it went deeper than the
+/// `max_call_depth` option, or exhausted the native stack.
+fn is_stack_overflow(instance: &Instance, error: &CallError) -> bool {
+    if runtime_breakpoints::get_runtime_breakpoint_value(instance)
+        == runtime_breakpoints::BREAKPOINT_VALUE_CALL_DEPTH_LIMIT
+    {
+        return true;
+    }
+    match error {
+        CallError::Runtime(RuntimeError(data)) => {
+            data.downcast_ref::<ExceptionCode>() == Some(&ExceptionCode::StackOverflow)
+        }
+        _ => false,
+    }
+}
+
+/// Writes the outcome of a call made with `call_instance`: its first
+/// result, if any, into `results`, if there is room for it, and its
+/// number of results into `results_written_out`, unless it is a null
+/// pointer; or its error as the last error.
+unsafe fn write_call_outcome(
+    instance: &Instance,
+    result: CallResult<Vec<Value>>,
+    results: &mut [wasmer_value_t],
+    results_written_out: *mut u32,
+) -> wasmer_result_t {
+    match result {
+        Ok(results_vec) => {
+            if !results_written_out.is_null() {
+                *results_written_out = results_vec.len() as u32;
+            }
+            if !results_vec.is_empty() && !results.is_empty() {
+                let ret = match results_vec[0] {
+                    Value::I32(x) => wasmer_value_t {
+                        tag: wasmer_value_tag::WASM_I32,
+                        value: wasmer_value { I32: x },
+                    },
+                    Value::I64(x) => wasmer_value_t {
+                        tag: wasmer_value_tag::WASM_I64,
+                        value: wasmer_value { I64: x },
+                    },
+                    Value::F32(x) => wasmer_value_t {
+                        tag: wasmer_value_tag::WASM_F32,
+                        value: wasmer_value { F32: x },
+                    },
+                    Value::F64(x) => wasmer_value_t {
+                        tag: wasmer_value_tag::WASM_F64,
+                        value: wasmer_value { F64: x },
+                    },
+                    Value::V128(_) => unimplemented!("calling function with V128 parameter"),
+                };
+                results[0] = ret;
+            }
+            wasmer_result_t::WASMER_OK
+        }
+        Err(err) => {
+            // Running out of gas, or of instructions, is a trap to the runtime, but not to the
+            // caller.
+            let code = match runtime_breakpoints::get_runtime_breakpoint_value(instance) {
+                runtime_breakpoints::BREAKPOINT_VALUE_OUT_OF_GAS => {
+                    wasmer_error_code_t::WASMER_ERROR_CODE_OUT_OF_GAS
+                }
+                runtime_breakpoints::BREAKPOINT_VALUE_INSTRUCTION_LIMIT => {
+                    wasmer_error_code_t::WASMER_ERROR_CODE_INSTRUCTION_LIMIT
+                }
+                _ => error_code(&err),
+            };
+            update_last_error(CApiError {
+                code,
+                msg: err.to_string(),
+            });
+            if runtime_breakpoints::get_runtime_breakpoint_value(instance)
+                == runtime_breakpoints::BREAKPOINT_VALUE_INTERRUPTED
+            {
+                wasmer_result_t::WASMER_INTERRUPTED
+            } else if is_stack_overflow(instance, &err) {
+                wasmer_result_t::WASMER_STACK_OVERFLOW
+            } else {
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    }
+}
+
+/// Calls a function of a WebAssembly instance by its index, with its
+/// parameters packed in a buffer, to spare the `wasmer_value_t` array of
+/// `wasmer_instance_call()` to the callers of functions with many
+/// parameters.
+///
+/// `func_index` is the index of the function in the function index
+/// space of the module, imported functions first; the function does
+/// not have to be exported.
+///
+/// The `params_len` parameters have the types of `param_types`, and
+/// are read in order from `param_data`, each one in its own size (4
+/// bytes for an `i32` or an `f32`, 8 bytes for an `i64` or an `f64`),
+/// in little-endian order, without padding. `param_data_len` must be
+/// the exact sum of these sizes.
+///
+/// The results are written like the ones of `wasmer_instance_call()`.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped, and `wasmer_result_t::WASMER_INTERRUPTED` when the call was
+/// interrupted. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// // sum(i32, i64) -> i64
+/// wasmer_value_tag param_types[] = {WASM_I32, WASM_I64};
+/// uint8_t param_data[12];
+/// int32_t a = 1;
+/// int64_t b = 2;
+/// memcpy(param_data, &a, 4); // On a little-endian host.
+/// memcpy(param_data + 4, &b, 8);
+///
+/// wasmer_value_t results[1];
+/// wasmer_instance_call_packed(instance, sum_index, param_types, 2, param_data, sizeof(param_data), results, 1);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_call_packed(
+    instance: *mut wasmer_instance_t,
+    func_index: u32,
+    param_types: *const wasmer_value_tag,
+    params_len: u32,
+    param_data: *const u8,
+    param_data_len: u32,
+    results: *mut wasmer_value_t,
+    results_len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null()
+            || (param_types.is_null() && params_len != 0)
+            || (param_data.is_null() && param_data_len != 0)
+            || (results.is_null() && results_len != 0)
+        {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance, param_types, param_data and results ptrs must not be null"
+                    .to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &mut *(instance as *mut Instance);
+        let functions_count = instance.module.info.func_assoc.len();
+        if func_index as usize >= functions_count {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "function index {} is out of range, the module has {} functions",
+                    func_index, functions_count
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let param_types: &[wasmer_value_tag] = if params_len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(param_types, params_len as usize)
+        };
+        let param_data: &[u8] = if param_data_len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(param_data, param_data_len as usize)
+        };
+        let params = match unpack_params(param_types, param_data) {
+            Ok(params) => params,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let results: &mut [wasmer_value_t] = if results.is_null() {
+            &mut []
+        } else {
+            slice::from_raw_parts_mut(results, results_len as usize)
+        };
+        let result = call_instance(instance, |instance| {
+            instance.call_with_index(FuncIndex::new(func_index as usize), &params[..])
+        });
+
+        write_call_outcome(instance, result, results, ptr::null_mut())
+    })
+}
+
+/// Reads parameters of the given types from a packed buffer, see
+/// `wasmer_instance_call_packed()`.
+fn unpack_params(types: &[wasmer_value_tag], data: &[u8]) -> Result<Vec<Value>, CApiError> {
+    let mut params = Vec::with_capacity(types.len());
+    let mut offset = 0;
+
+    for ty in types {
+        let size = match ty {
+            wasmer_value_tag::WASM_I32 | wasmer_value_tag::WASM_F32 => 4,
+            wasmer_value_tag::WASM_I64 | wasmer_value_tag::WASM_F64 => 8,
+        };
+        let bytes = match data.get(offset..offset + size) {
+            Some(bytes) => bytes,
+            None => break,
+        };
+        let mut value = [0; 8];
+        value[..size].copy_from_slice(bytes);
+        let bits = u64::from_le_bytes(value);
+
+        params.push(match ty {
+            wasmer_value_tag::WASM_I32 => Value::I32(bits as u32 as i32),
+            wasmer_value_tag::WASM_F32 => Value::F32(f32::from_bits(bits as u32)),
+            wasmer_value_tag::WASM_I64 => Value::I64(bits as i64),
+            wasmer_value_tag::WASM_F64 => Value::F64(f64::from_bits(bits)),
+        });
+        offset += size;
+    }
+
+    if params.len() != types.len() || offset != data.len() {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: format!(
+                "param_data has {} bytes, which do not match the {} param_types",
+                data.len(),
+                types.len()
+            ),
+        });
+    }
+
+    Ok(params)
+}
+
+/// Calls an exported function of a WebAssembly instance by `name`
+/// with the provided parameters, like `wasmer_instance_call()`, and
+/// writes its results packed in `result_data`, without their tags, to
+/// spare the `wasmer_value_t` results to the callers which know the
+/// result types of the function.
+///
+/// The results are written in order, each one in its own size (4 bytes
+/// for an `i32` or an `f32`, 8 bytes for an `i64` or an `f64`, 16 bytes
+/// for a `v128`), in little-endian order, without padding, like the
+/// parameters of `wasmer_instance_call_packed()`. `result_data_len`
+/// must be at least the sum of the sizes of the result types of the
+/// function, otherwise the function is not called. `result_data` may
+/// be a null pointer if the function has no results.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped or when `result_data` is too small, and
+/// `wasmer_result_t::WASMER_INTERRUPTED` when the call was interrupted.
+/// Use `wasmer_last_error_length` and `wasmer_last_error_message` to
+/// get an error message.
+///
+/// Example:
+///
+/// ```c
+/// // sum(i32, i32) -> i32
+/// uint8_t result_data[4];
+/// wasmer_result_t call_result = wasmer_instance_call_raw_results(
+///     instance, "sum", params, 2, result_data, sizeof(result_data));
+///
+/// int32_t sum;
+/// memcpy(&sum, result_data, 4); // On a little-endian host.
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_call_raw_results(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    result_data: *mut u8,
+    result_data_len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null()
+            || name.is_null()
+            || (params.is_null() && params_len != 0)
+            || (result_data.is_null() && result_data_len != 0)
+        {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance, name, params and result_data ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let func_name = match CStr::from_ptr(name).to_str() {
+            Ok(func_name) => func_name,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "name is not a valid UTF-8 string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        let params: Vec<Value> = if params_len == 0 {
+            Vec::new()
+        } else {
+            slice::from_raw_parts(params, params_len as usize)
+                .iter()
+                .cloned()
+                .map(|x| x.into())
+                .collect()
+        };
+
+        let instance = &mut *(instance as *mut Instance);
+        let results_size: usize = match instance.dyn_func(func_name) {
+            Ok(func) => func.signature().returns().iter().map(packed_size).sum(),
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        if results_size > result_data_len as usize {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "result_data is too small: {} bytes, while the results of `{}` take {} bytes",
+                    result_data_len, func_name, results_size
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let result = call_instance(instance, |instance| instance.call(func_name, &params[..]));
+        let results = match result {
+            Ok(ref results) => results,
+            Err(_) => return write_call_outcome(instance, result, &mut [], ptr::null_mut()),
+        };
+
+        let mut offset = 0;
+        for value in results {
+            let bytes = match *value {
+                Value::I32(x) => x.to_le_bytes().to_vec(),
+                Value::I64(x) => x.to_le_bytes().to_vec(),
+                Value::F32(x) => x.to_bits().to_le_bytes().to_vec(),
+                Value::F64(x) => x.to_bits().to_le_bytes().to_vec(),
+                Value::V128(x) => x.to_le_bytes().to_vec(),
+            };
+            ptr::copy_nonoverlapping(bytes.as_ptr(), result_data.add(offset), bytes.len());
+            offset += bytes.len();
+        }
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// The size of a value of the given type in a packed buffer, see
+/// `wasmer_instance_call_raw_results()`.
+fn packed_size(ty: &Type) -> usize {
+    match ty {
+        Type::I32 | Type::F32 => 4,
+        Type::I64 | Type::F64 => 8,
+        Type::V128 => 16,
+    }
+}
+
+/// Calls an exported function of a WebAssembly instance which returns
+/// a `(ptr, len)` pair of `i32`s designating bytes in its memory, e.g.
+/// a string, and copies these bytes into `out_buf`.
+///
+/// The function is called like with `wasmer_instance_call()`, and must
+/// return exactly two `i32`s: the offset of the bytes in the memory at
+/// index 0, then their length. The function is not called if its
+/// signature does not match.
+///
+/// The multi-value proposal is not enabled by the compiler, so the
+/// functions returning two `i32`s cannot be compiled yet. Until then,
+/// the functions returning a single `i64` packing the pair are called
+/// with `wasmer_instance_call_returning_packed_bytes()`.
+///
+/// `written` receives the length of the bytes, even when `out_buf`,
+/// which can hold `out_buf_len` bytes, is too small for them. `out_buf`
+/// may be a null pointer if `out_buf_len` is 0.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped, when the bytes are out of the bounds of the memory, or when
+/// `out_buf` is too small, in which case nothing is written to
+/// `out_buf`; and `wasmer_result_t::WASMER_INTERRUPTED` when the call
+/// was interrupted. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint8_t name[64];
+/// uint32_t name_length = 0;
+/// wasmer_result_t call_result = wasmer_instance_call_returning_bytes(
+///     instance, "get_name", params, 0, name, sizeof(name), &name_length);
+///
+/// if (call_result == WASMER_OK) {
+///     printf("Name: %.*s\n", name_length, name);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_call_returning_bytes(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    out_buf: *mut u8,
+    out_buf_len: u32,
+    written: *mut u32,
+) -> wasmer_result_t {
+    call_returning_bytes(
+        instance,
+        name,
+        params,
+        params_len,
+        out_buf,
+        out_buf_len,
+        written,
+        BytesReturn::Pair,
+    )
+}
+
+/// Calls an exported function of a WebAssembly instance which returns
+/// a single `i64` packing the location of bytes in its memory, and
+/// copies these bytes into `out_buf`, like
+/// `wasmer_instance_call_returning_bytes()`.
+///
+/// The function must return exactly one `i64`, packing the offset of
+/// the bytes in the memory at index 0 in its low 32 bits, and their
+/// length in its high 32 bits. The function is not called if its
+/// signature does not match: the functions returning any other `i64`
+/// are never misread as returning bytes.
+///
+/// The arguments and the results are the ones of
+/// `wasmer_instance_call_returning_bytes()`.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_call_returning_packed_bytes(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    out_buf: *mut u8,
+    out_buf_len: u32,
+    written: *mut u32,
+) -> wasmer_result_t {
+    call_returning_bytes(
+        instance,
+        name,
+        params,
+        params_len,
+        out_buf,
+        out_buf_len,
+        written,
+        BytesReturn::Packed,
+    )
+}
+
+/// Calls a function returning the location of bytes in the memory the
+/// way given by `bytes_return`, and copies these bytes into `out_buf`,
+/// see `wasmer_instance_call_returning_bytes()`.
+#[allow(clippy::too_many_arguments)]
+unsafe fn call_returning_bytes(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    out_buf: *mut u8,
+    out_buf_len: u32,
+    written: *mut u32,
+    bytes_return: BytesReturn,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null()
+            || name.is_null()
+            || (params.is_null() && params_len != 0)
+            || (out_buf.is_null() && out_buf_len != 0)
+            || written.is_null()
+        {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance, name, params, out_buf and written ptrs must not be null"
+                    .to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        *written = 0;
+
+        let func_name = match CStr::from_ptr(name).to_str() {
+            Ok(func_name) => func_name,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "name is not a valid UTF-8 string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        let params: Vec<Value> = if params_len == 0 {
+            Vec::new()
+        } else {
+            slice::from_raw_parts(params, params_len as usize)
+                .iter()
+                .cloned()
+                .map(|x| x.into())
+                .collect()
+        };
+
+        let instance = &mut *(instance as *mut Instance);
+        let returns_bytes = match instance.dyn_func(func_name) {
+            Ok(func) => bytes_return.matches(func.signature().returns()),
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        if !returns_bytes {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!("function `{}` does not return {}", func_name, bytes_return),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let result = call_instance(instance, |instance| instance.call(func_name, &params[..]));
+        let (offset, length) = match result {
+            Ok(ref results) => bytes_return.location(results),
+            Err(_) => return write_call_outcome(instance, result, &mut [], ptr::null_mut()),
+        };
+        *written = length;
+
+        let bytes = match returned_bytes(instance, offset, length) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        if length > out_buf_len {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "out_buf is too small: {} bytes, while the function returned {} bytes",
+                    out_buf_len, length
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if length != 0 {
+            slice::from_raw_parts_mut(out_buf, length as usize).copy_from_slice(bytes);
+        }
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// How a function returns the location of bytes in its memory.
+#[derive(Clone, Copy)]
+enum BytesReturn {
+    /// A `(ptr, len)` pair of `i32`s, see
+    /// `wasmer_instance_call_returning_bytes()`.
+    Pair,
+    /// An `i64` packing the offset in its low 32 bits and the length in
+    /// its high 32 bits, see `wasmer_instance_call_returning_packed_bytes()`.
+    Packed,
+}
+
+impl BytesReturn {
+    /// Whether a function with the given results returns bytes this way.
+    fn matches(self, returns: &[Type]) -> bool {
+        match (self, returns) {
+            (BytesReturn::Pair, [Type::I32, Type::I32]) => true,
+            (BytesReturn::Packed, [Type::I64]) => true,
+            _ => false,
+        }
+    }
+
+    /// The offset and the length of the bytes designated by the results
+    /// of a function whose signature matches.
+    fn location(self, results: &[Value]) -> (u32, u32) {
+        match (self, results) {
+            (BytesReturn::Pair, [Value::I32(offset), Value::I32(length)]) => {
+                (*offset as u32, *length as u32)
+            }
+            (BytesReturn::Packed, [Value::I64(packed)]) => {
+                (*packed as u32, (*packed as u64 >> 32) as u32)
+            }
+            _ => unreachable!("the signature was checked before the call"),
+        }
+    }
+}
+
+impl fmt::Display for BytesReturn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BytesReturn::Pair => write!(f, "a (ptr, len) pair of i32s"),
+            BytesReturn::Packed => write!(f, "an i64 packing a (ptr, len) pair"),
+        }
+    }
+}
+
+/// Returns the `length` bytes at `offset` in the memory at index 0 of
+/// the instance, or an error if they are out of its bounds. The slice
+/// must not outlive a growth of the memory.
+unsafe fn returned_bytes(
+    instance: &Instance,
+    offset: u32,
+    length: u32,
+) -> Result<&[u8], CApiError> {
+    let memory = instance_memory(instance, 0).map_err(|msg| CApiError {
+        code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+        msg,
+    })?;
+    let end = (offset as usize).checked_add(length as usize);
+
+    end.and_then(|end| memory_bytes(memory).get(offset as usize..end))
+        .ok_or_else(|| CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: format!(
+                "the {} bytes at offset {} are out of the bounds of the memory",
+                length, offset
+            ),
+        })
+}
+
+/// Runs a contract in a single call: calls its exported function
+/// `name` with the given parameters and gas limit, like
+/// `wasmer_instance_call_with_gas()`, then reads the gas it consumed,
+/// its return data and, if it trapped, the kind of trap.
+///
+/// The function must return its return data like for
+/// `wasmer_instance_call_returning_bytes()`, as a `(ptr, len)` pair of
+/// `i32`s, or return nothing, in which case there is no return data.
+/// The function is not called if its signature does not match. Use
+/// `wasmer_instance_execute_packed()` for the functions returning an
+/// `i64` packing the pair.
+///
+/// Once the function is called:
+///
+/// * `gas_used_out` receives the gas consumed by the call, including
+///   when it failed, e.g. to charge a failed transaction,
+/// * `return_data_out` and `return_data_len_out` receive a copy of the
+///   return data upon success, or a null pointer and 0 when there is
+///   none; the copy is owned by the caller, and must be freed with
+///   `wasmer_buffer_destroy()`,
+/// * `result_code_out` receives the code of the trap if the call
+///   trapped, `WASMER_TRAP_OUT_OF_GAS` included, and is left untouched
+///   otherwise.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success;
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped, or when the return data is out of the bounds of the memory;
+/// `wasmer_result_t::WASMER_INTERRUPTED` when the call was interrupted,
+/// and `wasmer_result_t::WASMER_STACK_OVERFLOW` when it went deeper
+/// than the `max_call_depth` compilation option. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint64_t gas_used = 0;
+/// uint8_t *return_data = NULL;
+/// uint32_t return_data_length = 0;
+/// wasmer_trap_code_t trap_code = WASMER_TRAP_UNKNOWN;
+///
+/// wasmer_result_t call_result = wasmer_instance_execute(
+///     instance, "transfer", params, 2, 1000000,
+///     &gas_used, &return_data, &return_data_length, &trap_code);
+///
+/// if (call_result == WASMER_OK) {
+///     send_result(return_data, return_data_length, gas_used);
+///     wasmer_buffer_destroy(return_data, return_data_length);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_execute(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    gas_limit: u64,
+    gas_used_out: *mut u64,
+    return_data_out: *mut *mut u8,
+    return_data_len_out: *mut u32,
+    result_code_out: *mut trap::wasmer_trap_code_t,
+) -> wasmer_result_t {
+    execute(
+        instance,
+        name,
+        params,
+        params_len,
+        gas_limit,
+        gas_used_out,
+        return_data_out,
+        return_data_len_out,
+        result_code_out,
+        BytesReturn::Pair,
+    )
+}
+
+/// Runs a contract in a single call, like `wasmer_instance_execute()`,
+/// for a function returning its return data like for
+/// `wasmer_instance_call_returning_packed_bytes()`, as an `i64` packing
+/// the `(ptr, len)` pair, or returning nothing.
+///
+/// The function is not called if its signature does not match. The
+/// arguments and the results are the ones of `wasmer_instance_execute()`.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_execute_packed(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    gas_limit: u64,
+    gas_used_out: *mut u64,
+    return_data_out: *mut *mut u8,
+    return_data_len_out: *mut u32,
+    result_code_out: *mut trap::wasmer_trap_code_t,
+) -> wasmer_result_t {
+    execute(
+        instance,
+        name,
+        params,
+        params_len,
+        gas_limit,
+        gas_used_out,
+        return_data_out,
+        return_data_len_out,
+        result_code_out,
+        BytesReturn::Packed,
+    )
+}
+
+/// Runs a contract in a single call, for a function returning its
+/// return data the way given by `bytes_return`, or returning nothing,
+/// see `wasmer_instance_execute()`.
+#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "metering")]
+unsafe fn execute(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    gas_limit: u64,
+    gas_used_out: *mut u64,
+    return_data_out: *mut *mut u8,
+    return_data_len_out: *mut u32,
+    result_code_out: *mut trap::wasmer_trap_code_t,
+    bytes_return: BytesReturn,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null()
+            || name.is_null()
+            || (params.is_null() && params_len != 0)
+            || gas_used_out.is_null()
+            || return_data_out.is_null()
+            || return_data_len_out.is_null()
+            || result_code_out.is_null()
+        {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance, name, params, gas_used_out, return_data_out, return_data_len_out and result_code_out ptrs must not be null"
+                    .to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        *gas_used_out = 0;
+        *return_data_out = ptr::null_mut();
+        *return_data_len_out = 0;
+
+        let func_name = match CStr::from_ptr(name).to_str() {
+            Ok(func_name) => func_name,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "name is not a valid UTF-8 string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        let params: Vec<Value> = if params_len == 0 {
+            Vec::new()
+        } else {
+            slice::from_raw_parts(params, params_len as usize)
+                .iter()
+                .cloned()
+                .map(|x| x.into())
+                .collect()
+        };
+
+        let instance = &mut *(instance as *mut Instance);
+        let returns_data = match instance.dyn_func(func_name) {
+            Ok(func) => match func.signature().returns() {
+                [] => Some(false),
+                returns if bytes_return.matches(returns) => Some(true),
+                _ => None,
+            },
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        let returns_data = match returns_data {
+            Some(returns_data) => returns_data,
+            None => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: format!(
+                        "function `{}` does not return {}, or nothing",
+                        func_name, bytes_return
+                    ),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        metering::set_points_limit(instance, gas_limit);
+        metering::set_points_used(instance, 0);
+        runtime_breakpoints::set_runtime_breakpoint_value(
+            instance,
+            runtime_breakpoints::BREAKPOINT_VALUE_NO_BREAKPOINT,
+        );
+
+        let result = call_instance(instance, |instance| instance.call(func_name, &params[..]));
+        *gas_used_out = metering::get_points_used(instance);
+
+        let (offset, length) = match result {
+            Ok(ref results) if returns_data => bytes_return.location(results),
+            Ok(_) => return wasmer_result_t::WASMER_OK,
+            Err(_) => {
+                if let Some(trap_info) = trap::last_trap_info(instance) {
+                    *result_code_out = trap_info.code;
+                }
+                return write_call_outcome(instance, result, &mut [], ptr::null_mut());
+            }
+        };
+        if length == 0 {
+            return wasmer_result_t::WASMER_OK;
+        }
+
+        let bytes = match returned_bytes(instance, offset, length) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        let buffer = into_c_buffer(bytes.to_vec().into_boxed_slice());
+        if buffer.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                msg: "return data buffer allocation failed".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+        *return_data_out = buffer;
+        *return_data_len_out = length;
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Calls an exported function of a WebAssembly instance by `name`
+/// with the provided parameters, like `wasmer_instance_call()`, but
+/// without a results buffer. Any result of the function is discarded.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped or ran out of gas. You can use `wasmer_last_error_message()`
+/// to get the generated error message, and
+/// `wasmer_instance_get_runtime_breakpoint_value()` to know whether the
+/// call ran out of gas.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_value_t arguments[] = {};
+///
+/// wasmer_result_t call_result = wasmer_instance_call_void(instance, "init", arguments, 0);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_call_void(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+) -> wasmer_result_t {
+    wasmer_instance_call(
+        instance,
+        name,
+        params,
+        params_len,
+        ptr::NonNull::dangling().as_ptr(),
+        0,
+    )
+}
+
+/// Gets all the exports of the given WebAssembly instance.
+///
+/// This function stores a Rust vector of exports into `exports` as an
+/// opaque pointer of kind `wasmer_exports_t`.
+///
+/// As is, you can do anything with `exports` except using the
+/// companion functions, like `wasmer_exports_len()`,
+/// `wasmer_exports_get()` or `wasmer_export_kind()`. See the example below.
+///
+/// **Warning**: The caller owns the object and should call
+/// `wasmer_exports_destroy()` to free it.
+///
+/// The exports are in the order they are declared in the module, so
+/// the index of an export given to `wasmer_exports_get()` is stable
+/// across calls, runs, and cache round-trips.
+///
+/// Example:
+///
+/// ```c
+/// // Get the exports.
+/// wasmer_exports_t *exports = NULL;
+/// wasmer_instance_exports(instance, &exports);
+///
+/// // Get the number of exports.
+/// int exports_length = wasmer_exports_len(exports);
+/// printf("Number of exports: %d\n", exports_length);
+///
+/// // Read the first export.
+/// wasmer_export_t *export = wasmer_exports_get(exports, 0);
+///
+/// // Get the kind of the export.
+/// wasmer_import_export_kind export_kind = wasmer_export_kind(export);
+///
+/// // Assert it is a function (why not).
+/// assert(export_kind == WASM_FUNCTION);
+///
+/// // Read the export name.
+/// wasmer_byte_array name_bytes = wasmer_export_name(export);
+///
+/// assert(name_bytes.bytes_len == sizeof("sum") - 1);
+/// assert(memcmp(name_bytes.bytes, "sum", sizeof("sum") - 1) == 0);
+///
+/// // Destroy the exports.
+/// wasmer_exports_destroy(exports);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_exports(
+    instance: *mut wasmer_instance_t,
+    exports: *mut *mut wasmer_exports_t,
+) {
+    if instance.is_null() {
+        return;
+    }
+
+    let instance_ref = &mut *(instance as *mut Instance);
+    let mut exports_vec: Vec<NamedExport> = Vec::with_capacity(instance_ref.exports().count());
+
+    // `Instance::exports` yields the exports in declaration order;
+    // callers rely on it to index exports by position.
+    for (name, export) in instance_ref.exports() {
+        exports_vec.push(NamedExport {
+            name: name.clone(),
+            export: export.clone(),
+            instance: instance as *mut Instance,
+        });
+    }
+
+    let named_exports: Box<NamedExports> = Box::new(NamedExports(exports_vec));
+
+    *exports = Box::into_raw(named_exports) as *mut wasmer_exports_t;
+}
+
+/// Gets the number of exports of the given WebAssembly instance.
+///
+/// Unlike `wasmer_instance_exports()`, this function does not
+/// allocate the exports; it is a cheap way to check whether an
+/// instance exports anything, or to size a buffer.
+///
+/// This function returns `0` if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t exports_count = wasmer_instance_exports_count(instance);
+/// printf("Number of exports: %d\n", exports_count);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_exports_count(instance: *mut wasmer_instance_t) -> u32 {
+    if instance.is_null() {
+        return 0;
+    }
+
+    let instance = &*(instance as *const Instance);
+
+    instance.exports().count() as u32
+}
+
+/// Reads the peak size, in pages, reached by the memory of the given
+/// WebAssembly instance during the last call to `wasmer_instance_call()`,
+/// or during the call in progress when called from a host function.
+///
+/// The high-water mark is reset at the start of each call to
+/// `wasmer_instance_call()`, and by `wasmer_instance_reset()`. Since a
+/// memory never shrinks during a call, the peak is the size of the
+/// memory at the end of the call; it is 0 if the instance has no
+/// memory.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_instance_call(instance, "run", params, 0, results, 0);
+///
+/// uint32_t peak_pages = 0;
+/// wasmer_instance_get_peak_memory_pages(instance, &peak_pages);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_get_peak_memory_pages(
+    instance: *mut wasmer_instance_t,
+    pages: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || pages.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and pages ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let module_info = &instance.module.info;
+
+        *pages = if module_info.memories.len() + module_info.imported_memories.len() == 0 {
+            0
+        } else {
+            instance.context().memory(0).size().0
+        };
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Gets the data of a memory of the given WebAssembly instance, by
+/// instance handle rather than by instance context, e.g. to read the
+/// memory of the caller instance from a host function of a callee
+/// instance, when orchestrating several instances.
+///
+/// `data` receives a pointer to the first byte of the memory at index
+/// `memory_idx`, and `data_length` its length in bytes; any access must
+/// stay within these bounds. The pointer is valid until the memory
+/// grows, or until the instance is destroyed.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint8_t *caller_data = NULL;
+/// uint32_t caller_data_length = 0;
+/// wasmer_instance_memory_data(caller_instance, 0, &caller_data, &caller_data_length);
+///
+/// if (offset <= caller_data_length && length <= caller_data_length - offset) {
+///     memcpy(arguments, caller_data + offset, length);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_memory_data(
+    instance: *const wasmer_instance_t,
+    memory_idx: u32,
+    data: *mut *mut u8,
+    data_length: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || data.is_null() || data_length.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance, data and data_length ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let memory = match instance_memory(instance, memory_idx) {
+            Ok(memory) => memory,
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg,
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        *data = memory.view::<u8>()[..].as_ptr() as *mut Cell<u8> as *mut u8;
+        *data_length = memory.size().bytes().0 as u32;
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Length in bytes of the hash computed by
+/// `wasmer_instance_memory_hash()`.
+pub const WASMER_MEMORY_HASH_LENGTH: u32 = 32;
+
+/// Computes a deterministic hash of the contents of a memory of the
+/// given WebAssembly instance, e.g. to check that two independent
+/// executions left their instances in the same state.
+///
+/// The hash covers the current size of the memory at index
+/// `memory_idx` exactly, so that two memories with the same contents
+/// but different sizes have different hashes. It does not depend on
+/// the backend, nor on the platform.
+///
+/// The first `WASMER_MEMORY_HASH_LENGTH` bytes of `out` receive the
+/// hash; `out_len` must be at least that.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_memory_hash(
+    instance: *const wasmer_instance_t,
+    memory_idx: u32,
+    out: *mut u8,
+    out_len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and out ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if out_len < WASMER_MEMORY_HASH_LENGTH {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "out is too small: {} bytes, while the hash needs {} bytes",
+                    out_len, WASMER_MEMORY_HASH_LENGTH
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let memory = match instance_memory(instance, memory_idx) {
+            Ok(memory) => memory,
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg,
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let hash = WasmHash::generate(memory_bytes(memory)).into_array();
+        let out = slice::from_raw_parts_mut(out, WASMER_MEMORY_HASH_LENGTH as usize);
+        out.copy_from_slice(&hash);
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Compares the contents of a memory of the given WebAssembly instance
+/// with an expected image, byte for byte.
+///
+/// The memory at index `memory_idx` is equal to the image only if its
+/// current size is exactly `expected_len` bytes. `expected` may be a
+/// null pointer if `expected_len` is 0.
+///
+/// This function returns 1 if the memory is equal to the image, 0 if it
+/// is not, and -1 upon failure, including when the instance has no
+/// memory at `memory_idx`. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// if (wasmer_instance_memory_equals(instance, 0, expected_image, expected_image_length) != 1) {
+///     // The execution did not produce the expected state.
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_memory_equals(
+    instance: *const wasmer_instance_t,
+    memory_idx: u32,
+    expected: *const u8,
+    expected_len: u32,
+) -> c_int {
+    catch_panic(-1, || {
+        if instance.is_null() || (expected.is_null() && expected_len != 0) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and expected ptrs must not be null".to_string(),
+            });
+            return -1;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let memory = match instance_memory(instance, memory_idx) {
+            Ok(memory) => memory,
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg,
+                });
+                return -1;
+            }
+        };
+
+        let expected: &[u8] = if expected_len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(expected, expected_len as usize)
+        };
+
+        (memory_bytes(memory) == expected) as c_int
+    })
+}
+
+/// The granularity of the touches of `wasmer_instance_prefault_memory()`,
+/// the smallest page size of the supported platforms.
+const PREFAULT_STRIDE: usize = 4096;
+
+/// Touches the first `pages` wasm pages of a memory of the given
+/// WebAssembly instance, so that the operating system commits them
+/// now rather than on their first access, e.g. right after the
+/// instantiation, to spare a latency-sensitive call the page faults.
+///
+/// The contents of the memory are left unchanged: each touched byte is
+/// written back with its own value. The pages beyond the current size
+/// of the memory at index `memory_idx` are ignored, so that `pages` can
+/// be larger than the memory; the memory is never grown.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_instance_prefault_memory(instance, 0, 16);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_prefault_memory(
+    instance: *mut wasmer_instance_t,
+    memory_idx: u32,
+    pages: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let memory = match instance_memory(instance, memory_idx) {
+            Ok(memory) => memory,
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg,
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let view = memory.view::<u8>();
+        let base = view[..].as_ptr() as *mut u8;
+        let length = (pages as usize)
+            .saturating_mul(WASM_PAGE_SIZE)
+            .min(view.len());
+
+        // Volatile accesses, so that the writes of unchanged values are
+        // not optimized away.
+        for offset in (0..length).step_by(PREFAULT_STRIDE) {
+            let byte = base.add(offset);
+            ptr::write_volatile(byte, ptr::read_volatile(byte));
+        }
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Copies the contents of a memory of the given WebAssembly instance
+/// into a new buffer, e.g. to snapshot the state of the instance before
+/// a call, and restore it with `wasmer_instance_memory_import_buffer()`
+/// if the call must be rolled back.
+///
+/// `buf_out` receives the buffer, and `len_out` its length, which is
+/// the current size in bytes of the memory at index `memory_idx`. The
+/// buffer is owned by the caller, and must be freed with
+/// `wasmer_buffer_destroy()`, or with the `free` function registered
+/// with `wasmer_set_allocator()` if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// const uint8_t *snapshot = NULL;
+/// uint32_t snapshot_length = 0;
+/// wasmer_instance_memory_export_buffer(instance, 0, &snapshot, &snapshot_length);
+///
+/// if (wasmer_instance_call(instance, "main", params, 0, results, 0) != WASMER_OK) {
+///     wasmer_instance_memory_import_buffer(instance, 0, snapshot, snapshot_length);
+/// }
+///
+/// wasmer_buffer_destroy((uint8_t *) snapshot, snapshot_length);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_memory_export_buffer(
+    instance: *const wasmer_instance_t,
+    memory_idx: u32,
+    buf_out: *mut *const u8,
+    len_out: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || buf_out.is_null() || len_out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance, buf_out and len_out ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let memory = match instance_memory(instance, memory_idx) {
+            Ok(memory) => memory,
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg,
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let bytes: Box<[u8]> = memory_bytes(memory).into();
+        let bytes_len = bytes.len();
+        let buffer = into_c_buffer(bytes);
+        if buffer.is_null() && bytes_len != 0 {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                msg: "buffer allocation failed".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+        *buf_out = buffer;
+        *len_out = bytes_len as u32;
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Overwrites the contents of a memory of the given WebAssembly
+/// instance with the `len` bytes of `buf`, e.g. a snapshot taken with
+/// `wasmer_instance_memory_export_buffer()`, and resizes the memory to
+/// the size of the snapshot: the bytes beyond `len` up to the next page
+/// are zeroed. `buf` may be a null pointer if `len` is 0.
+///
+/// The memory at index `memory_idx` grows to hold the bytes if needed,
+/// as if by `memory.grow`: the growth counts towards the
+/// `max_memory_grow` option and is subject to the
+/// `max_memory_grow_delta` option, to the maximum size the memory
+/// declares and to the callback set by
+/// `wasmer_instance_set_memory_grow_callback()`.
+///
+/// The memory shrinks back if it is larger than the snapshot, but
+/// never below its minimum size, like `wasmer_instance_reset()` does;
+/// a memory which declares a maximum size cannot shrink.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`, or when the memory can
+/// neither grow nor shrink to the size of the snapshot; the memory is
+/// left unchanged then. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_memory_import_buffer(
+    instance: *mut wasmer_instance_t,
+    memory_idx: u32,
+    buf: *const u8,
+    len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || (buf.is_null() && len != 0) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and buf ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &mut *(instance as *mut Instance);
+        let (minimum_pages, current_pages) = match instance_memory(instance, memory_idx) {
+            Ok(memory) => (memory.descriptor().minimum.0, memory.size().0),
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg,
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let required_pages = ((len as usize + WASM_PAGE_SIZE - 1) / WASM_PAGE_SIZE) as u32;
+        let target_pages = required_pages.max(minimum_pages);
+        let resized = if target_pages > current_pages {
+            opcode_control::grow_memory(instance, memory_idx, target_pages - current_pages)
+                .map(|_| ())
+                .map_err(|error| format!("cannot grow the memory: {}", error))
+        } else if target_pages < current_pages {
+            instance
+                .context_mut()
+                .shrink_memory(memory_idx, Pages(target_pages))
+                .map_err(|error| format!("cannot shrink the memory: {}", error))
+        } else {
+            Ok(())
+        };
+        if let Err(msg) = resized {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg,
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let memory = instance.context().memory(memory_idx);
+        let view = memory.view::<u8>();
+        let data = slice::from_raw_parts_mut(view[..].as_ptr() as *mut u8, view.len());
+        let (image, rest) = data.split_at_mut(len as usize);
+        if len != 0 {
+            image.copy_from_slice(slice::from_raw_parts(buf, len as usize));
+        }
+        ptr::write_bytes(rest.as_mut_ptr(), 0, rest.len());
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Returns the memory of the instance at index `memory_idx`, or an
+/// error if the instance has no such memory.
+fn instance_memory(instance: &Instance, memory_idx: u32) -> Result<&Memory, String> {
+    context_memory(instance.context(), memory_idx)
+}
+
+/// Returns the memory at index `memory_idx` of the instance of a
+/// context, or an error if the instance has no such memory.
+fn context_memory(ctx: &Ctx, memory_idx: u32) -> Result<&Memory, String> {
+    let module_info = unsafe { &(*ctx.module).info };
+    let memories_count = module_info.memories.len() + module_info.imported_memories.len();
+
+    if memory_idx as usize >= memories_count {
+        return Err(format!(
+            "memory index {} is out of bounds, the instance has {} memories",
+            memory_idx, memories_count
+        ));
+    }
+
+    Ok(ctx.memory(memory_idx))
+}
+
+/// Returns the current contents of the memory, as bytes. The slice must
+/// not outlive a growth of the memory.
+unsafe fn memory_bytes(memory: &Memory) -> &[u8] {
+    let view = memory.view::<u8>();
+    slice::from_raw_parts(view[..].as_ptr() as *const u8, view.len())
+}
+
+/// A function called before each `memory.grow` of an instance, with
+/// the user data given to `wasmer_instance_set_memory_grow_callback()`
+/// and the number of requested pages. Returning 0 allows the growth;
+/// returning any other value denies it.
+#[allow(non_camel_case_types)]
+pub type wasmer_memory_grow_callback_t =
+    extern "C" fn(user_data: *mut c_void, requested_pages: u32) -> c_int;
+
+/// Sets the function called before each `memory.grow` performed by the
+/// given WebAssembly instance, to observe or veto the growth of its
+/// memory. When the callback denies a growth, `memory.grow` returns -1
+/// to the WebAssembly code, as for any failed growth.
+///
+/// `user_data` is given back to the callback as is; it is owned by the
+/// caller and must outlive the instance, or the callback.
+///
+/// A null `callback` removes the current callback, if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// int check_balance(void *user_data, uint32_t requested_pages) {
+///     account_t *account = (account_t *) user_data;
+///
+///     return account->balance < requested_pages * PAGE_PRICE;
+/// }
+///
+/// wasmer_instance_set_memory_grow_callback(instance, check_balance, &account);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_set_memory_grow_callback(
+    instance: *mut wasmer_instance_t,
+    callback: Option<wasmer_memory_grow_callback_t>,
+    user_data: *mut c_void,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &mut *(instance as *mut Instance);
+        let ctx = instance.context_mut();
+
+        ctx.memory_grow_hook = callback;
+        ctx.memory_grow_hook_data = if callback.is_some() {
+            user_data
+        } else {
+            ptr::null_mut()
+        };
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// A function called before each call of an imported function by an
+/// instance, with the user data given to
+/// `wasmer_instance_set_import_call_callback()` and the name of the
+/// import, without its module name.
+#[allow(non_camel_case_types)]
+pub type wasmer_import_call_callback_t =
+    extern "C" fn(user_data: *mut c_void, import_name: *const c_char);
+
+/// Sets the function called before each call of an imported function
+/// by the given WebAssembly instance, e.g. to count the host functions
+/// used by a contract.
+///
+/// The callback only observes the calls: it receives neither the
+/// arguments of the imported function nor the instance, and cannot
+/// change them. It is called for the direct `call`s of imported
+/// functions made during `wasmer_instance_call()` and the functions
+/// built on it; imported functions reached through a table with
+/// `call_indirect` are not reported. The name given to the callback is
+/// only valid during the callback.
+///
+/// Only the instances of modules compiled with the
+/// `trace_import_calls` compilation option report their calls.
+///
+/// `user_data` is given back to the callback as is; it is owned by the
+/// caller and must outlive the instance, or the callback.
+///
+/// A null `callback` removes the current callback, if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void count_import_call(void *user_data, const char *import_name) {
+///     histogram_t *histogram = (histogram_t *) user_data;
+///
+///     histogram_increment(histogram, import_name);
+/// }
+///
+/// wasmer_instance_set_import_call_callback(instance, count_import_call, &histogram);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_set_import_call_callback(
+    instance: *mut wasmer_instance_t,
+    callback: Option<wasmer_import_call_callback_t>,
+    user_data: *mut c_void,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &mut *(instance as *mut Instance);
+        import_call_trace::set_import_call_hook(instance, callback, user_data);
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// A function called on the entry of each basic block executed by an
+/// instance, with the user data given to
+/// `wasmer_instance_set_block_coverage_callback()` and the id of the
+/// block.
+#[allow(non_camel_case_types)]
+pub type wasmer_block_coverage_callback_t = extern "C" fn(user_data: *mut c_void, block_id: u32);
+
+/// Sets the function called on the entry of each basic block executed
+/// by the given WebAssembly instance, e.g. to collect the edge coverage
+/// of a fuzzer.
+///
+/// Only the instances of modules compiled with the `block_coverage`
+/// compilation option report their blocks. The blocks start at the
+/// beginning of each function, and after each control flow operator or
+/// call which the execution may continue after. Their ids are numbered
+/// from 0 in the order of the module, whatever the other compilation
+/// options, so they are stable for a given module. The blocks are
+/// reported during `wasmer_instance_call()` and the functions built on
+/// it.
+///
+/// `user_data` is given back to the callback as is; it is owned by the
+/// caller and must outlive the instance, or the callback.
+///
+/// A null `callback` removes the current callback, if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void record_block(void *user_data, uint32_t block_id) {
+///     coverage_t *coverage = (coverage_t *) user_data;
+///
+///     coverage_record_edge(coverage, coverage->previous_block, block_id);
+///     coverage->previous_block = block_id;
+/// }
+///
+/// wasmer_instance_set_block_coverage_callback(instance, record_block, &coverage);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_set_block_coverage_callback(
+    instance: *mut wasmer_instance_t,
+    callback: Option<wasmer_block_coverage_callback_t>,
+    user_data: *mut c_void,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &mut *(instance as *mut Instance);
+        block_coverage::set_block_coverage_hook(instance, callback, user_data);
 
-    *exports = Box::into_raw(named_exports) as *mut wasmer_exports_t;
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 /// Sets the data that can be hold by an instance context.
@@ -629,6 +3583,11 @@ pub unsafe extern "C" fn wasmer_instance_exports(
 ///     // …
 /// }
 /// ```
+///
+/// The data is not freed by the instance, see
+/// `wasmer_instance_context_data_set_with_finalizer()` otherwise. If the
+/// previous data was set with a finalizer, the finalizer is called on it
+/// first, unless it is `data_ptr` again.
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
 pub extern "C" fn wasmer_instance_context_data_set(
@@ -641,7 +3600,154 @@ pub extern "C" fn wasmer_instance_context_data_set(
 
     let instance = unsafe { &mut *(instance as *mut Instance) };
 
-    instance.context_mut().data = data_ptr;
+    set_context_data(instance.context_mut(), data_ptr, None);
+}
+
+/// A function called with the data of an instance when the instance is
+/// destroyed, see `wasmer_instance_context_data_set_with_finalizer()`.
+#[allow(non_camel_case_types)]
+pub type wasmer_instance_context_data_finalizer_t = extern "C" fn(data: *mut c_void);
+
+/// Sets the data held by an instance context, like
+/// `wasmer_instance_context_data_set()`, and gives its ownership to the
+/// instance: `finalizer` is called with the data when the instance is
+/// destroyed by `wasmer_instance_destroy()`, e.g. to free it.
+///
+/// The finalizer is also called when the data is replaced by other
+/// data, with or without a finalizer. A null `finalizer` sets the data
+/// exactly like `wasmer_instance_context_data_set()`.
+///
+/// The instance holds one finalizer at most: the finalizer set by the
+/// runtime for its own data, e.g. the WASI state, is replaced as well,
+/// after finalizing that data, so that no data is finalized twice.
+///
+/// This function does nothing if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// void free_data(void *data) {
+///     free(data);
+/// }
+///
+/// my_data *data = malloc(sizeof(my_data));
+/// wasmer_instance_context_data_set_with_finalizer(instance, (void*) data, free_data);
+///
+/// // Frees the data too.
+/// wasmer_instance_destroy(instance);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_instance_context_data_set_with_finalizer(
+    instance: *mut wasmer_instance_t,
+    data_ptr: *mut c_void,
+    finalizer: Option<wasmer_instance_context_data_finalizer_t>,
+) {
+    if instance.is_null() {
+        return;
+    }
+
+    let instance = unsafe { &mut *(instance as *mut Instance) };
+
+    set_context_data(instance.context_mut(), data_ptr, finalizer);
+}
+
+/// Replaces the data of the context and its finalizer, and finalizes
+/// the previous data, unless it is the new data again. The finalizer
+/// set by the runtime, e.g. for the WASI state, is replaced too, see
+/// `Ctx::set_data`.
+fn set_context_data(
+    ctx: &mut Ctx,
+    data_ptr: *mut c_void,
+    finalizer: Option<wasmer_instance_context_data_finalizer_t>,
+) {
+    ctx.set_data(data_ptr, finalizer);
+}
+
+/// The key of the keyed data which holds the second data slot of the
+/// instance contexts, see `wasmer_instance_context_data2_set()`. The
+/// embedders must not use it for their own keyed data.
+pub const WASMER_CONTEXT_DATA2_KEY: u32 = u32::max_value();
+
+/// Sets the second data slot of an instance context.
+///
+/// The second slot works like the data set by
+/// `wasmer_instance_context_data_set()`, and is independent of it, so
+/// that two host subsystems, e.g. a storage and a crypto module, can
+/// each find their own context object without sharing a single struct
+/// behind the first slot. It is the keyed data under
+/// `WASMER_CONTEXT_DATA2_KEY`, see
+/// `wasmer_instance_context_data_set_keyed()`. This function is
+/// complementary of `wasmer_instance_context_data2_get()`.
+///
+/// The data is never freed by the instance.
+///
+/// This function does nothing if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_instance_context_data_set(instance, (void*) storage);
+/// wasmer_instance_context_data2_set(instance, (void*) entropy_source);
+///
+/// // Within a host function.
+/// entropy_source_t *entropy_source = (entropy_source_t*) wasmer_instance_context_data2_get(context);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_instance_context_data2_set(
+    instance: *mut wasmer_instance_t,
+    data_ptr: *mut c_void,
+) {
+    wasmer_instance_context_data_set_keyed(instance, WASMER_CONTEXT_DATA2_KEY, data_ptr);
+}
+
+/// Sets the data of an instance context under the given key.
+///
+/// Unlike the single slot of `wasmer_instance_context_data_set()`, the
+/// keyed data lets independent host subsystems, e.g. the storage, the
+/// crypto and the logging, each own a key and find their own context
+/// object, without coordinating the layout of a shared struct. The
+/// keys are small integers defined by the embedder, and are
+/// independent of the first data slot. `WASMER_CONTEXT_DATA2_KEY` is
+/// reserved for the second data slot. This function is complementary
+/// of `wasmer_instance_context_data_get_keyed()`.
+///
+/// Setting a null `data_ptr` removes the key. The data is never freed
+/// by the instance.
+///
+/// This function does nothing if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// enum { STORAGE_KEY, CRYPTO_KEY };
+///
+/// wasmer_instance_context_data_set_keyed(instance, STORAGE_KEY, (void*) storage);
+/// wasmer_instance_context_data_set_keyed(instance, CRYPTO_KEY, (void*) crypto);
+///
+/// // Within a host function.
+/// crypto_t *crypto = (crypto_t*) wasmer_instance_context_data_get_keyed(context, CRYPTO_KEY);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_instance_context_data_set_keyed(
+    instance: *mut wasmer_instance_t,
+    key: u32,
+    data_ptr: *mut c_void,
+) {
+    if instance.is_null() {
+        return;
+    }
+
+    let instance = unsafe { &mut *(instance as *mut Instance) };
+    let keyed_data = &mut instance.context_mut().keyed_data;
+
+    if data_ptr.is_null() {
+        keyed_data.remove(&key);
+    } else {
+        keyed_data.insert(key, data_ptr);
+    }
 }
 
 /// Gets the `memory_idx`th memory of the instance.
@@ -651,6 +3757,11 @@ pub extern "C" fn wasmer_instance_context_data_set(
 /// This function is mostly used inside host functions (aka imported
 /// functions) to read the instance memory.
 ///
+/// This function returns a null pointer if `ctx` is a null pointer, or
+/// if the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
 /// Example of a _host function_ that reads and prints a string based on a pointer and a length:
 ///
 /// ```c
@@ -669,11 +3780,408 @@ pub extern "C" fn wasmer_instance_context_data_set(
 #[no_mangle]
 pub extern "C" fn wasmer_instance_context_memory(
     ctx: *const wasmer_instance_context_t,
-    _memory_idx: u32,
+    memory_idx: u32,
 ) -> *const wasmer_memory_t {
+    if ctx.is_null() {
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "ctx ptr is null".to_string(),
+        });
+        return ptr::null();
+    }
+
+    let ctx = unsafe { &*(ctx as *const Ctx) };
+    match context_memory(ctx, memory_idx) {
+        Ok(memory) => memory as *const Memory as *const wasmer_memory_t,
+        Err(msg) => {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg,
+            });
+            ptr::null()
+        }
+    }
+}
+
+/// Gets the current base address of the `memory_idx`th memory of the
+/// instance, from within a host function, for host code which reads
+/// and writes the memory often and wants to skip the indirection of
+/// `wasmer_instance_context_memory()` and `wasmer_memory_data()`.
+///
+/// The memory spans `wasmer_instance_context_memory_length()` bytes
+/// from the returned address; accesses beyond it are undefined
+/// behavior.
+///
+/// The address is invalidated by any growth of the memory, by
+/// `memory.grow` or by `wasmer_memory_grow()`, which may move the
+/// memory. Get it again after any call which may grow the memory,
+/// including calls into the instance.
+///
+/// Note that the index is always `0` until multiple memories are
+/// supported.
+///
+/// This function returns a null pointer if `ctx` is a null pointer, if
+/// `memory_idx` is not `0`, or if the instance has no memory.
+///
+/// Example:
+///
+/// ```c
+/// void write_answer(wasmer_instance_context_t *context, int32_t pointer) {
+///     uint8_t *memory_base = wasmer_instance_context_memory_base(context, 0);
+///     uint32_t memory_length = wasmer_instance_context_memory_length(context, 0);
+///
+///     if (memory_base != NULL && (uint32_t) pointer < memory_length) {
+///         memory_base[pointer] = 42;
+///     }
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_instance_context_memory_base(
+    ctx: *const wasmer_instance_context_t,
+    memory_idx: u32,
+) -> *mut u8 {
+    if ctx.is_null() || memory_idx != 0 {
+        return ptr::null_mut();
+    }
+
+    let ctx = unsafe { &*(ctx as *const Ctx) };
+    ctx.internal.memory_base
+}
+
+/// Gets the current size in bytes of the `memory_idx`th memory of the
+/// instance, from within a host function. See
+/// `wasmer_instance_context_memory_base()`.
+///
+/// This function returns 0 if `ctx` is a null pointer, if `memory_idx`
+/// is not `0`, or if the instance has no memory.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_instance_context_memory_length(
+    ctx: *const wasmer_instance_context_t,
+    memory_idx: u32,
+) -> u32 {
+    if ctx.is_null() || memory_idx != 0 {
+        return 0;
+    }
+
     let ctx = unsafe { &*(ctx as *const Ctx) };
-    let memory = ctx.memory(0);
-    memory as *const Memory as *const wasmer_memory_t
+    if ctx.internal.memory_base.is_null() {
+        return 0;
+    }
+    let Bytes(length) = ctx.memory(0).size().bytes();
+
+    length as u32
+}
+
+/// The length in bytes of a `v128` value.
+const V128_LENGTH: usize = 16;
+
+/// Gets the `len` bytes at `offset` in the memory of the instance, from
+/// within a host function, or an error if they are not all inside the
+/// memory. This is the one place that bounds-checks the typed accessors,
+/// such as `wasmer_instance_context_memory_read_v128()`.
+pub(crate) unsafe fn context_memory_range<'a>(
+    ctx: *const wasmer_instance_context_t,
+    offset: u32,
+    len: usize,
+) -> Result<&'a mut [u8], CApiError> {
+    if ctx.is_null() {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "ctx ptr is null".to_string(),
+        });
+    }
+
+    let memory_base = wasmer_instance_context_memory_base(ctx, 0);
+    let memory_length = wasmer_instance_context_memory_length(ctx, 0) as usize;
+    if memory_base.is_null() {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: "the instance has no memory".to_string(),
+        });
+    }
+
+    let start = offset as usize;
+    match start.checked_add(len) {
+        Some(end) if end <= memory_length => {
+            Ok(slice::from_raw_parts_mut(memory_base.add(start), len))
+        }
+        _ => Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: format!(
+                "{} bytes at offset {} are out of the bounds of the memory of {} bytes",
+                len, offset, memory_length
+            ),
+        }),
+    }
+}
+
+/// Reads the `v128` value at `offset` in the memory of the instance,
+/// from within a host function, into `out`.
+///
+/// The 16 bytes are copied as they are stored in the memory, that is
+/// in the little-endian order of wasm: the first byte of `out` is the
+/// lowest byte of lane 0, whatever the lane shape.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the 16 bytes are not all inside the memory, in which case `out` is
+/// left untouched. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void read_vector(wasmer_instance_context_t *context, int32_t pointer) {
+///     uint8_t vector[16];
+///
+///     if (wasmer_instance_context_memory_read_v128(context, pointer, &vector) == WASMER_OK) {
+///         // Use `vector`.
+///     }
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_memory_read_v128(
+    ctx: *const wasmer_instance_context_t,
+    offset: u32,
+    out: *mut [u8; 16],
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "out ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        match context_memory_range(ctx, offset, V128_LENGTH) {
+            Ok(bytes) => {
+                (*out).copy_from_slice(bytes);
+                wasmer_result_t::WASMER_OK
+            }
+            Err(error) => {
+                update_last_error(error);
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
+/// Writes the `v128` value of `value` at `offset` in the memory of the
+/// instance, from within a host function. The 16 bytes are copied as
+/// they are, see `wasmer_instance_context_memory_read_v128()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the 16 bytes are not all inside the memory, in which case the memory
+/// is left untouched. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_memory_write_v128(
+    ctx: *mut wasmer_instance_context_t,
+    offset: u32,
+    value: *const [u8; 16],
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if value.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "value ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        match context_memory_range(ctx, offset, V128_LENGTH) {
+            Ok(bytes) => {
+                bytes.copy_from_slice(&*value);
+                wasmer_result_t::WASMER_OK
+            }
+            Err(error) => {
+                update_last_error(error);
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
+/// Sets the `len` bytes at `offset` in the memory of the instance to
+/// `value`, from within a host function, like the `memory.fill`
+/// instruction.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the bytes are not all inside the memory, in which case the memory is
+/// left untouched. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_memory_fill(
+    ctx: *mut wasmer_instance_context_t,
+    offset: u32,
+    value: u8,
+    len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let bytes = match context_memory_range(ctx, offset, len as usize) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        for byte in bytes.iter_mut() {
+            *byte = value;
+        }
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Copies the `len` bytes at `src` in the memory of the instance to
+/// `dst`, from within a host function, like the `memory.copy`
+/// instruction. The ranges may overlap: the bytes are copied as if
+/// through an intermediate buffer, like with `memmove`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the source or the destination bytes are not all inside the memory,
+/// in which case the memory is left untouched. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// void shift(wasmer_instance_context_t *context, int32_t pointer, int32_t length) {
+///     // Moves the bytes one byte up, the ranges overlap.
+///     wasmer_instance_context_memory_copy(context, pointer + 1, pointer, length);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_memory_copy(
+    ctx: *mut wasmer_instance_context_t,
+    dst: u32,
+    src: u32,
+    len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        // The ranges may overlap, so they are only checked, and copied
+        // within a single slice of the whole memory.
+        let memory = context_memory_range(ctx, dst, len as usize)
+            .and_then(|_| context_memory_range(ctx, src, len as usize))
+            .and_then(|_| {
+                let memory_length = wasmer_instance_context_memory_length(ctx, 0) as usize;
+                context_memory_range(ctx, 0, memory_length)
+            });
+
+        match memory {
+            Ok(memory) => {
+                let src = src as usize;
+                memory.copy_within(src..src + len as usize, dst as usize);
+                wasmer_result_t::WASMER_OK
+            }
+            Err(error) => {
+                update_last_error(error);
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
+/// Reads the UTF-8 string of `len` bytes at `offset` in the memory of
+/// the instance, from within a host function, and stores a
+/// NUL-terminated copy of it in `out`.
+///
+/// The copy is owned by the caller, who must free it with
+/// `wasmer_string_destroy()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, in which case
+/// `out` is left untouched, with the error code:
+///
+/// * `WASMER_ERROR_CODE_INVALID_ARGUMENT` if the bytes are not all
+///   inside the memory, or if they contain a NUL byte,
+/// * `WASMER_ERROR_CODE_UTF8` if they are not valid UTF-8.
+///
+/// Use `wasmer_last_error_code`, `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get the error.
+///
+/// Example:
+///
+/// ```c
+/// void log_message(wasmer_instance_context_t *context, int32_t pointer, int32_t length) {
+///     char *message = NULL;
+///
+///     if (wasmer_instance_context_read_string(context, pointer, length, &message) != WASMER_OK) {
+///         wasmer_trap(context, "invalid message");
+///     }
+///
+///     printf("%s\n", message);
+///     wasmer_string_destroy(message);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_read_string(
+    ctx: *const wasmer_instance_context_t,
+    offset: u32,
+    len: u32,
+    out: *mut *mut c_char,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "out ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let string = context_memory_range(ctx, offset, len as usize).and_then(|bytes| {
+            let string = str::from_utf8(bytes).map_err(|error| CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                msg: format!(
+                    "the string at offset {} is not valid UTF-8: {}",
+                    offset, error
+                ),
+            })?;
+            CString::new(string).map_err(|_| CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!("the string at offset {} contains a NUL byte", offset),
+            })
+        });
+
+        match string {
+            Ok(string) => {
+                *out = string.into_raw();
+                wasmer_result_t::WASMER_OK
+            }
+            Err(error) => {
+                update_last_error(error);
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
+/// Frees a string returned by `wasmer_instance_context_read_string()`.
+///
+/// If `string` is a null pointer, this function does nothing.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_string_destroy(string: *mut c_char) {
+    if !string.is_null() {
+        CString::from_raw(string);
+    }
 }
 
 /// Gets the data that can be hold by an instance.
@@ -698,6 +4206,48 @@ pub extern "C" fn wasmer_instance_context_data_get(
     ctx.data
 }
 
+/// Gets the second data slot of an instance context.
+///
+/// This function is complementary of
+/// `wasmer_instance_context_data2_set()`. Please read its
+/// documentation.
+///
+/// This function returns a null pointer if `ctx` is a null pointer, or
+/// if the second slot was never set.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_instance_context_data2_get(
+    ctx: *const wasmer_instance_context_t,
+) -> *mut c_void {
+    wasmer_instance_context_data_get_keyed(ctx, WASMER_CONTEXT_DATA2_KEY)
+}
+
+/// Gets the data of an instance context under the given key.
+///
+/// This function is complementary of
+/// `wasmer_instance_context_data_set_keyed()`. Please read its
+/// documentation.
+///
+/// This function returns a null pointer if `ctx` is a null pointer, or
+/// if no data is set under `key`.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub extern "C" fn wasmer_instance_context_data_get_keyed(
+    ctx: *const wasmer_instance_context_t,
+    key: u32,
+) -> *mut c_void {
+    if ctx.is_null() {
+        return ptr::null_mut() as _;
+    }
+
+    let ctx = unsafe { &*(ctx as *const Ctx) };
+
+    ctx.keyed_data
+        .get(&key)
+        .cloned()
+        .unwrap_or_else(ptr::null_mut)
+}
+
 /// Frees memory for the given `wasmer_instance_t`.
 ///
 /// Check the `wasmer_instantiate()` function to get a complete
@@ -1,24 +1,21 @@
 use crate::{
-    error::{update_last_error, CApiError},
-    instance::{wasmer_instance_t, wasmer_compilation_options_t, CompilationOptions, prepare_middleware_chain_generator, get_compiler},
+    allocator::into_c_buffer,
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    import::cached_import_object,
+    instance::{
+        check_required_loop_costs, get_compiler, instantiate_metered,
+        prepare_middleware_chain_generator, read_compilation_options, start_failure_result,
+        wasmer_compilation_options_t, wasmer_instance_t,
+    },
+    version::{add_version_stamp, strip_version_stamp},
     wasmer_result_t,
 };
 
-use rkyv::{
-    Deserialize as RkyvDeserialize,
-    ser::Serializer,
-    ser::serializers::AllocSerializer,
-};
+use rkyv::{ser::serializers::AllocSerializer, ser::Serializer, Deserialize as RkyvDeserialize};
 
-use wasmer_runtime_core::{
-    cache::{Artifact, Error as CacheError},
-    import::ImportObject,
-};
+use crate::module::check_initial_memory_pages;
 use std::slice;
-use crate::import::GLOBAL_IMPORT_OBJECT;
-
-#[cfg(not(feature = "cranelift-backend"))]
-use wasmer_middleware_common::metering;
+use wasmer_runtime_core::cache::{Artifact, Error as CacheError};
 
 #[cfg(feature = "singlepass-backend")]
 #[allow(clippy::cast_ptr_alignment)]
@@ -44,7 +41,21 @@ pub unsafe fn is_rkyv_enabled() -> bool {
     false
 }
 
-
+/// Serializes the module of an instance into cache bytes, which can be
+/// given to `wasmer_instance_from_cache()` later on.
+///
+/// The cache bytes are stamped with the version of the runtime, see
+/// `wasmer_version()`, and with the version of their serialization
+/// format, and only this version can load them.
+///
+/// The cache bytes are owned by the caller, and must be freed with
+/// `wasmer_buffer_destroy()`, or with the `free` function registered
+/// with `wasmer_set_allocator()` if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
 pub unsafe extern "C" fn wasmer_instance_cache(
@@ -52,41 +63,59 @@ pub unsafe extern "C" fn wasmer_instance_cache(
     cache_bytes: *mut *const u8,
     cache_len: *mut u32,
 ) -> wasmer_result_t {
-    if instance.is_null() {
-        update_last_error(CApiError {
-            msg: "null instance".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-
-    let instance = &mut *(instance as *mut wasmer_runtime::Instance);
-    let module = instance.module();
-
-    match module.cache() {
-        Err(error) => {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
             update_last_error(CApiError {
-                msg: format!("wasmer_instance_cache: artifact creation failed: {:?}", error),
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "null instance".to_string(),
             });
             return wasmer_result_t::WASMER_ERROR;
         }
-        Ok(artifact) => {
-            match serialize_artifact(artifact) {
+
+        let instance = &mut *(instance as *mut wasmer_runtime::Instance);
+        let module = instance.module();
+
+        match module.cache() {
+            Err(error) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                    msg: format!(
+                        "wasmer_instance_cache: artifact creation failed: {:?}",
+                        error
+                    ),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+            Ok(artifact) => match serialize_artifact(artifact) {
                 Err(error) => {
                     update_last_error(CApiError {
-                        msg: format!("wasmer_instance_cache: artifact serialization failed: {:?}", error),
+                        code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                        msg: format!(
+                            "wasmer_instance_cache: artifact serialization failed: {:?}",
+                            error
+                        ),
                     });
                     return wasmer_result_t::WASMER_ERROR;
                 }
                 Ok(bytes) => {
-                    *cache_bytes = bytes.as_ptr();
-                    *cache_len = bytes.len() as u32;
-                    std::mem::forget(bytes);
+                    let bytes = add_version_stamp(&bytes).into_boxed_slice();
+                    let bytes_len = bytes.len();
+                    let buffer = into_c_buffer(bytes);
+                    if buffer.is_null() {
+                        update_last_error(CApiError {
+                            code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                            msg: "wasmer_instance_cache: buffer allocation failed".to_string(),
+                        });
+                        return wasmer_result_t::WASMER_ERROR;
+                    }
+                    *cache_bytes = buffer;
+                    *cache_len = bytes_len as u32;
                 }
-            }
-        }
-    };
+            },
+        };
 
-    wasmer_result_t::WASMER_OK
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 #[allow(clippy::cast_ptr_alignment)]
@@ -97,52 +126,93 @@ pub unsafe extern "C" fn wasmer_instance_from_cache(
     cache_len: u32,
     options: *const wasmer_compilation_options_t,
 ) -> wasmer_result_t {
-    if cache_bytes.is_null() {
-        update_last_error(CApiError {
-            msg: "cache bytes ptr is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-
-    let bytes: &[u8] = slice::from_raw_parts(cache_bytes, cache_len as usize);
-    let options: &CompilationOptions = &*(options as *const CompilationOptions);
-    let compiler_chain_generator = prepare_middleware_chain_generator(&options);
-    let compiler = get_compiler(compiler_chain_generator);
-
-    let artifact = match deserialize_artifact(bytes) {
-        Ok(deserialized_artifact) => deserialized_artifact,
-        Err(_) => {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if cache_bytes.is_null() {
             update_last_error(CApiError {
-                msg: "wasmer_instance_from_cache: artifact deserialization failed".to_string(),
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "cache bytes ptr is null".to_string(),
             });
             return wasmer_result_t::WASMER_ERROR;
         }
-    };
 
-    let new_module = match wasmer_runtime_core::load_cache_with(artifact, &compiler) {
-        Ok(deserialized_module) => {
-            deserialized_module
+        let bytes: &[u8] = slice::from_raw_parts(cache_bytes, cache_len as usize);
+        let bytes = match strip_version_stamp(bytes) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        let options = match read_compilation_options(options) {
+            Ok(options) => options,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        if let Err(error) = check_required_loop_costs(&options) {
+            update_last_error(error);
+            return wasmer_result_t::WASMER_ERROR;
         }
-        Err(_) => {
+        let compiler_chain_generator = prepare_middleware_chain_generator(&options);
+        let compiler = get_compiler(compiler_chain_generator);
+
+        let artifact = match deserialize_artifact(bytes) {
+            Ok(deserialized_artifact) => deserialized_artifact,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: "wasmer_instance_from_cache: artifact deserialization failed".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let new_module = match wasmer_runtime_core::load_cache_with(artifact, &compiler) {
+            Ok(deserialized_module) => deserialized_module,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: "wasmer_instance_from_cache: artifact instantiation into module failed"
+                        .to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        if options.reject_start_function && new_module.info().start_func.is_some() {
             update_last_error(CApiError {
-                msg: "wasmer_instance_from_cache: artifact instantiation into module failed".to_string(),
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: "wasmer_instance_from_cache: module has a start function".to_string(),
             });
             return wasmer_result_t::WASMER_ERROR;
         }
-    };
-
-    let import_object: &mut ImportObject = &mut *(GLOBAL_IMPORT_OBJECT as *mut ImportObject);
-    let result_instantiation = new_module.instantiate(&import_object);
-    let mut new_instance = match result_instantiation {
-        Ok(instance) => instance,
-        Err(error) => {
-            update_last_error(error);
+        if let Err(msg) =
+            check_initial_memory_pages(new_module.info(), options.max_initial_memory_pages)
+        {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!("wasmer_instance_from_cache: {}", msg),
+            });
             return wasmer_result_t::WASMER_ERROR;
         }
-    };
-    metering::set_points_limit(&mut new_instance, options.gas_limit);
-    *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
-    wasmer_result_t::WASMER_OK
+
+        let mut start_gas_used = 0;
+        let import_object = cached_import_object();
+        let new_instance =
+            match instantiate_metered(&new_module, &import_object, &options, &mut start_gas_used) {
+                Ok(instance) => instance,
+                Err(error) => {
+                    let result = start_failure_result(&error);
+                    update_last_error(CApiError {
+                        code: error.code,
+                        msg: format!("wasmer_instance_from_cache: {}", error.msg),
+                    });
+                    return result;
+                }
+            };
+        *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 #[cfg(feature = "singlepass-backend")]
@@ -166,7 +236,9 @@ fn serialize_artifact_with_rkyv(artifact: Artifact) -> Result<Box<[u8]>, CacheEr
     serializer.serialize_value(&artifact).unwrap();
     let serialized = serializer.into_serializer().into_inner().into_boxed_slice();
     if serialized.is_empty() {
-        return Err(CacheError::SerializeError("rkyv serialization failed".to_string()));
+        return Err(CacheError::SerializeError(
+            "rkyv serialization failed".to_string(),
+        ));
     }
 
     Ok(serialized)
@@ -197,7 +269,8 @@ fn deserialize_artifact(bytes: &[u8]) -> Result<Artifact, CacheError> {
 #[cfg(feature = "singlepass-backend")]
 fn deserialize_artifact_with_rkyv(bytes: &[u8]) -> Result<Artifact, CacheError> {
     let archived = unsafe { rkyv::archived_root::<Artifact>(&bytes[..]) };
-    let artifact: Artifact = RkyvDeserialize::<Artifact, _>::deserialize(archived, &mut rkyv::Infallible).unwrap();
+    let artifact: Artifact =
+        RkyvDeserialize::<Artifact, _>::deserialize(archived, &mut rkyv::Infallible).unwrap();
     Ok(artifact)
 }
 
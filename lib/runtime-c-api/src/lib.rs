@@ -93,6 +93,13 @@
 extern crate wasmer_runtime;
 extern crate wasmer_runtime_core;
 
+pub mod allocator;
+pub mod backend;
+pub mod batch;
+pub mod compile_streaming;
+#[cfg(feature = "metering")]
+pub mod compile_violations;
+pub mod diagnostics;
 pub mod error;
 pub mod export;
 pub mod global;
@@ -104,6 +111,7 @@ pub mod memory;
 pub mod metering;
 
 pub mod instance_cache;
+pub mod opcode_trace;
 pub mod runtime_breakpoints;
 pub mod signals;
 
@@ -113,7 +121,9 @@ pub mod table;
 // if you want to change the meaning of these `cfg`s in the header file.
 #[cfg(all(not(target_family = "windows"), target_arch = "x86_64"))]
 pub mod trampoline;
+pub mod trap;
 pub mod value;
+pub mod version;
 
 /// The `wasmer_result_t` enum is a type that represents either a
 /// success, or a failure.
@@ -125,6 +135,24 @@ pub enum wasmer_result_t {
 
     /// Represents a failure.
     WASMER_ERROR = 2,
+
+    /// Represents a call interrupted by
+    /// `wasmer_instance_request_interrupt()`, which is a failure too.
+    WASMER_INTERRUPTED = 3,
+
+    /// Represents a compilation aborted because it exceeded the
+    /// `compile_timeout_ms` compilation option, which is a failure too.
+    WASMER_TIMEOUT = 4,
+
+    /// Represents a call which trapped because its nested calls went
+    /// deeper than the `max_call_depth` compilation option, e.g. with a
+    /// too deep recursion, which is a failure too.
+    WASMER_STACK_OVERFLOW = 5,
+
+    /// Represents a call, or a start function run at instantiation,
+    /// which ran out of gas, see `BREAKPOINT_VALUE_OUT_OF_GAS`, which is
+    /// a failure too.
+    WASMER_OUT_OF_GAS = 6,
 }
 
 /// The `wasmer_limits_t` struct is a type that describes a memory
@@ -1,7 +1,7 @@
 //! Create, read, write, grow, destroy memory of an instance.
 
 use crate::{
-    error::{update_last_error, CApiError},
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
     wasmer_limits_t, wasmer_result_t,
 };
 use std::{cell::Cell, ptr};
@@ -66,31 +66,34 @@ pub unsafe extern "C" fn wasmer_memory_new(
     memory: *mut *mut wasmer_memory_t,
     limits: wasmer_limits_t,
 ) -> wasmer_result_t {
-    let max = if limits.max.has_some {
-        Some(Pages(limits.max.some))
-    } else {
-        None
-    };
-    let desc = MemoryDescriptor::new(Pages(limits.min), max, false);
-    let new_desc = match desc {
-        Ok(desc) => desc,
-        Err(error) => {
-            update_last_error(CApiError {
-                msg: error.to_string(),
-            });
-            return wasmer_result_t::WASMER_ERROR;
-        }
-    };
-    let result = Memory::new(new_desc);
-    let new_memory = match result {
-        Ok(memory) => memory,
-        Err(error) => {
-            update_last_error(error);
-            return wasmer_result_t::WASMER_ERROR;
-        }
-    };
-    *memory = Box::into_raw(Box::new(new_memory)) as *mut wasmer_memory_t;
-    wasmer_result_t::WASMER_OK
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let max = if limits.max.has_some {
+            Some(Pages(limits.max.some))
+        } else {
+            None
+        };
+        let desc = MemoryDescriptor::new(Pages(limits.min), max, false);
+        let new_desc = match desc {
+            Ok(desc) => desc,
+            Err(error) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: error.to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        let result = Memory::new(new_desc);
+        let new_memory = match result {
+            Ok(memory) => memory,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        *memory = Box::into_raw(Box::new(new_memory)) as *mut wasmer_memory_t;
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 /// Grows a memory by the given number of pages (of 65Kb each).
@@ -112,25 +115,28 @@ pub unsafe extern "C" fn wasmer_memory_new(
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
 pub extern "C" fn wasmer_memory_grow(memory: *mut wasmer_memory_t, delta: u32) -> wasmer_result_t {
-    if memory.is_null() {
-        update_last_error(CApiError {
-            msg: "`memory` is NULL.".to_string(),
-        });
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if memory.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "`memory` is NULL.".to_string(),
+            });
 
-        return wasmer_result_t::WASMER_ERROR;
-    }
+            return wasmer_result_t::WASMER_ERROR;
+        }
 
-    let memory = unsafe { &*(memory as *mut Memory) };
-    let delta_result = memory.grow(Pages(delta));
+        let memory = unsafe { &*(memory as *mut Memory) };
+        let delta_result = memory.grow(Pages(delta));
 
-    match delta_result {
-        Ok(_) => wasmer_result_t::WASMER_OK,
-        Err(grow_error) => {
-            update_last_error(grow_error);
+        match delta_result {
+            Ok(_) => wasmer_result_t::WASMER_OK,
+            Err(grow_error) => {
+                update_last_error(grow_error);
 
-            wasmer_result_t::WASMER_ERROR
+                wasmer_result_t::WASMER_ERROR
+            }
         }
-    }
+    })
 }
 
 /// Reads the current length (in pages) of the given memory.
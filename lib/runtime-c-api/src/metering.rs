@@ -1,16 +1,27 @@
 use crate::{
-    error::{update_last_error, CApiError},
-    instance::wasmer_instance_t,
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    instance::{
+        get_compiler_config, read_compilation_options, start_timer, wasmer_compilation_options_t,
+        wasmer_compile_stats_t, wasmer_instance_call, wasmer_instance_context_t, wasmer_instance_t,
+        write_compile_stats,
+    },
     module::wasmer_module_t,
+    value::wasmer_value_t,
     wasmer_result_t,
 };
-use std::slice;
+use libc::{c_char, c_int};
+use std::{ptr, slice};
+use wasmer_runtime::Ctx;
 
 #[cfg(feature = "metering")]
-use wasmer_runtime_core::backend::Compiler;
+use crate::instance::gas_metering_options_hash;
+#[cfg(feature = "metering")]
+use wasmer_runtime_core::backend::{Compiler, CompilerConfig, Features};
+#[cfg(feature = "metering")]
+use wasmer_runtime_core::codegen::validating_parser_config;
 
 #[cfg(not(feature = "cranelift-backend"))]
-use wasmer_middleware_common::metering;
+use wasmer_middleware_common::{metering, metering_costs, runtime_breakpoints};
 
 pub const OPCODE_COUNT: usize = 448;
 pub static mut OPCODE_COSTS: [u32; OPCODE_COUNT] = [0; OPCODE_COUNT];
@@ -18,12 +29,38 @@ pub static mut OPCODE_COSTS: [u32; OPCODE_COUNT] = [0; OPCODE_COUNT];
 #[allow(clippy::cast_ptr_alignment)]
 #[cfg(feature = "metering")]
 #[no_mangle]
-pub unsafe extern "C" fn wasmer_set_opcode_costs(
-    opcode_costs_pointer: *const u32,
-) {
+pub unsafe extern "C" fn wasmer_set_opcode_costs(opcode_costs_pointer: *const u32) {
     OPCODE_COSTS.copy_from_slice(slice::from_raw_parts(opcode_costs_pointer, OPCODE_COUNT));
 }
 
+/// Checks that the opcode costs set by `wasmer_set_opcode_costs()`
+/// charge the opcodes which let the code run again, i.e. `loop`, `br`,
+/// `br_if`, `br_table`, `call` and `call_indirect`: the gas limit does
+/// not bound the execution of a module if any of them costs nothing.
+/// The `require_loop_costs` compilation option applies this check.
+///
+/// Returns `wasmer_result_t::WASMER_OK` when the costs bound the execution.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` otherwise, with the
+/// `WASMER_ERROR_CODE_INVALID_ARGUMENT` error code. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_check_opcode_costs() -> wasmer_result_t {
+    catch_panic(
+        wasmer_result_t::WASMER_ERROR,
+        || match metering_costs::check_loop_enabling_costs(&OPCODE_COSTS) {
+            Ok(()) => wasmer_result_t::WASMER_OK,
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg,
+                });
+                wasmer_result_t::WASMER_ERROR
+            }
+        },
+    )
+}
 
 // returns gas used
 #[allow(clippy::cast_ptr_alignment)]
@@ -68,49 +105,892 @@ pub unsafe extern "C" fn wasmer_instance_set_points_limit(
     metering::set_points_limit(instance, limit)
 }
 
-
-/// Creates a new Module with gas limit from the given wasm bytes.
+/// Reads the gas limit of the instance into `limit_out`, i.e. the
+/// `gas_limit` compilation option it was instantiated with, or the last
+/// limit set with `wasmer_instance_set_points_limit()`.
 ///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
 ///
-/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
-/// and `wasmer_last_error_message` to get an error message.
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
 #[allow(clippy::cast_ptr_alignment)]
-#[cfg(feature = "metering")]
 #[no_mangle]
-pub unsafe extern "C" fn wasmer_compile_with_gas_metering(
-    module: *mut *mut wasmer_module_t,
-    wasm_bytes: *mut u8,
-    wasm_bytes_len: u32,
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_get_points_limit(
+    instance: *const wasmer_instance_t,
+    limit_out: *mut u64,
 ) -> wasmer_result_t {
-    if module.is_null() {
+    if instance.is_null() || limit_out.is_null() {
         update_last_error(CApiError {
-            msg: "module is null".to_string(),
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "instance or limit_out ptr is null".to_string(),
         });
         return wasmer_result_t::WASMER_ERROR;
     }
-    if wasm_bytes.is_null() {
+
+    let instance = &*(instance as *const wasmer_runtime::Instance);
+    *limit_out = metering::get_points_limit(instance);
+
+    wasmer_result_t::WASMER_OK
+}
+
+/// Gets the number of instructions executed by the last call of the
+/// instance, counted against the `hard_instruction_limit` compilation
+/// option independently of the gas.
+///
+/// When the limit is exceeded, the call traps whatever its gas limit,
+/// and the runtime breakpoint value of the instance is set to
+/// `BREAKPOINT_VALUE_INSTRUCTION_LIMIT` instead of
+/// `BREAKPOINT_VALUE_OUT_OF_GAS`, see
+/// `wasmer_instance_get_runtime_breakpoint_value()`, and the error code
+/// of the call is `WASMER_ERROR_CODE_INSTRUCTION_LIMIT`. The count is reset
+/// by each call, and by the start function.
+///
+/// This function returns 0 if `instance` is a null pointer, or if the
+/// instance was created without a hard instruction limit.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_get_instructions_used(
+    instance: *const wasmer_instance_t,
+) -> u64 {
+    if instance.is_null() {
+        return 0;
+    }
+    let instance = &*(instance as *const wasmer_runtime::Instance);
+    metering::get_instructions_used(instance)
+}
+
+/// The `wasmer_gas_breakdown_t` struct receives the gas used by the last
+/// call of an instance per category of opcodes, see
+/// `wasmer_instance_get_gas_breakdown()`.
+#[repr(C)]
+#[cfg(feature = "metering")]
+pub struct wasmer_gas_breakdown_t {
+    /// The gas used by the constants, locals, parametric, numeric,
+    /// conversion, reference and SIMD lane opcodes.
+    pub arithmetic: u64,
+
+    /// The gas used by the globals, loads, stores, memory and table
+    /// opcodes, atomics, and by the allocation of the locals.
+    pub memory: u64,
+
+    /// The gas used by the blocks, branches, returns, `nop` and
+    /// `unreachable`.
+    pub control: u64,
+
+    /// The gas used by the direct and indirect calls.
+    pub call: u64,
+}
+
+/// Reads the gas used by the last call of the instance, broken down by
+/// category of opcodes, into `breakdown`, e.g. to check whether the
+/// opcode costs are balanced for a representative workload.
+///
+/// Only the instances of modules compiled with the `gas_breakdown`
+/// compilation option, along with `metering`, count the gas per
+/// category: the counting adds to the cost of each charge of the
+/// metering. The breakdown of other instances is all zeros.
+///
+/// The breakdown is reset by each call, by the start function, and by
+/// `wasmer_instance_reset()`. It includes the gas charged for the block
+/// which ran out of gas, if any, and the gas used by the host functions
+/// with `wasmer_instance_context_use_gas()` is not part of any category,
+/// so its total may differ from `wasmer_instance_get_points_used()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_gas_breakdown_t breakdown;
+///
+/// if (wasmer_instance_get_gas_breakdown(instance, &breakdown) == WASMER_OK) {
+///     printf("Calls: %llu\n", breakdown.call);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_get_gas_breakdown(
+    instance: *const wasmer_instance_t,
+    breakdown: *mut wasmer_gas_breakdown_t,
+) -> wasmer_result_t {
+    use metering_costs::GasCategory;
+
+    if instance.is_null() || breakdown.is_null() {
         update_last_error(CApiError {
-            msg: "wasm bytes is null".to_string(),
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "instance or breakdown ptr is null".to_string(),
         });
         return wasmer_result_t::WASMER_ERROR;
     }
 
-    let compiler = get_metered_compiler();
+    let instance = &*(instance as *const wasmer_runtime::Instance);
+    let points = metering::get_gas_breakdown(instance);
+    *breakdown = wasmer_gas_breakdown_t {
+        arithmetic: points[GasCategory::Arithmetic as usize],
+        memory: points[GasCategory::Memory as usize],
+        control: points[GasCategory::Control as usize],
+        call: points[GasCategory::Call as usize],
+    };
+
+    wasmer_result_t::WASMER_OK
+}
+
+/// The `wasmer_function_gas_t` struct receives the gas used by a
+/// function during the last call, see
+/// `wasmer_instance_get_per_function_gas()`.
+#[repr(C)]
+#[cfg(feature = "metering")]
+pub struct wasmer_function_gas_t {
+    /// The index of the function, counting the imported functions, as
+    /// expected by `wasmer_module_function_name()`.
+    pub func_index: u32,
+
+    /// The gas charged in the code of the function itself, excluding
+    /// the functions it called.
+    pub gas: u64,
+}
+
+/// Reads the gas used by the last call to `wasmer_instance_call()` on
+/// the instance, per function where it was spent, e.g. to find the
+/// hotspots of a contract.
+///
+/// Only the instances of modules compiled with the `per_function_gas`
+/// compilation option, along with `metering`, attribute the gas to the
+/// functions: each charge of the metering then calls into the runtime,
+/// which makes the calls much slower. Other instances record nothing.
+///
+/// The functions are sorted by index, and the functions which used no
+/// gas are left out. Like the breakdown of
+/// `wasmer_instance_get_gas_breakdown()`, the gas includes the block
+/// which ran out of gas, if any, and excludes the gas used by the host
+/// functions.
+///
+/// At most `out_len` entries are copied into `out`. If `out` is a null
+/// pointer, nothing is copied, which can be used to allocate a buffer
+/// of the right size.
+///
+/// This function returns the number of functions which used gas,
+/// whatever the number of copied entries, or -1 if `instance` is a
+/// null pointer.
+///
+/// Example:
+///
+/// ```c
+/// int functions_len = wasmer_instance_get_per_function_gas(instance, NULL, 0);
+/// wasmer_function_gas_t *functions = malloc(sizeof(wasmer_function_gas_t) * functions_len);
+/// wasmer_instance_get_per_function_gas(instance, functions, functions_len);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_get_per_function_gas(
+    instance: *const wasmer_instance_t,
+    out: *mut wasmer_function_gas_t,
+    out_len: u32,
+) -> c_int {
+    if instance.is_null() {
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "instance ptr is null".to_string(),
+        });
+        return -1;
+    }
+
+    let instance = &*(instance as *const wasmer_runtime::Instance);
+    let functions = metering::get_per_function_gas(instance);
+
+    if !out.is_null() {
+        let out = slice::from_raw_parts_mut(out, out_len as usize);
+        for (slot, &(func_index, gas)) in out.iter_mut().zip(functions.iter()) {
+            *slot = wasmer_function_gas_t { func_index, gas };
+        }
+    }
+
+    functions.len() as c_int
+}
+
+/// Sets whether the gas used of the instance, when a call runs out of
+/// gas, leaves out the cost of the failing block, i.e. of the operators
+/// charged together with the failed gas check.
+///
+/// By default, the cost of the failing block is charged, so the gas
+/// used exceeds the gas limit. With `exclude` set, the gas used is the
+/// cost of the blocks which ran, which does not exceed the limit. Either
+/// way, the reported gas used is deterministic: it only depends on the
+/// code, the opcode costs and the gas limit. The `charge_full_on_oog`
+/// compilation option takes precedence, and charges the gas limit.
+///
+/// The same applies to `wasmer_instance_context_use_gas()`, whose
+/// `amount` is the failing charge.
+///
+/// Leaving the failing block out does not give the gas back: a call of
+/// a sequence sharing its budget which runs out of gas still ends the
+/// sequence, see `wasmer_instance_call_shared_gas()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_set_exclude_failing_charge_on_oog(
+    instance: *mut wasmer_instance_t,
+    exclude: bool,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &mut *(instance as *mut wasmer_runtime::Instance);
+        metering::set_exclude_failing_charge_on_out_of_gas(instance, exclude);
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Reads the gas left to the running call into `gas_left`, from within
+/// a host function. This is the gas limit of the instance minus the
+/// gas used so far, or 0 if the limit is already reached.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the module of the instance was not compiled with metering, i.e. with
+/// the `metering` compilation option or by
+/// `wasmer_compile_with_gas_metering()`, however it was instantiated.
+/// Use `wasmer_last_error_length` and `wasmer_last_error_message` to get
+/// an error message.
+///
+/// Example:
+///
+/// ```c
+/// void expensive_operation(wasmer_instance_context_t *context) {
+///     uint64_t gas_left = 0;
+///
+///     if (wasmer_instance_context_gas_left(context, &gas_left) != WASMER_OK || gas_left < EXPENSIVE_OPERATION_COST) {
+///         return;
+///     }
+///
+///     // Do the expensive operation.
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_context_gas_left(
+    ctx: *const wasmer_instance_context_t,
+    gas_left: *mut u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if ctx.is_null() || gas_left.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "ctx and gas_left ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let ctx = &*(ctx as *const Ctx);
 
-    let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
-    let result = wasmer_runtime_core::compile_with(bytes, &compiler);
-    let new_module = match result {
-        Ok(instance) => instance,
-        Err(_) => {
+        if !metering::is_metering_enabled_ctx(ctx) {
             update_last_error(CApiError {
-                msg: "compile error".to_string(),
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UNSUPPORTED,
+                msg: "metering is not enabled for this instance".to_string(),
             });
             return wasmer_result_t::WASMER_ERROR;
         }
-    };
-    *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
-    wasmer_result_t::WASMER_OK
+
+        *gas_left =
+            metering::get_points_limit_ctx(ctx).saturating_sub(metering::get_points_used_ctx(ctx));
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Charges `amount` gas to the running call, from within a host
+/// function, e.g. for the native work done by the host function.
+///
+/// If the gas used exceeds the gas limit of the instance, the call
+/// traps with an out of gas runtime breakpoint as soon as the host
+/// function returns, the same way it would for wasm code. The gas used
+/// is then charged as configured for the instance, see
+/// `wasmer_instance_set_exclude_failing_charge_on_oog()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` if the gas limit is exceeded,
+/// in which case the host function should return without doing its
+/// work, or upon failure, including when the module of the instance was
+/// not compiled with metering, see `wasmer_instance_context_gas_left()`.
+/// Use `wasmer_last_error_length` and `wasmer_last_error_message` to get
+/// an error message.
+///
+/// Example:
+///
+/// ```c
+/// void hash(wasmer_instance_context_t *context, int32_t pointer, int32_t length) {
+///     if (wasmer_instance_context_use_gas(context, length * GAS_PER_HASHED_BYTE) != WASMER_OK) {
+///         return;
+///     }
+///
+///     // Hash the bytes.
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_context_use_gas(
+    ctx: *mut wasmer_instance_context_t,
+    amount: u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if ctx.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "ctx ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let ctx = &mut *(ctx as *mut Ctx);
+
+        if !metering::is_metering_enabled_ctx(ctx) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UNSUPPORTED,
+                msg: "metering is not enabled for this instance".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let previous_points_used = metering::get_points_used_ctx(ctx);
+        let points_used = previous_points_used.saturating_add(amount);
+        metering::set_points_used_ctx(ctx, points_used);
+
+        let points_limit = metering::get_points_limit_ctx(ctx);
+        if points_used > points_limit {
+            if metering::is_exclude_failing_charge_on_out_of_gas_ctx(ctx) {
+                metering::set_points_used_ctx(ctx, previous_points_used);
+            }
+            if metering::is_charge_full_on_out_of_gas_ctx(ctx) {
+                metering::set_points_used_ctx(ctx, points_limit);
+            }
+            runtime_breakpoints::set_runtime_breakpoint_value_ctx(
+                ctx,
+                runtime_breakpoints::BREAKPOINT_VALUE_OUT_OF_GAS,
+            );
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_OUT_OF_GAS,
+                msg: "out of gas".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Refunds `amount` gas to the running call, from within a host
+/// function, e.g. for a host operation freeing storage, which gives
+/// back gas to the rest of the call.
+///
+/// The refund is subtracted from the gas used by the instance, and is
+/// capped at the gas used by the running call so far: a call cannot get
+/// back more gas than it spent, so the gas used never drops below the
+/// gas used when the call started. Precisely, the gas used becomes
+/// `max(gas_used - amount, gas_used_at_call_start)`, and the refund is
+/// lost beyond that point. In particular, a refund never gives back the
+/// gas of the previous calls when they share their budget, see
+/// `wasmer_instance_call_shared_gas()`. A refund does not cancel an out
+/// of gas trap already raised by `wasmer_instance_context_use_gas()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the module of the instance was not compiled with metering, i.e. with
+/// the `metering` compilation option or by
+/// `wasmer_compile_with_gas_metering()`, however it was instantiated.
+/// Use `wasmer_last_error_length` and `wasmer_last_error_message` to get
+/// an error message.
+///
+/// Example:
+///
+/// ```c
+/// void clear_storage(wasmer_instance_context_t *context, int32_t key) {
+///     // Clear the storage at `key`.
+///
+///     wasmer_instance_context_refund_gas(context, CLEAR_STORAGE_REFUND);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_context_refund_gas(
+    ctx: *mut wasmer_instance_context_t,
+    amount: u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if ctx.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "ctx ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let ctx = &mut *(ctx as *mut Ctx);
+
+        if !metering::is_metering_enabled_ctx(ctx) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UNSUPPORTED,
+                msg: "metering is not enabled for this instance".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        metering::refund_points_used_ctx(ctx, amount);
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Calls an exported function like `wasmer_instance_call()`, with the
+/// given gas limit. The gas used and the runtime breakpoint value of the
+/// instance are reset before the call, and the gas consumed by the call
+/// is stored in `gas_used`.
+///
+/// If the call runs out of gas, it traps, the runtime breakpoint value
+/// of the instance is set to `BREAKPOINT_VALUE_OUT_OF_GAS`, see
+/// `wasmer_instance_get_runtime_breakpoint_value()`, and the function
+/// returns `wasmer_result_t::WASMER_OUT_OF_GAS`.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success, and
+/// otherwise the result of `wasmer_instance_call()`, e.g.
+/// `wasmer_result_t::WASMER_ERROR` when the call trapped. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint64_t gas_used = 0;
+/// wasmer_result_t call_result = wasmer_instance_call_with_gas(instance, "sum", params, 2, results, 1, 1000000, &gas_used);
+///
+/// if (call_result == WASMER_OUT_OF_GAS) {
+///     // The call ran out of gas.
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_call_with_gas(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    results: *mut wasmer_value_t,
+    results_len: u32,
+    gas_limit: u64,
+    gas_used: *mut u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if gas_used.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "gas_used ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance_ref = &mut *(instance as *mut wasmer_runtime::Instance);
+        metering::set_points_limit(instance_ref, gas_limit);
+        metering::set_points_used(instance_ref, 0);
+        runtime_breakpoints::set_runtime_breakpoint_value(
+            instance_ref,
+            runtime_breakpoints::BREAKPOINT_VALUE_NO_BREAKPOINT,
+        );
+
+        let result = wasmer_instance_call(instance, name, params, params_len, results, results_len);
+
+        *gas_used = metering::get_points_used(instance_ref);
+
+        match result {
+            wasmer_result_t::WASMER_ERROR
+                if runtime_breakpoints::get_runtime_breakpoint_value(instance_ref)
+                    == runtime_breakpoints::BREAKPOINT_VALUE_OUT_OF_GAS =>
+            {
+                wasmer_result_t::WASMER_OUT_OF_GAS
+            }
+            result => result,
+        }
+    })
+}
+
+/// Calls an exported function like `wasmer_instance_call()`, as one
+/// call of a sequence sharing a single gas budget.
+///
+/// Neither the gas used nor the gas limit of the instance are reset:
+/// the gas limit is the budget of the whole sequence, and the gas
+/// consumed by the call is added to the gas used by the previous calls.
+/// To start a new sequence, reset the runtime breakpoint value with
+/// `wasmer_instance_set_runtime_breakpoint_value()`, set the gas used to
+/// 0 with `wasmer_instance_set_points_used()`, and its budget with
+/// `wasmer_instance_set_points_limit()`.
+///
+/// The gas consumed by this call alone is stored in `gas_used`, unless
+/// it is a null pointer, and the total is read with
+/// `wasmer_instance_get_points_used()`.
+///
+/// If the call runs out of gas, it traps, and the runtime breakpoint
+/// value of the instance is set to `BREAKPOINT_VALUE_OUT_OF_GAS`. The
+/// budget is then exhausted: every following call of the sequence
+/// fails without being called, with the `out of gas` error message, and
+/// stores 0 in `gas_used`. This holds whether or not the cost of the
+/// failing block is charged, see
+/// `wasmer_instance_set_exclude_failing_charge_on_oog()`, i.e. even
+/// though the gas used may be below the limit. The same applies once
+/// the gas used reaches the limit.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_instance_set_points_used(instance, 0);
+/// wasmer_instance_set_points_limit(instance, 1000000);
+///
+/// // Both calls are charged on the same budget of 1000000.
+/// wasmer_instance_call_shared_gas(instance, "transfer", params, 2, results, 1, NULL);
+/// wasmer_instance_call_shared_gas(instance, "notify", params, 2, results, 1, NULL);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_call_shared_gas(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    results: *mut wasmer_value_t,
+    results_len: u32,
+    gas_used: *mut u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance_ref = &mut *(instance as *mut wasmer_runtime::Instance);
+        let points_before = metering::get_points_used(instance_ref);
+        let ran_out_of_gas = runtime_breakpoints::get_runtime_breakpoint_value(instance_ref)
+            == runtime_breakpoints::BREAKPOINT_VALUE_OUT_OF_GAS;
+
+        if ran_out_of_gas || points_before >= metering::get_points_limit(instance_ref) {
+            if !gas_used.is_null() {
+                *gas_used = 0;
+            }
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_OUT_OF_GAS,
+                msg: "out of gas".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        runtime_breakpoints::set_runtime_breakpoint_value(
+            instance_ref,
+            runtime_breakpoints::BREAKPOINT_VALUE_NO_BREAKPOINT,
+        );
+
+        let result = wasmer_instance_call(instance, name, params, params_len, results, results_len);
+
+        if !gas_used.is_null() {
+            *gas_used = metering::get_points_used(instance_ref).saturating_sub(points_before);
+        }
+
+        result
+    })
+}
+
+/// Calls an exported function like `wasmer_instance_call()`, but
+/// without enforcing the gas limit of the instance: the gas keeps
+/// being counted, and the call never traps because of gas exhaustion.
+/// This is useful to estimate the fee of a call which may exceed the
+/// limit.
+///
+/// The gas consumed by the call is stored in `gas_used`, and
+/// `trapped` is set to `true` if the call trapped for any other reason
+/// than gas exhaustion. The gas limit of the instance is restored
+/// before returning.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_instance_call_estimate(
+    instance: *mut wasmer_instance_t,
+    name: *const c_char,
+    params: *const wasmer_value_t,
+    params_len: u32,
+    results: *mut wasmer_value_t,
+    results_len: u32,
+    gas_used: *mut u64,
+    trapped: *mut bool,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if gas_used.is_null() || trapped.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "gas_used and trapped ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance_ref = &mut *(instance as *mut wasmer_runtime::Instance);
+        let points_before = metering::get_points_used(instance_ref);
+
+        let points_limit = metering::get_points_limit(instance_ref);
+
+        // Lifting the limit keeps the points counted, while the out-of-gas
+        // breakpoint can never be reached.
+        metering::set_points_limit(instance_ref, u64::max_value());
+        let result = wasmer_instance_call(instance, name, params, params_len, results, results_len);
+        metering::set_points_limit(instance_ref, points_limit);
+
+        *gas_used = metering::get_points_used(instance_ref).saturating_sub(points_before);
+        *trapped = match result {
+            wasmer_result_t::WASMER_OK => false,
+            wasmer_result_t::WASMER_ERROR
+            | wasmer_result_t::WASMER_INTERRUPTED
+            | wasmer_result_t::WASMER_TIMEOUT
+            | wasmer_result_t::WASMER_STACK_OVERFLOW
+            | wasmer_result_t::WASMER_OUT_OF_GAS => true,
+        };
+
+        result
+    })
+}
+
+/// Gas charged for compiling any module, whatever its content.
+pub const COMPILE_GAS_BASE: u64 = 10_000;
+/// Gas charged for compiling each function body.
+pub const COMPILE_GAS_PER_FUNCTION: u64 = 1_000;
+/// Gas charged for compiling each opcode of the function bodies.
+pub const COMPILE_GAS_PER_OPCODE: u64 = 10;
+/// Gas charged for compiling each local of the function bodies.
+pub const COMPILE_GAS_PER_LOCAL: u64 = 2;
+/// Gas charged for each table defined by the module.
+pub const COMPILE_GAS_PER_TABLE: u64 = 500;
+/// Gas charged for each global defined by the module.
+pub const COMPILE_GAS_PER_GLOBAL: u64 = 100;
+
+/// Estimates the gas cost of compiling the given wasm bytes with the
+/// given options, without compiling them.
+///
+/// The estimate is derived from the structure of the module only (the
+/// number of functions, opcodes, locals, tables and globals), and is
+/// therefore deterministic: the same bytes and options always give
+/// the same estimate. The module is validated against the features
+/// enabled in `options`.
+///
+/// The estimate is stored in `gas_out`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the wasm bytes are invalid. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+#[cfg(feature = "metering")]
+pub unsafe extern "C" fn wasmer_estimate_compile_gas(
+    wasm_bytes: *const u8,
+    wasm_bytes_len: u32,
+    options: *const wasmer_compilation_options_t,
+    gas_out: *mut u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if wasm_bytes.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "wasm bytes ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if options.is_null() || gas_out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "options and gas_out ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let bytes: &[u8] = slice::from_raw_parts(wasm_bytes, wasm_bytes_len as usize);
+        let options = match read_compilation_options(options) {
+            Ok(options) => options,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        match estimate_compile_gas(bytes, &get_compiler_config(&options).features) {
+            Ok(gas) => {
+                *gas_out = gas;
+                wasmer_result_t::WASMER_OK
+            }
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                    msg,
+                });
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
+fn estimate_compile_gas(bytes: &[u8], features: &Features) -> Result<u64, String> {
+    use wasmer_runtime_core::wasmparser::{ParserState, ValidatingParser, WasmDecoder};
+
+    let mut gas = COMPILE_GAS_BASE;
+    let mut parser = ValidatingParser::new(bytes, Some(validating_parser_config(features)));
+
+    loop {
+        let cost = match *parser.read() {
+            ParserState::EndWasm => break,
+            ParserState::Error(ref e) => return Err(format!("{}", e)),
+            ParserState::BeginFunctionBody { .. } => COMPILE_GAS_PER_FUNCTION,
+            ParserState::FunctionBodyLocals { ref locals } => locals
+                .iter()
+                .map(|&(count, _)| u64::from(count).saturating_mul(COMPILE_GAS_PER_LOCAL))
+                .fold(0, u64::saturating_add),
+            ParserState::CodeOperator(_) => COMPILE_GAS_PER_OPCODE,
+            ParserState::TableSectionEntry(_) => COMPILE_GAS_PER_TABLE,
+            ParserState::BeginGlobalSectionEntry(_) => COMPILE_GAS_PER_GLOBAL,
+            _ => 0,
+        };
+        gas = gas.saturating_add(cost);
+    }
+
+    Ok(gas)
+}
+
+/// Creates a new Module with gas limit from the given wasm bytes.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_compile_with_gas_metering(
+    module: *mut *mut wasmer_module_t,
+    wasm_bytes: *mut u8,
+    wasm_bytes_len: u32,
+) -> wasmer_result_t {
+    wasmer_compile_with_gas_metering_and_stats(module, wasm_bytes, wasm_bytes_len, ptr::null_mut())
+}
+
+/// Creates a new Module with gas limit from the given wasm bytes, like
+/// `wasmer_compile_with_gas_metering()`, and stores the compilation
+/// statistics in `stats`, unless it is a null pointer. The
+/// `instantiate_nanos` field of `stats` is set to 0.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_compile_with_gas_metering_and_stats(
+    module: *mut *mut wasmer_module_t,
+    wasm_bytes: *mut u8,
+    wasm_bytes_len: u32,
+    stats: *mut wasmer_compile_stats_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+        if wasm_bytes.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "wasm bytes is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let compiler = get_metered_compiler();
+
+        let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
+        let compile_start = start_timer(stats);
+        let compiler_config = CompilerConfig {
+            compile_options_hash: gas_metering_options_hash(),
+            metered: true,
+            ..Default::default()
+        };
+        let result = wasmer_runtime_core::compile_with_config(bytes, &compiler, compiler_config);
+        let new_module = match result {
+            Ok(instance) => instance,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                    msg: "compile error".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        if let Some(compile_start) = compile_start {
+            write_compile_stats(&mut *stats, &new_module, compile_start);
+        }
+        *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 #[cfg(feature = "metering")]
@@ -126,8 +1006,6 @@ unsafe fn get_metered_compiler() -> impl Compiler {
     #[cfg(feature = "cranelift-backend")]
     use wasmer_clif_backend::CraneliftModuleCodeGenerator as MeteredMCG;
 
-    use wasmer_middleware_common::runtime_breakpoints;
-
     let c: StreamingCompiler<MeteredMCG, _, _, _, _> = StreamingCompiler::new(move || {
         let mut chain = MiddlewareChain::new();
 
@@ -139,7 +1017,6 @@ unsafe fn get_metered_compiler() -> impl Compiler {
     c
 }
 
-
 /*** placeholder implementation if metering feature off ***/
 
 // Without metering, wasmer_compile_with_gas_metering is a copy of wasmer_compile
@@ -150,31 +1027,35 @@ pub unsafe extern "C" fn wasmer_compile_with_gas_metering(
     wasm_bytes: *mut u8,
     wasm_bytes_len: u32,
 ) -> wasmer_result_t {
-    if module.is_null() {
-        update_last_error(CApiError {
-            msg: "module is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-    if wasm_bytes.is_null() {
-        update_last_error(CApiError {
-            msg: "wasm bytes is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-
-    let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
-    // TODO: this implicitly uses default_compiler() is that proper? maybe we override default_compiler
-    let result = wasmer_runtime::compile(bytes);
-    let new_module = match result {
-        Ok(instance) => instance,
-        Err(error) => {
-            update_last_error(error);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module is null".to_string(),
+            });
             return wasmer_result_t::WASMER_ERROR;
         }
-    };
-    *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
-    wasmer_result_t::WASMER_OK
+        if wasm_bytes.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "wasm bytes is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
+        // TODO: this implicitly uses default_compiler() is that proper? maybe we override default_compiler
+        let result = wasmer_runtime::compile(bytes);
+        let new_module = match result {
+            Ok(instance) => instance,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 // returns gas used -- placeholder implementation, when "metering" is disabled
@@ -1,10 +1,13 @@
 //! Compile, validate, instantiate, serialize, and destroy modules.
 
 use crate::{
-    error::{update_last_error, CApiError},
+    allocator::into_c_buffer,
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
     export::wasmer_import_export_kind,
     import::{wasmer_import_object_t, wasmer_import_t},
     instance::wasmer_instance_t,
+    value::wasmer_value_t,
+    version::{add_version_stamp, strip_version_stamp},
     wasmer_byte_array, wasmer_result_t,
 };
 use libc::c_int;
@@ -12,7 +15,31 @@ use std::{collections::HashMap, slice};
 use wasmer_runtime::{
     compile, default_compiler, Global, ImportObject, Instance, Memory, Module, Table,
 };
-use wasmer_runtime_core::{cache::Artifact, export::Export, import::Namespace, load_cache_with};
+use wasmer_runtime_core::{
+    backend::CompilerConfig,
+    cache::{Artifact, WasmHash, WASMER_VERSION_HASH},
+    export::Export,
+    import::Namespace,
+    load_cache_with,
+    module::ModuleInfo,
+    structures::TypedIndex,
+    types::{FuncIndex, GlobalIndex, Initializer, LocalOrImport, MemoryDescriptor, Value},
+};
+
+#[cfg(feature = "metering")]
+use crate::{
+    error::CausedError,
+    instance::{
+        compile_failure_result, compile_with_options, read_compilation_options,
+        wasmer_compilation_options_t,
+    },
+};
+#[cfg(feature = "metering")]
+use libc::c_char;
+#[cfg(feature = "metering")]
+use memmap::Mmap;
+#[cfg(feature = "metering")]
+use std::{ffi::CStr, fs::File, ptr};
 
 #[repr(C)]
 pub struct wasmer_module_t;
@@ -33,24 +60,182 @@ pub unsafe extern "C" fn wasmer_compile(
     wasm_bytes: *mut u8,
     wasm_bytes_len: u32,
 ) -> wasmer_result_t {
-    let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
-    // TODO: this implicitly uses default_compiler() is that proper? a better way to handle metering?
-    let result = compile(bytes);
-    let new_module = match result {
-        Ok(instance) => instance,
-        Err(error) => {
-            update_last_error(error);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let bytes: &[u8] = slice::from_raw_parts_mut(wasm_bytes, wasm_bytes_len as usize);
+        // TODO: this implicitly uses default_compiler() is that proper? a better way to handle metering?
+        let result = compile(bytes);
+        let new_module = match result {
+            Ok(instance) => instance,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Creates a new Module from the given wasm bytes, with the given
+/// compilation options, see `wasmer_instantiate_with_options()`. If
+/// `options` is a null pointer, the module is compiled like with
+/// `wasmer_compile()`.
+///
+/// The `already_validated` option skips the validation of the bytes,
+/// for the bytes already validated with `wasmer_validate()`, then kept
+/// in a trusted store. It is unsafe for untrusted bytes, and must never
+/// be set for them.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, and
+/// `wasmer_result_t::WASMER_TIMEOUT` when the compilation exceeds the
+/// `compile_timeout_ms` option; no module is created then. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_compile_with_options(
+    module: *mut *mut wasmer_module_t,
+    wasm_bytes: *mut u8,
+    wasm_bytes_len: u32,
+    options: *const wasmer_compilation_options_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() || (wasm_bytes.is_null() && wasm_bytes_len != 0) {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module and wasm_bytes ptrs must not be null".to_string(),
+            });
             return wasmer_result_t::WASMER_ERROR;
         }
-    };
-    *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
-    wasmer_result_t::WASMER_OK
+
+        let bytes: &[u8] = if wasm_bytes_len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(wasm_bytes, wasm_bytes_len as usize)
+        };
+        let new_module = match compile_bytes_with_options(bytes, options) {
+            Ok(module) => module,
+            Err(error) => {
+                let result = compile_failure_result(&error);
+                update_last_error(error);
+                return result;
+            }
+        };
+        *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Creates a new Module from the wasm file at `path`, with the given
+/// compilation options, see `wasmer_instantiate_with_options()`. If
+/// `options` is a null pointer, the module is compiled like with
+/// `wasmer_compile()`.
+///
+/// The file is memory-mapped and compiled in place, instead of being
+/// read into a buffer first. It must not be modified during the
+/// compilation.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the file does not exist or cannot be read, and
+/// `wasmer_result_t::WASMER_TIMEOUT` when the compilation exceeds the
+/// `compile_timeout_ms` option; no module is created then. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_module_t *module = NULL;
+/// if (wasmer_compile_file_with_options(&module, "contracts/counter.wasm", options) != WASMER_OK) {
+///     // Get the error message with `wasmer_last_error_message()`.
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[cfg(feature = "metering")]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_compile_file_with_options(
+    module: *mut *mut wasmer_module_t,
+    path: *const c_char,
+    options: *const wasmer_compilation_options_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() || path.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module and path ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let path = match CStr::from_ptr(path).to_str() {
+            Ok(path) => path,
+            Err(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "path is not a valid UTF-8 string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        let bytes = match File::open(path).and_then(|file| Mmap::map(&file)) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: format!("cannot read the wasm file `{}`: {}", path, error),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let new_module = match compile_bytes_with_options(&bytes, options) {
+            Ok(module) => module,
+            Err(error) => {
+                let result = compile_failure_result(&error);
+                update_last_error(error);
+                return result;
+            }
+        };
+        *module = Box::into_raw(Box::new(new_module)) as *mut wasmer_module_t;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Compiles the bytes with the given options, or like `wasmer_compile()`
+/// if `options` is a null pointer.
+#[cfg(feature = "metering")]
+unsafe fn compile_bytes_with_options(
+    bytes: &[u8],
+    options: *const wasmer_compilation_options_t,
+) -> Result<Module, CausedError> {
+    if options.is_null() {
+        compile(bytes).map_err(|error| {
+            CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                msg: error.to_string(),
+            }
+            .caused_by(error)
+        })
+    } else {
+        read_compilation_options(options)
+            .map_err(Into::into)
+            .and_then(|options| compile_with_options(bytes, &options, ptr::null_mut()))
+    }
 }
 
 /// Validates a sequence of bytes hoping it represents a valid WebAssembly module.
 ///
 /// The function returns true if the bytes are valid, false otherwise.
 ///
+/// The bytes validated once, then kept in a trusted store, can be
+/// compiled again without being validated, see the `already_validated`
+/// option of `wasmer_compile_with_options()`.
+///
 /// Example:
 ///
 /// ```c
@@ -72,86 +257,655 @@ pub unsafe extern "C" fn wasmer_validate(wasm_bytes: *const u8, wasm_bytes_len:
     wasmer_runtime_core::validate(bytes)
 }
 
-/// Creates a new Instance from the given module and imports.
+/// Removes the custom sections, e.g. the `name`, `producers` or DWARF
+/// sections, from the given wasm bytes, to get the smallest module with
+/// the same behavior.
+///
+/// The stripped bytes are owned by the caller, and must be freed with
+/// `wasmer_buffer_destroy()`, or with the `free` function registered
+/// with `wasmer_set_allocator()` if any.
+///
+/// **Warning**: Without the `name` section, functions can no longer be
+/// symbolicated by name, and the offsets of an opcode trace refer to the
+/// stripped bytes, not to the original ones.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, e.g. if the
+/// sections of the wasm bytes are malformed. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// const uint8_t *stripped_bytes;
+/// uint32_t stripped_length;
+///
+/// if (wasmer_strip_custom_sections(bytes, bytes_length, &stripped_bytes, &stripped_length) == WASMER_OK) {
+///     // Store the stripped bytes.
+///
+///     wasmer_buffer_destroy((uint8_t *) stripped_bytes, stripped_length);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_strip_custom_sections(
+    wasm_bytes: *const u8,
+    wasm_bytes_len: u32,
+    stripped_bytes: *mut *const u8,
+    stripped_bytes_len: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if wasm_bytes.is_null() || stripped_bytes.is_null() || stripped_bytes_len.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "wasm_bytes, stripped_bytes and stripped_bytes_len ptrs must not be null"
+                    .to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let bytes: &[u8] = slice::from_raw_parts(wasm_bytes, wasm_bytes_len as usize);
+        let stripped = match strip_custom_sections(bytes) {
+            Ok(stripped) => stripped,
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                    msg,
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        let stripped_len = stripped.len();
+        let buffer = into_c_buffer(stripped.into_boxed_slice());
+        if buffer.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                msg: "wasmer_strip_custom_sections: buffer allocation failed".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        *stripped_bytes = buffer;
+        *stripped_bytes_len = stripped_len as u32;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Returns the descriptor of the memory of the module, imported or
+/// not, if any.
+pub(crate) fn memory_descriptor(module_info: &ModuleInfo) -> Option<MemoryDescriptor> {
+    module_info
+        .imported_memories
+        .values()
+        .map(|(_, descriptor)| *descriptor)
+        .chain(module_info.memories.values().copied())
+        .next()
+}
+
+/// Checks that the minimum size of the memory declared by the module,
+/// if any, does not exceed `max_initial_memory_pages`. A cap of 0 means
+/// no cap.
+pub(crate) fn check_initial_memory_pages(
+    module_info: &ModuleInfo,
+    max_initial_memory_pages: u32,
+) -> Result<(), String> {
+    if max_initial_memory_pages == 0 {
+        return Ok(());
+    }
+
+    match memory_descriptor(module_info) {
+        Some(descriptor) if descriptor.minimum.0 > max_initial_memory_pages => Err(format!(
+            "declared initial memory of {} pages exceeds the maximum of {} pages",
+            descriptor.minimum.0, max_initial_memory_pages
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Copies the wasm bytes without their custom sections, i.e. the
+/// sections with id 0. The other sections are copied verbatim.
+pub(crate) fn strip_custom_sections(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    const HEADER_LEN: usize = 8;
+    const CUSTOM_SECTION_ID: u8 = 0;
+
+    if bytes.len() < HEADER_LEN || &bytes[..4] != b"\0asm" {
+        return Err("invalid wasm header".to_string());
+    }
+
+    let mut stripped = bytes[..HEADER_LEN].to_vec();
+    let mut position = HEADER_LEN;
+
+    while position < bytes.len() {
+        let section_start = position;
+        let id = bytes[position];
+        position += 1;
+
+        let mut payload_len: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = *bytes
+                .get(position)
+                .ok_or_else(|| "unexpected end of section size".to_string())?;
+            position += 1;
+            payload_len |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+            if shift > 28 {
+                return Err("invalid section size".to_string());
+            }
+        }
+
+        let section_end = position
+            .checked_add(payload_len as usize)
+            .filter(|&end| end <= bytes.len())
+            .ok_or_else(|| "section size out of bounds".to_string())?;
+
+        if id != CUSTOM_SECTION_ID {
+            stripped.extend_from_slice(&bytes[section_start..section_end]);
+        }
+
+        position = section_end;
+    }
+
+    Ok(stripped)
+}
+
+/// Returns the size in bytes of the machine code generated by the
+/// compiler backend for the given module.
+///
+/// This is the native code footprint of the module, not the length
+/// of its wasm bytes. It is 0 if `module` is a null pointer, or if
+/// the backend does not expose its generated code.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_code_size(module: *const wasmer_module_t) -> usize {
+    if module.is_null() {
+        return 0;
+    }
+
+    let module = &*(module as *const Module);
+
+    module.code_size()
+}
+
+/// Returns true if the given module has a `start` function, which
+/// runs implicitly when the module is instantiated.
+///
+/// Returns false if `module` is a null pointer.
+///
+/// Set `reject_start_function` in the compilation options to refuse
+/// such modules in `wasmer_instantiate_with_options()`.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_has_start(module: *const wasmer_module_t) -> bool {
+    if module.is_null() {
+        return false;
+    }
+
+    let module = &*(module as *const Module);
+
+    module.info().start_func.is_some()
+}
+
+/// Reads the memory limits declared by the given module, imported
+/// memory included, to know its initial allocation before
+/// instantiating it.
+///
+/// `min_pages` receives the initial number of pages. `max_pages`
+/// receives the maximum number of pages, and `has_max` whether the
+/// module declares a maximum at all; `max_pages` is 0 when it does
+/// not. If the module has no memory, `min_pages` and `max_pages` are
+/// 0 and `has_max` is false.
 ///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
 ///
 /// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
 /// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t min_pages, max_pages;
+/// bool has_max;
+/// wasmer_module_memory_limits(module, &min_pages, &max_pages, &has_max);
+///
+/// if (min_pages > MAX_INITIAL_PAGES || !has_max) {
+///     // Reject the module.
+/// }
+/// ```
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
-pub unsafe extern "C" fn wasmer_module_instantiate(
+pub unsafe extern "C" fn wasmer_module_memory_limits(
     module: *const wasmer_module_t,
-    instance: *mut *mut wasmer_instance_t,
-    imports: *mut wasmer_import_t,
-    imports_len: c_int,
+    min_pages: *mut u32,
+    max_pages: *mut u32,
+    has_max: *mut bool,
 ) -> wasmer_result_t {
-    let imports: &[wasmer_import_t] = slice::from_raw_parts(imports, imports_len as usize);
-    let mut import_object = ImportObject::new();
-    let mut namespaces = HashMap::new();
-    for import in imports {
-        let module_name = slice::from_raw_parts(
-            import.module_name.bytes,
-            import.module_name.bytes_len as usize,
-        );
-        let module_name = if let Ok(s) = std::str::from_utf8(module_name) {
-            s
-        } else {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() || min_pages.is_null() || max_pages.is_null() || has_max.is_null() {
             update_last_error(CApiError {
-                msg: "error converting module name to string".to_string(),
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module, min_pages, max_pages and has_max ptrs must not be null".to_string(),
             });
             return wasmer_result_t::WASMER_ERROR;
-        };
-        let import_name = slice::from_raw_parts(
-            import.import_name.bytes,
-            import.import_name.bytes_len as usize,
-        );
-        let import_name = if let Ok(s) = std::str::from_utf8(import_name) {
-            s
-        } else {
+        }
+
+        let module = &*(module as *const Module);
+        let descriptor = memory_descriptor(module.info());
+
+        *min_pages = descriptor.map_or(0, |descriptor| descriptor.minimum.0);
+        *max_pages = descriptor
+            .and_then(|descriptor| descriptor.maximum)
+            .map_or(0, |maximum| maximum.0);
+        *has_max = descriptor.map_or(false, |descriptor| descriptor.maximum.is_some());
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Reads the number of operators in the body of a function of the
+/// given module into `count`, e.g. to bound statically the gas used
+/// by a function without loops. These are the operators seen by the
+/// compiler middlewares, the final `end` included.
+///
+/// `func_index` is in the function index space of the module, imported
+/// functions first.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// `func_index` is out of range, or refers to an imported function,
+/// which has no body. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_function_opcode_count(
+    module: *const wasmer_module_t,
+    func_index: u32,
+    count: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() || count.is_null() {
             update_last_error(CApiError {
-                msg: "error converting import_name to string".to_string(),
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module and count ptrs must not be null".to_string(),
             });
             return wasmer_result_t::WASMER_ERROR;
-        };
+        }
 
-        let namespace = namespaces.entry(module_name).or_insert_with(Namespace::new);
+        let module = &*(module as *const Module);
+        let info = module.info();
 
-        let export = match import.tag {
-            wasmer_import_export_kind::WASM_MEMORY => {
-                let mem = import.value.memory as *mut Memory;
-                Export::Memory((&*mem).clone())
+        if func_index as usize >= info.func_assoc.len() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "function index {} is out of range, the module has {} functions",
+                    func_index,
+                    info.func_assoc.len()
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        match FuncIndex::new(func_index as usize).local_or_import(info) {
+            LocalOrImport::Local(local_func_index) => {
+                *count = info.func_opcode_counts[local_func_index];
+                wasmer_result_t::WASMER_OK
             }
-            wasmer_import_export_kind::WASM_FUNCTION => {
-                let func_export = import.value.func as *mut Export;
-                (&*func_export).clone()
+            LocalOrImport::Import(_) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: format!("function {} is imported, and has no body", func_index),
+                });
+                wasmer_result_t::WASMER_ERROR
             }
-            wasmer_import_export_kind::WASM_GLOBAL => {
-                let global = import.value.global as *mut Global;
-                Export::Global((&*global).clone())
+        }
+    })
+}
+
+/// Reads the name of a function of the given module, as given by the
+/// `name` custom section, e.g. to symbolicate the function indices of
+/// trap information and opcode traces. The name is not NUL-terminated.
+///
+/// `func_index` is in the function index space of the module, imported
+/// functions first.
+///
+/// At most `out_len` bytes of the name are copied into `out`, and the
+/// number of copied bytes is stored in `written`. If `out` is a null
+/// pointer, nothing is copied and the full length of the name is stored
+/// in `written`, which can be used to allocate a buffer of the right
+/// size.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the function has no name, e.g. because the module has no `name`
+/// section. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t name_length = 0;
+/// if (wasmer_module_function_name(module, func_index, NULL, 0, &name_length) == WASMER_OK) {
+///     uint8_t *name = malloc(name_length);
+///     wasmer_module_function_name(module, func_index, name, name_length, &name_length);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_function_name(
+    module: *const wasmer_module_t,
+    func_index: u32,
+    out: *mut u8,
+    out_len: u32,
+    written: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() || written.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module and written ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let module = &*(module as *const Module);
+        let name = match module
+            .info()
+            .func_names
+            .get(&FuncIndex::new(func_index as usize))
+        {
+            Some(name) => name.as_bytes(),
+            None => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: format!("function {} has no name", func_index),
+                });
+                return wasmer_result_t::WASMER_ERROR;
             }
-            wasmer_import_export_kind::WASM_TABLE => {
-                let table = import.value.table as *mut Table;
-                Export::Table((&*table).clone())
+        };
+
+        if out.is_null() {
+            *written = name.len() as u32;
+            return wasmer_result_t::WASMER_OK;
+        }
+
+        let count = name.len().min(out_len as usize);
+        slice::from_raw_parts_mut(out, count).copy_from_slice(&name[..count]);
+
+        *written = count as u32;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Evaluates the initializer of a global of the given module, without
+/// instantiating it, e.g. to read the constants of a module by static
+/// analysis.
+///
+/// `global_index` is in the global index space of the module, imported
+/// globals first.
+///
+/// If the initial value of the global is a constant, it is stored in
+/// `value_out` and `is_resolvable_out` receives `true`. If it depends on
+/// an import, i.e. if the global is imported or initialized with the
+/// value of an imported global, `is_resolvable_out` receives `false` and
+/// `value_out` is left untouched.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success, including when the
+/// value is not resolvable.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the module has no global at `global_index`, or when the global is of
+/// a type unsupported by the C API. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_value_t value;
+/// bool is_resolvable = false;
+/// wasmer_module_global_init_value(module, global_index, &value, &is_resolvable);
+///
+/// if (is_resolvable && value.tag == WASM_I32) {
+///     printf("Initial value: %d\n", value.value.I32);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_global_init_value(
+    module: *const wasmer_module_t,
+    global_index: u32,
+    value_out: *mut wasmer_value_t,
+    is_resolvable_out: *mut bool,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() || value_out.is_null() || is_resolvable_out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module, value_out and is_resolvable_out ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let module = &*(module as *const Module);
+        let module_info = module.info();
+        let globals_count = module_info.globals.len() + module_info.imported_globals.len();
+        if global_index as usize >= globals_count {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "global index {} is out of bounds, the module has {} globals",
+                    global_index, globals_count
+                ),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let init = match GlobalIndex::new(global_index as usize).local_or_import(module_info) {
+            LocalOrImport::Local(local_index) => &module_info.globals[local_index].init,
+            LocalOrImport::Import(_) => {
+                *is_resolvable_out = false;
+                return wasmer_result_t::WASMER_OK;
             }
         };
-        namespace.insert(import_name, export);
-    }
-    for (module_name, namespace) in namespaces.into_iter() {
-        import_object.register(module_name, namespace);
-    }
 
-    let module = &*(module as *const Module);
-    let new_instance = match module.instantiate(&import_object) {
-        Ok(instance) => instance,
-        Err(error) => {
-            update_last_error(error);
+        match init {
+            Initializer::Const(Value::V128(_)) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UNSUPPORTED,
+                    msg: format!(
+                        "global {} is a v128, unsupported by the C API",
+                        global_index
+                    ),
+                });
+                wasmer_result_t::WASMER_ERROR
+            }
+            Initializer::Const(value) => {
+                *value_out = value.clone().into();
+                *is_resolvable_out = true;
+                wasmer_result_t::WASMER_OK
+            }
+            Initializer::GetGlobal(_) => {
+                *is_resolvable_out = false;
+                wasmer_result_t::WASMER_OK
+            }
+        }
+    })
+}
+
+/// Length in bytes of the hash computed by
+/// `wasmer_module_content_hash()`.
+pub const WASMER_MODULE_CONTENT_HASH_LENGTH: u32 = 32;
+
+/// Computes a deterministic hash of the given module, to be used as a
+/// key when sharing its serialized artifact.
+///
+/// The hash covers the wasm bytes the module was compiled from, and
+/// the effective compilation options: the backend, the WebAssembly
+/// features, the version of the runtime, the compilation options which
+/// change the compiled code, e.g. of `wasmer_compile_file_with_options()`,
+/// and the opcode costs of the metered modules, e.g. of
+/// `wasmer_compile_with_gas_metering()`. Compiling the same bytes with
+/// the same options and costs by the same runtime gives the same hash,
+/// also after a serialization round-trip.
+///
+/// The first `WASMER_MODULE_CONTENT_HASH_LENGTH` bytes of `out` receive
+/// the hash; `out_len` must be at least that.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_content_hash(
+    module: *const wasmer_module_t,
+    out: *mut u8,
+    out_len: u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if module.is_null() || out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "module and out ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if out_len < WASMER_MODULE_CONTENT_HASH_LENGTH {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                msg: format!(
+                    "out is too small: {} bytes, while the hash needs {} bytes",
+                    out_len, WASMER_MODULE_CONTENT_HASH_LENGTH
+                ),
+            });
             return wasmer_result_t::WASMER_ERROR;
         }
-    };
 
-    *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
-    wasmer_result_t::WASMER_OK
+        let module = &*(module as *const Module);
+        let hash = content_hash(module.info());
+        let out = slice::from_raw_parts_mut(out, WASMER_MODULE_CONTENT_HASH_LENGTH as usize);
+        out.copy_from_slice(&hash);
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Hashes the wasm hash of the module along with the options it was
+/// compiled with. Every variable-length field is prefixed with its
+/// length, so that distinct inputs cannot collide.
+fn content_hash(info: &ModuleInfo) -> [u8; 32] {
+    let features = CompilerConfig::default().features;
+    let mut input = Vec::new();
+
+    input.extend_from_slice(&info.wasm_hash);
+    input.extend_from_slice(&info.compile_options_hash);
+    for field in &[info.backend.as_bytes(), WASMER_VERSION_HASH.as_bytes()] {
+        input.extend_from_slice(&(field.len() as u64).to_le_bytes());
+        input.extend_from_slice(field);
+    }
+    input.extend_from_slice(&[
+        features.simd as u8,
+        features.threads as u8,
+        features.bulk_memory as u8,
+        features.reference_types as u8,
+    ]);
+
+    WasmHash::generate(&input).into_array()
+}
+
+/// Creates a new Instance from the given module and imports.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_instantiate(
+    module: *const wasmer_module_t,
+    instance: *mut *mut wasmer_instance_t,
+    imports: *mut wasmer_import_t,
+    imports_len: c_int,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let imports: &[wasmer_import_t] = slice::from_raw_parts(imports, imports_len as usize);
+        let mut import_object = ImportObject::new();
+        let mut namespaces = HashMap::new();
+        for import in imports {
+            let module_name = slice::from_raw_parts(
+                import.module_name.bytes,
+                import.module_name.bytes_len as usize,
+            );
+            let module_name = if let Ok(s) = std::str::from_utf8(module_name) {
+                s
+            } else {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "error converting module name to string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            };
+            let import_name = slice::from_raw_parts(
+                import.import_name.bytes,
+                import.import_name.bytes_len as usize,
+            );
+            let import_name = if let Ok(s) = std::str::from_utf8(import_name) {
+                s
+            } else {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UTF8,
+                    msg: "error converting import_name to string".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            };
+
+            let namespace = namespaces.entry(module_name).or_insert_with(Namespace::new);
+
+            let export = match import.tag {
+                wasmer_import_export_kind::WASM_MEMORY => {
+                    let mem = import.value.memory as *mut Memory;
+                    Export::Memory((&*mem).clone())
+                }
+                wasmer_import_export_kind::WASM_FUNCTION => {
+                    let func_export = import.value.func as *mut Export;
+                    (&*func_export).clone()
+                }
+                wasmer_import_export_kind::WASM_GLOBAL => {
+                    let global = import.value.global as *mut Global;
+                    Export::Global((&*global).clone())
+                }
+                wasmer_import_export_kind::WASM_TABLE => {
+                    let table = import.value.table as *mut Table;
+                    Export::Table((&*table).clone())
+                }
+            };
+            namespace.insert(import_name, export);
+        }
+        for (module_name, namespace) in namespaces.into_iter() {
+            import_object.register(module_name, namespace);
+        }
+
+        let module = &*(module as *const Module);
+        let new_instance = match module.instantiate(&import_object) {
+            Ok(instance) => instance,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 /// Given:
@@ -165,23 +919,33 @@ pub unsafe extern "C" fn wasmer_module_import_instantiate(
     module: *const wasmer_module_t,
     import_object: *const wasmer_import_object_t,
 ) -> wasmer_result_t {
-    let import_object: &ImportObject = &*(import_object as *const ImportObject);
-    let module: &Module = &*(module as *const Module);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let import_object: &ImportObject = &*(import_object as *const ImportObject);
+        let module: &Module = &*(module as *const Module);
 
-    let new_instance: Instance = match module.instantiate(import_object) {
-        Ok(instance) => instance,
-        Err(error) => {
-            update_last_error(error);
-            return wasmer_result_t::WASMER_ERROR;
-        }
-    };
-    *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
+        let new_instance: Instance = match module.instantiate(import_object) {
+            Ok(instance) => instance,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        *instance = Box::into_raw(Box::new(new_instance)) as *mut wasmer_instance_t;
 
-    return wasmer_result_t::WASMER_OK;
+        return wasmer_result_t::WASMER_OK;
+    })
 }
 
 /// Serialize the given Module.
 ///
+/// The serialized module is stamped with the version of the runtime,
+/// see `wasmer_version()`, and with the version of its serialization
+/// format, and only this version can deserialize it.
+///
+/// With the singlepass backend, the same wasm bytes compiled with the
+/// same options by the same version serialize to the same bytes, on
+/// any machine.
+///
 /// The caller owns the object and should call `wasmer_serialized_module_destroy` to free it.
 ///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
@@ -194,29 +958,34 @@ pub unsafe extern "C" fn wasmer_module_serialize(
     serialized_module: *mut *mut wasmer_serialized_module_t,
     module: *const wasmer_module_t,
 ) -> wasmer_result_t {
-    let module = &*(module as *const Module);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let module = &*(module as *const Module);
 
-    match module.cache() {
-        Ok(artifact) => match artifact.serialize() {
-            Ok(serialized_artifact) => {
-                *serialized_module = Box::into_raw(Box::new(serialized_artifact)) as _;
+        match module.cache() {
+            Ok(artifact) => match artifact.serialize() {
+                Ok(serialized_artifact) => {
+                    let serialized_artifact = add_version_stamp(&serialized_artifact);
+                    *serialized_module = Box::into_raw(Box::new(serialized_artifact)) as _;
 
-                wasmer_result_t::WASMER_OK
-            }
+                    wasmer_result_t::WASMER_OK
+                }
+                Err(_) => {
+                    update_last_error(CApiError {
+                        code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                        msg: "Failed to serialize the module artifact".to_string(),
+                    });
+                    wasmer_result_t::WASMER_ERROR
+                }
+            },
             Err(_) => {
                 update_last_error(CApiError {
-                    msg: "Failed to serialize the module artifact".to_string(),
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                    msg: "Failed to serialize the module".to_string(),
                 });
                 wasmer_result_t::WASMER_ERROR
             }
-        },
-        Err(_) => {
-            update_last_error(CApiError {
-                msg: "Failed to serialize the module".to_string(),
-            });
-            wasmer_result_t::WASMER_ERROR
         }
-    }
+    })
 }
 
 /// Get bytes of the serialized module.
@@ -248,64 +1017,81 @@ pub unsafe extern "C" fn wasmer_serialized_module_from_bytes(
     serialized_module_bytes: *const u8,
     serialized_module_bytes_length: u32,
 ) -> wasmer_result_t {
-    if serialized_module.is_null() {
-        update_last_error(CApiError {
-            msg: "`serialized_module_bytes` pointer is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if serialized_module.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "`serialized_module_bytes` pointer is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
 
-    let serialized_module_bytes: &[u8] = slice::from_raw_parts(
-        serialized_module_bytes,
-        serialized_module_bytes_length as usize,
-    );
+        let serialized_module_bytes: &[u8] = slice::from_raw_parts(
+            serialized_module_bytes,
+            serialized_module_bytes_length as usize,
+        );
 
-    *serialized_module = Box::into_raw(Box::new(serialized_module_bytes)) as _;
-    wasmer_result_t::WASMER_OK
+        *serialized_module = Box::into_raw(Box::new(serialized_module_bytes)) as _;
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 /// Deserialize the given serialized module.
 ///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
 ///
-/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
-/// and `wasmer_last_error_message` to get an error message.
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the module was serialized by another version of the runtime. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
 pub unsafe extern "C" fn wasmer_module_deserialize(
     module: *mut *mut wasmer_module_t,
     serialized_module: *const wasmer_serialized_module_t,
 ) -> wasmer_result_t {
-    if serialized_module.is_null() {
-        update_last_error(CApiError {
-            msg: "`serialized_module` pointer is null".to_string(),
-        });
-        return wasmer_result_t::WASMER_ERROR;
-    }
-
-    let serialized_module: &[u8] = &*(serialized_module as *const &[u8]);
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if serialized_module.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "`serialized_module` pointer is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
 
-    match Artifact::deserialize(serialized_module) {
-        // TODO: we need to use a different call here to support middleware (or modify wasmer-runtime)
-        Ok(artifact) => match load_cache_with(artifact, &default_compiler()) {
-            Ok(deserialized_module) => {
-                *module = Box::into_raw(Box::new(deserialized_module)) as _;
-                wasmer_result_t::WASMER_OK
+        let serialized_module: &[u8] = &*(serialized_module as *const &[u8]);
+        let serialized_module = match strip_version_stamp(serialized_module) {
+            Ok(serialized_module) => serialized_module,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
             }
+        };
+
+        match Artifact::deserialize(serialized_module) {
+            // TODO: we need to use a different call here to support middleware (or modify wasmer-runtime)
+            Ok(artifact) => match load_cache_with(artifact, &default_compiler()) {
+                Ok(deserialized_module) => {
+                    *module = Box::into_raw(Box::new(deserialized_module)) as _;
+                    wasmer_result_t::WASMER_OK
+                }
+                Err(_) => {
+                    update_last_error(CApiError {
+                        code: wasmer_error_code_t::WASMER_ERROR_CODE_COMPILE,
+                        msg: "Failed to compile the serialized module".to_string(),
+                    });
+                    wasmer_result_t::WASMER_ERROR
+                }
+            },
             Err(_) => {
                 update_last_error(CApiError {
-                    msg: "Failed to compile the serialized module".to_string(),
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: "Failed to deserialize the module".to_string(),
                 });
                 wasmer_result_t::WASMER_ERROR
             }
-        },
-        Err(_) => {
-            update_last_error(CApiError {
-                msg: "Failed to deserialize the module".to_string(),
-            });
-            wasmer_result_t::WASMER_ERROR
         }
-    }
+    })
 }
 
 /// Frees memory for the given serialized Module.
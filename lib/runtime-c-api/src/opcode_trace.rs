@@ -0,0 +1,300 @@
+//! Read the opcode trace collected during the last call.
+//!
+//! Each entry of the trace identifies its opcode by a stable numeric
+//! code, which external tools can rely on. The codes are defined by
+//! `wasmer_middleware_common::opcode_codes::Opcode`: existing codes never
+//! change, and new opcodes are given new codes. Use
+//! `wasmer_opcode_name()` to get the mnemonic of a code.
+//!
+//! Host functions can interleave their own markers with the opcodes,
+//! see `wasmer_instance_context_trace_mark()`.
+
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    instance::{wasmer_instance_context_t, wasmer_instance_t},
+    wasmer_result_t,
+};
+use lazy_static::lazy_static;
+use libc::c_char;
+use std::{ffi::CString, ptr, slice};
+use wasmer_runtime::{Ctx, Instance};
+
+use wasmer_middleware_common::{
+    opcode_codes::{get_opcode_name, OPCODE_CODE_COUNT},
+    opcode_trace::{
+        get_opcode_trace, get_opcodetracer_last_location, push_opcode_trace_marker,
+        reset_opcode_trace, reset_opcodetracer_last_location, OPCODE_TRACE_MARKER,
+        OPCODE_TRACE_MAX_ENTRIES,
+    },
+};
+
+lazy_static! {
+    /// The NUL-terminated mnemonics returned by `wasmer_opcode_name`.
+    static ref OPCODE_NAMES: Vec<CString> = (0..OPCODE_CODE_COUNT as u16)
+        .map(|code| CString::new(get_opcode_name(code).unwrap()).unwrap())
+        .collect();
+}
+
+/// The `opcode` of the entries of the opcode trace which are markers
+/// pushed by `wasmer_instance_context_trace_mark()`. No opcode has
+/// this code.
+pub const WASMER_OPCODE_TRACE_MARKER: u16 = OPCODE_TRACE_MARKER;
+
+/// The maximum number of entries of the opcode trace of an instance.
+/// The entries recorded once the trace is full are dropped, so a trace
+/// of this length may be truncated.
+pub const WASMER_OPCODE_TRACE_MAX_ENTRIES: u32 = OPCODE_TRACE_MAX_ENTRIES as u32;
+
+/// An executed opcode and its byte offset in the original wasm
+/// binary. The offset can be used to symbolicate the trace against
+/// the name or DWARF sections of the module.
+///
+/// The entries whose `opcode` is `WASMER_OPCODE_TRACE_MARKER` are
+/// markers pushed by host functions instead.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct wasmer_opcode_trace_entry_t {
+    /// The stable code of the opcode, see `wasmer_opcode_name()`.
+    pub opcode: u16,
+
+    /// The byte offset of the opcode in the wasm binary, or 0 for a
+    /// marker.
+    pub offset: u32,
+
+    /// The marker given to `wasmer_instance_context_trace_mark()`, or 0
+    /// for an opcode.
+    pub marker: u64,
+}
+
+/// Reads the opcodes executed by the instance during its last call,
+/// e.g. with `wasmer_instance_call()`, and the markers its host
+/// functions pushed meanwhile. The calls on other instances, including
+/// the nested ones made by host functions, do not affect it.
+///
+/// The opcodes are only collected when the instance was created with
+/// the `collect_opcode_trace` compilation option; the markers of the
+/// host functions are always collected.
+///
+/// The `trace_function_start` and `trace_function_end` compilation
+/// options restrict the collected opcodes to the functions whose index,
+/// counting the imported functions, is in
+/// `trace_function_start..trace_function_end`. A `trace_function_end`
+/// of 0 means no upper bound.
+///
+/// The trace holds at most `WASMER_OPCODE_TRACE_MAX_ENTRIES` entries;
+/// the later ones are dropped.
+///
+/// At most `out_len` entries are copied into `out`, and the number of
+/// copied entries is stored in `written`. If `out` is a null pointer,
+/// nothing is copied and the full length of the trace is stored in
+/// `written`, which can be used to allocate a buffer of the right size.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t trace_length = 0;
+/// wasmer_instance_get_opcode_trace(instance, NULL, 0, &trace_length);
+///
+/// wasmer_opcode_trace_entry_t *trace = malloc(sizeof(wasmer_opcode_trace_entry_t) * trace_length);
+/// wasmer_instance_get_opcode_trace(instance, trace, trace_length, &trace_length);
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_get_opcode_trace(
+    instance: *mut wasmer_instance_t,
+    out: *mut wasmer_opcode_trace_entry_t,
+    out_len: u32,
+    written: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if written.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "written ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+        let trace = get_opcode_trace(instance);
+
+        if out.is_null() {
+            *written = trace.len() as u32;
+            return wasmer_result_t::WASMER_OK;
+        }
+
+        let out: &mut [wasmer_opcode_trace_entry_t] =
+            slice::from_raw_parts_mut(out, out_len as usize);
+        let count = out.len().min(trace.len());
+
+        for (slot, entry) in out.iter_mut().zip(trace.iter()) {
+            *slot = wasmer_opcode_trace_entry_t {
+                opcode: entry.opcode,
+                offset: entry.offset,
+                marker: entry.marker,
+            };
+        }
+
+        *written = count as u32;
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Appends a marker to the opcode trace of the instance of the current
+/// call, from within a host function, to show where the host function
+/// ran among the executed opcodes, e.g. when diagnosing the ordering of
+/// the calls between a module and its host.
+///
+/// The marker is read back by `wasmer_instance_get_opcode_trace()`, as
+/// an entry whose `opcode` is `WASMER_OPCODE_TRACE_MARKER` and whose
+/// `marker` is the given value. Markers are recorded even when the
+/// instance does not collect the opcodes, in which case the trace only
+/// holds the markers.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void transfer(wasmer_instance_context_t *ctx, int32_t amount) {
+///     wasmer_instance_context_trace_mark(ctx, TRANSFER_MARKER);
+///     // ...
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_trace_mark(
+    ctx: *mut wasmer_instance_context_t,
+    marker: u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if ctx.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "ctx ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let ctx = &mut *(ctx as *mut Ctx);
+        push_opcode_trace_marker(ctx, marker);
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Reads the byte offset, in the original wasm binary, of the last
+/// opcode executed by the last call to `wasmer_instance_call()` on the
+/// instance, and stores it in `loc_out`. It tells where a trapping
+/// call stopped.
+///
+/// The location is only tracked when the instance was created with the
+/// `opcode_trace` or the `collect_opcode_trace` compilation option;
+/// otherwise it is 0.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_get_last_opcode_location(
+    instance: *mut wasmer_instance_t,
+    loc_out: *mut u64,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || loc_out.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and loc_out ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+        let instance = &mut *(instance as *mut wasmer_runtime::Instance);
+
+        *loc_out = get_opcodetracer_last_location(instance);
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Clears the last opcode location and the opcode trace of the
+/// instance, before a call on the instance.
+pub(crate) fn reset_opcode_trace_state(instance: &mut Instance) {
+    reset_opcodetracer_last_location(instance);
+    reset_opcode_trace(instance);
+}
+
+/// Clears the last opcode location of the instance, see
+/// `wasmer_instance_get_last_opcode_location()`, and its opcode trace,
+/// see `wasmer_instance_get_opcode_trace()`.
+///
+/// Every call entry point of the instance, e.g.
+/// `wasmer_instance_call()`, `wasmer_instance_call_packed()`,
+/// `wasmer_call_batch_run()` or `wasmer_export_func_call()`, already
+/// does it before calling; this is for the hosts driving the calls
+/// themselves, e.g. to tell apart the opcodes run by the start function
+/// from the ones of a later call.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_reset_opcode_trace(
+    instance: *mut wasmer_instance_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+        let instance = &mut *(instance as *mut Instance);
+
+        reset_opcode_trace_state(instance);
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Returns the mnemonic of the opcode with the given code, as found in
+/// the `opcode` field of `wasmer_opcode_trace_entry_t`, e.g. `i32.add`.
+///
+/// The returned string is NUL-terminated and statically allocated; it
+/// must not be freed.
+///
+/// Returns a null pointer if the code is unknown.
+///
+/// Example:
+///
+/// ```c
+/// const char *name = wasmer_opcode_name(trace[0].opcode);
+///
+/// if (name != NULL) {
+///     printf("%s at offset %#x\n", name, trace[0].offset);
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn wasmer_opcode_name(code: u16) -> *const c_char {
+    match OPCODE_NAMES.get(code as usize) {
+        Some(name) => name.as_ptr(),
+        None => ptr::null(),
+    }
+}
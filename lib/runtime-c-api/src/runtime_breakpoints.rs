@@ -1,11 +1,69 @@
-use crate::instance::wasmer_instance_t;
+//! Read and write the runtime breakpoint value of an instance.
+//!
+//! After each call, the wasm code compiled with the
+//! `runtime_breakpoints` compilation option checks the runtime
+//! breakpoint value of its instance, and traps if it is not
+//! `BREAKPOINT_VALUE_NO_BREAKPOINT`. The runtime itself uses the values
+//! up to `BREAKPOINT_VALUE_RESERVED_MAX`; host code must use greater
+//! values to signal its own interruptions.
+
+use crate::{
+    error::{update_last_error, wasmer_error_code_t, CApiError},
+    instance::{wasmer_instance_context_t, wasmer_instance_t},
+    wasmer_result_t,
+};
+use wasmer_runtime::Ctx;
 
 use wasmer_middleware_common::runtime_breakpoints::{
-    set_runtime_breakpoint_value,
-    get_runtime_breakpoint_value,
-    BREAKPOINT_VALUE_NO_BREAKPOINT
+    get_runtime_breakpoint_value, get_runtime_breakpoint_value_ctx, request_interrupt,
+    set_runtime_breakpoint_value, set_runtime_breakpoint_value_ctx,
 };
 
+/// No breakpoint: the execution goes on.
+pub const BREAKPOINT_VALUE_NO_BREAKPOINT: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_NO_BREAKPOINT;
+
+/// The execution failed.
+pub const BREAKPOINT_VALUE_EXECUTION_FAILED: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_EXECUTION_FAILED;
+
+/// The gas limit of the instance has been exceeded.
+pub const BREAKPOINT_VALUE_OUT_OF_GAS: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_OUT_OF_GAS;
+
+/// The memory of the instance has grown beyond its limit.
+pub const BREAKPOINT_VALUE_MEMORY_LIMIT: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_MEMORY_LIMIT;
+
+/// The running call was interrupted by
+/// `wasmer_instance_request_interrupt()`.
+pub const BREAKPOINT_VALUE_INTERRUPTED: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_INTERRUPTED;
+
+/// The wasm stack has grown beyond the `max_stack_height` compilation
+/// option.
+pub const BREAKPOINT_VALUE_STACK_LIMIT: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_STACK_LIMIT;
+
+/// The call has executed more instructions than the
+/// `hard_instruction_limit` compilation option, whatever the gas limit.
+pub const BREAKPOINT_VALUE_INSTRUCTION_LIMIT: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_INSTRUCTION_LIMIT;
+
+/// A table of the instance has grown beyond the `max_table_elements`
+/// compilation option.
+pub const BREAKPOINT_VALUE_TABLE_LIMIT: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_TABLE_LIMIT;
+
+/// The nested calls of wasm functions have gone deeper than the
+/// `max_call_depth` compilation option.
+pub const BREAKPOINT_VALUE_CALL_DEPTH_LIMIT: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_CALL_DEPTH_LIMIT;
+
+/// The greatest breakpoint value reserved for the runtime.
+pub const BREAKPOINT_VALUE_RESERVED_MAX: u64 =
+    wasmer_middleware_common::runtime_breakpoints::BREAKPOINT_VALUE_RESERVED_MAX;
+
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
 pub unsafe extern "C" fn wasmer_instance_set_runtime_breakpoint_value(
@@ -31,3 +89,113 @@ pub unsafe extern "C" fn wasmer_instance_get_runtime_breakpoint_value(
 
     get_runtime_breakpoint_value(instance)
 }
+
+/// Reads the runtime breakpoint value of the instance, from within a
+/// host function.
+///
+/// Returns `BREAKPOINT_VALUE_NO_BREAKPOINT` if `ctx` is a null pointer.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_breakpoint_value(
+    ctx: *const wasmer_instance_context_t,
+) -> u64 {
+    if ctx.is_null() {
+        return BREAKPOINT_VALUE_NO_BREAKPOINT;
+    }
+    let ctx = &*(ctx as *const Ctx);
+
+    get_runtime_breakpoint_value_ctx(ctx)
+}
+
+/// Sets the runtime breakpoint value of the instance, from within a
+/// host function.
+///
+/// Any value other than `BREAKPOINT_VALUE_NO_BREAKPOINT` makes the
+/// running call trap as soon as the host function returns, if the
+/// instance was created with the `runtime_breakpoints` compilation
+/// option. The value can then be read with
+/// `wasmer_instance_get_runtime_breakpoint_value()`.
+///
+/// Values up to `BREAKPOINT_VALUE_RESERVED_MAX` are reserved for the
+/// runtime, e.g. `BREAKPOINT_VALUE_OUT_OF_GAS`; host code must use
+/// greater values for its own interruptions.
+///
+/// Example:
+///
+/// ```c
+/// #define BREAKPOINT_VALUE_ASYNC_CALL (BREAKPOINT_VALUE_RESERVED_MAX + 1)
+///
+/// void async_call(wasmer_instance_context_t *context) {
+///     // Register the asynchronous call.
+///
+///     wasmer_instance_context_set_breakpoint_value(context, BREAKPOINT_VALUE_ASYNC_CALL);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_context_set_breakpoint_value(
+    ctx: *mut wasmer_instance_context_t,
+    value: u64,
+) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = &mut *(ctx as *mut Ctx);
+
+    set_runtime_breakpoint_value_ctx(ctx, value);
+}
+
+/// Requests the running call of the instance to stop, e.g. when a
+/// deadline is reached. Unlike the other functions of the C API, this
+/// one may be called from any thread, including while another thread
+/// runs `wasmer_instance_call()` on the instance.
+///
+/// The call traps at its next call or loop iteration, if the instance
+/// was created with the `runtime_breakpoints` compilation option, or
+/// by `wasmer_compile_with_gas_metering()`, and returns
+/// `wasmer_result_t::WASMER_INTERRUPTED`. The runtime breakpoint value
+/// of the instance is then `BREAKPOINT_VALUE_INTERRUPTED`.
+///
+/// If no call is running, the next call of the instance is
+/// interrupted. The request is kept apart from the runtime breakpoint
+/// value: it stays pending until a call is interrupted, even if the
+/// value is reset meanwhile, e.g. by
+/// `wasmer_instance_set_runtime_breakpoint_value()` or
+/// `wasmer_instance_call_shared_gas()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// // In the watchdog thread.
+/// if (deadline_reached()) {
+///     wasmer_instance_request_interrupt(instance);
+/// }
+///
+/// // In the thread running the instance.
+/// if (wasmer_instance_call(instance, "main", params, 0, results, 0) == WASMER_INTERRUPTED) {
+///     // The deadline was reached.
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_request_interrupt(
+    instance: *mut wasmer_instance_t,
+) -> wasmer_result_t {
+    if instance.is_null() {
+        update_last_error(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+            msg: "instance ptr is null".to_string(),
+        });
+        return wasmer_result_t::WASMER_ERROR;
+    }
+    let instance = &*(instance as *mut wasmer_runtime::Instance);
+
+    request_interrupt(instance);
+
+    wasmer_result_t::WASMER_OK
+}
@@ -1,5 +1,11 @@
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    wasmer_result_t,
+};
 use std::sync::atomic::Ordering;
-use wasmer_runtime_core::fault::{install_sighandler_as_dylib, SIGSEGV_PASSTHROUGH};
+use wasmer_runtime_core::fault::{
+    install_sighandler_as_dylib, set_sigsegv_handler_enabled, SIGSEGV_PASSTHROUGH,
+};
 
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
@@ -12,3 +18,39 @@ pub unsafe extern "C" fn wasmer_set_sigsegv_passthrough() {
 pub unsafe extern "C" fn wasmer_force_install_sighandlers() {
     install_sighandler_as_dylib();
 }
+
+/// Installs or removes the signal handler of the runtime for SIGSEGV.
+///
+/// When enabled, an out-of-bounds or illegal memory access from wasm
+/// code becomes a trap, and the running call returns
+/// `wasmer_result_t::WASMER_ERROR` instead of crashing the process. The
+/// other trap signals are handled as well.
+///
+/// When disabled, SIGSEGV gets its default disposition back, like after
+/// `wasmer_set_sigsegv_passthrough()`, so that the host can handle it.
+/// A handler the host installed before is not restored: until the host
+/// installs one again, an out-of-bounds access from wasm code terminates
+/// the process instead of trapping.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` if a signal handler cannot be
+/// changed. Use `wasmer_last_error_length` and `wasmer_last_error_message`
+/// to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_set_signal_handler_enabled(enabled: bool) -> wasmer_result_t {
+    catch_panic(
+        wasmer_result_t::WASMER_ERROR,
+        || match set_sigsegv_handler_enabled(enabled) {
+            Ok(()) => wasmer_result_t::WASMER_OK,
+            Err(error) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_UNKNOWN,
+                    msg: format!("cannot change the signal handler: {}", error),
+                });
+                wasmer_result_t::WASMER_ERROR
+            }
+        },
+    )
+}
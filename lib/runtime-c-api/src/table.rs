@@ -1,8 +1,16 @@
 //! Create, grow, destroy tables of an instance.
 
-use crate::{error::update_last_error, wasmer_limits_t, wasmer_result_t};
-use wasmer_runtime::Table;
-use wasmer_runtime_core::types::{ElementType, TableDescriptor};
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    instance::wasmer_instance_t,
+    wasmer_limits_t, wasmer_result_t,
+};
+use wasmer_middleware_common::opcode_control::grow_table;
+use wasmer_runtime::{Instance, Table};
+use wasmer_runtime_core::{
+    structures::TypedIndex,
+    types::{ElementType, TableDescriptor, TableIndex},
+};
 
 #[repr(C)]
 #[derive(Clone)]
@@ -22,26 +30,28 @@ pub unsafe extern "C" fn wasmer_table_new(
     table: *mut *mut wasmer_table_t,
     limits: wasmer_limits_t,
 ) -> wasmer_result_t {
-    let max = if limits.max.has_some {
-        Some(limits.max.some)
-    } else {
-        None
-    };
-    let desc = TableDescriptor {
-        element: ElementType::Anyfunc,
-        minimum: limits.min,
-        maximum: max,
-    };
-    let result = Table::new(desc);
-    let new_table = match result {
-        Ok(table) => table,
-        Err(error) => {
-            update_last_error(error);
-            return wasmer_result_t::WASMER_ERROR;
-        }
-    };
-    *table = Box::into_raw(Box::new(new_table)) as *mut wasmer_table_t;
-    wasmer_result_t::WASMER_OK
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let max = if limits.max.has_some {
+            Some(limits.max.some)
+        } else {
+            None
+        };
+        let desc = TableDescriptor {
+            element: ElementType::Anyfunc,
+            minimum: limits.min,
+            maximum: max,
+        };
+        let result = Table::new(desc);
+        let new_table = match result {
+            Ok(table) => table,
+            Err(error) => {
+                update_last_error(error);
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+        *table = Box::into_raw(Box::new(new_table)) as *mut wasmer_table_t;
+        wasmer_result_t::WASMER_OK
+    })
 }
 
 /// Grows a Table by the given number of elements.
@@ -53,15 +63,17 @@ pub unsafe extern "C" fn wasmer_table_new(
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
 pub extern "C" fn wasmer_table_grow(table: *mut wasmer_table_t, delta: u32) -> wasmer_result_t {
-    let table = unsafe { &*(table as *mut Table) };
-    let delta_result = table.grow(delta);
-    match delta_result {
-        Ok(_) => wasmer_result_t::WASMER_OK,
-        Err(grow_error) => {
-            update_last_error(grow_error);
-            wasmer_result_t::WASMER_ERROR
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        let table = unsafe { &*(table as *mut Table) };
+        let delta_result = table.grow(delta);
+        match delta_result {
+            Ok(_) => wasmer_result_t::WASMER_OK,
+            Err(grow_error) => {
+                update_last_error(grow_error);
+                wasmer_result_t::WASMER_ERROR
+            }
         }
-    }
+    })
 }
 
 /// Returns the current length of the given Table
@@ -72,6 +84,151 @@ pub extern "C" fn wasmer_table_length(table: *mut wasmer_table_t) -> u32 {
     table.size()
 }
 
+/// Reads the current length of the table at `table_index` of an
+/// instance, imported tables included, into `len`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, e.g. if there
+/// is no such table. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_table_len(
+    instance: *mut wasmer_instance_t,
+    table_index: u32,
+    len: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || len.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and len ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+
+        match instance.table_size(TableIndex::new(table_index as usize)) {
+            Some(size) => {
+                *len = size;
+                wasmer_result_t::WASMER_OK
+            }
+            None => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: format!("table index {} is out of range", table_index),
+                });
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
+/// Grows the table at `table_index` of an instance, imported tables
+/// included, by `delta` elements, and stores its previous length in
+/// `prev_out`, unless it is a null pointer.
+///
+/// The table cannot grow beyond its declared maximum, nor beyond the
+/// `max_table_elements` compilation option of the instance. The same
+/// limit is checked before `table.grow` on the backends compiling it,
+/// which the singlepass backend does not.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, e.g. if there
+/// is no such table, or if it cannot grow by `delta` elements, in which
+/// case it is left untouched. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_table_grow(
+    instance: *mut wasmer_instance_t,
+    table_index: u32,
+    delta: u32,
+    prev_out: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+
+        match grow_table(instance, TableIndex::new(table_index as usize), delta) {
+            Ok(previous_len) => {
+                if !prev_out.is_null() {
+                    *prev_out = previous_len;
+                }
+                wasmer_result_t::WASMER_OK
+            }
+            Err(msg) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg,
+                });
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
+/// Reads the index of the function stored at `elem_index` in the table
+/// at `table_index` of an instance into `func_index`. Function indices
+/// include the imported functions.
+///
+/// If the element is null, or refers to a function which does not
+/// belong to the instance, `func_index` is set to `UINT32_MAX`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, e.g. if the
+/// table or the element index is out of range. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_table_get(
+    instance: *mut wasmer_instance_t,
+    table_index: u32,
+    elem_index: u32,
+    func_index: *mut u32,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() || func_index.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance and func_index ptrs must not be null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+
+        match instance.table_func_index(TableIndex::new(table_index as usize), elem_index) {
+            Ok(index) => {
+                *func_index = index.map_or(u32::max_value(), |index| index.index() as u32);
+                wasmer_result_t::WASMER_OK
+            }
+            Err(()) => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: format!(
+                        "table index {} or element index {} is out of range",
+                        table_index, elem_index
+                    ),
+                });
+                wasmer_result_t::WASMER_ERROR
+            }
+        }
+    })
+}
+
 /// Frees memory for the given Table
 #[allow(clippy::cast_ptr_alignment)]
 #[no_mangle]
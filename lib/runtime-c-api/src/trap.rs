@@ -0,0 +1,428 @@
+//! Read structured information about the traps raised by an instance.
+
+use crate::{
+    error::{catch_panic, update_last_error, wasmer_error_code_t, CApiError},
+    instance::wasmer_instance_t,
+    wasmer_result_t,
+};
+use libc::{c_char, c_int};
+use std::slice;
+use wasmer_middleware_common::runtime_breakpoints;
+use wasmer_runtime::{Ctx, Instance};
+use wasmer_runtime_core::{
+    backend::ExceptionCode,
+    fault::{clear_last_trap_info, get_last_trap_info},
+    vm::InternalField,
+};
+
+static FIELD_HOST_FORCED_TRAP: InternalField = InternalField::allocate();
+static FIELD_HOST_TRAP_CODE: InternalField = InternalField::allocate();
+
+/// The trap code of the last call plus one, or 0 if it did not trap.
+static FIELD_LAST_TRAP_CODE: InternalField = InternalField::allocate();
+/// The function index of the trap of the last call plus one, or 0
+/// without location.
+static FIELD_LAST_TRAP_FUNC_INDEX: InternalField = InternalField::allocate();
+static FIELD_LAST_TRAP_OPERATOR_INDEX: InternalField = InternalField::allocate();
+
+/// List of trap codes.
+///
+/// Each kind of trap of WebAssembly has one code:
+///
+/// * `unreachable`: `WASMER_TRAP_UNREACHABLE`,
+/// * an out-of-bounds memory access: `WASMER_TRAP_MEMORY_OUT_OF_BOUNDS`,
+/// * an out-of-bounds table access by `call_indirect`:
+///   `WASMER_TRAP_CALL_INDIRECT_OUT_OF_BOUNDS`,
+/// * a `call_indirect` signature mismatch:
+///   `WASMER_TRAP_INCORRECT_CALL_INDIRECT_SIGNATURE`,
+/// * an integer overflow or an integer division by zero:
+///   `WASMER_TRAP_ILLEGAL_ARITHMETIC`,
+/// * a stack overflow: `WASMER_TRAP_STACK_OVERFLOW`,
+/// * running out of gas: `WASMER_TRAP_OUT_OF_GAS`.
+///
+/// The codes are only canonical, i.e. the same whatever the backend
+/// which compiled the module, for the modules compiled with the
+/// `deterministic_traps` compilation option: only then can the kind of
+/// a trap be relied upon, e.g. to decide the outcome of a transaction.
+/// Without it, the singlepass backend, the default one, only tells
+/// apart `unreachable`, the arithmetic traps, the stack overflows and
+/// the out of gas traps; its other traps are `WASMER_TRAP_UNKNOWN`.
+#[allow(non_camel_case_types)]
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum wasmer_trap_code_t {
+    /// An `unreachable` opcode was executed.
+    WASMER_TRAP_UNREACHABLE = 0,
+
+    /// A `call_indirect` was made with an incorrect signature.
+    WASMER_TRAP_INCORRECT_CALL_INDIRECT_SIGNATURE = 1,
+
+    /// A memory access was out of bounds.
+    WASMER_TRAP_MEMORY_OUT_OF_BOUNDS = 2,
+
+    /// A `call_indirect` was made out of the table bounds.
+    WASMER_TRAP_CALL_INDIRECT_OUT_OF_BOUNDS = 3,
+
+    /// An arithmetic exception: an integer division or remainder by
+    /// zero, or a signed integer division overflow (`INT_MIN / -1`).
+    ///
+    /// Every backend reports these with this code.
+    WASMER_TRAP_ILLEGAL_ARITHMETIC = 4,
+
+    /// A misaligned atomic access.
+    WASMER_TRAP_MISALIGNED_ATOMIC_ACCESS = 5,
+
+    /// The nested calls went deeper than the `max_call_depth`
+    /// compilation option, see `BREAKPOINT_VALUE_CALL_DEPTH_LIMIT`.
+    WASMER_TRAP_STACK_OVERFLOW = 6,
+
+    /// The gas limit of the instance was exceeded, see
+    /// `BREAKPOINT_VALUE_OUT_OF_GAS`.
+    WASMER_TRAP_OUT_OF_GAS = 7,
+
+    /// The trap code could not be determined.
+    WASMER_TRAP_UNKNOWN = 255,
+}
+
+impl From<ExceptionCode> for wasmer_trap_code_t {
+    fn from(code: ExceptionCode) -> Self {
+        match code {
+            ExceptionCode::Unreachable => Self::WASMER_TRAP_UNREACHABLE,
+            ExceptionCode::IncorrectCallIndirectSignature => {
+                Self::WASMER_TRAP_INCORRECT_CALL_INDIRECT_SIGNATURE
+            }
+            ExceptionCode::MemoryOutOfBounds => Self::WASMER_TRAP_MEMORY_OUT_OF_BOUNDS,
+            ExceptionCode::CallIndirectOOB => Self::WASMER_TRAP_CALL_INDIRECT_OUT_OF_BOUNDS,
+            ExceptionCode::IllegalArithmetic => Self::WASMER_TRAP_ILLEGAL_ARITHMETIC,
+            ExceptionCode::MisalignedAtomicAccess => Self::WASMER_TRAP_MISALIGNED_ATOMIC_ACCESS,
+            ExceptionCode::StackOverflow => Self::WASMER_TRAP_STACK_OVERFLOW,
+        }
+    }
+}
+
+/// Returns the trap code with the given value, see
+/// `FIELD_LAST_TRAP_CODE`.
+fn trap_code_from_u64(code: u64) -> wasmer_trap_code_t {
+    use wasmer_trap_code_t::*;
+
+    match code {
+        0 => WASMER_TRAP_UNREACHABLE,
+        1 => WASMER_TRAP_INCORRECT_CALL_INDIRECT_SIGNATURE,
+        2 => WASMER_TRAP_MEMORY_OUT_OF_BOUNDS,
+        3 => WASMER_TRAP_CALL_INDIRECT_OUT_OF_BOUNDS,
+        4 => WASMER_TRAP_ILLEGAL_ARITHMETIC,
+        5 => WASMER_TRAP_MISALIGNED_ATOMIC_ACCESS,
+        6 => WASMER_TRAP_STACK_OVERFLOW,
+        7 => WASMER_TRAP_OUT_OF_GAS,
+        _ => WASMER_TRAP_UNKNOWN,
+    }
+}
+
+/// The `wasmer_trap_info_t` struct describes where and why a trap
+/// occurred. See `wasmer_instance_last_trap_info()`.
+#[repr(C)]
+pub struct wasmer_trap_info_t {
+    /// The trap code.
+    pub code: wasmer_trap_code_t,
+
+    /// Whether `func_index` and `operator_index` are set.
+    pub has_location: bool,
+
+    /// The index of the function which trapped, imported functions
+    /// included.
+    pub func_index: u32,
+
+    /// The index of the instruction which trapped among the
+    /// instructions of the function body, counting from 0. This is not
+    /// a byte offset in the wasm binary. The instructions inserted by the
+    /// middlewares of the module, e.g. the gas metering, are counted too.
+    pub operator_index: u32,
+}
+
+/// Reads the information about the trap raised during the last call
+/// to `wasmer_instance_call()` on the instance.
+///
+/// Returns `wasmer_result_t::WASMER_OK` and fills `trap_info` if the
+/// last call trapped.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` if the last call did not
+/// trap, or upon failure. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_trap_info_t trap_info;
+///
+/// if (wasmer_instance_last_trap_info(instance, &trap_info) == WASMER_OK && trap_info.has_location) {
+///     printf("Trap %d at function %d, instruction %d\n", trap_info.code, trap_info.func_index, trap_info.operator_index);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_last_trap_info(
+    instance: *mut wasmer_instance_t,
+    trap_info: *mut wasmer_trap_info_t,
+) -> wasmer_result_t {
+    catch_panic(wasmer_result_t::WASMER_ERROR, || {
+        if instance.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "instance ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        if trap_info.is_null() {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_NULL_POINTER,
+                msg: "trap_info ptr is null".to_string(),
+            });
+            return wasmer_result_t::WASMER_ERROR;
+        }
+
+        let instance = &*(instance as *const Instance);
+
+        *trap_info = match last_trap_info(instance) {
+            Some(last_trap_info) => last_trap_info,
+            None => {
+                update_last_error(CApiError {
+                    code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+                    msg: "no trap information available".to_string(),
+                });
+                return wasmer_result_t::WASMER_ERROR;
+            }
+        };
+
+        wasmer_result_t::WASMER_OK
+    })
+}
+
+/// Forgets the trap raised during the previous call on the instance, if
+/// any.
+pub(crate) fn reset_last_trap_info(instance: &mut Instance) {
+    instance.set_internal(&FIELD_LAST_TRAP_CODE, 0);
+    instance.set_internal(&FIELD_LAST_TRAP_FUNC_INDEX, 0);
+    instance.set_internal(&FIELD_LAST_TRAP_OPERATOR_INDEX, 0);
+}
+
+/// Moves the information about the trap just raised on the current
+/// thread to the instance which raised it, if `trapped`, so that it
+/// neither survives on the thread nor is read from another instance,
+/// e.g. the one calling the host function which called this instance.
+pub(crate) fn record_last_trap_info(instance: &mut Instance, trapped: bool) {
+    let trap_info = get_last_trap_info();
+    clear_last_trap_info();
+
+    let trap_info = match trap_info {
+        Some(trap_info) if trapped => trap_info,
+        _ => return,
+    };
+
+    let code = trap_info
+        .exception_code
+        .map(Into::into)
+        .unwrap_or(wasmer_trap_code_t::WASMER_TRAP_UNKNOWN);
+    instance.set_internal(&FIELD_LAST_TRAP_CODE, code as u64 + 1);
+
+    if let Some((local_function_id, operator_index)) = trap_info.location {
+        let func_index = local_function_id + instance.module.info.imported_functions.len();
+        instance.set_internal(&FIELD_LAST_TRAP_FUNC_INDEX, func_index as u64 + 1);
+        instance.set_internal(&FIELD_LAST_TRAP_OPERATOR_INDEX, operator_index as u64);
+    }
+}
+
+/// Returns the information about the trap raised during the last call
+/// on the instance, with function indices in the function index space
+/// of `instance`, if the last call trapped.
+///
+/// Running out of gas stops the call at a runtime breakpoint instead of
+/// a trap: it is reported as a trap without location, as long as the
+/// runtime breakpoint value of the instance is the out of gas one.
+pub(crate) fn last_trap_info(instance: &Instance) -> Option<wasmer_trap_info_t> {
+    let code = match instance.get_internal(&FIELD_LAST_TRAP_CODE) {
+        0 => {
+            let code =
+                match runtime_breakpoints::get_runtime_breakpoint_value_ctx(instance.context()) {
+                    runtime_breakpoints::BREAKPOINT_VALUE_OUT_OF_GAS => {
+                        wasmer_trap_code_t::WASMER_TRAP_OUT_OF_GAS
+                    }
+                    runtime_breakpoints::BREAKPOINT_VALUE_CALL_DEPTH_LIMIT => {
+                        wasmer_trap_code_t::WASMER_TRAP_STACK_OVERFLOW
+                    }
+                    _ => return None,
+                };
+            return Some(wasmer_trap_info_t {
+                code,
+                has_location: false,
+                func_index: 0,
+                operator_index: 0,
+            });
+        }
+        code => trap_code_from_u64(code - 1),
+    };
+
+    let func_index = instance.get_internal(&FIELD_LAST_TRAP_FUNC_INDEX);
+
+    Some(wasmer_trap_info_t {
+        code,
+        has_location: func_index != 0,
+        func_index: func_index.saturating_sub(1) as u32,
+        operator_index: instance.get_internal(&FIELD_LAST_TRAP_OPERATOR_INDEX) as u32,
+    })
+}
+
+/// Formats the trap raised during the last call to
+/// `wasmer_instance_call()` on the instance, with its location,
+/// e.g. `unreachable at func 3 instruction 12`, into the provided
+/// buffer `out` up to the given `out_len`. See
+/// `wasmer_instance_last_trap_info()` for the meaning of the function
+/// index and the instruction index. When the location of the trap is
+/// unknown, the message is the description of the trap alone.
+///
+/// The function returns the length of the message in bytes, trailing
+/// null character included, 0 if the last call did not trap, and -1 if
+/// `instance` or `out` is a null pointer, if `out_len` is not positive,
+/// or if `out` is too small to hold the message.
+///
+/// Example:
+///
+/// ```c
+/// char trap_message[128];
+///
+/// if (wasmer_instance_call(instance, "transfer", params, 2, results, 1) != WASMER_OK
+///     && wasmer_instance_last_trap_message_with_location(instance, trap_message, sizeof(trap_message)) > 0) {
+///     printf("Trap: %s\n", trap_message);
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_last_trap_message_with_location(
+    instance: *mut wasmer_instance_t,
+    out: *mut c_char,
+    out_len: c_int,
+) -> c_int {
+    if instance.is_null() || out.is_null() || out_len <= 0 {
+        return -1;
+    }
+
+    catch_panic(-1, || {
+        let instance = &*(instance as *const Instance);
+        let message = match last_trap_message(instance) {
+            Some(message) => message,
+            None => return 0,
+        };
+
+        let out_len = out_len as usize;
+        if message.len() >= out_len {
+            return -1;
+        }
+
+        let out = slice::from_raw_parts_mut(out as *mut u8, out_len);
+        out[..message.len()].copy_from_slice(message.as_bytes());
+        out[message.len()] = 0;
+
+        message.len() as c_int + 1
+    })
+}
+
+/// Formats the trap raised during the last call on the instance,
+/// with its location if known, see
+/// `wasmer_instance_last_trap_message_with_location()`.
+pub(crate) fn last_trap_message(instance: &Instance) -> Option<String> {
+    let trap_info = last_trap_info(instance)?;
+
+    let description = match trap_info.code {
+        wasmer_trap_code_t::WASMER_TRAP_UNREACHABLE => "unreachable",
+        wasmer_trap_code_t::WASMER_TRAP_INCORRECT_CALL_INDIRECT_SIGNATURE => {
+            "incorrect call_indirect signature"
+        }
+        wasmer_trap_code_t::WASMER_TRAP_MEMORY_OUT_OF_BOUNDS => "memory out of bounds",
+        wasmer_trap_code_t::WASMER_TRAP_CALL_INDIRECT_OUT_OF_BOUNDS => {
+            "call_indirect out of bounds"
+        }
+        wasmer_trap_code_t::WASMER_TRAP_ILLEGAL_ARITHMETIC => "illegal arithmetic",
+        wasmer_trap_code_t::WASMER_TRAP_MISALIGNED_ATOMIC_ACCESS => "misaligned atomic access",
+        wasmer_trap_code_t::WASMER_TRAP_STACK_OVERFLOW => "stack overflow",
+        wasmer_trap_code_t::WASMER_TRAP_OUT_OF_GAS => "out of gas",
+        wasmer_trap_code_t::WASMER_TRAP_UNKNOWN => "unknown trap",
+    };
+    Some(if trap_info.has_location {
+        format!(
+            "{} at func {} instruction {}",
+            description, trap_info.func_index, trap_info.operator_index
+        )
+    } else {
+        description.to_string()
+    })
+}
+
+/// Records, on the instance of `ctx`, that the host is forcing a trap
+/// with `code`, see `wasmer_trap_with_code()`.
+pub(crate) fn set_host_forced_trap(ctx: &mut Ctx, code: u32) {
+    ctx.set_internal(&FIELD_HOST_FORCED_TRAP, 1);
+    ctx.set_internal(&FIELD_HOST_TRAP_CODE, code as u64);
+}
+
+/// Forgets the trap forced by the host during the previous call, if any.
+pub(crate) fn reset_host_forced_trap(instance: &mut Instance) {
+    instance.set_internal(&FIELD_HOST_FORCED_TRAP, 0);
+    instance.set_internal(&FIELD_HOST_TRAP_CODE, 0);
+}
+
+/// Returns whether the last call to `wasmer_instance_call()` on the
+/// instance was aborted by a host function, with `wasmer_trap()` or
+/// `wasmer_trap_with_code()`, rather than by a trap of the WebAssembly
+/// code itself, e.g. an `unreachable` opcode.
+///
+/// This function returns 1 if the trap was forced by the host, and 0
+/// otherwise, including when the last call did not trap, or if
+/// `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// if (wasmer_instance_call(instance, "transfer", params, 2, results, 1) != WASMER_OK) {
+///     if (wasmer_instance_last_trap_was_host_forced(instance)) {
+///         uint32_t reason = wasmer_instance_last_host_trap_code(instance);
+///         // The host rejected the call.
+///     } else {
+///         // The contract failed.
+///     }
+/// }
+/// ```
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_last_trap_was_host_forced(
+    instance: *const wasmer_instance_t,
+) -> c_int {
+    if instance.is_null() {
+        return 0;
+    }
+
+    let instance = &*(instance as *const Instance);
+
+    (instance.get_internal(&FIELD_HOST_FORCED_TRAP) != 0) as c_int
+}
+
+/// Returns the error code given to `wasmer_trap_with_code()` by the
+/// host function which aborted the last call to
+/// `wasmer_instance_call()` on the instance.
+///
+/// This function returns 0 if the trap was forced with `wasmer_trap()`,
+/// if the last call was not aborted by the host, see
+/// `wasmer_instance_last_trap_was_host_forced()`, or if `instance` is
+/// a null pointer.
+#[allow(clippy::cast_ptr_alignment)]
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_last_host_trap_code(
+    instance: *const wasmer_instance_t,
+) -> u32 {
+    if instance.is_null() {
+        return 0;
+    }
+
+    let instance = &*(instance as *const Instance);
+
+    instance.get_internal(&FIELD_HOST_TRAP_CODE) as u32
+}
@@ -28,8 +28,12 @@ pub enum wasmer_value_tag {
 /// This is a [Rust union][rust-union], which is equivalent to the C
 /// union. See `wasmer_value_t` to get a complete example.
 ///
+/// The union is 8-byte aligned on every target, including 32-bit
+/// targets where `i64` and `f64` may only be 4-byte aligned, so that
+/// its layout does not depend on the C compiler of the host.
+///
 /// [rust-union]: https://doc.rust-lang.org/reference/items/unions.html
-#[repr(C)]
+#[repr(C, align(8))]
 #[derive(Clone, Copy)]
 #[allow(non_snake_case)]
 pub union wasmer_value {
@@ -69,6 +73,11 @@ pub struct wasmer_value_t {
     pub value: wasmer_value,
 }
 
+// The layout of `wasmer_value_t` must be the same on every target:
+// the tag, 4 bytes of padding, then the value.
+const _: [(); 8] = [(); std::mem::align_of::<wasmer_value>()];
+const _: [(); 16] = [(); std::mem::size_of::<wasmer_value_t>()];
+
 impl From<wasmer_value_t> for Value {
     fn from(v: wasmer_value_t) -> Self {
         unsafe {
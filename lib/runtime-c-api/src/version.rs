@@ -0,0 +1,164 @@
+//! Read the version of the runtime and of its compiler backends, and
+//! stamp the serialized modules with it.
+
+use crate::{
+    backend::wasmer_compiler_backend_t,
+    error::{update_last_error, wasmer_error_code_t, CApiError},
+};
+use lazy_static::lazy_static;
+use libc::c_char;
+use std::{ffi::CString, ptr};
+use wasmer_runtime_core::cache::WASMER_VERSION_HASH;
+
+/// The version of the runtime, as a C string.
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
+
+/// Magic bytes starting the version stamp of the serialized modules.
+const STAMP_MAGIC: [u8; 16] = *b"WASMER-VERSION\0\0";
+
+/// The version of the format of the serialized modules, stamped along
+/// with the version hash of the runtime, which only hashes the version
+/// of the crate.
+///
+/// It must be bumped by every change of the serialized bytes, e.g. of
+/// `ModuleInfo` or of the cache image of a backend, so that the modules
+/// serialized before the change are rejected instead of misread.
+///
+/// * 1: the first stamped format.
+/// * 2: `ModuleInfo::compile_options_hash`.
+/// * 3: `ModuleInfo::metered`.
+pub(crate) const SERIALIZATION_FORMAT_VERSION: u32 = 3;
+
+/// Length of the version stamp of the serialized modules: the magic
+/// bytes, the version hash of the runtime, then the format version,
+/// little-endian, and zeroes. It is a multiple of 16, so that the stamp
+/// does not misalign the rkyv archive following it.
+const STAMP_LEN: usize = 96;
+
+/// Offset of the format version in the version stamp.
+const STAMP_FORMAT_OFFSET: usize = STAMP_MAGIC.len() + WASMER_VERSION_HASH.len();
+
+const _: [(); STAMP_LEN] = [(); STAMP_FORMAT_OFFSET + 16];
+
+lazy_static! {
+    static ref BACKEND_VERSIONS: Vec<(wasmer_compiler_backend_t, CString)> = vec![
+        #[cfg(feature = "singlepass-backend")]
+        (
+            wasmer_compiler_backend_t::WASMER_BACKEND_SINGLEPASS,
+            CString::new(wasmer_singlepass_backend::VERSION).unwrap(),
+        ),
+        #[cfg(feature = "cranelift-backend")]
+        (
+            wasmer_compiler_backend_t::WASMER_BACKEND_CRANELIFT,
+            CString::new(wasmer_clif_backend::VERSION).unwrap(),
+        ),
+        #[cfg(feature = "llvm-backend")]
+        (
+            wasmer_compiler_backend_t::WASMER_BACKEND_LLVM,
+            CString::new(wasmer_llvm_backend::VERSION).unwrap(),
+        ),
+    ];
+}
+
+/// Returns the version of the runtime, e.g. `0.15.0`, as a
+/// NUL-terminated string.
+///
+/// The string is static: it must not be freed.
+///
+/// Example:
+///
+/// ```c
+/// printf("wasmer %s\n", wasmer_version());
+/// ```
+#[no_mangle]
+pub extern "C" fn wasmer_version() -> *const c_char {
+    VERSION.as_ptr() as *const c_char
+}
+
+/// Returns the version of the given compiler backend as a
+/// NUL-terminated string, or a null pointer if this library was not
+/// built with the backend, see `wasmer_available_backends()`.
+///
+/// The string is static: it must not be freed.
+///
+/// Example:
+///
+/// ```c
+/// const char *version = wasmer_backend_version(WASMER_BACKEND_SINGLEPASS);
+///
+/// if (version != NULL) {
+///     printf("singlepass %s\n", version);
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn wasmer_backend_version(backend: wasmer_compiler_backend_t) -> *const c_char {
+    match BACKEND_VERSIONS
+        .iter()
+        .find(|(available, _)| *available as u32 == backend as u32)
+    {
+        Some((_, version)) => version.as_ptr(),
+        None => {
+            update_last_error(CApiError {
+                code: wasmer_error_code_t::WASMER_ERROR_CODE_UNSUPPORTED,
+                msg: "the backend is not available in this build".to_string(),
+            });
+            ptr::null()
+        }
+    }
+}
+
+/// Prefixes serialized module bytes with the version stamp of the
+/// runtime, see `strip_version_stamp`.
+pub(crate) fn add_version_stamp(bytes: &[u8]) -> Vec<u8> {
+    let mut stamped = Vec::with_capacity(STAMP_LEN + bytes.len());
+    stamped.extend_from_slice(&STAMP_MAGIC);
+    stamped.extend_from_slice(WASMER_VERSION_HASH.as_bytes());
+    stamped.extend_from_slice(&SERIALIZATION_FORMAT_VERSION.to_le_bytes());
+    stamped.resize(STAMP_LEN, 0);
+    stamped.extend_from_slice(bytes);
+
+    stamped
+}
+
+/// Checks that serialized module bytes were stamped by this version of
+/// the runtime, in this format, and returns them without their stamp.
+/// The compiled code of another version must not be loaded, even if it
+/// deserializes.
+pub(crate) fn strip_version_stamp(bytes: &[u8]) -> Result<&[u8], CApiError> {
+    if bytes.len() < STAMP_LEN || bytes[..STAMP_MAGIC.len()] != STAMP_MAGIC {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: "the serialized module has no version stamp".to_string(),
+        });
+    }
+
+    let (stamp, body) = bytes.split_at(STAMP_LEN);
+    if &stamp[STAMP_MAGIC.len()..STAMP_FORMAT_OFFSET] != WASMER_VERSION_HASH.as_bytes() {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: format!(
+                "the serialized module was produced by another version of wasmer than {}",
+                env!("CARGO_PKG_VERSION")
+            ),
+        });
+    }
+
+    let mut format_version = [0; 4];
+    format_version.copy_from_slice(&stamp[STAMP_FORMAT_OFFSET..STAMP_FORMAT_OFFSET + 4]);
+    let format_version = u32::from_le_bytes(format_version);
+    if format_version != SERIALIZATION_FORMAT_VERSION
+        || stamp[STAMP_FORMAT_OFFSET + 4..]
+            .iter()
+            .any(|&byte| byte != 0)
+    {
+        return Err(CApiError {
+            code: wasmer_error_code_t::WASMER_ERROR_CODE_INVALID_ARGUMENT,
+            msg: format!(
+                "the serialized module has the format version {}, while this version of wasmer reads {}",
+                format_version, SERIALIZATION_FORMAT_VERSION
+            ),
+        });
+    }
+
+    Ok(body)
+}
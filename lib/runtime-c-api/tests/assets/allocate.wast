@@ -0,0 +1,15 @@
+(module
+  (import "env" "get_name" (func $get_name (result i32)))
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 1024))
+
+  ;; A bump allocator, returning the offset of `len` free bytes.
+  (func (export "allocate") (param $len i32) (result i32)
+      global.get $next
+      global.get $next
+      local.get $len
+      i32.add
+      global.set $next)
+
+  (func (export "call_get_name") (result i32)
+      call $get_name))
@@ -0,0 +1,10 @@
+(module
+  (global $count (mut i64) (i64.const 0))
+  (global $flag (mut i32) (i32.const 0))
+
+  (func (export "inc") (result i64)
+      global.get $count
+      i64.const 1
+      i64.add
+      global.set $count
+      global.get $count))
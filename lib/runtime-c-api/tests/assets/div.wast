@@ -0,0 +1,25 @@
+(module
+  (func (export "i32_div_s") (param i32 i32) (result i32)
+      local.get 0
+      local.get 1
+      i32.div_s)
+  (func (export "i32_div_u") (param i32 i32) (result i32)
+      local.get 0
+      local.get 1
+      i32.div_u)
+  (func (export "i32_rem_s") (param i32 i32) (result i32)
+      local.get 0
+      local.get 1
+      i32.rem_s)
+  (func (export "i64_div_s") (param i64 i64) (result i64)
+      local.get 0
+      local.get 1
+      i64.div_s)
+  (func (export "i64_div_u") (param i64 i64) (result i64)
+      local.get 0
+      local.get 1
+      i64.div_u)
+  (func (export "i64_rem_s") (param i64 i64) (result i64)
+      local.get 0
+      local.get 1
+      i64.rem_s))
@@ -0,0 +1,5 @@
+(module
+  (import "env" "base" (global $base i32))
+  (global $copy i32 (global.get $base))
+  (global $answer i64 (i64.const 42))
+  (global $ratio f64 (f64.const 1.5)))
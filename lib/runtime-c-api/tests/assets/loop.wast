@@ -0,0 +1,19 @@
+(module
+  (global $iterations (mut i64) (i64.const 0))
+
+  (func (export "spin")
+      loop
+        br 0
+      end)
+  (func (export "answer") (result i32)
+      i32.const 42)
+  (func (export "count")
+      loop
+        global.get $iterations
+        i64.const 1
+        i64.add
+        global.set $iterations
+        br 0
+      end)
+  (func (export "iterations") (result i64)
+      global.get $iterations))
@@ -0,0 +1,18 @@
+(module
+  (memory 1)
+  (func (export "load_div_u") (param i32 i32) (result i32)
+      local.get 0
+      i32.load
+      local.get 1
+      i32.div_u)
+  (func (export "load_rem_s") (param i32 i64) (result i64)
+      local.get 0
+      i64.load
+      local.get 1
+      i64.rem_s)
+  (func (export "store") (param i32 i32)
+      local.get 0
+      local.get 1
+      i32.store)
+  (func (export "fail")
+      unreachable))
@@ -0,0 +1,15 @@
+(module
+  ;; Returns `n` after recursing `n` times.
+  (func $recurse (export "recurse") (param $n i32) (result i32)
+      local.get $n
+      i32.eqz
+      if (result i32)
+        i32.const 0
+      else
+        local.get $n
+        i32.const 1
+        i32.sub
+        call $recurse
+        i32.const 1
+        i32.add
+      end))
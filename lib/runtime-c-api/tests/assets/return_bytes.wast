@@ -0,0 +1,15 @@
+(module
+  (memory (export "memory") 1)
+  (data (i32.const 16) "Hello")
+
+  ;; Returns the offset 16 in the low 32 bits, and the length 5 in the
+  ;; high 32 bits.
+  (func (export "get_bytes") (result i64)
+      i64.const 21474836496)
+
+  ;; Returns 10 bytes at the offset 65530, past the end of the memory.
+  (func (export "get_out_of_bounds") (result i64)
+      i64.const 42949738490)
+
+  (func (export "get_offset") (result i32)
+      i32.const 16))
@@ -0,0 +1,6 @@
+(module
+  ;; Export names must be unique whatever the kind of the exports: this
+  ;; module is invalid.
+  (func (export "answer") (result i32)
+      i32.const 42)
+  (global (export "answer") i32 (i32.const 42)))
@@ -0,0 +1,8 @@
+(module
+  (global $initialized (mut i32) (i32.const 0))
+  (func $start
+    i32.const 42
+    global.set $initialized)
+  (func (export "initialized") (result i32)
+    global.get $initialized)
+  (start $start))
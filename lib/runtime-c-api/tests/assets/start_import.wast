@@ -0,0 +1,5 @@
+(module
+  (import "env" "inc" (func $inc))
+  (func $start
+    call $inc)
+  (start $start))
@@ -0,0 +1,7 @@
+(module
+  (table 3 funcref)
+  (func $first)
+  (func $second)
+
+  ;; The last element is left null.
+  (elem (i32.const 0) $second $first))
@@ -0,0 +1,9 @@
+(module
+  (table 1 4 funcref)
+
+  ;; Grows the table by `delta` null elements, and returns its previous
+  ;; size, or -1 upon failure.
+  (func (export "grow") (param $delta i32) (result i32)
+      ref.null func
+      local.get $delta
+      table.grow))
@@ -0,0 +1,4 @@
+(module
+  (func (export "fail")
+      nop
+      unreachable))
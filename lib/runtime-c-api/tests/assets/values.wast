@@ -0,0 +1,15 @@
+(module
+  (func (export "i32_identity") (param i32) (result i32)
+      local.get 0)
+
+  (func (export "i64_identity") (param i64) (result i64)
+      local.get 0)
+
+  (func (export "f32_identity") (param f32) (result f32)
+      local.get 0)
+
+  (func (export "f64_identity") (param f64) (result f64)
+      local.get 0)
+
+  (func (export "i64_after_i32") (param i32 i64) (result i64)
+      local.get 1))
@@ -0,0 +1,9 @@
+(module
+  ;; The WASI `proc_raise` function is not implemented by the runtime: it
+  ;; panics.
+  (import "wasi_snapshot_preview1" "proc_raise" (func $proc_raise (param i32) (result i32)))
+  (memory (export "memory") 1)
+  ;; Raises the signal `sig` through WASI.
+  (func (export "raise") (param $sig i32) (result i32)
+      local.get $sig
+      call $proc_raise))
@@ -0,0 +1,68 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+
+static int malloc_count = 0;
+static int free_count = 0;
+
+void *counting_malloc(size_t size)
+{
+    malloc_count++;
+    return malloc(size);
+}
+
+void counting_free(void *ptr)
+{
+    free_count++;
+    free(ptr);
+}
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/sum.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    const uint8_t *stripped_bytes = NULL;
+    uint32_t stripped_length = 0;
+
+    // The buffers returned to C are allocated with the registered
+    // `malloc`, and can be freed with the matching `free`.
+    assert(wasmer_set_allocator(counting_malloc, counting_free) == WASMER_OK);
+    assert(wasmer_strip_custom_sections(bytes, len, &stripped_bytes, &stripped_length) == WASMER_OK);
+    assert(malloc_count == 1);
+    assert(wasmer_validate(stripped_bytes, stripped_length));
+    counting_free((void *) stripped_bytes);
+    assert(free_count == 1);
+
+    // `wasmer_buffer_destroy` frees them with the registered `free` too.
+    assert(wasmer_strip_custom_sections(bytes, len, &stripped_bytes, &stripped_length) == WASMER_OK);
+    assert(malloc_count == 2);
+    wasmer_buffer_destroy((uint8_t *) stripped_bytes, stripped_length);
+    assert(free_count == 2);
+
+    // Both functions are needed.
+    assert(wasmer_set_allocator(counting_malloc, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(wasmer_set_allocator(NULL, counting_free) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    // Without an allocator, the Rust allocator is used again.
+    assert(wasmer_set_allocator(NULL, NULL) == WASMER_OK);
+    assert(wasmer_strip_custom_sections(bytes, len, &stripped_bytes, &stripped_length) == WASMER_OK);
+    wasmer_buffer_destroy((uint8_t *) stripped_bytes, stripped_length);
+    assert(malloc_count == 2);
+    assert(free_count == 2);
+
+    free(bytes);
+
+    return 0;
+}
@@ -0,0 +1,78 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+typedef struct {
+    uint32_t count;
+    uint32_t blocks[16];
+} coverage_t;
+
+void record_block(void *user_data, uint32_t block_id)
+{
+    coverage_t *coverage = (coverage_t *) user_data;
+    if (coverage->count < 16) {
+        coverage->blocks[coverage->count] = block_id;
+    }
+    coverage->count++;
+}
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/memory_div.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.block_coverage = true;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+
+    coverage_t coverage;
+    memset(&coverage, 0, sizeof(coverage));
+    assert(wasmer_instance_set_block_coverage_callback(instance, record_block, &coverage) == WASMER_OK);
+
+    wasmer_value_t div_params[] = {
+        {.tag = WASM_I32, .value.I32 = 0},
+        {.tag = WASM_I32, .value.I32 = 1},
+    };
+    wasmer_value_t rem_params[] = {
+        {.tag = WASM_I32, .value.I32 = 0},
+        {.tag = WASM_I64, .value.I64 = 1},
+    };
+    wasmer_value_t results[1];
+
+    // The first function starts with the first block of the module.
+    assert(wasmer_instance_call(instance, "load_div_u", div_params, 2, results, 1) == WASMER_OK);
+    printf("Blocks entered (load_div_u): %u\n", coverage.count);
+    assert(coverage.count > 0);
+    assert(coverage.blocks[0] == 0);
+
+    // Another function enters other blocks.
+    uint32_t div_count = coverage.count;
+    assert(wasmer_instance_call(instance, "load_rem_s", rem_params, 2, results, 1) == WASMER_OK);
+    printf("Blocks entered (load_rem_s): %u\n", coverage.count - div_count);
+    assert(coverage.count > div_count);
+    assert(coverage.blocks[div_count] > 0);
+
+    // Removing the callback stops the reports.
+    uint32_t count = coverage.count;
+    assert(wasmer_instance_set_block_coverage_callback(instance, NULL, NULL) == WASMER_OK);
+    assert(wasmer_instance_call(instance, "load_div_u", div_params, 2, results, 1) == WASMER_OK);
+    assert(coverage.count == count);
+
+    assert(wasmer_instance_set_block_coverage_callback(NULL, record_block, &coverage) == WASMER_ERROR);
+
+    wasmer_instance_destroy(instance);
+    free(bytes);
+    return 0;
+}
@@ -0,0 +1,100 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+void add_div(wasmer_call_batch_t *batch, int32_t a, int32_t b)
+{
+    wasmer_value_t params[2];
+    params[0].tag = WASM_I32;
+    params[0].value.I32 = a;
+    params[1].tag = WASM_I32;
+    params[1].value.I32 = b;
+    assert(wasmer_call_batch_add(batch, "i32_div_s", params, 2) == WASMER_OK);
+}
+
+int main()
+{
+    FILE *file = fopen("assets/div.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate(&instance, bytes, len, NULL, 0) == WASMER_OK);
+
+    // Every call runs, and reports its own outcome.
+    {
+        wasmer_call_batch_t *batch = wasmer_call_batch_new(false);
+        uint32_t results_len = 1;
+        assert(wasmer_call_batch_results(batch, &results_len) == NULL);
+        assert(results_len == 0);
+
+        add_div(batch, 7, 2);
+        add_div(batch, 1, 0);
+        assert(wasmer_call_batch_add(batch, "missing", NULL, 0) == WASMER_OK);
+        add_div(batch, 8, 2);
+
+        assert(wasmer_call_batch_run(batch, instance) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_TRAP);
+
+        const wasmer_call_batch_result_t *results = wasmer_call_batch_results(batch, &results_len);
+        assert(results_len == 4);
+        assert(results[0].result == WASMER_OK);
+        assert(results[0].error_code == WASMER_ERROR_CODE_NONE);
+        assert(results[0].error_message == NULL);
+        assert(results[1].result == WASMER_ERROR);
+        assert(results[1].error_code == WASMER_ERROR_CODE_TRAP);
+        printf("Call 1 error: `%s`\n", results[1].error_message);
+        assert(strlen(results[1].error_message) > 0);
+        assert(results[2].result == WASMER_ERROR);
+        assert(results[2].error_code == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+        printf("Call 2 error: `%s`\n", results[2].error_message);
+        assert(results[3].result == WASMER_OK);
+
+        wasmer_call_batch_destroy(batch);
+    }
+
+    // The batch stops at the first failed call.
+    {
+        wasmer_call_batch_t *batch = wasmer_call_batch_new(true);
+        add_div(batch, 7, 2);
+        add_div(batch, 1, 0);
+        add_div(batch, 8, 2);
+
+        assert(wasmer_call_batch_run(batch, instance) == WASMER_ERROR);
+        uint32_t results_len = 0;
+        const wasmer_call_batch_result_t *results = wasmer_call_batch_results(batch, &results_len);
+        assert(results_len == 2);
+        assert(results[0].result == WASMER_OK);
+        assert(results[1].error_code == WASMER_ERROR_CODE_TRAP);
+
+        wasmer_call_batch_destroy(batch);
+    }
+
+    // A batch of successful calls succeeds.
+    {
+        wasmer_call_batch_t *batch = wasmer_call_batch_new(true);
+        add_div(batch, 7, 2);
+        assert(wasmer_call_batch_run(batch, instance) == WASMER_OK);
+        uint32_t results_len = 0;
+        assert(wasmer_call_batch_results(batch, &results_len) != NULL);
+        assert(results_len == 1);
+
+        assert(wasmer_call_batch_run(batch, NULL) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+        assert(wasmer_call_batch_add(batch, NULL, NULL, 0) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+        wasmer_call_batch_destroy(batch);
+    }
+
+    wasmer_instance_destroy(instance);
+    free(bytes);
+
+    return 0;
+}
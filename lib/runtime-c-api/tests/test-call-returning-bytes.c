@@ -0,0 +1,126 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/return_bytes.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    wasmer_result_t compile_result = wasmer_instantiate(&instance, bytes, len, imports, 0);
+    printf("Compile result: %d\n", compile_result);
+    assert(compile_result == WASMER_OK);
+
+    wasmer_value_t params[] = {};
+    uint8_t out[16];
+    uint32_t written = 0;
+
+    // `get_bytes` returns the 5 bytes of "Hello".
+    wasmer_result_t call_result = wasmer_instance_call_returning_packed_bytes(instance, "get_bytes", params, 0, out, sizeof(out), &written);
+    printf("Call result: %d\n", call_result);
+    assert(call_result == WASMER_OK);
+    assert(written == 5);
+    assert(memcmp(out, "Hello", 5) == 0);
+
+    // The length is written even if the buffer is too small.
+    written = 0;
+    assert(wasmer_instance_call_returning_packed_bytes(instance, "get_bytes", params, 0, out, 4, &written) == WASMER_ERROR);
+    assert(written == 5);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    // The bytes must be within the memory.
+    assert(wasmer_instance_call_returning_packed_bytes(instance, "get_out_of_bounds", params, 0, out, sizeof(out), &written) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    // The function must return a packed (ptr, len) pair.
+    written = 42;
+    assert(wasmer_instance_call_returning_packed_bytes(instance, "get_offset", params, 0, out, sizeof(out), &written) == WASMER_ERROR);
+    assert(written == 0);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    // Without the explicit opt-in, an i64 is not read as a packed pair.
+    written = 42;
+    assert(wasmer_instance_call_returning_bytes(instance, "get_bytes", params, 0, out, sizeof(out), &written) == WASMER_ERROR);
+    assert(written == 0);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    assert(wasmer_instance_call_returning_packed_bytes(instance, "get_bytes", params, 0, out, sizeof(out), NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    printf("Destroy instance\n");
+    wasmer_instance_destroy(instance);
+
+    // Every operator costs 1 point.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_with_gas_metering(&module, bytes, len) == WASMER_OK);
+    assert(wasmer_module_instantiate(module, &instance, imports, 0) == WASMER_OK);
+
+    // `wasmer_instance_execute_packed` reads the gas used and a copy of
+    // the return data in one call.
+    uint64_t gas_used = 0;
+    uint8_t *return_data = NULL;
+    uint32_t return_data_length = 0;
+    wasmer_trap_code_t trap_code = WASMER_TRAP_UNKNOWN;
+    call_result = wasmer_instance_execute_packed(instance, "get_bytes", params, 0, 100, &gas_used, &return_data, &return_data_length, &trap_code);
+    assert(call_result == WASMER_OK);
+    assert(gas_used > 0);
+    assert(gas_used == wasmer_instance_get_points_used(instance));
+    assert(return_data_length == 5);
+    assert(memcmp(return_data, "Hello", 5) == 0);
+    assert(trap_code == WASMER_TRAP_UNKNOWN);
+    wasmer_buffer_destroy(return_data, return_data_length);
+
+    // Running out of gas is reported as a trap, with the gas used and
+    // without return data.
+    call_result = wasmer_instance_execute_packed(instance, "get_bytes", params, 0, 0, &gas_used, &return_data, &return_data_length, &trap_code);
+    assert(call_result == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_OUT_OF_GAS);
+    assert(gas_used > 0);
+    assert(return_data == NULL);
+    assert(return_data_length == 0);
+    assert(trap_code == WASMER_TRAP_OUT_OF_GAS);
+
+    // The return data must be within the memory.
+    assert(wasmer_instance_execute_packed(instance, "get_out_of_bounds", params, 0, 100, &gas_used, &return_data, &return_data_length, &trap_code) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(return_data == NULL);
+
+    // The function must return a packed (ptr, len) pair, or nothing.
+    gas_used = 42;
+    assert(wasmer_instance_execute_packed(instance, "get_offset", params, 0, 100, &gas_used, &return_data, &return_data_length, &trap_code) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(gas_used == 0);
+
+    // Without the explicit opt-in, an i64 is not read as a packed pair.
+    gas_used = 42;
+    assert(wasmer_instance_execute(instance, "get_bytes", params, 0, 100, &gas_used, &return_data, &return_data_length, &trap_code) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(gas_used == 0);
+    assert(return_data == NULL);
+
+    assert(wasmer_instance_execute_packed(instance, "get_bytes", params, 0, 100, &gas_used, NULL, &return_data_length, &trap_code) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+    free(bytes);
+
+    return 0;
+}
@@ -0,0 +1,124 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+int main()
+{
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/sum.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_instance_t *instance = NULL;
+
+    // A null options struct is rejected.
+    wasmer_result_t instantiate_result = wasmer_instantiate_with_options(&instance, bytes, len, NULL);
+    assert(instantiate_result == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    // A missing or too large `struct_size` is rejected.
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    instantiate_result = wasmer_instantiate_with_options(&instance, bytes, len, &options);
+    printf("Instantiate result (no struct_size): %d\n", instantiate_result);
+    assert(instantiate_result == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    options.struct_size = sizeof(options) + 8;
+    instantiate_result = wasmer_instantiate_with_options(&instance, bytes, len, &options);
+    printf("Instantiate result (too large struct_size): %d\n", instantiate_result);
+    assert(instantiate_result == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    // A struct from an older header: the fields past `struct_size` are
+    // read as zeroes, even if the caller left garbage in them.
+    memset(&options, 0xff, sizeof(options));
+    options.struct_size = sizeof(uint32_t);
+    instantiate_result = wasmer_instantiate_with_options(&instance, bytes, len, &options);
+    printf("Instantiate result (short struct_size): %d\n", instantiate_result);
+    assert(instantiate_result == WASMER_OK);
+
+    wasmer_value_t param_one;
+    param_one.tag = WASM_I32;
+    param_one.value.I32 = 7;
+    wasmer_value_t param_two;
+    param_two.tag = WASM_I32;
+    param_two.value.I32 = 8;
+    wasmer_value_t params[] = {param_one, param_two};
+
+    wasmer_value_t result_one;
+    wasmer_value_t results[] = {result_one};
+
+    wasmer_result_t call_result = wasmer_instance_call(instance, "sum", params, 2, results, 1);
+    printf("Call result:  %d\n", call_result);
+    assert(call_result == WASMER_OK);
+    assert(results[0].value.I32 == 15);
+    assert(wasmer_instance_get_points_used(instance) == 0);
+    wasmer_instance_destroy(instance);
+
+    // The full struct, with metering.
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.runtime_breakpoints = true;
+    options.gas_limit = 1000;
+    instantiate_result = wasmer_instantiate_with_options(&instance, bytes, len, &options);
+    printf("Instantiate result (metering): %d\n", instantiate_result);
+    assert(instantiate_result == WASMER_OK);
+
+    call_result = wasmer_instance_call(instance, "sum", params, 2, results, 1);
+    printf("Call result:  %d\n", call_result);
+    printf("Points used: %llu\n", (unsigned long long) wasmer_instance_get_points_used(instance));
+    assert(call_result == WASMER_OK);
+    assert(results[0].value.I32 == 15);
+    assert(wasmer_instance_get_points_used(instance) > 0);
+    wasmer_instance_destroy(instance);
+
+    // A compilation within its timeout succeeds.
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.compile_timeout_ms = 60000;
+    instantiate_result = wasmer_instantiate_with_options(&instance, bytes, len, &options);
+    printf("Instantiate result (compile timeout): %d\n", instantiate_result);
+    assert(instantiate_result == WASMER_OK);
+    call_result = wasmer_instance_call(instance, "sum", params, 2, results, 1);
+    assert(call_result == WASMER_OK);
+    assert(results[0].value.I32 == 15);
+    wasmer_instance_destroy(instance);
+
+    // Denying an opcode the module does not use changes nothing.
+    uint16_t denied_opcodes[] = {94 /* i32.div_u */};
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.denied_opcodes = denied_opcodes;
+    options.denied_opcodes_len = 1;
+    instantiate_result = wasmer_instantiate_with_options(&instance, bytes, len, &options);
+    printf("Instantiate result (denied i32.div_u): %d\n", instantiate_result);
+    assert(instantiate_result == WASMER_OK);
+    call_result = wasmer_instance_call(instance, "sum", params, 2, results, 1);
+    assert(call_result == WASMER_OK);
+    assert(results[0].value.I32 == 15);
+    wasmer_instance_destroy(instance);
+
+    // Denying one it uses rejects the module.
+    denied_opcodes[0] = 90; // i32.add
+    instantiate_result = wasmer_instantiate_with_options(&instance, bytes, len, &options);
+    printf("Instantiate result (denied i32.add): %d\n", instantiate_result);
+    assert(instantiate_result == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_COMPILE);
+
+    free(bytes);
+    return 0;
+}
@@ -0,0 +1,72 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/sum.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    // The module is received in chunks of 7 bytes, cutting through its
+    // header and its sections.
+    wasmer_compile_streaming_t *streaming = wasmer_compile_streaming_begin();
+    for (long offset = 0; offset < len; offset += 7) {
+        uint32_t chunk_len = len - offset < 7 ? len - offset : 7;
+        assert(wasmer_compile_streaming_feed(streaming, bytes + offset, chunk_len) == WASMER_OK);
+    }
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_streaming_finish(streaming, &module, NULL) == WASMER_OK);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate(module, &instance, imports, 0) == WASMER_OK);
+
+    wasmer_value_t params[] = {{.tag = WASM_I32, .value.I32 = 7}, {.tag = WASM_I32, .value.I32 = 8}};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "sum", params, 2, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 15);
+
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+
+    // An invalid header is rejected by its first chunk, which frees the
+    // streaming compilation.
+    streaming = wasmer_compile_streaming_begin();
+    assert(wasmer_compile_streaming_feed(streaming, (const uint8_t *) "\0asn", 4) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_COMPILE);
+
+    // So is an unknown section id.
+    streaming = wasmer_compile_streaming_begin();
+    assert(wasmer_compile_streaming_feed(streaming, bytes, 8) == WASMER_OK);
+    assert(wasmer_compile_streaming_feed(streaming, (const uint8_t *) "\x42\x00", 2) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_COMPILE);
+
+    // A module cut in the middle of a section is not compiled.
+    streaming = wasmer_compile_streaming_begin();
+    assert(wasmer_compile_streaming_feed(streaming, bytes, len - 1) == WASMER_OK);
+    module = NULL;
+    assert(wasmer_compile_streaming_finish(streaming, &module, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_COMPILE);
+    assert(module == NULL);
+
+    // The compilation can be aborted.
+    streaming = wasmer_compile_streaming_begin();
+    assert(wasmer_compile_streaming_feed(streaming, bytes, 8) == WASMER_OK);
+    wasmer_compile_streaming_destroy(streaming);
+
+    assert(wasmer_compile_streaming_feed(NULL, bytes, 8) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    free(bytes);
+
+    return 0;
+}
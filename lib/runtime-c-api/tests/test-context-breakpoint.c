@@ -0,0 +1,96 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+#define BREAKPOINT_VALUE_ASYNC_CALL (BREAKPOINT_VALUE_RESERVED_MAX + 1)
+
+static uint64_t breakpoint_value_in_inc = 1;
+static bool get_called = false;
+
+// Stops the call once it returns, e.g. to resume it asynchronously.
+void inc(wasmer_instance_context_t *ctx)
+{
+    breakpoint_value_in_inc = wasmer_instance_context_breakpoint_value(ctx);
+    wasmer_instance_context_set_breakpoint_value(ctx, BREAKPOINT_VALUE_ASYNC_CALL);
+}
+
+void mul(wasmer_instance_context_t *ctx)
+{
+}
+
+int32_t get(wasmer_instance_context_t *ctx)
+{
+    get_called = true;
+    return 0;
+}
+
+wasmer_import_t create_import(char* import_name, wasmer_import_func_t *func) {
+    wasmer_import_t import;
+    import.module_name.bytes = (const uint8_t *) "env";
+    import.module_name.bytes_len = 3;
+    import.import_name.bytes = (const uint8_t *) import_name;
+    import.import_name.bytes_len = strlen(import_name);
+    import.tag = WASM_FUNCTION;
+    import.value.func = func;
+
+    return import;
+}
+
+int main()
+{
+    wasmer_value_tag no_sig[] = {};
+    wasmer_value_tag get_returns_sig[] = {WASM_I32};
+    wasmer_import_func_t *inc_func = wasmer_import_func_new((void (*)(void *)) inc, no_sig, 0, no_sig, 0);
+    wasmer_import_func_t *mul_func = wasmer_import_func_new((void (*)(void *)) mul, no_sig, 0, no_sig, 0);
+    wasmer_import_func_t *get_func = wasmer_import_func_new((void (*)(void *)) get, no_sig, 0, get_returns_sig, 1);
+    wasmer_import_t imports[] = {
+        create_import("inc", inc_func),
+        create_import("mul", mul_func),
+        create_import("get", get_func),
+    };
+
+    FILE *file = fopen("assets/inc.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.runtime_breakpoints = true;
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_with_options(&module, bytes, len, &options) == WASMER_OK);
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate_with_options(module, &instance, imports, 3, &options, NULL) == WASMER_OK);
+
+    // The value set by `inc` stops `inc_and_get` before it calls `get`.
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "inc_and_get", params, 0, results, 1) == WASMER_ERROR);
+    assert(breakpoint_value_in_inc == BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(!get_called);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_ASYNC_CALL);
+
+    // Once reset, the calls run again.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(wasmer_instance_call(instance, "mul_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(get_called);
+
+    assert(wasmer_instance_context_breakpoint_value(NULL) == BREAKPOINT_VALUE_NO_BREAKPOINT);
+    wasmer_instance_context_set_breakpoint_value(NULL, BREAKPOINT_VALUE_ASYNC_CALL);
+
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+    wasmer_import_func_destroy(inc_func);
+    wasmer_import_func_destroy(mul_func);
+    wasmer_import_func_destroy(get_func);
+    free(bytes);
+
+    return 0;
+}
@@ -0,0 +1,115 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+static const char *name = "wasmer";
+static wasmer_error_code_t last_error_code = WASMER_ERROR_CODE_NONE;
+
+int32_t get_name(wasmer_instance_context_t *ctx)
+{
+    int64_t offset = wasmer_instance_context_return_bytes(ctx, (const uint8_t *) name, strlen(name));
+    if (offset < 0) {
+        last_error_code = wasmer_last_error_code();
+        return -1;
+    }
+
+    return (int32_t) offset;
+}
+
+int main()
+{
+    wasmer_value_tag params_sig[] = {};
+    wasmer_value_tag returns_sig[] = {WASM_I32};
+    wasmer_import_func_t *func = wasmer_import_func_new((void (*)(void *)) get_name, params_sig, 0, returns_sig, 1);
+
+    char *module_name = "env";
+    wasmer_byte_array module_name_bytes = {
+        .bytes = (const uint8_t *) module_name,
+        .bytes_len = strlen(module_name),
+    };
+
+    char *import_name = "get_name";
+    wasmer_byte_array import_name_bytes = {
+        .bytes = (const uint8_t *) import_name,
+        .bytes_len = strlen(import_name),
+    };
+
+    wasmer_import_t import = {
+        .module_name = module_name_bytes,
+        .import_name = import_name_bytes,
+        .tag = WASM_FUNCTION,
+        .value.func = func,
+    };
+
+    wasmer_import_t imports[] = {import};
+
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/allocate.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate(&instance, bytes, len, imports, 1) == WASMER_OK);
+
+    const wasmer_instance_context_t *ctx = wasmer_instance_context_get(instance);
+    const wasmer_memory_t *memory = wasmer_instance_context_memory(ctx, 0);
+    uint8_t *memory_bytes = wasmer_memory_data(memory);
+
+    // The bytes are copied into the buffers of the allocator of the module.
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "call_get_name", params, 0, results, 1) == WASMER_OK);
+    printf("First offset: %d\n", results[0].value.I32);
+    assert(results[0].value.I32 == 1024);
+    assert(memcmp(memory_bytes + 1024, name, strlen(name)) == 0);
+
+    assert(wasmer_instance_call(instance, "call_get_name", params, 0, results, 1) == WASMER_OK);
+    printf("Second offset: %d\n", results[0].value.I32);
+    assert(results[0].value.I32 == 1024 + (int32_t) strlen(name));
+    assert(memcmp(memory_bytes + 1024 + strlen(name), name, strlen(name)) == 0);
+
+    // The module does not export the configured allocator.
+    assert(wasmer_instance_set_wasm_allocator_export(instance, "malloc") == WASMER_OK);
+    assert(wasmer_instance_call(instance, "call_get_name", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == -1);
+    assert(last_error_code == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    // The allocator export is set per instance.
+    wasmer_instance_t *other_instance = NULL;
+    assert(wasmer_instantiate(&other_instance, bytes, len, imports, 1) == WASMER_OK);
+    assert(wasmer_instance_call(other_instance, "call_get_name", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 1024);
+    wasmer_instance_destroy(other_instance);
+
+    // An export which is not a function with the expected signature is rejected.
+    assert(wasmer_instance_set_wasm_allocator_export(instance, "memory") == WASMER_OK);
+    last_error_code = WASMER_ERROR_CODE_NONE;
+    assert(wasmer_instance_call(instance, "call_get_name", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == -1);
+    assert(last_error_code == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(wasmer_instance_set_wasm_allocator_export(instance, "call_get_name") == WASMER_OK);
+    last_error_code = WASMER_ERROR_CODE_NONE;
+    assert(wasmer_instance_call(instance, "call_get_name", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == -1);
+    assert(last_error_code == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    assert(wasmer_instance_set_wasm_allocator_export(instance, "allocate") == WASMER_OK);
+    assert(wasmer_instance_set_wasm_allocator_export(instance, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    assert(wasmer_instance_set_wasm_allocator_export(NULL, "allocate") == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    assert(wasmer_instance_context_return_bytes(NULL, (const uint8_t *) name, strlen(name)) == -1);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    wasmer_import_func_destroy(func);
+    wasmer_instance_destroy(instance);
+    free(bytes);
+
+    return 0;
+}
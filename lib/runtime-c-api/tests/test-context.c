@@ -51,6 +51,13 @@ counter_data *init_counter(int32_t value, int32_t amount) {
     return counter;
 }
 
+static int finalized_count = 0;
+
+void finalize_counter(void *data) {
+    finalized_count++;
+    free(data);
+}
+
 void assert_counter(wasmer_instance_t *instance, int32_t expected) {
     wasmer_value_t result_one;
     wasmer_value_t params[] = {};
@@ -126,10 +133,63 @@ int main()
     assert_counter(instance, 12);
     assert_counter(instance, 17);
 
+    // The second data slot is independent of the first one
+    const wasmer_instance_context_t *ctx = wasmer_instance_context_get(instance);
+    assert(wasmer_instance_context_data2_get(ctx) == NULL);
+    counter_data *other_counter = init_counter(0, 1);
+    wasmer_instance_context_data2_set(instance, other_counter);
+    assert(wasmer_instance_context_data2_get(ctx) == other_counter);
+    assert(wasmer_instance_context_data_get_keyed(ctx, WASMER_CONTEXT_DATA2_KEY) == other_counter);
+    assert(wasmer_instance_context_data_get(ctx) == counter);
+    assert_counter(instance, 22);
+    assert(other_counter->value == 0);
+    wasmer_instance_context_data2_set(instance, NULL);
+    assert(wasmer_instance_context_data2_get(ctx) == NULL);
+    assert(wasmer_instance_context_data2_get(NULL) == NULL);
+    free(other_counter);
+
+    // The keyed data is independent of the first data slot and of each other
+    counter_data *first_keyed_counter = init_counter(0, 1);
+    counter_data *second_keyed_counter = init_counter(0, 1);
+    assert(wasmer_instance_context_data_get_keyed(ctx, 1) == NULL);
+    wasmer_instance_context_data_set_keyed(instance, 1, first_keyed_counter);
+    wasmer_instance_context_data_set_keyed(instance, 42, second_keyed_counter);
+    assert(wasmer_instance_context_data_get_keyed(ctx, 1) == first_keyed_counter);
+    assert(wasmer_instance_context_data_get_keyed(ctx, 42) == second_keyed_counter);
+    assert(wasmer_instance_context_data_get_keyed(ctx, 2) == NULL);
+    assert(wasmer_instance_context_data_get(ctx) == counter);
+    wasmer_instance_context_data_set_keyed(instance, 1, second_keyed_counter);
+    assert(wasmer_instance_context_data_get_keyed(ctx, 1) == second_keyed_counter);
+    wasmer_instance_context_data_set_keyed(instance, 1, NULL);
+    assert(wasmer_instance_context_data_get_keyed(ctx, 1) == NULL);
+    assert(wasmer_instance_context_data_get_keyed(ctx, 42) == second_keyed_counter);
+    assert(wasmer_instance_context_data_get_keyed(NULL, 42) == NULL);
+    free(first_keyed_counter);
+    free(second_keyed_counter);
+
+    // Give the ownership of a counter to a second instance
+    wasmer_instance_t *owning_instance = NULL;
+    assert(wasmer_instantiate(&owning_instance, wasm_file.bytes, wasm_file.bytes_len, imports, 3) == WASMER_OK);
+    wasmer_instance_context_data_set_with_finalizer(owning_instance, init_counter(0, 1), finalize_counter);
+    assert_counter(owning_instance, 1);
+
+    // Replacing the counter finalizes it, but setting it again does not
+    wasmer_instance_context_data_set_with_finalizer(owning_instance, init_counter(10, 1), finalize_counter);
+    assert(finalized_count == 1);
+    assert_counter(owning_instance, 11);
+    const wasmer_instance_context_t *owning_ctx = wasmer_instance_context_get(owning_instance);
+    void *owned_counter = wasmer_instance_context_data_get(owning_ctx);
+    wasmer_instance_context_data_set_with_finalizer(owning_instance, owned_counter, finalize_counter);
+    assert(finalized_count == 1);
+
+    wasmer_instance_destroy(owning_instance);
+    assert(finalized_count == 2);
+
     // Clear resources
     wasmer_import_func_destroy(inc_func);
     wasmer_import_func_destroy(get_func);
     wasmer_instance_destroy(instance);
+    assert(finalized_count == 2);
     free(counter);
     free(wasm_file.bytes);
 
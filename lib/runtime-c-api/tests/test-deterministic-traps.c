@@ -0,0 +1,69 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+wasmer_trap_code_t trap_code(wasmer_instance_t *instance, const char *name, wasmer_value_t *params, uint32_t params_len, uint32_t results_len)
+{
+    wasmer_value_t result;
+    wasmer_value_t results[] = {result};
+    assert(wasmer_instance_call(instance, name, params, params_len, results, results_len) == WASMER_ERROR);
+
+    wasmer_trap_info_t trap_info;
+    assert(wasmer_instance_last_trap_info(instance, &trap_info) == WASMER_OK);
+    printf("Trap code of `%s`: %d\n", name, trap_info.code);
+    return trap_info.code;
+}
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/memory_div.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.deterministic_traps = true;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+
+    wasmer_value_t out_of_bounds_params[] = {
+        {.tag = WASM_I32, .value.I32 = 65536},
+        {.tag = WASM_I32, .value.I32 = 1},
+    };
+    wasmer_value_t division_by_zero_params[] = {
+        {.tag = WASM_I32, .value.I32 = 0},
+        {.tag = WASM_I32, .value.I32 = 0},
+    };
+    wasmer_value_t store_params[] = {
+        {.tag = WASM_I32, .value.I32 = 65535},
+        {.tag = WASM_I32, .value.I32 = 1},
+    };
+    wasmer_value_t params[] = {};
+
+    // Every trap reports its kind.
+    assert(trap_code(instance, "load_div_u", out_of_bounds_params, 2, 1) == WASMER_TRAP_MEMORY_OUT_OF_BOUNDS);
+    assert(trap_code(instance, "load_div_u", division_by_zero_params, 2, 1) == WASMER_TRAP_ILLEGAL_ARITHMETIC);
+    assert(trap_code(instance, "store", store_params, 2, 0) == WASMER_TRAP_MEMORY_OUT_OF_BOUNDS);
+    assert(trap_code(instance, "fail", params, 0, 0) == WASMER_TRAP_UNREACHABLE);
+    wasmer_instance_destroy(instance);
+
+    // Without the option, `unreachable` and the arithmetic traps are
+    // still told apart.
+    options.deterministic_traps = false;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    assert(trap_code(instance, "load_div_u", division_by_zero_params, 2, 1) == WASMER_TRAP_ILLEGAL_ARITHMETIC);
+    assert(trap_code(instance, "fail", params, 0, 0) == WASMER_TRAP_UNREACHABLE);
+    wasmer_instance_destroy(instance);
+
+    free(bytes);
+    return 0;
+}
@@ -0,0 +1,46 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/counter.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+
+    // The base cost, 1 function without locals, its 6 opcodes, `end`
+    // included, and 2 globals.
+    uint64_t gas = 0;
+    assert(wasmer_estimate_compile_gas(bytes, len, &options, &gas) == WASMER_OK);
+    printf("Compile gas: %llu\n", (unsigned long long) gas);
+    assert(gas == 10000 + 1000 + 6 * 10 + 2 * 100);
+
+    // The estimate is deterministic.
+    uint64_t other_gas = 0;
+    assert(wasmer_estimate_compile_gas(bytes, len, &options, &other_gas) == WASMER_OK);
+    assert(other_gas == gas);
+
+    uint8_t invalid_bytes[] = {0x00, 0x61, 0x73, 0x6d, 0x02, 0x00, 0x00, 0x00};
+    assert(wasmer_estimate_compile_gas(invalid_bytes, sizeof(invalid_bytes), &options, &gas) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_COMPILE);
+
+    assert(wasmer_estimate_compile_gas(bytes, len, NULL, &gas) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    assert(wasmer_estimate_compile_gas(NULL, 0, &options, &gas) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    free(bytes);
+
+    return 0;
+}
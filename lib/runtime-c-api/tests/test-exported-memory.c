@@ -60,15 +60,96 @@ int main()
     uint32_t memory_length = wasmer_memory_length(memory);
     assert(memory_length == 17);
 
+    uint32_t peak_pages = 0;
+    assert(wasmer_instance_get_peak_memory_pages(instance, &peak_pages) == WASMER_OK);
+    assert(peak_pages == memory_length);
+
     // Read the data from the memory.
     uint8_t *memory_data = wasmer_memory_data(memory);
+
+    uint8_t *instance_memory_data = NULL;
+    uint32_t instance_memory_data_length = 0;
+    assert(wasmer_instance_memory_data(instance, 0, &instance_memory_data, &instance_memory_data_length) == WASMER_OK);
+    assert(instance_memory_data == memory_data);
+    assert(instance_memory_data_length == wasmer_memory_data_length(memory));
+    assert(wasmer_instance_memory_data(instance, 1, &instance_memory_data, &instance_memory_data_length) == WASMER_ERROR);
     uint8_t *returned_string = memory_data + results[0].value.I32;
 
     printf("Returned string from Wasm: %s\n", returned_string);
     assert(strcmp("Hello, World!", (const char *) returned_string) == 0);
 
+    // Compare the memory with an image of itself.
+    uint8_t *image = malloc(instance_memory_data_length);
+    memcpy(image, instance_memory_data, instance_memory_data_length);
+    assert(wasmer_instance_memory_equals(instance, 0, image, instance_memory_data_length) == 1);
+    assert(wasmer_instance_memory_equals(instance, 0, image, instance_memory_data_length - 1) == 0);
+    assert(wasmer_instance_memory_equals(instance, 1, image, instance_memory_data_length) == -1);
+
+    uint8_t hash[WASMER_MEMORY_HASH_LENGTH];
+    uint8_t other_hash[WASMER_MEMORY_HASH_LENGTH];
+    assert(wasmer_instance_memory_hash(instance, 0, hash, sizeof(hash)) == WASMER_OK);
+    assert(wasmer_instance_memory_hash(instance, 0, other_hash, sizeof(other_hash)) == WASMER_OK);
+    assert(memcmp(hash, other_hash, sizeof(hash)) == 0);
+    assert(wasmer_instance_memory_hash(instance, 0, other_hash, sizeof(other_hash) - 1) == WASMER_ERROR);
+    assert(wasmer_instance_memory_hash(instance, 1, other_hash, sizeof(other_hash)) == WASMER_ERROR);
+
+    // A single changed byte changes both results.
+    returned_string[0] = 'J';
+    assert(wasmer_instance_memory_equals(instance, 0, image, instance_memory_data_length) == 0);
+    assert(wasmer_instance_memory_hash(instance, 0, other_hash, sizeof(other_hash)) == WASMER_OK);
+    assert(memcmp(hash, other_hash, sizeof(hash)) != 0);
+    returned_string[0] = 'H';
+
+    // Prefaulting leaves the memory unchanged, even beyond its size.
+    assert(wasmer_instance_prefault_memory(instance, 0, memory_length + 1) == WASMER_OK);
+    assert(wasmer_instance_memory_equals(instance, 0, image, instance_memory_data_length) == 1);
+    assert(wasmer_instance_prefault_memory(instance, 1, 1) == WASMER_ERROR);
+
+    // Snapshot the memory, change it, and roll it back.
+    const uint8_t *snapshot = NULL;
+    uint32_t snapshot_length = 0;
+    assert(wasmer_instance_memory_export_buffer(instance, 0, &snapshot, &snapshot_length) == WASMER_OK);
+    assert(snapshot_length == instance_memory_data_length);
+    assert(memcmp(snapshot, image, snapshot_length) == 0);
+    assert(wasmer_instance_memory_export_buffer(instance, 1, &snapshot, &snapshot_length) == WASMER_ERROR);
+
+    returned_string[0] = 'J';
+    assert(wasmer_instance_memory_equals(instance, 0, image, instance_memory_data_length) == 0);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, snapshot, snapshot_length) == WASMER_OK);
+    assert(wasmer_instance_memory_equals(instance, 0, image, instance_memory_data_length) == 1);
+    assert(wasmer_instance_memory_import_buffer(instance, 1, snapshot, snapshot_length) == WASMER_ERROR);
+    wasmer_buffer_destroy((uint8_t *) snapshot, snapshot_length);
+    free(image);
+
     printf("Destroy instance\n");
     wasmer_instance_destroy(instance);
 
+    // The modules declaring a larger initial memory than
+    // `max_initial_memory_pages` are not instantiated.
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.max_initial_memory_pages = 16;
+    instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(instance == NULL);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile(&module, bytes, len) == WASMER_OK);
+    assert(wasmer_module_instantiate_with_options(module, &instance, imports, 0, &options, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(instance == NULL);
+
+    options.max_initial_memory_pages = 17;
+    assert(wasmer_module_instantiate_with_options(module, &instance, imports, 0, &options, NULL) == WASMER_OK);
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+
+    options.max_initial_memory_pages = 0;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    wasmer_instance_destroy(instance);
+    free(bytes);
+
     return 0;
 }
@@ -32,6 +32,8 @@ int main()
 
     int exports_length = wasmer_exports_len(exports);
     printf("Number of exports: %d\n", exports_length);
+    assert(wasmer_instance_exports_count(instance) == (uint32_t) exports_length);
+    assert(wasmer_instance_exports_count(NULL) == 0);
 
     {
         printf("\nCheck the `sum` exported function\n");
@@ -433,6 +435,71 @@ int main()
         }
     }
 
+    {
+        printf("\nCheck the exports by name and kind\n");
+
+        wasmer_export_t *export = wasmer_exports_get_by_name_and_kind(exports, "sum", WASM_FUNCTION);
+        assert(export != NULL);
+        assert(wasmer_export_kind(export) == WASM_FUNCTION);
+
+        wasmer_byte_array name_bytes = wasmer_export_name(export);
+        assert(name_bytes.bytes_len == sizeof("sum") - 1);
+        assert(memcmp(name_bytes.bytes, "sum", sizeof("sum") - 1) == 0);
+
+        assert(wasmer_exports_get_by_name_and_kind(exports, "sum", WASM_GLOBAL) == NULL);
+        assert(wasmer_exports_get_by_name_and_kind(exports, "missing", WASM_FUNCTION) == NULL);
+
+        export = wasmer_exports_get_by_name_and_kind(exports, "memory", WASM_MEMORY);
+        assert(export != NULL);
+        assert(wasmer_export_kind(export) == WASM_MEMORY);
+
+        assert(wasmer_exports_get_by_name_and_kind(exports, "sum", 42) == NULL);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+        assert(wasmer_exports_get_by_name_and_kind(exports, NULL, WASM_FUNCTION) == NULL);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+        assert(wasmer_exports_get_by_name_and_kind(exports, "\xff\xfe", WASM_FUNCTION) == NULL);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_UTF8);
+    }
+
+    {
+        printf("\nCheck a function and a global cannot share a name\n");
+
+        FILE *file = fopen("assets/shared_name.wasm", "r");
+        fseek(file, 0, SEEK_END);
+        long shared_name_bytes_length = ftell(file);
+        uint8_t *shared_name_bytes = (uint8_t *) malloc(shared_name_bytes_length);
+        fseek(file, 0, SEEK_SET);
+        fread(shared_name_bytes, 1, shared_name_bytes_length, file);
+        fclose(file);
+
+        assert(!wasmer_validate(shared_name_bytes, shared_name_bytes_length));
+
+        wasmer_module_t *shared_name_module = NULL;
+        assert(wasmer_compile(&shared_name_module, shared_name_bytes, shared_name_bytes_length) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_COMPILE);
+
+        free(shared_name_bytes);
+    }
+
+    {
+        printf("\nCheck the exports order is stable\n");
+
+        wasmer_exports_t *other_exports = NULL;
+        wasmer_instance_exports(instance, &other_exports);
+
+        assert(wasmer_exports_len(other_exports) == exports_length);
+
+        for (int nth = 0; nth < exports_length; ++nth) {
+            wasmer_byte_array name = wasmer_export_name(wasmer_exports_get(exports, nth));
+            wasmer_byte_array other_name = wasmer_export_name(wasmer_exports_get(other_exports, nth));
+
+            assert(name.bytes_len == other_name.bytes_len);
+            assert(memcmp(name.bytes, other_name.bytes, name.bytes_len) == 0);
+        }
+
+        wasmer_exports_destroy(other_exports);
+    }
+
     printf("\nDestroy instance\n");
 
     wasmer_instance_destroy(instance);
@@ -0,0 +1,78 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+wasmer_instance_t *instantiate(const char *path)
+{
+    FILE *file = fopen(path, "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.runtime_breakpoints = true;
+    options.gas_limit = 1000;
+    options.gas_breakdown = true;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    free(bytes);
+    return instance;
+}
+
+int main()
+{
+    // Every operator costs 1 point.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    // `inc` runs `global.get`, `i64.const`, `i64.add`, `global.set`,
+    // `global.get` and `end`.
+    wasmer_instance_t *instance = instantiate("assets/counter.wasm");
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "inc", params, 0, results, 1) == WASMER_OK);
+
+    wasmer_gas_breakdown_t breakdown;
+    assert(wasmer_instance_get_gas_breakdown(instance, &breakdown) == WASMER_OK);
+    printf("Breakdown: arithmetic %llu, memory %llu, control %llu, call %llu\n",
+        (unsigned long long) breakdown.arithmetic, (unsigned long long) breakdown.memory,
+        (unsigned long long) breakdown.control, (unsigned long long) breakdown.call);
+    assert(breakdown.arithmetic == 2);
+    assert(breakdown.memory == 3);
+    assert(breakdown.control == 1);
+    assert(breakdown.call == 0);
+    assert(wasmer_instance_get_points_used(instance) == 6);
+    wasmer_instance_destroy(instance);
+
+    // Only the recursive calls are charged to the calls.
+    instance = instantiate("assets/recursion.wasm");
+    wasmer_value_t recurse_params[] = {{.tag = WASM_I32, .value.I32 = 0}};
+    assert(wasmer_instance_call(instance, "recurse", recurse_params, 1, results, 1) == WASMER_OK);
+    assert(wasmer_instance_get_gas_breakdown(instance, &breakdown) == WASMER_OK);
+    assert(breakdown.arithmetic > 0);
+    assert(breakdown.control > 0);
+    assert(breakdown.call == 0);
+
+    recurse_params[0].value.I32 = 2;
+    assert(wasmer_instance_call(instance, "recurse", recurse_params, 1, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 2);
+    assert(wasmer_instance_get_gas_breakdown(instance, &breakdown) == WASMER_OK);
+    printf("Call gas: %llu\n", (unsigned long long) breakdown.call);
+    assert(breakdown.call == 2);
+    assert(breakdown.memory == 0);
+    wasmer_instance_destroy(instance);
+
+    return 0;
+}
@@ -0,0 +1,78 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+wasmer_instance_t *instantiate(const char *path)
+{
+    FILE *file = fopen(path, "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    wasmer_result_t instantiate_result = wasmer_instantiate(&instance, bytes, len, imports, 0);
+    assert(instantiate_result == WASMER_OK);
+    free(bytes);
+
+    return instance;
+}
+
+int64_t call_inc(wasmer_instance_t *instance)
+{
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    wasmer_result_t call_result = wasmer_instance_call(instance, "inc", params, 0, results, 1);
+    assert(call_result == WASMER_OK);
+
+    return results[0].value.I64;
+}
+
+int main()
+{
+    wasmer_instance_t *instance = instantiate("assets/counter.wasm");
+
+    // The counter has 2 mutable globals: a header of 36 bytes, and 9 bytes per global.
+    uint32_t snapshot_length = wasmer_instance_globals_snapshot_length(instance);
+    assert(snapshot_length == 36 + 2 * 9);
+    uint8_t *snapshot = malloc(snapshot_length);
+
+    assert(call_inc(instance) == 1);
+    assert(wasmer_instance_globals_snapshot(instance, snapshot, snapshot_length - 1) == WASMER_ERROR);
+    assert(wasmer_instance_globals_snapshot(instance, snapshot, snapshot_length) == WASMER_OK);
+    assert(call_inc(instance) == 2);
+    assert(call_inc(instance) == 3);
+
+    assert(wasmer_instance_globals_restore(instance, snapshot, snapshot_length) == WASMER_OK);
+    assert(call_inc(instance) == 2);
+
+    // A truncated snapshot is rejected, and nothing is restored.
+    assert(wasmer_instance_globals_restore(instance, snapshot, snapshot_length - 1) == WASMER_ERROR);
+    assert(call_inc(instance) == 3);
+
+    // A snapshot of another module is rejected.
+    wasmer_instance_t *other_instance = instantiate("assets/sum.wasm");
+    uint32_t other_snapshot_length = wasmer_instance_globals_snapshot_length(other_instance);
+    uint8_t *other_snapshot = malloc(other_snapshot_length);
+    assert(wasmer_instance_globals_snapshot(other_instance, other_snapshot, other_snapshot_length) == WASMER_OK);
+    assert(wasmer_instance_globals_restore(instance, other_snapshot, other_snapshot_length) == WASMER_ERROR);
+
+    int error_len = wasmer_last_error_length();
+    char *error_str = malloc(error_len);
+    wasmer_last_error_message(error_str, error_len);
+    printf("Error str: `%s`\n", error_str);
+    assert(strstr(error_str, "snapshot does not match the instance") != NULL);
+    free(error_str);
+
+    free(other_snapshot);
+    free(snapshot);
+    wasmer_instance_destroy(other_instance);
+    wasmer_instance_destroy(instance);
+
+    return 0;
+}
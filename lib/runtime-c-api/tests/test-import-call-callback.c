@@ -0,0 +1,158 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+typedef struct {
+    int inc_calls;
+    int get_calls;
+} import_calls_t;
+
+int32_t counter = 0;
+
+void inc_counter(wasmer_instance_context_t *ctx) {
+    counter += 1;
+}
+
+void mul_counter(wasmer_instance_context_t *ctx) {
+    counter *= 2;
+}
+
+int32_t get_counter(wasmer_instance_context_t *ctx) {
+    return counter;
+}
+
+void count_import_call(void *user_data, const char *import_name) {
+    import_calls_t *import_calls = (import_calls_t *) user_data;
+
+    if (strcmp(import_name, "inc") == 0) {
+        import_calls->inc_calls += 1;
+    } else if (strcmp(import_name, "get") == 0) {
+        import_calls->get_calls += 1;
+    }
+}
+
+void count_block(void *user_data, uint32_t block_id) {
+    *(uint32_t *) user_data += 1;
+}
+
+wasmer_import_t create_import(char* import_name, wasmer_import_func_t *func) {
+    wasmer_import_t import;
+    import.module_name.bytes = (const uint8_t *) "env";
+    import.module_name.bytes_len = 3;
+    import.import_name.bytes = (const uint8_t *) import_name;
+    import.import_name.bytes_len = strlen(import_name);
+    import.tag = WASM_FUNCTION;
+    import.value.func = func;
+
+    return import;
+}
+
+int main()
+{
+    wasmer_value_tag no_sig[] = {};
+    wasmer_value_tag get_returns_sig[] = {WASM_I32};
+    wasmer_import_func_t *inc_func = wasmer_import_func_new((void (*)(void *)) inc_counter, no_sig, 0, no_sig, 0);
+    wasmer_import_func_t *mul_func = wasmer_import_func_new((void (*)(void *)) mul_counter, no_sig, 0, no_sig, 0);
+    wasmer_import_func_t *get_func = wasmer_import_func_new((void (*)(void *)) get_counter, no_sig, 0, get_returns_sig, 1);
+    wasmer_import_t imports[] = {
+        create_import("inc", inc_func),
+        create_import("mul", mul_func),
+        create_import("get", get_func),
+    };
+
+    FILE *file = fopen("assets/inc.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    // Only the modules compiled with the `trace_import_calls` option
+    // report their calls.
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.trace_import_calls = true;
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_file_with_options(&module, "assets/inc.wasm", &options) == WASMER_OK);
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate(module, &instance, imports, 3) == WASMER_OK);
+    wasmer_instance_set_points_limit(instance, 1000);
+
+    import_calls_t import_calls = {0, 0};
+    assert(wasmer_instance_set_import_call_callback(instance, count_import_call, &import_calls) == WASMER_OK);
+
+    // `inc_and_get` calls the `inc` and `get` imports once each.
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 1);
+    assert(wasmer_instance_call(instance, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 2);
+    assert(import_calls.inc_calls == 2);
+    assert(import_calls.get_calls == 2);
+
+    // The calls through an exported function are reported too.
+    wasmer_exports_t *exports = NULL;
+    wasmer_instance_exports(instance, &exports);
+    const wasmer_export_func_t *inc_and_get = wasmer_export_to_func(wasmer_exports_get(exports, 0));
+    assert(wasmer_export_func_call(inc_and_get, params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 3);
+    assert(import_calls.inc_calls == 3);
+    assert(import_calls.get_calls == 3);
+    wasmer_exports_destroy(exports);
+
+    // Once the callback is removed, the calls are not reported anymore.
+    assert(wasmer_instance_set_import_call_callback(instance, NULL, NULL) == WASMER_OK);
+    assert(wasmer_instance_call(instance, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 4);
+    assert(import_calls.inc_calls == 3);
+    assert(import_calls.get_calls == 3);
+
+    // The module is not compiled with the `block_coverage` option, so
+    // its blocks are not reported.
+    uint32_t blocks = 0;
+    assert(wasmer_instance_set_block_coverage_callback(instance, count_block, &blocks) == WASMER_OK);
+    assert(wasmer_instance_call(instance, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(blocks == 0);
+    assert(wasmer_instance_set_block_coverage_callback(NULL, count_block, &blocks) == WASMER_ERROR);
+
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+
+    // Without the option, the calls are not reported.
+    assert(wasmer_compile_with_gas_metering(&module, bytes, len) == WASMER_OK);
+    assert(wasmer_module_instantiate(module, &instance, imports, 3) == WASMER_OK);
+    wasmer_instance_set_points_limit(instance, 1000);
+    assert(wasmer_instance_set_import_call_callback(instance, count_import_call, &import_calls) == WASMER_OK);
+    assert(wasmer_instance_call(instance, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(import_calls.inc_calls == 3);
+    assert(import_calls.get_calls == 3);
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+
+    // The start function run by `wasmer_instance_run_start()` reports
+    // its calls too.
+    assert(wasmer_compile_file_with_options(&module, "assets/start_import.wasm", &options) == WASMER_OK);
+    options.gas_limit = 1000;
+    options.skip_start = true;
+    assert(wasmer_module_instantiate_with_options(module, &instance, imports, 1, &options, NULL) == WASMER_OK);
+    assert(wasmer_instance_set_import_call_callback(instance, count_import_call, &import_calls) == WASMER_OK);
+    assert(wasmer_instance_run_start(instance, NULL) == WASMER_OK);
+    assert(import_calls.inc_calls == 4);
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+
+    wasmer_import_func_destroy(inc_func);
+    wasmer_import_func_destroy(mul_func);
+    wasmer_import_func_destroy(get_func);
+    free(bytes);
+
+    return 0;
+}
@@ -26,6 +26,38 @@ void print_str(wasmer_instance_context_t *ctx, int32_t ptr, int32_t len)
     const wasmer_memory_t *memory = wasmer_instance_context_memory(ctx, 0);
     uint32_t mem_len = wasmer_memory_length(memory);
     uint8_t *mem_bytes = wasmer_memory_data(memory);
+    assert(wasmer_instance_context_memory_base(ctx, 0) == mem_bytes);
+    assert(wasmer_instance_context_memory_length(ctx, 0) == wasmer_memory_data_length((wasmer_memory_t *) memory));
+    assert(wasmer_instance_context_memory_base(ctx, 1) == NULL);
+    assert(wasmer_instance_context_memory(ctx, 1) == NULL);
+    assert(wasmer_instance_context_trace_mark(ctx, 0xdeadbeef) == WASMER_OK);
+    uint32_t mem_bytes_len = wasmer_instance_context_memory_length(ctx, 0);
+    uint8_t vector[16];
+    assert(wasmer_instance_context_memory_read_v128(ctx, ptr, &vector) == WASMER_OK);
+    assert(memcmp(vector, mem_bytes + ptr, sizeof(vector)) == 0);
+    assert(wasmer_instance_context_memory_write_v128(ctx, mem_bytes_len - 16, &vector) == WASMER_OK);
+    assert(memcmp(mem_bytes + mem_bytes_len - 16, mem_bytes + ptr, sizeof(vector)) == 0);
+    assert(wasmer_instance_context_memory_read_v128(ctx, mem_bytes_len - 15, &vector) == WASMER_ERROR);
+    assert(wasmer_instance_context_memory_write_v128(ctx, mem_bytes_len - 15, &vector) == WASMER_ERROR);
+    assert(wasmer_instance_context_memory_fill(ctx, mem_bytes_len - 4, 0xab, 4) == WASMER_OK);
+    assert(mem_bytes[mem_bytes_len - 4] == 0xab && mem_bytes[mem_bytes_len - 1] == 0xab);
+    mem_bytes[mem_bytes_len - 4] = 1;
+    assert(wasmer_instance_context_memory_copy(ctx, mem_bytes_len - 3, mem_bytes_len - 4, 3) == WASMER_OK);
+    assert(mem_bytes[mem_bytes_len - 3] == 1 && mem_bytes[mem_bytes_len - 2] == 0xab && mem_bytes[mem_bytes_len - 1] == 0xab);
+    assert(wasmer_instance_context_memory_fill(ctx, mem_bytes_len - 1, 0, 2) == WASMER_ERROR);
+    assert(wasmer_instance_context_memory_copy(ctx, 0, mem_bytes_len - 1, 2) == WASMER_ERROR);
+    assert(wasmer_instance_context_memory_copy(ctx, mem_bytes_len - 1, 0, 2) == WASMER_ERROR);
+    char *str = NULL;
+    assert(wasmer_instance_context_read_string(ctx, ptr, len, &str) == WASMER_OK);
+    assert(strcmp(str, "Hello, World!") == 0);
+    wasmer_string_destroy(str);
+    str = NULL;
+    assert(wasmer_instance_context_read_string(ctx, mem_bytes_len - 1, 2, &str) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    // The last byte, 0xab, is a lone UTF-8 continuation byte.
+    assert(wasmer_instance_context_read_string(ctx, mem_bytes_len - 1, 1, &str) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_UTF8);
+    assert(str == NULL);
     for (int32_t idx = 0; idx < len; idx++)
     {
         actual_str[idx] = mem_bytes[ptr + idx];
@@ -85,6 +117,22 @@ int main()
     fread(bytes, 1, len, file);
     fclose(file);
 
+    printf("Instantiating with a mismatching signature\n");
+    wasmer_import_func_t *wrong_func = wasmer_import_func_new((void (*)(void *)) print_str_callable, params_sig, 1, returns_sig, 0);
+    wasmer_import_t wrong_import = import;
+    wrong_import.value.func = wrong_func;
+    wasmer_import_t wrong_imports[] = {wrong_import};
+    wasmer_instance_t *wrong_instance = NULL;
+    wasmer_result_t wrong_result = wasmer_instantiate(&wrong_instance, bytes, len, wrong_imports, 1);
+    assert(wrong_result == WASMER_ERROR);
+    int wrong_error_len = wasmer_last_error_length();
+    char *wrong_error_str = malloc(wrong_error_len);
+    wasmer_last_error_message(wrong_error_str, wrong_error_len);
+    printf("Error str: `%s`\n", wrong_error_str);
+    assert(strstr(wrong_error_str, "Incorrect import signature") != NULL);
+    free(wrong_error_str);
+    wasmer_import_func_destroy(wrong_func);
+
     printf("Instantiating\n");
     wasmer_instance_t *instance = NULL;
     wasmer_result_t compile_result = wasmer_instantiate(&instance, bytes, len, imports, 1);
@@ -117,6 +165,21 @@ int main()
     assert(context_data_value == actual_context_data_value);
     assert(local_context.call_count == 1);
 
+    // The instance does not collect its opcodes, so its trace only holds
+    // the marker of `print_str`.
+    wasmer_opcode_trace_entry_t trace[2];
+    uint32_t trace_length = 0;
+    assert(wasmer_instance_get_opcode_trace(instance, trace, 2, &trace_length) == WASMER_OK);
+    assert(trace_length == 1);
+    assert(trace[0].opcode == WASMER_OPCODE_TRACE_MARKER);
+    assert(trace[0].marker == 0xdeadbeef);
+
+    // Resetting the trace drops the marker.
+    assert(wasmer_instance_reset_opcode_trace(instance) == WASMER_OK);
+    assert(wasmer_instance_get_opcode_trace(instance, trace, 2, &trace_length) == WASMER_OK);
+    assert(trace_length == 0);
+    assert(wasmer_instance_reset_opcode_trace(NULL) == WASMER_ERROR);
+
     printf("Destroying trampoline buffer\n");
     wasmer_trampoline_buffer_destroy(tb);
     printf("Destroying func\n");
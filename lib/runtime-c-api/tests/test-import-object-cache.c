@@ -0,0 +1,98 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <pthread.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+
+uint8_t *bytes = NULL;
+long bytes_len = 0;
+wasmer_compilation_options_t options;
+wasmer_import_func_t *inc_func = NULL;
+int counter = 0;
+
+void inc_counter(wasmer_instance_context_t *ctx) {
+    counter += 1;
+}
+
+wasmer_import_t create_import(char* import_name, wasmer_import_func_t *func) {
+    wasmer_import_t import;
+    import.module_name.bytes = (const uint8_t *) "env";
+    import.module_name.bytes_len = 3;
+    import.import_name.bytes = (const uint8_t *) import_name;
+    import.import_name.bytes_len = strlen(import_name);
+    import.tag = WASM_FUNCTION;
+    import.value.func = func;
+
+    return import;
+}
+
+// The start function of the module calls its `env.inc` import.
+wasmer_result_t instantiate() {
+    wasmer_instance_t *instance = NULL;
+    wasmer_result_t result = wasmer_instantiate_with_options(&instance, bytes, bytes_len, &options);
+
+    if (result == WASMER_OK) {
+        wasmer_instance_destroy(instance);
+    }
+
+    return result;
+}
+
+void *validator(void *arg) {
+    // The imports cached by the main thread are not visible here.
+    assert(instantiate() == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_LINK);
+    assert(counter == 1);
+
+    wasmer_import_t imports[] = {create_import("inc", inc_func)};
+    assert(wasmer_import_object_cache_from_imports(imports, 1) == WASMER_OK);
+    assert(instantiate() == WASMER_OK);
+    assert(counter == 2);
+
+    return NULL;
+}
+
+int main()
+{
+    FILE *file = fopen("assets/start_import.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    bytes_len = ftell(file);
+    bytes = malloc(bytes_len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, bytes_len, file);
+    fclose(file);
+
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.gas_limit = 1000;
+
+    wasmer_value_tag no_sig[] = {};
+    inc_func = wasmer_import_func_new((void (*)(void *)) inc_counter, no_sig, 0, no_sig, 0);
+
+    // Without cached imports, the import is missing.
+    assert(instantiate() == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_LINK);
+    assert(counter == 0);
+
+    wasmer_import_t imports[] = {create_import("inc", inc_func)};
+    assert(wasmer_import_object_cache_from_imports(imports, 1) == WASMER_OK);
+    assert(instantiate() == WASMER_OK);
+    assert(counter == 1);
+
+    // The imports are cached per thread.
+    pthread_t validator_thread;
+    assert(pthread_create(&validator_thread, NULL, validator, NULL) == 0);
+    assert(pthread_join(validator_thread, NULL) == 0);
+
+    // Caching imports on another thread left the ones of this thread.
+    assert(instantiate() == WASMER_OK);
+    assert(counter == 3);
+
+    wasmer_import_func_destroy(inc_func);
+    free(bytes);
+
+    return 0;
+}
@@ -6,9 +6,16 @@
 
 static const char *trap_error_message = "Hello";
 
+enum { TRAP, TRAP_WITH_CODE, NO_TRAP };
+static int trap_mode = TRAP;
+
 void print_str(wasmer_instance_context_t *ctx, int32_t _ptr, int32_t _len)
 {
-    wasmer_trap(ctx, trap_error_message);
+    if (trap_mode == TRAP) {
+        wasmer_trap(ctx, trap_error_message);
+    } else if (trap_mode == TRAP_WITH_CODE) {
+        wasmer_trap_with_code(ctx, 42, trap_error_message);
+    }
 }
 
 int main()
@@ -71,6 +78,25 @@ int main()
 
     assert(0 == strcmp(error_str, "Call error: \"Hello\""));
 
+    // The trap was forced by the host, without an error code.
+    assert(wasmer_instance_last_trap_was_host_forced(instance) == 1);
+    assert(wasmer_instance_last_host_trap_code(instance) == 0);
+
+    // The error code given by the host is kept.
+    trap_mode = TRAP_WITH_CODE;
+    assert(wasmer_instance_call(instance, "hello_wasm", params, 0, results, 0) == WASMER_ERROR);
+    assert(wasmer_instance_last_trap_was_host_forced(instance) == 1);
+    assert(wasmer_instance_last_host_trap_code(instance) == 42);
+
+    // Each call forgets the trap of the previous one.
+    trap_mode = NO_TRAP;
+    assert(wasmer_instance_call(instance, "hello_wasm", params, 0, results, 0) == WASMER_OK);
+    assert(wasmer_instance_last_trap_was_host_forced(instance) == 0);
+    assert(wasmer_instance_last_host_trap_code(instance) == 0);
+
+    assert(wasmer_instance_last_trap_was_host_forced(NULL) == 0);
+    assert(wasmer_instance_last_host_trap_code(NULL) == 0);
+
     printf("Destroying func\n");
     wasmer_import_func_destroy(func);
     printf("Destroy instance\n");
@@ -0,0 +1,84 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+uint8_t *read_wasm(const char *path, long *len)
+{
+    FILE *file = fopen(path, "r");
+    fseek(file, 0, SEEK_END);
+    *len = ftell(file);
+    uint8_t *bytes = malloc(*len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, *len, file);
+    fclose(file);
+    return bytes;
+}
+
+int main()
+{
+    long len = 0;
+    uint8_t *bytes = read_wasm("assets/memory_div.wasm", &len);
+
+    // Every operator costs 1 point.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    uint8_t image[] = {42, 0, 0, 0};
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.runtime_breakpoints = true;
+    options.gas_limit = 1000;
+    options.initial_memory = image;
+    options.initial_memory_len = sizeof(image);
+    options.initial_memory_offset = 8;
+    options.initial_memory_gas_per_byte = 10;
+
+    // The image is written before the first call, and charged for each
+    // of its bytes.
+    wasmer_instance_t *instance = NULL;
+    uint64_t gas_used = 0;
+    assert(wasmer_instantiate_with_options_and_gas(&instance, bytes, len, &options, &gas_used) == WASMER_OK);
+    assert(gas_used == 40);
+    assert(wasmer_instance_get_points_used(instance) == 40);
+
+    wasmer_value_t params[] = {
+        {.tag = WASM_I32, .value.I32 = 8},
+        {.tag = WASM_I32, .value.I32 = 1},
+    };
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "load_div_u", params, 2, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 42);
+    wasmer_instance_destroy(instance);
+
+    // The image must be affordable.
+    options.gas_limit = 39;
+    instance = NULL;
+    assert(wasmer_instantiate_with_options_and_gas(&instance, bytes, len, &options, &gas_used) == WASMER_OUT_OF_GAS);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_OUT_OF_GAS);
+    assert(instance == NULL);
+
+    // It must fit in the memory.
+    options.gas_limit = 1000;
+    options.initial_memory_offset = 65536 - 2;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(instance == NULL);
+    free(bytes);
+
+    // And the module must have a memory.
+    bytes = read_wasm("assets/counter.wasm", &len);
+    options.initial_memory_offset = 0;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(instance == NULL);
+    free(bytes);
+
+    return 0;
+}
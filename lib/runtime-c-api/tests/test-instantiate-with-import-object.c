@@ -0,0 +1,96 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+int32_t counter = 0;
+
+void inc_counter(wasmer_instance_context_t *ctx) {
+    counter += 1;
+}
+
+void mul_counter(wasmer_instance_context_t *ctx) {
+    counter *= 2;
+}
+
+int32_t get_counter(wasmer_instance_context_t *ctx) {
+    return counter;
+}
+
+wasmer_import_t create_import(char* import_name, wasmer_import_func_t *func) {
+    wasmer_import_t import;
+    import.module_name.bytes = (const uint8_t *) "env";
+    import.module_name.bytes_len = 3;
+    import.import_name.bytes = (const uint8_t *) import_name;
+    import.import_name.bytes_len = strlen(import_name);
+    import.tag = WASM_FUNCTION;
+    import.value.func = func;
+
+    return import;
+}
+
+int main()
+{
+    wasmer_value_tag no_sig[] = {};
+    wasmer_value_tag get_returns_sig[] = {WASM_I32};
+    wasmer_import_func_t *inc_func = wasmer_import_func_new((void (*)(void *)) inc_counter, no_sig, 0, no_sig, 0);
+    wasmer_import_func_t *mul_func = wasmer_import_func_new((void (*)(void *)) mul_counter, no_sig, 0, no_sig, 0);
+    wasmer_import_func_t *get_func = wasmer_import_func_new((void (*)(void *)) get_counter, no_sig, 0, get_returns_sig, 1);
+    wasmer_import_t imports[] = {
+        create_import("inc", inc_func),
+        create_import("mul", mul_func),
+        create_import("get", get_func),
+    };
+
+    wasmer_import_object_t *import_object = wasmer_import_object_new();
+    assert(wasmer_import_object_extend(import_object, imports, 3) == WASMER_OK);
+
+    FILE *file = fopen("assets/inc.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.gas_limit = 1000;
+
+    // The same import object can be used by several instantiations.
+    wasmer_instance_t *first = NULL;
+    wasmer_instance_t *second = NULL;
+    assert(wasmer_instantiate_with_import_object(&first, bytes, len, import_object, &options) == WASMER_OK);
+    assert(wasmer_instantiate_with_import_object(&second, bytes, len, import_object, &options) == WASMER_OK);
+
+    // The instances keep their own reference to the imports, so they
+    // can still be used once the import object is destroyed.
+    wasmer_import_object_destroy(import_object);
+
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(first, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 1);
+    assert(wasmer_instance_call(second, "mul_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 2);
+    wasmer_instance_destroy(first);
+    assert(wasmer_instance_call(second, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 3);
+    wasmer_instance_destroy(second);
+
+    // A null import object is rejected.
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_import_object(&instance, bytes, len, NULL, &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    assert(instance == NULL);
+
+    wasmer_import_func_destroy(inc_func);
+    wasmer_import_func_destroy(mul_func);
+    wasmer_import_func_destroy(get_func);
+    free(bytes);
+
+    return 0;
+}
@@ -44,6 +44,7 @@ int main()
     wasmer_result_t call_result2 = wasmer_instance_call(instance, "sum", params, 1, results, 1);
     printf("Call result bad:  %d\n", call_result2);
     assert(call_result2 == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
 
     int error_len = wasmer_last_error_length();
     printf("Error len: `%d`\n", error_len);
@@ -53,6 +54,100 @@ int main()
     printf("Error str: `%s`\n", error_str);
     assert(0 == strcmp(error_str, "Call error: Parameters of type [I32] did not match signature [I32, I32] -> [I32]"));
     free(error_str);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NONE);
+
+    wasmer_result_t call_result3 = wasmer_instance_call(instance, "\xff\xfe", params, 2, results, 1);
+    printf("Call result invalid name:  %d\n", call_result3);
+    assert(call_result3 == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_UTF8);
+
+    error_len = wasmer_last_error_length();
+    error_str = malloc(error_len);
+    wasmer_last_error_message(error_str, error_len);
+    printf("Error str: `%s`\n", error_str);
+    assert(0 == strcmp(error_str, "name is not a valid UTF-8 string"));
+    free(error_str);
+
+    // Without room for results, the results are dropped.
+    wasmer_result_t call_result4 = wasmer_instance_call(instance, "sum", params, 2, results, 0);
+    printf("Call result without room for results:  %d\n", call_result4);
+    assert(call_result4 == WASMER_OK);
+    assert(wasmer_instance_call(instance, "sum", params, 2, NULL, 0) == WASMER_OK);
+
+    wasmer_result_t call_result5 = wasmer_instance_call(instance, "sum", params, 2, NULL, 1);
+    printf("Call result null results:  %d\n", call_result5);
+    assert(call_result5 == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    error_len = wasmer_last_error_length();
+    error_str = malloc(error_len);
+    wasmer_last_error_message(error_str, error_len);
+    printf("Error str: `%s`\n", error_str);
+    assert(0 == strcmp(error_str, "results ptr is null"));
+    free(error_str);
+
+    // `sum` is the function 0, called with packed parameters.
+    {
+        wasmer_value_tag param_types[] = {WASM_I32, WASM_I32};
+        uint8_t param_data[] = {7, 0, 0, 0, 8, 0, 0, 0};
+        wasmer_value_t packed_results[1];
+        assert(wasmer_instance_call_packed(instance, 0, param_types, 2, param_data, sizeof(param_data), packed_results, 1) == WASMER_OK);
+        assert(packed_results[0].value.I32 == 15);
+
+        assert(wasmer_instance_call_packed(instance, 0, param_types, 2, param_data, sizeof(param_data) - 1, packed_results, 1) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+        assert(wasmer_instance_call_packed(instance, 1, param_types, 2, param_data, sizeof(param_data), packed_results, 1) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    }
+
+    // `sum` returns an `i32`, written as 4 raw bytes.
+    {
+        uint8_t result_data[8] = {0};
+        assert(wasmer_instance_call_raw_results(instance, "sum", params, 2, result_data, sizeof(result_data)) == WASMER_OK);
+        int32_t sum;
+        memcpy(&sum, result_data, 4);
+        assert(sum == 15);
+        assert(result_data[4] == 0);
+
+        assert(wasmer_instance_call_raw_results(instance, "sum", params, 2, result_data, 3) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+        assert(wasmer_instance_call_raw_results(instance, "sum", params, 2, NULL, 4) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    }
+
+    // A module without imports can be instantiated without an imports array.
+    {
+        wasmer_instance_t *other_instance = NULL;
+        assert(wasmer_instantiate(&other_instance, bytes, len, NULL, 0) == WASMER_OK);
+        assert(wasmer_instance_call(other_instance, "sum", params, 2, results, 1) == WASMER_OK);
+        assert(results[0].value.I32 == 15);
+        wasmer_instance_destroy(other_instance);
+    }
+
+    // Invalid bytes fail to compile: the compile error is the cause of the last error.
+    {
+        uint8_t invalid_bytes[] = {0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0xff};
+        wasmer_instance_t *invalid_instance = NULL;
+        assert(wasmer_instantiate(&invalid_instance, invalid_bytes, sizeof(invalid_bytes), imports, 0) == WASMER_ERROR);
+
+        char cause[1024];
+        assert(wasmer_last_error_cause(0, NULL, sizeof(cause)) == -1);
+        assert(wasmer_last_error_cause(0, cause, 1) == -1);
+        int cause_result = wasmer_last_error_cause(0, cause, sizeof(cause));
+        printf("Cause: `%s`\n", cause);
+        assert(cause_result > 0);
+        assert(cause_result == (int) strlen(cause) + 1);
+        assert(wasmer_last_error_cause(1, cause, sizeof(cause)) == 0);
+
+        // Reading the causes leaves the last error untouched.
+        error_len = wasmer_last_error_length();
+        assert(error_len > 0);
+        error_str = malloc(error_len);
+        wasmer_last_error_message(error_str, error_len);
+        printf("Error str: `%s`\n", error_str);
+        free(error_str);
+        assert(wasmer_last_error_cause(0, cause, sizeof(cause)) == 0);
+    }
 
     printf("Destroy instance\n");
     wasmer_instance_destroy(instance);
@@ -0,0 +1,60 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+int main()
+{
+    // Every operator is free: only the instruction limit can stop a loop.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 0;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/loop.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.runtime_breakpoints = true;
+    options.gas_limit = 1000000;
+    options.hard_instruction_limit = 1000;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+
+    // The infinite loop stops at the instruction limit, not at the gas limit.
+    wasmer_result_t call_result = wasmer_instance_call(instance, "spin", params, 0, results, 0);
+    printf("Call result (spin): %d\n", call_result);
+    assert(call_result == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INSTRUCTION_LIMIT);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_INSTRUCTION_LIMIT);
+    printf("Instructions used: %llu\n", (unsigned long long) wasmer_instance_get_instructions_used(instance));
+    assert(wasmer_instance_get_instructions_used(instance) > 1000);
+    assert(wasmer_instance_get_points_used(instance) == 0);
+
+    // The count is reset by each call.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    call_result = wasmer_instance_call(instance, "answer", params, 0, results, 1);
+    assert(call_result == WASMER_OK);
+    assert(results[0].value.I32 == 42);
+    assert(wasmer_instance_get_instructions_used(instance) > 0);
+    assert(wasmer_instance_get_instructions_used(instance) < 1000);
+
+    wasmer_instance_destroy(instance);
+    free(bytes);
+    return 0;
+}
@@ -0,0 +1,99 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+
+wasmer_result_t call_i32(wasmer_instance_t *instance, const char *name, int32_t a, int32_t b, int32_t *result)
+{
+    wasmer_value_t params[2];
+    params[0].tag = WASM_I32;
+    params[0].value.I32 = a;
+    params[1].tag = WASM_I32;
+    params[1].value.I32 = b;
+    wasmer_value_t results[1];
+    wasmer_result_t call_result = wasmer_instance_call(instance, name, params, 2, results, 1);
+    *result = results[0].value.I32;
+
+    return call_result;
+}
+
+wasmer_result_t call_i64(wasmer_instance_t *instance, const char *name, int64_t a, int64_t b, int64_t *result)
+{
+    wasmer_value_t params[2];
+    params[0].tag = WASM_I64;
+    params[0].value.I64 = a;
+    params[1].tag = WASM_I64;
+    params[1].value.I64 = b;
+    wasmer_value_t results[1];
+    wasmer_result_t call_result = wasmer_instance_call(instance, name, params, 2, results, 1);
+    *result = results[0].value.I64;
+
+    return call_result;
+}
+
+void assert_illegal_arithmetic(wasmer_instance_t *instance, wasmer_result_t call_result)
+{
+    assert(call_result == WASMER_ERROR);
+
+    wasmer_trap_info_t trap_info;
+    assert(wasmer_instance_last_trap_info(instance, &trap_info) == WASMER_OK);
+    assert(trap_info.code == WASMER_TRAP_ILLEGAL_ARITHMETIC);
+}
+
+int main()
+{
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    FILE *file = fopen("assets/div.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_with_gas_metering(&module, bytes, len) == WASMER_OK);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate(module, &instance, imports, 0) == WASMER_OK);
+    wasmer_instance_set_points_limit(instance, 1000);
+
+    int32_t i32_result;
+    int64_t i64_result;
+
+    // Regular divisions.
+    assert(call_i32(instance, "i32_div_s", 7, -2, &i32_result) == WASMER_OK);
+    assert(i32_result == -3);
+    assert(call_i64(instance, "i64_div_s", 7, -2, &i64_result) == WASMER_OK);
+    assert(i64_result == -3);
+
+    // Signed division overflow.
+    assert_illegal_arithmetic(instance, call_i32(instance, "i32_div_s", INT32_MIN, -1, &i32_result));
+    assert_illegal_arithmetic(instance, call_i64(instance, "i64_div_s", INT64_MIN, -1, &i64_result));
+
+    // The remainder of the same operands is defined, and does not trap.
+    assert(call_i32(instance, "i32_rem_s", INT32_MIN, -1, &i32_result) == WASMER_OK);
+    assert(i32_result == 0);
+    assert(call_i64(instance, "i64_rem_s", INT64_MIN, -1, &i64_result) == WASMER_OK);
+    assert(i64_result == 0);
+
+    // Division by zero.
+    assert_illegal_arithmetic(instance, call_i32(instance, "i32_div_s", 1, 0, &i32_result));
+    assert_illegal_arithmetic(instance, call_i32(instance, "i32_div_u", 1, 0, &i32_result));
+    assert_illegal_arithmetic(instance, call_i32(instance, "i32_rem_s", 1, 0, &i32_result));
+    assert_illegal_arithmetic(instance, call_i64(instance, "i64_div_s", 1, 0, &i64_result));
+    assert_illegal_arithmetic(instance, call_i64(instance, "i64_div_u", 1, 0, &i64_result));
+    assert_illegal_arithmetic(instance, call_i64(instance, "i64_rem_s", 1, 0, &i64_result));
+
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+    free(bytes);
+
+    return 0;
+}
@@ -0,0 +1,79 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <pthread.h>
+#include <stdint.h>
+#include <unistd.h>
+
+void *watchdog(void *instance)
+{
+    // Give the call time to enter its infinite loop.
+    usleep(100 * 1000);
+    assert(wasmer_instance_request_interrupt((wasmer_instance_t *) instance) == WASMER_OK);
+
+    return NULL;
+}
+
+int main()
+{
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 0;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    FILE *file = fopen("assets/loop.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    // The loop costs nothing, so only the interrupt can stop it.
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_with_gas_metering(&module, bytes, len) == WASMER_OK);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate(module, &instance, imports, 0) == WASMER_OK);
+    wasmer_instance_set_points_limit(instance, 1000);
+
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+
+    pthread_t watchdog_thread;
+    assert(pthread_create(&watchdog_thread, NULL, watchdog, instance) == 0);
+    assert(wasmer_instance_call(instance, "spin", params, 0, results, 0) == WASMER_INTERRUPTED);
+    assert(pthread_join(watchdog_thread, NULL) == 0);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_INTERRUPTED);
+
+    // Once the breakpoint value is reset, the instance can be called again.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(wasmer_instance_call(instance, "answer", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 42);
+
+    // An interrupt requested while no call runs stops the next one.
+    assert(wasmer_instance_request_interrupt(instance) == WASMER_OK);
+    assert(wasmer_instance_call(instance, "spin", params, 0, results, 0) == WASMER_INTERRUPTED);
+
+    // The request is kept apart from the breakpoint value, so resetting
+    // the value, as `wasmer_instance_call_shared_gas()` does, keeps it.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(wasmer_instance_request_interrupt(instance) == WASMER_OK);
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(wasmer_instance_call_shared_gas(instance, "spin", params, 0, results, 0, NULL) == WASMER_INTERRUPTED);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_INTERRUPTED);
+
+    // The interrupted call consumed the request.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(wasmer_instance_call(instance, "answer", params, 0, results, 1) == WASMER_OK);
+
+    assert(wasmer_instance_request_interrupt(NULL) == WASMER_ERROR);
+
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+    free(bytes);
+
+    return 0;
+}
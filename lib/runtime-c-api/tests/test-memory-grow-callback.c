@@ -0,0 +1,86 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+
+typedef struct {
+    uint32_t calls;
+    uint32_t max_requested_pages;
+} grow_policy_t;
+
+int check_grow(void *user_data, uint32_t requested_pages)
+{
+    grow_policy_t *policy = (grow_policy_t *) user_data;
+    policy->calls += 1;
+
+    return requested_pages > policy->max_requested_pages;
+}
+
+int32_t call_grow(wasmer_instance_t *instance, int32_t delta)
+{
+    wasmer_value_t params[] = {{.tag = WASM_I32, .value.I32 = delta}};
+    wasmer_value_t results[1];
+
+    wasmer_result_t call_result = wasmer_instance_call(instance, "grow", params, 1, results, 1);
+    assert(call_result == WASMER_OK);
+
+    return results[0].value.I32;
+}
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/memory_grow.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    wasmer_result_t compile_result = wasmer_instantiate(&instance, bytes, len, imports, 0);
+    printf("Compile result:  %d\n", compile_result);
+    assert(compile_result == WASMER_OK);
+
+    grow_policy_t policy = {.calls = 0, .max_requested_pages = 2};
+    assert(wasmer_instance_set_memory_grow_callback(instance, check_grow, &policy) == WASMER_OK);
+    assert(wasmer_instance_set_memory_grow_callback(NULL, check_grow, &policy) == WASMER_ERROR);
+
+    uint32_t peak_pages = 0;
+    assert(wasmer_instance_get_peak_memory_pages(instance, &peak_pages) == WASMER_OK);
+    assert(peak_pages == 1);
+
+    // Allowed by the callback.
+    assert(call_grow(instance, 2) == 1);
+    assert(policy.calls == 1);
+    assert(wasmer_instance_get_peak_memory_pages(instance, &peak_pages) == WASMER_OK);
+    assert(peak_pages == 3);
+
+    // Denied by the callback.
+    assert(call_grow(instance, 3) == -1);
+    assert(policy.calls == 2);
+    assert(wasmer_instance_get_peak_memory_pages(instance, &peak_pages) == WASMER_OK);
+    assert(peak_pages == 3);
+
+    // Without callback, the growth is allowed again.
+    assert(wasmer_instance_set_memory_grow_callback(instance, NULL, NULL) == WASMER_OK);
+    assert(call_grow(instance, 3) == 3);
+    assert(policy.calls == 2);
+    assert(wasmer_instance_get_peak_memory_pages(instance, &peak_pages) == WASMER_OK);
+    assert(peak_pages == 6);
+
+    // A reset shrinks the memory back, and the peak with it.
+    assert(wasmer_instance_reset(instance) == WASMER_OK);
+    assert(wasmer_instance_get_peak_memory_pages(instance, &peak_pages) == WASMER_OK);
+    assert(peak_pages == 1);
+    assert(wasmer_instance_get_peak_memory_pages(instance, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    printf("Destroy instance\n");
+    wasmer_instance_destroy(instance);
+    free(bytes);
+
+    return 0;
+}
@@ -0,0 +1,115 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+#define PAGE_SIZE 65536
+
+int deny_grow(void *user_data, uint32_t requested_pages)
+{
+    (void) user_data;
+    (void) requested_pages;
+
+    return 1;
+}
+
+int32_t call_grow(wasmer_instance_t *instance, int32_t delta)
+{
+    wasmer_value_t params[] = {{.tag = WASM_I32, .value.I32 = delta}};
+    wasmer_value_t results[1];
+
+    wasmer_result_t call_result = wasmer_instance_call(instance, "grow", params, 1, results, 1);
+    assert(call_result == WASMER_OK);
+
+    return results[0].value.I32;
+}
+
+uint8_t *read_file(const char *path, long *len)
+{
+    FILE *file = fopen(path, "r");
+    fseek(file, 0, SEEK_END);
+    *len = ftell(file);
+    uint8_t *bytes = malloc(*len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, *len, file);
+    fclose(file);
+
+    return bytes;
+}
+
+int main()
+{
+    long len = 0;
+    uint8_t *bytes = read_file("assets/memory_grow.wasm", &len);
+
+    uint8_t *image = calloc(4 * PAGE_SIZE, 1);
+    image[PAGE_SIZE + 1] = 7;
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.max_memory_grow = 3;
+    options.max_memory_grow_delta = 2;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+
+    const uint8_t *snapshot = NULL;
+    uint32_t snapshot_length = 0;
+    assert(wasmer_instance_memory_export_buffer(instance, 0, &snapshot, &snapshot_length) == WASMER_OK);
+    assert(snapshot_length == PAGE_SIZE);
+
+    // Rolling back a memory which grew shrinks it back.
+    assert(call_grow(instance, 1) == 1);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, snapshot, snapshot_length) == WASMER_OK);
+    assert(call_grow(instance, 0) == 1);
+
+    // Importing a larger image grows the memory, as `memory.grow` does.
+    assert(wasmer_instance_memory_import_buffer(instance, 0, image, 2 * PAGE_SIZE) == WASMER_OK);
+    assert(wasmer_instance_memory_equals(instance, 0, image, 2 * PAGE_SIZE) == 1);
+
+    // The growth counts towards `max_memory_grow`: the memory is left unchanged.
+    assert(wasmer_instance_memory_import_buffer(instance, 0, snapshot, snapshot_length) == WASMER_OK);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, image, 2 * PAGE_SIZE) == WASMER_ERROR);
+    assert(wasmer_instance_memory_equals(instance, 0, snapshot, snapshot_length) == 1);
+    wasmer_instance_destroy(instance);
+
+    // The growth is subject to `max_memory_grow_delta`.
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, image, 4 * PAGE_SIZE) == WASMER_ERROR);
+    assert(wasmer_instance_memory_equals(instance, 0, snapshot, snapshot_length) == 1);
+    wasmer_instance_destroy(instance);
+
+    // The growth is subject to the memory grow callback.
+    wasmer_import_t imports[] = {};
+    assert(wasmer_instantiate(&instance, bytes, len, imports, 0) == WASMER_OK);
+    assert(wasmer_instance_set_memory_grow_callback(instance, deny_grow, NULL) == WASMER_OK);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, image, 2 * PAGE_SIZE) == WASMER_ERROR);
+    assert(wasmer_instance_memory_equals(instance, 0, snapshot, snapshot_length) == 1);
+    assert(wasmer_instance_set_memory_grow_callback(instance, NULL, NULL) == WASMER_OK);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, image, 2 * PAGE_SIZE) == WASMER_OK);
+    assert(wasmer_instance_memory_equals(instance, 0, image, 2 * PAGE_SIZE) == 1);
+    wasmer_instance_destroy(instance);
+    free(bytes);
+
+    // The growth is subject to the maximum size of the memory, 2 pages,
+    // and a memory which declares a maximum size cannot shrink.
+    bytes = read_file("assets/memory_grow_max.wasm", &len);
+    assert(wasmer_instantiate(&instance, bytes, len, imports, 0) == WASMER_OK);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, image, 3 * PAGE_SIZE) == WASMER_ERROR);
+    assert(wasmer_instance_memory_equals(instance, 0, snapshot, snapshot_length) == 1);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, image, 2 * PAGE_SIZE) == WASMER_OK);
+    assert(wasmer_instance_memory_equals(instance, 0, image, 2 * PAGE_SIZE) == 1);
+    assert(wasmer_instance_memory_import_buffer(instance, 0, snapshot, snapshot_length) == WASMER_ERROR);
+    assert(wasmer_instance_memory_equals(instance, 0, image, 2 * PAGE_SIZE) == 1);
+    assert(call_grow(instance, 0) == 2);
+
+    printf("Destroy instance\n");
+    wasmer_instance_destroy(instance);
+    wasmer_buffer_destroy((uint8_t *) snapshot, snapshot_length);
+    free(image);
+    free(bytes);
+
+    return 0;
+}
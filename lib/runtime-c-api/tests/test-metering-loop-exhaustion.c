@@ -0,0 +1,88 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+
+int64_t get_iterations(wasmer_instance_t *instance)
+{
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "iterations", params, 0, results, 1) == WASMER_OK);
+
+    return results[0].value.I64;
+}
+
+// Runs the endless counting loop with a fresh budget until it runs out
+// of gas, and returns the gas used.
+uint64_t count_until_out_of_gas(wasmer_instance_t *instance, uint64_t limit)
+{
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[] = {};
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, limit);
+    assert(wasmer_instance_call(instance, "count", params, 0, results, 0) == WASMER_ERROR);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+
+    uint64_t points_used = wasmer_instance_get_points_used(instance);
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, 1000);
+
+    return points_used;
+}
+
+int main()
+{
+    // Every operator costs 1 point.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    FILE *file = fopen("assets/loop.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_with_gas_metering(&module, bytes, len) == WASMER_OK);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate(module, &instance, imports, 0) == WASMER_OK);
+
+    // `count` charges 1 point for its `loop`, then each iteration
+    // charges 4 points up to its `global.set`, and 1 point for its `br`:
+    // the gas used is 5 * n after the `global.set` of the n-th
+    // iteration, and 5 * n + 1 after its `br`. With a limit of 13, the
+    // third iteration cannot afford its `global.set`.
+    assert(count_until_out_of_gas(instance, 13) == 15);
+    assert(get_iterations(instance) == 2);
+
+    // The failing block is left out of the gas used.
+    assert(wasmer_instance_set_exclude_failing_charge_on_oog(instance, true) == WASMER_OK);
+    assert(count_until_out_of_gas(instance, 13) == 11);
+    assert(get_iterations(instance) == 4);
+
+    // A limit of 16 stops the loop at the `br` of the third iteration
+    // instead, once its `global.set` ran.
+    assert(count_until_out_of_gas(instance, 16) == 15);
+    assert(get_iterations(instance) == 7);
+    assert(wasmer_instance_set_exclude_failing_charge_on_oog(instance, false) == WASMER_OK);
+    assert(count_until_out_of_gas(instance, 16) == 16);
+    assert(get_iterations(instance) == 10);
+
+    assert(wasmer_instance_set_exclude_failing_charge_on_oog(NULL, true) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+    free(bytes);
+
+    return 0;
+}
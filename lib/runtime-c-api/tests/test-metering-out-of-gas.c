@@ -0,0 +1,242 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+
+int64_t call_inc(wasmer_instance_t *instance, wasmer_result_t *call_result)
+{
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    *call_result = wasmer_instance_call(instance, "inc", params, 0, results, 1);
+
+    return results[0].value.I64;
+}
+
+int main()
+{
+    // Every operator costs 1 point.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    FILE *file = fopen("assets/counter.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_module_t *module = NULL;
+
+    // A free `loop` would let the module run forever within its gas
+    // limit: only the callers who opt in reject such costs.
+    opcode_costs[3] = 0;
+    wasmer_set_opcode_costs(opcode_costs);
+    assert(wasmer_compile_with_gas_metering(&module, bytes, len) == WASMER_OK);
+    wasmer_module_destroy(module);
+    module = NULL;
+    assert(wasmer_check_opcode_costs() == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    wasmer_compilation_options_t options = { .struct_size = sizeof(options) };
+    options.metering = true;
+    options.gas_limit = 100;
+    wasmer_instance_t *checked_instance = NULL;
+    assert(wasmer_instantiate_with_options(&checked_instance, bytes, len, &options) == WASMER_OK);
+    wasmer_instance_destroy(checked_instance);
+    options.require_loop_costs = true;
+    assert(wasmer_instantiate_with_options(&checked_instance, bytes, len, &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    opcode_costs[3] = 1;
+    wasmer_set_opcode_costs(opcode_costs);
+    assert(wasmer_check_opcode_costs() == WASMER_OK);
+    assert(wasmer_instantiate_with_options(&checked_instance, bytes, len, &options) == WASMER_OK);
+    wasmer_instance_destroy(checked_instance);
+
+    assert(wasmer_compile_with_gas_metering(&module, bytes, len) == WASMER_OK);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate(module, &instance, imports, 0) == WASMER_OK);
+
+    // `inc` costs 4 points up to its `global.set`, then 2 more points.
+    wasmer_result_t call_result;
+    wasmer_instance_set_points_limit(instance, 3);
+    call_inc(instance, &call_result);
+    assert(call_result == WASMER_ERROR);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+
+    // The block which could not afford to run left the global unchanged.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, 100);
+    assert(call_inc(instance, &call_result) == 1);
+    assert(call_result == WASMER_OK);
+    assert(wasmer_instance_get_points_used(instance) == 6);
+
+    // A sequence of calls sharing a budget of 9 points: the first call
+    // uses 6 of them, so the second one cannot afford its `global.set`.
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    uint64_t gas_used = 0;
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, 9);
+    assert(wasmer_instance_call_shared_gas(instance, "inc", params, 0, results, 1, &gas_used) == WASMER_OK);
+    assert(results[0].value.I64 == 2);
+    assert(gas_used == 6);
+    assert(wasmer_instance_call_shared_gas(instance, "inc", params, 0, results, 1, &gas_used) == WASMER_ERROR);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+    assert(wasmer_instance_get_points_used(instance) > 9);
+
+    // The limit is kept by the instance, whatever the calls used.
+    uint64_t points_limit = 0;
+    assert(wasmer_instance_get_points_limit(instance, &points_limit) == WASMER_OK);
+    assert(points_limit == 9);
+    assert(wasmer_instance_get_points_limit(instance, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    assert(wasmer_instance_get_points_limit(NULL, &points_limit) == WASMER_ERROR);
+
+    // Once the budget is exhausted, the function is not called anymore.
+    uint64_t points_used = wasmer_instance_get_points_used(instance);
+    assert(wasmer_instance_call_shared_gas(instance, "inc", params, 0, results, 1, &gas_used) == WASMER_ERROR);
+    assert(gas_used == 0);
+    assert(wasmer_instance_get_points_used(instance) == points_used);
+
+    // Leaving the failing block out keeps the gas used below the
+    // budget, but the sequence still ends with the call which ran out of
+    // gas.
+    assert(wasmer_instance_set_exclude_failing_charge_on_oog(instance, true) == WASMER_OK);
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    wasmer_instance_set_points_used(instance, 0);
+    assert(wasmer_instance_call_shared_gas(instance, "inc", params, 0, results, 1, &gas_used) == WASMER_OK);
+    assert(results[0].value.I64 == 3);
+    assert(wasmer_instance_call_shared_gas(instance, "inc", params, 0, results, 1, &gas_used) == WASMER_ERROR);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+    points_used = wasmer_instance_get_points_used(instance);
+    assert(points_used == 6);
+    assert(wasmer_instance_call_shared_gas(instance, "inc", params, 0, results, 1, &gas_used) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_OUT_OF_GAS);
+    assert(gas_used == 0);
+    assert(wasmer_instance_get_points_used(instance) == points_used);
+    assert(wasmer_instance_set_exclude_failing_charge_on_oog(instance, false) == WASMER_OK);
+
+    // None of the failed calls changed the global.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, 100);
+    assert(call_inc(instance, &call_result) == 4);
+    assert(call_result == WASMER_OK);
+
+    // Reaching the limit exactly traps: the check before the `global.set`
+    // fails with 4 points used out of 4, and the global is left unchanged.
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, 4);
+    call_inc(instance, &call_result);
+    assert(call_result == WASMER_ERROR);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+    assert(wasmer_instance_get_points_used(instance) == 4);
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+
+    // One more point lets the block run: the last 2 points are charged
+    // when the call returns, without any check.
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, 5);
+    assert(call_inc(instance, &call_result) == 5);
+    assert(call_result == WASMER_OK);
+    assert(wasmer_instance_get_points_used(instance) == 6);
+
+    // `wasmer_instance_call_with_gas` gives the call its own budget, and
+    // tells when it ran out of gas.
+    assert(wasmer_instance_call_with_gas(instance, "inc", params, 0, results, 1, 3, &gas_used) == WASMER_OUT_OF_GAS);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_OUT_OF_GAS);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+    assert(gas_used > 3);
+    assert(wasmer_instance_call_with_gas(instance, "inc", params, 0, results, 1, 100, &gas_used) == WASMER_OK);
+    assert(results[0].value.I64 == 6);
+    assert(gas_used == 6);
+    assert(wasmer_instance_get_points_used(instance) == 6);
+    assert(wasmer_instance_call_with_gas(instance, "inc", params, 0, results, 1, 100, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    // An estimate runs the call whatever its cost, and restores the limit.
+    bool trapped = true;
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, 3);
+    assert(wasmer_instance_call_estimate(instance, "inc", params, 0, results, 1, &gas_used, &trapped) == WASMER_OK);
+    assert(results[0].value.I64 == 7);
+    assert(gas_used == 6);
+    assert(!trapped);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(wasmer_instance_get_points_limit(instance, &points_limit) == WASMER_OK);
+    assert(points_limit == 3);
+    assert(wasmer_instance_call_estimate(instance, "inc", params, 0, results, 1, &gas_used, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    wasmer_instance_set_points_limit(instance, 100);
+
+    // The module has no start function: running it does nothing.
+    uint64_t start_gas_used = 1;
+    points_used = wasmer_instance_get_points_used(instance);
+    assert(wasmer_instance_run_start(instance, &start_gas_used) == WASMER_OK);
+    assert(start_gas_used == 0);
+    assert(wasmer_instance_get_points_used(instance) == points_used);
+    assert(wasmer_instance_run_start(NULL, &start_gas_used) == WASMER_ERROR);
+
+    // Without a hard instruction limit, the instructions are not counted.
+    assert(wasmer_instance_get_instructions_used(instance) == 0);
+    assert(wasmer_instance_get_instructions_used(NULL) == 0);
+
+    // Without the `gas_breakdown` option, the gas is not broken down.
+    wasmer_gas_breakdown_t breakdown = {1, 1, 1, 1};
+    assert(wasmer_instance_get_gas_breakdown(instance, &breakdown) == WASMER_OK);
+    assert(breakdown.arithmetic == 0);
+    assert(breakdown.memory == 0);
+    assert(breakdown.control == 0);
+    assert(breakdown.call == 0);
+    assert(wasmer_instance_get_gas_breakdown(instance, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    assert(wasmer_instance_get_gas_breakdown(NULL, &breakdown) == WASMER_ERROR);
+
+    // Without the `per_function_gas` option, no function records gas.
+    wasmer_function_gas_t functions[4];
+    assert(wasmer_instance_get_per_function_gas(instance, NULL, 0) == 0);
+    assert(wasmer_instance_get_per_function_gas(instance, functions, 4) == 0);
+    assert(wasmer_instance_get_per_function_gas(NULL, functions, 4) == -1);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    // Gas can only be refunded from within a host function.
+    assert(wasmer_instance_context_refund_gas(NULL, 10) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    // Without `charge_full_on_oog`, running out of gas charges the gas
+    // used until the exhaustion was detected, beyond the limit.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    wasmer_instance_set_points_used(instance, 0);
+    wasmer_instance_set_points_limit(instance, 3);
+    call_inc(instance, &call_result);
+    assert(call_result == WASMER_ERROR);
+    assert(wasmer_instance_get_points_used(instance) > 3);
+
+    // With it, the whole limit is charged.
+    options.charge_full_on_oog = true;
+    options.gas_limit = 3;
+    wasmer_instance_t *full_charge_instance = NULL;
+    assert(wasmer_instantiate_with_options(&full_charge_instance, bytes, len, &options) == WASMER_OK);
+    call_inc(full_charge_instance, &call_result);
+    assert(call_result == WASMER_ERROR);
+    assert(wasmer_instance_get_runtime_breakpoint_value(full_charge_instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+    assert(wasmer_instance_get_points_used(full_charge_instance) == 3);
+
+    assert(wasmer_instance_call_with_gas(full_charge_instance, "inc", params, 0, results, 1, 2, &gas_used) == WASMER_OUT_OF_GAS);
+    assert(gas_used == 2);
+    wasmer_instance_destroy(full_charge_instance);
+
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+    free(bytes);
+
+    return 0;
+}
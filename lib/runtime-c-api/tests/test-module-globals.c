@@ -0,0 +1,47 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+
+int main()
+{
+    FILE *file = fopen("assets/globals.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile(&module, bytes, len) == WASMER_OK);
+
+    wasmer_value_t value;
+    bool is_resolvable = true;
+
+    // The imported global, and the global initialized with it.
+    assert(wasmer_module_global_init_value(module, 0, &value, &is_resolvable) == WASMER_OK);
+    assert(!is_resolvable);
+    is_resolvable = true;
+    assert(wasmer_module_global_init_value(module, 1, &value, &is_resolvable) == WASMER_OK);
+    assert(!is_resolvable);
+
+    // The constant globals.
+    assert(wasmer_module_global_init_value(module, 2, &value, &is_resolvable) == WASMER_OK);
+    assert(is_resolvable);
+    assert(value.tag == WASM_I64);
+    assert(value.value.I64 == 42);
+    assert(wasmer_module_global_init_value(module, 3, &value, &is_resolvable) == WASMER_OK);
+    assert(is_resolvable);
+    assert(value.tag == WASM_F64);
+    assert(value.value.F64 == 1.5);
+
+    assert(wasmer_module_global_init_value(module, 4, &value, &is_resolvable) == WASMER_ERROR);
+    assert(wasmer_last_error_length() > 0);
+    assert(wasmer_module_global_init_value(module, 2, NULL, &is_resolvable) == WASMER_ERROR);
+
+    wasmer_module_destroy(module);
+    free(bytes);
+
+    return 0;
+}
@@ -137,6 +137,56 @@ int main()
     printf("Call result:  %d\n", call2_result);
     printf("Result: %d\n", results[0].value.I32);
 
+    // Register the same host functions one by one in a new import
+    // object, and share it between two instances
+    wasmer_import_object_t *shared_import_object = wasmer_import_object_new();
+    wasmer_import_t all_imports[] = {inc_import, mul_import, get_import};
+    for (int i = 0; i < 3; i++) {
+        wasmer_result_t register_res = wasmer_import_object_register(
+            shared_import_object,
+            all_imports[i].module_name,
+            all_imports[i].import_name,
+            all_imports[i].tag,
+            all_imports[i].value);
+        assert(register_res == WASMER_OK);
+    }
+
+    wasmer_instance_t *first_instance = NULL;
+    wasmer_instance_t *second_instance = NULL;
+    assert(wasmer_module_import_instantiate(&first_instance, module, shared_import_object) == WASMER_OK);
+    assert(wasmer_module_import_instantiate(&second_instance, module, shared_import_object) == WASMER_OK);
+
+    counter_data *first_counter = init_counter(1, 3);
+    counter_data *second_counter = init_counter(10, 2);
+    wasmer_instance_context_data_set(first_instance, first_counter);
+    wasmer_instance_context_data_set(second_instance, second_counter);
+
+    assert(wasmer_instance_call(first_instance, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 4);
+    assert(wasmer_instance_call(second_instance, "mul_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 20);
+
+    wasmer_instance_destroy(first_instance);
+    wasmer_instance_destroy(second_instance);
+    wasmer_import_object_destroy(shared_import_object);
+    free(first_counter);
+    free(second_counter);
+
+    // Instantiate from an imports array, without options
+    wasmer_instance_t *third_instance = NULL;
+    assert(wasmer_module_instantiate_with_options(module, &third_instance, all_imports, 3, NULL, NULL) == WASMER_OK);
+    counter_data *third_counter = init_counter(5, 1);
+    wasmer_instance_context_data_set(third_instance, third_counter);
+    assert(wasmer_instance_call(third_instance, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 6);
+    wasmer_instance_destroy(third_instance);
+    free(third_counter);
+
+    // A module with imports cannot be instantiated without them
+    wasmer_instance_t *missing_imports_instance = NULL;
+    assert(wasmer_module_instantiate_with_options(module, &missing_imports_instance, NULL, 0, NULL, NULL) == WASMER_ERROR);
+    assert(wasmer_module_instantiate_with_options(module, &missing_imports_instance, NULL, 3, NULL, NULL) == WASMER_ERROR);
+
     // Clear resources
     wasmer_import_func_destroy(inc_func);
     wasmer_import_func_destroy(mul_func);
@@ -2,6 +2,8 @@
 #include "../wasmer.h"
 #include <assert.h>
 #include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
 
 int main()
 {
@@ -79,6 +81,134 @@ int main()
     wasmer_result_t instantiate_result_two = wasmer_module_instantiate(module_three, &instance, imports, 0);
     assert(instantiate_result_two == WASMER_OK);
 
+    // A module serialized by another version of the runtime is rejected.
+    uint8_t *stale_bytes = malloc(serialized_module_bytes.bytes_len);
+    memcpy(stale_bytes, serialized_module_bytes.bytes, serialized_module_bytes.bytes_len);
+    stale_bytes[16] ^= 1;
+
+    wasmer_serialized_module_t *stale_serialized_module = NULL;
+    assert(wasmer_serialized_module_from_bytes(
+        &stale_serialized_module,
+        stale_bytes,
+        serialized_module_bytes.bytes_len
+    ) == WASMER_OK);
+
+    wasmer_module_t *stale_module = NULL;
+    wasmer_result_t stale_result = wasmer_module_deserialize(&stale_module, stale_serialized_module);
+    printf("Stale deserialize result: %d\n", stale_result);
+    assert(stale_result == WASMER_ERROR);
+    assert(stale_module == NULL);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    wasmer_serialized_module_destroy(stale_serialized_module);
+
+    // So is a module serialized in another format by the same version:
+    // the format version follows the 16 magic bytes and the 64 bytes of
+    // the version hash.
+    memcpy(stale_bytes, serialized_module_bytes.bytes, serialized_module_bytes.bytes_len);
+    stale_bytes[80] ^= 1;
+
+    assert(wasmer_serialized_module_from_bytes(
+        &stale_serialized_module,
+        stale_bytes,
+        serialized_module_bytes.bytes_len
+    ) == WASMER_OK);
+
+    stale_result = wasmer_module_deserialize(&stale_module, stale_serialized_module);
+    printf("Stale format deserialize result: %d\n", stale_result);
+    assert(stale_result == WASMER_ERROR);
+    assert(stale_module == NULL);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    wasmer_serialized_module_destroy(stale_serialized_module);
+    free(stale_bytes);
+
+    // Compiling the same bytes twice gives the same serialized module,
+    // whatever the order of its custom sections in memory.
+    {
+        FILE *custom_sections_file = fopen("assets/custom_sections.wasm", "r");
+        fseek(custom_sections_file, 0, SEEK_END);
+        long custom_sections_len = ftell(custom_sections_file);
+        uint8_t *custom_sections_bytes = malloc(custom_sections_len);
+        fseek(custom_sections_file, 0, SEEK_SET);
+        fread(custom_sections_bytes, 1, custom_sections_len, custom_sections_file);
+        fclose(custom_sections_file);
+
+        wasmer_module_t *first_module = NULL;
+        wasmer_module_t *second_module = NULL;
+        assert(wasmer_compile(&first_module, custom_sections_bytes, custom_sections_len) == WASMER_OK);
+        assert(wasmer_compile(&second_module, custom_sections_bytes, custom_sections_len) == WASMER_OK);
+
+        wasmer_serialized_module_t *first_serialized = NULL;
+        wasmer_serialized_module_t *second_serialized = NULL;
+        assert(wasmer_module_serialize(&first_serialized, first_module) == WASMER_OK);
+        assert(wasmer_module_serialize(&second_serialized, second_module) == WASMER_OK);
+
+        wasmer_byte_array first_bytes = wasmer_serialized_module_bytes(first_serialized);
+        wasmer_byte_array second_bytes = wasmer_serialized_module_bytes(second_serialized);
+        assert(first_bytes.bytes_len == second_bytes.bytes_len);
+        assert(0 == memcmp(first_bytes.bytes, second_bytes.bytes, first_bytes.bytes_len));
+
+        wasmer_serialized_module_destroy(first_serialized);
+        wasmer_serialized_module_destroy(second_serialized);
+        wasmer_module_destroy(first_module);
+        wasmer_module_destroy(second_module);
+        free(custom_sections_bytes);
+    }
+
+    // So does a module whose exception table records its memory
+    // accesses and divisions.
+    {
+        wasmer_compilation_options_t options;
+        memset(&options, 0, sizeof(options));
+        options.struct_size = sizeof(options);
+        options.deterministic_traps = true;
+
+        wasmer_module_t *first_module = NULL;
+        wasmer_module_t *second_module = NULL;
+        assert(wasmer_compile_file_with_options(&first_module, "assets/memory_div.wasm", &options) == WASMER_OK);
+        assert(wasmer_compile_file_with_options(&second_module, "assets/memory_div.wasm", &options) == WASMER_OK);
+
+        wasmer_serialized_module_t *first_serialized = NULL;
+        wasmer_serialized_module_t *second_serialized = NULL;
+        assert(wasmer_module_serialize(&first_serialized, first_module) == WASMER_OK);
+        assert(wasmer_module_serialize(&second_serialized, second_module) == WASMER_OK);
+
+        wasmer_byte_array first_bytes = wasmer_serialized_module_bytes(first_serialized);
+        wasmer_byte_array second_bytes = wasmer_serialized_module_bytes(second_serialized);
+        assert(first_bytes.bytes_len == second_bytes.bytes_len);
+        assert(0 == memcmp(first_bytes.bytes, second_bytes.bytes, first_bytes.bytes_len));
+
+        // The deserialized module still reports the kind of its traps.
+        wasmer_module_t *deserialized_module = NULL;
+        assert(wasmer_module_deserialize(&deserialized_module, first_serialized) == WASMER_OK);
+        wasmer_import_t imports[] = {};
+        wasmer_instance_t *instance = NULL;
+        assert(wasmer_module_instantiate(deserialized_module, &instance, imports, 0) == WASMER_OK);
+
+        wasmer_value_t params[] = {
+            {.tag = WASM_I32, .value.I32 = 0},
+            {.tag = WASM_I32, .value.I32 = 0},
+        };
+        wasmer_value_t result;
+        wasmer_value_t results[] = {result};
+        assert(wasmer_instance_call(instance, "load_div_u", params, 2, results, 1) == WASMER_ERROR);
+
+        wasmer_trap_info_t trap_info;
+        assert(wasmer_instance_last_trap_info(instance, &trap_info) == WASMER_OK);
+        assert(trap_info.code == WASMER_TRAP_ILLEGAL_ARITHMETIC);
+
+        wasmer_instance_destroy(instance);
+        wasmer_module_destroy(deserialized_module);
+        wasmer_serialized_module_destroy(first_serialized);
+        wasmer_serialized_module_destroy(second_serialized);
+        wasmer_module_destroy(first_module);
+        wasmer_module_destroy(second_module);
+    }
+
+    const char *version = wasmer_version();
+    printf("Version: %s\n", version);
+    assert(version != NULL && strlen(version) > 0);
+    assert(wasmer_backend_version(WASMER_BACKEND_SINGLEPASS) != NULL);
+
     printf("Destroy the serialized module\n");
     wasmer_serialized_module_destroy(serialized_module);
     wasmer_serialized_module_destroy(serialized_module_two);
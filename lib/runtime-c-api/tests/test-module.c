@@ -2,6 +2,7 @@
 #include "../wasmer.h"
 #include <assert.h>
 #include <stdint.h>
+#include <string.h>
 
 int main()
 {
@@ -14,11 +15,107 @@ int main()
     fread(bytes, 1, len, file);
     fclose(file);
 
+    wasmer_compiler_backend_t backends[3];
+    uint32_t backends_length = 0;
+    assert(wasmer_available_backends(backends, 3, &backends_length) == WASMER_OK);
+    assert(backends_length >= 1);
+    assert(wasmer_available_backends(backends, 0, &backends_length) == WASMER_ERROR);
+
     wasmer_module_t *module = NULL;
     wasmer_result_t compile_result = wasmer_compile(&module, bytes, len);
     printf("Compile result:  %d\n", compile_result);
     assert(compile_result == WASMER_OK);
 
+    assert(wasmer_module_has_start(module) == false);
+    assert(wasmer_module_has_start(NULL) == false);
+
+    // The generated code is measured, not the wasm bytes; the gas
+    // metering adds its own code.
+    size_t code_size = wasmer_module_code_size(module);
+    printf("Code size: %zu\n", code_size);
+    assert(code_size > 0);
+    assert(wasmer_module_code_size(NULL) == 0);
+    {
+        wasmer_module_t *metered_module = NULL;
+        assert(wasmer_compile_with_gas_metering(&metered_module, bytes, len) == WASMER_OK);
+        assert(wasmer_module_code_size(metered_module) > code_size);
+        wasmer_module_destroy(metered_module);
+    }
+
+    uint32_t min_pages = 1, max_pages = 1;
+    bool has_max = true;
+    assert(wasmer_module_memory_limits(module, &min_pages, &max_pages, &has_max) == WASMER_OK);
+    assert(min_pages == 0 && max_pages == 0 && has_max == false);
+    assert(wasmer_module_memory_limits(NULL, &min_pages, &max_pages, &has_max) == WASMER_ERROR);
+
+    {
+        uint8_t hash[WASMER_MODULE_CONTENT_HASH_LENGTH];
+        uint8_t other_hash[WASMER_MODULE_CONTENT_HASH_LENGTH];
+        wasmer_module_t *other_module = NULL;
+        assert(wasmer_compile(&other_module, bytes, len) == WASMER_OK);
+        assert(wasmer_module_content_hash(module, hash, sizeof(hash)) == WASMER_OK);
+        assert(wasmer_module_content_hash(other_module, other_hash, sizeof(other_hash)) == WASMER_OK);
+        assert(memcmp(hash, other_hash, sizeof(hash)) == 0);
+        assert(wasmer_module_content_hash(module, hash, sizeof(hash) - 1) == WASMER_ERROR);
+        wasmer_module_destroy(other_module);
+
+        // The opcode costs of the metered modules are hashed too.
+        uint32_t opcode_costs[OPCODE_COUNT];
+        for (int i = 0; i < OPCODE_COUNT; i++) {
+            opcode_costs[i] = 1;
+        }
+        wasmer_set_opcode_costs(opcode_costs);
+        uint8_t metered_hash[WASMER_MODULE_CONTENT_HASH_LENGTH];
+        assert(wasmer_compile_with_gas_metering(&other_module, bytes, len) == WASMER_OK);
+        assert(wasmer_module_content_hash(other_module, metered_hash, sizeof(metered_hash)) == WASMER_OK);
+        assert(memcmp(hash, metered_hash, sizeof(hash)) != 0);
+        wasmer_module_destroy(other_module);
+
+        opcode_costs[0] = 2;
+        wasmer_set_opcode_costs(opcode_costs);
+        assert(wasmer_compile_with_gas_metering(&other_module, bytes, len) == WASMER_OK);
+        assert(wasmer_module_content_hash(other_module, other_hash, sizeof(other_hash)) == WASMER_OK);
+        assert(memcmp(metered_hash, other_hash, sizeof(hash)) != 0);
+        wasmer_module_destroy(other_module);
+
+        // So are the compilation options.
+        wasmer_compilation_options_t options;
+        memset(&options, 0, sizeof(options));
+        options.struct_size = sizeof(options);
+        assert(wasmer_compile_file_with_options(&other_module, "assets/sum.wasm", &options) == WASMER_OK);
+        assert(wasmer_module_content_hash(other_module, metered_hash, sizeof(metered_hash)) == WASMER_OK);
+        wasmer_module_destroy(other_module);
+
+        options.deterministic_traps = true;
+        assert(wasmer_compile_file_with_options(&other_module, "assets/sum.wasm", &options) == WASMER_OK);
+        assert(wasmer_module_content_hash(other_module, other_hash, sizeof(other_hash)) == WASMER_OK);
+        assert(memcmp(metered_hash, other_hash, sizeof(hash)) != 0);
+        wasmer_module_destroy(other_module);
+
+        for (int i = 0; i < OPCODE_COUNT; i++) {
+            opcode_costs[i] = 0;
+        }
+        wasmer_set_opcode_costs(opcode_costs);
+    }
+
+    uint32_t opcode_count = 0;
+    assert(wasmer_module_function_opcode_count(module, 0, &opcode_count) == WASMER_OK);
+    assert(opcode_count == 4);
+    assert(wasmer_module_function_opcode_count(module, 1, &opcode_count) == WASMER_ERROR);
+
+    uint32_t name_length = 0;
+    uint8_t name[8];
+    assert(wasmer_module_function_name(module, 0, NULL, 0, &name_length) == WASMER_OK);
+    assert(name_length == 3);
+    assert(wasmer_module_function_name(module, 0, name, sizeof(name), &name_length) == WASMER_OK);
+    assert(name_length == 3 && memcmp(name, "sum", 3) == 0);
+    assert(wasmer_module_function_name(module, 0, name, 2, &name_length) == WASMER_OK);
+    assert(name_length == 2);
+    assert(wasmer_module_function_name(module, 1, name, sizeof(name), &name_length) == WASMER_ERROR);
+
+    assert(strcmp(wasmer_opcode_name(0), "unreachable") == 0);
+    assert(wasmer_opcode_name(UINT16_MAX) == NULL);
+
     wasmer_import_t imports[] = {};
     wasmer_instance_t *instance = NULL;
     wasmer_result_t instantiate_result = wasmer_module_instantiate(module, &instance, imports, 0);
@@ -42,10 +139,105 @@ int main()
     assert(results[0].value.I32 == 15);
     assert(call_result == WASMER_OK);
 
+    uint32_t results_count = 0;
+    assert(wasmer_instance_call_with_results_count(instance, "sum", params, 2, results, 0, &results_count) == WASMER_OK);
+    assert(results_count == 1);
+    results_count = 1;
+    assert(wasmer_instance_call_with_results_count(instance, "missing", params, 2, results, 1, &results_count) == WASMER_ERROR);
+    assert(results_count == 0);
+
+    assert(wasmer_instance_call_void(instance, "sum", params, 2) == WASMER_OK);
+    assert(wasmer_instance_call_void(instance, "missing", params, 2) == WASMER_ERROR);
+
+    // The instance was created without opcode tracing.
+    uint64_t last_opcode_location = 1;
+    assert(wasmer_instance_get_last_opcode_location(instance, &last_opcode_location) == WASMER_OK);
+    assert(last_opcode_location == 0);
+    assert(wasmer_instance_get_last_opcode_location(instance, NULL) == WASMER_ERROR);
+
     printf("Destroy instance\n");
     wasmer_instance_destroy(instance);
 
     printf("Destroy module\n");
     wasmer_module_destroy(module);
+
+    {
+        const uint8_t *stripped_bytes = NULL;
+        uint32_t stripped_length = 0;
+        wasmer_result_t strip_result = wasmer_strip_custom_sections(bytes, len, &stripped_bytes, &stripped_length);
+        assert(strip_result == WASMER_OK);
+        assert(stripped_length < len);
+        assert(wasmer_validate(stripped_bytes, stripped_length));
+        wasmer_buffer_destroy((uint8_t *) stripped_bytes, stripped_length);
+
+        uint8_t truncated_bytes[] = {0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00, 0xff};
+        strip_result = wasmer_strip_custom_sections(truncated_bytes, sizeof(truncated_bytes), &stripped_bytes, &stripped_length);
+        assert(strip_result == WASMER_ERROR);
+    }
+
+    {
+        wasmer_module_t *metered_module = NULL;
+        wasmer_compile_stats_t stats;
+        stats.instantiate_nanos = 1;
+        assert(wasmer_compile_with_gas_metering_and_stats(&metered_module, bytes, len, &stats) == WASMER_OK);
+        assert(stats.function_count == 1);
+        assert(stats.code_size == wasmer_module_code_size(metered_module));
+        assert(stats.instantiate_nanos == 0);
+        wasmer_module_destroy(metered_module);
+
+        assert(wasmer_compile_with_gas_metering_and_stats(&metered_module, bytes, len, NULL) == WASMER_OK);
+        wasmer_module_destroy(metered_module);
+    }
+
+    {
+        wasmer_module_t *file_module = NULL;
+        assert(wasmer_compile_file_with_options(&file_module, "assets/sum.wasm", NULL) == WASMER_OK);
+        assert(wasmer_module_code_size(file_module) > 0);
+        wasmer_module_destroy(file_module);
+
+        assert(wasmer_compile_file_with_options(&file_module, "assets/missing.wasm", NULL) == WASMER_ERROR);
+        assert(wasmer_last_error_length() > 0);
+        assert(wasmer_compile_file_with_options(&file_module, NULL, NULL) == WASMER_ERROR);
+    }
+
+    {
+        // A module which compiles has no violations.
+        wasmer_module_t *options_module = NULL;
+        assert(wasmer_compile_file_with_options(&options_module, "assets/sum.wasm", NULL) == WASMER_OK);
+        assert(wasmer_last_compile_violations_len() == 0);
+        wasmer_compile_violation_t violation;
+        assert(wasmer_last_compile_violations_get(0, &violation) == WASMER_ERROR);
+        wasmer_module_destroy(options_module);
+    }
+
+    {
+        // Every denied opcode is reported, in the order of the module.
+        uint16_t denied_opcodes[] = {0 /* unreachable */, 94 /* i32.div_u */};
+        wasmer_compilation_options_t options = { .struct_size = sizeof(options) };
+        options.denied_opcodes = denied_opcodes;
+        options.denied_opcodes_len = 2;
+        wasmer_module_t *options_module = NULL;
+        assert(wasmer_compile_file_with_options(&options_module, "assets/memory_div.wasm", &options) == WASMER_ERROR);
+        assert(wasmer_last_compile_violations_len() == 2);
+        wasmer_compile_violation_t violation;
+        assert(wasmer_last_compile_violations_get(0, &violation) == WASMER_OK);
+        assert(violation.kind == WASMER_COMPILE_VIOLATION_DENIED_OPCODE);
+        assert(violation.opcode == 94);
+        assert(violation.func_index == 0);
+        uint32_t first_offset = violation.offset;
+        assert(wasmer_last_compile_violations_get(1, &violation) == WASMER_OK);
+        assert(violation.kind == WASMER_COMPILE_VIOLATION_DENIED_OPCODE);
+        assert(violation.opcode == 0);
+        assert(violation.func_index == 3);
+        assert(violation.offset > first_offset);
+        assert(wasmer_last_compile_violations_get(2, &violation) == WASMER_ERROR);
+
+        // A compilation which fails before compiling forgets them too.
+        denied_opcodes[1] = 0xffff;
+        assert(wasmer_compile_file_with_options(&options_module, "assets/memory_div.wasm", &options) == WASMER_ERROR);
+        assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+        assert(wasmer_last_compile_violations_len() == 0);
+    }
+
     return 0;
 }
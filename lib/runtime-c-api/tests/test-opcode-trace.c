@@ -0,0 +1,98 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+bool trace_contains(wasmer_instance_t *instance, uint16_t opcode)
+{
+    wasmer_opcode_trace_entry_t trace[64];
+    uint32_t trace_length = 0;
+    assert(wasmer_instance_get_opcode_trace(instance, trace, 64, &trace_length) == WASMER_OK);
+    for (uint32_t i = 0; i < trace_length; i++) {
+        if (trace[i].opcode == opcode) {
+            return true;
+        }
+    }
+    return false;
+}
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/memory_div.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    // Only `load_rem_s`, the function 1, is traced.
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.collect_opcode_trace = true;
+    options.trace_function_start = 1;
+    options.trace_function_end = 2;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+
+    wasmer_value_t div_params[] = {
+        {.tag = WASM_I32, .value.I32 = 0},
+        {.tag = WASM_I32, .value.I32 = 1},
+    };
+    wasmer_value_t rem_params[] = {
+        {.tag = WASM_I32, .value.I32 = 0},
+        {.tag = WASM_I64, .value.I64 = 1},
+    };
+    wasmer_value_t results[1];
+
+    uint32_t trace_length = 1;
+    assert(wasmer_instance_call(instance, "load_div_u", div_params, 2, results, 1) == WASMER_OK);
+    assert(wasmer_instance_get_opcode_trace(instance, NULL, 0, &trace_length) == WASMER_OK);
+    printf("Trace length (load_div_u): %u\n", trace_length);
+    assert(trace_length == 0);
+
+    assert(wasmer_instance_call(instance, "load_rem_s", rem_params, 2, results, 1) == WASMER_OK);
+    assert(wasmer_instance_get_opcode_trace(instance, NULL, 0, &trace_length) == WASMER_OK);
+    printf("Trace length (load_rem_s): %u\n", trace_length);
+    assert(trace_length > 0);
+    assert(trace_contains(instance, 113 /* i64.rem_s */));
+    wasmer_instance_destroy(instance);
+
+    // Without an end, every function from the start on is traced.
+    options.trace_function_start = 0;
+    options.trace_function_end = 0;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    assert(wasmer_instance_call(instance, "load_div_u", div_params, 2, results, 1) == WASMER_OK);
+    assert(trace_contains(instance, 94 /* i32.div_u */));
+
+    // Each instance keeps its own trace: a call on another instance
+    // leaves the trace of the first one untouched.
+    wasmer_instance_t *other_instance = NULL;
+    assert(wasmer_instantiate_with_options(&other_instance, bytes, len, &options) == WASMER_OK);
+    uint32_t other_trace_length = 0;
+    assert(wasmer_instance_get_opcode_trace(other_instance, NULL, 0, &other_trace_length) == WASMER_OK);
+    assert(other_trace_length == 0);
+
+    assert(wasmer_instance_get_opcode_trace(instance, NULL, 0, &trace_length) == WASMER_OK);
+    assert(wasmer_instance_call(other_instance, "load_rem_s", rem_params, 2, results, 1) == WASMER_OK);
+    assert(wasmer_instance_get_opcode_trace(other_instance, NULL, 0, &other_trace_length) == WASMER_OK);
+    assert(other_trace_length > 0);
+    assert(trace_contains(other_instance, 113 /* i64.rem_s */));
+
+    uint32_t unchanged_trace_length = 0;
+    assert(wasmer_instance_get_opcode_trace(instance, NULL, 0, &unchanged_trace_length) == WASMER_OK);
+    assert(unchanged_trace_length == trace_length);
+    assert(trace_contains(instance, 94 /* i32.div_u */));
+    assert(!trace_contains(instance, 113 /* i64.rem_s */));
+    wasmer_instance_destroy(other_instance);
+
+    assert(WASMER_OPCODE_TRACE_MAX_ENTRIES > 0);
+    wasmer_instance_destroy(instance);
+
+    free(bytes);
+    return 0;
+}
@@ -0,0 +1,80 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+wasmer_instance_t *instantiate(const char *path)
+{
+    FILE *file = fopen(path, "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.runtime_breakpoints = true;
+    options.gas_limit = 1000;
+    options.per_function_gas = true;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    free(bytes);
+    return instance;
+}
+
+int main()
+{
+    // Every operator costs 1 point.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    wasmer_instance_t *instance = instantiate("assets/counter.wasm");
+    wasmer_instance_t *other_instance = instantiate("assets/counter.wasm");
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    wasmer_function_gas_t functions[4];
+
+    // `inc`, the function 0, uses 6 points.
+    assert(wasmer_instance_call(instance, "inc", params, 0, results, 1) == WASMER_OK);
+    assert(wasmer_instance_get_per_function_gas(instance, NULL, 0) == 1);
+    assert(wasmer_instance_get_per_function_gas(instance, functions, 4) == 1);
+    printf("Function %u: %llu\n", functions[0].func_index, (unsigned long long) functions[0].gas);
+    assert(functions[0].func_index == 0);
+    assert(functions[0].gas == 6);
+
+    // The gas is recorded per instance: calling another instance
+    // leaves it unchanged.
+    assert(wasmer_instance_get_per_function_gas(other_instance, functions, 4) == 0);
+    assert(wasmer_instance_call(other_instance, "inc", params, 0, results, 1) == WASMER_OK);
+    assert(wasmer_instance_call(other_instance, "inc", params, 0, results, 1) == WASMER_OK);
+    assert(wasmer_instance_get_per_function_gas(other_instance, functions, 4) == 1);
+    assert(functions[0].gas == 6);
+    assert(wasmer_instance_get_per_function_gas(instance, functions, 4) == 1);
+    assert(functions[0].gas == 6);
+
+    // The recursive calls of a function add up.
+    wasmer_instance_t *recursion_instance = instantiate("assets/recursion.wasm");
+    wasmer_value_t recurse_params[] = {{.tag = WASM_I32, .value.I32 = 0}};
+    assert(wasmer_instance_call(recursion_instance, "recurse", recurse_params, 1, results, 1) == WASMER_OK);
+    assert(wasmer_instance_get_per_function_gas(recursion_instance, functions, 4) == 1);
+    uint64_t base_gas = functions[0].gas;
+    recurse_params[0].value.I32 = 2;
+    assert(wasmer_instance_call(recursion_instance, "recurse", recurse_params, 1, results, 1) == WASMER_OK);
+    assert(wasmer_instance_get_per_function_gas(recursion_instance, functions, 4) == 1);
+    assert(functions[0].gas > 2 * base_gas);
+
+    wasmer_instance_destroy(recursion_instance);
+    wasmer_instance_destroy(other_instance);
+    wasmer_instance_destroy(instance);
+
+    return 0;
+}
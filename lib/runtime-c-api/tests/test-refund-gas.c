@@ -0,0 +1,184 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+bool metered = true;
+uint64_t inc_cost = 100;
+wasmer_result_t inc_result = WASMER_OK;
+wasmer_error_code_t inc_error = WASMER_ERROR_CODE_UNKNOWN;
+int get_calls = 0;
+uint64_t refund_amount = 0;
+uint64_t get_gas_left = 0;
+uint64_t mul_gas_left = 0;
+wasmer_result_t mul_gas_left_result = WASMER_OK;
+wasmer_error_code_t mul_gas_left_error = WASMER_ERROR_CODE_UNKNOWN;
+
+// Charges `inc_cost` gas, e.g. for a storage write.
+void inc(wasmer_instance_context_t *ctx) {
+    inc_result = wasmer_instance_context_use_gas(ctx, inc_cost);
+    if (inc_result != WASMER_OK) {
+        inc_error = wasmer_last_error_code();
+    }
+}
+
+// Reads the gas left, which fails when the instance is not metered.
+void mul(wasmer_instance_context_t *ctx) {
+    mul_gas_left_result = wasmer_instance_context_gas_left(ctx, &mul_gas_left);
+    mul_gas_left_error = wasmer_last_error_code();
+}
+
+// Reads the gas left, then refunds `refund_amount` gas, e.g. for a
+// storage cleared.
+int32_t get(wasmer_instance_context_t *ctx) {
+    if (!metered) {
+        return 0;
+    }
+    get_calls++;
+    assert(wasmer_instance_context_gas_left(ctx, &get_gas_left) == WASMER_OK);
+    assert(wasmer_instance_context_gas_left(ctx, NULL) == WASMER_ERROR);
+    assert(wasmer_instance_context_refund_gas(ctx, refund_amount) == WASMER_OK);
+    return 0;
+}
+
+wasmer_import_t create_import(char* import_name, wasmer_import_func_t *func) {
+    wasmer_import_t import;
+    import.module_name.bytes = (const uint8_t *) "env";
+    import.module_name.bytes_len = 3;
+    import.import_name.bytes = (const uint8_t *) import_name;
+    import.import_name.bytes_len = strlen(import_name);
+    import.tag = WASM_FUNCTION;
+    import.value.func = func;
+
+    return import;
+}
+
+uint64_t call_inc_and_get(wasmer_instance_t *instance)
+{
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    uint64_t gas_used = 0;
+    assert(wasmer_instance_call_shared_gas(instance, "inc_and_get", params, 0, results, 1, &gas_used) == WASMER_OK);
+
+    return gas_used;
+}
+
+int main()
+{
+    // Every operator costs 1 point.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    wasmer_value_tag no_sig[] = {};
+    wasmer_value_tag get_returns_sig[] = {WASM_I32};
+    wasmer_import_func_t *inc_func = wasmer_import_func_new((void (*)(void *)) inc, no_sig, 0, no_sig, 0);
+    wasmer_import_func_t *mul_func = wasmer_import_func_new((void (*)(void *)) mul, no_sig, 0, no_sig, 0);
+    wasmer_import_func_t *get_func = wasmer_import_func_new((void (*)(void *)) get, no_sig, 0, get_returns_sig, 1);
+    wasmer_import_t imports[] = {
+        create_import("inc", inc_func),
+        create_import("mul", mul_func),
+        create_import("get", get_func),
+    };
+
+    FILE *file = fopen("assets/inc.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_with_gas_metering(&module, bytes, len) == WASMER_OK);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.gas_limit = 100000;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate_with_options(module, &instance, imports, 3, &options, NULL) == WASMER_OK);
+    wasmer_instance_set_points_used(instance, 0);
+
+    // `inc_and_get` uses 100 gas in `inc`, plus the gas of its code.
+    uint64_t gas_used = call_inc_and_get(instance);
+    printf("Gas used without refund: %llu\n", (unsigned long long) gas_used);
+    assert(gas_used > 100);
+    assert(inc_result == WASMER_OK);
+
+    // When `get` runs, the 100 gas of `inc` are already used, but not
+    // the gas of the code after the call.
+    printf("Gas left in `get`: %llu\n", (unsigned long long) get_gas_left);
+    assert(get_gas_left <= options.gas_limit - 100);
+    assert(get_gas_left >= options.gas_limit - gas_used);
+
+    // `get` gives back part of it.
+    refund_amount = 30;
+    assert(call_inc_and_get(instance) == gas_used - 30);
+
+    // A refund cannot give back more gas than the call used so far: the
+    // gas of the previous calls sharing the budget is kept.
+    refund_amount = 100000;
+    uint64_t points_before = wasmer_instance_get_points_used(instance);
+    uint64_t capped_gas_used = call_inc_and_get(instance);
+    printf("Gas used with a capped refund: %llu\n", (unsigned long long) capped_gas_used);
+    assert(capped_gas_used < gas_used - 100);
+    assert(wasmer_instance_get_points_used(instance) == points_before + capped_gas_used);
+
+    // Charging more than the gas left stops the call as soon as `inc`
+    // returns, before `get` is called.
+    inc_cost = options.gas_limit;
+    int get_calls_before = get_calls;
+    wasmer_value_t oog_params[] = {};
+    wasmer_value_t oog_results[1];
+    assert(wasmer_instance_call_shared_gas(instance, "inc_and_get", oog_params, 0, oog_results, 1, &gas_used) == WASMER_ERROR);
+    assert(inc_result == WASMER_ERROR);
+    assert(inc_error == WASMER_ERROR_CODE_OUT_OF_GAS);
+    assert(get_calls == get_calls_before);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+    assert(wasmer_instance_get_points_used(instance) > options.gas_limit);
+    inc_cost = 100;
+
+    wasmer_instance_destroy(instance);
+
+    // The module is metered however it is instantiated: without the
+    // options, its host functions still read, use and refund gas.
+    refund_amount = 0;
+    assert(wasmer_module_instantiate(module, &instance, imports, 3) == WASMER_OK);
+    wasmer_instance_set_points_limit(instance, options.gas_limit);
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "mul_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(mul_gas_left_result == WASMER_OK);
+    assert(mul_gas_left > 0);
+    assert(mul_gas_left < options.gas_limit);
+    uint64_t points_before_inc = wasmer_instance_get_points_used(instance);
+    assert(wasmer_instance_call(instance, "inc_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(inc_result == WASMER_OK);
+    assert(wasmer_instance_get_points_used(instance) > points_before_inc + 100);
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+
+    // A module compiled without metering has no gas left to read.
+    metered = false;
+    module = NULL;
+    assert(wasmer_compile(&module, bytes, len) == WASMER_OK);
+    assert(wasmer_module_instantiate(module, &instance, imports, 3) == WASMER_OK);
+    assert(wasmer_instance_call(instance, "mul_and_get", params, 0, results, 1) == WASMER_OK);
+    assert(mul_gas_left_result == WASMER_ERROR);
+    assert(mul_gas_left_error == WASMER_ERROR_CODE_UNSUPPORTED);
+    wasmer_instance_destroy(instance);
+
+    wasmer_module_destroy(module);
+    wasmer_import_func_destroy(inc_func);
+    wasmer_import_func_destroy(mul_func);
+    wasmer_import_func_destroy(get_func);
+    free(bytes);
+
+    return 0;
+}
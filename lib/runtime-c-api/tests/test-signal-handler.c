@@ -0,0 +1,57 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <signal.h>
+#include <stdint.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/memory_div.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate(&instance, bytes, len, NULL, 0) == WASMER_OK);
+
+    wasmer_value_t out_of_bounds_params[] = {
+        {.tag = WASM_I32, .value.I32 = 65536},
+        {.tag = WASM_I32, .value.I32 = 1},
+    };
+    wasmer_value_t results[] = {};
+
+    // With the handler, an out-of-bounds store is a trap.
+    assert(wasmer_set_signal_handler_enabled(true) == WASMER_OK);
+    wasmer_result_t call_result = wasmer_instance_call(instance, "store", out_of_bounds_params, 2, results, 0);
+    printf("Call result (enabled): %d\n", call_result);
+    assert(call_result == WASMER_ERROR);
+
+    // Without it, the store terminates the process.
+    assert(wasmer_set_signal_handler_enabled(false) == WASMER_OK);
+    pid_t child = fork();
+    assert(child >= 0);
+    if (child == 0) {
+        wasmer_instance_call(instance, "store", out_of_bounds_params, 2, results, 0);
+        _exit(0);
+    }
+    int status = 0;
+    assert(waitpid(child, &status, 0) == child);
+    printf("Child terminated by signal: %d\n", WIFSIGNALED(status) ? WTERMSIG(status) : 0);
+    assert(WIFSIGNALED(status) && WTERMSIG(status) == SIGSEGV);
+
+    // Enabling the handler again makes it a trap again.
+    assert(wasmer_set_signal_handler_enabled(true) == WASMER_OK);
+    call_result = wasmer_instance_call(instance, "store", out_of_bounds_params, 2, results, 0);
+    printf("Call result (enabled again): %d\n", call_result);
+    assert(call_result == WASMER_ERROR);
+
+    wasmer_instance_destroy(instance);
+    free(bytes);
+    return 0;
+}
@@ -0,0 +1,67 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+uint8_t *bytes = NULL;
+long bytes_len = 0;
+
+wasmer_instance_t *instantiate(uint64_t max_stack_height)
+{
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.runtime_breakpoints = true;
+    options.meter_stack_height = true;
+    options.max_stack_height = max_stack_height;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, bytes_len, &options) == WASMER_OK);
+    return instance;
+}
+
+wasmer_result_t recurse(wasmer_instance_t *instance, int32_t n)
+{
+    wasmer_value_t params[] = {{.tag = WASM_I32, .value.I32 = n}};
+    wasmer_value_t results[1];
+
+    return wasmer_instance_call(instance, "recurse", params, 1, results, 1);
+}
+
+int main()
+{
+    FILE *file = fopen("assets/recursion.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    bytes_len = ftell(file);
+    bytes = malloc(bytes_len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, bytes_len, file);
+    fclose(file);
+
+    // A frame of `recurse` takes 2 slots, one for the call and one for
+    // its parameter, so 10 slots hold 5 frames: `recurse(4)`.
+    wasmer_instance_t *instance = instantiate(10);
+    assert(recurse(instance, 4) == WASMER_OK);
+    assert(recurse(instance, 5) == WASMER_ERROR);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_STACK_LIMIT);
+
+    // The frames of the call which trapped are not left on the stack.
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(recurse(instance, 4) == WASMER_OK);
+    wasmer_instance_destroy(instance);
+
+    // A limit below a single frame traps on every call.
+    instance = instantiate(1);
+    assert(recurse(instance, 0) == WASMER_ERROR);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_STACK_LIMIT);
+    wasmer_instance_destroy(instance);
+
+    // A limit of 0 means no limit.
+    instance = instantiate(0);
+    assert(recurse(instance, 100) == WASMER_OK);
+    wasmer_instance_destroy(instance);
+
+    free(bytes);
+    return 0;
+}
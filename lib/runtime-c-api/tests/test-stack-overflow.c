@@ -0,0 +1,75 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+wasmer_result_t call_recurse(wasmer_instance_t *instance, int32_t n, int32_t *result)
+{
+    wasmer_value_t params[] = {{.tag = WASM_I32, .value.I32 = n}};
+    wasmer_value_t results[1];
+    wasmer_result_t call_result = wasmer_instance_call(instance, "recurse", params, 1, results, 1);
+    *result = results[0].value.I32;
+
+    return call_result;
+}
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/recursion.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.max_call_depth = 1000;
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+
+    // `recurse(n)` runs n + 1 nested frames.
+    int32_t result = 0;
+    assert(call_recurse(instance, 999, &result) == WASMER_OK);
+    assert(result == 999);
+
+    // One frame more traps at the same depth on every machine.
+    assert(call_recurse(instance, 1000, &result) == WASMER_STACK_OVERFLOW);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_TRAP);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_CALL_DEPTH_LIMIT);
+
+    wasmer_trap_info_t trap_info;
+    assert(wasmer_instance_last_trap_info(instance, &trap_info) == WASMER_OK);
+    assert(trap_info.code == WASMER_TRAP_STACK_OVERFLOW);
+
+    char trap_message[128];
+    assert(wasmer_instance_last_trap_message_with_location(instance, trap_message, sizeof(trap_message)) > 0);
+    printf("Trap: %s\n", trap_message);
+    assert(strncmp(trap_message, "stack overflow", strlen("stack overflow")) == 0);
+
+    // The depth of the frames left by the trap is not counted against
+    // the next calls.
+    assert(call_recurse(instance, 999, &result) == WASMER_OK);
+    assert(result == 999);
+    assert(call_recurse(instance, 1000, &result) == WASMER_STACK_OVERFLOW);
+    assert(call_recurse(instance, 10, &result) == WASMER_OK);
+    assert(result == 10);
+
+    wasmer_instance_destroy(instance);
+
+    // Without the option, the depth is not bounded.
+    options.max_call_depth = 0;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    assert(call_recurse(instance, 1000, &result) == WASMER_OK);
+    assert(result == 1000);
+
+    wasmer_instance_destroy(instance);
+    free(bytes);
+
+    return 0;
+}
@@ -0,0 +1,128 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+int32_t call_initialized(wasmer_instance_t *instance)
+{
+    wasmer_value_t params[] = {};
+    wasmer_value_t result_one;
+    wasmer_value_t results[] = {result_one};
+
+    wasmer_result_t call_result = wasmer_instance_call(instance, "initialized", params, 0, results, 1);
+    assert(call_result == WASMER_OK);
+    return results[0].value.I32;
+}
+
+int main()
+{
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/start.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_instance_t *instance = NULL;
+
+    // The start function runs at instantiation.
+    wasmer_import_t imports[] = {};
+    assert(wasmer_instantiate(&instance, bytes, len, imports, 0) == WASMER_OK);
+    assert(call_initialized(instance) == 42);
+    wasmer_instance_destroy(instance);
+
+    // The default options run it too.
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    assert(call_initialized(instance) == 42);
+    wasmer_instance_destroy(instance);
+
+    // With `skip_start`, it is left to `wasmer_instance_run_start()`.
+    options.skip_start = true;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_OK);
+    assert(call_initialized(instance) == 0);
+
+    uint64_t start_gas_used = 1;
+    assert(wasmer_instance_run_start(instance, &start_gas_used) == WASMER_OK);
+    assert(start_gas_used == 0);
+    assert(call_initialized(instance) == 42);
+    wasmer_instance_destroy(instance);
+
+    // A reset runs the start function again, metered from zero.
+    uint32_t opcode_costs[OPCODE_COUNT];
+    for (int i = 0; i < OPCODE_COUNT; i++) {
+        opcode_costs[i] = 1;
+    }
+    wasmer_set_opcode_costs(opcode_costs);
+
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.metering = true;
+    options.runtime_breakpoints = true;
+    options.gas_limit = 1000;
+    uint64_t gas_used = 0;
+    assert(wasmer_instantiate_with_options_and_gas(&instance, bytes, len, &options, &gas_used) == WASMER_OK);
+    printf("Start gas used: %llu\n", (unsigned long long) gas_used);
+    assert(gas_used > 0);
+    assert(wasmer_instance_get_points_used(instance) == gas_used);
+    assert(call_initialized(instance) == 42);
+    assert(wasmer_instance_get_points_used(instance) > gas_used);
+
+    // The instance keeps the gas limit of the options.
+    uint64_t points_limit = 0;
+    assert(wasmer_instance_get_points_limit(instance, &points_limit) == WASMER_OK);
+    assert(points_limit == 1000);
+
+    wasmer_instance_set_points_used(instance, 1000);
+    wasmer_instance_set_runtime_breakpoint_value(instance, BREAKPOINT_VALUE_OUT_OF_GAS);
+    assert(wasmer_instance_reset(instance) == WASMER_OK);
+    assert(wasmer_instance_get_points_used(instance) == gas_used);
+    assert(wasmer_instance_get_points_limit(instance, &points_limit) == WASMER_OK);
+    assert(points_limit == 1000);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_NO_BREAKPOINT);
+    assert(call_initialized(instance) == 42);
+
+    // Without the start function, nothing is charged.
+    assert(wasmer_instance_reset_zeroed(instance) == WASMER_OK);
+    assert(wasmer_instance_get_points_used(instance) == 0);
+
+    // A start function which runs out of gas fails the reset.
+    wasmer_instance_set_points_limit(instance, 1);
+    assert(wasmer_instance_reset(instance) == WASMER_OUT_OF_GAS);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_OUT_OF_GAS);
+    assert(wasmer_instance_get_runtime_breakpoint_value(instance) == BREAKPOINT_VALUE_OUT_OF_GAS);
+    wasmer_instance_destroy(instance);
+
+    // It fails the instantiation too, without creating an instance, and
+    // the gas it consumed is still reported.
+    options.gas_limit = 1;
+    instance = NULL;
+    gas_used = 0;
+    assert(wasmer_instantiate_with_options_and_gas(&instance, bytes, len, &options, &gas_used) == WASMER_OUT_OF_GAS);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_OUT_OF_GAS);
+    assert(instance == NULL);
+    assert(gas_used > 1);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_with_options(&module, bytes, len, &options) == WASMER_OK);
+    gas_used = 0;
+    assert(wasmer_module_instantiate_with_options(module, &instance, imports, 0, &options, &gas_used) == WASMER_OUT_OF_GAS);
+    assert(instance == NULL);
+    assert(gas_used > 1);
+    wasmer_module_destroy(module);
+    options.gas_limit = 1000;
+
+    // Modules with a start function can be rejected altogether.
+    options.skip_start = false;
+    options.reject_start_function = true;
+    assert(wasmer_instantiate_with_options(&instance, bytes, len, &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+
+    free(bytes);
+    return 0;
+}
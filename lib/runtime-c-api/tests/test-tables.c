@@ -2,6 +2,8 @@
 #include "../wasmer.h"
 #include <assert.h>
 #include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
 
 int main()
 {
@@ -47,5 +49,95 @@ int main()
 
     printf("Destroy table\n");
     wasmer_table_destroy(table);
+
+    // (module (table 1 4 funcref))
+    uint8_t table_module[] = {
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        0x04, 0x05, 0x01, 0x70, 0x01, 0x01, 0x04,
+    };
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate(&instance, table_module, sizeof(table_module), NULL, 0) == WASMER_OK);
+
+    uint32_t prev_len = 0;
+    assert(wasmer_instance_table_grow(instance, 0, 2, &prev_len) == WASMER_OK);
+    assert(prev_len == 1);
+    uint32_t instance_table_len = 0;
+    assert(wasmer_instance_table_len(instance, 0, &instance_table_len) == WASMER_OK);
+    assert(instance_table_len == 3);
+
+    // Beyond the declared maximum, the table is left untouched.
+    assert(wasmer_instance_table_grow(instance, 0, 2, &prev_len) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(wasmer_instance_table_len(instance, 0, &instance_table_len) == WASMER_OK);
+    assert(instance_table_len == 3);
+
+    assert(wasmer_instance_table_grow(instance, 1, 1, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(wasmer_instance_table_grow(NULL, 0, 1, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    wasmer_instance_destroy(instance);
+
+    // The elements of a table are read as function indices.
+    FILE *elements_file = fopen("assets/table_elements.wasm", "r");
+    fseek(elements_file, 0, SEEK_END);
+    long elements_len = ftell(elements_file);
+    uint8_t *elements_bytes = malloc(elements_len);
+    fseek(elements_file, 0, SEEK_SET);
+    fread(elements_bytes, 1, elements_len, elements_file);
+    fclose(elements_file);
+
+    assert(wasmer_instantiate(&instance, elements_bytes, elements_len, NULL, 0) == WASMER_OK);
+    uint32_t func_index = 0;
+    assert(wasmer_instance_table_get(instance, 0, 0, &func_index) == WASMER_OK);
+    assert(func_index == 1);
+    assert(wasmer_instance_table_get(instance, 0, 1, &func_index) == WASMER_OK);
+    assert(func_index == 0);
+    assert(wasmer_instance_table_get(instance, 0, 2, &func_index) == WASMER_OK);
+    assert(func_index == UINT32_MAX);
+
+    assert(wasmer_instance_table_get(instance, 0, 3, &func_index) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(wasmer_instance_table_get(instance, 1, 0, &func_index) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(wasmer_instance_table_get(instance, 0, 0, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    assert(wasmer_instance_table_len(instance, 0, &instance_table_len) == WASMER_OK);
+    assert(instance_table_len == 3);
+    wasmer_instance_destroy(instance);
+    free(elements_bytes);
+
+    // The `max_table_elements` option bounds the growth below the
+    // declared maximum.
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.max_table_elements = 2;
+    assert(wasmer_instantiate_with_options(&instance, table_module, sizeof(table_module), &options) == WASMER_OK);
+    assert(wasmer_instance_table_grow(instance, 0, 1, &prev_len) == WASMER_OK);
+    assert(prev_len == 1);
+    assert(wasmer_instance_table_grow(instance, 0, 1, &prev_len) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(wasmer_instance_table_len(instance, 0, &instance_table_len) == WASMER_OK);
+    assert(instance_table_len == 2);
+    wasmer_instance_destroy(instance);
+
+    // `table.grow` is not compiled by the singlepass backend, so the
+    // modules growing their tables themselves are rejected, and the
+    // limit only applies to `wasmer_instance_table_grow()`.
+    FILE *file = fopen("assets/table_grow.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long bytes_len = ftell(file);
+    uint8_t *bytes = malloc(bytes_len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, bytes_len, file);
+    fclose(file);
+
+    options.metering = true;
+    options.enable_reference_types = true;
+    assert(wasmer_instantiate_with_options(&instance, bytes, bytes_len, &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_COMPILE);
+    free(bytes);
+
     return 0;
 }
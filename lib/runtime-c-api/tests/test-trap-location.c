@@ -0,0 +1,97 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+int main()
+{
+    FILE *file = fopen("assets/unreachable.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_instantiate(&instance, bytes, len, NULL, 0) == WASMER_OK);
+
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[] = {};
+    char trap_message[128];
+
+    // Nothing trapped yet.
+    assert(wasmer_instance_last_trap_message_with_location(instance, trap_message, sizeof(trap_message)) == 0);
+
+    assert(wasmer_instance_call(instance, "fail", params, 0, results, 0) == WASMER_ERROR);
+
+    wasmer_trap_info_t trap_info;
+    assert(wasmer_instance_last_trap_info(instance, &trap_info) == WASMER_OK);
+    assert(trap_info.code == WASMER_TRAP_UNREACHABLE);
+    assert(trap_info.has_location);
+    assert(trap_info.func_index == 0);
+    // `unreachable` is the second instruction of `fail`, after `nop`.
+    assert(trap_info.operator_index == 1);
+
+    int message_len = wasmer_instance_last_trap_message_with_location(instance, trap_message, sizeof(trap_message));
+    printf("Trap message: `%s`\n", trap_message);
+    assert(message_len == (int) strlen(trap_message) + 1);
+
+    char expected[128];
+    snprintf(expected, sizeof(expected), "unreachable at func 0 instruction %u", trap_info.operator_index);
+    assert(0 == strcmp(trap_message, expected));
+
+    assert(wasmer_instance_last_trap_message_with_location(instance, trap_message, message_len - 1) == -1);
+    assert(wasmer_instance_last_trap_message_with_location(instance, trap_message, 0) == -1);
+    assert(wasmer_instance_last_trap_message_with_location(instance, trap_message, -1) == -1);
+    assert(wasmer_instance_last_trap_message_with_location(instance, NULL, sizeof(trap_message)) == -1);
+    assert(wasmer_instance_last_trap_message_with_location(NULL, trap_message, sizeof(trap_message)) == -1);
+
+    // The diagnostics gather the trap along with the state of the instance.
+    uint32_t diagnostics_len = 0;
+    assert(wasmer_instance_dump_diagnostics(instance, NULL, 0, &diagnostics_len) == WASMER_OK);
+    char *diagnostics = malloc(diagnostics_len + 1);
+    uint32_t written = 0;
+    assert(wasmer_instance_dump_diagnostics(instance, (uint8_t *) diagnostics, diagnostics_len, &written) == WASMER_OK);
+    assert(written == diagnostics_len);
+    diagnostics[written] = '\0';
+    printf("Diagnostics: `%s`\n", diagnostics);
+    assert(diagnostics[0] == '{' && diagnostics[written - 1] == '}');
+    assert(strstr(diagnostics, "\"memory_size\":0") != NULL);
+    assert(strstr(diagnostics, "\"exports\":1,\"imports\":0") != NULL);
+    char expected_trap[160];
+    snprintf(expected_trap, sizeof(expected_trap), "\"last_trap\":\"%s\"", expected);
+    assert(strstr(diagnostics, expected_trap) != NULL);
+
+    // A too small buffer is rejected rather than truncating the JSON.
+    written = 0;
+    assert(wasmer_instance_dump_diagnostics(instance, (uint8_t *) diagnostics, 4, &written) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_INVALID_ARGUMENT);
+    assert(written == diagnostics_len);
+    assert(wasmer_instance_dump_diagnostics(instance, NULL, 0, NULL) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+    assert(wasmer_instance_dump_diagnostics(NULL, NULL, 0, &written) == WASMER_ERROR);
+    free(diagnostics);
+
+    // The trap only belongs to the instance which raised it.
+    wasmer_instance_t *other_instance = NULL;
+    assert(wasmer_instantiate(&other_instance, bytes, len, NULL, 0) == WASMER_OK);
+    assert(wasmer_instance_last_trap_info(other_instance, &trap_info) == WASMER_ERROR);
+    assert(wasmer_instance_last_trap_message_with_location(other_instance, trap_message, sizeof(trap_message)) == 0);
+    assert(wasmer_instance_dump_diagnostics(other_instance, NULL, 0, &diagnostics_len) == WASMER_OK);
+    diagnostics = malloc(diagnostics_len + 1);
+    assert(wasmer_instance_dump_diagnostics(other_instance, (uint8_t *) diagnostics, diagnostics_len, &written) == WASMER_OK);
+    diagnostics[written] = '\0';
+    assert(strstr(diagnostics, "\"last_trap\":null") != NULL);
+    free(diagnostics);
+    wasmer_instance_destroy(other_instance);
+
+    assert(wasmer_instance_last_trap_info(instance, &trap_info) == WASMER_OK);
+    assert(trap_info.code == WASMER_TRAP_UNREACHABLE);
+
+    wasmer_instance_destroy(instance);
+    free(bytes);
+
+    return 0;
+}
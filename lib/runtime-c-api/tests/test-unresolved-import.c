@@ -0,0 +1,78 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+static int handler_call_count = 0;
+static char handler_module_name[16] = {};
+static char handler_import_name[16] = {};
+
+void unresolved_import_handler(wasmer_instance_context_t *ctx, const char *module_name, const char *import_name)
+{
+    assert(ctx != NULL);
+
+    handler_call_count++;
+    strncpy(handler_module_name, module_name, sizeof(handler_module_name) - 1);
+    strncpy(handler_import_name, import_name, sizeof(handler_import_name) - 1);
+}
+
+int main()
+{
+    // Read the wasm file bytes; the module imports `env.print_str`.
+    FILE *file = fopen("assets/wasm_sample_app.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile(&module, bytes, len) == WASMER_OK);
+
+    wasmer_import_object_t *import_object = wasmer_import_object_new();
+    wasmer_instance_t *instance = NULL;
+
+    // By default, a missing import fails the instantiation.
+    assert(wasmer_module_import_instantiate(&instance, module, import_object) == WASMER_ERROR);
+
+    // With a handler, the instantiation succeeds, and calling the
+    // missing import traps.
+    assert(wasmer_import_object_set_unresolved_import_handler(import_object, unresolved_import_handler) == WASMER_OK);
+    assert(wasmer_module_import_instantiate(&instance, module, import_object) == WASMER_OK);
+
+    wasmer_value_t params[] = {};
+    wasmer_value_t results[] = {};
+    assert(wasmer_instance_call(instance, "hello_wasm", params, 0, results, 0) == WASMER_ERROR);
+
+    int error_len = wasmer_last_error_length();
+    char *error_str = malloc(error_len);
+    wasmer_last_error_message(error_str, error_len);
+    printf("Error str: `%s`\n", error_str);
+    assert(strstr(error_str, "called unimplemented host function env.print_str") != NULL);
+    free(error_str);
+
+    assert(handler_call_count == 1);
+    assert(strcmp(handler_module_name, "env") == 0);
+    assert(strcmp(handler_import_name, "print_str") == 0);
+
+    wasmer_instance_destroy(instance);
+
+    // The handler only applies to its import object.
+    wasmer_import_t imports[] = {};
+    assert(wasmer_instantiate(&instance, bytes, len, imports, 0) == WASMER_ERROR);
+
+    // Removing the handler restores the strict linking.
+    assert(wasmer_import_object_set_unresolved_import_handler(import_object, NULL) == WASMER_OK);
+    assert(wasmer_module_import_instantiate(&instance, module, import_object) == WASMER_ERROR);
+
+    assert(wasmer_import_object_set_unresolved_import_handler(NULL, unresolved_import_handler) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    wasmer_import_object_destroy(import_object);
+    wasmer_module_destroy(module);
+    free(bytes);
+
+    return 0;
+}
@@ -2,6 +2,8 @@
 #include "../wasmer.h"
 #include <assert.h>
 #include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
 
 int main()
 {
@@ -18,5 +20,44 @@ int main()
     printf("Result: %d", result);
     assert(result);
 
+    // The validated bytes can be compiled again without being
+    // validated.
+    wasmer_compilation_options_t options;
+    memset(&options, 0, sizeof(options));
+    options.struct_size = sizeof(options);
+    options.already_validated = true;
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile_with_options(&module, bytes, len, &options) == WASMER_OK);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_instantiate(module, &instance, imports, 0) == WASMER_OK);
+
+    wasmer_value_t params[] = {
+        {.tag = WASM_I32, .value.I32 = 7},
+        {.tag = WASM_I32, .value.I32 = 8},
+    };
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "sum", params, 2, results, 1) == WASMER_OK);
+    assert(results[0].value.I32 == 15);
+    wasmer_instance_destroy(instance);
+    wasmer_module_destroy(module);
+
+    // Without the option, the bytes are validated again.
+    options.already_validated = false;
+    assert(wasmer_compile_with_options(&module, bytes, len, &options) == WASMER_OK);
+    wasmer_module_destroy(module);
+
+    uint8_t invalid_bytes[] = {0x00, 0x61, 0x73, 0x6d, 0x02, 0x00, 0x00, 0x00};
+    assert(!wasmer_validate(invalid_bytes, sizeof(invalid_bytes)));
+    assert(wasmer_compile_with_options(&module, invalid_bytes, sizeof(invalid_bytes), &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_COMPILE);
+
+    assert(wasmer_compile_with_options(NULL, bytes, len, &options) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_NULL_POINTER);
+
+    free(bytes);
+
     return 0;
 }
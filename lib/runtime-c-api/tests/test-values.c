@@ -0,0 +1,89 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stddef.h>
+#include <stdint.h>
+
+int main()
+{
+    // The layout of `wasmer_value_t` must not depend on the target.
+    assert(_Alignof(wasmer_value) == 8);
+    assert(sizeof(wasmer_value) == 8);
+    assert(sizeof(wasmer_value_t) == 16);
+    assert(offsetof(wasmer_value_t, value) == 8);
+
+    // Read the wasm file bytes
+    FILE *file = fopen("assets/values.wasm", "r");
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_import_t imports[] = {};
+    wasmer_instance_t *instance = NULL;
+    wasmer_result_t compile_result = wasmer_instantiate(&instance, bytes, len, imports, 0);
+    printf("Compile result:  %d\n", compile_result);
+    assert(compile_result == WASMER_OK);
+
+    {
+        wasmer_value_t params[] = {{.tag = WASM_I32, .value.I32 = -42}};
+        wasmer_value_t results[1];
+
+        wasmer_result_t call_result = wasmer_instance_call(instance, "i32_identity", params, 1, results, 1);
+        assert(call_result == WASMER_OK);
+        assert(results[0].tag == WASM_I32);
+        assert(results[0].value.I32 == -42);
+    }
+
+    {
+        wasmer_value_t params[] = {{.tag = WASM_I64, .value.I64 = INT64_C(-0x123456789abcdef)}};
+        wasmer_value_t results[1];
+
+        wasmer_result_t call_result = wasmer_instance_call(instance, "i64_identity", params, 1, results, 1);
+        assert(call_result == WASMER_OK);
+        assert(results[0].tag == WASM_I64);
+        assert(results[0].value.I64 == INT64_C(-0x123456789abcdef));
+    }
+
+    {
+        wasmer_value_t params[] = {{.tag = WASM_F32, .value.F32 = 1.5f}};
+        wasmer_value_t results[1];
+
+        wasmer_result_t call_result = wasmer_instance_call(instance, "f32_identity", params, 1, results, 1);
+        assert(call_result == WASMER_OK);
+        assert(results[0].tag == WASM_F32);
+        assert(results[0].value.F32 == 1.5f);
+    }
+
+    {
+        wasmer_value_t params[] = {{.tag = WASM_F64, .value.F64 = -2.25}};
+        wasmer_value_t results[1];
+
+        wasmer_result_t call_result = wasmer_instance_call(instance, "f64_identity", params, 1, results, 1);
+        assert(call_result == WASMER_OK);
+        assert(results[0].tag == WASM_F64);
+        assert(results[0].value.F64 == -2.25);
+    }
+
+    {
+        // An `i64` following an `i32` is where a misaligned union shows up.
+        wasmer_value_t params[] = {
+            {.tag = WASM_I32, .value.I32 = 7},
+            {.tag = WASM_I64, .value.I64 = INT64_C(0x7fffffff00000001)},
+        };
+        wasmer_value_t results[1];
+
+        wasmer_result_t call_result = wasmer_instance_call(instance, "i64_after_i32", params, 2, results, 1);
+        assert(call_result == WASMER_OK);
+        assert(results[0].tag == WASM_I64);
+        assert(results[0].value.I64 == INT64_C(0x7fffffff00000001));
+    }
+
+    printf("Destroy instance\n");
+    wasmer_instance_destroy(instance);
+    free(bytes);
+
+    return 0;
+}
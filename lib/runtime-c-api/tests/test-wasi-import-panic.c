@@ -0,0 +1,48 @@
+#include <stdio.h>
+#include "../wasmer.h"
+#include <assert.h>
+#include <stdint.h>
+#include <string.h>
+
+int main()
+{
+    FILE *file = fopen("assets/wasi_proc_raise.wasm", "r");
+    assert(file);
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    uint8_t *bytes = malloc(len);
+    fseek(file, 0, SEEK_SET);
+    fread(bytes, 1, len, file);
+    fclose(file);
+
+    wasmer_module_t *module = NULL;
+    assert(wasmer_compile(&module, bytes, len) == WASMER_OK);
+
+    wasmer_import_object_t *import_object = wasmer_wasi_generate_default_import_object();
+    wasmer_instance_t *instance = NULL;
+    assert(wasmer_module_import_instantiate(&instance, module, import_object) == WASMER_OK);
+
+    // The WASI `proc_raise` host function panics. The panic does not
+    // unwind through the C API: the call traps instead.
+    wasmer_value_t params[] = {{.tag = WASM_I32, .value.I32 = 1}};
+    wasmer_value_t results[1];
+    assert(wasmer_instance_call(instance, "raise", params, 1, results, 1) == WASMER_ERROR);
+    assert(wasmer_last_error_code() == WASMER_ERROR_CODE_TRAP);
+
+    int error_len = wasmer_last_error_length();
+    char *error_str = malloc(error_len);
+    wasmer_last_error_message(error_str, error_len);
+    printf("Error str: `%s`\n", error_str);
+    assert(strstr(error_str, "panic: ") != NULL);
+    free(error_str);
+
+    // The instance can still be called.
+    assert(wasmer_instance_call(instance, "raise", params, 1, results, 1) == WASMER_ERROR);
+
+    wasmer_instance_destroy(instance);
+    wasmer_import_object_destroy(import_object);
+    wasmer_module_destroy(module);
+    free(bytes);
+
+    return 0;
+}
@@ -27,6 +27,14 @@
 #  define DEPRECATED(message) __declspec(deprecated(message))
 #endif
 
+// Define the `WASMER_ALIGNED` macro, used by the aligned types, e.g.
+// `wasmer_value`.
+#if defined(GCC) || defined(__GNUC__) || __has_attribute(aligned)
+#  define WASMER_ALIGNED(n) __attribute__((aligned(n)))
+#elif defined(MSVC) || __has_declspec_attribute(align)
+#  define WASMER_ALIGNED(n) __declspec(align(n))
+#endif
+
 #endif // WASMER_H_MACROS
 
 
@@ -38,8 +46,66 @@
 #include <cstdlib>
 #include <new>
 
+/// The nested calls of wasm functions have gone deeper than the
+/// `max_call_depth` compilation option.
+static const uint64_t BREAKPOINT_VALUE_CALL_DEPTH_LIMIT = 10;
+
+/// The execution failed.
+static const uint64_t BREAKPOINT_VALUE_EXECUTION_FAILED = 1;
+
+/// The call has executed more instructions than the
+/// `hard_instruction_limit` compilation option, whatever the gas limit.
+static const uint64_t BREAKPOINT_VALUE_INSTRUCTION_LIMIT = 8;
+
+/// The running call was interrupted by
+/// `wasmer_instance_request_interrupt()`.
+static const uint64_t BREAKPOINT_VALUE_INTERRUPTED = 6;
+
+/// The memory of the instance has grown beyond its limit.
+static const uint64_t BREAKPOINT_VALUE_MEMORY_LIMIT = 5;
+
+/// No breakpoint: the execution goes on.
+static const uint64_t BREAKPOINT_VALUE_NO_BREAKPOINT = 0;
+
+/// The gas limit of the instance has been exceeded.
+static const uint64_t BREAKPOINT_VALUE_OUT_OF_GAS = 4;
+
+/// The greatest breakpoint value reserved for the runtime.
+static const uint64_t BREAKPOINT_VALUE_RESERVED_MAX = 15;
+
+/// The wasm stack has grown beyond the `max_stack_height` compilation
+/// option.
+static const uint64_t BREAKPOINT_VALUE_STACK_LIMIT = 7;
+
+/// A table of the instance has grown beyond the `max_table_elements`
+/// compilation option.
+static const uint64_t BREAKPOINT_VALUE_TABLE_LIMIT = 9;
+
 static const uintptr_t OPCODE_COUNT = 448;
 
+/// The key of the keyed data which holds the second data slot of the
+/// instance contexts, see `wasmer_instance_context_data2_set()`. The
+/// embedders must not use it for their own keyed data.
+static const uint32_t WASMER_CONTEXT_DATA2_KEY = 4294967295;
+
+/// Length in bytes of the hash computed by
+/// `wasmer_instance_memory_hash()`.
+static const uint32_t WASMER_MEMORY_HASH_LENGTH = 32;
+
+/// Length in bytes of the hash computed by
+/// `wasmer_module_content_hash()`.
+static const uint32_t WASMER_MODULE_CONTENT_HASH_LENGTH = 32;
+
+/// The `opcode` of the entries of the opcode trace which are markers
+/// pushed by `wasmer_instance_context_trace_mark()`. No opcode has
+/// this code.
+static const uint16_t WASMER_OPCODE_TRACE_MARKER = 65535;
+
+/// The maximum number of entries of the opcode trace of an instance.
+/// The entries recorded once the trace is full are dropped, so a trace
+/// of this length may be truncated.
+static const uint32_t WASMER_OPCODE_TRACE_MAX_ENTRIES = 1048576;
+
 #if defined(WASMER_WASI_ENABLED)
 enum class Version : uint8_t {
   /// Version cannot be detected or is unknown.
@@ -73,6 +139,20 @@ enum class wasmer_result_t {
   WASMER_OK = 1,
   /// Represents a failure.
   WASMER_ERROR = 2,
+  /// Represents a call interrupted by
+  /// `wasmer_instance_request_interrupt()`, which is a failure too.
+  WASMER_INTERRUPTED = 3,
+  /// Represents a compilation aborted because it exceeded the
+  /// `compile_timeout_ms` compilation option, which is a failure too.
+  WASMER_TIMEOUT = 4,
+  /// Represents a call which trapped because its nested calls went
+  /// deeper than the `max_call_depth` compilation option, e.g. with a
+  /// too deep recursion, which is a failure too.
+  WASMER_STACK_OVERFLOW = 5,
+  /// Represents a call, or a start function run at instantiation,
+  /// which ran out of gas, see `BREAKPOINT_VALUE_OUT_OF_GAS`, which is
+  /// a failure too.
+  WASMER_OUT_OF_GAS = 6,
 };
 
 /// Represents all possibles WebAssembly value types.
@@ -89,6 +169,51 @@ enum class wasmer_value_tag : uint32_t {
   WASM_F64,
 };
 
+/// Represents a compiler backend.
+enum class wasmer_compiler_backend_t : uint32_t {
+  /// The Singlepass backend.
+  WASMER_BACKEND_SINGLEPASS,
+  /// The Cranelift backend.
+  WASMER_BACKEND_CRANELIFT,
+  /// The LLVM backend.
+  WASMER_BACKEND_LLVM,
+};
+
+/// The categories of the errors of the C API, see
+/// `wasmer_last_error_code()`.
+enum class wasmer_error_code_t : uint32_t {
+  /// There is no last error.
+  WASMER_ERROR_CODE_NONE = 0,
+  /// A pointer argument is null.
+  WASMER_ERROR_CODE_NULL_POINTER = 1,
+  /// A string argument is not valid UTF-8.
+  WASMER_ERROR_CODE_UTF8 = 2,
+  /// The wasm bytes cannot be parsed, validated or compiled.
+  WASMER_ERROR_CODE_COMPILE = 3,
+  /// The imports do not match the imports of the module.
+  WASMER_ERROR_CODE_LINK = 4,
+  /// The wasm code trapped, or hit a runtime breakpoint other than
+  /// the out of gas one.
+  WASMER_ERROR_CODE_TRAP = 5,
+  /// The gas limit of the instance is exceeded.
+  WASMER_ERROR_CODE_OUT_OF_GAS = 6,
+  /// An argument is invalid, e.g. an index out of bounds, or the
+  /// module does not comply with the compilation options.
+  WASMER_ERROR_CODE_INVALID_ARGUMENT = 7,
+  /// The operation is not supported, e.g. by the C API, or without
+  /// the `metering` compilation option.
+  WASMER_ERROR_CODE_UNSUPPORTED = 8,
+  /// The compilation exceeded the `compile_timeout_ms` compilation
+  /// option.
+  WASMER_ERROR_CODE_TIMEOUT = 9,
+  /// The call executed more instructions than the
+  /// `hard_instruction_limit` compilation option, whatever its gas
+  /// limit.
+  WASMER_ERROR_CODE_INSTRUCTION_LIMIT = 10,
+  /// Any other error, e.g. a panic or a failed allocation.
+  WASMER_ERROR_CODE_UNKNOWN = 255,
+};
+
 struct wasmer_module_t {
 
 };
@@ -102,6 +227,30 @@ struct wasmer_instance_t {
 
 };
 
+/// Opaque pointer to a batch of calls, see `wasmer_call_batch_new()`.
+struct wasmer_call_batch_t {
+
+};
+
+/// The `wasmer_call_batch_result_t` struct describes the outcome of a
+/// call of a batch. See `wasmer_call_batch_results()`.
+struct wasmer_call_batch_result_t {
+  /// The result of the call, as returned by `wasmer_instance_call()`.
+  wasmer_result_t result;
+  /// The category of the error of the call, or
+  /// `WASMER_ERROR_CODE_NONE` if the call succeeded.
+  wasmer_error_code_t error_code;
+  /// The message of the error of the call, or a null pointer if the
+  /// call succeeded.
+  const char *error_message;
+};
+
+/// Opaque pointer to a streaming compilation, see
+/// `wasmer_compile_streaming_begin()`.
+struct wasmer_compile_streaming_t {
+
+};
+
 struct wasmer_byte_array {
   const uint8_t *bytes;
   uint32_t bytes_len;
@@ -138,8 +287,12 @@ struct wasmer_export_func_t {
 /// This is a [Rust union][rust-union], which is equivalent to the C
 /// union. See `wasmer_value_t` to get a complete example.
 ///
+/// The union is 8-byte aligned on every target, including 32-bit
+/// targets where `i64` and `f64` may only be 4-byte aligned, so that
+/// its layout does not depend on the C compiler of the host.
+///
 /// [rust-union]: https://doc.rust-lang.org/reference/items/unions.html
-union wasmer_value {
+union WASMER_ALIGNED(8) wasmer_value {
   int32_t I32;
   int64_t I64;
   float F32;
@@ -270,10 +423,335 @@ struct wasmer_instance_context_t {
 
 };
 
+/// The `wasmer_compilation_options_t` struct holds the options of the
+/// compilation and the instantiation of a module, e.g. for
+/// `wasmer_instantiate_with_options()`.
+///
+/// `struct_size` must be set to `sizeof(wasmer_compilation_options_t)`.
+/// The fields past `struct_size` are read as zeroes, so that a caller
+/// built against an older `wasmer.h` keeps working when fields are
+/// appended; new fields are only ever appended. Every option is
+/// disabled when its field is zero, so a zero-initialized struct with
+/// its `struct_size` set holds the default options, except for the
+/// `memory.grow` limits, which deny any growth when zero:
+///
+/// ```c
+/// wasmer_compilation_options_t options = { .struct_size = sizeof(options) };
+/// options.metering = true;
+/// options.gas_limit = 1000000;
+/// ```
 struct wasmer_compilation_options_t {
+  /// The size of the struct as known by the caller.
+  uint32_t struct_size;
+  /// The gas limit of the instance.
+  uint64_t gas_limit;
+  /// The number of locals of a function which are not charged.
+  uintptr_t unmetered_locals;
+  /// The maximum number of `memory.grow` executed by the instance.
+  uintptr_t max_memory_grow;
+  /// The maximum number of pages added by a single `memory.grow`.
+  uintptr_t max_memory_grow_delta;
+  /// Records the location of the last executed opcode, see
+  /// `wasmer_instance_get_last_opcode_location()`.
+  bool opcode_trace;
+  /// Meters the gas used by the instance, with the opcode costs set
+  /// by `wasmer_set_opcode_costs()`.
+  bool metering;
+  /// Lets the instance stop at runtime breakpoints, e.g. when it runs
+  /// out of gas.
+  bool runtime_breakpoints;
+  /// Records the executed opcodes, see
+  /// `wasmer_instance_get_opcode_trace()`.
+  bool collect_opcode_trace;
+  /// Accepts the bulk memory operations.
+  bool enable_bulk_memory;
+  /// Accepts the reference types.
+  bool enable_reference_types;
+  /// Accepts the SIMD operations.
+  bool enable_simd;
+  /// Accepts the threads operations.
+  bool enable_threads;
+  /// The image written at `initial_memory_offset` into the memory of
+  /// the instance once it is created, or a null pointer.
+  const uint8_t *initial_memory;
+  /// The length of `initial_memory`.
+  uint32_t initial_memory_len;
+  /// The offset of `initial_memory` in the memory.
+  uint32_t initial_memory_offset;
+  /// The gas charged for each byte of `initial_memory`.
+  uint64_t initial_memory_gas_per_byte;
+  /// Rejects the modules with a start function.
+  bool reject_start_function;
+  /// Removes the custom sections before the compilation.
+  bool strip_custom_sections;
+  /// The maximum initial size of the memory in pages, 0 meaning no
+  /// maximum.
+  uint32_t max_initial_memory_pages;
+  /// Charges the whole gas limit when the instance runs out of gas.
+  bool charge_full_on_oog;
+  /// Traps when the stack height of the instance exceeds
+  /// `max_stack_height`, with the runtime breakpoint value
+  /// `BREAKPOINT_VALUE_STACK_LIMIT`. The frame of a function takes
+  /// one slot, plus one per parameter and local. No gas is charged
+  /// for the frames: the `metering` option charges the locals of each
+  /// function, beyond `unmetered_locals`, as their allocation.
+  bool meter_stack_height;
+  /// The maximum stack height, in slots, see `meter_stack_height`.
+  /// 0 means no limit.
+  uint64_t max_stack_height;
+  /// The codes of the opcodes rejected at compilation, or a null
+  /// pointer, see `wasmer_opcode_name()`.
+  const uint16_t *denied_opcodes;
+  /// The length of `denied_opcodes`.
+  uint32_t denied_opcodes_len;
+  /// Does not run the start function at instantiation, see
+  /// `wasmer_instance_run_start()`.
+  bool skip_start;
+  /// The maximum number of instructions executed by a call, 0 meaning
+  /// no maximum.
+  uint64_t hard_instruction_limit;
+  /// The maximum duration of the compilation in milliseconds, 0
+  /// meaning no maximum.
+  uint64_t compile_timeout_ms;
+  /// The index of the first function traced by the opcode trace.
+  uint32_t trace_function_start;
+  /// The index past the last function traced by the opcode trace, 0
+  /// meaning no bound.
+  uint32_t trace_function_end;
+  /// Reports the entries of the basic blocks, see
+  /// `wasmer_instance_set_block_coverage_callback()`.
+  bool block_coverage;
+  /// Breaks the gas used down by opcode category, see
+  /// `wasmer_instance_get_gas_breakdown()`.
+  bool gas_breakdown;
+  /// The maximum number of elements of the table, 0 meaning no
+  /// maximum, see `wasmer_instance_table_grow()`. The singlepass
+  /// backend does not compile `table.grow`, so the modules growing
+  /// their tables themselves are rejected anyway.
+  uint32_t max_table_elements;
+  /// Records the gas used by each function, see
+  /// `wasmer_instance_get_per_function_gas()`.
+  bool per_function_gas;
+  /// Skips the validation of bytes already validated, see
+  /// `wasmer_validate()`.
+  bool already_validated;
+  /// Records the kind of each instruction which may trap, see
+  /// `wasmer_trap_code_t`.
+  bool deterministic_traps;
+  /// Rejects a metered instance when one of the opcodes which let the
+  /// code run again, i.e. `loop`, `br`, `br_if`, `br_table`, `call`
+  /// and `call_indirect`, costs nothing, since the gas limit would not
+  /// bound its execution, see `wasmer_check_opcode_costs()`.
+  bool require_loop_costs;
+  /// Reports the calls of imported functions, see
+  /// `wasmer_instance_set_import_call_callback()`.
+  bool trace_import_calls;
+  /// The maximum number of nested calls of wasm functions, 0 meaning
+  /// no maximum. A call going deeper traps with the runtime breakpoint
+  /// value `BREAKPOINT_VALUE_CALL_DEPTH_LIMIT` and returns
+  /// `WASMER_STACK_OVERFLOW`. Unlike the size of the native stack, the
+  /// depth does not depend on the machine, so that every machine traps
+  /// at the same call.
+  uint32_t max_call_depth;
+  /// Replaces the NaN results of `nearest`, `trunc`, `floor`, `ceil` and
+  /// `sqrt` on f32 and f64 with the canonical NaN, so that these
+  /// operators give the same bits on every backend and CPU: their other
+  /// results are exact. The runtime built with the default
+  /// `deterministic-execution` feature rejects the float operators
+  /// anyway.
+  bool deterministic_float;
+};
+
+/// The `wasmer_compile_stats_t` struct receives statistics about the
+/// compilation and the instantiation of a module, see
+/// `wasmer_instantiate_with_options_and_stats()`.
+struct wasmer_compile_stats_t {
+  /// The time spent compiling the module, in nanoseconds.
+  uint64_t compile_nanos;
+  /// The time spent instantiating the module, including its start
+  /// function and its initial memory image, in nanoseconds.
+  uint64_t instantiate_nanos;
+  /// The size in bytes of the machine code generated for the module,
+  /// or 0 if the backend does not expose its code.
+  uint64_t code_size;
+  /// The number of functions compiled, i.e. the functions defined by
+  /// the module, imported functions excluded.
+  uint32_t function_count;
+};
+
+/// The reasons why an opcode is not allowed by the compilation options.
+enum class wasmer_compile_violation_kind_t : uint32_t {
+  /// The opcode needs the `enable_bulk_memory` option.
+  WASMER_COMPILE_VIOLATION_BULK_MEMORY = 0,
+  /// The opcode needs the `enable_reference_types` option.
+  WASMER_COMPILE_VIOLATION_REFERENCE_TYPES = 1,
+  /// The opcode needs the `enable_simd` option.
+  WASMER_COMPILE_VIOLATION_SIMD = 2,
+  /// The opcode needs the `enable_threads` option.
+  WASMER_COMPILE_VIOLATION_THREADS = 3,
+  /// The opcode is one of the `denied_opcodes` of the options.
+  WASMER_COMPILE_VIOLATION_DENIED_OPCODE = 4,
+};
+
+/// An opcode of a module which is not allowed by the compilation
+/// options, see `wasmer_last_compile_violations_get()`.
+struct wasmer_compile_violation_t {
+  /// Why the opcode is not allowed.
+  wasmer_compile_violation_kind_t kind;
+  /// The stable code of the opcode, see `wasmer_opcode_name()`.
+  uint16_t opcode;
+  /// The index of the function using the opcode, in the function
+  /// index space of the module, imported functions first.
+  uint32_t func_index;
+  /// The byte offset of the opcode in the wasm binary.
+  uint32_t offset;
+};
+
+/// The `wasmer_function_gas_t` struct receives the gas used by a
+/// function during the last call, see
+/// `wasmer_instance_get_per_function_gas()`.
+struct wasmer_function_gas_t {
+  /// The index of the function, counting the imported functions, as
+  /// expected by `wasmer_module_function_name()`.
+  uint32_t func_index;
+  /// The gas charged in the code of the function itself, excluding
+  /// the functions it called.
+  uint64_t gas;
+};
+
+/// The `wasmer_gas_breakdown_t` struct receives the gas used by the last
+/// call of an instance per category of opcodes, see
+/// `wasmer_instance_get_gas_breakdown()`.
+struct wasmer_gas_breakdown_t {
+  /// The gas used by the constants, locals, parametric, numeric,
+  /// conversion, reference and SIMD lane opcodes.
+  uint64_t arithmetic;
+  /// The gas used by the globals, loads, stores, memory and table
+  /// opcodes, atomics, and by the allocation of the locals.
+  uint64_t memory;
+  /// The gas used by the blocks, branches, returns, `nop` and
+  /// `unreachable`.
+  uint64_t control;
+  /// The gas used by the direct and indirect calls.
+  uint64_t call;
+};
+
+/// An executed opcode and its byte offset in the original wasm
+/// binary. The offset can be used to symbolicate the trace against
+/// the name or DWARF sections of the module.
+///
+/// The entries whose `opcode` is `WASMER_OPCODE_TRACE_MARKER` are
+/// markers pushed by host functions instead.
+struct wasmer_opcode_trace_entry_t {
+  /// The stable code of the opcode, see `wasmer_opcode_name()`.
+  uint16_t opcode;
+  /// The byte offset of the opcode in the wasm binary, or 0 for a
+  /// marker.
+  uint32_t offset;
+  /// The marker given to `wasmer_instance_context_trace_mark()`, or 0
+  /// for an opcode.
+  uint64_t marker;
+};
+
+/// List of trap codes.
+///
+/// Each kind of trap of WebAssembly has one code:
+///
+/// * `unreachable`: `WASMER_TRAP_UNREACHABLE`,
+/// * an out-of-bounds memory access: `WASMER_TRAP_MEMORY_OUT_OF_BOUNDS`,
+/// * an out-of-bounds table access by `call_indirect`:
+///   `WASMER_TRAP_CALL_INDIRECT_OUT_OF_BOUNDS`,
+/// * a `call_indirect` signature mismatch:
+///   `WASMER_TRAP_INCORRECT_CALL_INDIRECT_SIGNATURE`,
+/// * an integer overflow or an integer division by zero:
+///   `WASMER_TRAP_ILLEGAL_ARITHMETIC`,
+/// * a stack overflow: `WASMER_TRAP_STACK_OVERFLOW`,
+/// * running out of gas: `WASMER_TRAP_OUT_OF_GAS`.
+///
+/// The codes are only canonical, i.e. the same whatever the backend
+/// which compiled the module, for the modules compiled with the
+/// `deterministic_traps` compilation option: only then can the kind of
+/// a trap be relied upon, e.g. to decide the outcome of a transaction.
+/// Without it, the singlepass backend, the default one, only tells
+/// apart `unreachable`, the arithmetic traps, the stack overflows and
+/// the out of gas traps; its other traps are `WASMER_TRAP_UNKNOWN`.
+enum class wasmer_trap_code_t : uint32_t {
+  /// An `unreachable` opcode was executed.
+  WASMER_TRAP_UNREACHABLE = 0,
+  /// A `call_indirect` was made with an incorrect signature.
+  WASMER_TRAP_INCORRECT_CALL_INDIRECT_SIGNATURE = 1,
+  /// A memory access was out of bounds.
+  WASMER_TRAP_MEMORY_OUT_OF_BOUNDS = 2,
+  /// A `call_indirect` was made out of the table bounds.
+  WASMER_TRAP_CALL_INDIRECT_OUT_OF_BOUNDS = 3,
+  /// An arithmetic exception: an integer division or remainder by
+  /// zero, or a signed integer division overflow (`INT_MIN / -1`).
+  ///
+  /// Every backend reports these with this code.
+  WASMER_TRAP_ILLEGAL_ARITHMETIC = 4,
+  /// A misaligned atomic access.
+  WASMER_TRAP_MISALIGNED_ATOMIC_ACCESS = 5,
+  /// The nested calls went deeper than the `max_call_depth`
+  /// compilation option, see `BREAKPOINT_VALUE_CALL_DEPTH_LIMIT`.
+  WASMER_TRAP_STACK_OVERFLOW = 6,
+  /// The gas limit of the instance was exceeded, see
+  /// `BREAKPOINT_VALUE_OUT_OF_GAS`.
+  WASMER_TRAP_OUT_OF_GAS = 7,
+  /// The trap code could not be determined.
+  WASMER_TRAP_UNKNOWN = 255,
+};
 
+/// The `wasmer_trap_info_t` struct describes where and why a trap
+/// occurred. See `wasmer_instance_last_trap_info()`.
+struct wasmer_trap_info_t {
+  /// The trap code.
+  wasmer_trap_code_t code;
+  /// Whether `func_index` and `operator_index` are set.
+  bool has_location;
+  /// The index of the function which trapped, imported functions
+  /// included.
+  uint32_t func_index;
+  /// The index of the instruction which trapped among the
+  /// instructions of the function body, counting from 0. This is not
+  /// a byte offset in the wasm binary. The instructions inserted by the
+  /// middlewares of the module, e.g. the gas metering, are counted too.
+  uint32_t operator_index;
 };
 
+/// A `malloc`-like function, used to allocate the buffers returned to
+/// C. See `wasmer_set_allocator()`.
+using wasmer_malloc_t = void*(*)(uintptr_t size);
+
+/// A `free`-like function, used to free the buffers returned to C.
+/// See `wasmer_set_allocator()`.
+using wasmer_free_t = void(*)(void *ptr);
+
+/// A function called on the entry of each basic block executed by an
+/// instance, with the user data given to
+/// `wasmer_instance_set_block_coverage_callback()` and the id of the
+/// block.
+using wasmer_block_coverage_callback_t = void(*)(void *user_data, uint32_t block_id);
+
+/// A function called before each call of an imported function by an
+/// instance, with the user data given to
+/// `wasmer_instance_set_import_call_callback()` and the name of the
+/// import, without its module name.
+using wasmer_import_call_callback_t = void(*)(void *user_data, const char *import_name);
+
+/// A function called with the data of an instance when the instance is
+/// destroyed, see `wasmer_instance_context_data_set_with_finalizer()`.
+using wasmer_instance_context_data_finalizer_t = void(*)(void *data);
+
+/// A function called before each `memory.grow` of an instance, with
+/// the user data given to `wasmer_instance_set_memory_grow_callback()`
+/// and the number of requested pages. Returning 0 allows the growth;
+/// returning any other value denies it.
+using wasmer_memory_grow_callback_t = int(*)(void *user_data, uint32_t requested_pages);
+
+/// A function called in place of the function imports which are not
+/// provided, see `wasmer_import_object_set_unresolved_import_handler()`.
+using wasmer_unresolved_import_handler_t = void(*)(wasmer_instance_context_t *ctx, const char *module_name, const char *import_name);
+
 /// The `wasmer_limit_option_t` struct represents an optional limit
 /// for `wasmer_limits_t`.
 struct wasmer_limit_option_t {
@@ -328,6 +806,152 @@ struct wasmer_wasi_map_dir_entry_t {
 
 extern "C" {
 
+/// Lists the compiler backends this library was built with, since they
+/// depend on the features enabled at build time.
+///
+/// The backends are written to `out`, which can hold `out_len` of them,
+/// and their number is stored in `written`. At most 3 backends are
+/// available.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// `out` is too small, in which case nothing is written to `out`, and
+/// `written` receives the number of available backends. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_compiler_backend_t backends[3];
+/// uint32_t backends_length = 0;
+/// wasmer_available_backends(backends, 3, &backends_length);
+///
+/// for (uint32_t i = 0; i < backends_length; i++) {
+///     if (backends[i] == WASMER_BACKEND_SINGLEPASS) {
+///         // Singlepass is available.
+///     }
+/// }
+/// ```
+wasmer_result_t wasmer_available_backends(wasmer_compiler_backend_t *out,
+                                          uint32_t out_len,
+                                          uint32_t *written);
+
+/// Returns the version of the given compiler backend as a
+/// NUL-terminated string, or a null pointer if this library was not
+/// built with the backend, see `wasmer_available_backends()`.
+///
+/// The string is static: it must not be freed.
+///
+/// Example:
+///
+/// ```c
+/// const char *version = wasmer_backend_version(WASMER_BACKEND_SINGLEPASS);
+///
+/// if (version != NULL) {
+///     printf("singlepass %s\n", version);
+/// }
+/// ```
+const char *wasmer_backend_version(wasmer_compiler_backend_t backend);
+
+/// Frees a buffer returned to C, e.g. by `wasmer_instance_cache()`,
+/// with the registered `free` function if any, or with the Rust
+/// allocator otherwise. `length` must be the length of the buffer.
+///
+/// The allocator must not have changed since the buffer was returned.
+void wasmer_buffer_destroy(uint8_t *buffer, uint32_t length);
+
+/// Adds to a batch a call of the exported function `name` with the
+/// given parameters. The name and the parameters are copied.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_call_batch_add(wasmer_call_batch_t *batch,
+                                      const char *name,
+                                      const wasmer_value_t *params,
+                                      uint32_t params_len);
+
+/// Frees memory for the given batch of calls.
+void wasmer_call_batch_destroy(wasmer_call_batch_t *batch);
+
+/// Creates an empty batch of calls. Add calls with
+/// `wasmer_call_batch_add()`, then run them with
+/// `wasmer_call_batch_run()`.
+///
+/// If `stop_on_error` is true, running the batch stops at the first
+/// call which fails; otherwise every call runs, whatever the outcome of
+/// the previous ones.
+///
+/// The caller owns the object and should call
+/// `wasmer_call_batch_destroy()` to free it.
+wasmer_call_batch_t *wasmer_call_batch_new(bool stop_on_error);
+
+/// Returns the outcomes of the calls of the last run of a batch, in the
+/// order of the calls, and stores their number in `results_len`. With
+/// `stop_on_error`, there are no outcomes for the calls following the
+/// first failed one, which were not run.
+///
+/// The outcomes, and their error messages, are owned by the batch, and
+/// stay valid until it is run again or destroyed.
+///
+/// This function returns a null pointer, and stores 0 in
+/// `results_len`, if the batch has not been run, or if `batch` is a
+/// null pointer.
+const wasmer_call_batch_result_t *wasmer_call_batch_results(const wasmer_call_batch_t *batch,
+                                                            uint32_t *results_len);
+
+/// Runs the calls of a batch on an instance, in the order they were
+/// added, like with `wasmer_instance_call()`; the results of the
+/// functions are discarded. Use `wasmer_call_batch_results()` to see
+/// the outcome of each call.
+///
+/// The batch can be run again, on the same instance or on another one.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` if every call
+/// succeeded, and `wasmer_result_t::WASMER_ERROR` if a call failed, or
+/// upon failure. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message; its category is
+/// the one of the first failed call.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_call_batch_t *batch = wasmer_call_batch_new(false);
+/// wasmer_call_batch_add(batch, "transfer", transfer_params, 2);
+/// wasmer_call_batch_add(batch, "burn", burn_params, 1);
+///
+/// if (wasmer_call_batch_run(batch, instance) != WASMER_OK) {
+///     uint32_t results_len = 0;
+///     const wasmer_call_batch_result_t *results = wasmer_call_batch_results(batch, &results_len);
+///
+///     for (uint32_t index = 0; index < results_len; index++) {
+///         if (results[index].result != WASMER_OK) {
+///             printf("Call %d failed: %s\n", index, results[index].error_message);
+///         }
+///     }
+/// }
+///
+/// wasmer_call_batch_destroy(batch);
+/// ```
+wasmer_result_t wasmer_call_batch_run(wasmer_call_batch_t *batch, wasmer_instance_t *instance);
+
+/// Checks that the opcode costs set by `wasmer_set_opcode_costs()`
+/// charge the opcodes which let the code run again, i.e. `loop`, `br`,
+/// `br_if`, `br_table`, `call` and `call_indirect`: the gas limit does
+/// not bound the execution of a module if any of them costs nothing.
+/// The `require_loop_costs` compilation option applies this check.
+///
+/// Returns `wasmer_result_t::WASMER_OK` when the costs bound the execution.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` otherwise, with the
+/// `WASMER_ERROR_CODE_INVALID_ARGUMENT` error code. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_check_opcode_costs();
+
 /// Creates a new Module from the given wasm bytes.
 ///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
@@ -338,6 +962,114 @@ wasmer_result_t wasmer_compile(wasmer_module_t **module,
                                uint8_t *wasm_bytes,
                                uint32_t wasm_bytes_len);
 
+/// Creates a new Module from the wasm file at `path`, with the given
+/// compilation options, see `wasmer_instantiate_with_options()`. If
+/// `options` is a null pointer, the module is compiled like with
+/// `wasmer_compile()`.
+///
+/// The file is memory-mapped and compiled in place, instead of being
+/// read into a buffer first. It must not be modified during the
+/// compilation.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the file does not exist or cannot be read, and
+/// `wasmer_result_t::WASMER_TIMEOUT` when the compilation exceeds the
+/// `compile_timeout_ms` option; no module is created then. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_module_t *module = NULL;
+/// if (wasmer_compile_file_with_options(&module, "contracts/counter.wasm", options) != WASMER_OK) {
+///     // Get the error message with `wasmer_last_error_message()`.
+/// }
+/// ```
+wasmer_result_t wasmer_compile_file_with_options(wasmer_module_t **module,
+                                                 const char *path,
+                                                 const wasmer_compilation_options_t *options);
+
+/// Starts the compilation of a module whose wasm bytes are received in
+/// chunks, e.g. fetched from a peer. Give the chunks, in order, to
+/// `wasmer_compile_streaming_feed()`, then compile the module with
+/// `wasmer_compile_streaming_finish()`.
+///
+/// The headers of the module and of its sections are checked as the
+/// chunks arrive, so that a malformed module is rejected before all its
+/// bytes are received. The module itself is only compiled by
+/// `wasmer_compile_streaming_finish()`: the chunks are buffered until
+/// then, so the compilation does not overlap with the transfer of the
+/// bytes. The caller can release its own copies of the chunks.
+///
+/// The caller owns the object until it is given to
+/// `wasmer_compile_streaming_finish()`, or freed with
+/// `wasmer_compile_streaming_destroy()` to abort the compilation.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_compile_streaming_t *streaming = wasmer_compile_streaming_begin();
+///
+/// while ((chunk_len = receive_chunk(peer, chunk, sizeof(chunk))) > 0) {
+///     if (wasmer_compile_streaming_feed(streaming, chunk, chunk_len) != WASMER_OK) {
+///         // The streaming compilation is freed.
+///         return;
+///     }
+/// }
+///
+/// wasmer_module_t *module = NULL;
+/// if (wasmer_compile_streaming_finish(streaming, &module, options) != WASMER_OK) {
+///     // Get the error message with `wasmer_last_error_message()`.
+/// }
+/// ```
+wasmer_compile_streaming_t *wasmer_compile_streaming_begin();
+
+/// Frees a streaming compilation without compiling its module, e.g.
+/// when the transfer of the bytes fails.
+void wasmer_compile_streaming_destroy(wasmer_compile_streaming_t *streaming);
+
+/// Gives the next chunk of the wasm bytes to a streaming compilation,
+/// see `wasmer_compile_streaming_begin()`.
+///
+/// `chunk` may be a null pointer if `chunk_len` is 0.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, with the
+/// `WASMER_ERROR_CODE_COMPILE` error code if the bytes received so far
+/// are not the beginning of a valid module, e.g. they have an invalid
+/// header or an unknown section. The streaming compilation is freed
+/// upon failure, and must not be used anymore. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_compile_streaming_feed(wasmer_compile_streaming_t *streaming,
+                                              const uint8_t *chunk,
+                                              uint32_t chunk_len);
+
+/// Compiles the module of a streaming compilation, once all its chunks
+/// have been given to `wasmer_compile_streaming_feed()`, and frees the
+/// streaming compilation, whatever the outcome.
+///
+/// The module is compiled like by `wasmer_compile_file_with_options()`,
+/// with the given options, or like by `wasmer_compile()` if `options` is
+/// a null pointer. The options are only supported with the `metering`
+/// feature.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the bytes end in the middle of a section, and
+/// `wasmer_result_t::WASMER_TIMEOUT` when the compilation exceeds the
+/// `compile_timeout_ms` option; no module is created then. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_compile_streaming_finish(wasmer_compile_streaming_t *streaming,
+                                                wasmer_module_t **module,
+                                                const wasmer_compilation_options_t *options);
+
 /// Creates a new Module with gas limit from the given wasm bytes.
 ///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
@@ -348,9 +1080,41 @@ wasmer_result_t wasmer_compile_with_gas_metering(wasmer_module_t **module,
                                                  uint8_t *wasm_bytes,
                                                  uint32_t wasm_bytes_len);
 
-wasmer_result_t wasmer_compile_with_gas_metering(wasmer_module_t **module,
-                                                 uint8_t *wasm_bytes,
-                                                 uint32_t wasm_bytes_len);
+/// Creates a new Module with gas limit from the given wasm bytes, like
+/// `wasmer_compile_with_gas_metering()`, and stores the compilation
+/// statistics in `stats`, unless it is a null pointer. The
+/// `instantiate_nanos` field of `stats` is set to 0.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_compile_with_gas_metering_and_stats(wasmer_module_t **module,
+                                                           uint8_t *wasm_bytes,
+                                                           uint32_t wasm_bytes_len,
+                                                           wasmer_compile_stats_t *stats);
+
+/// Creates a new Module from the given wasm bytes, with the given
+/// compilation options, see `wasmer_instantiate_with_options()`. If
+/// `options` is a null pointer, the module is compiled like with
+/// `wasmer_compile()`.
+///
+/// The `already_validated` option skips the validation of the bytes,
+/// for the bytes already validated with `wasmer_validate()`, then kept
+/// in a trusted store. It is unsafe for untrusted bytes, and must never
+/// be set for them.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, and
+/// `wasmer_result_t::WASMER_TIMEOUT` when the compilation exceeds the
+/// `compile_timeout_ms` option; no module is created then. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_compile_with_options(wasmer_module_t **module,
+                                            uint8_t *wasm_bytes,
+                                            uint32_t wasm_bytes_len,
+                                            const wasmer_compilation_options_t *options);
 
 #if defined(WASMER_EMSCRIPTEN_ENABLED)
 /// Convenience function for setting up arguments and calling the Emscripten
@@ -400,6 +1164,27 @@ wasmer_result_t wasmer_emscripten_set_up(wasmer_instance_t *instance,
                                          wasmer_emscripten_globals_t *globals);
 #endif
 
+/// Estimates the gas cost of compiling the given wasm bytes with the
+/// given options, without compiling them.
+///
+/// The estimate is derived from the structure of the module only (the
+/// number of functions, opcodes, locals, tables and globals), and is
+/// therefore deterministic: the same bytes and options always give
+/// the same estimate. The module is validated against the features
+/// enabled in `options`.
+///
+/// The estimate is stored in `gas_out`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the wasm bytes are invalid. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_estimate_compile_gas(const uint8_t *wasm_bytes,
+                                            uint32_t wasm_bytes_len,
+                                            const wasmer_compilation_options_t *options,
+                                            uint64_t *gas_out);
+
 /// Gets export descriptor kind
 wasmer_import_export_kind wasmer_export_descriptor_kind(wasmer_export_descriptor_t *export_);
 
@@ -425,6 +1210,9 @@ int wasmer_export_descriptors_len(wasmer_export_descriptors_t *exports);
 /// Calls a `func` with the provided parameters.
 /// Results are set using the provided `results` pointer.
 ///
+/// The call is run like by `wasmer_instance_call()`, with the same
+/// per-call bookkeeping and hooks of the instance holding the export.
+///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
 ///
 /// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
@@ -511,6 +1299,39 @@ void wasmer_exports_destroy(wasmer_exports_t *exports);
 /// Gets wasmer_export by index
 wasmer_export_t *wasmer_exports_get(wasmer_exports_t *exports, int idx);
 
+/// Gets the export of the given name and kind.
+///
+/// Export names are unique within a module, whatever their kind: a
+/// module exporting, say, a function and a global under the same name
+/// is invalid, and fails to compile. Looking an export up by its name
+/// and kind therefore never picks an export of another kind, where a
+/// lookup by name alone would have to check the kind of the export it
+/// found. Should several exports match, the first one in declaration
+/// order is returned.
+///
+/// The returned export is owned by `exports`, and is valid as long as
+/// `exports` is.
+///
+/// This function returns a null pointer if no export matches. It
+/// returns a null pointer and updates the last error if `kind` is not a
+/// `wasmer_import_export_kind`, if `exports` or `name` is a null
+/// pointer, or if `name` is not a valid UTF-8 string.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_export_t *memory_export =
+///     wasmer_exports_get_by_name_and_kind(exports, "memory", WASM_MEMORY);
+///
+/// if (memory_export != NULL) {
+///     wasmer_memory_t *memory = NULL;
+///     wasmer_export_to_memory(memory_export, &memory);
+/// }
+/// ```
+wasmer_export_t *wasmer_exports_get_by_name_and_kind(wasmer_exports_t *exports,
+                                                     const char *name,
+                                                     uint32_t kind);
+
 /// Gets the length of the exports
 int wasmer_exports_len(wasmer_exports_t *exports);
 
@@ -575,6 +1396,12 @@ void wasmer_import_func_destroy(wasmer_import_func_t *func);
 /// The function pointer must have a lifetime greater than the
 /// WebAssembly instance lifetime.
 ///
+/// `params` and `returns` are the signature of the function. It is
+/// checked against the signature the module declares for the import
+/// when instantiating the module, so a mismatch fails the
+/// instantiation with an `Incorrect import signature` error, instead of
+/// trapping at the first call.
+///
 /// The caller owns the object and should call
 /// `wasmer_import_func_destroy` to free it.
 wasmer_import_func_t *wasmer_import_func_new(void (*func)(void *data),
@@ -620,6 +1447,19 @@ wasmer_result_t wasmer_import_func_returns(const wasmer_import_func_t *func,
 wasmer_result_t wasmer_import_func_returns_arity(const wasmer_import_func_t *func,
                                                  uint32_t *result);
 
+/// Caches the given imports for the later instantiations made on the
+/// current thread which take no imports, e.g.
+/// `wasmer_instantiate_with_options()` or `wasmer_instance_from_cache()`.
+///
+/// The cache is per thread: each thread instantiating modules must
+/// cache its own imports, and replacing them on a thread does not affect
+/// the instantiations made on the others. The instances already created
+/// keep the imports they were given.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
 wasmer_result_t wasmer_import_object_cache_from_imports(wasmer_import_t *imports,
                                                         unsigned int imports_len);
 
@@ -674,13 +1514,79 @@ wasmer_import_object_iter_t *wasmer_import_object_iterate_functions(const wasmer
 /// See also `wasmer_import_object_append`
 wasmer_import_object_t *wasmer_import_object_new();
 
+/// Registers a single host import in an existing import object, under
+/// the given module name and import name. The value is copied, so the
+/// caller keeps ownership of it.
+///
+/// The import object can then be shared by many instantiations, see
+/// `wasmer_instantiate_with_import_object()`.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_import_object_register(wasmer_import_object_t *import_object,
+                                              wasmer_byte_array module_name,
+                                              wasmer_byte_array import_name,
+                                              wasmer_import_export_kind tag,
+                                              wasmer_import_export_value value);
+
+/// Sets the handler of the function imports which are not provided by
+/// the import object, instead of failing the instantiations against it.
+///
+/// Each function import which is not provided is resolved to a
+/// placeholder which calls the handler with the names of the import,
+/// then traps with a message such as `called unimplemented host
+/// function env.foo`, so the instantiation only fails when the missing
+/// function is actually called.
+///
+/// The handler only applies to the instantiations against this import
+/// object, e.g. with `wasmer_module_import_instantiate()`. When it is
+/// null, these instantiations fail again on missing imports, which is
+/// the default. The handler is only supported on x86_64 unix platforms,
+/// and ignored elsewhere.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void log_unresolved_import(wasmer_instance_context_t *ctx, const char *module_name, const char *import_name) {
+///     fprintf(stderr, "unresolved import %s.%s\n", module_name, import_name);
+/// }
+///
+/// wasmer_import_object_t *import_object = wasmer_import_object_new();
+/// wasmer_import_object_set_unresolved_import_handler(import_object, log_unresolved_import);
+/// ```
+wasmer_result_t wasmer_import_object_set_unresolved_import_handler(wasmer_import_object_t *import_object,
+                                                                   wasmer_unresolved_import_handler_t handler);
+
+/// Serializes the module of an instance into cache bytes, which can be
+/// given to `wasmer_instance_from_cache()` later on.
+///
+/// The cache bytes are stamped with the version of the runtime, see
+/// `wasmer_version()`, and with the version of their serialization
+/// format, and only this version can load them.
+///
+/// The cache bytes are owned by the caller, and must be freed with
+/// `wasmer_buffer_destroy()`, or with the `free` function registered
+/// with `wasmer_set_allocator()` if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
 wasmer_result_t wasmer_instance_cache(wasmer_instance_t *instance,
                                       const uint8_t **cache_bytes,
                                       uint32_t *cache_len);
 
 /// Calls an exported function of a WebAssembly instance by `name`
 /// with the provided parameters. The exported function results are
-/// stored on the provided `results` pointer.
+/// stored on the provided `results` pointer, which may be a null
+/// pointer if `results_len` is 0.
 ///
 /// This function returns `wasmer_result_t::WASMER_OK` upon success,
 /// `wasmer_result_t::WASMER_ERROR` otherwise. You can use
@@ -690,7 +1596,26 @@ wasmer_result_t wasmer_instance_cache(wasmer_instance_t *instance,
 ///
 ///   * `instance` is a null pointer,
 ///   * `name` is a null pointer,
-///   * `params` is a null pointer.
+///   * `params` is a null pointer,
+///   * `results` is a null pointer while `results_len` is not 0.
+///
+/// It returns `wasmer_result_t::WASMER_INTERRUPTED` if the call was
+/// interrupted by `wasmer_instance_request_interrupt()`, and
+/// `wasmer_result_t::WASMER_STACK_OVERFLOW` if it trapped because its
+/// nested calls went deeper than the `max_call_depth` compilation
+/// option, e.g. with a too deep recursion. The instance can still be
+/// called afterwards.
+///
+/// A panic of an imported function implemented by the runtime, e.g. a
+/// WASI function, does not unwind through the caller: the call traps,
+/// and the error message holds `panic: ` and the panic message.
+///
+/// When the instance is metered, the call resets neither the gas used
+/// nor the gas limit of the instance: the gas it consumes is added to
+/// the gas used by the previous calls, and the limit bounds the total.
+/// See `wasmer_instance_call_shared_gas()` to charge a sequence of calls
+/// on one budget, and `wasmer_instance_call_with_gas()` to give a call
+/// its own budget.
 ///
 /// Example of calling an exported function that needs two parameters, and returns one value:
 ///
@@ -734,76 +1659,538 @@ wasmer_result_t wasmer_instance_call(wasmer_instance_t *instance,
                                      wasmer_value_t *results,
                                      uint32_t results_len);
 
-/// Gets the data that can be hold by an instance.
-///
-/// This function is complementary of
-/// `wasmer_instance_context_data_set()`. Please read its
-/// documentation. You can also read the documentation of
-/// `wasmer_instance_context_t` to get other examples.
+/// Calls an exported function like `wasmer_instance_call()`, but
+/// without enforcing the gas limit of the instance: the gas keeps
+/// being counted, and the call never traps because of gas exhaustion.
+/// This is useful to estimate the fee of a call which may exceed the
+/// limit.
 ///
-/// This function returns nothing if `ctx` is a null pointer.
-void *wasmer_instance_context_data_get(const wasmer_instance_context_t *ctx);
-
-/// Sets the data that can be hold by an instance context.
+/// The gas consumed by the call is stored in `gas_used`, and
+/// `trapped` is set to `true` if the call trapped for any other reason
+/// than gas exhaustion. The gas limit of the instance is restored
+/// before returning.
 ///
-/// An instance context (represented by the opaque
-/// `wasmer_instance_context_t` structure) can hold user-defined
-/// data. This function sets the data. This function is complementary
-/// of `wasmer_instance_context_data_get()`.
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_call_estimate(wasmer_instance_t *instance,
+                                              const char *name,
+                                              const wasmer_value_t *params,
+                                              uint32_t params_len,
+                                              wasmer_value_t *results,
+                                              uint32_t results_len,
+                                              uint64_t *gas_used,
+                                              bool *trapped);
+
+/// Calls a function of a WebAssembly instance by its index, with its
+/// parameters packed in a buffer, to spare the `wasmer_value_t` array of
+/// `wasmer_instance_call()` to the callers of functions with many
+/// parameters.
+///
+/// `func_index` is the index of the function in the function index
+/// space of the module, imported functions first; the function does
+/// not have to be exported.
+///
+/// The `params_len` parameters have the types of `param_types`, and
+/// are read in order from `param_data`, each one in its own size (4
+/// bytes for an `i32` or an `f32`, 8 bytes for an `i64` or an `f64`),
+/// in little-endian order, without padding. `param_data_len` must be
+/// the exact sum of these sizes.
+///
+/// The results are written like the ones of `wasmer_instance_call()`.
 ///
-/// This function does nothing if `instance` is a null pointer.
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped, and `wasmer_result_t::WASMER_INTERRUPTED` when the call was
+/// interrupted. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
 ///
 /// Example:
 ///
 /// ```c
-/// // Define your own data.
-/// typedef struct {
-///     // …
-/// } my_data;
-///
-/// // Allocate them and set them on the given instance.
-/// my_data *data = malloc(sizeof(my_data));
-/// data->… = …;
-/// wasmer_instance_context_data_set(instance, (void*) my_data);
-///
-/// // You can read your data.
-/// {
-///     my_data *data = (my_data*) wasmer_instance_context_data_get(wasmer_instance_context_get(instance));
-///     // …
-/// }
+/// // sum(i32, i64) -> i64
+/// wasmer_value_tag param_types[] = {WASM_I32, WASM_I64};
+/// uint8_t param_data[12];
+/// int32_t a = 1;
+/// int64_t b = 2;
+/// memcpy(param_data, &a, 4); // On a little-endian host.
+/// memcpy(param_data + 4, &b, 8);
+///
+/// wasmer_value_t results[1];
+/// wasmer_instance_call_packed(instance, sum_index, param_types, 2, param_data, sizeof(param_data), results, 1);
 /// ```
-void wasmer_instance_context_data_set(wasmer_instance_t *instance,
-                                      void *data_ptr);
+wasmer_result_t wasmer_instance_call_packed(wasmer_instance_t *instance,
+                                            uint32_t func_index,
+                                            const wasmer_value_tag *param_types,
+                                            uint32_t params_len,
+                                            const uint8_t *param_data,
+                                            uint32_t param_data_len,
+                                            wasmer_value_t *results,
+                                            uint32_t results_len);
 
-/// Returns the instance context. Learn more by looking at the
-/// `wasmer_instance_context_t` struct.
+/// Calls an exported function of a WebAssembly instance by `name`
+/// with the provided parameters, like `wasmer_instance_call()`, and
+/// writes its results packed in `result_data`, without their tags, to
+/// spare the `wasmer_value_t` results to the callers which know the
+/// result types of the function.
+///
+/// The results are written in order, each one in its own size (4 bytes
+/// for an `i32` or an `f32`, 8 bytes for an `i64` or an `f64`, 16 bytes
+/// for a `v128`), in little-endian order, without padding, like the
+/// parameters of `wasmer_instance_call_packed()`. `result_data_len`
+/// must be at least the sum of the sizes of the result types of the
+/// function, otherwise the function is not called. `result_data` may
+/// be a null pointer if the function has no results.
 ///
-/// This function returns `null` if `instance` is a null pointer.
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped or when `result_data` is too small, and
+/// `wasmer_result_t::WASMER_INTERRUPTED` when the call was interrupted.
+/// Use `wasmer_last_error_length` and `wasmer_last_error_message` to
+/// get an error message.
 ///
 /// Example:
 ///
 /// ```c
-/// const wasmer_instance_context_get *context = wasmer_instance_context_get(instance);
-/// my_data *data = (my_data *) wasmer_instance_context_data_get(context);
-/// // Do something with `my_data`.
+/// // sum(i32, i32) -> i32
+/// uint8_t result_data[4];
+/// wasmer_result_t call_result = wasmer_instance_call_raw_results(
+///     instance, "sum", params, 2, result_data, sizeof(result_data));
+///
+/// int32_t sum;
+/// memcpy(&sum, result_data, 4); // On a little-endian host.
 /// ```
+wasmer_result_t wasmer_instance_call_raw_results(wasmer_instance_t *instance,
+                                                 const char *name,
+                                                 const wasmer_value_t *params,
+                                                 uint32_t params_len,
+                                                 uint8_t *result_data,
+                                                 uint32_t result_data_len);
+
+/// Calls an exported function of a WebAssembly instance which returns
+/// a `(ptr, len)` pair of `i32`s designating bytes in its memory, e.g.
+/// a string, and copies these bytes into `out_buf`.
+///
+/// The function is called like with `wasmer_instance_call()`, and must
+/// return exactly two `i32`s: the offset of the bytes in the memory at
+/// index 0, then their length. The function is not called if its
+/// signature does not match.
+///
+/// The multi-value proposal is not enabled by the compiler, so the
+/// functions returning two `i32`s cannot be compiled yet. Until then,
+/// the functions returning a single `i64` packing the pair are called
+/// with `wasmer_instance_call_returning_packed_bytes()`.
+///
+/// `written` receives the length of the bytes, even when `out_buf`,
+/// which can hold `out_buf_len` bytes, is too small for them. `out_buf`
+/// may be a null pointer if `out_buf_len` is 0.
 ///
-/// It is often useful with `wasmer_instance_context_data_set()`.
-const wasmer_instance_context_t *wasmer_instance_context_get(wasmer_instance_t *instance);
-
-/// Gets the `memory_idx`th memory of the instance.
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped, when the bytes are out of the bounds of the memory, or when
+/// `out_buf` is too small, in which case nothing is written to
+/// `out_buf`; and `wasmer_result_t::WASMER_INTERRUPTED` when the call
+/// was interrupted. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
 ///
-/// Note that the index is always `0` until multiple memories are supported.
+/// Example:
 ///
-/// This function is mostly used inside host functions (aka imported
-/// functions) to read the instance memory.
+/// ```c
+/// uint8_t name[64];
+/// uint32_t name_length = 0;
+/// wasmer_result_t call_result = wasmer_instance_call_returning_bytes(
+///     instance, "get_name", params, 0, name, sizeof(name), &name_length);
 ///
-/// Example of a _host function_ that reads and prints a string based on a pointer and a length:
+/// if (call_result == WASMER_OK) {
+///     printf("Name: %.*s\n", name_length, name);
+/// }
+/// ```
+wasmer_result_t wasmer_instance_call_returning_bytes(wasmer_instance_t *instance,
+                                                     const char *name,
+                                                     const wasmer_value_t *params,
+                                                     uint32_t params_len,
+                                                     uint8_t *out_buf,
+                                                     uint32_t out_buf_len,
+                                                     uint32_t *written);
+
+/// Calls an exported function of a WebAssembly instance which returns
+/// a single `i64` packing the location of bytes in its memory, and
+/// copies these bytes into `out_buf`, like
+/// `wasmer_instance_call_returning_bytes()`.
+///
+/// The function must return exactly one `i64`, packing the offset of
+/// the bytes in the memory at index 0 in its low 32 bits, and their
+/// length in its high 32 bits. The function is not called if its
+/// signature does not match: the functions returning any other `i64`
+/// are never misread as returning bytes.
+///
+/// The arguments and the results are the ones of
+/// `wasmer_instance_call_returning_bytes()`.
+wasmer_result_t wasmer_instance_call_returning_packed_bytes(wasmer_instance_t *instance,
+                                                            const char *name,
+                                                            const wasmer_value_t *params,
+                                                            uint32_t params_len,
+                                                            uint8_t *out_buf,
+                                                            uint32_t out_buf_len,
+                                                            uint32_t *written);
+
+/// Calls an exported function like `wasmer_instance_call()`, as one
+/// call of a sequence sharing a single gas budget.
+///
+/// Neither the gas used nor the gas limit of the instance are reset:
+/// the gas limit is the budget of the whole sequence, and the gas
+/// consumed by the call is added to the gas used by the previous calls.
+/// To start a new sequence, reset the runtime breakpoint value with
+/// `wasmer_instance_set_runtime_breakpoint_value()`, set the gas used to
+/// 0 with `wasmer_instance_set_points_used()`, and its budget with
+/// `wasmer_instance_set_points_limit()`.
+///
+/// The gas consumed by this call alone is stored in `gas_used`, unless
+/// it is a null pointer, and the total is read with
+/// `wasmer_instance_get_points_used()`.
+///
+/// If the call runs out of gas, it traps, and the runtime breakpoint
+/// value of the instance is set to `BREAKPOINT_VALUE_OUT_OF_GAS`. The
+/// budget is then exhausted: every following call of the sequence
+/// fails without being called, with the `out of gas` error message, and
+/// stores 0 in `gas_used`. This holds whether or not the cost of the
+/// failing block is charged, see
+/// `wasmer_instance_set_exclude_failing_charge_on_oog()`, i.e. even
+/// though the gas used may be below the limit. The same applies once
+/// the gas used reaches the limit.
 ///
-/// ```c
-/// void print_string(const wasmer_instance_context_t *context, int32_t pointer, int32_t length) {
-///     // Get the 0th memory.
-///     const wasmer_memory_t *memory = wasmer_instance_context_memory(context, 0);
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_instance_set_points_used(instance, 0);
+/// wasmer_instance_set_points_limit(instance, 1000000);
+///
+/// // Both calls are charged on the same budget of 1000000.
+/// wasmer_instance_call_shared_gas(instance, "transfer", params, 2, results, 1, NULL);
+/// wasmer_instance_call_shared_gas(instance, "notify", params, 2, results, 1, NULL);
+/// ```
+wasmer_result_t wasmer_instance_call_shared_gas(wasmer_instance_t *instance,
+                                                const char *name,
+                                                const wasmer_value_t *params,
+                                                uint32_t params_len,
+                                                wasmer_value_t *results,
+                                                uint32_t results_len,
+                                                uint64_t *gas_used);
+
+/// Calls an exported function of a WebAssembly instance by `name`
+/// with the provided parameters, like `wasmer_instance_call()`, but
+/// without a results buffer. Any result of the function is discarded.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped or ran out of gas. You can use `wasmer_last_error_message()`
+/// to get the generated error message, and
+/// `wasmer_instance_get_runtime_breakpoint_value()` to know whether the
+/// call ran out of gas.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_value_t arguments[] = {};
+///
+/// wasmer_result_t call_result = wasmer_instance_call_void(instance, "init", arguments, 0);
+/// ```
+wasmer_result_t wasmer_instance_call_void(wasmer_instance_t *instance,
+                                          const char *name,
+                                          const wasmer_value_t *params,
+                                          uint32_t params_len);
+
+/// Calls an exported function like `wasmer_instance_call()`, with the
+/// given gas limit. The gas used and the runtime breakpoint value of the
+/// instance are reset before the call, and the gas consumed by the call
+/// is stored in `gas_used`.
+///
+/// If the call runs out of gas, it traps, the runtime breakpoint value
+/// of the instance is set to `BREAKPOINT_VALUE_OUT_OF_GAS`, see
+/// `wasmer_instance_get_runtime_breakpoint_value()`, and the function
+/// returns `wasmer_result_t::WASMER_OUT_OF_GAS`.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success, and
+/// otherwise the result of `wasmer_instance_call()`, e.g.
+/// `wasmer_result_t::WASMER_ERROR` when the call trapped. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint64_t gas_used = 0;
+/// wasmer_result_t call_result = wasmer_instance_call_with_gas(instance, "sum", params, 2, results, 1, 1000000, &gas_used);
+///
+/// if (call_result == WASMER_OUT_OF_GAS) {
+///     // The call ran out of gas.
+/// }
+/// ```
+wasmer_result_t wasmer_instance_call_with_gas(wasmer_instance_t *instance,
+                                              const char *name,
+                                              const wasmer_value_t *params,
+                                              uint32_t params_len,
+                                              wasmer_value_t *results,
+                                              uint32_t results_len,
+                                              uint64_t gas_limit,
+                                              uint64_t *gas_used);
+
+/// Calls an exported function like `wasmer_instance_call()`, and
+/// stores in `results_written_out`, unless it is a null pointer, the
+/// number of results the function produced, or 0 if the call failed.
+///
+/// Only the first result is stored in `results`, whatever
+/// `results_len`: a count greater than 1, or greater than
+/// `results_len`, tells that results were dropped.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t results_count = 0;
+/// wasmer_result_t call_result = wasmer_instance_call_with_results_count(
+///     instance, "sum", arguments, 2, results, 1, &results_count);
+///
+/// if (call_result == WASMER_OK && results_count > 1) {
+///     // Some results were lost.
+/// }
+/// ```
+wasmer_result_t wasmer_instance_call_with_results_count(wasmer_instance_t *instance,
+                                                        const char *name,
+                                                        const wasmer_value_t *params,
+                                                        uint32_t params_len,
+                                                        wasmer_value_t *results,
+                                                        uint32_t results_len,
+                                                        uint32_t *results_written_out);
+
+/// Reads the runtime breakpoint value of the instance, from within a
+/// host function.
+///
+/// Returns `BREAKPOINT_VALUE_NO_BREAKPOINT` if `ctx` is a null pointer.
+uint64_t wasmer_instance_context_breakpoint_value(const wasmer_instance_context_t *ctx);
+
+/// Gets the second data slot of an instance context.
+///
+/// This function is complementary of
+/// `wasmer_instance_context_data2_set()`. Please read its
+/// documentation.
+///
+/// This function returns a null pointer if `ctx` is a null pointer, or
+/// if the second slot was never set.
+void *wasmer_instance_context_data2_get(const wasmer_instance_context_t *ctx);
+
+/// Sets the second data slot of an instance context.
+///
+/// The second slot works like the data set by
+/// `wasmer_instance_context_data_set()`, and is independent of it, so
+/// that two host subsystems, e.g. a storage and a crypto module, can
+/// each find their own context object without sharing a single struct
+/// behind the first slot. It is the keyed data under
+/// `WASMER_CONTEXT_DATA2_KEY`, see
+/// `wasmer_instance_context_data_set_keyed()`. This function is
+/// complementary of `wasmer_instance_context_data2_get()`.
+///
+/// The data is never freed by the instance.
+///
+/// This function does nothing if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_instance_context_data_set(instance, (void*) storage);
+/// wasmer_instance_context_data2_set(instance, (void*) entropy_source);
+///
+/// // Within a host function.
+/// entropy_source_t *entropy_source = (entropy_source_t*) wasmer_instance_context_data2_get(context);
+/// ```
+void wasmer_instance_context_data2_set(wasmer_instance_t *instance, void *data_ptr);
+
+/// Gets the data that can be hold by an instance.
+///
+/// This function is complementary of
+/// `wasmer_instance_context_data_set()`. Please read its
+/// documentation. You can also read the documentation of
+/// `wasmer_instance_context_t` to get other examples.
+///
+/// This function returns nothing if `ctx` is a null pointer.
+void *wasmer_instance_context_data_get(const wasmer_instance_context_t *ctx);
+
+/// Gets the data of an instance context under the given key.
+///
+/// This function is complementary of
+/// `wasmer_instance_context_data_set_keyed()`. Please read its
+/// documentation.
+///
+/// This function returns a null pointer if `ctx` is a null pointer, or
+/// if no data is set under `key`.
+void *wasmer_instance_context_data_get_keyed(const wasmer_instance_context_t *ctx, uint32_t key);
+
+/// Sets the data that can be hold by an instance context.
+///
+/// An instance context (represented by the opaque
+/// `wasmer_instance_context_t` structure) can hold user-defined
+/// data. This function sets the data. This function is complementary
+/// of `wasmer_instance_context_data_get()`.
+///
+/// This function does nothing if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// // Define your own data.
+/// typedef struct {
+///     // …
+/// } my_data;
+///
+/// // Allocate them and set them on the given instance.
+/// my_data *data = malloc(sizeof(my_data));
+/// data->… = …;
+/// wasmer_instance_context_data_set(instance, (void*) my_data);
+///
+/// // You can read your data.
+/// {
+///     my_data *data = (my_data*) wasmer_instance_context_data_get(wasmer_instance_context_get(instance));
+///     // …
+/// }
+/// ```
+///
+/// The data is not freed by the instance, see
+/// `wasmer_instance_context_data_set_with_finalizer()` otherwise. If the
+/// previous data was set with a finalizer, the finalizer is called on it
+/// first, unless it is `data_ptr` again.
+void wasmer_instance_context_data_set(wasmer_instance_t *instance, void *data_ptr);
+
+/// Sets the data of an instance context under the given key.
+///
+/// Unlike the single slot of `wasmer_instance_context_data_set()`, the
+/// keyed data lets independent host subsystems, e.g. the storage, the
+/// crypto and the logging, each own a key and find their own context
+/// object, without coordinating the layout of a shared struct. The
+/// keys are small integers defined by the embedder, and are
+/// independent of the first data slot. `WASMER_CONTEXT_DATA2_KEY` is
+/// reserved for the second data slot. This function is complementary
+/// of `wasmer_instance_context_data_get_keyed()`.
+///
+/// Setting a null `data_ptr` removes the key. The data is never freed
+/// by the instance.
+///
+/// This function does nothing if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// enum { STORAGE_KEY, CRYPTO_KEY };
+///
+/// wasmer_instance_context_data_set_keyed(instance, STORAGE_KEY, (void*) storage);
+/// wasmer_instance_context_data_set_keyed(instance, CRYPTO_KEY, (void*) crypto);
+///
+/// // Within a host function.
+/// crypto_t *crypto = (crypto_t*) wasmer_instance_context_data_get_keyed(context, CRYPTO_KEY);
+/// ```
+void wasmer_instance_context_data_set_keyed(wasmer_instance_t *instance,
+                                            uint32_t key,
+                                            void *data_ptr);
+
+/// Sets the data held by an instance context, like
+/// `wasmer_instance_context_data_set()`, and gives its ownership to the
+/// instance: `finalizer` is called with the data when the instance is
+/// destroyed by `wasmer_instance_destroy()`, e.g. to free it.
+///
+/// The finalizer is also called when the data is replaced by other
+/// data, with or without a finalizer. A null `finalizer` sets the data
+/// exactly like `wasmer_instance_context_data_set()`.
+///
+/// The instance holds one finalizer at most: the finalizer set by the
+/// runtime for its own data, e.g. the WASI state, is replaced as well,
+/// after finalizing that data, so that no data is finalized twice.
+///
+/// This function does nothing if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// void free_data(void *data) {
+///     free(data);
+/// }
+///
+/// my_data *data = malloc(sizeof(my_data));
+/// wasmer_instance_context_data_set_with_finalizer(instance, (void*) data, free_data);
+///
+/// // Frees the data too.
+/// wasmer_instance_destroy(instance);
+/// ```
+void wasmer_instance_context_data_set_with_finalizer(wasmer_instance_t *instance,
+                                                     void *data_ptr,
+                                                     wasmer_instance_context_data_finalizer_t finalizer);
+
+/// Reads the gas left to the running call into `gas_left`, from within
+/// a host function. This is the gas limit of the instance minus the
+/// gas used so far, or 0 if the limit is already reached.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the module of the instance was not compiled with metering, i.e. with
+/// the `metering` compilation option or by
+/// `wasmer_compile_with_gas_metering()`, however it was instantiated.
+/// Use `wasmer_last_error_length` and `wasmer_last_error_message` to get
+/// an error message.
+///
+/// Example:
+///
+/// ```c
+/// void expensive_operation(wasmer_instance_context_t *context) {
+///     uint64_t gas_left = 0;
+///
+///     if (wasmer_instance_context_gas_left(context, &gas_left) != WASMER_OK || gas_left < EXPENSIVE_OPERATION_COST) {
+///         return;
+///     }
+///
+///     // Do the expensive operation.
+/// }
+/// ```
+wasmer_result_t wasmer_instance_context_gas_left(const wasmer_instance_context_t *ctx,
+                                                 uint64_t *gas_left);
+
+/// Returns the instance context. Learn more by looking at the
+/// `wasmer_instance_context_t` struct.
+///
+/// This function returns `null` if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// const wasmer_instance_context_get *context = wasmer_instance_context_get(instance);
+/// my_data *data = (my_data *) wasmer_instance_context_data_get(context);
+/// // Do something with `my_data`.
+/// ```
+///
+/// It is often useful with `wasmer_instance_context_data_set()`.
+const wasmer_instance_context_t *wasmer_instance_context_get(wasmer_instance_t *instance);
+
+/// Gets the `memory_idx`th memory of the instance.
+///
+/// Note that the index is always `0` until multiple memories are supported.
+///
+/// This function is mostly used inside host functions (aka imported
+/// functions) to read the instance memory.
+///
+/// This function returns a null pointer if `ctx` is a null pointer, or
+/// if the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example of a _host function_ that reads and prints a string based on a pointer and a length:
+///
+/// ```c
+/// void print_string(const wasmer_instance_context_t *context, int32_t pointer, int32_t length) {
+///     // Get the 0th memory.
+///     const wasmer_memory_t *memory = wasmer_instance_context_memory(context, 0);
 ///
 ///     // Get the memory data as a pointer.
 ///     uint8_t *memory_bytes = wasmer_memory_data(memory);
@@ -813,94 +2200,1349 @@ const wasmer_instance_context_t *wasmer_instance_context_get(wasmer_instance_t *
 /// }
 /// ```
 const wasmer_memory_t *wasmer_instance_context_memory(const wasmer_instance_context_t *ctx,
-                                                      uint32_t _memory_idx);
+                                                      uint32_t memory_idx);
 
-/// Frees memory for the given `wasmer_instance_t`.
+/// Gets the current base address of the `memory_idx`th memory of the
+/// instance, from within a host function, for host code which reads
+/// and writes the memory often and wants to skip the indirection of
+/// `wasmer_instance_context_memory()` and `wasmer_memory_data()`.
 ///
-/// Check the `wasmer_instantiate()` function to get a complete
-/// example.
+/// The memory spans `wasmer_instance_context_memory_length()` bytes
+/// from the returned address; accesses beyond it are undefined
+/// behavior.
 ///
-/// If `instance` is a null pointer, this function does nothing.
+/// The address is invalidated by any growth of the memory, by
+/// `memory.grow` or by `wasmer_memory_grow()`, which may move the
+/// memory. Get it again after any call which may grow the memory,
+/// including calls into the instance.
+///
+/// Note that the index is always `0` until multiple memories are
+/// supported.
+///
+/// This function returns a null pointer if `ctx` is a null pointer, if
+/// `memory_idx` is not `0`, or if the instance has no memory.
 ///
 /// Example:
 ///
 /// ```c
-/// // Get an instance.
-/// wasmer_instance_t *instance = NULL;
-/// wasmer_instantiate(&instance, bytes, bytes_length, imports, 0);
+/// void write_answer(wasmer_instance_context_t *context, int32_t pointer) {
+///     uint8_t *memory_base = wasmer_instance_context_memory_base(context, 0);
+///     uint32_t memory_length = wasmer_instance_context_memory_length(context, 0);
 ///
-/// // Destroy the instance.
-/// wasmer_instance_destroy(instance);
+///     if (memory_base != NULL && (uint32_t) pointer < memory_length) {
+///         memory_base[pointer] = 42;
+///     }
+/// }
 /// ```
-void wasmer_instance_destroy(wasmer_instance_t *instance);
+uint8_t *wasmer_instance_context_memory_base(const wasmer_instance_context_t *ctx,
+                                             uint32_t memory_idx);
 
-void wasmer_instance_disable_rkyv();
+/// Copies the `len` bytes at `src` in the memory of the instance to
+/// `dst`, from within a host function, like the `memory.copy`
+/// instruction. The ranges may overlap: the bytes are copied as if
+/// through an intermediate buffer, like with `memmove`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the source or the destination bytes are not all inside the memory,
+/// in which case the memory is left untouched. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// void shift(wasmer_instance_context_t *context, int32_t pointer, int32_t length) {
+///     // Moves the bytes one byte up, the ranges overlap.
+///     wasmer_instance_context_memory_copy(context, pointer + 1, pointer, length);
+/// }
+/// ```
+wasmer_result_t wasmer_instance_context_memory_copy(wasmer_instance_context_t *ctx,
+                                                    uint32_t dst,
+                                                    uint32_t src,
+                                                    uint32_t len);
 
-void wasmer_instance_enable_rkyv();
+/// Sets the `len` bytes at `offset` in the memory of the instance to
+/// `value`, from within a host function, like the `memory.fill`
+/// instruction.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the bytes are not all inside the memory, in which case the memory is
+/// left untouched. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_context_memory_fill(wasmer_instance_context_t *ctx,
+                                                    uint32_t offset,
+                                                    uint8_t value,
+                                                    uint32_t len);
 
-/// Gets all the exports of the given WebAssembly instance.
+/// Gets the current size in bytes of the `memory_idx`th memory of the
+/// instance, from within a host function. See
+/// `wasmer_instance_context_memory_base()`.
 ///
-/// This function stores a Rust vector of exports into `exports` as an
-/// opaque pointer of kind `wasmer_exports_t`.
+/// This function returns 0 if `ctx` is a null pointer, if `memory_idx`
+/// is not `0`, or if the instance has no memory.
+uint32_t wasmer_instance_context_memory_length(const wasmer_instance_context_t *ctx,
+                                               uint32_t memory_idx);
+
+/// Reads the `v128` value at `offset` in the memory of the instance,
+/// from within a host function, into `out`.
 ///
-/// As is, you can do anything with `exports` except using the
-/// companion functions, like `wasmer_exports_len()`,
-/// `wasmer_exports_get()` or `wasmer_export_kind()`. See the example below.
+/// The 16 bytes are copied as they are stored in the memory, that is
+/// in the little-endian order of wasm: the first byte of `out` is the
+/// lowest byte of lane 0, whatever the lane shape.
 ///
-/// **Warning**: The caller owns the object and should call
-/// `wasmer_exports_destroy()` to free it.
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the 16 bytes are not all inside the memory, in which case `out` is
+/// left untouched. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void read_vector(wasmer_instance_context_t *context, int32_t pointer) {
+///     uint8_t vector[16];
+///
+///     if (wasmer_instance_context_memory_read_v128(context, pointer, &vector) == WASMER_OK) {
+///         // Use `vector`.
+///     }
+/// }
+/// ```
+wasmer_result_t wasmer_instance_context_memory_read_v128(const wasmer_instance_context_t *ctx,
+                                                         uint32_t offset,
+                                                         uint8_t (*out)[16]);
+
+/// Writes the `v128` value of `value` at `offset` in the memory of the
+/// instance, from within a host function. The 16 bytes are copied as
+/// they are, see `wasmer_instance_context_memory_read_v128()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the 16 bytes are not all inside the memory, in which case the memory
+/// is left untouched. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_context_memory_write_v128(wasmer_instance_context_t *ctx,
+                                                          uint32_t offset,
+                                                          const uint8_t (*value)[16]);
+
+/// Reads the UTF-8 string of `len` bytes at `offset` in the memory of
+/// the instance, from within a host function, and stores a
+/// NUL-terminated copy of it in `out`.
+///
+/// The copy is owned by the caller, who must free it with
+/// `wasmer_string_destroy()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, in which case
+/// `out` is left untouched, with the error code:
+///
+/// * `WASMER_ERROR_CODE_INVALID_ARGUMENT` if the bytes are not all
+///   inside the memory, or if they contain a NUL byte,
+/// * `WASMER_ERROR_CODE_UTF8` if they are not valid UTF-8.
+///
+/// Use `wasmer_last_error_code`, `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get the error.
+///
+/// Example:
+///
+/// ```c
+/// void log_message(wasmer_instance_context_t *context, int32_t pointer, int32_t length) {
+///     char *message = NULL;
+///
+///     if (wasmer_instance_context_read_string(context, pointer, length, &message) != WASMER_OK) {
+///         wasmer_trap(context, "invalid message");
+///     }
+///
+///     printf("%s\n", message);
+///     wasmer_string_destroy(message);
+/// }
+/// ```
+wasmer_result_t wasmer_instance_context_read_string(const wasmer_instance_context_t *ctx,
+                                                    uint32_t offset,
+                                                    uint32_t len,
+                                                    char **out);
+
+/// Refunds `amount` gas to the running call, from within a host
+/// function, e.g. for a host operation freeing storage, which gives
+/// back gas to the rest of the call.
+///
+/// The refund is subtracted from the gas used by the instance, and is
+/// capped at the gas used by the running call so far: a call cannot get
+/// back more gas than it spent, so the gas used never drops below the
+/// gas used when the call started. Precisely, the gas used becomes
+/// `max(gas_used - amount, gas_used_at_call_start)`, and the refund is
+/// lost beyond that point. In particular, a refund never gives back the
+/// gas of the previous calls when they share their budget, see
+/// `wasmer_instance_call_shared_gas()`. A refund does not cancel an out
+/// of gas trap already raised by `wasmer_instance_context_use_gas()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the module of the instance was not compiled with metering, i.e. with
+/// the `metering` compilation option or by
+/// `wasmer_compile_with_gas_metering()`, however it was instantiated.
+/// Use `wasmer_last_error_length` and `wasmer_last_error_message` to get
+/// an error message.
+///
+/// Example:
+///
+/// ```c
+/// void clear_storage(wasmer_instance_context_t *context, int32_t key) {
+///     // Clear the storage at `key`.
+///
+///     wasmer_instance_context_refund_gas(context, CLEAR_STORAGE_REFUND);
+/// }
+/// ```
+wasmer_result_t wasmer_instance_context_refund_gas(wasmer_instance_context_t *ctx, uint64_t amount);
+
+/// Returns `len` bytes from `data` to the instance, from within a host
+/// function: the bytes are copied into a buffer allocated by the
+/// instance, whose offset in the memory is returned, so that the host
+/// function can return it to wasm.
+///
+/// The buffer is allocated by calling the allocator export of the
+/// module, see `wasmer_instance_set_wasm_allocator_export()`, which
+/// takes the length of the buffer as an `i32` and returns its offset as
+/// an `i32`.
+/// The call runs like any call of the instance: it is metered, and may
+/// trap. The instance owns the buffer once it is returned.
+///
+/// This function returns the offset of the buffer in the memory of the
+/// instance, or -1 upon failure, including when the module does not
+/// export an allocator with the expected signature, or when the
+/// returned buffer is out of the bounds of the memory. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// int32_t get_name(wasmer_instance_context_t *ctx) {
+///     const char *name = "wasmer";
+///     int64_t offset = wasmer_instance_context_return_bytes(ctx, (const uint8_t *) name, strlen(name));
+///     return offset < 0 ? 0 : (int32_t) offset;
+/// }
+/// ```
+int64_t wasmer_instance_context_return_bytes(wasmer_instance_context_t *ctx,
+                                             const uint8_t *data,
+                                             uint32_t len);
+
+/// Sets the runtime breakpoint value of the instance, from within a
+/// host function.
+///
+/// Any value other than `BREAKPOINT_VALUE_NO_BREAKPOINT` makes the
+/// running call trap as soon as the host function returns, if the
+/// instance was created with the `runtime_breakpoints` compilation
+/// option. The value can then be read with
+/// `wasmer_instance_get_runtime_breakpoint_value()`.
+///
+/// Values up to `BREAKPOINT_VALUE_RESERVED_MAX` are reserved for the
+/// runtime, e.g. `BREAKPOINT_VALUE_OUT_OF_GAS`; host code must use
+/// greater values for its own interruptions.
+///
+/// Example:
+///
+/// ```c
+/// #define BREAKPOINT_VALUE_ASYNC_CALL (BREAKPOINT_VALUE_RESERVED_MAX + 1)
+///
+/// void async_call(wasmer_instance_context_t *context) {
+///     // Register the asynchronous call.
+///
+///     wasmer_instance_context_set_breakpoint_value(context, BREAKPOINT_VALUE_ASYNC_CALL);
+/// }
+/// ```
+void wasmer_instance_context_set_breakpoint_value(wasmer_instance_context_t *ctx, uint64_t value);
+
+/// Appends a marker to the opcode trace of the instance of the current
+/// call, from within a host function, to show where the host function
+/// ran among the executed opcodes, e.g. when diagnosing the ordering of
+/// the calls between a module and its host.
+///
+/// The marker is read back by `wasmer_instance_get_opcode_trace()`, as
+/// an entry whose `opcode` is `WASMER_OPCODE_TRACE_MARKER` and whose
+/// `marker` is the given value. Markers are recorded even when the
+/// instance does not collect the opcodes, in which case the trace only
+/// holds the markers.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void transfer(wasmer_instance_context_t *ctx, int32_t amount) {
+///     wasmer_instance_context_trace_mark(ctx, TRANSFER_MARKER);
+///     // ...
+/// }
+/// ```
+wasmer_result_t wasmer_instance_context_trace_mark(wasmer_instance_context_t *ctx, uint64_t marker);
+
+/// Charges `amount` gas to the running call, from within a host
+/// function, e.g. for the native work done by the host function.
+///
+/// If the gas used exceeds the gas limit of the instance, the call
+/// traps with an out of gas runtime breakpoint as soon as the host
+/// function returns, the same way it would for wasm code. The gas used
+/// is then charged as configured for the instance, see
+/// `wasmer_instance_set_exclude_failing_charge_on_oog()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` if the gas limit is exceeded,
+/// in which case the host function should return without doing its
+/// work, or upon failure, including when the module of the instance was
+/// not compiled with metering, see `wasmer_instance_context_gas_left()`.
+/// Use `wasmer_last_error_length` and `wasmer_last_error_message` to get
+/// an error message.
+///
+/// Example:
+///
+/// ```c
+/// void hash(wasmer_instance_context_t *context, int32_t pointer, int32_t length) {
+///     if (wasmer_instance_context_use_gas(context, length * GAS_PER_HASHED_BYTE) != WASMER_OK) {
+///         return;
+///     }
+///
+///     // Hash the bytes.
+/// }
+/// ```
+wasmer_result_t wasmer_instance_context_use_gas(wasmer_instance_context_t *ctx, uint64_t amount);
+
+/// Frees memory for the given `wasmer_instance_t`.
+///
+/// Check the `wasmer_instantiate()` function to get a complete
+/// example.
+///
+/// If `instance` is a null pointer, this function does nothing.
+///
+/// Example:
+///
+/// ```c
+/// // Get an instance.
+/// wasmer_instance_t *instance = NULL;
+/// wasmer_instantiate(&instance, bytes, bytes_length, imports, 0);
+///
+/// // Destroy the instance.
+/// wasmer_instance_destroy(instance);
+/// ```
+void wasmer_instance_destroy(wasmer_instance_t *instance);
+
+void wasmer_instance_disable_rkyv();
+
+/// Writes a snapshot of the state of the instance, e.g. for the crash
+/// report of an unexpected execution failure, as a compact JSON object
+/// into `out`:
+///
+/// * `gas_used` and `gas_limit`: the gas used by the instance, and its
+///   limit, see `wasmer_instance_get_points_used()`,
+/// * `last_opcode_location`: see
+///   `wasmer_instance_get_last_opcode_location()`,
+/// * `runtime_breakpoint`: see
+///   `wasmer_instance_get_runtime_breakpoint_value()`,
+/// * `memory_size`: the size in bytes of the memory, or 0 without a
+///   memory,
+/// * `exports` and `imports`: the number of exports and imports of the
+///   module,
+/// * `last_trap`: the trap raised during the last call on the
+///   instance, see `wasmer_instance_last_trap_message_with_location()`,
+///   or `null`.
+///
+/// The fields may be extended, so the snapshot should be parsed as
+/// JSON rather than matched as text. It is not NUL-terminated.
+///
+/// The snapshot is copied into `out`, and its length is stored in
+/// `written`. If `out` is a null pointer, nothing is copied and the
+/// length of the snapshot is stored in `written`, which can be used to
+/// allocate a buffer of the right size.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including
+/// when `out_len` is smaller than the snapshot: then nothing is copied,
+/// the length of the snapshot is still stored in `written`, and the
+/// error code is `WASMER_ERROR_CODE_INVALID_ARGUMENT`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t diagnostics_length = 0;
+/// wasmer_instance_dump_diagnostics(instance, NULL, 0, &diagnostics_length);
+///
+/// uint8_t *diagnostics = malloc(diagnostics_length);
+/// wasmer_instance_dump_diagnostics(instance, diagnostics, diagnostics_length, &diagnostics_length);
+/// fprintf(stderr, "%.*s\n", diagnostics_length, diagnostics);
+/// ```
+wasmer_result_t wasmer_instance_dump_diagnostics(wasmer_instance_t *instance,
+                                                 uint8_t *out,
+                                                 uint32_t out_len,
+                                                 uint32_t *written);
+
+void wasmer_instance_enable_rkyv();
+
+/// Runs a contract in a single call: calls its exported function
+/// `name` with the given parameters and gas limit, like
+/// `wasmer_instance_call_with_gas()`, then reads the gas it consumed,
+/// its return data and, if it trapped, the kind of trap.
+///
+/// The function must return its return data like for
+/// `wasmer_instance_call_returning_bytes()`, as a `(ptr, len)` pair of
+/// `i32`s, or return nothing, in which case there is no return data.
+/// The function is not called if its signature does not match. Use
+/// `wasmer_instance_execute_packed()` for the functions returning an
+/// `i64` packing the pair.
+///
+/// Once the function is called:
+///
+/// * `gas_used_out` receives the gas consumed by the call, including
+///   when it failed, e.g. to charge a failed transaction,
+/// * `return_data_out` and `return_data_len_out` receive a copy of the
+///   return data upon success, or a null pointer and 0 when there is
+///   none; the copy is owned by the caller, and must be freed with
+///   `wasmer_buffer_destroy()`,
+/// * `result_code_out` receives the code of the trap if the call
+///   trapped, `WASMER_TRAP_OUT_OF_GAS` included, and is left untouched
+///   otherwise.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success;
+/// `wasmer_result_t::WASMER_ERROR` otherwise, including when the call
+/// trapped, or when the return data is out of the bounds of the memory;
+/// `wasmer_result_t::WASMER_INTERRUPTED` when the call was interrupted,
+/// and `wasmer_result_t::WASMER_STACK_OVERFLOW` when it went deeper
+/// than the `max_call_depth` compilation option. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint64_t gas_used = 0;
+/// uint8_t *return_data = NULL;
+/// uint32_t return_data_length = 0;
+/// wasmer_trap_code_t trap_code = WASMER_TRAP_UNKNOWN;
+///
+/// wasmer_result_t call_result = wasmer_instance_execute(
+///     instance, "transfer", params, 2, 1000000,
+///     &gas_used, &return_data, &return_data_length, &trap_code);
+///
+/// if (call_result == WASMER_OK) {
+///     send_result(return_data, return_data_length, gas_used);
+///     wasmer_buffer_destroy(return_data, return_data_length);
+/// }
+/// ```
+wasmer_result_t wasmer_instance_execute(wasmer_instance_t *instance,
+                                        const char *name,
+                                        const wasmer_value_t *params,
+                                        uint32_t params_len,
+                                        uint64_t gas_limit,
+                                        uint64_t *gas_used_out,
+                                        uint8_t **return_data_out,
+                                        uint32_t *return_data_len_out,
+                                        wasmer_trap_code_t *result_code_out);
+
+/// Runs a contract in a single call, like `wasmer_instance_execute()`,
+/// for a function returning its return data like for
+/// `wasmer_instance_call_returning_packed_bytes()`, as an `i64` packing
+/// the `(ptr, len)` pair, or returning nothing.
+///
+/// The function is not called if its signature does not match. The
+/// arguments and the results are the ones of `wasmer_instance_execute()`.
+wasmer_result_t wasmer_instance_execute_packed(wasmer_instance_t *instance,
+                                               const char *name,
+                                               const wasmer_value_t *params,
+                                               uint32_t params_len,
+                                               uint64_t gas_limit,
+                                               uint64_t *gas_used_out,
+                                               uint8_t **return_data_out,
+                                               uint32_t *return_data_len_out,
+                                               wasmer_trap_code_t *result_code_out);
+
+/// Gets all the exports of the given WebAssembly instance.
+///
+/// This function stores a Rust vector of exports into `exports` as an
+/// opaque pointer of kind `wasmer_exports_t`.
+///
+/// As is, you can do anything with `exports` except using the
+/// companion functions, like `wasmer_exports_len()`,
+/// `wasmer_exports_get()` or `wasmer_export_kind()`. See the example below.
+///
+/// **Warning**: The caller owns the object and should call
+/// `wasmer_exports_destroy()` to free it.
+///
+/// The exports are in the order they are declared in the module, so
+/// the index of an export given to `wasmer_exports_get()` is stable
+/// across calls, runs, and cache round-trips.
+///
+/// Example:
+///
+/// ```c
+/// // Get the exports.
+/// wasmer_exports_t *exports = NULL;
+/// wasmer_instance_exports(instance, &exports);
+///
+/// // Get the number of exports.
+/// int exports_length = wasmer_exports_len(exports);
+/// printf("Number of exports: %d\n", exports_length);
+///
+/// // Read the first export.
+/// wasmer_export_t *export = wasmer_exports_get(exports, 0);
+///
+/// // Get the kind of the export.
+/// wasmer_import_export_kind export_kind = wasmer_export_kind(export);
+///
+/// // Assert it is a function (why not).
+/// assert(export_kind == WASM_FUNCTION);
+///
+/// // Read the export name.
+/// wasmer_byte_array name_bytes = wasmer_export_name(export);
+///
+/// assert(name_bytes.bytes_len == sizeof("sum") - 1);
+/// assert(memcmp(name_bytes.bytes, "sum", sizeof("sum") - 1) == 0);
+///
+/// // Destroy the exports.
+/// wasmer_exports_destroy(exports);
+/// ```
+void wasmer_instance_exports(wasmer_instance_t *instance, wasmer_exports_t **exports);
+
+/// Gets the number of exports of the given WebAssembly instance.
+///
+/// Unlike `wasmer_instance_exports()`, this function does not
+/// allocate the exports; it is a cheap way to check whether an
+/// instance exports anything, or to size a buffer.
+///
+/// This function returns `0` if `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t exports_count = wasmer_instance_exports_count(instance);
+/// printf("Number of exports: %d\n", exports_count);
+/// ```
+uint32_t wasmer_instance_exports_count(wasmer_instance_t *instance);
+
+wasmer_result_t wasmer_instance_from_cache(wasmer_instance_t **instance,
+                                           uint8_t *cache_bytes,
+                                           uint32_t cache_len,
+                                           const wasmer_compilation_options_t *options);
+
+/// Reads the gas used by the last call of the instance, broken down by
+/// category of opcodes, into `breakdown`, e.g. to check whether the
+/// opcode costs are balanced for a representative workload.
+///
+/// Only the instances of modules compiled with the `gas_breakdown`
+/// compilation option, along with `metering`, count the gas per
+/// category: the counting adds to the cost of each charge of the
+/// metering. The breakdown of other instances is all zeros.
+///
+/// The breakdown is reset by each call, by the start function, and by
+/// `wasmer_instance_reset()`. It includes the gas charged for the block
+/// which ran out of gas, if any, and the gas used by the host functions
+/// with `wasmer_instance_context_use_gas()` is not part of any category,
+/// so its total may differ from `wasmer_instance_get_points_used()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_gas_breakdown_t breakdown;
+///
+/// if (wasmer_instance_get_gas_breakdown(instance, &breakdown) == WASMER_OK) {
+///     printf("Calls: %llu\n", breakdown.call);
+/// }
+/// ```
+wasmer_result_t wasmer_instance_get_gas_breakdown(const wasmer_instance_t *instance,
+                                                  wasmer_gas_breakdown_t *breakdown);
+
+/// Gets the number of instructions executed by the last call of the
+/// instance, counted against the `hard_instruction_limit` compilation
+/// option independently of the gas.
+///
+/// When the limit is exceeded, the call traps whatever its gas limit,
+/// and the runtime breakpoint value of the instance is set to
+/// `BREAKPOINT_VALUE_INSTRUCTION_LIMIT` instead of
+/// `BREAKPOINT_VALUE_OUT_OF_GAS`, see
+/// `wasmer_instance_get_runtime_breakpoint_value()`, and the error code
+/// of the call is `WASMER_ERROR_CODE_INSTRUCTION_LIMIT`. The count is reset
+/// by each call, and by the start function.
+///
+/// This function returns 0 if `instance` is a null pointer, or if the
+/// instance was created without a hard instruction limit.
+uint64_t wasmer_instance_get_instructions_used(const wasmer_instance_t *instance);
+
+/// Reads the byte offset, in the original wasm binary, of the last
+/// opcode executed by the last call to `wasmer_instance_call()` on the
+/// instance, and stores it in `loc_out`. It tells where a trapping
+/// call stopped.
+///
+/// The location is only tracked when the instance was created with the
+/// `opcode_trace` or the `collect_opcode_trace` compilation option;
+/// otherwise it is 0.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_get_last_opcode_location(wasmer_instance_t *instance,
+                                                         uint64_t *loc_out);
+
+/// Reads the opcodes executed by the instance during its last call,
+/// e.g. with `wasmer_instance_call()`, and the markers its host
+/// functions pushed meanwhile. The calls on other instances, including
+/// the nested ones made by host functions, do not affect it.
+///
+/// The opcodes are only collected when the instance was created with
+/// the `collect_opcode_trace` compilation option; the markers of the
+/// host functions are always collected.
+///
+/// The `trace_function_start` and `trace_function_end` compilation
+/// options restrict the collected opcodes to the functions whose index,
+/// counting the imported functions, is in
+/// `trace_function_start..trace_function_end`. A `trace_function_end`
+/// of 0 means no upper bound.
+///
+/// The trace holds at most `WASMER_OPCODE_TRACE_MAX_ENTRIES` entries;
+/// the later ones are dropped.
+///
+/// At most `out_len` entries are copied into `out`, and the number of
+/// copied entries is stored in `written`. If `out` is a null pointer,
+/// nothing is copied and the full length of the trace is stored in
+/// `written`, which can be used to allocate a buffer of the right size.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t trace_length = 0;
+/// wasmer_instance_get_opcode_trace(instance, NULL, 0, &trace_length);
+///
+/// wasmer_opcode_trace_entry_t *trace = malloc(sizeof(wasmer_opcode_trace_entry_t) * trace_length);
+/// wasmer_instance_get_opcode_trace(instance, trace, trace_length, &trace_length);
+/// ```
+wasmer_result_t wasmer_instance_get_opcode_trace(wasmer_instance_t *instance,
+                                                 wasmer_opcode_trace_entry_t *out,
+                                                 uint32_t out_len,
+                                                 uint32_t *written);
+
+/// Reads the peak size, in pages, reached by the memory of the given
+/// WebAssembly instance during the last call to `wasmer_instance_call()`,
+/// or during the call in progress when called from a host function.
+///
+/// The high-water mark is reset at the start of each call to
+/// `wasmer_instance_call()`, and by `wasmer_instance_reset()`. Since a
+/// memory never shrinks during a call, the peak is the size of the
+/// memory at the end of the call; it is 0 if the instance has no
+/// memory.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_instance_call(instance, "run", params, 0, results, 0);
+///
+/// uint32_t peak_pages = 0;
+/// wasmer_instance_get_peak_memory_pages(instance, &peak_pages);
+/// ```
+wasmer_result_t wasmer_instance_get_peak_memory_pages(wasmer_instance_t *instance, uint32_t *pages);
+
+/// Reads the gas used by the last call to `wasmer_instance_call()` on
+/// the instance, per function where it was spent, e.g. to find the
+/// hotspots of a contract.
+///
+/// Only the instances of modules compiled with the `per_function_gas`
+/// compilation option, along with `metering`, attribute the gas to the
+/// functions: each charge of the metering then calls into the runtime,
+/// which makes the calls much slower. Other instances record nothing.
+///
+/// The functions are sorted by index, and the functions which used no
+/// gas are left out. Like the breakdown of
+/// `wasmer_instance_get_gas_breakdown()`, the gas includes the block
+/// which ran out of gas, if any, and excludes the gas used by the host
+/// functions.
+///
+/// At most `out_len` entries are copied into `out`. If `out` is a null
+/// pointer, nothing is copied, which can be used to allocate a buffer
+/// of the right size.
+///
+/// This function returns the number of functions which used gas,
+/// whatever the number of copied entries, or -1 if `instance` is a
+/// null pointer.
+///
+/// Example:
+///
+/// ```c
+/// int functions_len = wasmer_instance_get_per_function_gas(instance, NULL, 0);
+/// wasmer_function_gas_t *functions = malloc(sizeof(wasmer_function_gas_t) * functions_len);
+/// wasmer_instance_get_per_function_gas(instance, functions, functions_len);
+/// ```
+int wasmer_instance_get_per_function_gas(const wasmer_instance_t *instance,
+                                         wasmer_function_gas_t *out,
+                                         uint32_t out_len);
+
+/// Reads the gas limit of the instance into `limit_out`, i.e. the
+/// `gas_limit` compilation option it was instantiated with, or the last
+/// limit set with `wasmer_instance_set_points_limit()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_instance_get_points_limit(const wasmer_instance_t *instance,
+                                                 uint64_t *limit_out);
+
+uint64_t wasmer_instance_get_points_used(wasmer_instance_t *instance);
+
+uint64_t wasmer_instance_get_runtime_breakpoint_value(wasmer_instance_t *instance);
+
+/// Restores the values of the mutable globals of the instance from a
+/// snapshot written by `wasmer_instance_globals_snapshot()`.
+///
+/// The snapshot must come from an instance of the same module: if its
+/// layout does not match the globals of the instance, no global is
+/// modified and an error is returned.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_globals_restore(wasmer_instance_t *instance,
+                                                const uint8_t *snapshot,
+                                                uint32_t snapshot_len);
+
+/// Captures the values of all the mutable globals defined by the
+/// instance into `out`, as an opaque snapshot to be given to
+/// `wasmer_instance_globals_restore()` later. `out_len` must be at
+/// least `wasmer_instance_globals_snapshot_length()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t snapshot_length = wasmer_instance_globals_snapshot_length(instance);
+/// uint8_t *snapshot = malloc(snapshot_length);
+/// wasmer_instance_globals_snapshot(instance, snapshot, snapshot_length);
+///
+/// // Run the instance.
+///
+/// wasmer_instance_globals_restore(instance, snapshot, snapshot_length);
+/// ```
+wasmer_result_t wasmer_instance_globals_snapshot(const wasmer_instance_t *instance,
+                                                 uint8_t *out,
+                                                 uint32_t out_len);
+
+/// Returns the length in bytes of the snapshot written by
+/// `wasmer_instance_globals_snapshot()` for the given instance.
+///
+/// Returns 0 if `instance` is a null pointer.
+uint32_t wasmer_instance_globals_snapshot_length(const wasmer_instance_t *instance);
+
+/// Verifies whether the specified function name is imported by the given instance.
+///
+/// This function returns `false` if `instance` or `name` is a null
+/// pointer. It also returns `false`, with a `WASMER_ERROR_CODE_UTF8`
+/// last error, if `name` is not a valid UTF-8 string.
+bool wasmer_instance_is_function_imported(wasmer_instance_t *instance, const char *name);
+
+/// Returns the error code given to `wasmer_trap_with_code()` by the
+/// host function which aborted the last call to
+/// `wasmer_instance_call()` on the instance.
+///
+/// This function returns 0 if the trap was forced with `wasmer_trap()`,
+/// if the last call was not aborted by the host, see
+/// `wasmer_instance_last_trap_was_host_forced()`, or if `instance` is
+/// a null pointer.
+uint32_t wasmer_instance_last_host_trap_code(const wasmer_instance_t *instance);
+
+/// Reads the information about the trap raised during the last call
+/// to `wasmer_instance_call()` on the instance.
+///
+/// Returns `wasmer_result_t::WASMER_OK` and fills `trap_info` if the
+/// last call trapped.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` if the last call did not
+/// trap, or upon failure. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_trap_info_t trap_info;
+///
+/// if (wasmer_instance_last_trap_info(instance, &trap_info) == WASMER_OK && trap_info.has_location) {
+///     printf("Trap %d at function %d, instruction %d\n", trap_info.code, trap_info.func_index, trap_info.operator_index);
+/// }
+/// ```
+wasmer_result_t wasmer_instance_last_trap_info(wasmer_instance_t *instance,
+                                               wasmer_trap_info_t *trap_info);
+
+/// Formats the trap raised during the last call to
+/// `wasmer_instance_call()` on the instance, with its location,
+/// e.g. `unreachable at func 3 instruction 12`, into the provided
+/// buffer `out` up to the given `out_len`. See
+/// `wasmer_instance_last_trap_info()` for the meaning of the function
+/// index and the instruction index. When the location of the trap is
+/// unknown, the message is the description of the trap alone.
+///
+/// The function returns the length of the message in bytes, trailing
+/// null character included, 0 if the last call did not trap, and -1 if
+/// `instance` or `out` is a null pointer, if `out_len` is not positive,
+/// or if `out` is too small to hold the message.
+///
+/// Example:
+///
+/// ```c
+/// char trap_message[128];
+///
+/// if (wasmer_instance_call(instance, "transfer", params, 2, results, 1) != WASMER_OK
+///     && wasmer_instance_last_trap_message_with_location(instance, trap_message, sizeof(trap_message)) > 0) {
+///     printf("Trap: %s\n", trap_message);
+/// }
+/// ```
+int wasmer_instance_last_trap_message_with_location(wasmer_instance_t *instance,
+                                                    char *out,
+                                                    int out_len);
+
+/// Returns whether the last call to `wasmer_instance_call()` on the
+/// instance was aborted by a host function, with `wasmer_trap()` or
+/// `wasmer_trap_with_code()`, rather than by a trap of the WebAssembly
+/// code itself, e.g. an `unreachable` opcode.
+///
+/// This function returns 1 if the trap was forced by the host, and 0
+/// otherwise, including when the last call did not trap, or if
+/// `instance` is a null pointer.
+///
+/// Example:
+///
+/// ```c
+/// if (wasmer_instance_call(instance, "transfer", params, 2, results, 1) != WASMER_OK) {
+///     if (wasmer_instance_last_trap_was_host_forced(instance)) {
+///         uint32_t reason = wasmer_instance_last_host_trap_code(instance);
+///         // The host rejected the call.
+///     } else {
+///         // The contract failed.
+///     }
+/// }
+/// ```
+int wasmer_instance_last_trap_was_host_forced(const wasmer_instance_t *instance);
+
+/// Gets the data of a memory of the given WebAssembly instance, by
+/// instance handle rather than by instance context, e.g. to read the
+/// memory of the caller instance from a host function of a callee
+/// instance, when orchestrating several instances.
+///
+/// `data` receives a pointer to the first byte of the memory at index
+/// `memory_idx`, and `data_length` its length in bytes; any access must
+/// stay within these bounds. The pointer is valid until the memory
+/// grows, or until the instance is destroyed.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint8_t *caller_data = NULL;
+/// uint32_t caller_data_length = 0;
+/// wasmer_instance_memory_data(caller_instance, 0, &caller_data, &caller_data_length);
+///
+/// if (offset <= caller_data_length && length <= caller_data_length - offset) {
+///     memcpy(arguments, caller_data + offset, length);
+/// }
+/// ```
+wasmer_result_t wasmer_instance_memory_data(const wasmer_instance_t *instance,
+                                            uint32_t memory_idx,
+                                            uint8_t **data,
+                                            uint32_t *data_length);
+
+/// Compares the contents of a memory of the given WebAssembly instance
+/// with an expected image, byte for byte.
+///
+/// The memory at index `memory_idx` is equal to the image only if its
+/// current size is exactly `expected_len` bytes. `expected` may be a
+/// null pointer if `expected_len` is 0.
+///
+/// This function returns 1 if the memory is equal to the image, 0 if it
+/// is not, and -1 upon failure, including when the instance has no
+/// memory at `memory_idx`. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// if (wasmer_instance_memory_equals(instance, 0, expected_image, expected_image_length) != 1) {
+///     // The execution did not produce the expected state.
+/// }
+/// ```
+int wasmer_instance_memory_equals(const wasmer_instance_t *instance,
+                                  uint32_t memory_idx,
+                                  const uint8_t *expected,
+                                  uint32_t expected_len);
+
+/// Copies the contents of a memory of the given WebAssembly instance
+/// into a new buffer, e.g. to snapshot the state of the instance before
+/// a call, and restore it with `wasmer_instance_memory_import_buffer()`
+/// if the call must be rolled back.
+///
+/// `buf_out` receives the buffer, and `len_out` its length, which is
+/// the current size in bytes of the memory at index `memory_idx`. The
+/// buffer is owned by the caller, and must be freed with
+/// `wasmer_buffer_destroy()`, or with the `free` function registered
+/// with `wasmer_set_allocator()` if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// const uint8_t *snapshot = NULL;
+/// uint32_t snapshot_length = 0;
+/// wasmer_instance_memory_export_buffer(instance, 0, &snapshot, &snapshot_length);
+///
+/// if (wasmer_instance_call(instance, "main", params, 0, results, 0) != WASMER_OK) {
+///     wasmer_instance_memory_import_buffer(instance, 0, snapshot, snapshot_length);
+/// }
+///
+/// wasmer_buffer_destroy((uint8_t *) snapshot, snapshot_length);
+/// ```
+wasmer_result_t wasmer_instance_memory_export_buffer(const wasmer_instance_t *instance,
+                                                     uint32_t memory_idx,
+                                                     const uint8_t **buf_out,
+                                                     uint32_t *len_out);
+
+/// Computes a deterministic hash of the contents of a memory of the
+/// given WebAssembly instance, e.g. to check that two independent
+/// executions left their instances in the same state.
+///
+/// The hash covers the current size of the memory at index
+/// `memory_idx` exactly, so that two memories with the same contents
+/// but different sizes have different hashes. It does not depend on
+/// the backend, nor on the platform.
+///
+/// The first `WASMER_MEMORY_HASH_LENGTH` bytes of `out` receive the
+/// hash; `out_len` must be at least that.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_instance_memory_hash(const wasmer_instance_t *instance,
+                                            uint32_t memory_idx,
+                                            uint8_t *out,
+                                            uint32_t out_len);
+
+/// Overwrites the contents of a memory of the given WebAssembly
+/// instance with the `len` bytes of `buf`, e.g. a snapshot taken with
+/// `wasmer_instance_memory_export_buffer()`, and resizes the memory to
+/// the size of the snapshot: the bytes beyond `len` up to the next page
+/// are zeroed. `buf` may be a null pointer if `len` is 0.
+///
+/// The memory at index `memory_idx` grows to hold the bytes if needed,
+/// as if by `memory.grow`: the growth counts towards the
+/// `max_memory_grow` option and is subject to the
+/// `max_memory_grow_delta` option, to the maximum size the memory
+/// declares and to the callback set by
+/// `wasmer_instance_set_memory_grow_callback()`.
+///
+/// The memory shrinks back if it is larger than the snapshot, but
+/// never below its minimum size, like `wasmer_instance_reset()` does;
+/// a memory which declares a maximum size cannot shrink.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`, or when the memory can
+/// neither grow nor shrink to the size of the snapshot; the memory is
+/// left unchanged then. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_memory_import_buffer(wasmer_instance_t *instance,
+                                                     uint32_t memory_idx,
+                                                     const uint8_t *buf,
+                                                     uint32_t len);
+
+/// Touches the first `pages` wasm pages of a memory of the given
+/// WebAssembly instance, so that the operating system commits them
+/// now rather than on their first access, e.g. right after the
+/// instantiation, to spare a latency-sensitive call the page faults.
+///
+/// The contents of the memory are left unchanged: each touched byte is
+/// written back with its own value. The pages beyond the current size
+/// of the memory at index `memory_idx` are ignored, so that `pages` can
+/// be larger than the memory; the memory is never grown.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the instance has no memory at `memory_idx`. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_instance_prefault_memory(instance, 0, 16);
+/// ```
+wasmer_result_t wasmer_instance_prefault_memory(wasmer_instance_t *instance,
+                                                uint32_t memory_idx,
+                                                uint32_t pages);
+
+/// Requests the running call of the instance to stop, e.g. when a
+/// deadline is reached. Unlike the other functions of the C API, this
+/// one may be called from any thread, including while another thread
+/// runs `wasmer_instance_call()` on the instance.
+///
+/// The call traps at its next call or loop iteration, if the instance
+/// was created with the `runtime_breakpoints` compilation option, or
+/// by `wasmer_compile_with_gas_metering()`, and returns
+/// `wasmer_result_t::WASMER_INTERRUPTED`. The runtime breakpoint value
+/// of the instance is then `BREAKPOINT_VALUE_INTERRUPTED`.
+///
+/// If no call is running, the next call of the instance is
+/// interrupted. The request is kept apart from the runtime breakpoint
+/// value: it stays pending until a call is interrupted, even if the
+/// value is reset meanwhile, e.g. by
+/// `wasmer_instance_set_runtime_breakpoint_value()` or
+/// `wasmer_instance_call_shared_gas()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// // In the watchdog thread.
+/// if (deadline_reached()) {
+///     wasmer_instance_request_interrupt(instance);
+/// }
+///
+/// // In the thread running the instance.
+/// if (wasmer_instance_call(instance, "main", params, 0, results, 0) == WASMER_INTERRUPTED) {
+///     // The deadline was reached.
+/// }
+/// ```
+wasmer_result_t wasmer_instance_request_interrupt(wasmer_instance_t *instance);
+
+/// Resets a WebAssembly instance to its state right after
+/// instantiation: the memories are shrunk to their minimum size,
+/// zeroed, and re-initialized with the data segments, the mutable
+/// globals are re-initialized, and the start function, if any, is
+/// called again. The gas used, the runtime breakpoint and the memory
+/// grow count are reset before the start function runs, so that the
+/// gas used afterwards is the gas of the start function, as right
+/// after instantiation.
+///
+/// Contents written to the memories after instantiation, e.g. the
+/// initial memory image given to `wasmer_instantiate_with_options()`,
+/// are not restored.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success, and
+/// `wasmer_result_t::WASMER_OUT_OF_GAS` if the start function runs out
+/// of gas.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon any other failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_reset(wasmer_instance_t *instance);
+
+/// Clears the last opcode location of the instance, see
+/// `wasmer_instance_get_last_opcode_location()`, and its opcode trace,
+/// see `wasmer_instance_get_opcode_trace()`.
+///
+/// Every call entry point of the instance, e.g.
+/// `wasmer_instance_call()`, `wasmer_instance_call_packed()`,
+/// `wasmer_call_batch_run()` or `wasmer_export_func_call()`, already
+/// does it before calling; this is for the hosts driving the calls
+/// themselves, e.g. to tell apart the opcodes run by the start function
+/// from the ones of a later call.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_reset_opcode_trace(wasmer_instance_t *instance);
+
+/// Resets a WebAssembly instance to a clean slate: like
+/// `wasmer_instance_reset()`, except that the start function is not
+/// called, so the memories only contain the data segments and zeroes.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_reset_zeroed(wasmer_instance_t *instance);
+
+/// Runs the start function of a WebAssembly instance created with the
+/// `skip_start` compilation option set, e.g. once the module has been
+/// inspected, or once its imports are ready.
+///
+/// The start function is metered like any call, against the gas limit
+/// and the gas already used by the instance; the gas it consumes is
+/// stored in `gas_used`, unless it is a null pointer, including when it
+/// runs out of gas. Nothing is run if the module has no start function.
+///
+/// The start function is run again at each call, it is up to the caller
+/// to run it only once.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_OUT_OF_GAS`, with the error message
+/// `out of gas`, if the start function runs out of gas, and
+/// `wasmer_result_t::WASMER_ERROR` upon any other failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// uint64_t start_gas_used = 0;
+///
+/// if (wasmer_instance_run_start(instance, &start_gas_used) != WASMER_OK) {
+///     // The instance is unusable.
+/// }
+/// ```
+wasmer_result_t wasmer_instance_run_start(wasmer_instance_t *instance, uint64_t *gas_used);
+
+/// Sets the function called on the entry of each basic block executed
+/// by the given WebAssembly instance, e.g. to collect the edge coverage
+/// of a fuzzer.
+///
+/// Only the instances of modules compiled with the `block_coverage`
+/// compilation option report their blocks. The blocks start at the
+/// beginning of each function, and after each control flow operator or
+/// call which the execution may continue after. Their ids are numbered
+/// from 0 in the order of the module, whatever the other compilation
+/// options, so they are stable for a given module. The blocks are
+/// reported during `wasmer_instance_call()` and the functions built on
+/// it.
+///
+/// `user_data` is given back to the callback as is; it is owned by the
+/// caller and must outlive the instance, or the callback.
+///
+/// A null `callback` removes the current callback, if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// void record_block(void *user_data, uint32_t block_id) {
+///     coverage_t *coverage = (coverage_t *) user_data;
+///
+///     coverage_record_edge(coverage, coverage->previous_block, block_id);
+///     coverage->previous_block = block_id;
+/// }
+///
+/// wasmer_instance_set_block_coverage_callback(instance, record_block, &coverage);
+/// ```
+wasmer_result_t wasmer_instance_set_block_coverage_callback(wasmer_instance_t *instance,
+                                                            wasmer_block_coverage_callback_t callback,
+                                                            void *user_data);
+
+/// Sets whether the gas used of the instance, when a call runs out of
+/// gas, leaves out the cost of the failing block, i.e. of the operators
+/// charged together with the failed gas check.
+///
+/// By default, the cost of the failing block is charged, so the gas
+/// used exceeds the gas limit. With `exclude` set, the gas used is the
+/// cost of the blocks which ran, which does not exceed the limit. Either
+/// way, the reported gas used is deterministic: it only depends on the
+/// code, the opcode costs and the gas limit. The `charge_full_on_oog`
+/// compilation option takes precedence, and charges the gas limit.
+///
+/// The same applies to `wasmer_instance_context_use_gas()`, whose
+/// `amount` is the failing charge.
+///
+/// Leaving the failing block out does not give the gas back: a call of
+/// a sequence sharing its budget which runs out of gas still ends the
+/// sequence, see `wasmer_instance_call_shared_gas()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_instance_set_exclude_failing_charge_on_oog(wasmer_instance_t *instance,
+                                                                  bool exclude);
+
+/// Sets the function called before each call of an imported function
+/// by the given WebAssembly instance, e.g. to count the host functions
+/// used by a contract.
+///
+/// The callback only observes the calls: it receives neither the
+/// arguments of the imported function nor the instance, and cannot
+/// change them. It is called for the direct `call`s of imported
+/// functions made during `wasmer_instance_call()` and the functions
+/// built on it; imported functions reached through a table with
+/// `call_indirect` are not reported. The name given to the callback is
+/// only valid during the callback.
+///
+/// Only the instances of modules compiled with the
+/// `trace_import_calls` compilation option report their calls.
+///
+/// `user_data` is given back to the callback as is; it is owned by the
+/// caller and must outlive the instance, or the callback.
+///
+/// A null `callback` removes the current callback, if any.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
 ///
 /// Example:
 ///
 /// ```c
-/// // Get the exports.
-/// wasmer_exports_t *exports = NULL;
-/// wasmer_instance_exports(instance, &exports);
+/// void count_import_call(void *user_data, const char *import_name) {
+///     histogram_t *histogram = (histogram_t *) user_data;
 ///
-/// // Get the number of exports.
-/// int exports_length = wasmer_exports_len(exports);
-/// printf("Number of exports: %d\n", exports_length);
+///     histogram_increment(histogram, import_name);
+/// }
 ///
-/// // Read the first export.
-/// wasmer_export_t *export = wasmer_exports_get(exports, 0);
+/// wasmer_instance_set_import_call_callback(instance, count_import_call, &histogram);
+/// ```
+wasmer_result_t wasmer_instance_set_import_call_callback(wasmer_instance_t *instance,
+                                                         wasmer_import_call_callback_t callback,
+                                                         void *user_data);
+
+/// Sets the function called before each `memory.grow` performed by the
+/// given WebAssembly instance, to observe or veto the growth of its
+/// memory. When the callback denies a growth, `memory.grow` returns -1
+/// to the WebAssembly code, as for any failed growth.
 ///
-/// // Get the kind of the export.
-/// wasmer_import_export_kind export_kind = wasmer_export_kind(export);
+/// `user_data` is given back to the callback as is; it is owned by the
+/// caller and must outlive the instance, or the callback.
 ///
-/// // Assert it is a function (why not).
-/// assert(export_kind == WASM_FUNCTION);
+/// A null `callback` removes the current callback, if any.
 ///
-/// // Read the export name.
-/// wasmer_byte_array name_bytes = wasmer_export_name(export);
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
 ///
-/// assert(name_bytes.bytes_len == sizeof("sum") - 1);
-/// assert(memcmp(name_bytes.bytes, "sum", sizeof("sum") - 1) == 0);
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
 ///
-/// // Destroy the exports.
-/// wasmer_exports_destroy(exports);
+/// Example:
+///
+/// ```c
+/// int check_balance(void *user_data, uint32_t requested_pages) {
+///     account_t *account = (account_t *) user_data;
+///
+///     return account->balance < requested_pages * PAGE_PRICE;
+/// }
+///
+/// wasmer_instance_set_memory_grow_callback(instance, check_balance, &account);
 /// ```
-void wasmer_instance_exports(wasmer_instance_t *instance, wasmer_exports_t **exports);
-
-wasmer_result_t wasmer_instance_from_cache(wasmer_instance_t **instance,
-                                           uint8_t *cache_bytes,
-                                           uint32_t cache_len,
-                                           const wasmer_compilation_options_t *options);
+wasmer_result_t wasmer_instance_set_memory_grow_callback(wasmer_instance_t *instance,
+                                                         wasmer_memory_grow_callback_t callback,
+                                                         void *user_data);
 
-uint64_t wasmer_instance_get_points_used(wasmer_instance_t *instance);
+void wasmer_instance_set_points_limit(wasmer_instance_t *instance, uint64_t limit);
 
-uint64_t wasmer_instance_get_runtime_breakpoint_value(wasmer_instance_t *instance);
+void wasmer_instance_set_points_used(wasmer_instance_t *instance, uint64_t new_gas);
 
-/// Verifies whether the specified function name is imported by the given instance.
-bool wasmer_instance_is_function_imported(wasmer_instance_t *instance, const char *name);
+void wasmer_instance_set_runtime_breakpoint_value(wasmer_instance_t *instance, uint64_t value);
 
-/// Reset an WebAssembly instance, cleaning memories and globals
-wasmer_result_t wasmer_instance_reset(wasmer_instance_t *instance);
+/// Sets the name of the function which the module of the instance
+/// exports to allocate the buffers returned to it by the host
+/// functions, see `wasmer_instance_context_return_bytes()`. The name is
+/// `allocate` by default, and only applies to this instance.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_instance_set_wasm_allocator_export(wasmer_instance_t *instance,
+                                                          const char *name);
 
-void wasmer_instance_set_points_limit(wasmer_instance_t *instance, uint64_t limit);
+/// Reads the index of the function stored at `elem_index` in the table
+/// at `table_index` of an instance into `func_index`. Function indices
+/// include the imported functions.
+///
+/// If the element is null, or refers to a function which does not
+/// belong to the instance, `func_index` is set to `UINT32_MAX`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, e.g. if the
+/// table or the element index is out of range. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_instance_table_get(wasmer_instance_t *instance,
+                                          uint32_t table_index,
+                                          uint32_t elem_index,
+                                          uint32_t *func_index);
 
-void wasmer_instance_set_points_used(wasmer_instance_t *instance, uint64_t new_gas);
+/// Grows the table at `table_index` of an instance, imported tables
+/// included, by `delta` elements, and stores its previous length in
+/// `prev_out`, unless it is a null pointer.
+///
+/// The table cannot grow beyond its declared maximum, nor beyond the
+/// `max_table_elements` compilation option of the instance. The same
+/// limit is checked before `table.grow` on the backends compiling it,
+/// which the singlepass backend does not.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, e.g. if there
+/// is no such table, or if it cannot grow by `delta` elements, in which
+/// case it is left untouched. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_table_grow(wasmer_instance_t *instance,
+                                           uint32_t table_index,
+                                           uint32_t delta,
+                                           uint32_t *prev_out);
 
-void wasmer_instance_set_runtime_breakpoint_value(wasmer_instance_t *instance, uint64_t value);
+/// Reads the current length of the table at `table_index` of an
+/// instance, imported tables included, into `len`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, e.g. if there
+/// is no such table. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_instance_table_len(wasmer_instance_t *instance,
+                                          uint32_t table_index,
+                                          uint32_t *len);
 
 /// Creates a new WebAssembly instance from the given bytes and imports.
 ///
@@ -914,6 +3556,8 @@ void wasmer_instance_set_runtime_breakpoint_value(wasmer_instance_t *instance, u
 /// The caller is responsible to free the instance with
 /// `wasmer_instance_destroy()`.
 ///
+/// `imports` may be a null pointer when `imports_len` is 0.
+///
 /// Example:
 ///
 /// ```c
@@ -950,11 +3594,204 @@ wasmer_result_t wasmer_instantiate(wasmer_instance_t **instance,
                                    wasmer_import_t *imports,
                                    int imports_len);
 
+/// Compiles and instantiates WebAssembly bytes with the given
+/// compilation options, resolving the imports of the module against
+/// the given import object instead of the cached one.
+///
+/// The import object is only borrowed during the instantiation: it can
+/// be shared by any number of instantiations, and each instance keeps
+/// its own reference to the imports, so the import object can be freed
+/// with `wasmer_import_object_destroy` while the instances created from
+/// it are still in use. It is still owned by the caller. See also
+/// `wasmer_import_object_register()`.
+///
+/// The gas semantics are the ones of
+/// `wasmer_instantiate_with_options_and_gas()`.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_instantiate_with_import_object(wasmer_instance_t **instance,
+                                                      uint8_t *wasm_bytes,
+                                                      uint32_t wasm_bytes_len,
+                                                      const wasmer_import_object_t *import_object,
+                                                      const wasmer_compilation_options_t *options);
+
+/// Compiles and instantiates WebAssembly bytes with the given
+/// compilation options, see `wasmer_instantiate_with_options_and_gas()`.
 wasmer_result_t wasmer_instantiate_with_options(wasmer_instance_t **instance,
                                                 uint8_t *wasm_bytes,
                                                 uint32_t wasm_bytes_len,
                                                 const wasmer_compilation_options_t *options);
 
+/// Compiles and instantiates WebAssembly bytes with the given
+/// compilation options, and stores the gas consumed by the
+/// instantiation in `gas_used`, unless it is a null pointer.
+///
+/// The imports of the module are resolved against the ones cached on
+/// the current thread by `wasmer_import_object_cache_from_imports()`.
+///
+/// The start function of the module, if any, runs with the
+/// `gas_limit` of the options, and its gas is charged like the gas of
+/// any call. The gas charged for the initial memory image of the
+/// options is added to it, and the total is the gas used by the
+/// instance once it is returned.
+///
+/// The start function is not run if the `skip_start` option is set;
+/// run it later with `wasmer_instance_run_start()`.
+///
+/// If the start function runs out of gas, no instance is created,
+/// `gas_used` still receives the gas consumed, and this function
+/// returns `wasmer_result_t::WASMER_OUT_OF_GAS`, with the error message
+/// `out of gas`.
+///
+/// If the initial memory image does not fit in the memory, or in the
+/// gas left by the start function, no instance is created either, and
+/// this function returns `wasmer_result_t::WASMER_ERROR` or
+/// `wasmer_result_t::WASMER_OUT_OF_GAS` respectively.
+///
+/// If the compilation takes longer than the `compile_timeout_ms`
+/// option, 0 meaning no timeout, it is aborted and no instance is
+/// created. The partially compiled code is freed, and this function
+/// returns `wasmer_result_t::WASMER_TIMEOUT`.
+///
+/// The `already_validated` option skips the validation of the bytes
+/// before their compilation, for the bytes already validated, e.g. with
+/// `wasmer_validate()`, and loaded again from a trusted store. It is
+/// unsafe for untrusted bytes, and must never be set for them.
+///
+/// The `deterministic_traps` option records the kind of each
+/// instruction which may trap, so that the traps of the instance report
+/// the same code on every backend, see `wasmer_trap_code_t`. It makes
+/// the compilation slower and the module bigger.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+wasmer_result_t wasmer_instantiate_with_options_and_gas(wasmer_instance_t **instance,
+                                                        uint8_t *wasm_bytes,
+                                                        uint32_t wasm_bytes_len,
+                                                        const wasmer_compilation_options_t *options,
+                                                        uint64_t *gas_used);
+
+/// Compiles and instantiates WebAssembly bytes like
+/// `wasmer_instantiate_with_options_and_gas()`, and stores statistics
+/// about both phases in `stats`, unless it is a null pointer.
+///
+/// The compilation statistics are stored once the module is compiled,
+/// even if the instantiation fails then; `instantiate_nanos` is only
+/// set upon success. Nothing is measured when `stats` is a null
+/// pointer.
+///
+/// This function returns `wasmer_result_t::WASMER_OK` upon success,
+/// `wasmer_result_t::WASMER_ERROR` otherwise. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_compile_stats_t stats;
+/// wasmer_result_t result = wasmer_instantiate_with_options_and_stats(&instance, bytes, bytes_len, options, NULL, &stats);
+///
+/// if (result == WASMER_OK) {
+///     printf("Compiled %u functions in %llu ns\n", stats.function_count, stats.compile_nanos);
+/// }
+/// ```
+wasmer_result_t wasmer_instantiate_with_options_and_stats(wasmer_instance_t **instance,
+                                                          uint8_t *wasm_bytes,
+                                                          uint32_t wasm_bytes_len,
+                                                          const wasmer_compilation_options_t *options,
+                                                          uint64_t *gas_used,
+                                                          wasmer_compile_stats_t *stats);
+
+/// Gets the `index`th violation of the compilation options by the last
+/// module which failed to compile, in the order of the opcodes in the
+/// module, see `wasmer_last_compile_violations_len()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// `index` is out of bounds. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_last_compile_violations_get(uint32_t index, wasmer_compile_violation_t *out);
+
+/// Gets the number of opcodes of the last module which failed to
+/// compile with options, e.g. with
+/// `wasmer_instantiate_with_options()`, that are not allowed by these
+/// options.
+///
+/// Only the opcodes of the disabled proposals, such as SIMD, and the
+/// denied opcodes are reported; other errors, such as a shared memory
+/// without the threads proposal, are only reported by the error
+/// message of the compilation. The violations are cleared by each
+/// compilation with options on the current thread, and are only
+/// collected when it fails.
+///
+/// Example:
+///
+/// ```c
+/// if (wasmer_instantiate_with_options(&instance, bytes, bytes_len, options) != WASMER_OK) {
+///     uint32_t violations_len = wasmer_last_compile_violations_len();
+///
+///     for (uint32_t i = 0; i < violations_len; i++) {
+///         wasmer_compile_violation_t violation;
+///         wasmer_last_compile_violations_get(i, &violation);
+///         printf("%s at offset %#x\n", wasmer_opcode_name(violation.opcode), violation.offset);
+///     }
+/// }
+/// ```
+uint32_t wasmer_last_compile_violations_len();
+
+/// Gets the message of a cause of the last error into the provided
+/// buffer `buffer` up to the given `length`, to see why an operation
+/// failed beyond the message of the error itself, e.g. the compile
+/// error behind a failed instantiation.
+///
+/// The causes form a chain: the cause at index 0 is the one of the last
+/// error, the cause at index 1 is the one of the cause at index 0, and
+/// so on. Walk the chain by increasing `index` until this function
+/// returns 0.
+///
+/// The last error is left untouched: call this function before
+/// `wasmer_last_error_message()`, which clears it.
+///
+/// The function returns the length of the message in bytes, trailing
+/// null character included, 0 if there is no cause at `index`, and -1
+/// if `buffer` is a null pointer, or is too small to hold the message.
+///
+/// Example:
+///
+/// ```c
+/// char cause[256];
+///
+/// for (uint32_t index = 0; wasmer_last_error_cause(index, cause, sizeof(cause)) > 0; index++) {
+///     printf("Caused by: `%s`\n", cause);
+/// }
+/// ```
+int wasmer_last_error_cause(uint32_t index, char *buffer, int length);
+
+/// Gets the category of the last error if any, to handle errors
+/// without parsing their message.
+///
+/// The last error is left untouched: call this function before
+/// `wasmer_last_error_message()`, which clears it.
+///
+/// Returns `WASMER_ERROR_CODE_NONE` if there is no last error.
+///
+/// Example:
+///
+/// ```c
+/// if (wasmer_instance_call(instance, "sum", params, 2, results, 1) != WASMER_OK) {
+///     if (wasmer_last_error_code() == WASMER_ERROR_CODE_OUT_OF_GAS) {
+///         // Charge the whole gas limit.
+///     }
+/// }
+/// ```
+wasmer_error_code_t wasmer_last_error_code();
+
 /// Gets the length in bytes of the last error if any.
 ///
 /// This can be used to dynamically allocate a buffer with the correct number of
@@ -1116,18 +3953,150 @@ uint32_t wasmer_memory_length(const wasmer_memory_t *memory);
 /// ```
 wasmer_result_t wasmer_memory_new(wasmer_memory_t **memory, wasmer_limits_t limits);
 
-/// Deserialize the given serialized module.
+/// Returns the size in bytes of the machine code generated by the
+/// compiler backend for the given module.
+///
+/// This is the native code footprint of the module, not the length
+/// of its wasm bytes. It is 0 if `module` is a null pointer, or if
+/// the backend does not expose its generated code.
+uintptr_t wasmer_module_code_size(const wasmer_module_t *module);
+
+/// Computes a deterministic hash of the given module, to be used as a
+/// key when sharing its serialized artifact.
+///
+/// The hash covers the wasm bytes the module was compiled from, and
+/// the effective compilation options: the backend, the WebAssembly
+/// features, the version of the runtime, the compilation options which
+/// change the compiled code, e.g. of `wasmer_compile_file_with_options()`,
+/// and the opcode costs of the metered modules, e.g. of
+/// `wasmer_compile_with_gas_metering()`. Compiling the same bytes with
+/// the same options and costs by the same runtime gives the same hash,
+/// also after a serialization round-trip.
+///
+/// The first `WASMER_MODULE_CONTENT_HASH_LENGTH` bytes of `out` receive
+/// the hash; `out_len` must be at least that.
 ///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
 ///
 /// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
 /// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_module_content_hash(const wasmer_module_t *module,
+                                           uint8_t *out,
+                                           uint32_t out_len);
+
+/// Deserialize the given serialized module.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the module was serialized by another version of the runtime. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
 wasmer_result_t wasmer_module_deserialize(wasmer_module_t **module,
                                           const wasmer_serialized_module_t *serialized_module);
 
 /// Frees memory for the given Module
 void wasmer_module_destroy(wasmer_module_t *module);
 
+/// Reads the name of a function of the given module, as given by the
+/// `name` custom section, e.g. to symbolicate the function indices of
+/// trap information and opcode traces. The name is not NUL-terminated.
+///
+/// `func_index` is in the function index space of the module, imported
+/// functions first.
+///
+/// At most `out_len` bytes of the name are copied into `out`, and the
+/// number of copied bytes is stored in `written`. If `out` is a null
+/// pointer, nothing is copied and the full length of the name is stored
+/// in `written`, which can be used to allocate a buffer of the right
+/// size.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the function has no name, e.g. because the module has no `name`
+/// section. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t name_length = 0;
+/// if (wasmer_module_function_name(module, func_index, NULL, 0, &name_length) == WASMER_OK) {
+///     uint8_t *name = malloc(name_length);
+///     wasmer_module_function_name(module, func_index, name, name_length, &name_length);
+/// }
+/// ```
+wasmer_result_t wasmer_module_function_name(const wasmer_module_t *module,
+                                            uint32_t func_index,
+                                            uint8_t *out,
+                                            uint32_t out_len,
+                                            uint32_t *written);
+
+/// Reads the number of operators in the body of a function of the
+/// given module into `count`, e.g. to bound statically the gas used
+/// by a function without loops. These are the operators seen by the
+/// compiler middlewares, the final `end` included.
+///
+/// `func_index` is in the function index space of the module, imported
+/// functions first.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// `func_index` is out of range, or refers to an imported function,
+/// which has no body. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_module_function_opcode_count(const wasmer_module_t *module,
+                                                    uint32_t func_index,
+                                                    uint32_t *count);
+
+/// Evaluates the initializer of a global of the given module, without
+/// instantiating it, e.g. to read the constants of a module by static
+/// analysis.
+///
+/// `global_index` is in the global index space of the module, imported
+/// globals first.
+///
+/// If the initial value of the global is a constant, it is stored in
+/// `value_out` and `is_resolvable_out` receives `true`. If it depends on
+/// an import, i.e. if the global is imported or initialized with the
+/// value of an imported global, `is_resolvable_out` receives `false` and
+/// `value_out` is left untouched.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success, including when the
+/// value is not resolvable.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, including when
+/// the module has no global at `global_index`, or when the global is of
+/// a type unsupported by the C API. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_value_t value;
+/// bool is_resolvable = false;
+/// wasmer_module_global_init_value(module, global_index, &value, &is_resolvable);
+///
+/// if (is_resolvable && value.tag == WASM_I32) {
+///     printf("Initial value: %d\n", value.value.I32);
+/// }
+/// ```
+wasmer_result_t wasmer_module_global_init_value(const wasmer_module_t *module,
+                                                uint32_t global_index,
+                                                wasmer_value_t *value_out,
+                                                bool *is_resolvable_out);
+
+/// Returns true if the given module has a `start` function, which
+/// runs implicitly when the module is instantiated.
+///
+/// Returns false if `module` is a null pointer.
+///
+/// Set `reject_start_function` in the compilation options to refuse
+/// such modules in `wasmer_instantiate_with_options()`.
+bool wasmer_module_has_start(const wasmer_module_t *module);
+
 /// Given:
 /// * A prepared `wasmer` import-object
 /// * A compiled wasmer module
@@ -1148,8 +4117,73 @@ wasmer_result_t wasmer_module_instantiate(const wasmer_module_t *module,
                                           wasmer_import_t *imports,
                                           int imports_len);
 
+/// Creates a new instance from the given module and imports, like
+/// `wasmer_module_instantiate()`, with the given options.
+///
+/// The options which apply to the compilation, such as `metering`,
+/// are ignored: they are the ones the module was compiled with, e.g.
+/// by `wasmer_compile_with_gas_metering()`. All the others apply as in
+/// `wasmer_instantiate_with_options_and_gas()`, including the gas
+/// limit of the start function, and `gas_used`, unless it is a null
+/// pointer, receives the gas consumed by the instantiation.
+///
+/// A null `options` instantiates the module exactly like
+/// `wasmer_module_instantiate()`, and leaves `gas_used` untouched.
+///
+/// `imports` may be a null pointer if `imports_len` is 0.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+wasmer_result_t wasmer_module_instantiate_with_options(const wasmer_module_t *module,
+                                                       wasmer_instance_t **instance,
+                                                       wasmer_import_t *imports,
+                                                       uint32_t imports_len,
+                                                       const wasmer_compilation_options_t *options,
+                                                       uint64_t *gas_used);
+
+/// Reads the memory limits declared by the given module, imported
+/// memory included, to know its initial allocation before
+/// instantiating it.
+///
+/// `min_pages` receives the initial number of pages. `max_pages`
+/// receives the maximum number of pages, and `has_max` whether the
+/// module declares a maximum at all; `max_pages` is 0 when it does
+/// not. If the module has no memory, `min_pages` and `max_pages` are
+/// 0 and `has_max` is false.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure. Use `wasmer_last_error_length`
+/// and `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// uint32_t min_pages, max_pages;
+/// bool has_max;
+/// wasmer_module_memory_limits(module, &min_pages, &max_pages, &has_max);
+///
+/// if (min_pages > MAX_INITIAL_PAGES || !has_max) {
+///     // Reject the module.
+/// }
+/// ```
+wasmer_result_t wasmer_module_memory_limits(const wasmer_module_t *module,
+                                            uint32_t *min_pages,
+                                            uint32_t *max_pages,
+                                            bool *has_max);
+
 /// Serialize the given Module.
 ///
+/// The serialized module is stamped with the version of the runtime,
+/// see `wasmer_version()`, and with the version of its serialization
+/// format, and only this version can deserialize it.
+///
+/// With the singlepass backend, the same wasm bytes compiled with the
+/// same options by the same version serialize to the same bytes, on
+/// any machine.
+///
 /// The caller owns the object and should call `wasmer_serialized_module_destroy` to free it.
 ///
 /// Returns `wasmer_result_t::WASMER_OK` upon success.
@@ -1159,6 +4193,25 @@ wasmer_result_t wasmer_module_instantiate(const wasmer_module_t *module,
 wasmer_result_t wasmer_module_serialize(wasmer_serialized_module_t **serialized_module,
                                         const wasmer_module_t *module);
 
+/// Returns the mnemonic of the opcode with the given code, as found in
+/// the `opcode` field of `wasmer_opcode_trace_entry_t`, e.g. `i32.add`.
+///
+/// The returned string is NUL-terminated and statically allocated; it
+/// must not be freed.
+///
+/// Returns a null pointer if the code is unknown.
+///
+/// Example:
+///
+/// ```c
+/// const char *name = wasmer_opcode_name(trace[0].opcode);
+///
+/// if (name != NULL) {
+///     printf("%s at offset %#x\n", name, trace[0].offset);
+/// }
+/// ```
+const char *wasmer_opcode_name(uint16_t code);
+
 /// Get bytes of the serialized module.
 wasmer_byte_array wasmer_serialized_module_bytes(const wasmer_serialized_module_t *serialized_module);
 
@@ -1177,10 +4230,101 @@ wasmer_result_t wasmer_serialized_module_from_bytes(wasmer_serialized_module_t *
                                                     const uint8_t *serialized_module_bytes,
                                                     uint32_t serialized_module_bytes_length);
 
+/// Registers the `malloc` and `free` functions used to allocate and
+/// free the buffers returned to C, e.g. by `wasmer_instance_cache()`.
+/// Such buffers can then be freed directly by the host with its own
+/// `free` function.
+///
+/// When both functions are null, the default Rust allocator is used
+/// again. Buffers allocated with the Rust allocator must be freed with
+/// `wasmer_buffer_destroy()`.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` if only one of the functions
+/// is null. Use `wasmer_last_error_length` and
+/// `wasmer_last_error_message` to get an error message.
+///
+/// Example:
+///
+/// ```c
+/// wasmer_set_allocator(malloc, free);
+///
+/// const uint8_t *cache_bytes;
+/// uint32_t cache_length;
+/// wasmer_instance_cache(instance, &cache_bytes, &cache_length);
+///
+/// // Do something with the cache bytes.
+///
+/// free((void *) cache_bytes);
+/// ```
+wasmer_result_t wasmer_set_allocator(wasmer_malloc_t malloc_fn, wasmer_free_t free_fn);
+
 void wasmer_set_opcode_costs(const uint32_t *opcode_costs_pointer);
 
+/// Installs or removes the signal handler of the runtime for SIGSEGV.
+///
+/// When enabled, an out-of-bounds or illegal memory access from wasm
+/// code becomes a trap, and the running call returns
+/// `wasmer_result_t::WASMER_ERROR` instead of crashing the process. The
+/// other trap signals are handled as well.
+///
+/// When disabled, SIGSEGV gets its default disposition back, like after
+/// `wasmer_set_sigsegv_passthrough()`, so that the host can handle it.
+/// A handler the host installed before is not restored: until the host
+/// installs one again, an out-of-bounds access from wasm code terminates
+/// the process instead of trapping.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` if a signal handler cannot be
+/// changed. Use `wasmer_last_error_length` and `wasmer_last_error_message`
+/// to get an error message.
+wasmer_result_t wasmer_set_signal_handler_enabled(bool enabled);
+
 void wasmer_set_sigsegv_passthrough();
 
+/// Frees a string returned by `wasmer_instance_context_read_string()`.
+///
+/// If `string` is a null pointer, this function does nothing.
+void wasmer_string_destroy(char *string);
+
+/// Removes the custom sections, e.g. the `name`, `producers` or DWARF
+/// sections, from the given wasm bytes, to get the smallest module with
+/// the same behavior.
+///
+/// The stripped bytes are owned by the caller, and must be freed with
+/// `wasmer_buffer_destroy()`, or with the `free` function registered
+/// with `wasmer_set_allocator()` if any.
+///
+/// **Warning**: Without the `name` section, functions can no longer be
+/// symbolicated by name, and the offsets of an opcode trace refer to the
+/// stripped bytes, not to the original ones.
+///
+/// Returns `wasmer_result_t::WASMER_OK` upon success.
+///
+/// Returns `wasmer_result_t::WASMER_ERROR` upon failure, e.g. if the
+/// sections of the wasm bytes are malformed. Use
+/// `wasmer_last_error_length` and `wasmer_last_error_message` to get an
+/// error message.
+///
+/// Example:
+///
+/// ```c
+/// const uint8_t *stripped_bytes;
+/// uint32_t stripped_length;
+///
+/// if (wasmer_strip_custom_sections(bytes, bytes_length, &stripped_bytes, &stripped_length) == WASMER_OK) {
+///     // Store the stripped bytes.
+///
+///     wasmer_buffer_destroy((uint8_t *) stripped_bytes, stripped_length);
+/// }
+/// ```
+wasmer_result_t wasmer_strip_custom_sections(const uint8_t *wasm_bytes,
+                                             uint32_t wasm_bytes_len,
+                                             const uint8_t **stripped_bytes,
+                                             uint32_t *stripped_bytes_len);
+
 /// Frees memory for the given Table
 void wasmer_table_destroy(wasmer_table_t *table);
 
@@ -1257,16 +4401,51 @@ void *wasmer_trampoline_get_context();
 /// function itself since the error is read outside the host function
 /// with `wasmer_last_error_message`.
 ///
+/// The trap is recorded as forced by the host, with the error code 0,
+/// see `wasmer_instance_last_trap_was_host_forced()` and
+/// `wasmer_trap_with_code()`.
+///
+/// This function returns `wasmer_result_t::WASMER_ERROR` if `ctx` or
+/// `error_message` are null.
+///
+/// This function never returns otherwise.
+wasmer_result_t wasmer_trap(wasmer_instance_context_t *ctx, const char *error_message);
+
+/// Stops the execution of a host function like `wasmer_trap()`, and
+/// records `code` as the error code of the trap, so that the caller of
+/// the instance can tell why the host aborted the call, e.g. an access
+/// control rejection, from a trap of the WebAssembly code itself. See
+/// `wasmer_instance_last_host_trap_code()`.
+///
+/// The meaning of the codes is up to the embedder.
+///
 /// This function returns `wasmer_result_t::WASMER_ERROR` if `ctx` or
 /// `error_message` are null.
 ///
 /// This function never returns otherwise.
-wasmer_result_t wasmer_trap(const wasmer_instance_context_t *ctx, const char *error_message);
+///
+/// Example:
+///
+/// ```c
+/// void storage_store(wasmer_instance_context_t *ctx, int32_t key, int32_t value) {
+///     if (!is_writable(key)) {
+///         wasmer_trap_with_code(ctx, ACCESS_DENIED, "storage key is read-only");
+///     }
+///     // …
+/// }
+/// ```
+wasmer_result_t wasmer_trap_with_code(wasmer_instance_context_t *ctx,
+                                      uint32_t code,
+                                      const char *error_message);
 
 /// Validates a sequence of bytes hoping it represents a valid WebAssembly module.
 ///
 /// The function returns true if the bytes are valid, false otherwise.
 ///
+/// The bytes validated once, then kept in a trusted store, can be
+/// compiled again without being validated, see the `already_validated`
+/// option of `wasmer_compile_with_options()`.
+///
 /// Example:
 ///
 /// ```c
@@ -1278,6 +4457,18 @@ wasmer_result_t wasmer_trap(const wasmer_instance_context_t *ctx, const char *er
 /// ```
 bool wasmer_validate(const uint8_t *wasm_bytes, uint32_t wasm_bytes_len);
 
+/// Returns the version of the runtime, e.g. `0.15.0`, as a
+/// NUL-terminated string.
+///
+/// The string is static: it must not be freed.
+///
+/// Example:
+///
+/// ```c
+/// printf("wasmer %s\n", wasmer_version());
+/// ```
+const char *wasmer_version();
+
 #if defined(WASMER_WASI_ENABLED)
 /// Convenience function that creates a WASI import object with no arguments,
 /// environment variables, preopened files, or mapped directories.
@@ -1,4 +1,10 @@
-use wasmer_runtime_core::{compile_with, imports};
+use wasmer_runtime_core::{
+    backend::{CompilerConfig, ExceptionCode},
+    compile_with, compile_with_config,
+    error::{CallError, RuntimeError},
+    imports,
+    types::Value,
+};
 use wasmer_runtime_core_tests::{get_compiler, wat2wasm};
 
 #[test]
@@ -19,3 +25,89 @@ fn exception_handling_works() {
         assert!(instance.call("throw_trap", &[]).is_err());
     }
 }
+
+#[test]
+fn precise_trap_codes() {
+    const MODULE: &str = r#"
+(module
+  (type $void (func))
+  (type $i32_to_void (func (param i32)))
+  (memory 1)
+  (table 1 funcref)
+  (elem (i32.const 0) $nop)
+  (func $nop (type $void))
+  (func (export "unreachable")
+    unreachable)
+  (func (export "divide_by_zero") (param i32) (result i32)
+    i32.const 1
+    local.get 0
+    i32.div_u)
+  (func (export "load_out_of_bounds") (result i32)
+    i32.const 65536
+    i32.load)
+  (func (export "call_out_of_bounds")
+    i32.const 1
+    call_indirect (type $void))
+  (func (export "call_bad_signature")
+    i32.const 0
+    i32.const 0
+    call_indirect (type $i32_to_void)))
+"#;
+
+    let wasm_binary = wat2wasm(MODULE.as_bytes()).expect("WAST not valid or malformed");
+    let compiler_config = CompilerConfig {
+        precise_trap_codes: true,
+        ..Default::default()
+    };
+    let module = compile_with_config(&wasm_binary, &get_compiler(), compiler_config).unwrap();
+    let instance = module.instantiate(&imports! {}).unwrap();
+
+    let exception_code = |name: &str, params: &[Value]| match instance.call(name, params) {
+        Err(CallError::Runtime(RuntimeError(data))) => {
+            data.downcast_ref::<ExceptionCode>().cloned()
+        }
+        result => panic!("unexpected result of {}: {:?}", name, result),
+    };
+
+    assert_eq!(
+        exception_code("unreachable", &[]),
+        Some(ExceptionCode::Unreachable)
+    );
+    assert_eq!(
+        exception_code("divide_by_zero", &[Value::I32(0)]),
+        Some(ExceptionCode::IllegalArithmetic)
+    );
+    assert_eq!(
+        exception_code("load_out_of_bounds", &[]),
+        Some(ExceptionCode::MemoryOutOfBounds)
+    );
+    assert_eq!(
+        exception_code("call_out_of_bounds", &[]),
+        Some(ExceptionCode::CallIndirectOOB)
+    );
+    assert_eq!(
+        exception_code("call_bad_signature", &[]),
+        Some(ExceptionCode::IncorrectCallIndirectSignature)
+    );
+}
+
+#[test]
+fn unreachable_trap_code_without_precise_trap_codes() {
+    const MODULE: &str = r#"
+(module
+  (func (export "unreachable")
+    unreachable))
+"#;
+
+    let wasm_binary = wat2wasm(MODULE.as_bytes()).expect("WAST not valid or malformed");
+    let module = compile_with(&wasm_binary, &get_compiler()).unwrap();
+    let instance = module.instantiate(&imports! {}).unwrap();
+
+    match instance.call("unreachable", &[]) {
+        Err(CallError::Runtime(RuntimeError(data))) => assert_eq!(
+            data.downcast_ref::<ExceptionCode>().cloned(),
+            Some(ExceptionCode::Unreachable)
+        ),
+        result => panic!("unexpected result of unreachable: {:?}", result),
+    }
+}
@@ -16,13 +16,9 @@ use crate::{
 use std::fmt;
 use std::{any::Any, ptr::NonNull};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use rkyv::{
-    Archive,
-    Serialize as RkyvSerialize,
-    Deserialize as RkyvDeserialize,
-};
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 
 pub mod sys {
     pub use crate::sys::*;
@@ -96,6 +92,14 @@ pub struct Features {
     ///
     /// [threads-prop]: https://github.com/webassembly/threads
     pub threads: bool,
+    /// Whether support for the [bulk memory proposal][bulk-memory-prop] is enabled.
+    ///
+    /// [bulk-memory-prop]: https://github.com/webassembly/bulk-memory-operations
+    pub bulk_memory: bool,
+    /// Whether support for the [reference types proposal][reference-types-prop] is enabled.
+    ///
+    /// [reference-types-prop]: https://github.com/webassembly/reference-types
+    pub reference_types: bool,
 }
 
 /// Use this to point to a compiler config struct provided by the backend.
@@ -125,6 +129,8 @@ pub struct CompilerConfig {
     /// Usually it's adequate to use hardware memory protection mechanisms such as `mprotect` on Unix to
     /// prevent stack overflow. But for low-level environments, e.g. the kernel, faults are generally
     /// not expected and relying on hardware memory protection would add too much complexity.
+    ///
+    /// The checks of the singlepass backend trap with `ExceptionCode::StackOverflow`.
     pub enforce_stack_check: bool,
 
     /// Whether to enable state tracking. Necessary for managed mode.
@@ -140,6 +146,32 @@ pub struct CompilerConfig {
 
     pub features: Features,
 
+    /// Whether to skip the validation pass which runs before the compilation, for wasm bytes
+    /// which were already validated, e.g. with `validate`.
+    ///
+    /// This only saves the time of validating the same bytes twice: it must never be set for
+    /// untrusted bytes, which could otherwise reach the code generators without having been
+    /// fully validated.
+    pub skip_pre_validation: bool,
+
+    /// Whether to record the `ExceptionCode` of each instruction which may trap, so that the
+    /// traps report the kind of the trap on every backend.
+    ///
+    /// The backends which rely on the signals raised by the trapping instructions, e.g.
+    /// singlepass, only record them when this is set: without it, their traps other than
+    /// `Unreachable`, `IllegalArithmetic` and `StackOverflow` have no exception code. The other
+    /// backends always report them.
+    pub precise_trap_codes: bool,
+
+    /// Hash of the options of the embedder which change the compiled code, e.g. the
+    /// configuration of its middlewares, recorded in `ModuleInfo::compile_options_hash`. Zero
+    /// when the module is compiled without such options.
+    pub compile_options_hash: [u8; 32],
+
+    /// Whether the embedder compiles the module with a metering middleware, recorded in
+    /// `ModuleInfo::metered`, so that its instances know whether their calls are metered.
+    pub metered: bool,
+
     // Target info. Presently only supported by LLVM.
     pub triple: Option<String>,
     pub cpu_name: Option<String>,
@@ -160,10 +192,14 @@ impl CompilerConfig {
 }
 
 /// An exception table for a `RunnableModule`.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[derive(
+    Clone, Debug, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize,
+)]
 pub struct ExceptionTable {
     /// Mappings from offsets in generated machine code to the corresponding exception code.
-    pub offset_to_code: HashMap<usize, ExceptionCode>,
+    ///
+    /// Ordered by offset, so that the table always serializes to the same bytes.
+    pub offset_to_code: BTreeMap<usize, ExceptionCode>,
 }
 
 impl ExceptionTable {
@@ -173,7 +209,20 @@ impl ExceptionTable {
 }
 
 /// The code of an exception.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+)]
 pub enum ExceptionCode {
     /// An `unreachable` opcode was executed.
     Unreachable = 0,
@@ -187,6 +236,8 @@ pub enum ExceptionCode {
     IllegalArithmetic = 4,
     /// Misaligned atomic access trap.
     MisalignedAtomicAccess = 5,
+    /// The native stack was exhausted, see `CompilerConfig::enforce_stack_check`.
+    StackOverflow = 6,
 }
 
 impl fmt::Display for ExceptionCode {
@@ -203,6 +254,7 @@ impl fmt::Display for ExceptionCode {
                 ExceptionCode::CallIndirectOOB => "`call_indirect` out-of-bounds",
                 ExceptionCode::IllegalArithmetic => "illegal arithmetic operation",
                 ExceptionCode::MisalignedAtomicAccess => "misaligned atomic access",
+                ExceptionCode::StackOverflow => "stack overflow",
             }
         )
     }
@@ -18,6 +18,16 @@ use crate::{
 };
 use std::{fmt::Debug, ptr::NonNull, slice};
 
+#[cfg(all(unix, target_arch = "x86_64"))]
+use crate::{
+    error::ImportPanic,
+    import::UnresolvedFunctionHandler,
+    trampoline_x64::{CallContext, TrampolineBufferBuilder},
+    types::FuncSig,
+};
+#[cfg(all(unix, target_arch = "x86_64"))]
+use std::{any::Any, panic};
+
 /// Size of the array for internal instance usage
 pub const INTERNALS_SIZE: usize = 256;
 
@@ -604,6 +614,10 @@ pub struct ImportBacking {
     pub(crate) vm_memories: BoxedMap<ImportedMemoryIndex, *mut vm::LocalMemory>,
     pub(crate) vm_tables: BoxedMap<ImportedTableIndex, *mut vm::LocalTable>,
     pub(crate) vm_globals: BoxedMap<ImportedGlobalIndex, *mut vm::LocalGlobal>,
+
+    /// The placeholders of the function imports which are not provided, see
+    /// `ImportObject::unresolved_function_handler`.
+    pub(crate) unresolved_functions: Vec<UnresolvedFunction>,
 }
 
 // manually implemented because ImportBacking contains raw pointers directly
@@ -619,11 +633,12 @@ impl ImportBacking {
         let mut failed = false;
         let mut link_errors = vec![];
 
-        let vm_functions = import_functions(module, imports, vmctx).unwrap_or_else(|le| {
-            failed = true;
-            link_errors.extend(le);
-            Map::new().into_boxed_map()
-        });
+        let (vm_functions, unresolved_functions) = import_functions(module, imports, vmctx)
+            .unwrap_or_else(|le| {
+                failed = true;
+                link_errors.extend(le);
+                (Map::new().into_boxed_map(), Vec::new())
+            });
 
         let (memories, vm_memories) = import_memories(module, imports).unwrap_or_else(|le| {
             failed = true;
@@ -655,6 +670,8 @@ impl ImportBacking {
                 vm_memories,
                 vm_tables,
                 vm_globals,
+
+                unresolved_functions,
             })
         }
     }
@@ -678,13 +695,106 @@ impl Drop for ImportBacking {
     }
 }
 
+/// The placeholder of a function import which is not provided. It calls the
+/// `ImportObject::unresolved_function_handler`, then traps.
+#[cfg(all(unix, target_arch = "x86_64"))]
+pub(crate) struct UnresolvedFunction {
+    trampoline: NonNull<u8>,
+    context: Box<UnresolvedFunctionContext>,
+}
+
+#[cfg(all(unix, target_arch = "x86_64"))]
+struct UnresolvedFunctionContext {
+    namespace: String,
+    name: String,
+    handler: UnresolvedFunctionHandler,
+}
+
+#[cfg(all(unix, target_arch = "x86_64"))]
+impl UnresolvedFunction {
+    fn new(
+        namespace: &str,
+        name: &str,
+        handler: UnresolvedFunctionHandler,
+        signature: &FuncSig,
+    ) -> Self {
+        unsafe extern "C" fn call_unresolved_function(
+            context: *const CallContext,
+            args: *const u64,
+        ) -> u64 {
+            let context = &*(context as *const UnresolvedFunctionContext);
+            let vmctx = *args.offset(0) as *mut vm::Ctx;
+
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                (context.handler)(&mut *vmctx, &context.namespace, &context.name)
+            }));
+            let err: Box<dyn Any + Send> = match result {
+                Ok(()) => Box::new(format!(
+                    "called unimplemented host function {}.{}",
+                    context.namespace, context.name
+                )),
+                Err(payload) => Box::new(ImportPanic::from_payload(payload)),
+            };
+
+            (&*(*vmctx).module).runnable_module.do_early_trap(err)
+        }
+
+        let context = Box::new(UnresolvedFunctionContext {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            handler,
+        });
+
+        let mut builder = TrampolineBufferBuilder::new();
+        builder.add_callinfo_trampoline(
+            call_unresolved_function,
+            &*context as *const UnresolvedFunctionContext as *const CallContext,
+            (signature.params().len() + 1) as u32, // +vmctx
+        );
+        let trampoline = builder
+            .insert_global()
+            .expect("cannot bump-allocate global trampoline memory");
+
+        Self {
+            trampoline,
+            context,
+        }
+    }
+}
+
+#[cfg(all(unix, target_arch = "x86_64"))]
+impl Debug for UnresolvedFunction {
+    fn fmt(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(
+            formatter,
+            "UnresolvedFunction({}.{})",
+            self.context.namespace, self.context.name
+        )
+    }
+}
+
+#[cfg(all(unix, target_arch = "x86_64"))]
+impl Drop for UnresolvedFunction {
+    fn drop(&mut self) {
+        unsafe { TrampolineBufferBuilder::remove_global(self.trampoline) }
+    }
+}
+
+/// Function imports which are not provided cannot be resolved on other platforms.
+#[cfg(not(all(unix, target_arch = "x86_64")))]
+pub(crate) type UnresolvedFunction = ();
+
 fn import_functions(
     module: &ModuleInner,
     imports: &ImportObject,
     vmctx: *mut vm::Ctx,
-) -> LinkResult<BoxedMap<ImportedFuncIndex, vm::ImportedFunc>> {
+) -> LinkResult<(
+    BoxedMap<ImportedFuncIndex, vm::ImportedFunc>,
+    Vec<UnresolvedFunction>,
+)> {
     let mut link_errors = vec![];
     let mut functions = Map::with_capacity(module.info.imported_functions.len());
+    let mut unresolved_functions = Vec::new();
     for (
         index,
         ImportName {
@@ -758,8 +868,26 @@ fn import_functions(
                     found: export_type_name,
                 });
             }
-            None => {
-                if imports.allow_missing_functions {
+            None => match &imports.unresolved_function_handler {
+                #[cfg(all(unix, target_arch = "x86_64"))]
+                Some(handler) => {
+                    let unresolved_function =
+                        UnresolvedFunction::new(namespace, name, handler.clone(), expected_sig);
+
+                    functions.push(vm::ImportedFunc {
+                        func: unresolved_function.trampoline.cast::<vm::Func>().as_ptr(),
+                        func_ctx: NonNull::new(Box::into_raw(Box::new(vm::FuncCtx {
+                            //                      ^^^^^^^^ `vm::FuncCtx` is purposely leaked.
+                            //                               It is dropped by the specific `Drop`
+                            //                               implementation of `ImportBacking`.
+                            vmctx: NonNull::new(vmctx).expect("`vmctx` must not be null."),
+                            func_env: None,
+                        })))
+                        .unwrap(),
+                    });
+                    unresolved_functions.push(unresolved_function);
+                }
+                _ if imports.allow_missing_functions => {
                     let always_trap = Func::new(always_trap);
 
                     functions.push(vm::ImportedFunc {
@@ -773,20 +901,21 @@ fn import_functions(
                         })))
                         .unwrap(),
                     });
-                } else {
+                }
+                _ => {
                     link_errors.push(LinkError::ImportNotFound {
                         namespace: namespace.to_string(),
                         name: name.to_string(),
                     });
                 }
-            }
+            },
         }
     }
 
     if !link_errors.is_empty() {
         Err(link_errors)
     } else {
-        Ok(functions.into_boxed_map())
+        Ok((functions.into_boxed_map(), unresolved_functions))
     }
 }
 
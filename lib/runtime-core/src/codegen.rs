@@ -257,9 +257,9 @@ pub fn validating_parser_config(features: &Features) -> wasmparser::ValidatingPa
     wasmparser::ValidatingParserConfig {
         operator_config: wasmparser::OperatorValidatorConfig {
             enable_threads: features.threads,
-            enable_reference_types: false,
+            enable_reference_types: features.reference_types,
             enable_simd: features.simd,
-            enable_bulk_memory: false,
+            enable_bulk_memory: features.bulk_memory,
             enable_multi_value: false,
 
             #[cfg(feature = "deterministic-execution")]
@@ -298,7 +298,7 @@ impl<
         compiler_config: CompilerConfig,
         _: Token,
     ) -> CompileResult<ModuleInner> {
-        if MCG::requires_pre_validation() {
+        if MCG::requires_pre_validation() && !compiler_config.skip_pre_validation {
             validate_with_features(wasm, &compiler_config.features)?;
         }
 
@@ -426,15 +426,9 @@ impl MiddlewareChain {
     }
 
     /// Notify this chain about a given local variable.
-    pub(crate) fn run_func_local(
-        &mut self,
-        ty: WpType,
-        n: usize,
-        loc: u32,
-    ) -> Result<(), String> {
+    pub(crate) fn run_func_local(&mut self, ty: WpType, n: usize, loc: u32) -> Result<(), String> {
         for m in &mut self.chain {
-            m.feed_local(ty, n, loc)
-                .map_err(|x| format!("{:?}", x))?;
+            m.feed_local(ty, n, loc).map_err(|x| format!("{:?}", x))?;
         }
 
         Ok(())
@@ -455,12 +449,7 @@ pub trait FunctionMiddleware {
     ) -> Result<(), Self::Error>;
 
     /// Notify the middleware about a given local variable.
-    fn feed_local(
-        &mut self,
-        _ty: WpType,
-        _n: usize,
-        _source_loc: u32,
-    ) -> Result<(), Self::Error> {
+    fn feed_local(&mut self, _ty: WpType, _n: usize, _source_loc: u32) -> Result<(), Self::Error> {
         Ok(())
     }
 }
@@ -474,12 +463,7 @@ pub(crate) trait GenericFunctionMiddleware {
         source_loc: u32,
     ) -> Result<(), String>;
 
-    fn feed_local(
-        &mut self,
-        _ty: WpType,
-        _n: usize,
-        _source_loc: u32,
-    ) -> Result<(), String>;
+    fn feed_local(&mut self, _ty: WpType, _n: usize, _source_loc: u32) -> Result<(), String>;
 }
 
 impl<E: Debug, T: FunctionMiddleware<Error = E>> GenericFunctionMiddleware for T {
@@ -494,12 +478,7 @@ impl<E: Debug, T: FunctionMiddleware<Error = E>> GenericFunctionMiddleware for T
             .map_err(|x| format!("{:?}", x))
     }
 
-    fn feed_local(
-        &mut self,
-        ty: WpType,
-        n: usize,
-        source_loc: u32,
-    ) -> Result<(), String> {
+    fn feed_local(&mut self, ty: WpType, n: usize, source_loc: u32) -> Result<(), String> {
         <Self as FunctionMiddleware>::feed_local(self, ty, n, source_loc)
             .map_err(|x| format!("{:?}", x))
     }
@@ -226,6 +226,8 @@ impl std::fmt::Display for RuntimeError {
             write!(f, "\"{}\"", s)
         } else if let Some(s) = data.downcast_ref::<&str>() {
             write!(f, "\"{}\"", s)
+        } else if let Some(ImportPanic(message)) = data.downcast_ref::<ImportPanic>() {
+            write!(f, "panic: {}", message)
         } else if let Some(exc_code) = data.downcast_ref::<ExceptionCode>() {
             write!(f, "Caught exception of type \"{:?}\".", exc_code)
         } else {
@@ -234,6 +236,28 @@ impl std::fmt::Display for RuntimeError {
     }
 }
 
+/// The data of the `RuntimeError` raised when an imported function
+/// panics: the panic is caught before it unwinds through the wasm
+/// frames, and the call traps instead.
+#[derive(Debug)]
+pub struct ImportPanic(pub String);
+
+impl ImportPanic {
+    /// Builds the data of the trap from the payload of a panic caught
+    /// with `std::panic::catch_unwind`.
+    pub fn from_payload(payload: Box<dyn Any + Send>) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+
+        ImportPanic(message)
+    }
+}
+
 impl std::fmt::Debug for RuntimeError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", self)
@@ -336,7 +360,14 @@ impl std::fmt::Debug for CallError {
     }
 }
 
-impl std::error::Error for CallError {}
+impl std::error::Error for CallError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CallError::Resolve(resolve_error) => Some(resolve_error),
+            CallError::Runtime(runtime_error) => Some(runtime_error),
+        }
+    }
+}
 
 /// This error type is produced when creating something,
 /// like a `Memory` or a `Table`.
@@ -470,7 +501,20 @@ impl std::fmt::Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::CompileError(err) => Some(err),
+            // Several link errors have no single source.
+            Error::LinkError(errs) if errs.len() == 1 => Some(&errs[0]),
+            Error::LinkError(_) => None,
+            Error::RuntimeError(err) => Some(err),
+            Error::ResolveError(err) => Some(err),
+            Error::CallError(err) => Some(err),
+            Error::CreationError(err) => Some(err),
+        }
+    }
+}
 
 /// An error occurred while growing a memory or table.
 #[derive(Debug)]
@@ -66,6 +66,10 @@ impl FuncPointer {
 }
 
 /// An iterator to an instance's exports.
+///
+/// Exports are yielded in the order they are declared in the
+/// module's export section, which is stable across runs and across
+/// cache round-trips.
 pub struct ExportIter<'a> {
     inner: &'a InstanceInner,
     iter: IndexMapIter<'a, String, ExportIndex>,
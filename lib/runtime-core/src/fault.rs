@@ -27,6 +27,7 @@ pub mod raw {
     }
 }
 
+use crate::backend::{ExceptionCode, ExceptionTable};
 use crate::codegen::{BreakpointInfo, BreakpointMap};
 use crate::state::x64::{build_instance_image, read_stack, X64Register, GPR};
 use crate::state::{CodeVersion, ExecutionStateImage};
@@ -85,6 +86,8 @@ thread_local! {
     static CURRENT_CTX: UnsafeCell<*mut vm::Ctx> = UnsafeCell::new(::std::ptr::null_mut());
     static CURRENT_CODE_VERSIONS: RefCell<Vec<CodeVersion>> = RefCell::new(vec![]);
     static WAS_SIGINT_TRIGGERED: Cell<bool> = Cell::new(false);
+    static LAST_TRAP_INFO: Cell<Option<TrapInfo>> = Cell::new(None);
+    static CURRENT_EXCEPTION_TABLE: Cell<Option<CurrentExceptionTable>> = Cell::new(None);
     static BOUNDARY_REGISTER_PRESERVATION: UnsafeCell<BoundaryRegisterPreservation> = UnsafeCell::new(BoundaryRegisterPreservation::default());
 }
 
@@ -129,6 +132,90 @@ pub fn was_sigint_triggered_fault() -> bool {
     WAS_SIGINT_TRIGGERED.with(|x| x.get())
 }
 
+/// Information about a trap raised by wasm code.
+#[derive(Copy, Clone, Debug)]
+pub struct TrapInfo {
+    /// The exception code of the trap, if it could be found in the exception tables.
+    pub exception_code: Option<ExceptionCode>,
+    /// The local function id and the index of the trapping instruction within the function
+    /// body, i.e. the `wasm_inst_offset`, of the innermost frame, if the stack could be read.
+    pub location: Option<(usize, usize)>,
+}
+
+/// Returns the information about the last trap raised on this thread.
+pub fn get_last_trap_info() -> Option<TrapInfo> {
+    LAST_TRAP_INFO.with(|x| x.get())
+}
+
+/// Clears the information about the last trap raised on this thread.
+pub fn clear_last_trap_info() {
+    LAST_TRAP_INFO.with(|x| x.set(None));
+}
+
+/// Records the information about a trap raised on this thread, for the backends which handle
+/// their traps without the signal handler of this module, e.g. cranelift.
+pub fn set_last_trap_info(trap_info: TrapInfo) {
+    LAST_TRAP_INFO.with(|x| x.set(Some(trap_info)));
+}
+
+/// The exception table of the code entered by the current thread, see `enter_exception_table`.
+#[derive(Copy, Clone)]
+struct CurrentExceptionTable {
+    code_start: usize,
+    code_end: usize,
+    table: *const ExceptionTable,
+}
+
+/// Restores the exception table of the code entered before, once dropped, see
+/// `enter_exception_table`.
+pub struct ExceptionTableScope {
+    previous: Option<CurrentExceptionTable>,
+}
+
+impl Drop for ExceptionTableScope {
+    fn drop(&mut self) {
+        CURRENT_EXCEPTION_TABLE.with(|x| x.set(self.previous));
+    }
+}
+
+/// Makes `table` the exception table of `code`, while the current thread runs it, so that the
+/// traps raised by `code` report the exception code of the trapping instruction, e.g. for the
+/// backends which do not run their code through `push_code_version`.
+///
+/// The code and the table must outlive the returned scope, which is to be kept until the thread
+/// leaves `code`.
+pub unsafe fn enter_exception_table(
+    code: Option<&[u8]>,
+    table: Option<&ExceptionTable>,
+) -> ExceptionTableScope {
+    let current = match (code, table) {
+        (Some(code), Some(table)) => Some(CurrentExceptionTable {
+            code_start: code.as_ptr() as usize,
+            code_end: code.as_ptr() as usize + code.len(),
+            table,
+        }),
+        _ => None,
+    };
+
+    ExceptionTableScope {
+        previous: CURRENT_EXCEPTION_TABLE.with(|x| x.replace(current)),
+    }
+}
+
+/// Looks up the exception code of the instruction at `ip` in the exception table of the code
+/// entered by the current thread, see `enter_exception_table`.
+unsafe fn lookup_current_exception_table(ip: usize) -> Option<ExceptionCode> {
+    let current = CURRENT_EXCEPTION_TABLE.with(|x| x.get())?;
+    if ip < current.code_start || ip >= current.code_end {
+        return None;
+    }
+
+    (*current.table)
+        .offset_to_code
+        .get(&(ip - current.code_start))
+        .cloned()
+}
+
 /// Runs a callback function with the given `Ctx`.
 pub unsafe fn with_ctx<R, F: FnOnce() -> R>(ctx: *mut vm::Ctx, cb: F) -> R {
     let addr = CURRENT_CTX.with(|x| x.get());
@@ -403,24 +490,47 @@ extern "C" fn signal_trap_handler(
                 }
 
                 // Look up the exception tables and try to find an exception code.
-                let exc_code = CURRENT_CODE_VERSIONS.with(|versions| {
-                    let versions = versions.borrow();
-                    for v in versions.iter() {
-                        if let Some(table) = v.runnable_module.get_exception_table() {
-                            let ip = fault.ip.get();
-                            let end = v.base + v.msm.total_size;
-                            if ip >= v.base && ip < end {
-                                if let Some(exc_code) = table.offset_to_code.get(&(ip - v.base)) {
-                                    return Some(*exc_code);
+                let exc_code = CURRENT_CODE_VERSIONS
+                    .with(|versions| {
+                        let versions = versions.borrow();
+                        for v in versions.iter() {
+                            if let Some(table) = v.runnable_module.get_exception_table() {
+                                let ip = fault.ip.get();
+                                let end = v.base + v.msm.total_size;
+                                if ip >= v.base && ip < end {
+                                    if let Some(exc_code) = table.offset_to_code.get(&(ip - v.base))
+                                    {
+                                        return Some(*exc_code);
+                                    }
                                 }
                             }
                         }
-                    }
-                    None
-                });
+                        None
+                    })
+                    .or_else(|| lookup_current_exception_table(fault.ip.get()))
+                    .or_else(|| match Signal::from_c_int(signum) {
+                        // Backends without exception tables, e.g. singlepass
+                        // without `CompilerConfig::precise_trap_codes`, only
+                        // raise `SIGFPE` from integer divisions, which trap on a
+                        // zero divisor and on signed overflow (`INT_MIN / -1`)
+                        // alike.
+                        Ok(SIGFPE) => Some(ExceptionCode::IllegalArithmetic),
+                        _ => None,
+                    });
                 if let Some(code) = exc_code {
                     unwind_result = Box::new(code);
                 }
+
+                let location = es_image
+                    .frames
+                    .first()
+                    .map(|frame| (frame.local_function_id, frame.wasm_inst_offset));
+                LAST_TRAP_INFO.with(|x| {
+                    x.set(Some(TrapInfo {
+                        exception_code: exc_code,
+                        location,
+                    }))
+                });
             }
 
             true
@@ -494,6 +604,38 @@ pub unsafe fn install_sighandler_as_dylib() {
     }
 }
 
+/// Enable or disable the handling of SIGSEGV. When enabled, invalid
+/// memory accesses from wasm code become traps; when disabled, SIGSEGV
+/// gets its default disposition back, and is passed through by
+/// [`install_sighandler_as_dylib`].
+///
+/// Disabling the handler does not restore a handler the host installed
+/// before the runtime: any SIGSEGV, including an out-of-bounds access
+/// from wasm code, then terminates the process unless the host installs
+/// its own handler afterwards.
+///
+/// Returns the error of `sigaction` if a handler cannot be changed.
+///
+/// [`install_sighandler_as_dylib`]: fn.install_sighandler_as_dylib.html
+pub unsafe fn set_sigsegv_handler_enabled(enabled: bool) -> nix::Result<()> {
+    SIGSEGV_PASSTHROUGH.store(!enabled, Ordering::SeqCst);
+
+    if enabled {
+        let sa_trap = SigAction::new(
+            SigHandler::SigAction(signal_trap_handler),
+            SaFlags::SA_ONSTACK,
+            SigSet::empty(),
+        );
+        for &signal in &[SIGFPE, SIGILL, SIGBUS, SIGTRAP, SIGSEGV] {
+            sigaction(signal, &sa_trap)?;
+        }
+    } else {
+        let sa_default = SigAction::new(SigHandler::SigDfl, SaFlags::empty(), SigSet::empty());
+        sigaction(SIGSEGV, &sa_default)?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 /// Info about the fault
 pub struct FaultInfo {
@@ -1,7 +1,7 @@
 //! The import module contains the implementation data structures and helper functions used to
 //! manipulate and access a wasm module's imports including memories, tables, globals, and
 //! functions.
-use crate::export::Export;
+use crate::{export::Export, vm::Ctx};
 use std::collections::VecDeque;
 use std::collections::{hash_map::Entry, HashMap};
 use std::{
@@ -33,6 +33,11 @@ impl IsExport for Export {
     }
 }
 
+/// A function called in place of a function import which no namespace
+/// provides, with the namespace and the name of the import, see
+/// `ImportObject::unresolved_function_handler`.
+pub type UnresolvedFunctionHandler = Arc<dyn Fn(&mut Ctx, &str, &str) + Send + Sync>;
+
 /// All of the import data used when instantiating.
 ///
 /// It's suggested that you use the [`imports!`] macro
@@ -61,6 +66,11 @@ pub struct ImportObject {
     /// Allow missing functions to be generated and instantiation to continue when required
     /// functions are not provided.
     pub allow_missing_functions: bool,
+    /// Resolve the function imports which are not provided to placeholders calling this
+    /// handler, then trapping with a message naming the import, instead of failing the
+    /// instantiation. It takes precedence over `allow_missing_functions`, and is only
+    /// supported on x86_64 unix platforms.
+    pub unresolved_function_handler: Option<UnresolvedFunctionHandler>,
 }
 
 impl ImportObject {
@@ -70,6 +80,7 @@ impl ImportObject {
             map: Arc::new(Mutex::new(HashMap::new())),
             state_creator: None,
             allow_missing_functions: false,
+            unresolved_function_handler: None,
         }
     }
 
@@ -82,6 +93,7 @@ impl ImportObject {
             map: Arc::new(Mutex::new(HashMap::new())),
             state_creator: Some(Arc::new(state_creator)),
             allow_missing_functions: false,
+            unresolved_function_handler: None,
         }
     }
 
@@ -164,6 +176,7 @@ impl ImportObject {
             map: Arc::clone(&self.map),
             state_creator: self.state_creator.clone(),
             allow_missing_functions: false,
+            unresolved_function_handler: None,
         }
     }
 
@@ -4,7 +4,8 @@ use crate::{
     backend::RunnableModule,
     backing::{ImportBacking, LocalBacking},
     error::{
-        CallError, CallResult, ResolveError, ResolveResult, Result, RuntimeError, RuntimeResult,
+        CallError, CallResult, GrowError, ResolveError, ResolveResult, Result, RuntimeError,
+        RuntimeResult,
     },
     export::{Context, Export, ExportIter, FuncPointer},
     global::Global,
@@ -16,7 +17,10 @@ use crate::{
     structures::TypedIndex,
     table::Table,
     typed_func::{Func, Wasm, WasmTypeList},
-    types::{FuncIndex, FuncSig, GlobalIndex, LocalOrImport, MemoryIndex, TableIndex, Type, Value},
+    types::{
+        FuncIndex, FuncSig, GlobalIndex, LocalFuncIndex, LocalOrImport, MemoryIndex, TableIndex,
+        Type, Value,
+    },
     vm::{self, InternalField},
 };
 use smallvec::{smallvec, SmallVec};
@@ -61,6 +65,17 @@ pub struct Instance {
 
 impl Instance {
     pub(crate) fn new(module: Arc<ModuleInner>, imports: &ImportObject) -> Result<Instance> {
+        let instance = Instance::new_without_start(module, imports)?;
+
+        instance.call_start_func()?;
+
+        Ok(instance)
+    }
+
+    pub(crate) fn new_without_start(
+        module: Arc<ModuleInner>,
+        imports: &ImportObject,
+    ) -> Result<Instance> {
         // We need the backing and import_backing to create a vm::Ctx, but we need
         // a vm::Ctx to create a backing and an import_backing. The solution is to create an
         // uninitialized vm::Ctx and then initialize it in-place.
@@ -97,26 +112,66 @@ impl Instance {
             import_object: imports.clone_ref(),
         };
 
-        if let Some(start_index) = instance.module.info.start_func {
+        Ok(instance)
+    }
+
+    /// Reset an `Instance` to its state right after instantiation: the
+    /// memories and globals are reset as with `reset_zeroed`, then the
+    /// start function, if any, is called again.
+    pub fn reset(&mut self) -> RuntimeResult<()> {
+        self.reset_zeroed()?;
+        self.call_start_func()
+    }
+
+    /// Reset an `Instance` to a clean slate: the memories are shrunk to
+    /// their minimum size, zeroed, and re-initialized with the data
+    /// segments only, and the mutable globals are re-initialized. The
+    /// start function is not called.
+    pub fn reset_zeroed(&mut self) -> RuntimeResult<()> {
+        let result = self.inner.backing.reset(&self.module.info);
+        unsafe { (*self.inner.vmctx).refresh_memory_bounds() };
+        result
+    }
+
+    /// Get the globals defined by the module, as opposed to the imported
+    /// ones, in the order they are declared.
+    pub fn local_globals(&self) -> Vec<Global> {
+        self.inner
+            .backing
+            .globals
+            .iter()
+            .map(|(_, global)| global.clone())
+            .collect()
+    }
+
+    /// Call the start function of the module, if any.
+    ///
+    /// It is already called by [`Module::instantiate`]; this is meant
+    /// for instances created with [`Module::instantiate_without_start`].
+    ///
+    /// [`Module::instantiate`]: struct.Module.html#method.instantiate
+    /// [`Module::instantiate_without_start`]: struct.Module.html#method.instantiate_without_start
+    pub fn call_start_func(&self) -> RuntimeResult<()> {
+        if let Some(start_index) = self.module.info.start_func {
             // We know that the start function takes no arguments and returns no values.
             // Therefore, we can call it without doing any signature checking, etc.
 
-            let func_ptr = match start_index.local_or_import(&instance.module.info) {
-                LocalOrImport::Local(local_func_index) => instance
+            let func_ptr = match start_index.local_or_import(&self.module.info) {
+                LocalOrImport::Local(local_func_index) => self
                     .module
                     .runnable_module
-                    .get_func(&instance.module.info, local_func_index)
+                    .get_func(&self.module.info, local_func_index)
                     .unwrap(),
                 LocalOrImport::Import(import_func_index) => NonNull::new(
-                    instance.inner.import_backing.vm_functions[import_func_index].func as *mut _,
+                    self.inner.import_backing.vm_functions[import_func_index].func as *mut _,
                 )
                 .unwrap(),
             };
 
-            let ctx_ptr = match start_index.local_or_import(&instance.module.info) {
-                LocalOrImport::Local(_) => instance.inner.vmctx,
+            let ctx_ptr = match start_index.local_or_import(&self.module.info) {
+                LocalOrImport::Local(_) => self.inner.vmctx,
                 LocalOrImport::Import(imported_func_index) => unsafe {
-                    instance.inner.import_backing.vm_functions[imported_func_index]
+                    self.inner.import_backing.vm_functions[imported_func_index]
                         .func_ctx
                         .as_ref()
                 }
@@ -124,17 +179,17 @@ impl Instance {
                 .as_ptr(),
             };
 
-            let sig_index = *instance
+            let sig_index = *self
                 .module
                 .info
                 .func_assoc
                 .get(start_index)
                 .expect("broken invariant, incorrect func index");
 
-            let wasm_trampoline = instance
+            let wasm_trampoline = self
                 .module
                 .runnable_module
-                .get_trampoline(&instance.module.info, sig_index)
+                .get_trampoline(&self.module.info, sig_index)
                 .expect("wasm trampoline");
 
             let start_func: Func<(), (), Wasm> =
@@ -143,12 +198,7 @@ impl Instance {
             start_func.call()?;
         }
 
-        Ok(instance)
-    }
-
-    /// Reset an `Instance`
-    pub fn reset(&mut self) -> RuntimeResult<()> {
-        self.inner.backing.reset(&self.module.info)
+        Ok(())
     }
 
     /// Load an `Instance` using the given loader.
@@ -380,6 +430,35 @@ impl Instance {
         Ok(results)
     }
 
+    /// Call a WebAssembly function given its index in the function index
+    /// space of the module, imported functions first, whether it is
+    /// exported or not. This skips the lookup of the export name of
+    /// [`call`].
+    ///
+    /// [`call`]: #method.call
+    ///
+    /// # Panics:
+    /// Panics if `func_index` is out of bounds.
+    pub fn call_with_index(
+        &self,
+        func_index: FuncIndex,
+        params: &[Value],
+    ) -> CallResult<Vec<Value>> {
+        let mut results = Vec::new();
+
+        call_func_with_index(
+            &self.module.info,
+            &**self.module.runnable_module,
+            &self.inner.import_backing,
+            self.inner.vmctx,
+            func_index,
+            params,
+            &mut results,
+        )?;
+
+        Ok(results)
+    }
+
     /// Returns an immutable reference to the
     /// [`Ctx`] used by this Instance.
     ///
@@ -397,7 +476,8 @@ impl Instance {
     }
 
     /// Returns an iterator over all of the items
-    /// exported from this instance.
+    /// exported from this instance, in the order
+    /// they are declared in the module.
     pub fn exports(&self) -> ExportIter {
         ExportIter::new(&self.module, &self.inner)
     }
@@ -416,6 +496,76 @@ impl Instance {
     pub fn set_internal(&mut self, field: &InternalField, value: u64) {
         self.inner.backing.internals.0[field.index()] = value;
     }
+
+    /// Returns the current size of the table at `table_index`, or
+    /// `None` if there is no such table.
+    pub fn table_size(&self, table_index: TableIndex) -> Option<u32> {
+        self.get_table(table_index).map(|table| table.size())
+    }
+
+    /// Grows the table at `table_index` by `delta` elements, and returns
+    /// its previous size, or `None` if there is no such table.
+    pub fn table_grow(
+        &self,
+        table_index: TableIndex,
+        delta: u32,
+    ) -> Option<std::result::Result<u32, GrowError>> {
+        self.get_table(table_index).map(|table| table.grow(delta))
+    }
+
+    /// Returns the index of the function stored at `elem_index` in the
+    /// table at `table_index`.
+    ///
+    /// Returns `Ok(None)` if the element is null or refers to a function
+    /// which does not belong to this instance, and `Err(())` if there is no
+    /// such table or element.
+    pub fn table_func_index(
+        &self,
+        table_index: TableIndex,
+        elem_index: u32,
+    ) -> std::result::Result<Option<FuncIndex>, ()> {
+        let table = self.get_table(table_index).ok_or(())?;
+        let func = table
+            .anyfunc_direct_access_mut(|elements| {
+                elements
+                    .get(elem_index as usize)
+                    .map(|anyfunc| anyfunc.func)
+            })
+            .ok_or(())?;
+
+        if func.is_null() {
+            return Ok(None);
+        }
+
+        let info = &self.module.info;
+
+        for (imported_func_index, imported_func) in self.inner.import_backing.vm_functions.iter() {
+            if imported_func.func == func {
+                return Ok(Some(imported_func_index.convert_up(info)));
+            }
+        }
+
+        let local_functions_len = info.func_assoc.len() - info.imported_functions.len();
+        for local_func_index in (0..local_functions_len).map(LocalFuncIndex::new) {
+            let local_func = self.module.runnable_module.get_func(info, local_func_index);
+
+            if local_func.map(|ptr| ptr.as_ptr() as *const vm::Func) == Some(func) {
+                return Ok(Some(local_func_index.convert_up(info)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn get_table(&self, table_index: TableIndex) -> Option<Table> {
+        let info = &self.module.info;
+
+        if table_index.index() < info.imported_tables.len() + info.tables.len() {
+            Some(self.inner.get_table_from_index(&self.module, table_index))
+        } else {
+            None
+        }
+    }
 }
 
 impl InstanceInner {
@@ -577,7 +727,7 @@ impl LikeNamespace for Arc<Mutex<Instance>> {
 }
 
 #[must_use]
-fn call_func_with_index(
+pub(crate) fn call_func_with_index(
     info: &ModuleInfo,
     runnable: &dyn RunnableModule,
     import_backing: &ImportBacking,
@@ -135,7 +135,11 @@ pub fn compile_with_config(
     let token = backend::Token::generate();
     compiler
         .compile(wasm, compiler_config, token)
-        .map(|inner| module::Module::new(Arc::new(inner)))
+        .map(|mut inner| {
+            let inner_info: &mut crate::module::ModuleInfo = &mut inner.info;
+            inner_info.import_custom_sections(wasm).unwrap();
+            module::Module::new(Arc::new(inner))
+        })
 }
 
 /// Perform validation as defined by the
@@ -159,9 +163,9 @@ pub fn validate_and_report_errors_with_features(
     let config = wasmparser::ValidatingParserConfig {
         operator_config: wasmparser::OperatorValidatorConfig {
             enable_simd: features.simd,
-            enable_bulk_memory: false,
+            enable_bulk_memory: features.bulk_memory,
             enable_multi_value: false,
-            enable_reference_types: false,
+            enable_reference_types: features.reference_types,
             enable_threads: features.threads,
 
             #[cfg(feature = "deterministic-execution")]
@@ -8,7 +8,7 @@ use crate::{
     structures::{Map, TypedIndex},
     types::{
         FuncIndex, FuncSig, GlobalDescriptor, GlobalIndex, GlobalInit, ImportedFuncIndex,
-        ImportedGlobalIndex, ImportedMemoryIndex, ImportedTableIndex, Initializer,
+        ImportedGlobalIndex, ImportedMemoryIndex, ImportedTableIndex, Initializer, LocalFuncIndex,
         LocalGlobalIndex, LocalMemoryIndex, LocalTableIndex, MemoryDescriptor, MemoryIndex,
         SigIndex, TableDescriptor, TableIndex,
     },
@@ -17,12 +17,13 @@ use crate::{
 
 use indexmap::IndexMap;
 
-use rkyv::{Archive, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize};
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 
 use crate::backend::CacheGen;
 #[cfg(feature = "generate-debug-information")]
 use crate::jit_debug;
-use std::collections::HashMap;
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 /// This is used to instantiate a new WebAssembly module.
@@ -69,18 +70,34 @@ pub struct ModuleInfo {
     pub func_assoc: Map<FuncIndex, SigIndex>,
     /// Map signature index to function signature.
     pub signatures: Map<SigIndex, FuncSig>,
+    /// Map local function index to the number of operators in its body.
+    pub func_opcode_counts: Map<LocalFuncIndex, u32>,
+    /// Map function index to the name given by the name section, if any.
+    #[serde(serialize_with = "serialize_sorted_map")]
+    pub func_names: HashMap<FuncIndex, String>,
     /// Backend.
     pub backend: String,
 
+    /// Hash of the wasm bytes the module was compiled from.
+    pub wasm_hash: [u8; 32],
+    /// Hash of the options the module was compiled with, see
+    /// `CompilerConfig::compile_options_hash`.
+    pub compile_options_hash: [u8; 32],
+    /// Whether the module was compiled with a metering middleware, see
+    /// `CompilerConfig::metered`.
+    pub metered: bool,
+
     /// Table of namespace indexes.
     pub namespace_table: StringTable<NamespaceIndex>,
     /// Table of name indexes.
     pub name_table: StringTable<NameIndex>,
 
     /// Symbol information from emscripten.
+    #[serde(serialize_with = "serialize_sorted_optional_map")]
     pub em_symbol_map: Option<HashMap<u32, String>>,
 
     /// Custom sections.
+    #[serde(serialize_with = "serialize_sorted_map")]
     pub custom_sections: HashMap<String, Vec<u8>>,
 
     /// Flag controlling whether or not debug information for use in a debugger
@@ -93,6 +110,33 @@ pub struct ModuleInfo {
     pub(crate) debug_info_manager: jit_debug::JitCodeDebugInfoManager,
 }
 
+/// Serializes a map with its entries sorted by key, so that a module always serializes to the
+/// same bytes, whatever the iteration order of its `HashMap`s, which is random per process.
+fn serialize_sorted_map<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+/// Serializes an optional map like `serialize_sorted_map`.
+fn serialize_sorted_optional_map<K, V, S>(
+    map: &Option<HashMap<K, V>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    match map {
+        Some(map) => serializer.serialize_some(&map.iter().collect::<BTreeMap<_, _>>()),
+        None => serializer.serialize_none(),
+    }
+}
+
 impl ModuleInfo {
     /// Creates custom section info from the given wasm file.
     pub fn import_custom_sections(&mut self, wasm: &[u8]) -> crate::error::ParseResult<()> {
@@ -152,6 +196,20 @@ impl Module {
         Instance::new(Arc::clone(&self.inner), import_object)
     }
 
+    /// Instantiate a WebAssembly module like [`instantiate`], but without
+    /// calling its `start` function, e.g. to set up the instance before.
+    /// The `start` function can then be called with
+    /// [`Instance::call_start_func`].
+    ///
+    /// [`instantiate`]: #method.instantiate
+    /// [`Instance::call_start_func`]: struct.Instance.html#method.call_start_func
+    pub fn instantiate_without_start(
+        &self,
+        import_object: &ImportObject,
+    ) -> error::Result<Instance> {
+        Instance::new_without_start(Arc::clone(&self.inner), import_object)
+    }
+
     /// Create a cache artifact from this module.
     pub fn cache(&self) -> Result<Artifact, CacheError> {
         let (backend_metadata, code) = self.inner.cache_gen.generate_cache()?;
@@ -166,6 +224,15 @@ impl Module {
     pub fn info(&self) -> &ModuleInfo {
         &self.inner.info
     }
+
+    /// Returns the size in bytes of the machine code generated for this
+    /// module, or 0 if the backend does not expose its code.
+    pub fn code_size(&self) -> usize {
+        self.inner
+            .runnable_module
+            .get_code()
+            .map_or(0, |code| code.len())
+    }
 }
 
 impl Clone for Module {
@@ -191,7 +258,18 @@ pub struct ImportName {
 /// Used in [`ModuleInfo`] to access function signatures ([`SigIndex`]s,
 /// [`FuncSig`]), [`GlobalInit`]s, [`MemoryDescriptor`]s, and
 /// [`TableDescriptor`]s.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Archive, RkyvSerialize, RkyvDeserialize)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+)]
 pub enum ExportIndex {
     /// Function export index. [`FuncIndex`] is a type-safe handle referring to
     /// a Wasm function.
@@ -315,14 +393,27 @@ impl<K: TypedIndex> StringTable<K> {
     pub fn to_vec(&self) -> Vec<&str> {
         self.table
             .values()
-            .map(|(offset, length)|
-                 &self.buffer[(*offset as usize)..(*offset as usize) + (*length as usize)])
+            .map(|(offset, length)| {
+                &self.buffer[(*offset as usize)..(*offset as usize) + (*length as usize)]
+            })
             .collect()
     }
 }
 
 /// A type-safe handle referring to a module namespace.
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, Archive, RkyvSerialize, RkyvDeserialize)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+)]
 pub struct NamespaceIndex(u32);
 
 impl TypedIndex for NamespaceIndex {
@@ -338,7 +429,19 @@ impl TypedIndex for NamespaceIndex {
 }
 
 /// A type-safe handle referring to a name in a module namespace.
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, Archive, RkyvSerialize, RkyvDeserialize)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+)]
 pub struct NameIndex(u32);
 
 impl TypedIndex for NameIndex {
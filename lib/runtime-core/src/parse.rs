@@ -4,6 +4,7 @@
 use crate::codegen::*;
 use crate::{
     backend::{CompilerConfig, RunnableModule},
+    cache::WasmHash,
     error::CompileError,
     module::{
         DataInitializer, ExportIndex, ImportName, ModuleInfo, StringTable, StringTableBuilder,
@@ -22,7 +23,7 @@ use std::fmt::Debug;
 use std::sync::{Arc, RwLock};
 use wasmparser::{
     BinaryReaderError, ElemSectionEntryTable, ElementItem, ExternalKind, FuncType,
-    ImportSectionEntryType, Operator, Type as WpType, WasmDecoder,
+    ImportSectionEntryType, NameEntry, Operator, Type as WpType, WasmDecoder,
 };
 
 /// Kind of load error.
@@ -88,7 +89,12 @@ pub fn read_module<
 
         func_assoc: Map::new(),
         signatures: Map::new(),
+        func_opcode_counts: Map::new(),
+        func_names: HashMap::new(),
         backend: MCG::backend_id().to_string(),
+        wasm_hash: WasmHash::generate(wasm).into_array(),
+        compile_options_hash: compiler_config.compile_options_hash,
+        metered: compiler_config.metered,
 
         namespace_table: StringTable::new(),
         name_table: StringTable::new(),
@@ -110,6 +116,7 @@ pub fn read_module<
     let mut namespace_builder = Some(StringTableBuilder::new());
     let mut name_builder = Some(StringTableBuilder::new());
     let mut func_count: usize = 0;
+    let mut func_opcode_counts = Map::new();
     let mut mcg_info_fed = false;
 
     loop {
@@ -277,7 +284,8 @@ pub fn read_module<
                             for &(count, ty) in locals.iter() {
                                 fcg.feed_local(ty, count as usize, cur_pos)
                                     .map_err(|x| LoadError::Codegen(format!("{:?}", x)))?;
-                                middlewares.run_func_local(ty, count as usize, cur_pos)
+                                middlewares
+                                    .run_func_local(ty, count as usize, cur_pos)
                                     .map_err(|x| LoadError::Codegen(format!("{:?}", x)))?;
                             }
                         }
@@ -304,10 +312,12 @@ pub fn read_module<
                 }
 
                 // loop until the function body ends
+                let mut opcode_count: u32 = 0;
                 loop {
                     match state {
                         ParserState::Error(err) => return Err(err.into()),
                         ParserState::CodeOperator(op) => {
+                            opcode_count += 1;
                             middlewares
                                 .run(Some(fcg), Event::Wasm(op), &info_read, cur_pos)
                                 .map_err(LoadError::Codegen)?;
@@ -329,6 +339,7 @@ pub fn read_module<
 
                 fcg.finalize()
                     .map_err(|x| LoadError::Codegen(format!("{:?}", x)))?;
+                func_opcode_counts.push(opcode_count);
                 func_count = func_count.wrapping_add(1);
             }
             ParserState::BeginElementSectionEntry {
@@ -433,6 +444,17 @@ pub fn read_module<
 
                 info.write().unwrap().globals.push(global_init);
             }
+            ParserState::NameSectionEntry(NameEntry::Function(ref names)) => {
+                info.write()
+                    .unwrap()
+                    .func_names
+                    .extend(names.iter().map(|naming| {
+                        (
+                            FuncIndex::new(naming.index as usize),
+                            naming.name.to_string(),
+                        )
+                    }));
+            }
             ParserState::EndWasm => {
                 // TODO Consolidate with BeginFunction body if possible
                 if !mcg_info_fed {
@@ -451,6 +473,7 @@ pub fn read_module<
             _ => {}
         }
     }
+    info.write().unwrap().func_opcode_counts = func_opcode_counts;
     Ok(info)
 }
 
@@ -1,7 +1,7 @@
 //! The typed func module implements a way of representing a wasm function
 //! with the correct types from rust. Function calls using a typed func have a low overhead.
 use crate::{
-    error::RuntimeError,
+    error::{ImportPanic, RuntimeError},
     export::{Context, Export, FuncPointer},
     import::IsExport,
     types::{FuncSig, NativeWasmType, Type, WasmExternType},
@@ -620,7 +620,7 @@ macro_rules! impl_traits {
                             let b: Box<_> = err.into();
                             b as Box<dyn Any + Send>
                         },
-                        Err(err) => err,
+                        Err(payload) => Box::new(ImportPanic::from_payload(payload)),
                     };
 
                     // At this point, there is an error that needs to
@@ -733,7 +733,7 @@ macro_rules! impl_traits {
                             let b: Box<_> = err.into();
                             b as Box<dyn Any + Send>
                         },
-                        Err(err) => err,
+                        Err(payload) => Box::new(ImportPanic::from_payload(payload)),
                     };
 
                     // At this point, there is an error that needs to
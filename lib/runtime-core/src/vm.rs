@@ -2,13 +2,14 @@
 //! execute wasm instance functions.
 pub use crate::backing::{ImportBacking, LocalBacking, INTERNALS_SIZE};
 use crate::{
-    error::CallResult,
-    instance::call_func_with_index_inner,
+    error::{CallResult, RuntimeError, RuntimeResult},
+    instance::{call_func_with_index, call_func_with_index_inner},
     memory::{Memory, MemoryType},
     module::{ModuleInfo, ModuleInner},
     sig_registry::SigRegistry,
     structures::TypedIndex,
-    types::{LocalOrImport, MemoryIndex, TableIndex, Value},
+    types::{FuncIndex, LocalOrImport, MemoryIndex, TableIndex, Value},
+    units::Pages,
     vmcalls,
 };
 use std::{
@@ -20,7 +21,7 @@ use std::{
     sync::Once,
 };
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// The context of the currently running WebAssembly instance.
 ///
@@ -68,18 +69,79 @@ pub struct Ctx {
     /// when the context is destructed, e.g. when an `Instance`
     /// is dropped.
     pub data_finalizer: Option<fn(data: *mut c_void)>,
+
+    /// If there's a function set in this field, it gets called before
+    /// each `memory.grow` with `memory_grow_hook_data` and the number
+    /// of requested pages. The memory grows only if it returns 0;
+    /// otherwise `memory.grow` fails and returns -1.
+    pub memory_grow_hook: Option<MemoryGrowHook>,
+
+    /// The data given to `memory_grow_hook`.
+    pub memory_grow_hook_data: *mut c_void,
+
+    /// If there's a function set in this field, it gets called with
+    /// `data` when the context is destructed, like `data_finalizer`,
+    /// for finalizers defined outside of Rust, e.g. through the C API.
+    pub data_extern_finalizer: Option<ExternDataFinalizer>,
+
+    /// User-supplied, per-instance contextual data, by keys which the
+    /// user defines, so that independent subsystems can each own a key
+    /// instead of sharing `data`. The data is never finalized.
+    pub keyed_data: HashMap<u32, *mut c_void>,
+
+    /// The points used per function index by the code compiled with the
+    /// per-function metering, which the metering middleware records
+    /// while the instance runs.
+    pub per_function_gas: BTreeMap<u32, u64>,
+
+    /// The opcodes executed by the code compiled with the opcode trace
+    /// collection, and the markers pushed by the host, which the opcode
+    /// tracer middleware records while the instance runs.
+    pub opcode_trace: Vec<OpcodeTraceEntry>,
+
+    /// The name of the function which the module exports to allocate
+    /// the buffers returned to it by the host functions, or `None` for
+    /// the default name of the embedder.
+    pub wasm_allocator_export: Option<String>,
+}
+
+/// An opcode executed by an instance, or a marker pushed by the host,
+/// see `Ctx::opcode_trace`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OpcodeTraceEntry {
+    /// The stable code of the opcode, or the code reserved for the
+    /// markers.
+    pub opcode: u16,
+    /// The byte offset of the opcode in the original wasm binary, or 0
+    /// for a marker.
+    pub offset: u32,
+    /// The value of the marker, or 0 for an opcode.
+    pub marker: u64,
 }
 
+/// A function called before each `memory.grow`, see
+/// `Ctx::memory_grow_hook`.
+pub type MemoryGrowHook = extern "C" fn(data: *mut c_void, requested_pages: u32) -> i32;
+
+/// A function called with the data of a context when it is destructed,
+/// see `Ctx::data_extern_finalizer`.
+pub type ExternDataFinalizer = extern "C" fn(data: *mut c_void);
+
 /// When an instance context is destructed, we're calling its `data_finalizer`
 /// In order avoid leaking resources.
 ///
 /// Implementing the `data_finalizer` function is the responsibility of the `wasmer` end-user.
 ///
+/// The data has one finalizer at most, see `Ctx::set_data`: `data_finalizer` is called, or else
+/// `data_extern_finalizer`.
+///
 /// See test: `test_data_finalizer` as an example
 impl Drop for Ctx {
     fn drop(&mut self) {
         if let Some(ref finalizer) = self.data_finalizer {
             finalizer(self.data);
+        } else if let Some(finalizer) = self.data_extern_finalizer {
+            finalizer(self.data);
         }
     }
 }
@@ -315,6 +377,16 @@ impl Ctx {
 
             data: ptr::null_mut(),
             data_finalizer: None,
+
+            memory_grow_hook: None,
+            memory_grow_hook_data: ptr::null_mut(),
+
+            data_extern_finalizer: None,
+
+            keyed_data: HashMap::new(),
+            per_function_gas: BTreeMap::new(),
+            opcode_trace: Vec::new(),
+            wasm_allocator_export: None,
         }
     }
 
@@ -368,6 +440,16 @@ impl Ctx {
 
             data,
             data_finalizer: Some(data_finalizer),
+
+            memory_grow_hook: None,
+            memory_grow_hook_data: ptr::null_mut(),
+
+            data_extern_finalizer: None,
+
+            keyed_data: HashMap::new(),
+            per_function_gas: BTreeMap::new(),
+            opcode_trace: Vec::new(),
+            wasm_allocator_export: None,
         }
     }
 
@@ -404,6 +486,61 @@ impl Ctx {
         }
     }
 
+    /// Grows the memory at `mem_index` by `delta` pages, like `memory.grow`
+    /// does: only if the memory grow hook, if any, allows it. Returns the
+    /// previous size of the memory.
+    pub fn grow_memory(&mut self, mem_index: u32, delta: Pages) -> Result<Pages, String> {
+        if !self.is_memory_grow_allowed(delta) {
+            return Err("the memory grow hook denied the growth".to_string());
+        }
+        let previous_size = self
+            .memory(mem_index)
+            .grow(delta)
+            .map_err(|error| error.to_string())?;
+        self.refresh_memory_bounds();
+        Ok(previous_size)
+    }
+
+    /// Shrinks the memory at `mem_index` to `pages`, or to its minimum size
+    /// if greater, like [`Instance::reset_zeroed`] does: the memory is
+    /// shrunk to its minimum size, then grown back to `pages`, which does not
+    /// involve the memory grow hook since the memory was that large already.
+    /// The memory is left unchanged if it cannot shrink, e.g. if it has a
+    /// maximum size.
+    ///
+    /// [`Instance::reset_zeroed`]: ../instance/struct.Instance.html#method.reset_zeroed
+    pub fn shrink_memory(&mut self, mem_index: u32, pages: Pages) -> RuntimeResult<()> {
+        let memory = self.memory(mem_index);
+        if pages >= memory.size() {
+            return Ok(());
+        }
+        memory.shrink_to_minimum()?;
+        let regrowth = if pages > memory.size() {
+            memory.grow(pages - memory.size()).map(|_| ())
+        } else {
+            Ok(())
+        };
+        self.refresh_memory_bounds();
+        regrowth.map_err(|error| RuntimeError(Box::new(error.to_string())))
+    }
+
+    /// Reloads the base and the bound of the memory 0, which the compiled
+    /// code caches, once the memory changed outside of the code.
+    pub(crate) fn refresh_memory_bounds(&mut self) {
+        let module = unsafe { &*self.module };
+        if module.info.memories.is_empty() && module.info.imported_memories.is_empty() {
+            return;
+        }
+        unsafe {
+            let mem = match MemoryIndex::new(0).local_or_import(&module.info) {
+                LocalOrImport::Local(index) => *self.internal.memories.add(index.index()),
+                LocalOrImport::Import(index) => *self.internal.imported_memories.add(index.index()),
+            };
+            self.internal.memory_base = (*mem).base;
+            self.internal.memory_bound = (*mem).bound;
+        }
+    }
+
     /// Get access to [`Memory`] and mutable access to the user defined data
     /// field as the type, `T`.
     ///
@@ -441,6 +578,34 @@ impl Ctx {
         }
     }
 
+    /// Replaces the data of the context, and its finalizer with `extern_finalizer`. The previous
+    /// data is finalized by its finalizer, whether `data_finalizer` or `data_extern_finalizer`,
+    /// unless it is the new data again, which is then finalized by `extern_finalizer` only: both
+    /// finalizers are cleared, so that the data is never finalized twice.
+    pub fn set_data(&mut self, data: *mut c_void, extern_finalizer: Option<ExternDataFinalizer>) {
+        let data_finalizer = self.data_finalizer.take();
+        let data_extern_finalizer = self.data_extern_finalizer.take();
+        if self.data != data {
+            if let Some(finalizer) = data_finalizer {
+                finalizer(self.data);
+            } else if let Some(finalizer) = data_extern_finalizer {
+                finalizer(self.data);
+            }
+        }
+
+        self.data = data;
+        self.data_extern_finalizer = extern_finalizer;
+    }
+
+    /// Asks the memory grow hook, if any, whether a memory may grow by
+    /// `requested_pages`.
+    pub(crate) fn is_memory_grow_allowed(&self, requested_pages: Pages) -> bool {
+        match self.memory_grow_hook {
+            Some(hook) => hook(self.memory_grow_hook_data, requested_pages.0) == 0,
+            None => true,
+        }
+    }
+
     /// Calls a host or Wasm function at the given table index
     pub fn call_with_table_index(
         &mut self,
@@ -475,6 +640,34 @@ impl Ctx {
 
         Ok(rets)
     }
+
+    /// Calls a function of the instance given its index in the function
+    /// index space of the module, imported functions first, e.g. an
+    /// export of the instance called back by a host function.
+    ///
+    /// # Panics:
+    /// Panics if `func_index` is out of bounds.
+    pub fn call_with_index(
+        &mut self,
+        func_index: FuncIndex,
+        args: &[Value],
+    ) -> CallResult<Vec<Value>> {
+        let module = unsafe { &*self.module };
+        let import_backing = unsafe { &*self.import_backing };
+        let mut rets = vec![];
+
+        call_func_with_index(
+            &module.info,
+            &**module.runnable_module,
+            import_backing,
+            self as *mut Ctx,
+            func_index,
+            args,
+            &mut rets,
+        )?;
+
+        Ok(rets)
+    }
 }
 
 #[doc(hidden)]
@@ -979,6 +1172,7 @@ mod vm_ctx_tests {
     use crate::module::{ModuleInfo, ModuleInner, StringTable};
     use crate::structures::Map;
     use std::ffi::c_void;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
 
     struct TestData {
@@ -1039,6 +1233,8 @@ mod vm_ctx_tests {
             vm_memories: Map::new().into_boxed_map(),
             vm_tables: Map::new().into_boxed_map(),
             vm_globals: Map::new().into_boxed_map(),
+
+            unresolved_functions: Vec::new(),
         };
 
         let module = generate_module();
@@ -1061,6 +1257,74 @@ mod vm_ctx_tests {
         drop(ctx);
     }
 
+    static FINALIZED_DATA: AtomicUsize = AtomicUsize::new(0);
+
+    fn count_data_finalizer(_data: *mut c_void) {
+        FINALIZED_DATA.fetch_add(1, Ordering::SeqCst);
+    }
+
+    extern "C" fn count_data_extern_finalizer(_data: *mut c_void) {
+        FINALIZED_DATA.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_set_data_finalizes_once() {
+        let mut local_backing = LocalBacking {
+            memories: Map::new().into_boxed_map(),
+            tables: Map::new().into_boxed_map(),
+            globals: Map::new().into_boxed_map(),
+
+            vm_memories: Map::new().into_boxed_map(),
+            vm_tables: Map::new().into_boxed_map(),
+            vm_globals: Map::new().into_boxed_map(),
+
+            dynamic_sigindices: Map::new().into_boxed_map(),
+            local_functions: Map::new().into_boxed_map(),
+
+            internals: crate::backing::Internals([0; crate::backing::INTERNALS_SIZE]),
+        };
+
+        let mut import_backing = ImportBacking {
+            memories: Map::new().into_boxed_map(),
+            tables: Map::new().into_boxed_map(),
+            globals: Map::new().into_boxed_map(),
+
+            vm_functions: Map::new().into_boxed_map(),
+            vm_memories: Map::new().into_boxed_map(),
+            vm_tables: Map::new().into_boxed_map(),
+            vm_globals: Map::new().into_boxed_map(),
+
+            unresolved_functions: Vec::new(),
+        };
+
+        let module = generate_module();
+        let mut first_data = 1u8;
+        let mut second_data = 2u8;
+        let first_data_ptr = &mut first_data as *mut _ as *mut c_void;
+        let second_data_ptr = &mut second_data as *mut _ as *mut c_void;
+        let mut ctx = unsafe {
+            Ctx::new_with_data(
+                &mut local_backing,
+                &mut import_backing,
+                &module,
+                first_data_ptr,
+                count_data_finalizer,
+            )
+        };
+
+        // The same data changes hands: it is not finalized yet.
+        ctx.set_data(first_data_ptr, Some(count_data_extern_finalizer));
+        assert_eq!(FINALIZED_DATA.load(Ordering::SeqCst), 0);
+        assert!(ctx.data_finalizer.is_none());
+
+        // The previous data is finalized once, by its finalizer.
+        ctx.set_data(second_data_ptr, Some(count_data_extern_finalizer));
+        assert_eq!(FINALIZED_DATA.load(Ordering::SeqCst), 1);
+
+        drop(ctx);
+        assert_eq!(FINALIZED_DATA.load(Ordering::SeqCst), 2);
+    }
+
     fn cast_test_data(data: *mut c_void) -> &'static mut TestData {
         let test_data: &mut TestData = unsafe { &mut *(data as *mut TestData) };
         test_data
@@ -1122,7 +1386,12 @@ mod vm_ctx_tests {
 
                 func_assoc: Map::new(),
                 signatures: Map::new(),
+                func_opcode_counts: Map::new(),
+                func_names: HashMap::new(),
                 backend: Default::default(),
+                wasm_hash: [0; 32],
+                compile_options_hash: [0; 32],
+                metered: false,
 
                 namespace_table: StringTable::new(),
                 name_table: StringTable::new(),
@@ -18,7 +18,7 @@ use crate::{
 /// `delta` [`Pages`].
 ///
 /// This function returns the number of pages before growing if successful, or
-/// `-1` if the grow failed.
+/// `-1` if the grow failed or was denied by the memory grow hook of `ctx`.
 ///
 /// # Safety
 ///
@@ -29,6 +29,10 @@ pub unsafe extern "C" fn local_static_memory_grow(
     memory_index: LocalMemoryIndex,
     delta: Pages,
 ) -> i32 {
+    if !ctx.is_memory_grow_allowed(delta) {
+        return -1;
+    }
+
     let local_memory = *ctx.internal.memories.add(memory_index.index());
     let memory = (*local_memory).memory as *mut StaticMemory;
 
@@ -63,7 +67,7 @@ pub unsafe extern "C" fn local_static_memory_size(
 /// `delta` [`Pages`].
 ///
 /// This function returns the number of pages before growing if successful, or
-/// `-1` if the grow failed.
+/// `-1` if the grow failed or was denied by the memory grow hook of `ctx`.
 ///
 /// # Safety
 ///
@@ -74,6 +78,10 @@ pub unsafe extern "C" fn local_dynamic_memory_grow(
     memory_index: LocalMemoryIndex,
     delta: Pages,
 ) -> i32 {
+    if !ctx.is_memory_grow_allowed(delta) {
+        return -1;
+    }
+
     let local_memory = *ctx.internal.memories.add(memory_index.index());
     let memory = (*local_memory).memory as *mut DynamicMemory;
 
@@ -112,7 +120,7 @@ pub unsafe extern "C" fn local_dynamic_memory_size(
 /// `delta` [`Pages`].
 ///
 /// This function returns the number of pages before growing if successful, or
-/// `-1` if the grow failed.
+/// `-1` if the grow failed or was denied by the memory grow hook of `ctx`.
 ///
 /// # Safety
 ///
@@ -123,6 +131,10 @@ pub unsafe extern "C" fn imported_static_memory_grow(
     import_memory_index: ImportedMemoryIndex,
     delta: Pages,
 ) -> i32 {
+    if !ctx.is_memory_grow_allowed(delta) {
+        return -1;
+    }
+
     let local_memory = *ctx
         .internal
         .imported_memories
@@ -163,7 +175,7 @@ pub unsafe extern "C" fn imported_static_memory_size(
 /// `delta` [`Pages`].
 ///
 /// This function returns the number of pages before growing if successful, or
-/// `-1` if the grow failed.
+/// `-1` if the grow failed or was denied by the memory grow hook of `ctx`.
 ///
 /// # Safety
 ///
@@ -174,6 +186,10 @@ pub unsafe extern "C" fn imported_dynamic_memory_grow(
     memory_index: ImportedMemoryIndex,
     delta: Pages,
 ) -> i32 {
+    if !ctx.is_memory_grow_allowed(delta) {
+        return -1;
+    }
+
     let local_memory = *ctx.internal.imported_memories.add(memory_index.index());
     let memory = (*local_memory).memory as *mut DynamicMemory;
 
@@ -0,0 +1,45 @@
+#[test]
+#[cfg(all(unix, target_arch = "x86_64"))]
+fn unresolved_function_handler() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use wabt::wat2wasm;
+    use wasmer_runtime::{imports, instantiate, Func};
+
+    static WAT: &'static str = r#"
+        (module
+        (type (;0;) (func (param i32 i64)))
+        (type (;1;) (func (result i32)))
+        (import "env" "missing" (func $missing (type 0)))
+        (func $call_missing (type 1)
+            i32.const 1
+            i64.const 2
+            call $missing
+            i32.const 42
+        )
+        (export "call_missing" (func $call_missing))
+        )
+    "#;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    let wasm = wat2wasm(WAT).unwrap();
+
+    // Unresolved imports still fail the instantiation by default.
+    let mut import_object = imports! {};
+    assert!(instantiate(&wasm, &import_object).is_err());
+
+    import_object.unresolved_function_handler = Some(Arc::new(|_, namespace, name| {
+        assert_eq!((namespace, name), ("env", "missing"));
+        CALLS.fetch_add(1, Ordering::SeqCst);
+    }));
+    let instance = instantiate(&wasm, &import_object).unwrap();
+
+    let call_missing: Func<(), i32> = instance.func("call_missing").unwrap();
+    let error = call_missing.call().unwrap_err();
+
+    assert!(format!("{}", error).contains("called unimplemented host function env.missing"));
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+}
@@ -12,22 +12,18 @@ use smallvec::SmallVec;
 use std::{
     any::Any,
     collections::{BTreeMap, HashMap},
+    convert::TryInto,
     ffi::c_void,
     iter, mem,
     ptr::NonNull,
     slice,
     sync::{Arc, RwLock},
     usize,
-    convert::TryInto,
 };
 
 use rkyv::{
-    Archive,
-    Archived,
-    Serialize as RkyvSerialize,
-    Deserialize as RkyvDeserialize,
-    ser::Serializer,
-    ser::serializers::AllocSerializer,
+    ser::serializers::AllocSerializer, ser::Serializer, Archive, Archived,
+    Deserialize as RkyvDeserialize, Serialize as RkyvSerialize,
 };
 
 use bincode;
@@ -242,6 +238,10 @@ pub struct X64FunctionCode {
     config: Arc<CodegenConfig>,
 
     exception_table: Option<ExceptionTable>,
+
+    /// Offsets of the `ud2` emitted for `unreachable`, recorded in the exception table even
+    /// without `precise_trap_codes`.
+    unreachable_offsets: Vec<usize>,
 }
 
 enum FuncPtrInner {}
@@ -347,8 +347,8 @@ impl RunnableModule for X64ExecutionContext {
 
     fn get_exception_table(&self) -> Option<&ExceptionTable> {
         match &self.exception_table {
-            Some(etable) => { Some(&etable) }
-            None => None
+            Some(etable) => Some(&etable),
+            None => None,
         }
     }
 
@@ -406,6 +406,10 @@ impl RunnableModule for X64ExecutionContext {
             let args =
                 slice::from_raw_parts(args, num_params_plus_one.unwrap().as_ptr() as usize - 1);
 
+            // Let the traps of the functions find their exception codes, when recorded.
+            let _exception_table =
+                fault::enter_exception_table(rm.get_code(), rm.get_exception_table());
+
             let ret = match fault::catch_unsafe_unwind(
                 || {
                     // Puts the arguments onto the stack and calls Wasm entry.
@@ -662,6 +666,7 @@ struct CodegenConfig {
     enforce_stack_check: bool,
     track_state: bool,
     full_preemption: bool,
+    precise_trap_codes: bool,
 }
 
 impl ModuleCodeGenerator<X64FunctionCode, X64ExecutionContext, CodegenError>
@@ -715,7 +720,12 @@ impl ModuleCodeGenerator<X64FunctionCode, X64ExecutionContext, CodegenError>
                     self.assembler.take().unwrap(),
                     self.function_labels.take().unwrap(),
                     HashMap::new(),
-                    None, // Some(ExceptionTable::new()),
+                    // The table is only worth its size when the traps must be told apart.
+                    if self.config.as_ref().unwrap().precise_trap_codes {
+                        Some(ExceptionTable::new())
+                    } else {
+                        None
+                    },
                 ),
             };
 
@@ -752,6 +762,7 @@ impl ModuleCodeGenerator<X64FunctionCode, X64ExecutionContext, CodegenError>
             unreachable_depth: 0,
             config: self.config.as_ref().unwrap().clone(),
             exception_table: exception_table,
+            unreachable_offsets: vec![],
         };
         self.functions.push(code);
         Ok(self.functions.last_mut().unwrap())
@@ -784,6 +795,20 @@ impl ModuleCodeGenerator<X64FunctionCode, X64ExecutionContext, CodegenError>
                 ),
             };
 
+        // `unreachable` is the only trap raising `SIGILL` which cannot be told apart from the
+        // signal alone, so its offsets are always recorded: one entry per `unreachable`.
+        let mut exception_table = exception_table;
+        for offset in self
+            .functions
+            .iter()
+            .flat_map(|x| x.unreachable_offsets.iter().cloned())
+        {
+            exception_table
+                .get_or_insert_with(ExceptionTable::new)
+                .offset_to_code
+                .insert(offset, ExceptionCode::Unreachable);
+        }
+
         let total_size = assembler.get_offset().0;
         let _output = assembler.finalize().unwrap();
         let mut output = CodeMemory::new(_output.len());
@@ -864,7 +889,8 @@ impl ModuleCodeGenerator<X64FunctionCode, X64ExecutionContext, CodegenError>
                 buffer: Arc::from(archived_cache_image.as_slice()),
             }
         } else {
-            let serialized_cache_image = bincode::serialize(&cache_image).unwrap().into_boxed_slice();
+            let serialized_cache_image =
+                bincode::serialize(&cache_image).unwrap().into_boxed_slice();
             SinglepassCache {
                 buffer: Arc::from(serialized_cache_image),
             }
@@ -952,6 +978,7 @@ impl ModuleCodeGenerator<X64FunctionCode, X64ExecutionContext, CodegenError>
             enforce_stack_check: config.enforce_stack_check,
             track_state: config.track_state,
             full_preemption: config.full_preemption,
+            precise_trap_codes: config.precise_trap_codes,
         }));
         Ok(())
     }
@@ -960,9 +987,13 @@ impl ModuleCodeGenerator<X64FunctionCode, X64ExecutionContext, CodegenError>
 
         let cache_image: CacheImage = if USE_RKYV_SERIALIZATION {
             let memory_contents = memory.as_slice_contents();
-            let archived_cache_image: &Archived<CacheImage>
-                = rkyv::archived_root::<CacheImage>(memory_contents);
-            RkyvDeserialize::<CacheImage, _>::deserialize(archived_cache_image, &mut rkyv::Infallible).unwrap()
+            let archived_cache_image: &Archived<CacheImage> =
+                rkyv::archived_root::<CacheImage>(memory_contents);
+            RkyvDeserialize::<CacheImage, _>::deserialize(
+                archived_cache_image,
+                &mut rkyv::Infallible,
+            )
+            .unwrap()
         } else {
             bincode::deserialize(memory.as_slice())
                 .map_err(|x| CacheError::DeserializeError(format!("{:?}", x)))?
@@ -1059,9 +1090,11 @@ impl X64FunctionCode {
                 Self::mark_trappable(a, m, fsm, control_stack);
 
                 match exception_table {
-                    Some(etable) => { etable
-                                        .offset_to_code
-                                        .insert(a.get_offset().0, ExceptionCode::IllegalArithmetic); }
+                    Some(etable) => {
+                        etable
+                            .offset_to_code
+                            .insert(a.get_offset().0, ExceptionCode::IllegalArithmetic);
+                    }
                     None => {}
                 };
 
@@ -1071,9 +1104,11 @@ impl X64FunctionCode {
                 Self::mark_trappable(a, m, fsm, control_stack);
 
                 match exception_table {
-                    Some(etable) => { etable
-                                        .offset_to_code
-                                        .insert(a.get_offset().0, ExceptionCode::IllegalArithmetic); }
+                    Some(etable) => {
+                        etable
+                            .offset_to_code
+                            .insert(a.get_offset().0, ExceptionCode::IllegalArithmetic);
+                    }
                     None => {}
                 };
 
@@ -2055,10 +2090,11 @@ impl X64FunctionCode {
                         |a| a.emit_conditional_trap(Condition::Above),
                     );
                 }
-                None => { a.emit_conditional_trap(Condition::Above); }
+                None => {
+                    a.emit_conditional_trap(Condition::Above);
+                }
             };
 
-
             m.release_temp_gpr(tmp_bound);
         }
 
@@ -2106,20 +2142,26 @@ impl X64FunctionCode {
                         |a| a.emit_conditional_trap(Condition::NotEqual),
                     );
                 }
-                None => { a.emit_conditional_trap(Condition::NotEqual); }
+                None => {
+                    a.emit_conditional_trap(Condition::NotEqual);
+                }
             };
 
-
             m.release_temp_gpr(tmp_aligncheck);
         }
 
         match exception_table {
             Some(etable) => {
-                Self::mark_range_with_exception_code(a, etable, ExceptionCode::MemoryOutOfBounds, |a| {
-                    cb(a, m, tmp_addr)
-                })?;
+                Self::mark_range_with_exception_code(
+                    a,
+                    etable,
+                    ExceptionCode::MemoryOutOfBounds,
+                    |a| cb(a, m, tmp_addr),
+                )?;
+            }
+            None => {
+                cb(a, m, tmp_addr)?;
             }
-            None => { cb(a, m, tmp_addr)?; }
         };
 
         m.release_temp_gpr(tmp_addr);
@@ -2253,9 +2295,11 @@ impl X64FunctionCode {
         a.emit_label(trap);
 
         match exception_table {
-            Some(etable) => { etable
-                                .offset_to_code
-                                .insert(a.get_offset().0, ExceptionCode::IllegalArithmetic); }
+            Some(etable) => {
+                etable
+                    .offset_to_code
+                    .insert(a.get_offset().0, ExceptionCode::IllegalArithmetic);
+            }
             None => {}
         };
 
@@ -2386,9 +2430,11 @@ impl X64FunctionCode {
         a.emit_label(trap);
 
         match exception_table {
-            Some(etable) => { etable
-                                .offset_to_code
-                                .insert(a.get_offset().0, ExceptionCode::IllegalArithmetic); }
+            Some(etable) => {
+                etable
+                    .offset_to_code
+                    .insert(a.get_offset().0, ExceptionCode::IllegalArithmetic);
+            }
             None => {}
         };
 
@@ -2521,11 +2567,13 @@ impl FunctionCodeGenerator<CodegenError> for X64FunctionCode {
                     Self::mark_range_with_exception_code(
                         a,
                         etable,
-                        ExceptionCode::MemoryOutOfBounds,
+                        ExceptionCode::StackOverflow,
                         |a| a.emit_conditional_trap(Condition::Below),
                     );
                 }
-                None => { a.emit_conditional_trap(Condition::Below); }
+                None => {
+                    a.emit_conditional_trap(Condition::Below);
+                }
             };
         }
 
@@ -6405,7 +6453,9 @@ impl FunctionCodeGenerator<CodegenError> for X64FunctionCode {
                             |a| a.emit_conditional_trap(Condition::BelowEqual),
                         );
                     }
-                    None => { a.emit_conditional_trap(Condition::BelowEqual); }
+                    None => {
+                        a.emit_conditional_trap(Condition::BelowEqual);
+                    }
                 };
 
                 a.emit_mov(Size::S32, func_index, Location::GPR(table_count));
@@ -6442,7 +6492,9 @@ impl FunctionCodeGenerator<CodegenError> for X64FunctionCode {
                             |a| a.emit_conditional_trap(Condition::NotEqual),
                         );
                     }
-                    None => { a.emit_conditional_trap(Condition::NotEqual); }
+                    None => {
+                        a.emit_conditional_trap(Condition::NotEqual);
+                    }
                 }
 
                 self.machine.release_temp_gpr(sigidx);
@@ -7530,12 +7582,7 @@ impl FunctionCodeGenerator<CodegenError> for X64FunctionCode {
             }
             Operator::Unreachable => {
                 Self::mark_trappable(a, &self.machine, &mut self.fsm, &mut self.control_stack);
-                match &mut self.exception_table {
-                    Some(etable) => {
-                        etable.offset_to_code.insert(a.get_offset().0, ExceptionCode::Unreachable);
-                    }
-                    None => {}
-                };
+                self.unreachable_offsets.push(a.get_offset().0);
                 a.emit_ud2();
                 self.unreachable_depth = 1;
             }
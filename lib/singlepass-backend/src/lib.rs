@@ -46,6 +46,9 @@ pub use codegen_x64::USE_RKYV_SERIALIZATION;
 pub use codegen_x64::X64FunctionCode as FunctionCodeGenerator;
 pub use codegen_x64::X64ModuleCodeGenerator as ModuleCodeGenerator;
 
+/// The current version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 use wasmer_runtime_core::codegen::SimpleStreamingCompilerGen;
 pub type SinglePassCompiler = SimpleStreamingCompilerGen<
     codegen_x64::X64ModuleCodeGenerator,
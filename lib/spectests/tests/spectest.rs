@@ -325,6 +325,8 @@ mod tests {
                             features: Features {
                                 simd: true,
                                 threads: true,
+                                bulk_memory: false,
+                                reference_types: false,
                             },
                             ..Default::default()
                         };
@@ -763,6 +765,8 @@ mod tests {
                             features: Features {
                                 simd: true,
                                 threads: true,
+                                bulk_memory: false,
+                                reference_types: false,
                             },
                             ..Default::default()
                         };
@@ -815,6 +819,8 @@ mod tests {
                             features: Features {
                                 simd: true,
                                 threads: true,
+                                bulk_memory: false,
+                                reference_types: false,
                             },
                             ..Default::default()
                         };
@@ -866,6 +872,8 @@ mod tests {
                         features: Features {
                             simd: true,
                             threads: true,
+                            bulk_memory: false,
+                            reference_types: false,
                         },
                         ..Default::default()
                     };
@@ -961,6 +969,8 @@ mod tests {
                             features: Features {
                                 simd: true,
                                 threads: true,
+                                bulk_memory: false,
+                                reference_types: false,
                             },
                             ..Default::default()
                         };
@@ -118,6 +118,8 @@ impl PrestandardFeatures {
         Features {
             simd: self.simd || self.all,
             threads: self.threads || self.all,
+            bulk_memory: false,
+            reference_types: false,
         }
     }
 }